@@ -1,7 +1,7 @@
 //! Intro to dfdx::optim
 
 use dfdx::{
-    losses::mse_loss,
+    losses::{mse_loss, ReduceMean},
     nn::{BuildOnDevice, Linear, ModuleMut, ReLU, Tanh},
     optim::{Momentum, Optimizer, Sgd, SgdConfig},
     shapes::Rank2,
@@ -42,7 +42,7 @@ fn main() {
     let prediction = mlp.forward_mut(x.trace());
 
     // next compute the loss against the target dummy data
-    let loss = mse_loss(prediction, y.clone());
+    let loss = mse_loss(prediction, y.clone(), ReduceMean);
     dbg!(loss.array());
 
     // extract the gradients
@@ -57,7 +57,7 @@ fn main() {
     // let's do this a couple times to make sure the loss decreases!
     for i in 0..5 {
         let prediction = mlp.forward_mut(x.trace());
-        let loss = mse_loss(prediction, y.clone());
+        let loss = mse_loss(prediction, y.clone(), ReduceMean);
         println!("Loss after update {i}: {:?}", loss.array());
         let gradients = loss.backward();
         sgd.update(&mut mlp, gradients)
@@ -114,7 +114,7 @@ fn main() {
             .map(|i| dataset.get_batch(&dev, i))
         {
             let logits = model.forward_mut(img.traced());
-            let loss = cross_entropy_with_logits_loss(logits, lbl);
+            let loss = cross_entropy_with_logits_loss(logits, lbl, ReduceMean);
 
             total_epoch_loss += loss.array();
             num_batches += 1;
@@ -53,11 +53,12 @@ impl<const IN: usize, const INNER: usize, const OUT: usize> nn::Module<Tensor<Ra
     for Mlp<IN, INNER, OUT>
 {
     type Output = Tensor<Rank1<OUT>, f32, Cpu>;
+    type Error = <Cpu as HasErr>::Err;
 
-    fn forward(&self, x: Tensor<Rank1<IN>, f32, Cpu>) -> Self::Output {
-        let x = self.l1.forward(x);
-        let x = self.relu.forward(x);
-        self.l2.forward(x)
+    fn try_forward(&self, x: Tensor<Rank1<IN>, f32, Cpu>) -> Result<Self::Output, Self::Error> {
+        let x = self.l1.try_forward(x)?;
+        let x = self.relu.try_forward(x)?;
+        self.l2.try_forward(x)
     }
 }
 
@@ -66,11 +67,15 @@ impl<const BATCH: usize, const IN: usize, const INNER: usize, const OUT: usize,
     nn::Module<Tensor<Rank2<BATCH, IN>, f32, Cpu, T>> for Mlp<IN, INNER, OUT>
 {
     type Output = Tensor<Rank2<BATCH, OUT>, f32, Cpu, T>;
+    type Error = <Cpu as HasErr>::Err;
 
-    fn forward(&self, x: Tensor<Rank2<BATCH, IN>, f32, Cpu, T>) -> Self::Output {
-        let x = self.l1.forward(x);
-        let x = self.relu.forward(x);
-        self.l2.forward(x)
+    fn try_forward(
+        &self,
+        x: Tensor<Rank2<BATCH, IN>, f32, Cpu, T>,
+    ) -> Result<Self::Output, Self::Error> {
+        let x = self.l1.try_forward(x)?;
+        let x = self.relu.try_forward(x)?;
+        self.l2.try_forward(x)
     }
 }
 
@@ -0,0 +1,35 @@
+//! Benchmarks core ops & training steps on every available device.
+//!
+//! Run with `cargo run --release --example bench`, and pass a name to only run the
+//! benchmarks that contain it, e.g. `cargo run --release --example bench -- matmul`.
+//! Add `--features cuda` to also benchmark the gpu.
+
+use dfdx::{
+    bench::{run_suite, BenchConfig, BenchResult},
+    tensor::Cpu,
+};
+
+fn print(results: Vec<BenchResult>, filter: &Option<String>) {
+    for result in results {
+        if filter
+            .as_ref()
+            .is_none_or(|f| result.name.contains(f.as_str()))
+        {
+            println!("{result}");
+        }
+    }
+}
+
+fn main() {
+    let filter = std::env::args().nth(1);
+    let cfg = BenchConfig::default();
+
+    let cpu: Cpu = Default::default();
+    print(run_suite(&cpu, &cfg).unwrap(), &filter);
+
+    #[cfg(feature = "cuda")]
+    {
+        let cuda: dfdx::tensor::Cuda = Default::default();
+        print(run_suite(&cuda, &cfg).unwrap(), &filter);
+    }
+}
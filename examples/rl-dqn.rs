@@ -1,7 +1,7 @@
 //! Implements Deep Q Learning on random data.
 
 use dfdx::{
-    losses::mse_loss,
+    losses::{mse_loss, ReduceMean},
     optim::{Momentum, Sgd, SgdConfig},
     prelude::*,
 };
@@ -60,7 +60,7 @@ fn main() {
         let q_values = q_net.forward(state.trace());
         let action_qs = q_values.select(action.clone());
 
-        let loss = mse_loss(action_qs, target_q);
+        let loss = mse_loss(action_qs, target_q, ReduceMean);
         let loss_v = loss.array();
 
         // run backprop
@@ -0,0 +1,213 @@
+"""Generates the reference fixtures for dfdx's parity tests (`cargo test --features parity`).
+
+Every op is computed in float64 from float32 inputs, and the output & local derivative of an op
+are each rounded to float32, like the float32 kernels of NumPy/PyTorch. Gradients are of
+`(y * w).sum()` for a fixed weight `w`, and follow PyTorch's conventions at the edges
+(e.g. the gradient of relu and abs at 0 is 0).
+
+Each fixture is a `.npz` archive of little endian float32 `.npy` arrays, so fixtures can also be
+exported straight from NumPy or PyTorch with `np.savez(path, x=..., w=..., y=..., dx=...)`.
+Only the standard library is used, so this runs anywhere:
+
+    python3 fixtures/parity/generate.py
+"""
+
+import math
+import os
+import struct
+import zipfile
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+INF = math.inf
+NAN = math.nan
+F32_MAX = 3.4028234663852886e38
+# values at or above this round to infinity in float32
+F32_OVERFLOW = 3.4028235677973366e38
+DENORMAL = 1e-40
+MIN_DENORMAL = 1.401298464324817e-45
+
+
+def f32(v):
+    """Rounds a float64 to the nearest float32, saturating to +-inf like IEEE 754."""
+    if math.isnan(v) or math.isinf(v):
+        return v
+    if abs(v) >= F32_OVERFLOW:
+        return math.copysign(INF, v)
+    return struct.unpack("<f", struct.pack("<f", v))[0]
+
+
+def div(a, b):
+    """IEEE 754 division, which python raises on when `b` is 0."""
+    if b != 0 or math.isnan(b):
+        return a / b
+    if a == 0 or math.isnan(a):
+        return NAN
+    return math.copysign(INF, a) * math.copysign(1.0, b)
+
+
+def mul(a, b):
+    if (math.isinf(a) and b == 0) or (a == 0 and math.isinf(b)):
+        return NAN
+    return a * b
+
+
+def exp(x):
+    try:
+        return math.exp(x)
+    except OverflowError:
+        return INF
+
+
+def ln(x):
+    if math.isnan(x) or x < 0:
+        return NAN
+    if x == 0:
+        return -INF
+    return math.log(x)
+
+
+def sqrt(x):
+    if math.isnan(x) or x < 0:
+        return NAN
+    return math.sqrt(x)
+
+
+def sigmoid(x):
+    return 1.0 / (1.0 + exp(-x)) if x >= 0 else exp(x) / (1.0 + exp(x))
+
+
+def logsumexp(xs):
+    m = max(xs)
+    if math.isinf(m):
+        return m
+    return m + math.log(sum(exp(x - m) for x in xs))
+
+
+EDGES = [
+    0.0, -0.0, DENORMAL, -DENORMAL, MIN_DENORMAL, 1.0, -1.0, 0.5, -2.5,
+    20.0, -20.0, 88.0, 89.0, -88.0, -104.0, F32_MAX, INF, -INF,
+]
+POSITIVE = [x for x in EDGES if math.copysign(1.0, x) > 0]
+
+
+def weights(n):
+    pattern = [1.0, -0.5, 2.0, 0.25, -1.5]
+    return [pattern[i % len(pattern)] for i in range(n)]
+
+
+def unary(f, df, xs):
+    xs = [f32(x) for x in xs]
+    w = weights(len(xs))
+    y = [f32(f(x)) for x in xs]
+    dx = [mul(f32(df(x, yi)), wi) for x, yi, wi in zip(xs, y, w)]
+    return {"x": xs, "w": w, "y": y, "dx": dx}
+
+
+def softmax(xs):
+    xs = [f32(x) for x in xs]
+    w = weights(len(xs))
+    lse = logsumexp(xs)
+    y = [exp(x - lse) for x in xs]
+    dot = sum(wi * yi for wi, yi in zip(w, y))
+    dx = [yi * (wi - dot) for wi, yi in zip(w, y)]
+    return {"x": xs, "w": w, "y": y, "dx": dx}
+
+
+def log_softmax(xs):
+    xs = [f32(x) for x in xs]
+    w = weights(len(xs))
+    lse = logsumexp(xs)
+    y = [x - lse for x in xs]
+    total = sum(w)
+    dx = [wi - exp(x - lse) * total for wi, x in zip(w, xs)]
+    return {"x": xs, "w": w, "y": y, "dx": dx}
+
+
+def binary(f, da, db, pairs):
+    a = [f32(p[0]) for p in pairs]
+    b = [f32(p[1]) for p in pairs]
+    w = weights(len(pairs))
+    y = [f(ai, bi) for ai, bi in zip(a, b)]
+    return {
+        "a": a,
+        "b": b,
+        "w": w,
+        "y": y,
+        "da": [mul(f32(da(ai, bi)), wi) for ai, bi, wi in zip(a, b, w)],
+        "db": [mul(f32(db(ai, bi)), wi) for ai, bi, wi in zip(a, b, w)],
+    }
+
+
+def matmul(a, b):
+    """`a` is m x k, `b` is k x n, both as nested lists."""
+    m, k, n = len(a), len(b), len(b[0])
+    a = [[f32(v) for v in row] for row in a]
+    b = [[f32(v) for v in row] for row in b]
+    w = [weights(n * (i + 1))[-n:] for i in range(m)]
+    y = [[sum(a[i][p] * b[p][j] for p in range(k)) for j in range(n)] for i in range(m)]
+    da = [[sum(w[i][j] * b[p][j] for j in range(n)) for p in range(k)] for i in range(m)]
+    db = [[sum(a[i][p] * w[i][j] for i in range(m)) for j in range(n)] for p in range(k)]
+    return {"a": a, "b": b, "w": w, "y": y, "da": da, "db": db}
+
+
+def npy(values):
+    """A float32 `.npy` file holding `values`, a list or a list of equal length lists."""
+    if values and isinstance(values[0], list):
+        shape = "(%d, %d)" % (len(values), len(values[0]))
+        flat = [v for row in values for v in row]
+    else:
+        shape = "(%d,)" % len(values)
+        flat = values
+    header = "{'descr': '<f4', 'fortran_order': False, 'shape': %s, }" % shape
+    header += " " * (63 - (len(header) + 10) % 64) + "\n"
+    data = b"".join(struct.pack("<f", f32(v)) for v in flat)
+    return b"\x93NUMPY\x01\x00" + struct.pack("<H", len(header)) + header.encode() + data
+
+
+def save(name, arrays):
+    path = os.path.join(HERE, name + ".npz")
+    with zipfile.ZipFile(path, "w", zipfile.ZIP_STORED) as z:
+        for key, values in arrays.items():
+            z.writestr(key + ".npy", npy(values))
+
+
+def main():
+    save("exp", unary(exp, lambda x, y: y, EDGES))
+    save("ln", unary(ln, lambda x, y: div(1.0, x), POSITIVE))
+    save("sqrt", unary(sqrt, lambda x, y: div(0.5, y), POSITIVE))
+    save("sigmoid", unary(sigmoid, lambda x, y: y * (1.0 - y), EDGES))
+    save("tanh", unary(math.tanh, lambda x, y: 1.0 - y * y, EDGES))
+    save("relu", unary(lambda x: max(x, 0.0), lambda x, y: 1.0 if x > 0 else 0.0, EDGES))
+    save("abs", unary(abs, lambda x, y: 0.0 if x == 0 else math.copysign(1.0, x), EDGES))
+    save("square", unary(lambda x: x * x, lambda x, y: 2.0 * x, EDGES))
+
+    rows = [
+        [1000.0, 1001.0, 999.5, -INF],
+        [0.0, -0.0, DENORMAL, -DENORMAL],
+        [-88.0, -104.0, -1000.0, 0.5],
+        [F32_MAX, 0.0, -F32_MAX, 1.0],
+    ]
+    save("softmax", softmax([x for row in rows for x in row]))
+    save("log_softmax", log_softmax([x for row in rows[:3] for x in row]))
+
+    pairs = [
+        (0.0, 1.0), (-0.0, 2.0), (DENORMAL, 0.5), (DENORMAL, 1e30), (1.0, 1e-30),
+        (3.0, -4.0), (F32_MAX, 0.5), (1.0, INF), (-2.0, -INF), (1.0, 0.0), (-1.0, 0.0),
+    ]
+    save("mul", binary(mul, lambda a, b: b, lambda a, b: a, pairs))
+    save(
+        "div",
+        binary(div, lambda a, b: div(1.0, b), lambda a, b: -div(f32(div(a, b)), b), pairs),
+    )
+
+    save(
+        "matmul",
+        matmul(
+            [[1.0, -2.0, 0.0], [DENORMAL, 3.0, 0.5]],
+            [[0.5, 1e-30, -1.0, 4.0], [2.0, 0.0, 1e30, -0.25], [-3.0, 7.0, 0.0, DENORMAL]],
+        ),
+    )
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,458 @@
+//! Benchmarks of core ops and training steps, so that performance regressions and
+//! different devices can be compared.
+//!
+//! [bench()] times any closure on a device, after some warmup iterations, and
+//! [run_suite()] benchmarks matmuls, softmax, layer norm and a full training step of an MLP
+//! for every size in [BenchConfig::sizes]. With the `nightly` feature, the suite also
+//! benchmarks conv2d and a full training step of a transformer.
+//!
+//! The `bench` example runs the suite on the cpu (and on the gpu with the `cuda` feature):
+//!
+//! ```sh
+//! cargo run --release --example bench
+//! cargo run --release --example bench --features cuda -- matmul
+//! ```
+//!
+//! Or benchmark your own model:
+//!
+//! ```rust
+//! # use dfdx::{prelude::*, bench::*};
+//! let dev: Cpu = Default::default();
+//! let model: (Linear<64, 64>, ReLU) = BuildModule::build(&dev);
+//! let x: Tensor<Rank2<32, 64>, f32, _> = dev.sample_normal();
+//! let cfg = BenchConfig { warmup: 1, iterations: 5, ..Default::default() };
+//! let result = bench("mlp forward", &dev, &cfg, 32.0, "samples", || {
+//!     model.try_forward(x.clone()).map(|_| ())
+//! })
+//! .unwrap();
+//! println!("{result}");
+//! ```
+
+use crate::{
+    nn::{BuildModule, LayerNorm1D, Linear, Module, ReLU},
+    optim::{Optimizer, Sgd},
+    shapes::*,
+    tensor::*,
+    tensor_ops::*,
+};
+use rand_distr::StandardNormal;
+use std::{format, string::String, time::Duration, time::Instant, vec, vec::Vec};
+
+/// How long to measure each benchmark for, and what sizes [run_suite()] benchmarks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Untimed iterations before the timed ones, to allocate memory & load kernels.
+    /// Defaults to `3`.
+    pub warmup: usize,
+
+    /// The number of timed iterations. Defaults to `20`.
+    pub iterations: usize,
+
+    /// The sizes [run_suite()] benchmarks. Matmuls are `n x n` times `n x n`, and softmax,
+    /// layer norm and the MLP use a batch of `n` rows. Defaults to `[64, 256, 1024]`.
+    pub sizes: Vec<usize>,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            iterations: 20,
+            sizes: vec![64, 256, 1024],
+        }
+    }
+}
+
+/// The time of each iteration of a benchmark.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub name: String,
+
+    /// The [DeviceStorage::device_name()] of the device that ran the benchmark.
+    pub device: String,
+
+    /// The amount of work done by each iteration, in `unit`s. Used for
+    /// [BenchResult::throughput()].
+    pub work: f64,
+    pub unit: &'static str,
+
+    /// The time of each timed iteration, in order.
+    pub samples: Vec<Duration>,
+}
+
+impl BenchResult {
+    /// The mean time of an iteration.
+    pub fn mean(&self) -> Duration {
+        self.samples.iter().sum::<Duration>() / self.samples.len().max(1) as u32
+    }
+
+    /// The sample standard deviation of the time of an iteration.
+    pub fn stddev(&self) -> Duration {
+        let n = self.samples.len();
+        if n < 2 {
+            return Duration::ZERO;
+        }
+        let mean = self.mean().as_secs_f64();
+        let var = self
+            .samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / (n - 1) as f64;
+        Duration::from_secs_f64(var.sqrt())
+    }
+
+    /// The fastest iteration.
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+
+    /// The median time of an iteration.
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 1 => sorted[n / 2],
+            n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        }
+    }
+
+    /// [BenchResult::work] per second, using the [BenchResult::median()] time, which is less
+    /// sensitive to outliers than the mean.
+    pub fn throughput(&self) -> f64 {
+        self.work / self.median().as_secs_f64()
+    }
+}
+
+impl core::fmt::Display for BenchResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (throughput, prefix) = si_prefix(self.throughput());
+        write!(
+            f,
+            "{:<32} {:<8} median {:>10.3?} mean {:>10.3?} ± {:>9.3?} min {:>10.3?} {:>8.2} {}{}/s",
+            self.name,
+            self.device,
+            self.median(),
+            self.mean(),
+            self.stddev(),
+            self.min(),
+            throughput,
+            prefix,
+            self.unit,
+        )
+    }
+}
+
+fn si_prefix(x: f64) -> (f64, &'static str) {
+    for (scale, prefix) in [(1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "K")] {
+        if x >= scale {
+            return (x / scale, prefix);
+        }
+    }
+    (x, "")
+}
+
+/// Runs `f` `cfg.warmup + cfg.iterations` times, and times the last `cfg.iterations`.
+/// Waits for `dev` to finish after every iteration with [DeviceStorage::try_synchronize()],
+/// so asynchronous devices are timed correctly.
+///
+/// `work` is the amount of work each iteration does in `unit`s, e.g. `"FLOP"` or
+/// `"samples"`, for [BenchResult::throughput()].
+pub fn bench<D: DeviceStorage, F: FnMut() -> Result<(), D::Err>>(
+    name: impl Into<String>,
+    dev: &D,
+    cfg: &BenchConfig,
+    work: f64,
+    unit: &'static str,
+    mut f: F,
+) -> Result<BenchResult, D::Err> {
+    for _ in 0..cfg.warmup {
+        f()?;
+        dev.try_synchronize()?;
+    }
+    let mut samples = Vec::with_capacity(cfg.iterations);
+    for _ in 0..cfg.iterations {
+        let start = Instant::now();
+        f()?;
+        dev.try_synchronize()?;
+        samples.push(start.elapsed());
+    }
+    Ok(BenchResult {
+        name: name.into(),
+        device: dev.device_name(),
+        work,
+        unit,
+        samples,
+    })
+}
+
+/// Benchmarks matmuls, softmax, layer norm, and a training step of an MLP for each of
+/// [BenchConfig::sizes]. With the `nightly` feature, also benchmarks conv2d and a training
+/// step of a transformer.
+#[cfg(not(feature = "nightly"))]
+pub fn run_suite<D: Device<f32>>(dev: &D, cfg: &BenchConfig) -> Result<Vec<BenchResult>, D::Err>
+where
+    Sgd<Mlp<D>>: Optimizer<Mlp<D>, D, f32>,
+{
+    let mut results = Vec::new();
+    for &n in cfg.sizes.iter() {
+        results.push(bench_matmul(dev, cfg, n)?);
+        results.push(bench_softmax(dev, cfg, n)?);
+        results.push(bench_layer_norm(dev, cfg, n)?);
+        results.push(bench_mlp_step(dev, cfg, n)?);
+    }
+    Ok(results)
+}
+
+/// Benchmarks matmuls, softmax, layer norm, and a training step of an MLP for each of
+/// [BenchConfig::sizes]. With the `nightly` feature, also benchmarks conv2d and a training
+/// step of a transformer.
+#[cfg(feature = "nightly")]
+pub fn run_suite<D: Device<f32>>(dev: &D, cfg: &BenchConfig) -> Result<Vec<BenchResult>, D::Err>
+where
+    Sgd<Mlp<D>>: Optimizer<Mlp<D>, D, f32>,
+    Conv<D>: Module<ConvInput<D>, Error = D::Err>,
+    Sgd<TransformerModel<D>>: Optimizer<TransformerModel<D>, D, f32>,
+{
+    let mut results = Vec::new();
+    for &n in cfg.sizes.iter() {
+        results.push(bench_matmul(dev, cfg, n)?);
+        results.push(bench_softmax(dev, cfg, n)?);
+        results.push(bench_layer_norm(dev, cfg, n)?);
+        results.push(bench_mlp_step(dev, cfg, n)?);
+        results.push(bench_conv2d(dev, cfg, n)?);
+        results.push(bench_transformer_step(dev, cfg, n)?);
+    }
+    Ok(results)
+}
+
+/// `n x n` times `n x n`.
+pub fn bench_matmul<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err> {
+    let a: Tensor<(usize, usize), f32, D> = dev.try_sample_like(&(n, n), StandardNormal)?;
+    let b: Tensor<(usize, usize), f32, D> = dev.try_sample_like(&(n, n), StandardNormal)?;
+    let flop = 2.0 * (n as f64).powi(3);
+    bench(
+        format!("matmul {n}x{n}x{n}"),
+        dev,
+        cfg,
+        flop,
+        "FLOP",
+        || a.clone().try_matmul(b.clone()).map(|_| ()),
+    )
+}
+
+/// Softmax over the last axis of `n` rows of 1024 logits.
+pub fn bench_softmax<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err> {
+    let x: Tensor<(usize, Const<1024>), f32, D> =
+        dev.try_sample_like(&(n, Const), StandardNormal)?;
+    let elems = (n * 1024) as f64;
+    bench(format!("softmax {n}x1024"), dev, cfg, elems, "elem", || {
+        x.clone().try_softmax::<Axis<1>>().map(|_| ())
+    })
+}
+
+/// [LayerNorm1D] of `n` rows of 1024 features.
+pub fn bench_layer_norm<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err> {
+    let norm: LayerNorm1D<1024, D> = BuildModule::try_build(dev)?;
+    let x: Tensor<(usize, Const<1024>), f32, D> =
+        dev.try_sample_like(&(n, Const), StandardNormal)?;
+    let elems = (n * 1024) as f64;
+    bench(
+        format!("layer_norm {n}x1024"),
+        dev,
+        cfg,
+        elems,
+        "elem",
+        || norm.try_forward(x.clone()).map(|_| ()),
+    )
+}
+
+type Mlp<D> = (
+    (Linear<256, 512, D>, ReLU),
+    (Linear<512, 512, D>, ReLU),
+    Linear<512, 10, D>,
+);
+
+/// Forward, backward and [Sgd] update of a 3 layer MLP with 512 hidden features, on a batch
+/// of `n` rows.
+pub fn bench_mlp_step<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err>
+where
+    Sgd<Mlp<D>>: Optimizer<Mlp<D>, D, f32>,
+{
+    let mut model: Mlp<D> = BuildModule::try_build(dev)?;
+    let mut opt: Sgd<Mlp<D>> = Sgd::new(&model, Default::default());
+    let x: Tensor<(usize, Const<256>), f32, D> =
+        dev.try_sample_like(&(n, Const), StandardNormal)?;
+    bench(
+        format!("mlp step {n}x256"),
+        dev,
+        cfg,
+        n as f64,
+        "samples",
+        || {
+            let loss = model.try_forward(x.trace())?.try_square()?.try_mean()?;
+            let grads = loss.backward();
+            match opt.update(&mut model, grads) {
+                Ok(()) => Ok(()),
+                Err(crate::optim::OptimizerUpdateError::DeviceError(e)) => Err(e),
+                Err(e) => panic!("{e}"),
+            }
+        },
+    )
+}
+
+#[cfg(feature = "nightly")]
+type Conv<D> = crate::nn::Conv2D<64, 64, 3, 1, 1, 1, D>;
+#[cfg(feature = "nightly")]
+type ConvInput<D> = Tensor<Rank4<16, 64, 32, 32>, f32, D>;
+#[cfg(feature = "nightly")]
+type TransformerModel<D> = crate::nn::Transformer<128, 4, 2, 2, 256, D>;
+
+/// 3x3 [crate::nn::Conv2D] with 64 input & output channels on `n` 32x32 images, batched
+/// 16 at a time.
+#[cfg(feature = "nightly")]
+pub fn bench_conv2d<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err>
+where
+    Conv<D>: Module<ConvInput<D>, Error = D::Err>,
+{
+    let conv: Conv<D> = BuildModule::try_build(dev)?;
+    let x: ConvInput<D> = dev.try_sample(StandardNormal)?;
+    let batches = n.div_ceil(16);
+    let flop = (batches * 16) as f64 * 2.0 * 64.0 * 64.0 * 9.0 * 32.0 * 32.0;
+    bench(
+        format!("conv2d {n}x64x32x32 k3"),
+        dev,
+        cfg,
+        flop,
+        "FLOP",
+        || {
+            for _ in 0..batches {
+                conv.try_forward(x.clone())?;
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Forward, backward and [Sgd] update of a 2 layer encoder/decoder
+/// [crate::nn::Transformer] with 128 features and sequences of 32 tokens, on `n` sequences
+/// batched 16 at a time.
+#[cfg(feature = "nightly")]
+pub fn bench_transformer_step<D: Device<f32>>(
+    dev: &D,
+    cfg: &BenchConfig,
+    n: usize,
+) -> Result<BenchResult, D::Err>
+where
+    Sgd<TransformerModel<D>>: Optimizer<TransformerModel<D>, D, f32>,
+{
+    let mut model: TransformerModel<D> = BuildModule::try_build(dev)?;
+    let mut opt: Sgd<TransformerModel<D>> = Sgd::new(&model, Default::default());
+    let src: Tensor<Rank3<16, 32, 128>, f32, D> = dev.try_sample(StandardNormal)?;
+    let tgt: Tensor<Rank3<16, 32, 128>, f32, D> = dev.try_sample(StandardNormal)?;
+    let batches = n.div_ceil(16);
+    let name = format!("transformer step {n}x32x128");
+    bench(name, dev, cfg, (batches * 16) as f64, "seqs", || {
+        for _ in 0..batches {
+            let y = model.try_forward((src.trace(), tgt.clone()))?;
+            let grads = y.try_square()?.try_mean()?.backward();
+            match opt.update(&mut model, grads) {
+                Ok(()) => {}
+                Err(crate::optim::OptimizerUpdateError::DeviceError(e)) => return Err(e),
+                Err(e) => panic!("{e}"),
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestDevice;
+
+    fn result(samples_ms: &[u64]) -> BenchResult {
+        BenchResult {
+            name: "test".into(),
+            device: "Cpu".into(),
+            work: 1e9,
+            unit: "FLOP",
+            samples: samples_ms
+                .iter()
+                .map(|&ms| Duration::from_millis(ms))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_bench_result_stats() {
+        let r = result(&[4, 1, 3, 100]);
+        assert_eq!(r.mean(), Duration::from_millis(27));
+        assert_eq!(r.median(), Duration::from_micros(3500));
+        assert_eq!(r.min(), Duration::from_millis(1));
+        let std = r.stddev().as_secs_f64();
+        assert!((std - 0.048675).abs() < 1e-5, "{std}");
+        assert_eq!(r.throughput().round(), (1e9 / 0.0035f64).round());
+        assert_eq!(result(&[5]).stddev(), Duration::ZERO);
+        assert_eq!(result(&[2, 8, 5]).median(), Duration::from_millis(5));
+
+        let s = format!("{}", result(&[2]));
+        assert!(s.starts_with("test"), "{s}");
+        assert!(s.ends_with("500.00 GFLOP/s"), "{s}");
+    }
+
+    #[test]
+    fn test_bench_iterations() {
+        let dev: TestDevice = Default::default();
+        let cfg = BenchConfig {
+            warmup: 2,
+            iterations: 3,
+            ..Default::default()
+        };
+        let mut calls = 0;
+        let r = bench("count", &dev, &cfg, 1.0, "call", || {
+            calls += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(calls, 5);
+        assert_eq!(r.samples.len(), 3);
+        assert_eq!(r.device, dev.device_name());
+    }
+
+    #[test]
+    fn test_run_suite() {
+        let dev: TestDevice = Default::default();
+        let cfg = BenchConfig {
+            warmup: 0,
+            iterations: 1,
+            sizes: vec![2, 3],
+        };
+        let results = run_suite(&dev, &cfg).unwrap();
+        assert_eq!(results[0].name, "matmul 2x2x2");
+        assert_eq!(results[results.len() / 2].name, "matmul 3x3x3");
+        assert_eq!(results[0].work, 16.0);
+        assert!(results.iter().all(|r| r.samples.len() == 1));
+    }
+}
@@ -0,0 +1,392 @@
+//! A collection of data utility classes such as [Arange], [OneHotEncode], and [SubsetIterator].
+//!
+//! Datasets too large to index can implement [IterableDataset], and be shuffled with
+//! [ShuffleBuffer] and loaded on background threads with [prefetch()].
+//!
+//! Tabular data can be read with [Table::from_csv()], and turned into feature and label
+//! tensors with a fitted [Preprocessor].
+//!
+//! Datasets can be split for evaluation with [random_split()], [stratified_split()] and
+//! [KFold]. Imbalanced classes can be oversampled with a [WeightedRandomSampler].
+
+mod sampler;
+mod split;
+mod tabular;
+
+pub use sampler::{inverse_frequency_weights, WeightedRandomSampler};
+pub use split::{random_split, stratified_split, KFold, Split};
+pub use tabular::{Column, CsvError, Encoding, FittedColumn, PreprocessError, Preprocessor, Table};
+
+use rand::prelude::SliceRandom;
+use std::vec::Vec;
+
+use crate::{
+    shapes::{Const, Rank1},
+    tensor::{CopySlice, DeviceStorage, Tensor, ZerosTensor},
+};
+
+/// Generates a tensor with ordered data from 0 to `N`.
+///
+/// Examples:
+/// ```rust
+/// use dfdx::{prelude::*, data::Arange};
+/// let dev: Cpu = Default::default();
+/// let t = dev.arange::<5>();
+/// assert_eq!(t.array(), [0.0, 1.0, 2.0, 3.0, 4.0]);
+/// ```
+pub trait Arange: DeviceStorage + ZerosTensor<f32> + CopySlice<f32> {
+    fn arange<const N: usize>(&self) -> Tensor<Rank1<N>, f32, Self> {
+        let mut data = Vec::with_capacity(N);
+        for i in 0..N {
+            data.push(i as f32);
+        }
+        let mut t = self.zeros();
+        t.copy_from(&data);
+        t
+    }
+}
+impl<D: DeviceStorage + ZerosTensor<f32> + CopySlice<f32>> Arange for D {}
+
+/// One hot encodes an array of class labels into a 2d tensor of probability
+/// vectors. This can be used in tandem with [crate::losses::cross_entropy_with_logits_loss()].
+///
+/// Const Generic Arguments:
+/// - `N` - the number of classes
+///
+/// Arguments:
+/// - `class_labels` - an array of size `B` where each element is the class label
+///
+/// Outputs: [Tensor] with shape `(usize, Const<N>)`
+///
+/// Examples:
+/// ```rust
+/// use dfdx::{prelude::*, data::OneHotEncode};
+/// let dev: Cpu = Default::default();
+/// let class_labels = [0, 1, 2, 1, 1];
+/// // NOTE: 5 is the batch size, 3 is the number of classes
+/// let probs: Tensor<(usize, Const<3>), f32, _> = dev.one_hot_encode::<3>(&class_labels);
+/// assert_eq!(&probs.as_vec(), &[
+///     1.0, 0.0, 0.0,
+///     0.0, 1.0, 0.0,
+///     0.0, 0.0, 1.0,
+///     0.0, 1.0, 0.0,
+///     0.0, 1.0, 0.0,
+/// ]);
+/// ```
+pub trait OneHotEncode: DeviceStorage + ZerosTensor<f32> + CopySlice<f32> {
+    fn one_hot_encode<const N: usize>(
+        &self,
+        labels: &[usize],
+    ) -> Tensor<(usize, Const<N>), f32, Self> {
+        let mut data = Vec::with_capacity(labels.len() * N);
+        for &l in labels {
+            for i in 0..N {
+                data.push(if i == l { 1.0 } else { 0.0 });
+            }
+        }
+        let mut t = self.zeros_like(&(labels.len(), Const::<N>));
+        t.copy_from(&data);
+        t
+    }
+}
+impl<D: DeviceStorage + ZerosTensor<f32> + CopySlice<f32>> OneHotEncode for D {}
+
+/// A utility class to simplify sampling a fixed number of indices for
+/// data from a dataset.
+///
+/// Generic Arguments:
+/// - `B` - The number of indices to sample for a batch.
+///
+/// Iterating a dataset in order:
+/// ```rust
+/// # use dfdx::{prelude::*, data::SubsetIterator};
+/// let mut subsets = SubsetIterator::<5>::in_order(100);
+/// assert_eq!(subsets.next(), Some([0, 1, 2, 3, 4]));
+/// ```
+///
+/// Iterating a dataset in random order:
+/// ```rust
+/// # use dfdx::{prelude::*, data::SubsetIterator};
+/// # use rand::prelude::*;
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let mut subsets = SubsetIterator::<5>::shuffled(100, &mut rng);
+/// assert_eq!(subsets.next(), Some([17, 4, 76, 81, 5]));
+/// ```
+pub struct SubsetIterator<const B: usize> {
+    i: usize,
+    indices: Vec<usize>,
+}
+
+impl<const B: usize> SubsetIterator<B> {
+    pub fn in_order(n: usize) -> Self {
+        let mut indices: Vec<usize> = Vec::with_capacity(n);
+        for i in 0..n {
+            indices.push(i);
+        }
+        Self { i: 0, indices }
+    }
+
+    pub fn shuffled<R: rand::Rng>(n: usize, rng: &mut R) -> Self {
+        let mut sampler = Self::in_order(n);
+        sampler.indices.shuffle(rng);
+        sampler
+    }
+}
+
+impl<const B: usize> Iterator for SubsetIterator<B> {
+    type Item = [usize; B];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.indices.len() < B || self.i + B > self.indices.len() {
+            None
+        } else {
+            let mut batch = [0; B];
+            batch.copy_from_slice(&self.indices[self.i..self.i + B]);
+            self.i += B;
+            Some(batch)
+        }
+    }
+}
+
+/// A dataset that can only be iterated, like shards of a text corpus, or tar files of samples,
+/// that are too large to index.
+///
+/// The items are split into `num_workers` shards that don't overlap, so multiple workers
+/// (e.g. the threads of [prefetch()]) can each iterate over their own shard. Usually each
+/// worker reads every `num_workers`th file:
+/// ```rust
+/// # use dfdx::data::IterableDataset;
+/// struct Shards(Vec<Vec<u32>>);
+///
+/// impl IterableDataset for Shards {
+///     type Item = u32;
+///     type Iter = std::vec::IntoIter<u32>;
+///     fn iter_shard(&self, worker: usize, num_workers: usize) -> Self::Iter {
+///         let files = self.0.iter().skip(worker).step_by(num_workers);
+///         files.flatten().copied().collect::<Vec<_>>().into_iter()
+///     }
+/// }
+///
+/// let data = Shards(vec![vec![1, 2], vec![3], vec![4, 5]]);
+/// assert_eq!(data.iter_shard(0, 2).collect::<Vec<_>>(), [1, 2, 4, 5]);
+/// assert_eq!(data.iter_shard(1, 2).collect::<Vec<_>>(), [3]);
+/// assert_eq!(data.iter().count(), 5);
+/// ```
+pub trait IterableDataset {
+    type Item;
+    type Iter: Iterator<Item = Self::Item>;
+
+    /// Iterates over shard `worker` of `num_workers` shards.
+    fn iter_shard(&self, worker: usize, num_workers: usize) -> Self::Iter;
+
+    /// Iterates over every item, which is the only shard when there is 1 worker.
+    fn iter(&self) -> Self::Iter {
+        self.iter_shard(0, 1)
+    }
+}
+
+/// Shuffles an iterator that is too large to hold in memory, by keeping a buffer of `size`
+/// items, and replacing a random one of them with the next item each iteration.
+///
+/// Items can only move up to about `size` positions earlier, so datasets stored in order
+/// (e.g. by class) need a buffer that is large compared to those runs.
+///
+/// ```rust
+/// # use dfdx::data::ShuffleBuffer;
+/// # use rand::prelude::*;
+/// let rng = StdRng::seed_from_u64(0);
+/// let mut shuffled: Vec<usize> = ShuffleBuffer::new(0..100, 10, rng).collect();
+/// assert_ne!(shuffled, (0..100).collect::<Vec<_>>());
+/// shuffled.sort();
+/// assert_eq!(shuffled, (0..100).collect::<Vec<_>>());
+/// ```
+pub struct ShuffleBuffer<I: Iterator, R> {
+    iter: I,
+    buffer: Vec<I::Item>,
+    size: usize,
+    rng: R,
+}
+
+impl<I: Iterator, R: rand::Rng> ShuffleBuffer<I, R> {
+    pub fn new(iter: I, size: usize, rng: R) -> Self {
+        Self {
+            iter,
+            buffer: Vec::with_capacity(size),
+            size: size.max(1),
+            rng,
+        }
+    }
+}
+
+impl<I: Iterator, R: rand::Rng> Iterator for ShuffleBuffer<I, R> {
+    type Item = I::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() < self.size {
+            match self.iter.next() {
+                Some(item) => self.buffer.push(item),
+                None => break,
+            }
+        }
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let i = self.rng.gen_range(0..self.buffer.len());
+        Some(self.buffer.swap_remove(i))
+    }
+}
+
+/// Iterates over `dataset` on `num_workers` background threads, each iterating over its own
+/// [IterableDataset::iter_shard()], so loading & decoding items overlaps with training.
+///
+/// Each worker keeps up to `buffer` items ready. Items are returned in the order the workers
+/// finish them, so the order isn't deterministic when there are multiple workers; wrap this
+/// in a [ShuffleBuffer] to shuffle across workers.
+///
+/// If a worker panics, the panic is resumed on the thread iterating, once the other workers
+/// are done. Dropping the iterator stops the workers after their next item.
+///
+/// ```rust
+/// # use dfdx::data::{prefetch, IterableDataset};
+/// # struct Range(u32);
+/// # impl IterableDataset for Range {
+/// #     type Item = u32;
+/// #     type Iter = std::iter::StepBy<std::ops::Range<u32>>;
+/// #     fn iter_shard(&self, worker: usize, num_workers: usize) -> Self::Iter {
+/// #         (worker as u32..self.0).step_by(num_workers)
+/// #     }
+/// # }
+/// let mut items: Vec<u32> = prefetch(Range(100), 4, 8).collect();
+/// items.sort();
+/// assert_eq!(items, (0..100).collect::<Vec<_>>());
+/// ```
+#[cfg(feature = "std")]
+pub fn prefetch<Ds>(dataset: Ds, num_workers: usize, buffer: usize) -> Prefetch<Ds::Item>
+where
+    Ds: IterableDataset + Send + Sync + 'static,
+    Ds::Item: Send + 'static,
+{
+    let num_workers = num_workers.max(1);
+    let dataset = std::sync::Arc::new(dataset);
+    let (sender, receiver) = std::sync::mpsc::sync_channel(buffer * num_workers);
+    let workers = (0..num_workers)
+        .map(|worker| {
+            let dataset = dataset.clone();
+            let sender = sender.clone();
+            std::thread::spawn(move || {
+                for item in dataset.iter_shard(worker, num_workers) {
+                    if sender.send(item).is_err() {
+                        // the iterator was dropped
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+    Prefetch { receiver, workers }
+}
+
+/// The iterator returned by [prefetch()].
+#[cfg(feature = "std")]
+pub struct Prefetch<T> {
+    receiver: std::sync::mpsc::Receiver<T>,
+    workers: Vec<std::thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "std")]
+impl<T> Iterator for Prefetch<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.receiver.recv() {
+            Ok(item) => Some(item),
+            Err(_) => {
+                // every worker is done
+                for worker in self.workers.drain(..) {
+                    if let Err(panic) = worker.join() {
+                        std::panic::resume_unwind(panic);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn sampler_uses_all() {
+        let mut seen: Vec<usize> = Vec::new();
+        for batch in SubsetIterator::<5>::in_order(100) {
+            seen.extend(batch.iter());
+        }
+        for i in 0..100 {
+            assert!(seen.contains(&i));
+        }
+    }
+
+    struct Shards(usize);
+
+    impl IterableDataset for Shards {
+        type Item = usize;
+        type Iter = std::iter::StepBy<std::ops::Range<usize>>;
+        fn iter_shard(&self, worker: usize, num_workers: usize) -> Self::Iter {
+            (worker..self.0).step_by(num_workers)
+        }
+    }
+
+    #[test]
+    fn shuffle_buffer_keeps_every_item() {
+        let rng = StdRng::seed_from_u64(0);
+        let shuffled: Vec<usize> = ShuffleBuffer::new(Shards(50).iter(), 8, rng).collect();
+        assert_eq!(shuffled.len(), 50);
+        let mut sorted = shuffled.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+
+        // the first item can only come from the first buffer
+        assert!(shuffled[0] < 8);
+
+        let rng = StdRng::seed_from_u64(0);
+        let mut empty = ShuffleBuffer::new(Shards(0).iter(), 8, rng);
+        assert_eq!(empty.next(), None);
+    }
+
+    #[test]
+    fn prefetch_iterates_every_shard() {
+        let mut items: Vec<usize> = prefetch(Shards(1000), 3, 4).collect();
+        items.sort();
+        assert_eq!(items, (0..1000).collect::<Vec<_>>());
+
+        // stopping early doesn't wait for the workers
+        let first: Vec<usize> = prefetch(Shards(usize::MAX), 2, 1).take(10).collect();
+        assert_eq!(first.len(), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn prefetch_resumes_worker_panics() {
+        struct Panics;
+        impl IterableDataset for Panics {
+            type Item = usize;
+            type Iter = std::vec::IntoIter<usize>;
+            fn iter_shard(&self, worker: usize, _: usize) -> Self::Iter {
+                assert_ne!(worker, 1);
+                std::vec![worker].into_iter()
+            }
+        }
+        prefetch(Panics, 2, 1).for_each(drop);
+    }
+
+    #[test]
+    fn sampler_drops_last() {
+        let mut seen: Vec<usize> = Vec::new();
+        for batch in SubsetIterator::<6>::in_order(100) {
+            seen.extend(batch.iter());
+        }
+        for i in 0..96 {
+            assert!(seen.contains(&i));
+        }
+    }
+}
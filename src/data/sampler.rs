@@ -0,0 +1,135 @@
+use std::{collections::BTreeMap, vec::Vec};
+
+use crate::tensor::AsVec;
+
+/// Samples batches of `B` indices with replacement, where index `i` is sampled with
+/// probability proportional to `weights[i]`, e.g. to oversample rare classes of an
+/// imbalanced dataset with [inverse_frequency_weights()].
+///
+/// Like [super::SubsetIterator], an epoch has `num_samples / B` batches, so the last partial
+/// batch is dropped.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, data::*};
+/// # use rand::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let labels = dev.tensor([0, 0, 0, 0, 0, 0, 1, 1]);
+/// let weights = inverse_frequency_weights(&labels);
+/// let rng = StdRng::seed_from_u64(0);
+/// let batches = WeightedRandomSampler::<4, _>::new(&weights, 1000, rng);
+/// let ones = batches.flatten().filter(|&i| i >= 6).count();
+/// assert!((400..600).contains(&ones));
+/// ```
+///
+/// **Panics** if the weights are negative, not finite, or all `0.0`.
+pub struct WeightedRandomSampler<const B: usize, R> {
+    cumulative: Vec<f64>,
+    remaining: usize,
+    rng: R,
+}
+
+impl<const B: usize, R: rand::Rng> WeightedRandomSampler<B, R> {
+    pub fn new(weights: &[f64], num_samples: usize, rng: R) -> Self {
+        let mut total = 0.0;
+        let cumulative: Vec<f64> = weights
+            .iter()
+            .map(|&w| {
+                assert!(w >= 0.0 && w.is_finite(), "invalid sampling weight {w}");
+                total += w;
+                total
+            })
+            .collect();
+        assert!(total > 0.0, "sampling weights sum to 0");
+        Self {
+            cumulative,
+            remaining: num_samples / B,
+            rng,
+        }
+    }
+
+    fn sample(&mut self) -> usize {
+        let total = self.cumulative[self.cumulative.len() - 1];
+        let x = self.rng.gen::<f64>() * total;
+        // the first index whose cumulative weight is more than `x`, which skips `0.0` weights
+        let i = self.cumulative.partition_point(|&c| c <= x);
+        i.min(self.cumulative.len() - 1)
+    }
+}
+
+impl<const B: usize, R: rand::Rng> Iterator for WeightedRandomSampler<B, R> {
+    type Item = [usize; B];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let mut batch = [0; B];
+        for i in batch.iter_mut() {
+            *i = self.sample();
+        }
+        Some(batch)
+    }
+}
+
+/// The weight of each label is `1 / (the number of items with that label)`, so each class is
+/// sampled equally often by a [WeightedRandomSampler]. `labels` can be any shape, and is
+/// read in order.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, data::inverse_frequency_weights};
+/// # let dev: Cpu = Default::default();
+/// let labels = dev.tensor([2, 0, 2, 2]);
+/// assert_eq!(inverse_frequency_weights(&labels), [1.0 / 3.0, 1.0, 1.0 / 3.0, 1.0 / 3.0]);
+/// ```
+pub fn inverse_frequency_weights<L: AsVec<Unit = usize>>(labels: &L) -> Vec<f64> {
+    let labels = labels.as_vec();
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    for &l in &labels {
+        *counts.entry(l).or_default() += 1;
+    }
+    labels.iter().map(|l| 1.0 / counts[l] as f64).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tests::TestDevice};
+    use rand::prelude::*;
+
+    #[test]
+    fn test_weighted_sampler_proportions() {
+        let rng = StdRng::seed_from_u64(0);
+        let weights = [1.0, 0.0, 3.0, 0.0];
+        let mut counts = [0; 4];
+        for batch in WeightedRandomSampler::<10, _>::new(&weights, 40_000, rng) {
+            for i in batch {
+                counts[i] += 1;
+            }
+        }
+        assert_eq!(counts[1], 0);
+        assert_eq!(counts[3], 0);
+        assert_eq!(counts[0] + counts[2], 40_000);
+        assert!((9_500..10_500).contains(&counts[0]), "{counts:?}");
+    }
+
+    #[test]
+    fn test_weighted_sampler_drops_last() {
+        let rng = StdRng::seed_from_u64(0);
+        let sampler = WeightedRandomSampler::<4, _>::new(&[1.0; 3], 10, rng);
+        assert_eq!(sampler.count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_weighted_sampler_zero_weights() {
+        WeightedRandomSampler::<4, _>::new(&[0.0; 3], 10, StdRng::seed_from_u64(0));
+    }
+
+    #[test]
+    fn test_inverse_frequency_weights() {
+        let dev: TestDevice = Default::default();
+        let labels = dev.tensor([[1, 1], [5, 1]]);
+        let weights = inverse_frequency_weights(&labels);
+        assert_eq!(weights, [1.0 / 3.0, 1.0 / 3.0, 1.0, 1.0 / 3.0]);
+    }
+}
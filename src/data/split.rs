@@ -0,0 +1,226 @@
+use rand::prelude::SliceRandom;
+use std::{collections::BTreeMap, vec::Vec};
+
+/// The indices of a dataset split into training & validation sets, as returned by
+/// [random_split()], [stratified_split()] and [KFold]. The indices of each set are sorted.
+///
+/// Use the indices with [super::SubsetIterator]-like batching, or
+/// [super::Preprocessor::transform_rows()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub train: Vec<usize>,
+    pub val: Vec<usize>,
+}
+
+/// The number of the `n` items that go in the validation set.
+fn num_val(n: usize, val_fraction: f64) -> usize {
+    assert!(
+        (0.0..=1.0).contains(&val_fraction),
+        "val_fraction must be in [0, 1], found {val_fraction}"
+    );
+    (n as f64 * val_fraction).round() as usize
+}
+
+fn split_at(mut indices: Vec<usize>, num_val: usize) -> Split {
+    let mut train = indices.split_off(num_val);
+    let mut val = indices;
+    train.sort_unstable();
+    val.sort_unstable();
+    Split { train, val }
+}
+
+/// Randomly splits the indices `0..n` so that `val_fraction` of them (rounded) are
+/// validation indices. Use a seeded rng to get the same split each run.
+///
+/// ```rust
+/// # use dfdx::data::random_split;
+/// # use rand::prelude::*;
+/// let split = random_split(10, 0.2, &mut StdRng::seed_from_u64(0));
+/// assert_eq!(split.train.len(), 8);
+/// assert_eq!(split.val.len(), 2);
+/// assert_eq!(split, random_split(10, 0.2, &mut StdRng::seed_from_u64(0)));
+/// ```
+///
+/// **Panics** if `val_fraction` is not in `[0, 1]`.
+pub fn random_split<R: rand::Rng>(n: usize, val_fraction: f64, rng: &mut R) -> Split {
+    let num_val = num_val(n, val_fraction);
+    let mut indices: Vec<usize> = (0..n).collect();
+    indices.shuffle(rng);
+    split_at(indices, num_val)
+}
+
+/// Randomly splits the indices of `labels` so that `val_fraction` (rounded) of the items with
+/// each label are validation indices, so both sets have about the same ratio of each label
+/// as the whole dataset.
+///
+/// ```rust
+/// # use dfdx::data::stratified_split;
+/// # use rand::prelude::*;
+/// let labels = [0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1];
+/// let split = stratified_split(&labels, 0.25, &mut StdRng::seed_from_u64(0));
+/// let val_labels: Vec<_> = split.val.iter().map(|&i| labels[i]).collect();
+/// assert_eq!(val_labels, [0, 1, 1]);
+/// ```
+///
+/// **Panics** if `val_fraction` is not in `[0, 1]`.
+pub fn stratified_split<L: Ord, R: rand::Rng>(
+    labels: &[L],
+    val_fraction: f64,
+    rng: &mut R,
+) -> Split {
+    let mut classes: BTreeMap<&L, Vec<usize>> = BTreeMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        classes.entry(label).or_default().push(i);
+    }
+    let mut train = Vec::with_capacity(labels.len());
+    let mut val = Vec::new();
+    for (_, indices) in classes {
+        let num_val = num_val(indices.len(), val_fraction);
+        let mut indices = indices;
+        indices.shuffle(rng);
+        let split = split_at(indices, num_val);
+        train.extend(split.train);
+        val.extend(split.val);
+    }
+    train.sort_unstable();
+    val.sort_unstable();
+    Split { train, val }
+}
+
+/// Iterates over the `k` [Split]s of k-fold cross validation, where each index of `0..n` is
+/// in the validation set of exactly one fold. The first `n % k` folds have one more
+/// validation index than the rest.
+///
+/// ```rust
+/// # use dfdx::data::{KFold, Split};
+/// let mut folds = KFold::in_order(5, 2);
+/// assert_eq!(folds.next(), Some(Split { train: vec![3, 4], val: vec![0, 1, 2] }));
+/// assert_eq!(folds.next(), Some(Split { train: vec![0, 1, 2], val: vec![3, 4] }));
+/// assert_eq!(folds.next(), None);
+/// ```
+///
+/// Use [KFold::shuffled()] when the dataset is stored in some order, like by class.
+///
+/// **Panics** if `k` is `0` or more than `n`.
+pub struct KFold {
+    indices: Vec<usize>,
+    k: usize,
+    fold: usize,
+}
+
+impl KFold {
+    pub fn in_order(n: usize, k: usize) -> Self {
+        assert!(k > 0 && k <= n, "can't split {n} items into {k} folds");
+        Self {
+            indices: (0..n).collect(),
+            k,
+            fold: 0,
+        }
+    }
+
+    pub fn shuffled<R: rand::Rng>(n: usize, k: usize, rng: &mut R) -> Self {
+        let mut folds = Self::in_order(n, k);
+        folds.indices.shuffle(rng);
+        folds
+    }
+
+    /// The range of `self.indices` in the validation set of `fold`.
+    fn val_range(&self, fold: usize) -> std::ops::Range<usize> {
+        let (size, rem) = (self.indices.len() / self.k, self.indices.len() % self.k);
+        let start = fold * size + fold.min(rem);
+        let end = start + size + usize::from(fold < rem);
+        start..end
+    }
+}
+
+impl Iterator for KFold {
+    type Item = Split;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.fold == self.k {
+            return None;
+        }
+        let range = self.val_range(self.fold);
+        self.fold += 1;
+        let mut val = self.indices[range.clone()].to_vec();
+        let mut train = self.indices[..range.start].to_vec();
+        train.extend_from_slice(&self.indices[range.end..]);
+        train.sort_unstable();
+        val.sort_unstable();
+        Some(Split { train, val })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.k - self.fold;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for KFold {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_random_split() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let split = random_split(101, 0.3, &mut rng);
+        assert_eq!(split.val.len(), 30);
+        let mut all = [split.train.clone(), split.val.clone()].concat();
+        all.sort_unstable();
+        assert_eq!(all, (0..101).collect::<Vec<_>>());
+        assert_ne!(split, random_split(101, 0.3, &mut rng));
+
+        assert!(random_split(5, 0.0, &mut rng).val.is_empty());
+        assert!(random_split(5, 1.0, &mut rng).train.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_random_split_bad_fraction() {
+        random_split(5, 1.5, &mut StdRng::seed_from_u64(0));
+    }
+
+    #[test]
+    fn test_stratified_split() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let labels: Vec<&str> = ["a"; 10].into_iter().chain(["b"; 30]).collect();
+        let split = stratified_split(&labels, 0.2, &mut rng);
+        let count = |indices: &[usize], l| indices.iter().filter(|&&i| labels[i] == l).count();
+        assert_eq!(count(&split.val, "a"), 2);
+        assert_eq!(count(&split.val, "b"), 6);
+        assert_eq!(count(&split.train, "a"), 8);
+        assert_eq!(count(&split.train, "b"), 24);
+        assert!(split.val.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_kfold() {
+        let folds: Vec<Split> = KFold::in_order(7, 3).collect();
+        assert_eq!(folds.len(), 3);
+        assert_eq!(folds[0].val, [0, 1, 2]);
+        assert_eq!(folds[1].val, [3, 4]);
+        assert_eq!(folds[2].val, [5, 6]);
+        assert_eq!(folds[2].train, [0, 1, 2, 3, 4]);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let folds = KFold::shuffled(20, 4, &mut rng);
+        assert_eq!(folds.len(), 4);
+        let mut seen = Vec::new();
+        for fold in folds {
+            assert_eq!(fold.val.len(), 5);
+            assert_eq!(fold.train.len(), 15);
+            assert!(fold.train.iter().all(|i| !fold.val.contains(i)));
+            seen.extend(fold.val);
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_kfold_too_many_folds() {
+        KFold::in_order(3, 4);
+    }
+}
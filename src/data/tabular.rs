@@ -0,0 +1,515 @@
+use std::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::tensor::{Tensor, TensorFromVec};
+
+/// A column of a [Table].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Column {
+    /// A column where every value is a number. Empty values are `NaN`.
+    Numeric(Vec<f32>),
+    /// Any other column.
+    Categorical(Vec<String>),
+}
+
+impl Column {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Numeric(values) => values.len(),
+            Self::Categorical(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Named columns of data, like a csv file read with [Table::from_csv()].
+///
+/// Use a [Preprocessor] to turn the columns into feature and label tensors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub names: Vec<String>,
+    pub columns: Vec<Column>,
+}
+
+/// Invalid csv passed to [Table::from_csv()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvError {
+    /// There was no header row.
+    MissingHeader,
+    /// A row (counting the header as row 0) had `found` values instead of `expected`.
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A quoted value in `row` was never closed.
+    UnterminatedQuote { row: usize },
+}
+
+impl std::fmt::Display for CsvError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingHeader => write!(f, "CsvError::MissingHeader"),
+            Self::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "CsvError::RowLength {{ row: {row}, expected: {expected}, found: {found} }}"
+            ),
+            Self::UnterminatedQuote { row } => {
+                write!(f, "CsvError::UnterminatedQuote {{ row: {row} }}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CsvError {}
+
+/// Splits a csv line into its values. Values can be quoted with `"`, with `""` for a quote
+/// inside of a quoted value. Returns `None` if a quote isn't closed.
+fn split_csv_line(line: &str) -> Option<Vec<String>> {
+    let mut values = Vec::new();
+    let mut value = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                value.push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => values.push(std::mem::take(&mut value)),
+            (c, _) => value.push(c),
+        }
+    }
+    if quoted {
+        return None;
+    }
+    values.push(value);
+    Some(values)
+}
+
+impl Table {
+    /// Parses csv `text` with a header row of column names. Values are separated by `,`, and
+    /// surrounding whitespace is trimmed. Columns where every value is a number or empty
+    /// are [Column::Numeric], and the rest are [Column::Categorical]. Empty lines are skipped.
+    ///
+    /// To read a file, pass the result of `std::fs::read_to_string()`.
+    ///
+    /// ```rust
+    /// # use dfdx::data::{Column, Table};
+    /// let table = Table::from_csv("height,color\n1.5,red\n2,\"blue, dark\"\n,red").unwrap();
+    /// assert_eq!(table.num_rows(), 3);
+    /// assert_eq!(table.column("color"), Some(&Column::Categorical(vec![
+    ///     "red".to_string(),
+    ///     "blue, dark".to_string(),
+    ///     "red".to_string(),
+    /// ])));
+    /// let Some(Column::Numeric(height)) = table.column("height") else { panic!() };
+    /// assert_eq!(height[..2], [1.5, 2.0]);
+    /// assert!(height[2].is_nan());
+    /// ```
+    pub fn from_csv(text: &str) -> Result<Self, CsvError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (_, header) = lines.next().ok_or(CsvError::MissingHeader)?;
+        let names: Vec<String> = split_csv_line(header)
+            .ok_or(CsvError::UnterminatedQuote { row: 0 })?
+            .iter()
+            .map(|name| name.trim().to_string())
+            .collect();
+
+        let mut raw: Vec<Vec<String>> = names.iter().map(|_| Vec::new()).collect();
+        for (row, line) in lines {
+            let values = split_csv_line(line).ok_or(CsvError::UnterminatedQuote { row })?;
+            if values.len() != names.len() {
+                return Err(CsvError::RowLength {
+                    row,
+                    expected: names.len(),
+                    found: values.len(),
+                });
+            }
+            for (column, value) in raw.iter_mut().zip(values) {
+                column.push(value.trim().to_string());
+            }
+        }
+
+        let columns = raw
+            .into_iter()
+            .map(|values| {
+                let numbers: Option<Vec<f32>> = values
+                    .iter()
+                    .map(|v| match v.is_empty() {
+                        true => Some(f32::NAN),
+                        false => v.parse().ok(),
+                    })
+                    .collect();
+                match numbers {
+                    Some(numbers) => Column::Numeric(numbers),
+                    None => Column::Categorical(values),
+                }
+            })
+            .collect();
+        Ok(Self { names, columns })
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.columns.first().map_or(0, Column::len)
+    }
+
+    /// The column called `name`, if there is one.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        let i = self.names.iter().position(|n| n == name)?;
+        Some(&self.columns[i])
+    }
+}
+
+/// How [Preprocessor::fit()] turns a column into features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Subtracts the mean and divides by the standard deviation. Missing values become `0.0`.
+    Standardize,
+    /// Scales the values from `[min, max]` to `[0, 1]`. Missing values become `0.0`.
+    MinMax,
+    /// One feature per category seen while fitting, which is `1.0` for the category of the row.
+    /// Categories that weren't seen while fitting are all `0.0`. Numeric columns are treated
+    /// as categories too, e.g. for integer class labels.
+    OneHot,
+    /// Uses a numeric column as is.
+    Identity,
+}
+
+/// The transform of a column fitted by [Preprocessor::fit()].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FittedColumn {
+    Standardize { mean: f32, std: f32 },
+    MinMax { min: f32, max: f32 },
+    OneHot { categories: Vec<String> },
+    Identity,
+}
+
+impl FittedColumn {
+    fn num_features(&self) -> usize {
+        match self {
+            Self::OneHot { categories } => categories.len(),
+            _ => 1,
+        }
+    }
+}
+
+/// An error from fitting or applying a [Preprocessor].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreprocessError {
+    /// The table doesn't have a column with this name.
+    MissingColumn(String),
+    /// This column isn't [Column::Numeric], but was encoded with a numeric [Encoding].
+    NotNumeric(String),
+    /// A row index is not less than the number of rows of the table.
+    RowOutOfBounds { row: usize, num_rows: usize },
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingColumn(name) => write!(f, "PreprocessError::MissingColumn({name:?})"),
+            Self::NotNumeric(name) => write!(f, "PreprocessError::NotNumeric({name:?})"),
+            Self::RowOutOfBounds { row, num_rows } => write!(
+                f,
+                "PreprocessError::RowOutOfBounds {{ row: {row}, num_rows: {num_rows} }}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PreprocessError {}
+
+fn category(column: &Column, row: usize) -> String {
+    match column {
+        Column::Numeric(values) => values[row].to_string(),
+        Column::Categorical(values) => values[row].clone(),
+    }
+}
+
+/// Turns columns of a [Table] into a `(rows, features)` tensor, with the statistics of each
+/// column (like its mean, or its categories) fitted once on the training data.
+///
+/// With the `serde` feature enabled, this can be serialized and deserialized, so inference
+/// applies exactly the same transforms as training.
+///
+/// Fit one preprocessor for the features, and one for the labels:
+/// ```rust
+/// # use dfdx::{prelude::*, data::*};
+/// # let dev: Cpu = Default::default();
+/// let csv = "age,city,label\n20,paris,0\n40,rome,1\n30,paris,2\n50,oslo,1";
+/// let train = Table::from_csv(csv).unwrap();
+///
+/// let features = Preprocessor::fit(
+///     &train,
+///     &[("age", Encoding::Standardize), ("city", Encoding::OneHot)],
+/// )
+/// .unwrap();
+/// let labels = Preprocessor::fit(&train, &[("label", Encoding::OneHot)]).unwrap();
+/// assert_eq!(features.num_features(), 4);
+///
+/// // a batch of rows
+/// let x = features.transform_rows(&dev, &train, &[1, 2]).unwrap();
+/// assert_eq!(x.shape(), &(2, 4));
+/// let y = labels.transform_rows(&dev, &train, &[1, 2]).unwrap();
+/// assert_eq!(y.as_vec(), [0.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Preprocessor {
+    /// The name of each column, and its fitted transform, in the order of the features.
+    pub columns: Vec<(String, FittedColumn)>,
+}
+
+impl Preprocessor {
+    /// Fits the transform of each column, with its [Encoding], to the values of `table`.
+    pub fn fit(table: &Table, encodings: &[(&str, Encoding)]) -> Result<Self, PreprocessError> {
+        let mut columns = Vec::with_capacity(encodings.len());
+        for &(name, encoding) in encodings {
+            let column = table
+                .column(name)
+                .ok_or_else(|| PreprocessError::MissingColumn(name.to_string()))?;
+            let numeric = || match column {
+                Column::Numeric(values) => Ok(values.iter().copied().filter(|v| !v.is_nan())),
+                Column::Categorical(_) => Err(PreprocessError::NotNumeric(name.to_string())),
+            };
+            let fitted = match encoding {
+                Encoding::Standardize => {
+                    let (n, sum) = numeric()?.fold((0.0, 0.0), |(n, s), v| (n + 1.0, s + v));
+                    let mean = if n > 0.0 { sum / n } else { 0.0 };
+                    let var = numeric()?.map(|v| (v - mean) * (v - mean)).sum::<f32>();
+                    let std = if n > 0.0 { (var / n).sqrt() } else { 0.0 };
+                    FittedColumn::Standardize { mean, std }
+                }
+                Encoding::MinMax => {
+                    let min = numeric()?.fold(f32::INFINITY, f32::min);
+                    let max = numeric()?.fold(f32::NEG_INFINITY, f32::max);
+                    FittedColumn::MinMax { min, max }
+                }
+                Encoding::OneHot => {
+                    let mut categories: Vec<String> = Vec::new();
+                    for row in 0..column.len() {
+                        let c = category(column, row);
+                        if !categories.contains(&c) {
+                            categories.push(c);
+                        }
+                    }
+                    categories.sort();
+                    FittedColumn::OneHot { categories }
+                }
+                Encoding::Identity => numeric().map(|_| FittedColumn::Identity)?,
+            };
+            columns.push((name.to_string(), fitted));
+        }
+        Ok(Self { columns })
+    }
+
+    /// The number of features of each row after [Preprocessor::transform()].
+    pub fn num_features(&self) -> usize {
+        self.columns.iter().map(|(_, c)| c.num_features()).sum()
+    }
+
+    /// Transforms every row of `table`. See [Preprocessor::transform_rows()].
+    pub fn transform<D: TensorFromVec<f32>>(
+        &self,
+        dev: &D,
+        table: &Table,
+    ) -> Result<Tensor<(usize, usize), f32, D>, PreprocessError> {
+        let rows: Vec<usize> = (0..table.num_rows()).collect();
+        self.transform_rows(dev, table, &rows)
+    }
+
+    /// Transforms `rows` of `table` into a `(rows.len(), num_features())` tensor, e.g. with
+    /// the indices from a [super::SubsetIterator] for a batch.
+    pub fn transform_rows<D: TensorFromVec<f32>>(
+        &self,
+        dev: &D,
+        table: &Table,
+        rows: &[usize],
+    ) -> Result<Tensor<(usize, usize), f32, D>, PreprocessError> {
+        let num_rows = table.num_rows();
+        if let Some(&row) = rows.iter().find(|&&row| row >= num_rows) {
+            return Err(PreprocessError::RowOutOfBounds { row, num_rows });
+        }
+        let num_features = self.num_features();
+        let mut data = std::vec![0.0; rows.len() * num_features];
+        let mut offset = 0;
+        for (name, fitted) in &self.columns {
+            let column = table
+                .column(name)
+                .ok_or_else(|| PreprocessError::MissingColumn(name.clone()))?;
+            let values = match (column, fitted) {
+                (_, FittedColumn::OneHot { .. }) => None,
+                (Column::Numeric(values), _) => Some(values),
+                (Column::Categorical(_), _) => {
+                    return Err(PreprocessError::NotNumeric(name.clone()))
+                }
+            };
+            for (i, &row) in rows.iter().enumerate() {
+                let out = &mut data[i * num_features + offset..];
+                let x = values.map_or(f32::NAN, |v| v[row]);
+                match fitted {
+                    FittedColumn::Standardize { mean, std } if !x.is_nan() => {
+                        out[0] = if *std > 0.0 { (x - mean) / std } else { 0.0 };
+                    }
+                    FittedColumn::MinMax { min, max } if !x.is_nan() => {
+                        out[0] = if max > min {
+                            (x - min) / (max - min)
+                        } else {
+                            0.0
+                        };
+                    }
+                    FittedColumn::OneHot { categories } => {
+                        let c = category(column, row);
+                        if let Some(j) = categories.iter().position(|k| *k == c) {
+                            out[j] = 1.0;
+                        }
+                    }
+                    FittedColumn::Identity => out[0] = x,
+                    _ => {}
+                }
+            }
+            offset += fitted.num_features();
+        }
+        Ok(dev.tensor_from_vec(data, (rows.len(), num_features)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    const CSV: &str = "x, y ,name\n1,10,a\n2,,\"b,c\"\n\n3,30,a\n6,20,d";
+
+    #[test]
+    fn test_from_csv() {
+        let table = Table::from_csv(CSV).unwrap();
+        assert_eq!(table.names, ["x", "y", "name"]);
+        assert_eq!(table.num_rows(), 4);
+        assert_eq!(
+            table.columns[0],
+            Column::Numeric(std::vec![1.0, 2.0, 3.0, 6.0])
+        );
+        let Column::Numeric(y) = &table.columns[1] else {
+            panic!()
+        };
+        assert!(y[1].is_nan());
+        assert_eq!(
+            table.columns[2],
+            Column::Categorical(["a", "b,c", "a", "d"].map(String::from).to_vec())
+        );
+
+        assert_eq!(Table::from_csv(""), Err(CsvError::MissingHeader));
+        assert_eq!(
+            Table::from_csv("a,b\n1,2\n3"),
+            Err(CsvError::RowLength {
+                row: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Table::from_csv("a\n\"1"),
+            Err(CsvError::UnterminatedQuote { row: 1 })
+        );
+        let quoted = Table::from_csv("a\n\"say \"\"hi\"\"\"").unwrap();
+        let expected = std::vec!["say \"hi\"".to_string()];
+        assert_eq!(quoted.columns[0], Column::Categorical(expected));
+    }
+
+    #[test]
+    fn test_preprocessor() {
+        let dev: TestDevice = Default::default();
+        let table = Table::from_csv(CSV).unwrap();
+        let pre = Preprocessor::fit(
+            &table,
+            &[
+                ("x", Encoding::Standardize),
+                ("y", Encoding::MinMax),
+                ("name", Encoding::OneHot),
+                ("x", Encoding::Identity),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            pre.columns[0].1,
+            FittedColumn::Standardize {
+                mean: 3.0,
+                std: 3.5f32.sqrt()
+            }
+        );
+        assert_eq!(
+            pre.columns[1].1,
+            FittedColumn::MinMax {
+                min: 10.0,
+                max: 30.0
+            }
+        );
+        assert_eq!(pre.num_features(), 6);
+
+        let x = pre.transform(&dev, &table).unwrap();
+        assert_eq!(x.shape(), &(4, 6));
+        let s = 3.5f32.sqrt();
+        #[rustfmt::skip]
+        assert_eq!(
+            x.as_vec(),
+            [
+                -2.0 / s, 0.0, 1.0, 0.0, 0.0, 1.0,
+                -1.0 / s, 0.0, 0.0, 1.0, 0.0, 2.0,
+                0.0, 1.0, 1.0, 0.0, 0.0, 3.0,
+                3.0 / s, 0.5, 0.0, 0.0, 1.0, 6.0,
+            ]
+        );
+
+        // the fitted statistics are reused on new data
+        let test = Table::from_csv("x,y,name\n3,40,z").unwrap();
+        let x = pre.transform(&dev, &test).unwrap();
+        assert_eq!(x.as_vec(), [0.0, 1.5, 0.0, 0.0, 0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_preprocessor_errors() {
+        let dev: TestDevice = Default::default();
+        let table = Table::from_csv(CSV).unwrap();
+        assert_eq!(
+            Preprocessor::fit(&table, &[("z", Encoding::OneHot)]),
+            Err(PreprocessError::MissingColumn("z".to_string()))
+        );
+        assert_eq!(
+            Preprocessor::fit(&table, &[("name", Encoding::Standardize)]),
+            Err(PreprocessError::NotNumeric("name".to_string()))
+        );
+        let pre = Preprocessor::fit(&table, &[("x", Encoding::Identity)]).unwrap();
+        assert_eq!(
+            pre.transform_rows(&dev, &table, &[0, 4]).unwrap_err(),
+            PreprocessError::RowOutOfBounds {
+                row: 4,
+                num_rows: 4
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_preprocessor_serde() {
+        fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+        assert_serde::<Preprocessor>();
+    }
+}
@@ -0,0 +1,408 @@
+//! Utilities for [denoising diffusion models](https://arxiv.org/abs/2006.11239), like the
+//! [NoiseSchedule] of the forward process and the [TimestepEmbedding] that conditions a
+//! denoiser (usually a U-Net) on the timestep.
+//!
+//! One step of DDPM training noises clean samples to random timesteps with
+//! [NoiseSchedule::q_sample()], and trains the model to predict the noise:
+//!
+//! ```rust
+//! # use dfdx::{prelude::*, diffusion::*};
+//! # use rand::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! # let mut rng = StdRng::seed_from_u64(0);
+//! let schedule = NoiseSchedule::new(BetaSchedule::Cosine { offset: 0.008 }, 1000);
+//! let embed: (TimestepEmbedding<16>, Linear<16, 4>) = BuildModule::build(&dev);
+//! let denoiser: Linear<4, 4> = BuildModule::build(&dev);
+//!
+//! let x0: Tensor<Rank2<8, 4>, f32, _> = dev.sample_normal();
+//! let t = schedule.sample_timesteps(&dev, Const::<8>, &mut rng);
+//! let noise: Tensor<Rank2<8, 4>, f32, _> = dev.sample_normal();
+//! let xt = schedule.q_sample(x0, t.clone(), noise.clone());
+//! let pred = denoiser.forward(xt.traced() + embed.forward(t));
+//! let loss = mse_loss(pred, noise, ReduceMean);
+//! let grads = loss.backward();
+//! ```
+
+use crate::{
+    gradients::Tape,
+    nn::{BuildModule, Module, NonMutableModule, ZeroSizedModule},
+    shapes::*,
+    tensor::{AsVec, Tensor, TensorFromVec},
+    tensor_ops::*,
+};
+use std::{vec, vec::Vec};
+
+/// How the variance `beta_t` of the noise added at each step of the forward process grows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BetaSchedule {
+    /// `beta_t` increases linearly from `start` to `end`, from
+    /// [DDPM](https://arxiv.org/abs/2006.11239), which uses `1e-4` to `0.02` with 1000 steps.
+    Linear { start: f64, end: f64 },
+    /// `alphas_cumprod` follows `cos((t / T + offset) / (1 + offset) * pi / 2)^2`, from
+    /// [Improved DDPM](https://arxiv.org/abs/2102.09672), which uses an `offset` of `0.008`.
+    /// This destroys information more slowly than [BetaSchedule::Linear], especially for
+    /// small images. `beta_t` is clipped to `0.999` near the end.
+    Cosine { offset: f64 },
+}
+
+impl BetaSchedule {
+    /// The `num_steps` values of `beta_t`.
+    pub fn betas(&self, num_steps: usize) -> Vec<f64> {
+        match *self {
+            Self::Linear { start, end } => {
+                let step = (end - start) / (num_steps.max(2) - 1) as f64;
+                (0..num_steps).map(|t| start + step * t as f64).collect()
+            }
+            Self::Cosine { offset } => {
+                let alpha_bar = |t: usize| {
+                    let x = (t as f64 / num_steps as f64 + offset) / (1.0 + offset);
+                    (x * core::f64::consts::FRAC_PI_2).cos().powi(2)
+                };
+                (0..num_steps)
+                    .map(|t| (1.0 - alpha_bar(t + 1) / alpha_bar(t)).min(0.999))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The forward process of a diffusion model, which adds gaussian noise with variance
+/// `betas[t]` at step `t`, so that
+/// `x_t = sqrt(alphas_cumprod[t]) * x_0 + sqrt(1 - alphas_cumprod[t]) * noise`.
+///
+/// The schedule is computed in `f64` on the host, and only the coefficients of each batch are
+/// copied to the device.
+///
+/// With the `serde` feature enabled, this can be serialized and deserialized, to sample with
+/// the same schedule as training.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoiseSchedule {
+    pub betas: Vec<f64>,
+    /// The cumulative product of `1 - betas`, which is how much of the signal is left at each
+    /// step.
+    pub alphas_cumprod: Vec<f64>,
+}
+
+impl NoiseSchedule {
+    /// **Panics** if `num_steps` is `0`, or a beta is not in `(0, 1)`.
+    pub fn new(schedule: BetaSchedule, num_steps: usize) -> Self {
+        Self::from_betas(schedule.betas(num_steps))
+    }
+
+    /// A schedule with custom `betas`.
+    ///
+    /// **Panics** if `betas` is empty, or a beta is not in `(0, 1)`.
+    pub fn from_betas(betas: Vec<f64>) -> Self {
+        assert!(!betas.is_empty(), "a noise schedule needs at least 1 step");
+        let mut alpha = 1.0;
+        let alphas_cumprod = betas
+            .iter()
+            .map(|&beta| {
+                assert!(
+                    beta > 0.0 && beta < 1.0,
+                    "beta must be in (0, 1), found {beta}"
+                );
+                alpha *= 1.0 - beta;
+                alpha
+            })
+            .collect();
+        Self {
+            betas,
+            alphas_cumprod,
+        }
+    }
+
+    pub fn num_steps(&self) -> usize {
+        self.betas.len()
+    }
+
+    /// `batch` timesteps sampled uniformly from `0..num_steps`.
+    pub fn sample_timesteps<B: Dim, D: TensorFromVec<usize>, R: rand::Rng>(
+        &self,
+        dev: &D,
+        batch: B,
+        rng: &mut R,
+    ) -> Tensor<(B,), usize, D> {
+        self.try_sample_timesteps(dev, batch, rng).unwrap()
+    }
+
+    /// Fallible version of [NoiseSchedule::sample_timesteps()]
+    pub fn try_sample_timesteps<B: Dim, D: TensorFromVec<usize>, R: rand::Rng>(
+        &self,
+        dev: &D,
+        batch: B,
+        rng: &mut R,
+    ) -> Result<Tensor<(B,), usize, D>, D::Err> {
+        let t: Vec<usize> = (0..batch.size())
+            .map(|_| rng.gen_range(0..self.num_steps()))
+            .collect();
+        dev.try_tensor_from_vec(t, (batch,))
+    }
+
+    /// Noises each item of `x0` to its timestep in `t`, which is the first axis of `x0`:
+    /// `sqrt(alphas_cumprod[t]) * x0 + sqrt(1 - alphas_cumprod[t]) * noise`.
+    ///
+    /// The tape of `x0` is kept, e.g. for latent diffusion with a trained encoder.
+    ///
+    /// **Panics** if a timestep is not less than [NoiseSchedule::num_steps()].
+    pub fn q_sample<S: Shape, B: Dim, Ax: Axes, D, T: Tape<D>>(
+        &self,
+        x0: Tensor<S, f32, D, T>,
+        t: Tensor<(B,), usize, D>,
+        noise: Tensor<S, f32, D>,
+    ) -> Tensor<S, f32, D, T>
+    where
+        (B,): BroadcastShapeTo<S, Ax>,
+        D: Device<f32> + TensorFromVec<f32>,
+    {
+        self.try_q_sample(x0, t, noise).unwrap()
+    }
+
+    /// Fallible version of [NoiseSchedule::q_sample()]
+    pub fn try_q_sample<S: Shape, B: Dim, Ax: Axes, D, T: Tape<D>>(
+        &self,
+        x0: Tensor<S, f32, D, T>,
+        t: Tensor<(B,), usize, D>,
+        noise: Tensor<S, f32, D>,
+    ) -> Result<Tensor<S, f32, D, T>, D::Err>
+    where
+        (B,): BroadcastShapeTo<S, Ax>,
+        D: Device<f32> + TensorFromVec<f32>,
+    {
+        let n = (self.num_steps(),);
+        let signal: Vec<f32> = self
+            .alphas_cumprod
+            .iter()
+            .map(|a| a.sqrt() as f32)
+            .collect();
+        let noise_scale: Vec<f32> = self
+            .alphas_cumprod
+            .iter()
+            .map(|a| (1.0 - a).sqrt() as f32)
+            .collect();
+        let shape = *x0.shape();
+        let signal = x0
+            .device
+            .try_tensor_from_vec(signal, n)?
+            .try_gather(t.clone())?
+            .try_broadcast_like(&shape)?;
+        let noise_scale = x0
+            .device
+            .try_tensor_from_vec(noise_scale, n)?
+            .try_gather(t)?
+            .try_broadcast_like(&shape)?;
+        x0.try_mul(signal)?.try_add(noise.try_mul(noise_scale)?)
+    }
+}
+
+/// The sinusoidal embedding of timesteps from [Attention Is All You Need](https://arxiv.org/abs/1706.03762),
+/// as used by diffusion models. Each `(B,)` timestep becomes `DIM` features: the sines of
+/// `t * freq` followed by their cosines, where the `DIM / 2` frequencies decrease
+/// geometrically from `1` to `1 / 10000`. If `DIM` is odd, the last feature is `0.0`.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, diffusion::timestep_embedding};
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([0.0, 10.0]);
+/// let emb: Tensor<Rank2<2, 8>, f32, _> = timestep_embedding(t);
+/// assert_eq!(emb.array()[0], [0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+/// ```
+pub fn timestep_embedding<const DIM: usize, B: Dim, D, T: Tape<D>>(
+    t: Tensor<(B,), f32, D, T>,
+) -> Tensor<(B, Const<DIM>), f32, D, T>
+where
+    D: Device<f32> + TensorFromVec<f32>,
+{
+    try_timestep_embedding(t).unwrap()
+}
+
+/// Fallible version of [timestep_embedding()]
+#[allow(clippy::type_complexity)]
+pub fn try_timestep_embedding<const DIM: usize, B: Dim, D, T: Tape<D>>(
+    t: Tensor<(B,), f32, D, T>,
+) -> Result<Tensor<(B, Const<DIM>), f32, D, T>, D::Err>
+where
+    D: Device<f32> + TensorFromVec<f32>,
+{
+    let half = DIM / 2;
+    let mut freqs = vec![0.0; DIM];
+    let mut sin_mask = vec![0.0; DIM];
+    let mut cos_mask = vec![0.0; DIM];
+    for i in 0..2 * half {
+        freqs[i] = (-(10000f32.ln()) * (i % half) as f32 / half as f32).exp();
+        if i < half {
+            sin_mask[i] = 1.0;
+        } else {
+            cos_mask[i] = 1.0;
+        }
+    }
+    let shape = (t.shape().0, Const::<DIM>);
+    let dev = t.device.clone();
+    let features = |values| -> Result<Tensor<(B, Const<DIM>), f32, D>, D::Err> {
+        dev.try_tensor_from_vec(values, (Const::<DIM>,))?
+            .try_broadcast_like(&shape)
+    };
+    let (freqs, sin_mask, cos_mask) = (features(freqs)?, features(sin_mask)?, features(cos_mask)?);
+    let x = t.try_broadcast_like::<_, Axis<1>>(&shape)?.try_mul(freqs)?;
+    let sin = x.retaped::<T>().try_sin()?.try_mul(sin_mask)?;
+    x.try_cos()?.try_mul(cos_mask)?.try_add(sin)
+}
+
+/// Unit struct that impls [Module] as calling [timestep_embedding()] on `input`. Integer
+/// timesteps are converted to `f32` on the host.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, diffusion::TimestepEmbedding};
+/// # let dev: Cpu = Default::default();
+/// let model: (TimestepEmbedding<32>, Linear<32, 64>, ReLU) = BuildModule::build(&dev);
+/// let _: Tensor<Rank2<3, 64>, f32, _> = model.forward(dev.tensor([0, 10, 999]));
+/// let _: Tensor<Rank2<3, 64>, f32, _> = model.forward(dev.tensor([0.0, 0.5, 1.0]));
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct TimestepEmbedding<const DIM: usize>;
+
+impl<const DIM: usize> ZeroSizedModule for TimestepEmbedding<DIM> {}
+impl<const DIM: usize> NonMutableModule for TimestepEmbedding<DIM> {}
+
+impl<const DIM: usize, D: Device<E>, E: Dtype> BuildModule<D, E> for TimestepEmbedding<DIM> {
+    fn try_build(_: &D) -> Result<Self, <D>::Err> {
+        Ok(Default::default())
+    }
+}
+
+impl<const DIM: usize, B: Dim, D, T: Tape<D>> Module<Tensor<(B,), f32, D, T>>
+    for TimestepEmbedding<DIM>
+where
+    D: Device<f32> + TensorFromVec<f32>,
+{
+    type Output = Tensor<(B, Const<DIM>), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, t: Tensor<(B,), f32, D, T>) -> Result<Self::Output, D::Err> {
+        try_timestep_embedding(t)
+    }
+}
+
+impl<const DIM: usize, B: Dim, D> Module<Tensor<(B,), usize, D>> for TimestepEmbedding<DIM>
+where
+    D: Device<f32> + TensorFromVec<f32>,
+{
+    type Output = Tensor<(B, Const<DIM>), f32, D>;
+    type Error = D::Err;
+    fn try_forward(&self, t: Tensor<(B,), usize, D>) -> Result<Self::Output, D::Err> {
+        let steps: Vec<f32> = t.as_vec().into_iter().map(|s| s as f32).collect();
+        try_timestep_embedding(t.device.try_tensor_from_vec(steps, *t.shape())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tests::*};
+    use rand::prelude::*;
+
+    #[test]
+    fn test_linear_betas() {
+        let betas = BetaSchedule::Linear {
+            start: 0.1,
+            end: 0.4,
+        }
+        .betas(4);
+        let to_f32 = |v: &[f64]| v.iter().map(|&x| x as f32).collect::<Vec<f32>>();
+        assert_close(&to_f32(&betas), &vec![0.1, 0.2, 0.3, 0.4]);
+        let schedule = NoiseSchedule::from_betas(betas);
+        assert_close(
+            &to_f32(&schedule.alphas_cumprod),
+            &vec![0.9, 0.72, 0.504, 0.3024],
+        );
+    }
+
+    #[test]
+    fn test_cosine_betas() {
+        let schedule = NoiseSchedule::new(BetaSchedule::Cosine { offset: 0.008 }, 1000);
+        let ac = &schedule.alphas_cumprod;
+        assert!(ac.windows(2).all(|w| w[1] < w[0]));
+        assert!((ac[0] - 1.0).abs() < 1e-3);
+        // about half the signal is left halfway through
+        assert!((ac[499] - 0.5).abs() < 0.02, "{}", ac[499]);
+        assert!(ac[999] < 1e-4);
+        assert!(schedule.betas.iter().all(|&b| b > 0.0 && b <= 0.999));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_schedule_bad_beta() {
+        NoiseSchedule::from_betas(vec![0.1, 1.0]);
+    }
+
+    #[test]
+    fn test_q_sample() {
+        let dev: TestDevice = Default::default();
+        let schedule = NoiseSchedule::from_betas(vec![0.36, 0.75]);
+        let x0 = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let noise = dev.tensor([[1.0, -1.0], [0.5, 0.0], [2.0, 2.0]]);
+        let t = dev.tensor([0, 1, 0]);
+        // alphas_cumprod is [0.64, 0.16]
+        let xt = schedule.q_sample(x0.trace(), t, noise);
+        assert_close(
+            &xt.array(),
+            &[
+                [0.8 + 0.6, 1.6 - 0.6],
+                [1.2 + 0.5 * 0.916515, 1.6],
+                [4.0 + 1.2, 4.8 + 1.2],
+            ],
+        );
+        let g = xt.sum().backward();
+        assert_close(&g.get(&x0).array(), &[[0.8, 0.8], [0.4, 0.4], [0.8, 0.8]]);
+    }
+
+    #[test]
+    fn test_q_sample_images() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let schedule = NoiseSchedule::new(
+            BetaSchedule::Linear {
+                start: 1e-4,
+                end: 0.02,
+            },
+            1000,
+        );
+        let t = schedule.sample_timesteps(&dev, 4, &mut rng);
+        assert!(t.as_vec().iter().all(|&t| t < 1000));
+        let x0: Tensor<(usize, Const<3>, Const<2>, Const<2>), f32, _> =
+            dev.ones_like(&(4, Const, Const, Const));
+        let xt = schedule.q_sample::<_, _, Axes3<1, 2, 3>, _, _>(
+            x0.clone(),
+            t.clone(),
+            dev.zeros_like(x0.shape()),
+        );
+        let t = t.as_vec();
+        let xt = xt.as_vec();
+        for (i, chunk) in xt.chunks(12).enumerate() {
+            let expected = schedule.alphas_cumprod[t[i]].sqrt() as f32;
+            assert!(chunk.iter().all(|&x| (x - expected).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_timestep_embedding() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([0.0, 1.0, 100.0]);
+        let emb: Tensor<Rank2<3, 5>, f32, _> = timestep_embedding(t);
+        let emb = emb.array();
+        assert_close(&emb[0], &[0.0, 0.0, 1.0, 1.0, 0.0]);
+        // the frequencies are 1 and 1 / 100
+        assert_close(
+            &emb[1],
+            &[1f32.sin(), 0.01f32.sin(), 1f32.cos(), 0.01f32.cos(), 0.0],
+        );
+        assert_close(
+            &emb[2],
+            &[100f32.sin(), 1f32.sin(), 100f32.cos(), 1f32.cos(), 0.0],
+        );
+
+        let m: TimestepEmbedding<5> = Default::default();
+        assert_close(&m.forward(dev.tensor([0, 1, 100])).array(), &emb);
+    }
+}
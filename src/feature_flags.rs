@@ -56,6 +56,16 @@
 //! dfdx = { version = "...", features = ["numpy"] }
 //! ```
 //!
+//! # "parity"
+//!
+//! Only used for dfdx's own tests. Enables tests that compare the outputs & gradients of ops
+//! against reference values in `fixtures/parity`, with edge cases like signed zeros,
+//! denormals & infinities. See `fixtures/parity/generate.py` for how the fixtures are made.
+//!
+//! ```bash
+//! cargo test --features parity parity
+//! ```
+//!
 //! # "nightly"
 //!
 //! Enables using all features that currently require the nightly rust compiler.
@@ -0,0 +1,432 @@
+//! Utilities for generating tokens with autoregressive language models.
+//!
+//! [SpeculativeDecoding] speeds up generation with a large model by letting a small draft model
+//! guess the next few tokens, which the large model then checks with a single forward pass,
+//! as introduced in [Fast Inference from Transformers via Speculative Decoding](https://arxiv.org/abs/2211.17192).
+//! The generated tokens follow exactly the same distribution as sampling from the large model
+//! one token at a time.
+//!
+//! Both models implement [CausalLm], which runs a model on new tokens while caching the
+//! state of the previous ones (usually in a [crate::nn::KvCache] per layer), and can roll back
+//! that cache when draft tokens are rejected.
+
+use crate::{
+    shapes::*,
+    tensor::{AsVec, Tensor},
+    tensor_ops::*,
+};
+use rand::Rng;
+use std::{vec, vec::Vec};
+
+/// A language model that caches the tokens it has seen, so it only runs on the new ones.
+pub trait CausalLm<D: Device<f32>> {
+    /// Runs the model on `tokens`, which come right after the cached tokens, and caches them.
+    /// Returns the logits of the token after each of `tokens`, with shape `(tokens.len(), vocab)`.
+    fn try_forward_tokens(
+        &mut self,
+        tokens: &[usize],
+    ) -> Result<Tensor<(usize, usize), f32, D>, D::Err>;
+
+    /// The number of cached tokens.
+    fn cached_len(&self) -> usize;
+
+    /// Keeps only the first `len` cached tokens, see [crate::nn::KvCache::truncate()].
+    fn truncate(&mut self, len: usize);
+}
+
+/// The tokens generated by [SpeculativeDecoding::generate()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generation {
+    /// The new tokens, without the prompt.
+    pub tokens: Vec<usize>,
+    /// The number of tokens proposed by the draft model.
+    pub num_drafted: usize,
+    /// The number of draft tokens that were accepted by the target model.
+    pub num_accepted: usize,
+}
+
+impl Generation {
+    /// The fraction of draft tokens that were accepted, or `0.0` if nothing was drafted.
+    pub fn acceptance_rate(&self) -> f32 {
+        if self.num_drafted == 0 {
+            0.0
+        } else {
+            self.num_accepted as f32 / self.num_drafted as f32
+        }
+    }
+}
+
+/// Generates tokens from a target model with speculative decoding, using a faster draft model.
+///
+/// Each step the draft model samples `num_draft_tokens` tokens one at a time, and the target
+/// model computes the logits of all of them in one batched forward pass. Draft token `x` is
+/// accepted with probability `min(1, p(x) / q(x))`, where `p` & `q` are the target & draft
+/// distributions. At the first rejection, a token is sampled from `max(0, p - q)` instead,
+/// and if every draft token is accepted the target model's logits give one extra token for free.
+/// The rejected tokens are then removed from the caches of both models with [CausalLm::truncate()].
+///
+/// Fields:
+/// - `num_draft_tokens`: how many tokens the draft model proposes each step.
+/// - `temperature`: logits are divided by this before the softmax. `<= 0.0` means greedy
+///   decoding, where the output is the same as greedy decoding with the target model.
+/// - `stop_token`: generation stops after this token.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, generation::*};
+/// # use rand::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// /// A toy model that likes to repeat the last token.
+/// struct Repeat {
+///     dev: Cpu,
+///     cache: Vec<usize>,
+/// }
+///
+/// impl CausalLm<Cpu> for Repeat {
+///     fn try_forward_tokens(
+///         &mut self,
+///         tokens: &[usize],
+///     ) -> Result<Tensor<(usize, usize), f32, Cpu>, CpuError> {
+///         self.cache.extend_from_slice(tokens);
+///         let logits: Vec<f32> = tokens
+///             .iter()
+///             .flat_map(|&t| (0..4).map(move |v| if v == t { 2.0 } else { 0.0 }))
+///             .collect();
+///         self.dev.try_tensor_from_vec(logits, (tokens.len(), 4))
+///     }
+///     fn cached_len(&self) -> usize {
+///         self.cache.len()
+///     }
+///     fn truncate(&mut self, len: usize) {
+///         self.cache.truncate(len);
+///     }
+/// }
+///
+/// let mut target = Repeat { dev: dev.clone(), cache: Vec::new() };
+/// let mut draft = Repeat { dev: dev.clone(), cache: Vec::new() };
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let decoding = SpeculativeDecoding { temperature: 0.0, ..Default::default() };
+/// let out = decoding.generate(&mut target, &mut draft, &[1, 2], 8, &mut rng);
+/// assert_eq!(out.tokens, [2; 8]);
+/// assert_eq!(out.acceptance_rate(), 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeculativeDecoding {
+    pub num_draft_tokens: usize,
+    pub temperature: f32,
+    pub stop_token: Option<usize>,
+}
+
+impl Default for SpeculativeDecoding {
+    fn default() -> Self {
+        Self {
+            num_draft_tokens: 4,
+            temperature: 1.0,
+            stop_token: None,
+        }
+    }
+}
+
+impl SpeculativeDecoding {
+    /// Generates up to `max_new_tokens` tokens after `prompt`.
+    ///
+    /// The caches of both models should either be empty, or hold the start of `prompt`.
+    /// Afterwards they hold everything but the last generated token.
+    ///
+    /// **Panics** if `prompt` is empty, or if a model has already cached all of it.
+    pub fn generate<D: Device<f32>>(
+        &self,
+        target: &mut impl CausalLm<D>,
+        draft: &mut impl CausalLm<D>,
+        prompt: &[usize],
+        max_new_tokens: usize,
+        rng: &mut impl Rng,
+    ) -> Generation {
+        self.try_generate(target, draft, prompt, max_new_tokens, rng)
+            .unwrap()
+    }
+
+    /// Fallible version of [SpeculativeDecoding::generate()]
+    pub fn try_generate<D: Device<f32>>(
+        &self,
+        target: &mut impl CausalLm<D>,
+        draft: &mut impl CausalLm<D>,
+        prompt: &[usize],
+        max_new_tokens: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Generation, D::Err> {
+        assert!(!prompt.is_empty(), "generate: the prompt is empty");
+        assert!(
+            target.cached_len() < prompt.len() && draft.cached_len() < prompt.len(),
+            "generate: the models have already cached the whole prompt"
+        );
+        let mut seq = prompt.to_vec();
+        let mut out = Generation {
+            tokens: Vec::new(),
+            num_drafted: 0,
+            num_accepted: 0,
+        };
+
+        while out.tokens.len() < max_new_tokens {
+            // the last token is generated by the target model, so the draft is one shorter
+            let num_draft = self
+                .num_draft_tokens
+                .min(max_new_tokens - out.tokens.len() - 1);
+
+            let mut drafted = Vec::with_capacity(num_draft);
+            let mut q = Vec::with_capacity(num_draft);
+            let mut pending = seq[draft.cached_len()..].to_vec();
+            for _ in 0..num_draft {
+                let logits = draft.try_forward_tokens(&pending)?;
+                let probs = self.try_probs(logits)?.pop().unwrap();
+                let token = sample(&probs, rng);
+                drafted.push(token);
+                q.push(probs);
+                pending = vec![token];
+            }
+
+            // the logits of every draft token, plus one extra, in a single forward
+            let mut tokens = seq[target.cached_len()..].to_vec();
+            tokens.extend_from_slice(&drafted);
+            let logits = target.try_forward_tokens(&tokens)?;
+            let p = self.try_probs(logits)?;
+            let p = &p[p.len() - num_draft - 1..];
+
+            let mut accepted = 0;
+            let mut next = None;
+            for (i, &token) in drafted.iter().enumerate() {
+                let (p, q) = (&p[i], &q[i]);
+                if rng.gen::<f32>() * q[token] < p[token] {
+                    accepted += 1;
+                    continue;
+                }
+                let residual: Vec<f32> = p.iter().zip(q).map(|(p, q)| (p - q).max(0.0)).collect();
+                next = Some(if residual.iter().sum::<f32>() > 0.0 {
+                    sample(&residual, rng)
+                } else {
+                    sample(p, rng)
+                });
+                break;
+            }
+            out.num_drafted += num_draft;
+            out.num_accepted += accepted;
+            let next = next.unwrap_or_else(|| sample(&p[num_draft], rng));
+
+            let mut stopped = false;
+            for &token in drafted[..accepted].iter().chain([next].iter()) {
+                seq.push(token);
+                out.tokens.push(token);
+                if Some(token) == self.stop_token {
+                    stopped = true;
+                    break;
+                }
+            }
+            // roll back the rejected draft tokens
+            target.truncate(seq.len() - 1);
+            draft.truncate(seq.len() - 1);
+            if stopped {
+                break;
+            }
+        }
+        Ok(out)
+    }
+
+    /// The probabilities of each row of `logits`, after applying the temperature.
+    fn try_probs<D: Device<f32>>(
+        &self,
+        logits: Tensor<(usize, usize), f32, D>,
+    ) -> Result<Vec<Vec<f32>>, D::Err> {
+        let vocab = logits.shape().1;
+        if self.temperature > 0.0 {
+            let data = logits
+                .try_mul(1.0 / self.temperature)?
+                .try_softmax::<Axis<1>>()?
+                .as_vec();
+            return Ok(data.chunks(vocab).map(|r| r.to_vec()).collect());
+        }
+        Ok(logits
+            .as_vec()
+            .chunks(vocab)
+            .map(|row| {
+                let best = (0..vocab).fold(0, |b, i| if row[i] > row[b] { i } else { b });
+                (0..vocab)
+                    .map(|i| if i == best { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// Samples an index from the unnormalized probabilities `weights`.
+fn sample(weights: &[f32], rng: &mut impl Rng) -> usize {
+    let total: f32 = weights.iter().sum();
+    let mut u = rng.gen::<f32>() * total;
+    for (i, &w) in weights.iter().enumerate() {
+        if u < w {
+            return i;
+        }
+        u -= w;
+    }
+    // rounding errors, so pick the last index that could have been sampled
+    weights.iter().rposition(|&w| w > 0.0).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tests::*};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const VOCAB: usize = 3;
+
+    /// The logits of the next token only depend on the last token and its position, so
+    /// a cache that isn't rolled back correctly changes the output.
+    struct ToyLm {
+        dev: TestDevice,
+        table: [[f32; VOCAB]; VOCAB],
+        cache: Vec<usize>,
+        num_forwards: usize,
+    }
+
+    impl ToyLm {
+        fn new(table: [[f32; VOCAB]; VOCAB]) -> Self {
+            Self {
+                dev: Default::default(),
+                table,
+                cache: Vec::new(),
+                num_forwards: 0,
+            }
+        }
+
+        fn logits(&self, token: usize, pos: usize) -> [f32; VOCAB] {
+            self.table[(token + pos) % VOCAB]
+        }
+
+        fn probs(&self, token: usize, pos: usize) -> [f32; VOCAB] {
+            let logits = self.logits(token, pos);
+            let total: f32 = logits.iter().map(|x| x.exp()).sum();
+            logits.map(|x| x.exp() / total)
+        }
+    }
+
+    impl CausalLm<TestDevice> for ToyLm {
+        fn try_forward_tokens(
+            &mut self,
+            tokens: &[usize],
+        ) -> Result<Tensor<(usize, usize), f32, TestDevice>, <TestDevice as HasErr>::Err> {
+            self.num_forwards += 1;
+            let mut logits = Vec::new();
+            for &t in tokens {
+                logits.extend(self.logits(t, self.cache.len()));
+                self.cache.push(t);
+            }
+            self.dev.try_tensor_from_vec(logits, (tokens.len(), VOCAB))
+        }
+
+        fn cached_len(&self) -> usize {
+            self.cache.len()
+        }
+
+        fn truncate(&mut self, len: usize) {
+            self.cache.truncate(len);
+        }
+    }
+
+    const TARGET: [[f32; VOCAB]; VOCAB] = [[0.0, 1.0, 0.5], [2.0, 0.0, 1.0], [0.3, 0.2, 0.1]];
+    const DRAFT: [[f32; VOCAB]; VOCAB] = [[0.0, 1.2, 0.0], [1.0, 0.0, 2.0], [0.0, 0.2, 0.1]];
+
+    #[test]
+    fn test_speculative_greedy_matches_target() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let prompt = [2, 0, 1];
+        let mut expected = Vec::new();
+        let mut last = prompt[2];
+        let target = ToyLm::new(TARGET);
+        for pos in prompt.len() - 1..prompt.len() + 11 {
+            let logits = target.logits(last, pos);
+            last = (0..VOCAB).fold(0, |b, i| if logits[i] > logits[b] { i } else { b });
+            expected.push(last);
+        }
+
+        for num_draft_tokens in [0, 1, 3, 20] {
+            let mut target = ToyLm::new(TARGET);
+            let mut draft = ToyLm::new(DRAFT);
+            let decoding = SpeculativeDecoding {
+                num_draft_tokens,
+                temperature: 0.0,
+                stop_token: None,
+            };
+            let out = decoding.generate(&mut target, &mut draft, &prompt, 12, &mut rng);
+            assert_eq!(out.tokens, expected);
+            if num_draft_tokens > 0 {
+                // some draft tokens were rolled back
+                assert!(out.num_accepted < out.num_drafted);
+            }
+            assert_eq!(target.cache.len(), prompt.len() + 11);
+            assert_eq!(&target.cache[prompt.len()..], &expected[..11]);
+        }
+    }
+
+    #[test]
+    fn test_speculative_identical_draft_accepts_everything() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut target = ToyLm::new(TARGET);
+        let mut draft = ToyLm::new(TARGET);
+        let decoding = SpeculativeDecoding {
+            num_draft_tokens: 4,
+            ..Default::default()
+        };
+        let out = decoding.generate(&mut target, &mut draft, &[0], 20, &mut rng);
+        assert_eq!(out.tokens.len(), 20);
+        assert_eq!(out.num_drafted, 16);
+        assert_eq!(out.acceptance_rate(), 1.0);
+        // 4 draft tokens & a bonus token per target forward
+        assert_eq!(target.num_forwards, 4);
+    }
+
+    #[test]
+    fn test_speculative_sampling_matches_target_distribution() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let decoding = SpeculativeDecoding {
+            num_draft_tokens: 1,
+            ..Default::default()
+        };
+        let prompt = [1];
+        let n = 20000;
+        let mut counts = [[0; VOCAB]; VOCAB];
+        for _ in 0..n {
+            let mut target = ToyLm::new(TARGET);
+            let mut draft = ToyLm::new(DRAFT);
+            let out = decoding.generate(&mut target, &mut draft, &prompt, 2, &mut rng);
+            counts[out.tokens[0]][out.tokens[1]] += 1;
+        }
+
+        let target = ToyLm::new(TARGET);
+        let first = target.probs(1, 0);
+        for a in 0..VOCAB {
+            let second = target.probs(a, 1);
+            for b in 0..VOCAB {
+                let expected = first[a] * second[b];
+                let actual = counts[a][b] as f32 / n as f32;
+                assert!(
+                    (actual - expected).abs() < 0.01,
+                    "{a} {b}: {actual} vs {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_speculative_stop_token() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut target = ToyLm::new(TARGET);
+        let mut draft = ToyLm::new(DRAFT);
+        let decoding = SpeculativeDecoding {
+            num_draft_tokens: 3,
+            temperature: 0.0,
+            stop_token: Some(0),
+        };
+        let out = decoding.generate(&mut target, &mut draft, &[0], 10, &mut rng);
+        assert_eq!(out.tokens, [1, 0]);
+        assert_eq!(target.cache, [0, 1]);
+    }
+}
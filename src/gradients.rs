@@ -1,7 +1,8 @@
 //! Implementations of [GradientTape] and generic Nd array containers via [Gradients].
 #![allow(clippy::type_complexity)]
 
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::collections::{HashMap, HashSet};
 use std::{boxed::Box, vec::Vec};
 
 use crate::tensor::storage_traits::{AllocGrad, DeviceStorage};
@@ -21,9 +22,18 @@ use crate::unique_id::{HasUniqueId, UniqueId};
 /// Under the hood, it actually is a HashMap, and stores values as Box<dyn Any>. The
 /// important part of key's implementing [HasShape], and [HasDtype] is that the associated type
 /// of that trait is used to downcast the box to the expected value.
+///
+/// # Reusing gradients
+///
+/// Calling [Gradients::recycle()] keeps the buffers of all the gradients around as spares.
+/// When a gradient needs to be allocated, a spare with the same layout is filled with zeros
+/// and used instead. Passing the recycled gradients to [crate::tensor::Tensor::trace_into()]
+/// means the next backward pass doesn't need to allocate any memory for gradients,
+/// as long as the shapes are the same as last time.
 #[derive(Debug, Default)]
 pub struct Gradients {
-    gradient_by_id: HashMap<UniqueId, Box<dyn std::any::Any>>,
+    gradient_by_id: HashMap<UniqueId, Box<dyn Any + Send + Sync>>,
+    spares: HashMap<TypeId, Vec<Box<dyn Any + Send + Sync>>>,
 }
 
 impl Gradients {
@@ -36,18 +46,88 @@ impl Gradients {
         Ok(self.get_mut(t))
     }
 
-    /// Inserts a gradient for `t`
+    /// Inserts a gradient for `t`, reusing a spare buffer if there is one with the right layout.
     pub(crate) fn try_alloc_for<T>(&mut self, t: &T) -> Result<(), T::Err>
     where
         T: HasUniqueId + AllocGrad,
     {
         if !self.gradient_by_id.contains_key(t.id()) {
-            let grad = t.try_alloc_grad()?;
-            self.gradient_by_id.insert(*t.id(), Box::new(grad));
+            let grad = match self.try_take_spare(t)? {
+                Some(grad) => grad,
+                None => Box::new(t.try_alloc_grad()?),
+            };
+            self.gradient_by_id.insert(*t.id(), grad);
         }
         Ok(())
     }
 
+    /// Removes a spare buffer that can be used as the gradient of `t`, after filling it with zeros.
+    fn try_take_spare<T: AllocGrad>(
+        &mut self,
+        t: &T,
+    ) -> Result<Option<Box<dyn Any + Send + Sync>>, T::Err> {
+        let spares = match self.spares.get_mut(&TypeId::of::<T::Gradient>()) {
+            Some(spares) => spares,
+            None => return Ok(None),
+        };
+        for i in (0..spares.len()).rev() {
+            if t.try_reuse_grad(spares[i].downcast_mut().unwrap())? {
+                return Ok(Some(spares.swap_remove(i)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Keeps `grad` as a spare buffer, to be reused by a later allocation.
+    pub(crate) fn recycle_grad<G: 'static + Send + Sync>(&mut self, grad: G) {
+        self.spares
+            .entry(TypeId::of::<G>())
+            .or_default()
+            .push(Box::new(grad));
+    }
+
+    /// Removes all of the gradients, but keeps their buffers around, so that
+    /// gradients allocated later reuse them instead of allocating new memory.
+    ///
+    /// Use this with [crate::tensor::Tensor::trace_into()] to reuse the
+    /// same gradient buffers for every training step:
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+    /// let mut grads = x.trace().square().sum().backward();
+    /// for _ in 0..10 {
+    ///     grads.recycle();
+    ///     grads = x.trace_into(grads).square().sum().backward();
+    /// }
+    /// ```
+    pub fn recycle(&mut self) {
+        for (_, grad) in self.gradient_by_id.drain() {
+            let type_id = grad.as_ref().type_id();
+            self.spares.entry(type_id).or_default().push(grad);
+        }
+    }
+
+    /// Moves all of the gradients & spare buffers of `other` into `self`.
+    /// Gradients are only added if they aren't already present in `self`.
+    fn absorb(&mut self, other: &mut Self) {
+        for (id, grad) in other.gradient_by_id.drain() {
+            self.gradient_by_id.entry(id).or_insert(grad);
+        }
+        for (type_id, mut spares) in other.spares.drain() {
+            self.spares.entry(type_id).or_default().append(&mut spares);
+        }
+    }
+
+    /// Inserts `grad` as the gradient of `t`, replacing any existing gradient.
+    pub(crate) fn insert<T>(&mut self, t: &T, grad: T::Gradient)
+    where
+        T: HasUniqueId + AllocGrad,
+    {
+        self.gradient_by_id.insert(*t.id(), Box::new(grad));
+    }
+
     /// Removes and returns the data associated with `t.id()`.
     ///
     /// **Panics** if data associated with `t` is not found. This indicates an unrecoverable bug.
@@ -132,6 +212,42 @@ impl Gradients {
         let r_ref = unsafe { &*r_ptr };
         (l1_ref, l2_ref, r_ref)
     }
+
+    /// Borrows a quadruplet of gradients `(&mut L1, &mut L2, &mut L3, &R)`.
+    pub(crate) fn three_muts_and_ref<L1, L2, L3, R>(
+        &mut self,
+        l1: &L1,
+        l2: &L2,
+        l3: &L3,
+        r: &R,
+    ) -> (
+        &mut L1::Gradient,
+        &mut L2::Gradient,
+        &mut L3::Gradient,
+        &R::Gradient,
+    )
+    where
+        L1: HasUniqueId + AllocGrad,
+        L2: HasUniqueId + AllocGrad,
+        L3: HasUniqueId + AllocGrad,
+        R: HasUniqueId + AllocGrad,
+    {
+        assert_ne!(l1.id(), l2.id());
+        assert_ne!(l1.id(), l3.id());
+        assert_ne!(l2.id(), l3.id());
+        assert_ne!(l1.id(), r.id());
+        assert_ne!(l2.id(), r.id());
+        assert_ne!(l3.id(), r.id());
+        let l1_ptr = self.get_mut(l1) as *mut _;
+        let l2_ptr = self.get_mut(l2) as *mut _;
+        let l3_ptr = self.get_mut(l3) as *mut _;
+        let r_ptr = self.get(r) as *const _;
+        let l1_ref = unsafe { &mut *l1_ptr };
+        let l2_ref = unsafe { &mut *l2_ptr };
+        let l3_ref = unsafe { &mut *l3_ptr };
+        let r_ref = unsafe { &*r_ptr };
+        (l1_ref, l2_ref, l3_ref, r_ref)
+    }
 }
 
 /// Records gradient computations to execute later.
@@ -166,14 +282,27 @@ impl Gradients {
 /// This would not be possible if these chain rule operations were inside of GradientTape!
 #[allow(clippy::type_complexity)]
 pub struct GradientTape<D: DeviceStorage> {
-    operations: Vec<Box<dyn FnOnce(&mut Gradients) -> Result<(), D::Err>>>,
+    operations: Vec<Operation<D>>,
+    allocations: Vec<Box<dyn FnOnce(&mut Gradients) -> Result<(), D::Err> + Send>>,
+    pending_ids: Vec<UniqueId>,
     gradients: Gradients,
 }
 
+/// A backward operation, along with the ids of the gradients it uses.
+/// If `ids` is `None`, the operation may use any gradient.
+/// `output` is the id of the gradient it reads, if known.
+struct Operation<D: DeviceStorage> {
+    run: Box<dyn FnOnce(&mut Gradients) -> Result<(), D::Err> + Send>,
+    ids: Option<Vec<UniqueId>>,
+    output: Option<UniqueId>,
+}
+
 impl<D: DeviceStorage> Default for GradientTape<D> {
     fn default() -> Self {
         Self {
             operations: Vec::new(),
+            allocations: Vec::new(),
+            pending_ids: Vec::new(),
             gradients: Default::default(),
         }
     }
@@ -191,41 +320,258 @@ impl<D: DeviceStorage> GradientTape<D> {
     /// Add an operation to be executed later. Implementation is all left to the caller,
     /// but the operation should likely call [Gradients::ref_gradient] and [Gradients::mut_gradient].
     ///
+    /// The operation can only use the gradients of the tensors passed to [GradientTape::alloc_grad()]
+    /// since the last operation was added, which lets operations that use different gradients
+    /// run on different threads. If no gradients were allocated, the operation can use any gradient,
+    /// and it is run on its own.
+    ///
+    /// The last gradient allocated is the one the operation reads (the gradient of the op's output),
+    /// and the others are the ones it adds to. This is used to skip operations whose output
+    /// can't affect the loss, see [GradientTape::prune()].
+    ///
     /// # Arguments
     /// * `operation` - A FnOnce that acts on [Gradients].
     ///
     /// See src/tensor_ops for implementation examples.
-    pub(crate) fn add_backward_op<F: 'static + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
-        &mut self,
-        operation: F,
-    ) {
-        self.operations.push(Box::new(operation));
+    pub(crate) fn add_backward_op<F>(&mut self, operation: F)
+    where
+        F: 'static + Send + FnOnce(&mut Gradients) -> Result<(), D::Err>,
+    {
+        let ids = std::mem::take(&mut self.pending_ids);
+        self.operations.push(Operation {
+            run: Box::new(operation),
+            output: ids.last().copied(),
+            ids: (!ids.is_empty()).then_some(ids),
+        });
+    }
+
+    /// Like [GradientTape::add_backward_op()], but the operation can use any gradient,
+    /// so it is always run on its own.
+    pub(crate) fn add_exclusive_backward_op<F>(&mut self, operation: F)
+    where
+        F: 'static + Send + FnOnce(&mut Gradients) -> Result<(), D::Err>,
+    {
+        let output = self.pending_ids.last().copied();
+        self.pending_ids.clear();
+        self.operations.push(Operation {
+            run: Box::new(operation),
+            ids: None,
+            output,
+        });
+    }
+
+    /// Removes the operations that can't affect the gradient of `root`'s inputs, like the
+    /// operations of an auxiliary head whose output isn't part of the loss. Operations are
+    /// removed when nothing that runs before them adds to the gradient they read.
+    ///
+    /// The gradients of the tensors these operations would have added to are still allocated,
+    /// so they are zero instead of missing.
+    pub(crate) fn prune(&mut self, root: UniqueId) {
+        let mut live: HashSet<UniqueId> = HashSet::new();
+        live.insert(root);
+        let mut keep = std::vec![true; self.operations.len()];
+        // operations run in reverse order, so the ones that add to a gradient come later
+        for (i, operation) in self.operations.iter().enumerate().rev() {
+            match (&operation.ids, operation.output) {
+                (_, Some(output)) if !live.contains(&output) => keep[i] = false,
+                (Some(ids), _) => live.extend(ids.iter().copied()),
+                // we don't know what this adds to, so everything before it is kept
+                (None, _) => break,
+            }
+        }
+        let mut keep = keep.into_iter();
+        self.operations.retain(|_| keep.next().unwrap());
+    }
+
+    /// Records that `t` needs a gradient. Gradients are only allocated when the tape is
+    /// executed, so that they can reuse the spare buffers of the [Gradients] they end up in,
+    /// even if `t` was recorded on a different tape that was merged into this one.
+    pub(crate) fn alloc_grad<T>(&mut self, t: &T)
+    where
+        T: 'static + Clone + Send + HasUniqueId + AllocGrad<Err = D::Err>,
+    {
+        self.pending_ids.push(*t.id());
+        let t = t.clone();
+        self.allocations
+            .push(Box::new(move |grads| grads.try_alloc_for(&t)));
     }
 
     /// Compute the [Gradients]! This just runs all the operations on a new [Gradients] struct.
+    /// See [GradientTape::execute_into()] for what `num_threads` does.
     ///
     /// Note that this method takes ownership of self, so it can't be called twice!
-    pub(crate) fn execute(mut self) -> Result<Gradients, D::Err> {
-        for operation in self.operations.drain(..).rev() {
-            (operation)(&mut self.gradients)?;
+    pub(crate) fn execute(mut self, num_threads: usize) -> Result<Gradients, D::Err> {
+        let mut gradients = std::mem::take(&mut self.gradients);
+        self.execute_into(&mut gradients, num_threads)?;
+        Ok(gradients)
+    }
+
+    /// Runs all the operations on an existing [Gradients] struct, instead of a new one.
+    /// Gradients allocated by this tape are only added to `gradients` if they aren't already present.
+    ///
+    /// If `num_threads` is more than 1, operations that don't use any of the same gradients
+    /// (like the different branches of a residual) are run on up to `num_threads` threads.
+    /// Gradients are still accumulated in the same order, so the result is exactly the same.
+    pub(crate) fn execute_into(
+        mut self,
+        gradients: &mut Gradients,
+        num_threads: usize,
+    ) -> Result<(), D::Err> {
+        gradients.absorb(&mut self.gradients);
+        for allocation in self.allocations.drain(..) {
+            (allocation)(gradients)?;
         }
-        Ok(self.gradients)
+        if num_threads <= 1 || cfg!(not(feature = "std")) {
+            for operation in self.operations.drain(..).rev() {
+                (operation.run)(gradients)?;
+            }
+            return Ok(());
+        }
+        for level in schedule(self.operations.drain(..).rev()) {
+            if level.len() == 1 {
+                for operation in level {
+                    (operation.run)(gradients)?;
+                }
+            } else {
+                run_parallel(level, gradients, num_threads)?;
+            }
+        }
+        Ok(())
     }
 
     /// Moves all the operations from `other` into self. Leaves `other` empty.
     pub(crate) fn append(&mut self, other: &mut Self) {
-        self.gradients
-            .gradient_by_id
-            .extend(other.gradients.gradient_by_id.drain());
+        self.gradients.absorb(&mut other.gradients);
+        self.allocations.append(&mut other.allocations);
+        self.pending_ids.append(&mut other.pending_ids);
         self.operations.append(&mut other.operations);
     }
 }
 
+/// Groups `operations` (in the order they are run) into levels that are run one after the other.
+/// Operations in the same level never use the same gradient, and an operation is always in
+/// a later level than the operations before it that use the same gradients.
+fn schedule<D: DeviceStorage>(
+    operations: impl Iterator<Item = Operation<D>>,
+) -> Vec<Vec<Operation<D>>> {
+    let mut levels: Vec<Vec<Operation<D>>> = Vec::new();
+    let mut last_level_by_id: HashMap<UniqueId, usize> = HashMap::new();
+    // the first level that operations can be in, so they run after exclusive operations
+    let mut first_level = 0;
+    for operation in operations {
+        let level = match &operation.ids {
+            None => {
+                first_level = levels.len() + 1;
+                levels.len()
+            }
+            Some(ids) => {
+                let level = ids
+                    .iter()
+                    .filter_map(|id| last_level_by_id.get(id))
+                    .map(|l| l + 1)
+                    .fold(first_level, usize::max);
+                for id in ids {
+                    last_level_by_id.insert(*id, level);
+                }
+                level
+            }
+        };
+        if level == levels.len() {
+            levels.push(Vec::new());
+        }
+        levels[level].push(operation);
+    }
+    levels
+}
+
+/// Runs `operations`, which don't use any of the same gradients, on up to `num_threads` threads.
+/// Each operation gets its own [Gradients], containing only the gradients it uses.
+#[cfg(feature = "std")]
+fn run_parallel<D: DeviceStorage>(
+    operations: Vec<Operation<D>>,
+    gradients: &mut Gradients,
+    num_threads: usize,
+) -> Result<(), D::Err> {
+    let num_threads = num_threads.min(operations.len());
+    let mut batches: Vec<Vec<_>> = (0..num_threads).map(|_| Vec::new()).collect();
+    for (i, operation) in operations.into_iter().enumerate() {
+        let ids = operation.ids.unwrap();
+        let mut used = Gradients::default();
+        for id in ids.iter() {
+            if let Some(grad) = gradients.gradient_by_id.remove(id) {
+                used.gradient_by_id.insert(*id, grad);
+            }
+        }
+        batches[i % num_threads].push((operation.run, ids, used));
+    }
+
+    let run_batch = |batch: Vec<(_, Vec<UniqueId>, Gradients)>| -> Result<Vec<Gradients>, D::Err> {
+        let mut done = Vec::with_capacity(batch.len());
+        for (run, ids, mut used) in batch {
+            let run: Box<dyn FnOnce(&mut Gradients) -> Result<(), D::Err> + Send> = run;
+            (run)(&mut used)?;
+            // otherwise merging the gradients back could overwrite another operation's gradient
+            debug_assert!(
+                used.gradient_by_id.keys().all(|id| ids.contains(id)),
+                "a backward operation used a gradient that wasn't allocated for it"
+            );
+            done.push(used);
+        }
+        Ok(done)
+    };
+
+    let mut batches = batches.into_iter();
+    let first = batches.next().unwrap();
+    let results = std::thread::scope(|s| {
+        let handles: Vec<_> = batches
+            .map(|batch| s.spawn(move || run_batch(batch)))
+            .collect();
+        let mut results = std::vec![run_batch(first)];
+        for handle in handles {
+            let result = handle
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e));
+            results.push(result);
+        }
+        results
+    });
+    for result in results {
+        for used in result? {
+            gradients.gradient_by_id.extend(used.gradient_by_id);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "std"))]
+fn run_parallel<D: DeviceStorage>(
+    operations: Vec<Operation<D>>,
+    gradients: &mut Gradients,
+    _: usize,
+) -> Result<(), D::Err> {
+    for operation in operations {
+        (operation.run)(gradients)?;
+    }
+    Ok(())
+}
+
 /// Contains a boxed [GradientTape]. When [Tape::add_backward_op] is called,
 /// this function passes the operation directly to [GradientTape].
 #[derive(Debug, Default)]
 pub struct OwnedTape<D: DeviceStorage>(pub(crate) Box<GradientTape<D>>);
 
+impl<D: DeviceStorage> OwnedTape<D> {
+    /// Creates a tape that stores gradients in `gradients`, reusing any of its spare buffers.
+    pub(crate) fn with_gradients(gradients: Gradients) -> Self {
+        Self(Box::new(GradientTape {
+            operations: Vec::new(),
+            allocations: Vec::new(),
+            pending_ids: Vec::new(),
+            gradients,
+        }))
+    }
+}
+
 /// Contains nothing. When [Tape::add_backward_op] is called, this struct does nothing.
 #[derive(Default, Debug, Clone, Copy)]
 pub struct NoneTape;
@@ -234,11 +580,17 @@ pub struct NoneTape;
 pub trait Tape<D: DeviceStorage>: Default + Merge<Self> + Merge<NoneTape> {
     /// Whether this object currently owns the [GradientTape]. This is known at compile time.
     const OWNS_TAPE: bool;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
+    /// Adds an operation that is run during the backward pass.
+    ///
+    /// The operation may only use the gradients of the tensors passed to [Tape::try_alloc_grad()]
+    /// since the previous operation was added, because operations that use different gradients
+    /// can run on different threads, each with only its own gradients. So allocate the gradients
+    /// an operation uses right before adding it. Using any other gradient panics in debug builds.
+    fn add_backward_op<F: 'static + Send + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
         &mut self,
         operation: F,
     );
-    fn try_alloc_grad<T: HasUniqueId + AllocGrad<Err = D::Err>>(
+    fn try_alloc_grad<T: 'static + Clone + Send + HasUniqueId + AllocGrad<Err = D::Err>>(
         &mut self,
         t: &T,
     ) -> Result<(), D::Err>;
@@ -246,25 +598,29 @@ pub trait Tape<D: DeviceStorage>: Default + Merge<Self> + Merge<NoneTape> {
 
 impl<D: DeviceStorage> Tape<D> for OwnedTape<D> {
     const OWNS_TAPE: bool = true;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
+    fn add_backward_op<F: 'static + Send + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
         &mut self,
         operation: F,
     ) {
         self.0.add_backward_op(operation)
     }
-    fn try_alloc_grad<T: HasUniqueId + AllocGrad<Err = D::Err>>(
+    fn try_alloc_grad<T: 'static + Clone + Send + HasUniqueId + AllocGrad<Err = D::Err>>(
         &mut self,
         t: &T,
     ) -> Result<(), D::Err> {
-        self.0.gradients.try_alloc_for(t)
+        self.0.alloc_grad(t);
+        Ok(())
     }
 }
 
 impl<D: DeviceStorage> Tape<D> for NoneTape {
     const OWNS_TAPE: bool = false;
-    fn add_backward_op<F: 'static + FnOnce(&mut Gradients) -> Result<(), D::Err>>(&mut self, _: F) {
+    fn add_backward_op<F: 'static + Send + FnOnce(&mut Gradients) -> Result<(), D::Err>>(
+        &mut self,
+        _: F,
+    ) {
     }
-    fn try_alloc_grad<T: HasUniqueId + AllocGrad<Err = D::Err>>(
+    fn try_alloc_grad<T: 'static + Clone + Send + HasUniqueId + AllocGrad<Err = D::Err>>(
         &mut self,
         _: &T,
     ) -> Result<(), D::Err> {
@@ -296,3 +652,158 @@ impl<D: DeviceStorage> Merge<OwnedTape<D>> for OwnedTape<D> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, shapes::*, tensor::*, tensor_ops::*, tests::*, unique_id::unique_id};
+
+    fn op(ids: Option<&[UniqueId]>) -> Operation<TestDevice> {
+        Operation {
+            run: Box::new(|_| Ok(())),
+            ids: ids.map(|ids| ids.to_vec()),
+            output: ids.and_then(|ids| ids.last().copied()),
+        }
+    }
+
+    #[test]
+    fn test_schedule_levels() {
+        let [a, b, c, d] = [unique_id(), unique_id(), unique_id(), unique_id()];
+        let ops = [
+            op(Some(&[a, b])),
+            op(Some(&[a, c])),
+            op(Some(&[b, d])),
+            op(None),
+            op(Some(&[d])),
+            op(Some(&[c])),
+        ];
+        let levels = schedule(ops.into_iter());
+        let ids: Vec<Vec<_>> = levels
+            .iter()
+            .map(|l| l.iter().map(|o| o.ids.clone()).collect())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                std::vec![Some(std::vec![a, b])],
+                std::vec![Some(std::vec![a, c]), Some(std::vec![b, d])],
+                std::vec![None],
+                std::vec![Some(std::vec![d]), Some(std::vec![c])],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_backward_matches_sequential() {
+        let dev: TestDevice = Default::default();
+        type Model = (
+            Residual<(Linear<4, 8>, Tanh, Linear<8, 4>)>,
+            SplitInto<(Linear<4, 3>, Linear<4, 3>, Linear<4, 3>)>,
+        );
+        let model = Model::build_on_device(&dev);
+        let x: Tensor<Rank2<5, 4>, f32, _> = dev.sample_normal();
+
+        let loss = |x: Tensor<Rank2<5, 4>, f32, _, OwnedTape<_>>| {
+            let (a, b, c) = model.forward(x);
+            (c.exp() + a * b).mean()
+        };
+        let g1 = loss(x.trace()).backward();
+        dev.set_backward_threads(4);
+        let g2 = loss(x.trace()).backward();
+        dev.set_backward_threads(1);
+
+        assert_eq!(g1.get(&x).array(), g2.get(&x).array());
+        let (r, s) = (&model.0 .0, &model.1 .0);
+        assert_eq!(g1.get(&r.0.weight).array(), g2.get(&r.0.weight).array());
+        assert_eq!(g1.get(&r.2.bias).array(), g2.get(&r.2.bias).array());
+        assert_eq!(g1.get(&s.1.weight).array(), g2.get(&s.1.weight).array());
+        assert_eq!(g1.get(&s.2.weight).array(), g2.get(&s.2.weight).array());
+    }
+
+    #[test]
+    fn test_parallel_backward_with_checkpointing() {
+        let dev: TestDevice = Default::default();
+        dev.set_backward_threads(2);
+        type Model = SplitInto<(Checkpointed<Linear<3, 2>>, Linear<3, 2>)>;
+        let model = Model::build_on_device(&dev);
+        let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let (a, b) = model.forward(x.trace());
+        let g = (b + a).sum().backward();
+        assert_eq!(g.get(&model.0 .0 .0.bias).array(), [1.0; 2]);
+        assert_eq!(g.get(&model.0 .1.bias).array(), [1.0; 2]);
+    }
+
+    #[cfg(feature = "std")]
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic = "a backward operation used a gradient that wasn't allocated for it"]
+    fn test_parallel_op_using_other_gradient() {
+        let [a, b, c] = [unique_id(), unique_id(), unique_id()];
+        let bad: Operation<TestDevice> = Operation {
+            run: Box::new(move |g| {
+                g.gradient_by_id.insert(c, Box::new(0.0f32));
+                Ok(())
+            }),
+            ids: Some(std::vec![a]),
+            output: Some(a),
+        };
+        let ops = std::vec![bad, op(Some(&[b]))];
+        run_parallel(ops, &mut Default::default(), 2).unwrap();
+    }
+
+    #[test]
+    fn test_prune_unreachable_operations() {
+        let [x, a, b, c, d] = [
+            unique_id(),
+            unique_id(),
+            unique_id(),
+            unique_id(),
+            unique_id(),
+        ];
+        // a = f(x), b = g(x), c = h(a), d = k(b, c), and only c is in the loss
+        let mut tape: GradientTape<TestDevice> = GradientTape {
+            operations: std::vec![
+                op(Some(&[x, a])),
+                op(Some(&[x, b])),
+                op(Some(&[a, c])),
+                op(Some(&[b, c, d])),
+            ],
+            ..Default::default()
+        };
+        tape.prune(c);
+        let ids: Vec<_> = tape.operations.iter().map(|o| o.ids.clone()).collect();
+        assert_eq!(ids, [Some(std::vec![x, a]), Some(std::vec![a, c])]);
+    }
+
+    #[test]
+    fn test_prune_stops_at_unknown_operation() {
+        let [x, a, b] = [unique_id(), unique_id(), unique_id()];
+        let mut tape: GradientTape<TestDevice> = GradientTape {
+            operations: std::vec![op(Some(&[x, a])), op(None), op(Some(&[x, b]))],
+            ..Default::default()
+        };
+        tape.prune(a);
+        assert_eq!(tape.operations.len(), 2);
+        assert_eq!(tape.operations[0].ids, Some(std::vec![x, a]));
+    }
+
+    #[test]
+    fn test_backward_skips_unused_head() {
+        let dev: TestDevice = Default::default();
+        let model = <SplitInto<(Linear<3, 2>, Linear<3, 4>)>>::build_on_device(&dev);
+        let x: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+
+        let (main, aux) = model.forward(x.trace());
+        let (aux, tape) = aux.exp().split_tape();
+        let g = main.put_tape(tape).square().mean().backward();
+
+        let expected = model.0 .0.forward(x.trace()).square().mean().backward();
+        assert_eq!(g.get(&x).array(), expected.get(&x).array());
+        assert_eq!(
+            g.get(&model.0 .0.weight).array(),
+            expected.get(&model.0 .0.weight).array()
+        );
+        assert_eq!(g.get(&model.0 .1.weight).array(), [[0.0; 3]; 4]);
+        assert_eq!(g.get(&aux).array(), [[0.0; 4]; 5]);
+    }
+}
@@ -72,7 +72,7 @@
 //! # let y_true = dev.sample_normal::<Rank1<5>>().softmax();
 //! # let y = model.forward(dev.zeros::<Rank1<10>>().trace());
 //! // compute cross entropy loss
-//! let loss = cross_entropy_with_logits_loss(y, y_true);
+//! let loss = cross_entropy_with_logits_loss(y, y_true, ReduceMean);
 //!
 //! // call `backward()` to compute gradients. The tensor *must* have `OwnedTape`!
 //! let gradients: Gradients = loss.backward();
@@ -84,7 +84,7 @@
 //! # let mut model: Linear<10, 5> = BuildModule::build(&dev);
 //! # let y_true = dev.sample_normal::<Rank1<5>>().softmax();
 //! # let y = model.forward(dev.zeros::<Rank1<10>>().trace());
-//! # let loss = cross_entropy_with_logits_loss(y, y_true);
+//! # let loss = cross_entropy_with_logits_loss(y, y_true, ReduceMean);
 //! # let gradients: Gradients = loss.backward();
 //! // Use stochastic gradient descent (Sgd), with a learning rate of 1e-2, and 0.9 momentum.
 //! let mut opt = Sgd::new(&model, SgdConfig {
@@ -104,16 +104,26 @@
 extern crate alloc;
 extern crate no_std_compat as std;
 
+#[cfg(feature = "std")]
+pub mod bench;
 pub mod data;
+pub mod diffusion;
 pub mod feature_flags;
+pub mod generation;
 pub mod gradients;
 pub mod losses;
+pub mod metrics;
 pub mod nn;
+pub mod ode;
 pub mod optim;
+#[cfg(all(test, feature = "parity"))]
+mod parity;
 pub mod shapes;
+pub mod sweep;
 pub mod tensor;
 pub mod tensor_ops;
 pub mod unique_id;
+pub mod unsupervised;
 
 /// Contains subset of all public exports.
 pub mod prelude {
@@ -207,6 +217,18 @@ pub(crate) mod tests {
         }
     }
 
+    impl<T: AssertClose> AssertClose for std::vec::Vec<T> {
+        fn get_far_pair(&self, rhs: &Self, tolerance: f32) -> Option<(f32, f32)> {
+            assert_eq!(self.len(), rhs.len());
+            for (l, r) in self.iter().zip(rhs.iter()) {
+                if let Some(pair) = l.get_far_pair(r, tolerance) {
+                    return Some(pair);
+                }
+            }
+            None
+        }
+    }
+
     pub fn assert_close<T: AssertClose + std::fmt::Debug>(a: &T, b: &T) {
         a.assert_close(b, TOLERANCE);
     }
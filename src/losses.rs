@@ -1,38 +1,167 @@
 //! Standard loss functions such as [mse_loss()], [cross_entropy_with_logits_loss()], and more.
+//!
+//! Every loss function takes a [Reduction], which is how the losses of each element (or each
+//! sample, for losses between distributions) are combined: [ReduceMean] & [ReduceSum] return a
+//! scalar, while [ReduceNone] returns all the losses, e.g. for weighting samples differently:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! let logits: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+//! let targ = dev.tensor([[1.0, 0.0, 0.0]; 4]);
+//! let per_sample = cross_entropy_with_logits_loss(logits.traced(), targ, ReduceNone);
+//! let weights = dev.tensor([0.1, 0.2, 0.3, 0.4]);
+//! let loss = (per_sample * weights).sum();
+//! ```
+//!
+//! [WeightedSum] & [WeightedMean] do the same for a given weight (or mask) tensor.
 
-use crate::{gradients::Tape, shapes::*, tensor::Tensor, tensor_ops::*};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{PutTape, SplitTape, Tensor},
+    tensor_ops::*,
+};
+
+/// How a loss function combines the losses of shape `S`. See [ReduceNone], [ReduceSum],
+/// [ReduceMean], [WeightedSum] and [WeightedMean].
+pub trait Reduction<S: Shape, D: Device<f32>>: Sized {
+    type Output: Shape;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<Self::Output, f32, D, T>;
+
+    /// Reduces the losses that are the sums of `terms` along `Ax`, like the loss of each
+    /// sample of [cross_entropy_with_logits_loss()].
+    fn reduce_sums<Ax: Axes, Src, T: Tape<D>>(
+        self,
+        terms: Tensor<Src, f32, D, T>,
+    ) -> Tensor<Self::Output, f32, D, T>
+    where
+        Src: ReduceShape<Ax, Reduced = S>,
+    {
+        self.reduce(terms.sum::<S, Ax>())
+    }
+}
+
+/// Returns every loss without reducing them. **Pytorch equivalent**: `reduction="none"`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReduceNone;
+
+/// Sums all the losses. **Pytorch equivalent**: `reduction="sum"`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReduceSum;
+
+/// Averages all the losses. **Pytorch equivalent**: `reduction="mean"`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReduceMean;
+
+/// Multiplies each loss by a weight of the same shape as the losses, then sums them.
+///
+/// For per sample weights of element wise losses, broadcast the weights to the shape of the
+/// losses.
+#[derive(Debug, Clone)]
+pub struct WeightedSum<W>(pub W);
+
+/// Multiplies each loss by a weight of the same shape as the losses, then divides their sum
+/// by the sum of the weights. With a mask of `0.0`s & `1.0`s this is the mean of the unmasked
+/// losses, like ignoring padded timesteps:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let logits: Tensor<Rank3<2, 4, 3>, f32, _> = dev.sample_normal();
+/// let targ = dev.tensor([[[1.0, 0.0, 0.0]; 4]; 2]);
+/// // the second sequence only has 2 timesteps
+/// let mask = dev.tensor([[1.0, 1.0, 1.0, 1.0], [1.0, 1.0, 0.0, 0.0]]);
+/// let loss = cross_entropy_with_logits_loss(logits.traced(), targ, WeightedMean(mask));
+/// ```
+///
+/// **Pytorch equivalent**: the `weight` argument of `nll_loss` with `reduction="mean"`.
+#[derive(Debug, Clone)]
+pub struct WeightedMean<W>(pub W);
+
+impl<S: Shape, D: Device<f32>> Reduction<S, D> for ReduceNone {
+    type Output = S;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<S, f32, D, T> {
+        losses
+    }
+}
+
+impl<S: Shape, D: Device<f32>> Reduction<S, D> for ReduceSum {
+    type Output = Rank0;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<Rank0, f32, D, T> {
+        losses.sum()
+    }
+}
+
+impl<S: Shape, D: Device<f32>> Reduction<S, D> for ReduceMean {
+    type Output = Rank0;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<Rank0, f32, D, T> {
+        losses.mean()
+    }
+
+    fn reduce_sums<Ax: Axes, Src, T: Tape<D>>(
+        self,
+        terms: Tensor<Src, f32, D, T>,
+    ) -> Tensor<Rank0, f32, D, T>
+    where
+        Src: ReduceShape<Ax, Reduced = S>,
+    {
+        // the mean of the sums is the mean of all the terms, scaled by the size of `Ax`
+        let num_terms = <Src as HasAxes<Ax>>::size(terms.shape()) as f32;
+        terms.mean() * num_terms
+    }
+}
+
+impl<S: Shape, D: Device<f32>> Reduction<S, D> for WeightedSum<Tensor<S, f32, D>> {
+    type Output = Rank0;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<Rank0, f32, D, T> {
+        (losses * self.0).sum()
+    }
+}
+
+impl<S: Shape, D: Device<f32>> Reduction<S, D> for WeightedMean<Tensor<S, f32, D>> {
+    type Output = Rank0;
+    fn reduce<T: Tape<D>>(self, losses: Tensor<S, f32, D, T>) -> Tensor<Rank0, f32, D, T> {
+        let total = self.0.clone().sum();
+        (losses * self.0).sum() / total
+    }
+}
 
 /// [Mean Squared Error](https://en.wikipedia.org/wiki/Mean_squared_error).
-/// This computes `(pred - targ).square().mean()`.
+/// This computes `(pred - targ).square()`, reduced with `reduction`.
 ///
 /// See [MeanTo], [square()], and [sub()].
-pub fn mse_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn mse_loss<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T> {
-    (pred - targ).square().mean()
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T> {
+    reduction.reduce((pred - targ).square())
 }
 
 /// [Root Mean square error](https://en.wikipedia.org/wiki/Root-mean-square_deviation).
-/// This computes `(pred - targ).square().mean().sqrt()`
+/// This computes `mse_loss(pred, targ, reduction).sqrt()`, so with [ReduceNone] this is the
+/// absolute error of each element, and with [ReduceSum] the euclidean distance.
 ///
 /// See [mse_loss()] and [sqrt()]
-pub fn rmse_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn rmse_loss<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T> {
-    mse_loss(pred, targ).sqrt()
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T> {
+    mse_loss(pred, targ, reduction).sqrt()
 }
 
 /// [Mean absolute error](https://en.wikipedia.org/wiki/Mean_absolute_error).
-/// This computes `(pred - targ).abs().mean()`
+/// This computes `(pred - targ).abs()`, reduced with `reduction`.
 ///
 /// See [MeanTo], [abs()], and [sub()]
-pub fn mae_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn mae_loss<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T> {
-    (pred - targ).abs().mean()
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T> {
+    reduction.reduce((pred - targ).abs())
 }
 
 /// [Huber Loss](https://en.wikipedia.org/wiki/Huber_loss)
@@ -47,14 +176,15 @@ pub fn mae_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
 /// # let dev: Cpu = Default::default();
 /// let x = dev.tensor([-1.0, -0.5]);
 /// let y = dev.tensor([0.5, 0.5]);
-/// let loss = huber_loss(x.traced(), y, 1.0);
+/// let loss = huber_loss(x.traced(), y, 1.0, ReduceMean);
 /// ```
-pub fn huber_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn huber_loss<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
     delta: f32,
-) -> Tensor<Rank0, f32, D, T> {
-    pred.huber_error(targ, delta).mean()
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T> {
+    reduction.reduce(pred.huber_error(targ, delta))
 }
 
 /// Smooth l1 loss (closely related to [Huber Loss](https://en.wikipedia.org/wiki/Huber_loss))
@@ -71,18 +201,20 @@ pub fn huber_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
 /// # let dev: Cpu = Default::default();
 /// let x = dev.tensor([-1.0, -0.5]);
 /// let y = dev.tensor([0.5, 0.5]);
-/// let loss = smooth_l1_loss(x.traced(), y, 1.0);
+/// let loss = smooth_l1_loss(x.traced(), y, 1.0, ReduceMean);
 /// ```
-pub fn smooth_l1_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn smooth_l1_loss<S: Shape, D: Device<f32>, T: Tape<D>, R: Reduction<S, D>>(
     pred: Tensor<S, f32, D, T>,
     targ: Tensor<S, f32, D>,
     delta: f32,
-) -> Tensor<Rank0, f32, D, T> {
-    huber_loss(pred, targ, delta) / delta
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T> {
+    huber_loss(pred, targ, delta, reduction) / delta
 }
 
 /// [Cross entropy loss](https://en.wikipedia.org/wiki/Cross_entropy#Cross-entropy_loss_function_and_logistic_regression).
-/// This computes: `-(logits.log_softmax() * target_probs).sum(-1).mean()`
+/// This computes: `-(logits.log_softmax() * target_probs).sum(-1)`, which is the loss of each
+/// sample, reduced with `reduction`.
 ///
 /// This will call `log_softmax(logits)`, so make sure logits is **not the
 /// output from** [softmax()] or [log_softmax()] already.
@@ -91,6 +223,7 @@ pub fn smooth_l1_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
 ///
 /// - `logits`: The un-normalized output from a model. [log_softmax()] is called **in** this function
 /// - `target_probs`: Target containing probability vectors **NOT** class indices.
+/// - `reduction`: How to combine the loss of each sample, e.g. [ReduceMean].
 ///
 /// # Example
 /// ```rust
@@ -98,21 +231,24 @@ pub fn smooth_l1_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
 /// # let dev: Cpu = Default::default();
 /// let logits = dev.tensor([-1.0, -0.5]);
 /// let target_probs = dev.tensor([0.5, 0.5]);
-/// let loss = cross_entropy_with_logits_loss(logits.traced(), target_probs);
+/// let loss = cross_entropy_with_logits_loss(logits.traced(), target_probs, ReduceMean);
 /// ```
-pub fn cross_entropy_with_logits_loss<Ax: Axes, S, D: Device<f32>, T: Tape<D>>(
+pub fn cross_entropy_with_logits_loss<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
     logits: Tensor<S, f32, D, T>,
     target_probs: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T>
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T>
 where
     S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D>,
 {
-    let last_axis_numel = <S as HasAxes<Ax>>::size(logits.shape()) as f32;
-    (logits.log_softmax::<Ax>() * target_probs).mean().negate() * last_axis_numel
+    let terms = logits.log_softmax::<Ax>() * target_probs;
+    reduction.reduce_sums::<Ax, S, T>(terms.negate())
 }
 
 /// [KL Divergence loss](https://en.wikipedia.org/wiki/Kullback%E2%80%93Leibler_divergence).
-/// This computes `(target_probs * (target_probs.log() - logits.log_softmax())).sum(-1).mean()`
+/// This computes `(target_probs * (target_probs.log() - logits.log_softmax())).sum(-1)`, which
+/// is the loss of each sample, reduced with `reduction`.
 ///
 /// This will call `log_softmax(logits)`, so make sure logits is **not the
 /// output from** [softmax()] or [log_softmax()] already.
@@ -121,6 +257,7 @@ where
 ///
 /// - `logits`: The un-normalized output from a model. [log_softmax()] is called **in** this function
 /// - `target_probs`: Target containing probability vectors **NOT** class indices.
+/// - `reduction`: How to combine the loss of each sample, e.g. [ReduceMean].
 ///
 /// # Example
 /// ```rust
@@ -128,21 +265,96 @@ where
 /// # let dev: Cpu = Default::default();
 /// let logits = dev.tensor([-1.0, -0.5]);
 /// let target_probs = dev.tensor([0.5, 0.5]);
-/// let loss = kl_div_with_logits_loss(logits.traced(), target_probs);
+/// let loss = kl_div_with_logits_loss(logits.traced(), target_probs, ReduceMean);
 /// ```
-pub fn kl_div_with_logits_loss<Ax: Axes, S: Shape<LastAxis = Ax>, D: Device<f32>, T: Tape<D>>(
+pub fn kl_div_with_logits_loss<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
     logits: Tensor<S, f32, D, T>,
     target_probs: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T>
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T>
 where
-    S: ReduceShape<Ax>,
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D>,
 {
-    let last_axis_numel = <S as HasAxes<Ax>>::size(logits.shape()) as f32;
     let probs = logits.log_softmax::<Ax>();
-    ((probs - target_probs.clone().ln()) * target_probs)
-        .mean()
-        .negate()
-        * last_axis_numel
+    let terms = (probs - target_probs.clone().ln()) * target_probs;
+    reduction.reduce_sums::<Ax, S, T>(terms.negate())
+}
+
+/// Knowledge distillation loss, as introduced in
+/// [Distilling the Knowledge in a Neural Network](https://arxiv.org/abs/1503.02531).
+///
+/// This computes a weighted sum of a soft target loss against the teacher, and a hard target loss,
+/// for each sample:
+/// ```ignore
+/// alpha * temperature^2 * kl_div_with_logits_loss(student / temperature, softmax(teacher / temperature))
+///     + (1 - alpha) * cross_entropy_with_logits_loss(student, target_probs)
+/// ```
+///
+/// The `temperature^2` keeps the magnitude of the soft target gradients independent of `temperature`.
+///
+/// # Arguments
+///
+/// - `student_logits`: The un-normalized output from the model being trained.
+/// - `teacher_logits`: The un-normalized output from the teacher.
+/// - `target_probs`: Target containing probability vectors **NOT** class indices.
+/// - `temperature`: Softens both distributions. Usually between 1 and 20.
+/// - `alpha`: Weight of the soft target loss, between 0 and 1.
+/// - `reduction`: How to combine the loss of each sample, e.g. [ReduceMean].
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*};
+/// # let dev: Cpu = Default::default();
+/// let student = dev.tensor([[-1.0, -0.5, 0.0]]);
+/// let teacher = dev.tensor([[-2.0, 1.0, 0.5]]);
+/// let target_probs = dev.tensor([[0.0, 1.0, 0.0]]);
+/// let loss = distillation_loss(student.traced(), teacher, target_probs, 4.0, 0.9, ReduceMean);
+/// ```
+///
+/// Intermediate hidden states can be matched by running the layers of both models separately,
+/// and adding e.g. [mse_loss()] between them:
+/// ```rust
+/// # use dfdx::{prelude::*};
+/// # let dev: Cpu = Default::default();
+/// let teacher: (Linear<4, 8>, ReLU, Linear<8, 3>) = BuildModule::build(&dev);
+/// let student: (Linear<4, 8>, Linear<8, 3>) = BuildModule::build(&dev);
+/// let x: Tensor<Rank2<2, 4>, f32, _> = dev.sample_normal();
+///
+/// let t_hidden = teacher.1.forward(teacher.0.forward(x.clone()));
+/// let t_logits = teacher.2.forward(t_hidden.clone());
+///
+/// let (s_hidden, tape) = student.0.forward(x.traced()).split_tape();
+/// let hidden_loss = mse_loss(s_hidden.clone().put_tape(tape), t_hidden, ReduceMean);
+/// let (hidden_loss, tape) = hidden_loss.split_tape();
+/// let s_logits = student.1.forward(s_hidden.put_tape(tape));
+///
+/// let targ = dev.tensor([[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]);
+/// let loss = distillation_loss(s_logits, t_logits, targ, 2.0, 0.5, ReduceMean) + hidden_loss;
+/// ```
+pub fn distillation_loss<Ax: Axes, S, D: Device<f32>, T: Tape<D>, R>(
+    student_logits: Tensor<S, f32, D, T>,
+    teacher_logits: Tensor<S, f32, D>,
+    target_probs: Tensor<S, f32, D>,
+    temperature: f32,
+    alpha: f32,
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T>
+where
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    R: Reduction<S::Reduced, D>,
+{
+    let teacher_probs = (teacher_logits / temperature).softmax::<Ax>();
+    let (student_logits, tape) = student_logits.split_tape();
+    let soft = kl_div_with_logits_loss(
+        student_logits.clone().put_tape(tape) / temperature,
+        teacher_probs,
+        ReduceNone,
+    ) * (alpha * temperature * temperature);
+    let (soft, tape) = soft.split_tape();
+    let hard =
+        cross_entropy_with_logits_loss(student_logits.put_tape(tape), target_probs, ReduceNone);
+    reduction.reduce(hard * (1.0 - alpha) + soft)
 }
 
 /// [Binary Cross Entropy](https://en.wikipedia.org/wiki/Cross_entropy#Cross-entropy_loss_function_and_logistic_regression)
@@ -153,6 +365,7 @@ where
 /// # Inputs
 /// - `logits` - unnormalized inputs. **NOT** output of sigmoid
 /// - `target_probs` - target values between 0 and 1.
+/// - `reduction`: How to combine the loss of each element, e.g. [ReduceMean].
 ///
 /// # Example
 /// ```rust
@@ -160,13 +373,17 @@ where
 /// # let dev: Cpu = Default::default();
 /// let logits = dev.tensor([-1.0, -0.5]);
 /// let target_probs = dev.tensor([1.0, 0.25]);
-/// let loss = binary_cross_entropy_with_logits_loss(logits.traced(), target_probs);
+/// let loss = binary_cross_entropy_with_logits_loss(logits.traced(), target_probs, ReduceMean);
 /// ```
-pub fn binary_cross_entropy_with_logits_loss<S: Shape, D: Device<f32>, T: Tape<D>>(
+pub fn binary_cross_entropy_with_logits_loss<S: Shape, D: Device<f32>, T: Tape<D>, R>(
     logits: Tensor<S, f32, D, T>,
     target_probs: Tensor<S, f32, D>,
-) -> Tensor<Rank0, f32, D, T> {
-    logits.bce_with_logits(target_probs).mean()
+    reduction: R,
+) -> Tensor<R::Output, f32, D, T>
+where
+    R: Reduction<S, D>,
+{
+    reduction.reduce(logits.bce_with_logits(target_probs))
 }
 
 #[cfg(test)]
@@ -179,7 +396,7 @@ mod tests {
         let dev: TestDevice = Default::default();
         let x = dev.tensor([0.87248087, -0.24252531, -1.0060949, 1.155084, 1.5545048]);
         let y = dev.tensor([-0.90954804, -1.0193185, -0.39221755, 2.2524886, 1.3035554]);
-        let loss = mse_loss(x.trace(), y);
+        let loss = mse_loss(x.trace(), y, ReduceMean);
         assert_eq!(loss.array(), 1.0846305);
         let g = loss.backward();
         assert_eq!(
@@ -193,7 +410,7 @@ mod tests {
         let dev: TestDevice = Default::default();
         let x = dev.tensor([0.87248087, -0.24252531, -1.0060949, 1.155084, 1.5545048]);
         let y = dev.tensor([-0.90954804, -1.0193186, -0.39221755, 2.2524886, 1.3035554]);
-        let loss = mae_loss(x.trace(), y);
+        let loss = mae_loss(x.trace(), y, ReduceMean);
         assert_close(&loss.array(), &0.9042107);
         let g = loss.backward();
         assert_eq!(g.get(&x).array(), [0.2, 0.2, -0.2, -0.2, 0.2]);
@@ -210,7 +427,7 @@ mod tests {
             [0.3180433, 0.15164024, 0.2352255, 0.08821669, 0.20687431],
             [0.15627657, 0.29779273, 0.10897867, 0.2879545, 0.14899758],
         ]);
-        let loss = cross_entropy_with_logits_loss(x.trace(), y.clone());
+        let loss = cross_entropy_with_logits_loss(x.trace(), y.clone(), ReduceMean);
         assert_close(&loss.array(), &1.9889611);
         let g = loss.backward();
         assert_close(
@@ -233,12 +450,12 @@ mod tests {
     fn test_hard_crossentropy() {
         let dev: TestDevice = Default::default();
         let x = dev.tensor([0.87248087, -0.24252531, -1.0060949, 1.155084, 1.5545048]);
-        let losses = [1.5655229, 2.680529, 3.444099, 1.2829198, 0.883499];
+        let losses = [1.5655229, 2.680529, 3.444099, 1.2829196, 0.8834989];
         for i in 0..5 {
             let mut targ = [0.0; 5];
             targ[i] = 1.0;
             let y = dev.tensor(targ);
-            let loss = cross_entropy_with_logits_loss(x.trace(), y.clone());
+            let loss = cross_entropy_with_logits_loss(x.trace(), y.clone(), ReduceMean);
             assert_eq!(loss.array(), losses[i]);
         }
     }
@@ -260,8 +477,8 @@ mod tests {
             [0.5809, 0.3623, 0.0568],
             [0.0166, 0.8512, 0.1322],
         ]);
-        let loss = kl_div_with_logits_loss(logits.trace(), targ);
-        assert_eq!(loss.array(), 0.40656143);
+        let loss = kl_div_with_logits_loss(logits.trace(), targ, ReduceMean);
+        assert_eq!(loss.array(), 0.40656146);
         let g = loss.backward();
         assert_close(
             &g.get(&logits).array(),
@@ -275,6 +492,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_distillation_loss() {
+        let dev: TestDevice = Default::default();
+        let student = dev.tensor([[-0.2354, 0.4408, 0.9688], [0.7420, 0.7186, 1.0785]]);
+        let teacher = dev.tensor([[1.2, -0.3, 0.1], [0.2, 2.0, -1.0]]);
+        let targ = dev.tensor([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+        let (t, alpha) = (3.0, 0.75);
+
+        let loss = distillation_loss(
+            student.trace(),
+            teacher.clone(),
+            targ.clone(),
+            t,
+            alpha,
+            ReduceMean,
+        );
+        let loss_value = loss.array();
+        let g = loss.backward();
+
+        let soft_probs = (teacher / t).softmax::<Axis<1>>();
+        let soft = kl_div_with_logits_loss(student.trace() / t, soft_probs, ReduceMean);
+        let hard = cross_entropy_with_logits_loss(student.trace(), targ, ReduceMean);
+        assert_close(
+            &loss_value,
+            &(alpha * t * t * soft.array() + (1.0 - alpha) * hard.array()),
+        );
+
+        let g_soft = soft.backward();
+        let g_hard = hard.backward();
+        let g_soft = g_soft.get(&student).array();
+        let g_hard = g_hard.get(&student).array();
+        let mut expected = [[0.0; 3]; 2];
+        for i in 0..2 {
+            for j in 0..3 {
+                expected[i][j] = alpha * t * t * g_soft[i][j] + (1.0 - alpha) * g_hard[i][j];
+            }
+        }
+        assert_close(&g.get(&student).array(), &expected);
+    }
+
     #[test]
     fn test_bce() {
         let dev: TestDevice = Default::default();
@@ -288,7 +545,7 @@ mod tests {
             [0.168392, 0.7987092, 0.1177533],
             [0.7026833, 0.5563793, 0.6429267],
         ]);
-        let loss = binary_cross_entropy_with_logits_loss(logit.trace(), prob.clone());
+        let loss = binary_cross_entropy_with_logits_loss(logit.trace(), prob.clone(), ReduceMean);
         assert_close(&loss.array(), &0.7045728);
 
         let g = loss.backward();
@@ -318,7 +575,7 @@ mod tests {
         let logit = dev.tensor([[100.0; 3], [-100.0; 3], [-1.0, 0.0, 1.0]]);
         let targ = dev.tensor([[0.0, 0.5, 1.0]; 3]);
 
-        let loss = binary_cross_entropy_with_logits_loss(logit.trace(), targ.clone());
+        let loss = binary_cross_entropy_with_logits_loss(logit.trace(), targ.clone(), ReduceMean);
         assert_eq!(loss.array(), 33.479965);
 
         let g = loss.backward();
@@ -356,7 +613,7 @@ mod tests {
             [-2.0449343, 1.8117315, 1.7505344, -1.2522424, 1.0921133],
         ]);
 
-        let loss = huber_loss(x.trace(), y.clone(), 0.5);
+        let loss = huber_loss(x.trace(), y.clone(), 0.5, ReduceMean);
         assert_eq!(loss.array(), 0.24506615);
 
         let g = loss.backward();
@@ -392,7 +649,7 @@ mod tests {
             [-2.0449343, 1.8117315, 1.7505344, -1.2522424, 1.0921133],
         ]);
 
-        let loss = smooth_l1_loss(x.trace(), y.clone(), 0.5);
+        let loss = smooth_l1_loss(x.trace(), y.clone(), 0.5, ReduceMean);
         assert_eq!(loss.array(), 0.4901323);
 
         let g = loss.backward();
@@ -413,4 +670,60 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_reductions() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.0, 1.0]]);
+        let y = dev.tensor([[1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+
+        let loss = mse_loss(x.trace(), y.clone(), ReduceNone);
+        assert_eq!(loss.array(), [[0.0, 4.0, 9.0], [1.0, 0.0, 1.0]]);
+        let g = loss.sum().backward();
+        assert_eq!(g.get(&x).array(), [[0.0, 4.0, 6.0], [-2.0, 0.0, 2.0]]);
+
+        let loss = mse_loss(x.trace(), y.clone(), ReduceSum);
+        assert_eq!(loss.array(), 15.0);
+        let loss = mae_loss(x.trace(), y.clone(), ReduceSum);
+        assert_eq!(loss.array(), 7.0);
+
+        // losses between distributions are per sample
+        let targ = dev.tensor([[0.0, 0.0, 1.0], [0.5, 0.5, 0.0]]);
+        let per_sample = cross_entropy_with_logits_loss(x.trace(), targ.clone(), ReduceNone);
+        let mean = cross_entropy_with_logits_loss(x.trace(), targ.clone(), ReduceMean);
+        let sum = cross_entropy_with_logits_loss(x.trace(), targ.clone(), ReduceSum);
+        let per_sample = per_sample.array();
+        assert_close(&per_sample, &[0.40760596, 1.9076059]);
+        assert_close(&mean.array(), &((per_sample[0] + per_sample[1]) / 2.0));
+        assert_close(&sum.array(), &(per_sample[0] + per_sample[1]));
+
+        // weighting samples
+        let loss = kl_div_with_logits_loss(x.trace(), targ.clone(), ReduceNone);
+        let g = (loss * dev.tensor([1.0, 0.0])).sum().backward();
+        assert_eq!(g.get(&x).array()[1], [0.0; 3]);
+    }
+
+    #[test]
+    fn test_weighted_reductions() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.0, 1.0]]);
+        let y = dev.tensor([[1.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+        let w = dev.tensor([[1.0, 0.5, 0.0], [2.0, 0.0, 0.0]]);
+
+        let loss = mse_loss(x.trace(), y.clone(), WeightedSum(w.clone()));
+        assert_eq!(loss.array(), 4.0);
+        let g = loss.backward();
+        assert_eq!(g.get(&x).array(), [[0.0, 2.0, 0.0], [-4.0, 0.0, 0.0]]);
+
+        let loss = mse_loss(x.trace(), y.clone(), WeightedMean(w));
+        assert_eq!(loss.array(), 4.0 / 3.5);
+
+        // masking padded samples is the same as dropping them
+        let targ = dev.tensor([[0.0, 0.0, 1.0], [0.5, 0.5, 0.0]]);
+        let mask = dev.tensor([1.0, 0.0]);
+        let loss = cross_entropy_with_logits_loss(x.trace(), targ.clone(), WeightedMean(mask));
+        assert_close(&loss.array(), &0.40760596);
+        let g = loss.backward();
+        assert_eq!(g.get(&x).array()[1], [0.0; 3]);
+    }
 }
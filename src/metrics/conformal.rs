@@ -0,0 +1,270 @@
+use crate::{shapes::*, tensor::Tensor, tensor_ops::*};
+
+use std::vec::Vec;
+
+/// The `ceil((n + 1) * (1 - alpha))`th smallest of the `n` scores, which is the threshold
+/// of split conformal prediction, or infinity if there are too few scores for `alpha`.
+fn conformal_quantile(mut scores: Vec<f32>, alpha: f32) -> f32 {
+    assert!(
+        alpha > 0.0 && alpha < 1.0,
+        "alpha must be in (0, 1), found {alpha}"
+    );
+    let n = scores.len();
+    let k = ((n + 1) as f32 * (1.0 - alpha)).ceil() as usize;
+    if k > n {
+        return f32::INFINITY;
+    }
+    scores.sort_by(|a, b| a.total_cmp(b));
+    scores[k.max(1) - 1]
+}
+
+/// Turns the probabilities of a classifier into prediction sets, which contain the true class
+/// with probability at least `1 - alpha`, using split conformal prediction from
+/// [A Gentle Introduction to Conformal Prediction](https://arxiv.org/abs/2107.07511).
+///
+/// [ConformalClassifier::calibrate()] fits a threshold to the probabilities the model gave
+/// to the true classes of a held-out calibration set. Each set then has every class with a
+/// probability of at least `1 - threshold`, so uncertain predictions get larger sets.
+///
+/// The guarantee holds on average over new data that is exchangeable with the calibration
+/// set (e.g. from the same distribution), for any model, but better models give smaller sets.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::*};
+/// # let dev: Cpu = Default::default();
+/// let cal_probs = dev.tensor([[0.9, 0.1], [0.6, 0.4], [0.2, 0.8], [0.3, 0.7]]);
+/// let cal_labels = dev.tensor([0, 1, 1, 1]);
+/// let conformal = ConformalClassifier::calibrate(&cal_probs, &cal_labels, 0.2);
+/// assert_eq!(conformal.threshold, 0.6);
+///
+/// let probs = dev.tensor([[0.95, 0.05], [0.5, 0.5]]);
+/// assert_eq!(conformal.prediction_sets(&probs), [vec![0], vec![0, 1]]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConformalClassifier {
+    /// The largest `1 - p` a class can have and still be in a set, where `p` is the
+    /// probability of the class.
+    pub threshold: f32,
+}
+
+impl ConformalClassifier {
+    /// Fits the threshold for sets that miss the true class with probability at most `alpha`,
+    /// from the probabilities `probs` (e.g. from [softmax()]) the model gave to the held-out
+    /// data with `labels`.
+    ///
+    /// With fewer than `1 / alpha - 1` rows there is no finite threshold, so every set has
+    /// every class.
+    ///
+    /// **Panics** if `alpha` is not in `(0, 1)`.
+    pub fn calibrate<B: Dim, C: Dim, D: Device<f32>>(
+        probs: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+        alpha: f32,
+    ) -> Self {
+        Self::try_calibrate(probs, labels, alpha).unwrap()
+    }
+
+    /// See [ConformalClassifier::calibrate()]
+    pub fn try_calibrate<B: Dim, C: Dim, D: Device<f32>>(
+        probs: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+        alpha: f32,
+    ) -> Result<Self, D::Err> {
+        let scores = probs
+            .clone()
+            .try_select(labels.clone())?
+            .try_negate()?
+            .try_add(1.0)?;
+        let mut buf = std::vec![0.0; scores.shape().0.size()];
+        scores.copy_into(&mut buf);
+        Ok(Self {
+            threshold: conformal_quantile(buf, alpha),
+        })
+    }
+
+    /// The classes in the prediction set of each row of `probs`, in ascending order.
+    pub fn prediction_sets<B: Dim, C: Dim, D: Device<f32>>(
+        &self,
+        probs: &Tensor<(B, C), f32, D>,
+    ) -> Vec<Vec<usize>> {
+        let (batch, classes) = *probs.shape();
+        let mut buf = std::vec![0.0; batch.size() * classes.size()];
+        probs.copy_into(&mut buf);
+        buf.chunks(classes.size().max(1))
+            .take(batch.size())
+            .map(|row| {
+                (0..classes.size())
+                    .filter(|&c| 1.0 - row[c] <= self.threshold)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Turns the predictions of a regressor into intervals `prediction ± radius`, which contain
+/// the true value with probability at least `1 - alpha`, using split conformal prediction
+/// with the absolute error as the score. See [ConformalClassifier] for the guarantee.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::*};
+/// # let dev: Cpu = Default::default();
+/// let cal_predictions = dev.tensor([1.0, 2.0, 3.0, 4.0]);
+/// let cal_targets = dev.tensor([1.5, 1.0, 3.25, 4.0]);
+/// let conformal = ConformalRegressor::calibrate(&cal_predictions, &cal_targets, 0.2);
+/// assert_eq!(conformal.radius, 1.0);
+///
+/// let (lower, upper) = conformal.intervals(dev.tensor([0.0, 10.0]));
+/// assert_eq!(lower.array(), [-1.0, 9.0]);
+/// assert_eq!(upper.array(), [1.0, 11.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConformalRegressor {
+    /// Half of the width of each interval.
+    pub radius: f32,
+}
+
+impl ConformalRegressor {
+    /// Fits the radius for intervals that miss the target with probability at most `alpha`,
+    /// from the `predictions` of the model for held-out data with `targets`. Every element
+    /// is a separate prediction.
+    ///
+    /// With fewer than `1 / alpha - 1` elements there is no finite radius, so every interval
+    /// is infinite.
+    ///
+    /// **Panics** if `alpha` is not in `(0, 1)`.
+    pub fn calibrate<S: Shape, D: Device<f32>>(
+        predictions: &Tensor<S, f32, D>,
+        targets: &Tensor<S, f32, D>,
+        alpha: f32,
+    ) -> Self {
+        Self::try_calibrate(predictions, targets, alpha).unwrap()
+    }
+
+    /// See [ConformalRegressor::calibrate()]
+    pub fn try_calibrate<S: Shape, D: Device<f32>>(
+        predictions: &Tensor<S, f32, D>,
+        targets: &Tensor<S, f32, D>,
+        alpha: f32,
+    ) -> Result<Self, D::Err> {
+        let scores = predictions.clone().try_sub(targets.clone())?.try_abs()?;
+        let mut buf = std::vec![0.0; scores.shape().num_elements()];
+        scores.copy_into(&mut buf);
+        Ok(Self {
+            radius: conformal_quantile(buf, alpha),
+        })
+    }
+
+    /// The lower & upper bounds of the interval of each prediction.
+    pub fn intervals<S: Shape, D: Device<f32>>(
+        &self,
+        predictions: Tensor<S, f32, D>,
+    ) -> (Tensor<S, f32, D>, Tensor<S, f32, D>) {
+        self.try_intervals(predictions).unwrap()
+    }
+
+    /// See [ConformalRegressor::intervals()]
+    #[allow(clippy::type_complexity)]
+    pub fn try_intervals<S: Shape, D: Device<f32>>(
+        &self,
+        predictions: Tensor<S, f32, D>,
+    ) -> Result<(Tensor<S, f32, D>, Tensor<S, f32, D>), D::Err> {
+        let lower = predictions.clone().try_sub(self.radius)?;
+        let upper = predictions.try_add(self.radius)?;
+        Ok((lower, upper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tests::*};
+    use rand::prelude::*;
+
+    #[test]
+    fn test_conformal_quantile() {
+        let scores = std::vec![0.5, 0.1, 0.9, 0.3];
+        // k = ceil(5 * 0.6) = 3
+        assert_eq!(conformal_quantile(scores.clone(), 0.4), 0.5);
+        // k = ceil(5 * 0.9) = 5 > 4
+        assert_eq!(conformal_quantile(scores.clone(), 0.1), f32::INFINITY);
+        assert_eq!(conformal_quantile(scores, 0.99999), 0.1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_conformal_bad_alpha() {
+        conformal_quantile(std::vec![0.5], 1.0);
+    }
+
+    #[test]
+    fn test_classifier_coverage() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        // labels are sampled from the probabilities, so they're exchangeable
+        let mut sample = |n: usize| {
+            let mut probs = std::vec::Vec::with_capacity(n * 3);
+            let mut labels = std::vec::Vec::with_capacity(n);
+            for _ in 0..n {
+                let logits: [f32; 3] = [rng.gen(), rng.gen::<f32>() * 2.0, rng.gen()];
+                let total: f32 = logits.iter().map(|l| l.exp()).sum();
+                let p = logits.map(|l| l.exp() / total);
+                let u: f32 = rng.gen();
+                labels.push(if u < p[0] {
+                    0
+                } else if u < p[0] + p[1] {
+                    1
+                } else {
+                    2
+                });
+                probs.extend(p);
+            }
+            (
+                dev.tensor_from_vec(probs, (n, Const::<3>)),
+                dev.tensor_from_vec(labels, (n,)),
+            )
+        };
+        let (cal_probs, cal_labels) = sample(500);
+        let conformal = ConformalClassifier::calibrate(&cal_probs, &cal_labels, 0.1);
+        let (probs, labels) = sample(2000);
+        let sets = conformal.prediction_sets(&probs);
+        let covered = sets
+            .iter()
+            .zip(labels.as_vec())
+            .filter(|(s, l)| s.contains(l))
+            .count();
+        let coverage = covered as f32 / 2000.0;
+        assert!((0.88..0.94).contains(&coverage), "{coverage}");
+        assert!(sets.iter().any(|s| s.len() < 3));
+    }
+
+    #[test]
+    fn test_regressor_coverage() {
+        let dev: TestDevice = Default::default();
+        let predictions: Tensor<Rank2<50, 20>, f32, _> = dev.sample_normal();
+        let noise: Tensor<Rank2<50, 20>, f32, _> = dev.sample_normal();
+        let targets = predictions.clone() + noise;
+        let conformal = ConformalRegressor::calibrate(&predictions, &targets, 0.05);
+        // the 95% interval of a standard normal is about 1.96
+        assert!(
+            (conformal.radius - 1.96).abs() < 0.15,
+            "{}",
+            conformal.radius
+        );
+
+        let (lower, upper) = conformal.intervals(predictions);
+        assert_close(
+            &(upper - lower).array(),
+            &[[2.0 * conformal.radius; 20]; 50],
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_conformal_serde() {
+        fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+        assert_serde::<ConformalClassifier>();
+        assert_serde::<ConformalRegressor>();
+    }
+}
@@ -0,0 +1,294 @@
+//! Metrics for evaluating trained models, like the calibration of a classifier's confidences
+//! with [reliability_diagram()] and [TemperatureScaling].
+//!
+//! For deployment, [ConformalClassifier] & [ConformalRegressor] turn predictions into sets and
+//! intervals with a guaranteed coverage.
+
+mod conformal;
+
+pub use conformal::{ConformalClassifier, ConformalRegressor};
+
+use crate::{
+    shapes::*,
+    tensor::{CopySlice, Tensor},
+    tensor_ops::*,
+};
+
+use std::vec::Vec;
+
+/// Post-hoc calibration of a classifier, which divides its logits by a single `temperature`
+/// fitted on a validation set, from [On Calibration of Modern Neural Networks](https://arxiv.org/abs/1706.04599).
+///
+/// Dividing by a temperature doesn't change which class is predicted, so the accuracy stays
+/// the same, but confidences that are too high (`temperature > 1.0`) or too low
+/// (`temperature < 1.0`) are moved closer to the accuracy.
+///
+/// With the `serde` feature enabled, this can be serialized and deserialized, to deploy
+/// along with the model.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::*};
+/// # let dev: Cpu = Default::default();
+/// // an overconfident classifier, which is wrong 1 out of 4 times
+/// let logits = dev.tensor([[8.0, 0.0], [0.0, 8.0], [8.0, 0.0], [0.0, 8.0]]);
+/// let labels = dev.tensor([0, 1, 0, 0]);
+/// let scaling = TemperatureScaling::fit(&logits, &labels);
+/// assert!(scaling.temperature > 1.0);
+/// let probs = scaling.apply(logits).softmax::<Axis<1>>();
+/// assert!((probs.array()[0][0] - 0.75).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemperatureScaling {
+    pub temperature: f32,
+}
+
+impl Default for TemperatureScaling {
+    fn default() -> Self {
+        Self { temperature: 1.0 }
+    }
+}
+
+/// The derivative of the average negative log likelihood of `labels` under the softmax of
+/// `logits * scale` with respect to `scale`, which is the mean over the rows of
+/// `sum(softmax(logits * scale) * logits) - logits[label]`.
+fn nll_scale_derivative<B: Dim, C: Dim, D: Device<f32>>(
+    logits: &Tensor<(B, C), f32, D>,
+    labels: &Tensor<(B,), usize, D>,
+    scale: f32,
+) -> Result<f32, D::Err> {
+    let expected = logits
+        .clone()
+        .try_mul(scale)?
+        .try_softmax::<Axis<1>>()?
+        .try_mul(logits.clone())?
+        .try_sum::<(B,), _>()?;
+    let derivative = expected
+        .try_sub(logits.clone().try_select(labels.clone())?)?
+        .try_mean::<Rank0, _>()?;
+    let mut buf = [0.0];
+    derivative.copy_into(&mut buf);
+    Ok(buf[0])
+}
+
+impl TemperatureScaling {
+    /// Fits the temperature that minimizes the negative log likelihood of `labels` (the
+    /// class index of each row) given `logits`, which should be from data the model wasn't
+    /// trained on.
+    ///
+    /// The likelihood is convex in `1 / temperature`, so this bisects on the sign of its
+    /// derivative over temperatures from `0.01` to `100.0`, which doesn't need a learning rate.
+    pub fn fit<B: Dim, C: Dim, D: Device<f32>>(
+        logits: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+    ) -> Self {
+        Self::try_fit(logits, labels).unwrap()
+    }
+
+    /// See [TemperatureScaling::fit()]
+    pub fn try_fit<B: Dim, C: Dim, D: Device<f32>>(
+        logits: &Tensor<(B, C), f32, D>,
+        labels: &Tensor<(B,), usize, D>,
+    ) -> Result<Self, D::Err> {
+        // bisects over `ln(1 / temperature)`, where the derivative is still increasing
+        let (mut lo, mut hi) = (-(100f32.ln()), 100f32.ln());
+        for _ in 0..40 {
+            let mid = (lo + hi) / 2.0;
+            if nll_scale_derivative(logits, labels, mid.exp())? > 0.0 {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        let log_scale = (lo + hi) / 2.0;
+        Ok(Self {
+            temperature: (-log_scale).exp(),
+        })
+    }
+
+    /// Divides `logits` by the temperature.
+    pub fn apply<S: Shape, D: Device<f32>, T: crate::gradients::Tape<D>>(
+        &self,
+        logits: Tensor<S, f32, D, T>,
+    ) -> Tensor<S, f32, D, T> {
+        logits / self.temperature
+    }
+}
+
+/// The predictions whose confidence fell in one bin of a [ReliabilityDiagram].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CalibrationBin {
+    /// The number of predictions in the bin.
+    pub count: usize,
+    /// The mean confidence of the predictions, or `0.0` if there are none.
+    pub confidence: f32,
+    /// The fraction of the predictions that were correct, or `0.0` if there are none.
+    pub accuracy: f32,
+}
+
+/// The accuracy of a classifier's predictions, grouped by their confidence into bins of equal
+/// width, for plotting accuracy against confidence. A perfectly calibrated classifier has
+/// the same accuracy as confidence in every bin. See [reliability_diagram()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReliabilityDiagram {
+    /// Bin `i` has the predictions with confidence in `(i / n, (i + 1) / n]`, where `n` is
+    /// the number of bins. Predictions with confidence `0.0` go in the first bin.
+    pub bins: Vec<CalibrationBin>,
+}
+
+impl ReliabilityDiagram {
+    /// The total number of predictions.
+    pub fn num_predictions(&self) -> usize {
+        self.bins.iter().map(|b| b.count).sum()
+    }
+
+    /// The expected calibration error (ECE), which is the average of `|accuracy - confidence|`
+    /// of the bins, weighted by their number of predictions.
+    pub fn expected_calibration_error(&self) -> f32 {
+        let n = self.num_predictions().max(1) as f32;
+        self.bins
+            .iter()
+            .map(|b| b.count as f32 / n * (b.accuracy - b.confidence).abs())
+            .sum()
+    }
+
+    /// The maximum calibration error (MCE), which is the largest `|accuracy - confidence|` of
+    /// the bins that have predictions.
+    pub fn max_calibration_error(&self) -> f32 {
+        self.bins
+            .iter()
+            .filter(|b| b.count > 0)
+            .map(|b| (b.accuracy - b.confidence).abs())
+            .fold(0.0, f32::max)
+    }
+}
+
+/// Groups the predictions of `probs` (the probabilities of each class for each row, e.g. from
+/// [softmax()]) into `num_bins` bins by their confidence, and compares the confidences
+/// to how often the predicted class was the one in `labels`.
+///
+/// The prediction of each row is the class with the largest probability, and its confidence
+/// is that probability.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, metrics::*};
+/// # let dev: Cpu = Default::default();
+/// let probs = dev.tensor([[0.9, 0.1], [0.85, 0.15], [0.3, 0.7], [0.35, 0.65]]);
+/// let labels = dev.tensor([0, 1, 1, 1]);
+/// let diagram = reliability_diagram(&probs, &labels, 5);
+/// assert_eq!(diagram.bins[3].count, 2); // 0.7 & 0.65
+/// assert_eq!(diagram.bins[3].accuracy, 1.0);
+/// assert_eq!(diagram.bins[4].count, 2); // 0.9 & 0.85
+/// assert_eq!(diagram.bins[4].accuracy, 0.5);
+/// assert!((diagram.expected_calibration_error() - 0.35).abs() < 1e-6);
+/// ```
+///
+/// **Panics** if `num_bins` is `0`, or the number of labels is different than the number of
+/// rows.
+pub fn reliability_diagram<B: Dim, C: Dim, D: Device<f32> + CopySlice<usize>>(
+    probs: &Tensor<(B, C), f32, D>,
+    labels: &Tensor<(B,), usize, D>,
+    num_bins: usize,
+) -> ReliabilityDiagram {
+    try_reliability_diagram(probs, labels, num_bins).unwrap()
+}
+
+/// Fallible version of [reliability_diagram()]
+pub fn try_reliability_diagram<B: Dim, C: Dim, D: Device<f32> + CopySlice<usize>>(
+    probs: &Tensor<(B, C), f32, D>,
+    labels: &Tensor<(B,), usize, D>,
+    num_bins: usize,
+) -> Result<ReliabilityDiagram, D::Err> {
+    assert!(num_bins > 0, "a reliability diagram needs at least 1 bin");
+    let (batch, _) = *probs.shape();
+    assert_eq!(
+        labels.shape().0.size(),
+        batch.size(),
+        "there must be one label per row"
+    );
+    let n = batch.size();
+    let mut predictions = std::vec![0; n];
+    probs.try_argmax::<Axis<1>>()?.copy_into(&mut predictions);
+    let mut confidences = std::vec![0.0; n];
+    probs
+        .clone()
+        .try_max::<(B,), _>()?
+        .copy_into(&mut confidences);
+    let mut label_buf = std::vec![0; n];
+    labels.copy_into(&mut label_buf);
+
+    let mut bins = std::vec![CalibrationBin::default(); num_bins];
+    for ((p, c), l) in predictions.iter().zip(confidences).zip(label_buf) {
+        let i = ((c * num_bins as f32).ceil() as usize).clamp(1, num_bins) - 1;
+        let bin = &mut bins[i];
+        bin.count += 1;
+        bin.confidence += c;
+        bin.accuracy += if *p == l { 1.0 } else { 0.0 };
+    }
+    for bin in bins.iter_mut().filter(|b| b.count > 0) {
+        bin.confidence /= bin.count as f32;
+        bin.accuracy /= bin.count as f32;
+    }
+    Ok(ReliabilityDiagram { bins })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tests::*};
+
+    #[test]
+    fn test_temperature_scaling_fits_known_temperature() {
+        let dev: TestDevice = Default::default();
+        let logits = dev.tensor([[2.0, 0.0]; 8]);
+        let labels = dev.tensor([0, 0, 0, 0, 0, 0, 1, 1]);
+        let scaling = TemperatureScaling::fit(&logits, &labels);
+        // with 6 of 8 correct, the best confidence is 0.75, so `2.0 / temperature = ln(3)`
+        let expected = 2.0 / 3f32.ln();
+        assert!((scaling.temperature - expected).abs() < 1e-4);
+
+        let probs = scaling.apply(logits).softmax::<Axis<1>>();
+        assert_close(&probs.array()[0], &[0.75, 0.25]);
+    }
+
+    #[test]
+    fn test_temperature_scaling_sharpens_underconfident() {
+        let dev: TestDevice = Default::default();
+        let logits = dev.tensor([[0.5, 0.0], [0.0, 0.5], [0.5, 0.0]]);
+        let labels = dev.tensor([0, 1, 0]);
+        let scaling = TemperatureScaling::fit(&logits, &labels);
+        assert!(scaling.temperature < 0.05);
+        assert_eq!(TemperatureScaling::default().temperature, 1.0);
+    }
+
+    #[test]
+    fn test_reliability_diagram() {
+        let dev: TestDevice = Default::default();
+        let probs = dev.tensor([
+            [1.0, 0.0, 0.0],
+            [0.2, 0.5, 0.3],
+            [0.1, 0.45, 0.45],
+            [0.0, 0.0, 1.0],
+        ]);
+        let labels = dev.tensor([0, 2, 1, 1]);
+        let diagram = reliability_diagram(&probs, &labels, 2);
+        assert_eq!(diagram.num_predictions(), 4);
+        let [low, high] = [diagram.bins[0], diagram.bins[1]];
+        assert_eq!((low.count, low.accuracy), (2, 0.5));
+        assert!((low.confidence - 0.475).abs() < 1e-6);
+        assert_eq!((high.count, high.confidence, high.accuracy), (2, 1.0, 0.5));
+        assert!((diagram.expected_calibration_error() - 0.2625).abs() < 1e-6);
+        assert_eq!(diagram.max_calibration_error(), 0.5);
+
+        let diagram = reliability_diagram(&probs, &labels, 10);
+        assert_eq!(diagram.bins[9].count, 2);
+        assert_eq!(diagram.bins[0], CalibrationBin::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_temperature_scaling_serde() {
+        fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+        assert_serde::<TemperatureScaling>();
+    }
+}
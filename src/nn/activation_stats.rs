@@ -0,0 +1,274 @@
+use crate::tensor_ops::Device;
+
+use super::{DynHook, DynLayer, DynModel};
+
+use std::{
+    collections::BTreeMap,
+    string::String,
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+
+/// Statistics of the output of a single layer from one forward (and backward) pass.
+/// See [ActivationStats].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LayerStats {
+    pub mean: f32,
+    pub std: f32,
+    pub max: f32,
+    /// The fraction of values that are exactly zero, e.g. the dead units after a ReLU.
+    pub zero_fraction: f32,
+    /// The L2 norm of the gradient of the output, or `None` if backprop hasn't
+    /// reached this layer (yet).
+    pub grad_norm: Option<f32>,
+}
+
+/// Records [LayerStats] for the layers of a [DynModel] every forward & backward pass,
+/// using [DynLayer::Hook]s and [DynLayer::GradHook]s. Useful for finding dead ReLUs,
+/// and layers whose activations or gradients explode.
+///
+/// Stats are recorded under the path the layer had before [ActivationStats::instrument()]
+/// was called. Each forward adds a new entry to the history of a layer, and backprop fills in
+/// [LayerStats::grad_norm] of the latest one, so the model should only be called once per
+/// backward. Clones share the same history.
+///
+/// The activations and gradients are copied from the device, so this is slow.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let config = ModelConfig {
+///     input_dim: 4,
+///     layers: std::vec![LayerConfig::Linear { inp: 4, out: 8 }, LayerConfig::ReLU],
+/// };
+/// let mut model: DynModel<Cpu> = config.build_on_device(&dev);
+/// let stats = ActivationStats::new();
+/// stats.instrument(&mut model);
+///
+/// let x: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(3, 4));
+/// let _ = model.forward(x.trace()).mean().backward();
+///
+/// stats.export(|name, value| println!("{name}: {value}"));
+/// assert_eq!(stats.history("1").len(), 1);
+/// assert!(stats.latest("1").unwrap().grad_norm.is_some());
+///
+/// // hooks shift the paths of the layers after them, so remove them before saving
+/// model.remove_hooks();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ActivationStats {
+    history: Arc<Mutex<BTreeMap<String, Vec<LayerStats>>>>,
+}
+
+impl ActivationStats {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Inserts a [DynLayer::Hook] and a [DynLayer::GradHook] after every layer of `model`
+    /// (including containers), which record to `self`.
+    pub fn instrument<D: Device<f32>>(&self, model: &mut DynModel<D>) {
+        let paths = model.layer_paths();
+        // layers after a path are instrumented first, so inserting doesn't shift the paths
+        // that are still left
+        for path in paths.iter().rev() {
+            if matches!(
+                model.layer(path),
+                Ok(DynLayer::Hook(_) | DynLayer::GradHook(_))
+            ) {
+                continue;
+            }
+            let next = next_path(path);
+            model
+                .insert_layer(&next, DynLayer::GradHook(self.grad_hook(path)))
+                .unwrap();
+            model
+                .insert_layer(&next, DynLayer::Hook(self.activation_hook(path)))
+                .unwrap();
+        }
+    }
+
+    /// A hook that records the stats of the activations it sees under `path`.
+    pub fn activation_hook(&self, path: &str) -> DynHook {
+        let history = self.history.clone();
+        let path: String = path.into();
+        DynHook::new(move |_, data| {
+            let stats = activation_stats(data);
+            let mut history = history.lock().unwrap();
+            history.entry(path.clone()).or_default().push(stats);
+        })
+    }
+
+    /// A hook that records the norm of the gradient it sees as the
+    /// [LayerStats::grad_norm] of the latest stats under `path`.
+    pub fn grad_hook(&self, path: &str) -> DynHook {
+        let history = self.history.clone();
+        let path: String = path.into();
+        DynHook::new(move |_, data| {
+            let norm = data.iter().map(|g| g * g).sum::<f32>().sqrt();
+            let mut history = history.lock().unwrap();
+            if let Some(stats) = history.get_mut(&path).and_then(|h| h.last_mut()) {
+                stats.grad_norm = Some(norm);
+            }
+        })
+    }
+
+    /// The paths of every layer that has recorded stats.
+    pub fn paths(&self) -> Vec<String> {
+        self.history.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// All stats recorded for the layer at `path`, oldest first.
+    pub fn history(&self, path: &str) -> Vec<LayerStats> {
+        let history = self.history.lock().unwrap();
+        history.get(path).cloned().unwrap_or_default()
+    }
+
+    /// The most recent stats recorded for the layer at `path`.
+    pub fn latest(&self, path: &str) -> Option<LayerStats> {
+        let history = self.history.lock().unwrap();
+        history.get(path).and_then(|h| h.last().copied())
+    }
+
+    /// Calls `sink` with the name & value of each of the latest stats of every layer,
+    /// e.g. `("1.0/mean", 0.5)`, for writing to a logger. Gradient norms are only
+    /// exported once recorded.
+    pub fn export<F: FnMut(&str, f32)>(&self, mut sink: F) {
+        let history = self.history.lock().unwrap();
+        for (path, stats) in history.iter() {
+            let Some(stats) = stats.last() else {
+                continue;
+            };
+            sink(&std::format!("{path}/mean"), stats.mean);
+            sink(&std::format!("{path}/std"), stats.std);
+            sink(&std::format!("{path}/max"), stats.max);
+            sink(&std::format!("{path}/zero_fraction"), stats.zero_fraction);
+            if let Some(norm) = stats.grad_norm {
+                sink(&std::format!("{path}/grad_norm"), norm);
+            }
+        }
+    }
+
+    /// Removes all recorded stats.
+    pub fn clear(&self) {
+        self.history.lock().unwrap().clear();
+    }
+}
+
+fn activation_stats(data: &[f32]) -> LayerStats {
+    let n = data.len().max(1) as f32;
+    let mean = data.iter().sum::<f32>() / n;
+    let var = data.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / n;
+    LayerStats {
+        mean,
+        std: var.sqrt(),
+        max: data.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+        zero_fraction: data.iter().filter(|&&v| v == 0.0).count() as f32 / n,
+        grad_norm: None,
+    }
+}
+
+/// The path of the layer right after the one at `path`, in the same container.
+fn next_path(path: &str) -> String {
+    let (prefix, last) = match path.rfind('.') {
+        Some(i) => path.split_at(i + 1),
+        None => ("", path),
+    };
+    let last: usize = last.parse().unwrap();
+    std::format!("{prefix}{}", last + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{LayerConfig, ModelConfig, Module},
+        shapes::*,
+        tensor::*,
+        tensor_ops::*,
+        tests::*,
+    };
+
+    fn config() -> ModelConfig {
+        ModelConfig {
+            input_dim: 3,
+            layers: std::vec![
+                LayerConfig::Linear { inp: 3, out: 4 },
+                LayerConfig::Residual(std::vec![LayerConfig::Linear { inp: 4, out: 4 }]),
+                LayerConfig::ReLU,
+                LayerConfig::Linear { inp: 4, out: 2 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_instrument_records_stats() {
+        let dev: TestDevice = Default::default();
+        let mut model = config().build_on_device(&dev);
+        let clean = model.clone();
+        let stats = ActivationStats::new();
+        stats.instrument(&mut model);
+        assert_eq!(stats.paths().len(), 0);
+        assert_eq!(model.layer_paths().len(), 3 * clean.layer_paths().len());
+
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(5, 3), rand_distr::StandardNormal);
+        let y = model.forward(x.trace());
+        assert_close(&y.as_vec(), &clean.forward(x.clone()).as_vec());
+        assert_eq!(stats.paths(), ["0", "1", "1.0", "2", "3"]);
+
+        let h: Tensor<(usize, usize), f32, _> = clean.layers[0].forward(x.clone());
+        let h = h.as_vec();
+        let latest = stats.latest("0").unwrap();
+        let mean = h.iter().sum::<f32>() / 20.0;
+        assert!((latest.mean - mean).abs() < 1e-6);
+        assert_eq!(latest.max, h.iter().copied().fold(f32::MIN, f32::max));
+        assert_eq!(latest.grad_norm, None);
+
+        // the gradient of the output of a sum is all ones
+        let _ = y.sum().backward();
+        let latest = stats.latest("3").unwrap();
+        assert!((latest.grad_norm.unwrap() - 10f32.sqrt()).abs() < 1e-6);
+        for path in stats.paths() {
+            assert!(stats.latest(&path).unwrap().grad_norm.is_some());
+        }
+
+        let _ = model.forward(x.trace()).sum().backward();
+        assert_eq!(stats.history("2").len(), 2);
+
+        model.remove_hooks();
+        assert_eq!(model.layer_paths(), clean.layer_paths());
+    }
+
+    #[test]
+    fn test_dead_relu_and_export() {
+        let dev: TestDevice = Default::default();
+        let mut model = config().build_on_device(&dev);
+        if let DynLayer::Linear(l) = &mut model.layers[0] {
+            l.weight = dev.zeros_like(l.weight.shape());
+            l.bias = dev.zeros_like(l.bias.shape()) - 1.0;
+        }
+        model.layers[1] = DynLayer::ReLU;
+        let stats = ActivationStats::new();
+        stats.instrument(&mut model);
+
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(5, 3), rand_distr::StandardNormal);
+        let _ = model.forward(x.trace()).sum().backward();
+
+        let relu = stats.latest("1").unwrap();
+        assert_eq!(relu.zero_fraction, 1.0);
+        assert_eq!(relu.std, 0.0);
+        assert_eq!(stats.latest("0").unwrap().zero_fraction, 0.0);
+
+        let mut exported = std::vec::Vec::new();
+        stats.export(|name, value| exported.push((String::from(name), value)));
+        assert_eq!(exported.len(), 4 * 5);
+        assert!(exported.contains(&(String::from("1/zero_fraction"), 1.0)));
+        assert!(exported.iter().any(|(name, _)| name == "3/grad_norm"));
+
+        stats.clear();
+        assert!(stats.paths().is_empty());
+    }
+}
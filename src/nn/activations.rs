@@ -3,7 +3,7 @@ use crate::{gradients::Tape, shapes::*, tensor::*, tensor_ops::*};
 use super::module::{BuildModule, Module, NonMutableModule, ZeroSizedModule};
 
 macro_rules! activation_impls {
-    ($struct_name:ident, $func_name:ident, #[$docstring:meta]) => {
+    ($struct_name:ident, $func_name:ident, $try_func_name:ident, #[$docstring:meta]) => {
         #[$docstring]
         #[derive(Default, Debug, Clone, Copy)]
         pub struct $struct_name;
@@ -21,24 +21,25 @@ macro_rules! activation_impls {
             for $struct_name
         {
             type Output = Tensor<S, E, D, T>;
-            fn forward(&self, input: Tensor<S, E, D, T>) -> Self::Output {
-                $func_name(input)
+            type Error = D::Err;
+            fn try_forward(&self, input: Tensor<S, E, D, T>) -> Result<Self::Output, D::Err> {
+                input.$try_func_name()
             }
         }
     };
 }
 
-activation_impls!(ReLU, relu, #[doc="Unit struct that impls [Module] as calling [relu()] on `input`."]);
-activation_impls!(GeLU, gelu, #[doc="Unit struct that impls [Module] as calling [gelu()] on `input`."]);
-activation_impls!(Sin, sin, #[doc="Unit struct that impls [Module] as calling [sin()] on `input`."]);
-activation_impls!(Cos, cos, #[doc="Unit struct that impls [Module] as calling [cos()] on `input`."]);
-activation_impls!(Ln, ln, #[doc="Unit struct that impls [Module] as calling [ln()] on `input`."]);
-activation_impls!(Exp, exp, #[doc="Unit struct that impls [Module] as calling [exp()] on `input`."]);
-activation_impls!(Sigmoid, sigmoid, #[doc="Unit struct that impls [Module] as calling [sigmoid()] on `input`."]);
-activation_impls!(Tanh, tanh, #[doc="Unit struct that impls [Module] as calling [tanh()] on `input`."]);
-activation_impls!(Square, square, #[doc="Unit struct that impls [Module] as calling [square()] on `input`."]);
-activation_impls!(Sqrt, sqrt, #[doc="Unit struct that impls [Module] as calling [sqrt()] on `input`."]);
-activation_impls!(Abs, abs, #[doc="Unit struct that impls [Module] as calling [abs()] on `input`."]);
+activation_impls!(ReLU, relu, try_relu, #[doc="Unit struct that impls [Module] as calling [relu()] on `input`."]);
+activation_impls!(GeLU, gelu, try_gelu, #[doc="Unit struct that impls [Module] as calling [gelu()] on `input`."]);
+activation_impls!(Sin, sin, try_sin, #[doc="Unit struct that impls [Module] as calling [sin()] on `input`."]);
+activation_impls!(Cos, cos, try_cos, #[doc="Unit struct that impls [Module] as calling [cos()] on `input`."]);
+activation_impls!(Ln, ln, try_ln, #[doc="Unit struct that impls [Module] as calling [ln()] on `input`."]);
+activation_impls!(Exp, exp, try_exp, #[doc="Unit struct that impls [Module] as calling [exp()] on `input`."]);
+activation_impls!(Sigmoid, sigmoid, try_sigmoid, #[doc="Unit struct that impls [Module] as calling [sigmoid()] on `input`."]);
+activation_impls!(Tanh, tanh, try_tanh, #[doc="Unit struct that impls [Module] as calling [tanh()] on `input`."]);
+activation_impls!(Square, square, try_square, #[doc="Unit struct that impls [Module] as calling [square()] on `input`."]);
+activation_impls!(Sqrt, sqrt, try_sqrt, #[doc="Unit struct that impls [Module] as calling [sqrt()] on `input`."]);
+activation_impls!(Abs, abs, try_abs, #[doc="Unit struct that impls [Module] as calling [abs()] on `input`."]);
 
 /// Unit struct that impls [Module] as calling [softmax()] on `input`."
 #[derive(Default, Debug, Clone, Copy)]
@@ -57,8 +58,9 @@ impl<Ax: Axes, S: Shape<LastAxis = Ax> + ReduceShape<Ax>, E: Dtype, D: Device<E>
     Module<Tensor<S, E, D, T>> for Softmax
 {
     type Output = Tensor<S, E, D, T>;
-    fn forward(&self, input: Tensor<S, E, D, T>) -> Self::Output {
-        input.softmax::<Ax>()
+    type Error = D::Err;
+    fn try_forward(&self, input: Tensor<S, E, D, T>) -> Result<Self::Output, D::Err> {
+        input.try_softmax::<Ax>()
     }
 }
 
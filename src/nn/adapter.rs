@@ -0,0 +1,145 @@
+use crate::{optim::*, tensor::*, tensor_ops::*};
+
+use super::{BuildModule, Linear, Module, ModuleMut, ReLU, ResetParams, ToDevice};
+
+/// A bottleneck adapter: `x + up(relu(down(x)))`, as introduced in
+/// [Parameter-Efficient Transfer Learning for NLP](https://arxiv.org/abs/1902.00751).
+///
+/// Meant to be inserted after the attention/feedforward blocks of a [Frozen](super::Frozen)
+/// model. [Self::up] is initialized with zeros, so a freshly built adapter is the identity.
+///
+/// # Generics
+/// - `M`: The size of the input/output features.
+/// - `B`: The size of the bottleneck.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let adapter: Adapter<8, 2> = BuildModule::build(&dev);
+/// let x: Tensor<Rank2<3, 8>, f32, _> = dev.sample_normal();
+/// assert_eq!(adapter.forward(x.clone()).array(), x.array());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Adapter<const M: usize, const B: usize, D: Device<f32> = Cpu> {
+    pub down: Linear<M, B, D>,
+    pub up: Linear<B, M, D>,
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>> GradientUpdate<D, f32> for Adapter<M, B, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.down.update(updater, unused)?;
+        self.up.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>> BuildModule<D, f32> for Adapter<M, B, D> {
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let down = BuildModule::try_build(device)?;
+        let up = Linear {
+            weight: device.try_zeros()?,
+            bias: device.try_zeros()?,
+        };
+        Ok(Self { down, up })
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>> ResetParams<D, f32> for Adapter<M, B, D> {
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.down.try_reset_params()?;
+        self.up.weight.try_fill_with_zeros()?;
+        self.up.bias.try_fill_with_zeros()?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const B: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2>
+    for Adapter<M, B, D1>
+{
+    type Output = Adapter<M, B, D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        Adapter {
+            down: self.down.to_device(device),
+            up: self.up.to_device(device),
+        }
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>, T> Module<T> for Adapter<M, B, D>
+where
+    T: SplitTape + TryAdd<T> + HasErr<Err = D::Err>,
+    Linear<M, B, D>: Module<T, Error = D::Err>,
+    ReLU: Module<<Linear<M, B, D> as Module<T>>::Output, Error = D::Err>,
+    Linear<B, M, D>: Module<
+        <ReLU as Module<<Linear<M, B, D> as Module<T>>::Output>>::Output,
+        Output = T,
+        Error = D::Err,
+    >,
+{
+    type Output = T;
+    type Error = D::Err;
+    fn try_forward(&self, x: T) -> Result<Self::Output, D::Err> {
+        let h = ReLU.try_forward(self.down.try_forward(x.with_empty_tape())?)?;
+        self.up.try_forward(h)?.try_add(x)
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>, T> ModuleMut<T> for Adapter<M, B, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{BuildOnDevice, Frozen},
+        shapes::*,
+        tests::*,
+    };
+
+    #[test]
+    fn test_adapter_forward_backward() {
+        let dev: TestDevice = Default::default();
+        let mut adapter: Adapter<2, 1, _> = BuildModule::build(&dev);
+        adapter.down.weight = dev.tensor([[1.0, -1.0]]);
+        adapter.down.bias = dev.tensor([0.5]);
+        adapter.up.weight = dev.tensor([[2.0], [-1.0]]);
+        adapter.up.bias = dev.tensor([0.0, 1.0]);
+
+        let x = dev.tensor([[1.0, 2.0], [3.0, 1.0]]);
+        let y = adapter.forward(x.trace());
+        // h = relu([-0.5, 2.5]) = [0, 2.5]
+        assert_close(&y.array(), &[[1.0, 3.0], [8.0, -0.5]]);
+
+        let g = y.sum().backward();
+        assert_close(&g.get(&adapter.up.weight).array(), &[[2.5], [2.5]]);
+        assert_close(&g.get(&adapter.down.weight).array(), &[[3.0, 1.0]]);
+        assert_close(&g.get(&x).array(), &[[1.0, 1.0], [2.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_adapter_finetune_frozen_model() {
+        let dev: TestDevice = Default::default();
+        type Model = (Frozen<Linear<4, 4>>, Adapter<4, 2>);
+        let mut model = Model::build_on_device(&dev);
+        let frozen = model.0 .0.weight.array();
+        let mut opt = Sgd::new(&model, Default::default());
+        let x: Tensor<Rank2<16, 4>, f32, _> = dev.sample_normal();
+        let y = model.forward_mut(x.trace());
+        let g = y.square().mean().backward();
+        opt.update(&mut model, g).expect("");
+        assert_eq!(model.0 .0.weight.array(), frozen);
+        assert_ne!(model.1.up.weight.array(), [[0.0; 2]; 4]);
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{optim::*, shapes::Dtype, tensor_ops::Device};
+use crate::{
+    optim::*,
+    shapes::Dtype,
+    tensor_ops::{Device, TryAdd},
+};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
 
 /// Add inputs together into a single tensor. `T` should be a tuple
 //// where every element of the tuple has the same output type
@@ -51,37 +55,46 @@ impl<T: ToDevice<D>, D> ToDevice<D> for AddInto<T> {
     }
 }
 
-macro_rules! sum {
+impl<T: ToDtype<E>, E> ToDtype<E> for AddInto<T> {
+    type Output = AddInto<T::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        AddInto(self.0.to_dtype())
+    }
+}
+
+macro_rules! try_sum {
     ($H:tt) => { $H };
-    ($H:tt, $($T:tt),+) => { $H + sum!($($T),+) };
+    ($H:tt, $($T:tt),+) => { $H.try_add(try_sum!($($T),+))? };
 }
 
 macro_rules! add_into_impls {
     ($([$Mod:tt $ModVar:tt $Inp:tt $InpVar:tt]),+) => {
         impl<
-            Out: std::ops::Add<Out, Output = Out>,
+            Out: TryAdd<Out>,
             $($Inp, )+
-            $($Mod: Module<$Inp, Output = Out>, )+
+            $($Mod: Module<$Inp, Output = Out, Error = Out::Err>, )+
         > Module<($($Inp, )+)> for AddInto<($($Mod, )+)> {
             type Output = Out;
-            fn forward(&self, x: ($($Inp, )+)) -> Self::Output {
+            type Error = Out::Err;
+            fn try_forward(&self, x: ($($Inp, )+)) -> Result<Self::Output, Self::Error> {
                 let ($($ModVar, )+) = &self.0;
                 let ($($InpVar, )+) = x;
-                $(let $InpVar = $ModVar.forward($InpVar);)+
-                sum!($($InpVar),*)
+                $(let $InpVar = $ModVar.try_forward($InpVar)?;)+
+                Ok(try_sum!($($InpVar),*))
             }
         }
         impl<
-            Out: std::ops::Add<Out, Output = Out>,
+            Out: TryAdd<Out>,
             $($Inp, )+
-            $($Mod: ModuleMut<$Inp, Output = Out>, )+
+            $($Mod: ModuleMut<$Inp, Output = Out, Error = Out::Err>, )+
         > ModuleMut<($($Inp, )+)> for AddInto<($($Mod, )+)> {
             type Output = Out;
-            fn forward_mut(&mut self, x: ($($Inp, )+)) -> Self::Output {
+            type Error = Out::Err;
+            fn try_forward_mut(&mut self, x: ($($Inp, )+)) -> Result<Self::Output, Self::Error> {
                 let ($($ModVar, )+) = &mut self.0;
                 let ($($InpVar, )+) = x;
-                $(let $InpVar = $ModVar.forward_mut($InpVar);)+
-                sum!($($InpVar),*)
+                $(let $InpVar = $ModVar.try_forward_mut($InpVar)?;)+
+                Ok(try_sum!($($InpVar),*))
             }
         }
     };
@@ -0,0 +1,189 @@
+use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*};
+
+/// The absolute value of the gradient of `f(x)` with respect to `x`, from
+/// [Deep Inside Convolutional Networks](https://arxiv.org/abs/1312.6034).
+///
+/// `f` runs the model and selects the scalar to explain, usually the logit of one class.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: (Linear<4, 8>, ReLU, Linear<8, 3>) = BuildModule::build(&dev);
+/// let x: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+/// let class = dev.tensor(2);
+/// let map = saliency(|x| model.forward(x).select(class.clone()), &x);
+/// assert!(map.array().iter().all(|&g| g >= 0.0));
+/// ```
+pub fn saliency<S: Shape, D: Device<f32>, F>(f: F, x: &Tensor<S, f32, D>) -> Tensor<S, f32, D>
+where
+    F: FnOnce(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    try_saliency(f, x).unwrap()
+}
+
+/// Fallible version of [saliency()]
+pub fn try_saliency<S: Shape, D: Device<f32>, F>(
+    f: F,
+    x: &Tensor<S, f32, D>,
+) -> Result<Tensor<S, f32, D>, D::Err>
+where
+    F: FnOnce(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    let grads = f(x.trace()).try_backward()?;
+    x.device.upgrade(grads.get(x).clone()).try_abs()
+}
+
+/// The integrated gradients of `f` from `baseline` to `x`, from
+/// [Axiomatic Attribution for Deep Networks](https://arxiv.org/abs/1703.01365):
+/// `(x - baseline) * mean(grad f(baseline + a * (x - baseline)))`, where the mean is over
+/// `steps` values of `a` evenly spaced in `(0, 1)`.
+///
+/// The attributions sum to about `f(x) - f(baseline)`, and get closer with more `steps`
+/// (usually 20 to 300). The baseline is an input with no signal, like a black image.
+///
+/// `f` is called once per step.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: (Linear<4, 8>, Tanh, Linear<8, 1>) = BuildModule::build(&dev);
+/// let f = |x: Tensor<Rank1<4>, f32, _, _>| model.forward(x).sum();
+/// let x: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+/// let baseline = dev.zeros();
+/// let attr = integrated_gradients(f, &x, &baseline, 50);
+/// let diff = f(x.trace()).array() - f(baseline.trace()).array();
+/// assert!((attr.array().iter().sum::<f32>() - diff).abs() < 1e-3);
+/// ```
+///
+/// **Panics** if `steps` is `0`.
+pub fn integrated_gradients<S: Shape, D: Device<f32>, F>(
+    f: F,
+    x: &Tensor<S, f32, D>,
+    baseline: &Tensor<S, f32, D>,
+    steps: usize,
+) -> Tensor<S, f32, D>
+where
+    F: Fn(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    try_integrated_gradients(f, x, baseline, steps).unwrap()
+}
+
+/// Fallible version of [integrated_gradients()]
+pub fn try_integrated_gradients<S: Shape, D: Device<f32>, F>(
+    f: F,
+    x: &Tensor<S, f32, D>,
+    baseline: &Tensor<S, f32, D>,
+    steps: usize,
+) -> Result<Tensor<S, f32, D>, D::Err>
+where
+    F: Fn(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    assert!(steps > 0, "integrated gradients needs at least 1 step");
+    let diff = x.clone().try_sub(baseline.clone())?;
+    let mut total = x.device.try_zeros_like(x.shape())?;
+    for step in 0..steps {
+        // midpoints of `steps` equal intervals, which is more accurate than either end
+        let a = (step as f32 + 0.5) / steps as f32;
+        let x_a = diff.clone().try_mul(a)?.try_add(baseline.clone())?;
+        let grads = f(x_a.trace()).try_backward()?;
+        total = total.try_add(x.device.upgrade(grads.get(&x_a).clone()))?;
+    }
+    total.try_mul(diff)?.try_div(steps as f32)
+}
+
+/// The class activation map of [Grad-CAM](https://arxiv.org/abs/1610.02391) for the
+/// `activations` of a convolutional layer, with shape `(channels, height, width)`.
+///
+/// `head` runs the rest of the model on the activations, and selects the scalar to explain.
+/// Each channel is weighted by the spatial mean of its gradient, and the map is the
+/// [relu()] of the weighted sum of the channels, so it shows where the activations increase
+/// the output.
+///
+/// Split a model into the layers before & after the explained layer to get `activations`, e.g.
+/// the fields of a tuple. The map has the resolution of the activations, so it is usually
+/// upsampled to the size of the input to overlay on it.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let head: (AvgPoolGlobal, Linear<4, 10>) = BuildModule::build(&dev);
+/// let activations: Tensor<Rank3<4, 7, 7>, f32, _> = dev.sample_normal();
+/// let class = dev.tensor(3);
+/// let cam = grad_cam(|a| head.forward(a).select(class.clone()), &activations);
+/// assert!(cam.array().iter().flatten().all(|&v| v >= 0.0));
+/// ```
+pub fn grad_cam<C: Dim, H: Dim, W: Dim, D: Device<f32>, F>(
+    head: F,
+    activations: &Tensor<(C, H, W), f32, D>,
+) -> Tensor<(H, W), f32, D>
+where
+    F: FnOnce(Tensor<(C, H, W), f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    try_grad_cam(head, activations).unwrap()
+}
+
+/// Fallible version of [grad_cam()]
+pub fn try_grad_cam<C: Dim, H: Dim, W: Dim, D: Device<f32>, F>(
+    head: F,
+    activations: &Tensor<(C, H, W), f32, D>,
+) -> Result<Tensor<(H, W), f32, D>, D::Err>
+where
+    F: FnOnce(Tensor<(C, H, W), f32, D, OwnedTape<D>>) -> Tensor<Rank0, f32, D, OwnedTape<D>>,
+{
+    let grads = head(activations.trace()).try_backward()?;
+    let grad = activations.device.upgrade(grads.get(activations).clone());
+    let weights = grad.try_mean::<(C,), Axes2<1, 2>>()?;
+    weights
+        .try_broadcast_like(activations.shape())?
+        .try_mul(activations.clone())?
+        .try_sum::<(H, W), Axis<0>>()?
+        .try_relu()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, tests::*};
+
+    #[test]
+    fn test_saliency_of_linear_is_abs_weight() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<3, 2, _> = BuildModule::build(&dev);
+        let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let map = saliency(|x| model.forward(x).select(dev.tensor(1)), &x);
+        let w = model.weight.array()[1].map(f32::abs);
+        assert_close(&map.array(), &w);
+    }
+
+    #[test]
+    fn test_integrated_gradients_completeness() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([1.0, -2.0, 0.5]);
+        let baseline = dev.zeros();
+        // for `sum(x^2)`, the attribution of each input is exactly `x^2`
+        let attr = integrated_gradients(|x| x.square().sum(), &x, &baseline, 4);
+        assert_close(&attr.array(), &[1.0, 4.0, 0.25]);
+
+        let model: (Linear<3, 5, _>, Sigmoid, Linear<5, 1, _>) = BuildModule::build(&dev);
+        let f = |x: Tensor<Rank1<3>, f32, _, _>| model.forward(x).sum();
+        let baseline = dev.tensor([0.5; 3]);
+        let attr = integrated_gradients(f, &x, &baseline, 100);
+        let diff = f(x.trace()).array() - f(baseline.trace()).array();
+        assert!((attr.array().iter().sum::<f32>() - diff).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_grad_cam() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[[1.0, 0.0], [0.0, 2.0]], [[0.0, 3.0], [1.0, 0.0]]]);
+        // channel 0 increases the output, and channel 1 decreases it
+        let w = dev.tensor([[[1.0; 2]; 2], [[-0.5; 2]; 2]]);
+        let cam = grad_cam(|a| (a * w.clone()).sum(), &a);
+        assert_close(&cam.array(), &[[1.0, 0.0], [0.0, 2.0]]);
+
+        let a: Tensor<(usize, usize, usize), f32, _> = dev.ones_like(&(3, 4, 5));
+        let cam = grad_cam(|a| a.sum(), &a);
+        assert_eq!(cam.shape(), &(4, 5));
+        assert_close(&cam.as_vec(), &std::vec![3.0; 20]);
+    }
+}
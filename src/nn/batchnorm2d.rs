@@ -43,8 +43,12 @@ pub struct BatchNorm2D<const C: usize, D: Device<f32> = Cpu> {
     /// Bias for affine transform. Defaults to 0.0
     pub bias: Tensor<Rank1<C>, f32, D>,
     /// Spatial mean that is updated during training. Defaults to 0.0
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
     pub running_mean: Tensor<Rank1<C>, f32, D>,
     /// Spatial variance that is updated during training. Defaults to 1.0
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
     pub running_var: Tensor<Rank1<C>, f32, D>,
     /// Added to variance before taking sqrt for numerical stability. Defaults to 1e-5
     pub epsilon: f32,
@@ -56,58 +60,75 @@ pub struct BatchNorm2D<const C: usize, D: Device<f32> = Cpu> {
 
 impl<const C: usize, D: Device<f32>> BatchNorm2D<C, D> {
     /// generic forward for inference
-    fn infer_fwd<S: Shape, Ax: Axes>(&self, x: Tensor<S, f32, D>) -> Tensor<S, f32, D>
+    pub(super) fn try_infer_fwd<S, Ax: Axes>(
+        &self,
+        x: Tensor<S, f32, D>,
+    ) -> Result<Tensor<S, f32, D>, D::Err>
     where
-        Rank1<C>: BroadcastShapeTo<S, Ax>,
+        S: Shape + ReduceShapeTo<Rank1<C>, Ax> + ReduceShape<Ax, Reduced = Rank1<C>>,
     {
         let shape = *x.shape();
 
         // statistics for normalizing
-        let std = (self.running_var.clone() + self.epsilon).sqrt();
+        let std = self.running_var.clone().try_add(self.epsilon)?.try_sqrt()?;
         let mean = self.running_mean.clone();
 
         // normalize & affine
-        let x = sub(x, mean.broadcast_like(&shape));
-        let x = div(x, std.broadcast_like(&shape));
-        let x = mul(x, self.scale.clone().broadcast_like(&shape));
-        add(x, self.bias.clone().broadcast_like(&shape))
+        let x = x.try_sub(mean.try_broadcast_like(&shape)?)?;
+        let x = x.try_div(std.try_broadcast_like(&shape)?)?;
+        x.try_affine::<Ax>(self.scale.clone(), self.bias.clone())
     }
 
-    fn train_fwd<S: Shape, T: Tape<D>, Ax: Axes>(
+    /// generic forward for training
+    pub(super) fn try_train_fwd<S, T: Tape<D>, Ax: Axes>(
         &mut self,
         x: Tensor<S, f32, D, T>,
-    ) -> Tensor<S, f32, D, T>
+    ) -> Result<Tensor<S, f32, D, T>, D::Err>
     where
-        S: HasAxes<Ax> + ReduceShapeTo<Rank1<C>, Ax>,
+        S: Shape + ReduceShapeTo<Rank1<C>, Ax> + ReduceShape<Ax, Reduced = Rank1<C>>,
     {
         let n = <S as HasAxes<Ax>>::size(x.shape()) as f32;
         let shape = *x.shape();
 
         // compute statistics for updating running stats later - on tape
-        let mean_chan = x.retaped::<T>().mean::<Rank1<C>, _>();
+        let mean_chan = x.retaped::<T>().try_mean::<Rank1<C>, _>()?;
 
         // update statistics since we are training - off tape
-        self.running_mean = self.running_mean.clone() * (1.0 - self.momentum)
-            + mean_chan.retaped::<NoneTape>() * self.momentum;
+        self.running_mean = self
+            .running_mean
+            .clone()
+            .try_mul(1.0 - self.momentum)?
+            .try_add(mean_chan.retaped::<NoneTape>().try_mul(self.momentum)?)?;
 
-        let mean = mean_chan.broadcast_like(&shape);
-        let centered = x - mean;
+        let mean = mean_chan.try_broadcast_like(&shape)?;
+        let centered = x.try_sub(mean)?;
 
-        let var_chan = centered.retaped::<T>().square().mean::<Rank1<C>, _>();
+        let var_chan = centered
+            .retaped::<T>()
+            .try_square()?
+            .try_mean::<Rank1<C>, _>()?;
 
         // NOTE: uses unbiased variance in running estimate
-        self.running_var = self.running_var.clone() * (1.0 - self.momentum)
-            + var_chan.retaped::<NoneTape>() * (self.momentum * n / (n - 1.0));
+        self.running_var = self
+            .running_var
+            .clone()
+            .try_mul(1.0 - self.momentum)?
+            .try_add(
+                var_chan
+                    .retaped::<NoneTape>()
+                    .try_mul(self.momentum * n / (n - 1.0))?,
+            )?;
 
         // statistics for normalizing - on tape
-        let std = (var_chan + self.epsilon).sqrt().broadcast_like(&shape);
-
-        // record broadcast of scale & bias - on tape
-        let scale = self.scale.retaped::<T>().broadcast_like(&shape);
-        let bias = self.bias.retaped::<T>().broadcast_like(&shape);
+        let std = var_chan
+            .try_add(self.epsilon)?
+            .try_sqrt()?
+            .try_broadcast_like(&shape)?;
 
         // normalize & affine - on tape
-        (centered / std) * scale + bias
+        centered
+            .try_div(std)?
+            .try_affine::<Ax>(self.scale.retaped::<T>(), self.bias.retaped::<T>())
     }
 }
 
@@ -115,10 +136,14 @@ impl<const C: usize, H: Dim, W: Dim, D: Device<f32>>
     Module<Tensor<(Const<C>, H, W), f32, D, NoneTape>> for BatchNorm2D<C, D>
 {
     type Output = Tensor<(Const<C>, H, W), f32, D, NoneTape>;
+    type Error = D::Err;
 
     /// Inference 3d forward - does **not** update [Self::running_mean] and [Self::running_var]
-    fn forward(&self, x: Tensor<(Const<C>, H, W), f32, D, NoneTape>) -> Self::Output {
-        self.infer_fwd(x)
+    fn try_forward(
+        &self,
+        x: Tensor<(Const<C>, H, W), f32, D, NoneTape>,
+    ) -> Result<Self::Output, D::Err> {
+        self.try_infer_fwd(x)
     }
 }
 
@@ -126,10 +151,14 @@ impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>>
     Module<Tensor<(B, Const<C>, H, W), f32, D, NoneTape>> for BatchNorm2D<C, D>
 {
     type Output = Tensor<(B, Const<C>, H, W), f32, D, NoneTape>;
+    type Error = D::Err;
 
     /// Inference 4d forward - does **not** update [Self::running_mean] and [Self::running_var]
-    fn forward(&self, x: Tensor<(B, Const<C>, H, W), f32, D, NoneTape>) -> Self::Output {
-        self.infer_fwd(x)
+    fn try_forward(
+        &self,
+        x: Tensor<(B, Const<C>, H, W), f32, D, NoneTape>,
+    ) -> Result<Self::Output, D::Err> {
+        self.try_infer_fwd(x)
     }
 }
 
@@ -137,10 +166,14 @@ impl<const C: usize, H: Dim, W: Dim, D: Device<f32>>
     ModuleMut<Tensor<(Const<C>, H, W), f32, D, OwnedTape<D>>> for BatchNorm2D<C, D>
 {
     type Output = Tensor<(Const<C>, H, W), f32, D, OwnedTape<D>>;
+    type Error = D::Err;
 
     /// Training 3d forward - updates [Self::running_mean] and [Self::running_var]
-    fn forward_mut(&mut self, x: Tensor<(Const<C>, H, W), f32, D, OwnedTape<D>>) -> Self::Output {
-        self.train_fwd(x)
+    fn try_forward_mut(
+        &mut self,
+        x: Tensor<(Const<C>, H, W), f32, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        self.try_train_fwd(x)
     }
 }
 
@@ -148,13 +181,14 @@ impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>>
     ModuleMut<Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>> for BatchNorm2D<C, D>
 {
     type Output = Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>;
+    type Error = D::Err;
 
     /// Training 4d forward - updates [Self::running_mean] and [Self::running_var]
-    fn forward_mut(
+    fn try_forward_mut(
         &mut self,
         x: Tensor<(B, Const<C>, H, W), f32, D, OwnedTape<D>>,
-    ) -> Self::Output {
-        self.train_fwd(x)
+    ) -> Result<Self::Output, D::Err> {
+        self.try_train_fwd(x)
     }
 }
 
@@ -202,6 +236,8 @@ impl<const C: usize, D: Device<f32>> GradientUpdate<D, f32> for BatchNorm2D<C, D
     {
         self.scale.update(updater, unused)?;
         self.bias.update(updater, unused)?;
+        updater.update_buffer(&mut self.running_mean)?;
+        updater.update_buffer(&mut self.running_var)?;
         Ok(())
     }
 }
@@ -209,7 +245,7 @@ impl<const C: usize, D: Device<f32>> GradientUpdate<D, f32> for BatchNorm2D<C, D
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::*;
+    use crate::{nn::tests::CountingUpdater, optim::Optimizer, tests::*};
 
     #[test]
     fn test_batchnorm2d_3d_forward_mut() {
@@ -330,4 +366,26 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_batchnorm2d_running_stats_are_buffers() {
+        let dev: TestDevice = Default::default();
+        let mut bn: BatchNorm2D<3, _> = BuildModule::build(&dev);
+        let m = bn.running_mean.array();
+
+        let mut counter: CountingUpdater = Default::default();
+        let mut unused = Default::default();
+        bn.update(&mut counter, &mut unused).unwrap();
+        assert_eq!((counter.params, counter.buffers), (2, 2));
+
+        let mut opt = crate::optim::Sgd::new(&bn, Default::default());
+        let g = bn
+            .forward_mut(dev.sample_normal::<Rank3<3, 2, 2>>().traced())
+            .mean()
+            .backward();
+        let m_after_fwd = bn.running_mean.array();
+        assert_ne!(m_after_fwd, m);
+        opt.update(&mut bn, g).unwrap();
+        assert_eq!(bn.running_mean.array(), m_after_fwd);
+    }
 }
@@ -0,0 +1,307 @@
+use crate::{gradients::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+#[cfg(feature = "nightly")]
+use super::conv::Conv2D;
+#[allow(unused)]
+use super::{
+    pool2d::{AvgPool2D, MaxPool2D, MinPool2D},
+    BatchNorm2D, BuildModule, Module, ModuleMut, ResetParams, ToDevice,
+};
+
+/// Runs the wrapped image module on channels last (NHWC) images, i.e. `(H, W, C)` or
+/// `(B, H, W, C)`, instead of `(C, H, W)` or `(B, C, H, W)`.
+///
+/// The parameters of the wrapped module are unchanged, so weights can be shared/saved/loaded
+/// between the two layouts. Supported modules are [Conv2D], [AvgPool2D], [MaxPool2D], [MinPool2D],
+/// and [BatchNorm2D]. Stacks of layers can be wrapped individually:
+///
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// type Model = (
+///     ChannelsLast<Conv2D<3, 8, 3>>,
+///     ChannelsLast<BatchNorm2D<8>>,
+///     ReLU,
+///     ChannelsLast<MaxPool2D<2, 2>>,
+/// );
+/// ```
+///
+/// Conv & pool kernels read & write the NHWC buffers directly, no intermediate transposes are made.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelsLast<M>(pub M);
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for ChannelsLast<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.0.update(updater, unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: BuildModule<D, E>> BuildModule<D, E> for ChannelsLast<M> {
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self(BuildModule::try_build(device)?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: ResetParams<D, E>> ResetParams<D, E> for ChannelsLast<M> {
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.0.try_reset_params()
+    }
+}
+
+impl<M: ToDevice<D>, D> ToDevice<D> for ChannelsLast<M> {
+    type Output = ChannelsLast<M::Output>;
+    fn to_device(&self, device: &D) -> Self::Output {
+        ChannelsLast(self.0.to_device(device))
+    }
+}
+
+impl<M: ToDtype<E>, E> ToDtype<E> for ChannelsLast<M> {
+    type Output = ChannelsLast<M::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        ChannelsLast(self.0.to_dtype())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > Module<Img> for ChannelsLast<Conv2D<C, O, K, S, P, G, D>>
+where
+    D: Device<f32>,
+    Const<{ C / G }>: Sized,
+    Img: 'static + TryConv2DNhwcTo<Tensor<Rank4<O, { C / G }, K, K>, f32, D>, S, P>,
+    for<'a> BiasNhwc<'a, O, D>: Module<Img::Output, Output = Img::Output, Error = Img::Err>,
+{
+    type Output = Img::Output;
+    type Error = Img::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+        let conv = &self.0;
+        BiasNhwc { beta: &conv.bias }.try_forward(x.try_conv2d_nhwc_to(conv.weight.clone())?)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > ModuleMut<Img> for ChannelsLast<Conv2D<C, O, K, S, P, G, D>>
+where
+    D: Device<f32>,
+    Const<{ C / G }>: Sized,
+    Self: Module<Img>,
+{
+    type Output = <Self as Module<Img>>::Output;
+    type Error = <Self as Module<Img>>::Error;
+    fn try_forward_mut(&mut self, input: Img) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[cfg(feature = "nightly")]
+#[derive(Clone, Debug)]
+struct BiasNhwc<'a, const C: usize, D: Device<f32> = Cpu> {
+    beta: &'a Tensor<Rank1<C>, f32, D>,
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(H, W, Const<C>), f32, D, T>> for BiasNhwc<'a, C, D>
+{
+    type Output = Tensor<(H, W, Const<C>), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(H, W, Const<C>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, H, W, Const<C>), f32, D, T>> for BiasNhwc<'a, C, D>
+{
+    type Output = Tensor<(B, H, W, Const<C>), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(B, H, W, Const<C>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+macro_rules! impl_pools {
+    ($PoolTy:tt, $Trait:ident) => {
+        #[cfg(feature = "nightly")]
+        impl<const K: usize, const S: usize, const P: usize, Img: $Trait<K, S, P>> Module<Img>
+            for ChannelsLast<$PoolTy<K, S, P>>
+        {
+            type Output = Img::Output;
+            type Error = Img::Err;
+            fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+                x.try_pool2d_nhwc()
+            }
+        }
+
+        #[cfg(feature = "nightly")]
+        impl<const K: usize, const S: usize, const P: usize, Img: $Trait<K, S, P>> ModuleMut<Img>
+            for ChannelsLast<$PoolTy<K, S, P>>
+        {
+            type Output = Img::Output;
+            type Error = Img::Err;
+            fn try_forward_mut(&mut self, x: Img) -> Result<Self::Output, Img::Err> {
+                x.try_pool2d_nhwc()
+            }
+        }
+    };
+}
+
+impl_pools!(AvgPool2D, NhwcAvgPool2D);
+impl_pools!(MaxPool2D, NhwcMaxPool2D);
+impl_pools!(MinPool2D, NhwcMinPool2D);
+
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>>
+    Module<Tensor<(H, W, Const<C>), f32, D, NoneTape>> for ChannelsLast<BatchNorm2D<C, D>>
+{
+    type Output = Tensor<(H, W, Const<C>), f32, D, NoneTape>;
+    type Error = D::Err;
+
+    /// Inference 3d forward - does **not** update running statistics
+    fn try_forward(
+        &self,
+        x: Tensor<(H, W, Const<C>), f32, D, NoneTape>,
+    ) -> Result<Self::Output, D::Err> {
+        self.0.try_infer_fwd(x)
+    }
+}
+
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>>
+    Module<Tensor<(B, H, W, Const<C>), f32, D, NoneTape>> for ChannelsLast<BatchNorm2D<C, D>>
+{
+    type Output = Tensor<(B, H, W, Const<C>), f32, D, NoneTape>;
+    type Error = D::Err;
+
+    /// Inference 4d forward - does **not** update running statistics
+    fn try_forward(
+        &self,
+        x: Tensor<(B, H, W, Const<C>), f32, D, NoneTape>,
+    ) -> Result<Self::Output, D::Err> {
+        self.0.try_infer_fwd(x)
+    }
+}
+
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>>
+    ModuleMut<Tensor<(H, W, Const<C>), f32, D, OwnedTape<D>>> for ChannelsLast<BatchNorm2D<C, D>>
+{
+    type Output = Tensor<(H, W, Const<C>), f32, D, OwnedTape<D>>;
+    type Error = D::Err;
+
+    /// Training 3d forward - updates running statistics
+    fn try_forward_mut(
+        &mut self,
+        x: Tensor<(H, W, Const<C>), f32, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        self.0.try_train_fwd(x)
+    }
+}
+
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>>
+    ModuleMut<Tensor<(B, H, W, Const<C>), f32, D, OwnedTape<D>>>
+    for ChannelsLast<BatchNorm2D<C, D>>
+{
+    type Output = Tensor<(B, H, W, Const<C>), f32, D, OwnedTape<D>>;
+    type Error = D::Err;
+
+    /// Training 4d forward - updates running statistics
+    fn try_forward_mut(
+        &mut self,
+        x: Tensor<(B, H, W, Const<C>), f32, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        self.0.try_train_fwd(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_batchnorm2d_channels_last() {
+        let dev = TestDevice::seed_from_u64(0);
+        let x: Tensor<Rank4<2, 3, 4, 5>, f32, _> = dev.sample_normal();
+        let x_nhwc = dev.tensor(x.clone().permute::<_, Axes4<0, 2, 3, 1>>().array());
+
+        let mut bn: BatchNorm2D<3, _> = BuildModule::build(&dev);
+        let mut bn_nhwc = ChannelsLast(bn.clone());
+
+        let y = bn.forward_mut(x.trace());
+        let y_nhwc = bn_nhwc.forward_mut(x_nhwc.trace());
+        assert_close(
+            &y_nhwc.array(),
+            &dev.tensor(y.array())
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+        assert_close(&bn_nhwc.0.running_mean.array(), &bn.running_mean.array());
+        assert_close(&bn_nhwc.0.running_var.array(), &bn.running_var.array());
+
+        let g = y.exp().mean().backward();
+        let g_nhwc = y_nhwc.exp().mean().backward();
+        assert_close(
+            &g_nhwc.get(&bn_nhwc.0.scale).array(),
+            &g.get(&bn.scale).array(),
+        );
+        assert_close(
+            &g_nhwc.get(&bn_nhwc.0.bias).array(),
+            &g.get(&bn.bias).array(),
+        );
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_conv_stack_channels_last() {
+        type Nchw = (Conv2D<2, 4, 3, 1, 1>, MaxPool2D<2, 2>);
+        type Nhwc = (
+            ChannelsLast<Conv2D<2, 4, 3, 1, 1>>,
+            ChannelsLast<MaxPool2D<2, 2>>,
+        );
+
+        let dev = TestDevice::seed_from_u64(1);
+        let m: <Nchw as ToDevice<TestDevice>>::Output = BuildModule::build(&dev);
+        let m_nhwc: <Nhwc as ToDevice<TestDevice>>::Output =
+            (ChannelsLast(m.0.clone()), Default::default());
+
+        let x: Tensor<Rank4<3, 2, 6, 6>, f32, _> = dev.sample_normal();
+        let x_nhwc = dev.tensor(x.clone().permute::<_, Axes4<0, 2, 3, 1>>().array());
+
+        let y = m.forward(x);
+        let y_nhwc: Tensor<Rank4<3, 3, 3, 4>, f32, _> = m_nhwc.forward(x_nhwc);
+        assert_close(
+            &y_nhwc.array(),
+            &y.permute::<_, Axes4<0, 2, 3, 1>>().array(),
+        );
+    }
+}
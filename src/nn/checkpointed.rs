@@ -0,0 +1,225 @@
+use crate::{
+    gradients::{NoneTape, OwnedTape, Tape},
+    optim::*,
+    shapes::*,
+    tensor::{PutTape, SplitTape, Tensor},
+    tensor_ops::Device,
+};
+
+use super::{BuildModule, Module, NonMutableModule, Repeated, ResetParams, ToDevice, ToDtype};
+
+/// Activation checkpointing for `M`: the forward pass of `M` is run without a tape, so none of
+/// its intermediate activations are stored. During the backward pass, `M` is run again with
+/// a tape to recompute them. This trades an extra forward pass of `M` for memory.
+///
+/// Only the input of `M` is kept until the backward pass, along with a clone of `M`
+/// (which doesn't copy any parameter data).
+///
+/// Since `M` is run twice, it must be deterministic, so [super::ModuleMut::forward_mut()]
+/// uses [Module::forward()]. This means things like [super::Dropout] are in inference mode.
+///
+/// See [CheckpointedRepeated] for checkpointing very deep models.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Checkpointed<(Linear<5, 32>, ReLU, Linear<32, 5>)>, Linear<5, 2>);
+/// let model = Model::build_on_device(&dev);
+/// let y = model.forward(dev.zeros::<Rank2<4, 5>>().traced());
+/// let _ = y.mean().backward();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Checkpointed<M>(pub M);
+
+/// Applies `M` `N` times, with activation checkpointing between every repeat.
+/// Only the inputs of each repeat are stored for the backward pass, so the memory used by
+/// activations is bounded by `N` tensors plus the activations of a single `M`.
+///
+/// See [Checkpointed] and [Repeated].
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = CheckpointedRepeated<(Linear<10, 10>, ReLU), 100>;
+/// let model = Model::build_on_device(&dev);
+/// let y = model.forward(dev.zeros::<Rank2<4, 10>>().traced());
+/// let _ = y.mean().backward();
+/// ```
+pub type CheckpointedRepeated<M, const N: usize> = Repeated<Checkpointed<M>, N>;
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for Checkpointed<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.0.update(updater, unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: BuildModule<D, E>> BuildModule<D, E> for Checkpointed<M> {
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self(BuildModule::try_build(device)?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: ResetParams<D, E>> ResetParams<D, E> for Checkpointed<M> {
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.0.try_reset_params()
+    }
+}
+
+impl<M: ToDevice<D>, D> ToDevice<D> for Checkpointed<M> {
+    type Output = Checkpointed<M::Output>;
+    fn to_device(&self, device: &D) -> Self::Output {
+        Checkpointed(self.0.to_device(device))
+    }
+}
+
+impl<M: ToDtype<E>, E> ToDtype<E> for Checkpointed<M> {
+    type Output = Checkpointed<M::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        Checkpointed(self.0.to_dtype())
+    }
+}
+
+impl<M> NonMutableModule for Checkpointed<M> {}
+
+impl<S: Shape, E: Dtype, D: Device<E>, M: Module<Tensor<S, E, D, NoneTape>>>
+    Module<Tensor<S, E, D, NoneTape>> for Checkpointed<M>
+{
+    type Output = M::Output;
+    type Error = M::Error;
+    fn try_forward(&self, x: Tensor<S, E, D, NoneTape>) -> Result<Self::Output, M::Error> {
+        self.0.try_forward(x)
+    }
+}
+
+impl<S: Shape, Y: Shape, E: Dtype, D: Device<E>, M> Module<Tensor<S, E, D, OwnedTape<D>>>
+    for Checkpointed<M>
+where
+    M: 'static
+        + Clone
+        + Send
+        + Module<Tensor<S, E, D, NoneTape>, Output = Tensor<Y, E, D, NoneTape>, Error = D::Err>
+        + Module<
+            Tensor<S, E, D, OwnedTape<D>>,
+            Output = Tensor<Y, E, D, OwnedTape<D>>,
+            Error = D::Err,
+        >,
+{
+    type Output = Tensor<Y, E, D, OwnedTape<D>>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<S, E, D, OwnedTape<D>>) -> Result<Self::Output, D::Err> {
+        let (x, mut tape) = x.split_tape();
+        let y = self.0.try_forward(x.clone())?;
+        let phantom_y = y.clone();
+        let module = self.0.clone();
+        tape.try_alloc_grad(&x)?;
+        tape.try_alloc_grad(&y)?;
+        // the gradients of the parameters of `module` are used too
+        tape.0.add_exclusive_backward_op(move |grads| {
+            // recompute the activations, and backprop the gradient of `y` through them
+            let num_threads = x.device.backward_threads();
+            let (y_inner, mut inner_tape) = module.try_forward(x.traced())?.split_tape();
+            inner_tape.try_alloc_grad(&y_inner)?;
+            let grad_y = grads.get(&phantom_y).clone();
+            inner_tape.add_backward_op(move |grads| {
+                *grads.get_mut(&y_inner) = grad_y;
+                Ok(())
+            });
+            inner_tape.0.execute_into(grads, num_threads)
+        });
+        Ok(y.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, BuildOnDevice, Linear, ReLU, Tanh, TiedRepeated},
+        tensor::*,
+        tensor_ops::*,
+        tests::*,
+    };
+
+    #[test]
+    fn test_checkpointed_same_as_unchecked() {
+        let dev: TestDevice = Default::default();
+        type Inner = (Linear<3, 8>, Tanh, Linear<8, 3>);
+        let model = <(Checkpointed<Inner>, ReLU, Checkpointed<Inner>)>::build_on_device(&dev);
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+
+        let y = model.forward(x.trace());
+        let y_expected = ((model.0).0.clone(), ReLU, (model.2).0.clone()).forward(x.trace());
+        assert_eq!(y.array(), y_expected.array());
+
+        let g = y.square().mean().backward();
+        let g_expected = y_expected.square().mean().backward();
+        assert_close(&g.get(&x).array(), &g_expected.get(&x).array());
+        for m in [&model.0 .0, &model.2 .0] {
+            assert_close(
+                &g.get(&m.0.weight).array(),
+                &g_expected.get(&m.0.weight).array(),
+            );
+            assert_close(
+                &g.get(&m.0.bias).array(),
+                &g_expected.get(&m.0.bias).array(),
+            );
+            assert_close(
+                &g.get(&m.2.weight).array(),
+                &g_expected.get(&m.2.weight).array(),
+            );
+            assert_close(
+                &g.get(&m.2.bias).array(),
+                &g_expected.get(&m.2.bias).array(),
+            );
+        }
+    }
+
+    #[test]
+    fn test_checkpointed_repeated() {
+        let dev: TestDevice = Default::default();
+        let mut model = CheckpointedRepeated::<(Linear<3, 3>, Tanh), 10>::build_on_device(&dev);
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let mut y_expected = x.trace();
+        for m in model.modules.iter() {
+            y_expected = m.0.forward(y_expected);
+        }
+        let y = model.forward(x.trace());
+        assert_eq!(y.array(), y_expected.array());
+
+        let g = y.mean().backward();
+        let g_expected = y_expected.mean().backward();
+        assert_close(&g.get(&x).array(), &g_expected.get(&x).array());
+        for m in model.modules.iter() {
+            assert_close(
+                &g.get(&m.0 .0.weight).array(),
+                &g_expected.get(&m.0 .0.weight).array(),
+            );
+        }
+
+        let mut g = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_checkpointed_tied_repeated() {
+        let dev: TestDevice = Default::default();
+        let model = TiedRepeated::<Checkpointed<(Linear<3, 3>, Tanh)>, 5>::build_on_device(&dev);
+        let unchecked: TiedRepeated<_, 5> = TiedRepeated {
+            module: model.module.0.clone(),
+        };
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let g = model.forward(x.trace()).mean().backward();
+        let g_expected = unchecked.forward(x.trace()).mean().backward();
+        let w = &model.module.0 .0.weight;
+        assert_close(&g.get(w).array(), &g_expected.get(w).array());
+    }
+}
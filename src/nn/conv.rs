@@ -12,6 +12,11 @@ use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
 /// - `KERNEL_SIZE`: The size of the kernel applied to both width and height of the images.
 /// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
 /// - `PADDING`: How much zero padding to add around the images. Defaults to `0`.
+/// - `GROUPS`: The number of groups the input & output channels are split into. Each group of
+///   output channels is only connected to its own group of input channels. `IN_CHAN` and `OUT_CHAN`
+///   must be divisible by `GROUPS`. Defaults to `1`. A depthwise convolution has
+///   `GROUPS == IN_CHAN`.
+#[cfg(feature = "nightly")]
 #[derive(Debug, Clone)]
 pub struct Conv2D<
     const IN_CHAN: usize,
@@ -19,16 +24,28 @@ pub struct Conv2D<
     const KERNEL_SIZE: usize,
     const STRIDE: usize = 1,
     const PADDING: usize = 0,
+    const GROUPS: usize = 1,
     D: Device<f32> = Cpu,
-> {
-    pub weight: Tensor<Rank4<OUT_CHAN, IN_CHAN, KERNEL_SIZE, KERNEL_SIZE>, f32, D>,
+> where
+    Const<{ IN_CHAN / GROUPS }>: Sized,
+{
+    pub weight: Tensor<Rank4<OUT_CHAN, { IN_CHAN / GROUPS }, KERNEL_SIZE, KERNEL_SIZE>, f32, D>,
     pub bias: Tensor<Rank1<OUT_CHAN>, f32, D>,
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
-    GradientUpdate<D, f32> for Conv2D<I, O, K, S, P, D>
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > GradientUpdate<D, f32> for Conv2D<I, O, K, S, P, G, D>
 where
     D: Device<f32>,
+    Const<{ I / G }>: Sized,
 {
     fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
     where
@@ -40,13 +57,43 @@ where
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
-    BuildModule<D, f32> for Conv2D<I, O, K, S, P, D>
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > Conv2D<I, O, K, S, P, G, D>
 where
     D: Device<f32>,
+    Const<{ I / G }>: Sized,
+{
+    const GROUPS_CHECK: () = assert!(
+        G > 0 && I % G == 0 && O % G == 0,
+        "IN_CHAN and OUT_CHAN must be divisible by GROUPS"
+    );
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > BuildModule<D, f32> for Conv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
 {
     fn try_build(device: &D) -> Result<Self, <D>::Err> {
-        let k = (I * K * K) as f32;
+        let () = Self::GROUPS_CHECK;
+        let k = ((I / G) * K * K) as f32;
         let bound = 1.0 / k.sqrt();
         let distr = rand_distr::Uniform::new(-bound, bound);
         Ok(Self {
@@ -56,13 +103,22 @@ where
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
-    ResetParams<D, f32> for Conv2D<I, O, K, S, P, D>
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > ResetParams<D, f32> for Conv2D<I, O, K, S, P, G, D>
 where
     D: Device<f32>,
+    Const<{ I / G }>: Sized,
 {
     fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
-        let k = (I * K * K) as f32;
+        let k = ((I / G) * K * K) as f32;
         let bound = 1.0 / k.sqrt();
         let distr = rand_distr::Uniform::new(-bound, bound);
         self.weight.try_fill_with_distr(distr)?;
@@ -71,13 +127,23 @@ where
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D1, D2>
-    ToDevice<D2> for Conv2D<I, O, K, S, P, D1>
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D1,
+        D2,
+    > ToDevice<D2> for Conv2D<I, O, K, S, P, G, D1>
 where
     D1: Device<f32>,
     D2: Device<f32>,
+    Const<{ I / G }>: Sized,
 {
-    type Output = Conv2D<I, O, K, S, P, D2>;
+    type Output = Conv2D<I, O, K, S, P, G, D2>;
 
     fn to_device(&self, device: &D2) -> Self::Output {
         Conv2D {
@@ -88,28 +154,374 @@ where
 }
 
 #[cfg(feature = "nightly")]
-impl<const C: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
-    Module<Img> for Conv2D<C, O, K, S, P, D>
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > Module<Img> for Conv2D<C, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ C / G }>: Sized,
+    Img: 'static + TryConv2DTo<Tensor<Rank4<O, { C / G }, K, K>, f32, D>, S, P, Err = D::Err>,
+    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output, Error = D::Err>,
+{
+    type Output = Img::Output;
+    type Error = D::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, D::Err> {
+        Bias2D { beta: &self.bias }.try_forward(x.try_conv2d_to(self.weight.clone())?)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > ModuleMut<Img> for Conv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
+    Self: Module<Img>,
+{
+    type Output = <Self as Module<Img>>::Output;
+    type Error = <Self as Module<Img>>::Error;
+    fn try_forward_mut(&mut self, input: Img) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+/// **Requires Nightly** A [Conv2D] without the bias, for when the layer is followed by a
+/// normalization (like [super::BatchNorm2D]) that would cancel out the bias anyway.
+///
+/// [Self::weight] is saved with the same name as [Conv2D::weight], so the weights of a saved
+/// [Conv2D] can be loaded into this (the bias is ignored).
+///
+/// **Pytorch Equivalent**: `torch.nn.Conv2d(..., bias=False)`
+///
+/// Generics are the same as [Conv2D].
+#[cfg(feature = "nightly")]
+#[derive(Debug, Clone)]
+pub struct UnbiasedConv2D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    const GROUPS: usize = 1,
+    D: Device<f32> = Cpu,
+> where
+    Const<{ IN_CHAN / GROUPS }>: Sized,
+{
+    pub weight: Tensor<Rank4<OUT_CHAN, { IN_CHAN / GROUPS }, KERNEL_SIZE, KERNEL_SIZE>, f32, D>,
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > GradientUpdate<D, f32> for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > BuildModule<D, f32> for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        let () = Conv2D::<I, O, K, S, P, G, D>::GROUPS_CHECK;
+        let k = ((I / G) * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            weight: device.try_sample(distr)?,
+        })
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+    > ResetParams<D, f32> for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        let k = ((I / G) * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D1,
+        D2,
+    > ToDevice<D2> for UnbiasedConv2D<I, O, K, S, P, G, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+    Const<{ I / G }>: Sized,
+{
+    type Output = UnbiasedConv2D<I, O, K, S, P, G, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        UnbiasedConv2D {
+            weight: self.weight.to_device(device),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > Module<Img> for UnbiasedConv2D<C, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ C / G }>: Sized,
+    Img: 'static + TryConv2DTo<Tensor<Rank4<O, { C / G }, K, K>, f32, D>, S, P, Err = D::Err>,
+{
+    type Output = Img::Output;
+    type Error = D::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, D::Err> {
+        x.try_conv2d_to(self.weight.clone())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D,
+        Img,
+    > ModuleMut<Img> for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    D: Device<f32>,
+    Const<{ I / G }>: Sized,
+    Self: Module<Img>,
+{
+    type Output = <Self as Module<Img>>::Output;
+    type Error = <Self as Module<Img>>::Error;
+    fn try_forward_mut(&mut self, input: Img) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+/// **Requires Nightly** Performs transposed 2d convolutions on 3d and 4d images.
+///
+/// **Pytorch Equivalent**: `torch.nn.ConvTranspose2d`
+///
+/// Generics:
+/// - `IN_CHAN`: The number of input channels in an image.
+/// - `OUT_CHAN`: The number of channels in the output of the layer.
+/// - `KERNEL_SIZE`: The size of the kernel applied to both width and height of the images.
+/// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
+/// - `PADDING`: How much padding is removed from each side of the output. Defaults to `0`.
+/// - `OUTPUT_PADDING`: Extra size added to one side of the output. Defaults to `0`.
+#[derive(Debug, Clone)]
+pub struct ConvTranspose2D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    const OUTPUT_PADDING: usize = 0,
+    D: Device<f32> = Cpu,
+> {
+    pub weight: Tensor<Rank4<IN_CHAN, OUT_CHAN, KERNEL_SIZE, KERNEL_SIZE>, f32, D>,
+    pub bias: Tensor<Rank1<OUT_CHAN>, f32, D>,
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D,
+    > GradientUpdate<D, f32> for ConvTranspose2D<I, O, K, S, P, OP, D>
+where
+    D: Device<f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D,
+    > BuildModule<D, f32> for ConvTranspose2D<I, O, K, S, P, OP, D>
+where
+    D: Device<f32>,
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        let k = (O * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            weight: device.try_sample(distr)?,
+            bias: device.try_sample(distr)?,
+        })
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D,
+    > ResetParams<D, f32> for ConvTranspose2D<I, O, K, S, P, OP, D>
+where
+    D: Device<f32>,
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        let k = (O * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)?;
+        self.bias.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D1,
+        D2,
+    > ToDevice<D2> for ConvTranspose2D<I, O, K, S, P, OP, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = ConvTranspose2D<I, O, K, S, P, OP, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        ConvTranspose2D {
+            weight: self.weight.to_device(device),
+            bias: self.bias.to_device(device),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D,
+        Img,
+    > Module<Img> for ConvTranspose2D<C, O, K, S, P, OP, D>
 where
     D: Device<f32>,
-    Img: TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P>,
-    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output>,
+    Img: TryConvTrans2DTo<Tensor<Rank4<C, O, K, K>, f32, D>, S, P, OP, Err = D::Err>,
+    for<'a> Bias2D<'a, O, D>: Module<Img::Output, Output = Img::Output, Error = D::Err>,
 {
     type Output = Img::Output;
-    fn forward(&self, x: Img) -> Self::Output {
-        Bias2D { beta: &self.bias }.forward(x.conv2d_to(self.weight.clone()))
+    type Error = D::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, D::Err> {
+        Bias2D { beta: &self.bias }.try_forward(x.try_conv_transpose2d_to(self.weight.clone())?)
     }
 }
 
-impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Img>
-    ModuleMut<Img> for Conv2D<I, O, K, S, P, D>
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D,
+        Img,
+    > ModuleMut<Img> for ConvTranspose2D<I, O, K, S, P, OP, D>
 where
     D: Device<f32>,
     Self: Module<Img>,
 {
     type Output = <Self as Module<Img>>::Output;
-    fn forward_mut(&mut self, input: Img) -> Self::Output {
-        self.forward(input)
+    type Error = <Self as Module<Img>>::Error;
+    fn try_forward_mut(&mut self, input: Img) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
     }
 }
 
@@ -122,8 +534,15 @@ impl<'a, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
     Module<Tensor<(Const<C>, H, W), f32, D, T>> for Bias2D<'a, C, D>
 {
     type Output = Tensor<(Const<C>, H, W), f32, D, T>;
-    fn forward(&self, input: Tensor<(Const<C>, H, W), f32, D, T>) -> Self::Output {
-        self.beta.retaped::<T>().broadcast_like(input.shape()) + input
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(Const<C>, H, W), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
     }
 }
 
@@ -131,8 +550,359 @@ impl<'a, B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
     Module<Tensor<(B, Const<C>, H, W), f32, D, T>> for Bias2D<'a, C, D>
 {
     type Output = Tensor<(B, Const<C>, H, W), f32, D, T>;
-    fn forward(&self, input: Tensor<(B, Const<C>, H, W), f32, D, T>) -> Self::Output {
-        self.beta.retaped::<T>().broadcast_like(input.shape()) + input
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(B, Const<C>, H, W), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+/// **Requires Nightly** Performs 1d convolutions on 2d and 3d sequences, i.e. `(C, L)` or `(B, C, L)`.
+///
+/// **Pytorch Equivalent**: `torch.nn.Conv1d`
+///
+/// Generics:
+/// - `IN_CHAN`: The number of input channels in a sequence.
+/// - `OUT_CHAN`: The number of channels in the output of the layer.
+/// - `KERNEL_SIZE`: The size of the kernel applied along the sequence.
+/// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
+/// - `PADDING`: How much zero padding to add to both ends of the sequence. Defaults to `0`.
+/// - `DILATION`: The spacing between the elements of the kernel. Defaults to `1`.
+#[derive(Debug, Clone)]
+pub struct Conv1D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    const DILATION: usize = 1,
+    D: Device<f32> = Cpu,
+> {
+    pub weight: Tensor<Rank3<OUT_CHAN, IN_CHAN, KERNEL_SIZE>, f32, D>,
+    pub bias: Tensor<Rank1<OUT_CHAN>, f32, D>,
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D,
+    > GradientUpdate<D, f32> for Conv1D<I, O, K, S, P, L, D>
+where
+    D: Device<f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D,
+    > BuildModule<D, f32> for Conv1D<I, O, K, S, P, L, D>
+where
+    D: Device<f32>,
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        let k = (I * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            weight: device.try_sample(distr)?,
+            bias: device.try_sample(distr)?,
+        })
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D,
+    > ResetParams<D, f32> for Conv1D<I, O, K, S, P, L, D>
+where
+    D: Device<f32>,
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        let k = (I * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)?;
+        self.bias.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D1,
+        D2,
+    > ToDevice<D2> for Conv1D<I, O, K, S, P, L, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = Conv1D<I, O, K, S, P, L, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        Conv1D {
+            weight: self.weight.to_device(device),
+            bias: self.bias.to_device(device),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const C: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D,
+        Seq,
+    > Module<Seq> for Conv1D<C, O, K, S, P, L, D>
+where
+    D: Device<f32>,
+    Seq: TryConv1DTo<Tensor<Rank3<O, C, K>, f32, D>, S, P, L, Err = D::Err>,
+    for<'a> Bias1D<'a, O, D>: Module<Seq::Output, Output = Seq::Output, Error = D::Err>,
+{
+    type Output = Seq::Output;
+    type Error = D::Err;
+    fn try_forward(&self, x: Seq) -> Result<Self::Output, D::Err> {
+        Bias1D { beta: &self.bias }.try_forward(x.try_conv1d_to(self.weight.clone())?)
+    }
+}
+
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D,
+        Seq,
+    > ModuleMut<Seq> for Conv1D<I, O, K, S, P, L, D>
+where
+    D: Device<f32>,
+    Self: Module<Seq>,
+{
+    type Output = <Self as Module<Seq>>::Output;
+    type Error = <Self as Module<Seq>>::Error;
+    fn try_forward_mut(&mut self, input: Seq) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Bias1D<'a, const C: usize, D: Device<f32> = Cpu> {
+    beta: &'a Tensor<Rank1<C>, f32, D>,
+}
+
+impl<'a, const C: usize, L: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(Const<C>, L), f32, D, T>> for Bias1D<'a, C, D>
+{
+    type Output = Tensor<(Const<C>, L), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, input: Tensor<(Const<C>, L), f32, D, T>) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+impl<'a, B: Dim, const C: usize, L: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, Const<C>, L), f32, D, T>> for Bias1D<'a, C, D>
+{
+    type Output = Tensor<(B, Const<C>, L), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(B, Const<C>, L), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+/// **Requires Nightly** Performs 3d convolutions on 4d and 5d volumes, i.e. `(C, D, H, W)` or `(B, C, D, H, W)`.
+///
+/// **Pytorch Equivalent**: `torch.nn.Conv3d`
+///
+/// Generics:
+/// - `IN_CHAN`: The number of input channels in a volume.
+/// - `OUT_CHAN`: The number of channels in the output of the layer.
+/// - `KERNEL_SIZE`: The size of the kernel applied along the depth, height & width.
+/// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
+/// - `PADDING`: How much zero padding to add around the depth, height & width. Defaults to `0`.
+#[derive(Debug, Clone)]
+pub struct Conv3D<
+    const IN_CHAN: usize,
+    const OUT_CHAN: usize,
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    D: Device<f32> = Cpu,
+> {
+    pub weight: Tensor<Rank5<OUT_CHAN, IN_CHAN, KERNEL_SIZE, KERNEL_SIZE, KERNEL_SIZE>, f32, D>,
+    pub bias: Tensor<Rank1<OUT_CHAN>, f32, D>,
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    GradientUpdate<D, f32> for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    BuildModule<D, f32> for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        let k = (I * K * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            weight: device.try_sample(distr)?,
+            bias: device.try_sample(distr)?,
+        })
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D>
+    ResetParams<D, f32> for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        let k = (I * K * K * K) as f32;
+        let bound = 1.0 / k.sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)?;
+        self.bias.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D1, D2>
+    ToDevice<D2> for Conv3D<I, O, K, S, P, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = Conv3D<I, O, K, S, P, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        Conv3D {
+            weight: self.weight.to_device(device),
+            bias: self.bias.to_device(device),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const C: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Vol>
+    Module<Vol> for Conv3D<C, O, K, S, P, D>
+where
+    D: Device<f32>,
+    Vol: TryConv3DTo<Tensor<Rank5<O, C, K, K, K>, f32, D>, S, P, Err = D::Err>,
+    for<'a> Bias3D<'a, O, D>: Module<Vol::Output, Output = Vol::Output, Error = D::Err>,
+{
+    type Output = Vol::Output;
+    type Error = D::Err;
+    fn try_forward(&self, x: Vol) -> Result<Self::Output, D::Err> {
+        Bias3D { beta: &self.bias }.try_forward(x.try_conv3d_to(self.weight.clone())?)
+    }
+}
+
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D, Vol>
+    ModuleMut<Vol> for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+    Self: Module<Vol>,
+{
+    type Output = <Self as Module<Vol>>::Output;
+    type Error = <Self as Module<Vol>>::Error;
+    fn try_forward_mut(&mut self, input: Vol) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Bias3D<'a, const C: usize, D: Device<f32> = Cpu> {
+    beta: &'a Tensor<Rank1<C>, f32, D>,
+}
+
+impl<'a, const C: usize, Z: Dim, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(Const<C>, Z, H, W), f32, D, T>> for Bias3D<'a, C, D>
+{
+    type Output = Tensor<(Const<C>, Z, H, W), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(Const<C>, Z, H, W), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
+    }
+}
+
+impl<'a, B: Dim, const C: usize, Z: Dim, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, Const<C>, Z, H, W), f32, D, T>> for Bias3D<'a, C, D>
+{
+    type Output = Tensor<(B, Const<C>, Z, H, W), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(B, Const<C>, Z, H, W), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
     }
 }
 
@@ -220,4 +990,167 @@ mod tests {
         assert_ne!(weight_init.array(), m.weight.array());
         assert_ne!(bias_init.array(), m.bias.array());
     }
+
+    #[test]
+    fn test_unbiased_conv_matches_conv_with_zero_bias() {
+        let dev: TestDevice = Default::default();
+        let mut m = UnbiasedConv2D::<2, 4, 3, 1, 1>::build_on_device(&dev);
+        let conv: Conv2D<2, 4, 3, 1, 1, 1, _> = Conv2D {
+            weight: m.weight.clone(),
+            bias: dev.zeros(),
+        };
+        let x = dev.sample_normal::<Rank4<2, 2, 5, 5>>();
+        let y = m.forward(x.trace());
+        assert_close(&y.array(), &conv.forward(x.clone()).array());
+
+        let weight_init = m.weight.clone();
+        let mut opt = Sgd::new(&m, Default::default());
+        let g = y.square().mean().backward();
+        opt.update(&mut m, g).expect("unused params");
+        assert_ne!(weight_init.array(), m.weight.array());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_grouped_conv_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.zeros::<Rank3<4, 10, 10>>();
+        let _: Tensor<Rank3<6, 8, 8>, _, _, _> = Conv2D::<4, 6, 3, 1, 0, 2>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank3<4, 5, 5>, _, _, _> = Conv2D::<4, 4, 3, 2, 1, 4>::build_on_device(&dev).forward(x.clone());
+        let x = dev.zeros::<Rank4<5, 4, 10, 10>>();
+        let _: Tensor<Rank4<5, 8, 8, 8>, _, _, _> = Conv2D::<4, 8, 3, 1, 0, 4>::build_on_device(&dev).forward(x.clone());
+        let m = Conv2D::<4, 8, 3, 1, 0, 2>::build_on_device(&dev);
+        let _: Tensor<Rank4<8, 2, 3, 3>, _, _> = m.weight;
+    }
+
+    #[test]
+    fn test_depthwise_conv_with_optimizer() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = Conv2D::<3, 3, 3, 1, 1, 3>::build_on_device(&dev);
+
+        let weight_init = m.weight.clone();
+        let bias_init = m.bias.clone();
+
+        let mut opt = Sgd::new(&m, Default::default());
+        let out = m.forward(dev.sample_normal::<Rank4<8, 3, 28, 28>>().trace());
+        let g = out.square().mean().backward();
+
+        assert_ne!(g.get(&m.weight).array(), [[[[0.0; 3]; 3]; 1]; 3]);
+        assert_ne!(g.get(&m.bias).array(), [0.0; 3]);
+
+        opt.update(&mut m, g).expect("unused params");
+
+        assert_ne!(weight_init.array(), m.weight.array());
+        assert_ne!(bias_init.array(), m.bias.array());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_conv1d_forward_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.zeros::<Rank2<3, 10>>();
+        let _: Tensor<Rank2<2, 8>, _, _, _> = Conv1D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank2<2, 4>, _, _, _> = Conv1D::<3, 2, 3, 2>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank2<2, 10>, _, _, _> = Conv1D::<3, 2, 3, 1, 1>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank2<2, 6>, _, _, _> = Conv1D::<3, 2, 3, 1, 0, 2>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank2<2, 4>, _, _, _> = Conv1D::<3, 2, 3, 2, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let x = dev.zeros::<Rank3<5, 3, 10>>();
+        let _: Tensor<Rank3<5, 2, 8>, _, _, _> = Conv1D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank3<5, 2, 6>, _, _, _> = Conv1D::<3, 2, 3, 1, 0, 2>::build_on_device(&dev).forward(x.clone());
+    }
+
+    #[test]
+    fn test_conv1d_with_optimizer() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = Conv1D::<2, 4, 3, 1, 1, 2>::build_on_device(&dev);
+
+        let weight_init = m.weight.clone();
+        let bias_init = m.bias.clone();
+
+        let mut opt = Sgd::new(&m, Default::default());
+        let out = m.forward(dev.sample_normal::<Rank3<8, 2, 16>>().trace());
+        let g = out.square().mean().backward();
+
+        assert_ne!(g.get(&m.weight).array(), [[[0.0; 3]; 2]; 4]);
+        assert_ne!(g.get(&m.bias).array(), [0.0; 4]);
+
+        opt.update(&mut m, g).expect("unused params");
+
+        assert_ne!(weight_init.array(), m.weight.array());
+        assert_ne!(bias_init.array(), m.bias.array());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_conv3d_forward_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.zeros::<Rank4<3, 6, 8, 10>>();
+        let _: Tensor<Rank4<2, 4, 6, 8>, _, _, _> = Conv3D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank4<2, 2, 3, 4>, _, _, _> = Conv3D::<3, 2, 3, 2>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank4<2, 6, 8, 10>, _, _, _> = Conv3D::<3, 2, 3, 1, 1>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank4<2, 3, 4, 5>, _, _, _> = Conv3D::<3, 2, 3, 2, 1>::build_on_device(&dev).forward(x.clone());
+        let x = dev.zeros::<Rank5<4, 3, 6, 8, 10>>();
+        let _: Tensor<Rank5<4, 2, 4, 6, 8>, _, _, _> = Conv3D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank5<4, 2, 3, 4, 5>, _, _, _> = Conv3D::<3, 2, 3, 2, 1>::build_on_device(&dev).forward(x.clone());
+    }
+
+    #[test]
+    fn test_conv3d_with_optimizer() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = Conv3D::<2, 4, 3, 1, 1>::build_on_device(&dev);
+
+        let weight_init = m.weight.clone();
+        let bias_init = m.bias.clone();
+
+        let mut opt = Sgd::new(&m, Default::default());
+        let out = m.forward(dev.sample_normal::<Rank5<2, 2, 4, 5, 6>>().trace());
+        let g = out.square().mean().backward();
+
+        assert!(g.get(&m.weight).as_vec().iter().any(|&x| x != 0.0));
+        assert_ne!(g.get(&m.bias).array(), [0.0; 4]);
+
+        opt.update(&mut m, g).expect("unused params");
+
+        assert_ne!(weight_init.as_vec(), m.weight.as_vec());
+        assert_ne!(bias_init.array(), m.bias.array());
+    }
+
+    #[rustfmt::skip]
+    #[test]
+    fn test_conv_transpose2d_forward_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.zeros::<Rank3<3, 5, 5>>();
+        let _: Tensor<Rank3<2, 7, 7>, _, _, _> = ConvTranspose2D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank3<2, 11, 11>, _, _, _> = ConvTranspose2D::<3, 2, 3, 2>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank3<2, 9, 9>, _, _, _> = ConvTranspose2D::<3, 2, 3, 2, 1>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank3<2, 10, 10>, _, _, _> = ConvTranspose2D::<3, 2, 3, 2, 1, 1>::build_on_device(&dev).forward(x.clone());
+        let x = dev.zeros::<Rank4<4, 3, 5, 5>>();
+        let _: Tensor<Rank4<4, 2, 7, 7>, _, _, _> = ConvTranspose2D::<3, 2, 3>::build_on_device(&dev).forward(x.clone());
+        let _: Tensor<Rank4<4, 2, 10, 10>, _, _, _> = ConvTranspose2D::<3, 2, 3, 2, 1, 1>::build_on_device(&dev).forward(x.clone());
+    }
+
+    #[test]
+    fn test_conv_transpose2d_with_optimizer() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = ConvTranspose2D::<2, 4, 3, 2, 1, 1>::build_on_device(&dev);
+
+        let weight_init = m.weight.clone();
+        let bias_init = m.bias.clone();
+
+        let mut opt = Sgd::new(&m, Default::default());
+        let out = m.forward(dev.sample_normal::<Rank4<8, 2, 5, 5>>().trace());
+        let g = out.square().mean().backward();
+
+        assert_ne!(g.get(&m.weight).array(), [[[[0.0; 3]; 3]; 4]; 2]);
+        assert_ne!(g.get(&m.bias).array(), [0.0; 4]);
+
+        opt.update(&mut m, g).expect("unused params");
+
+        assert_ne!(weight_init.array(), m.weight.array());
+        assert_ne!(bias_init.array(), m.bias.array());
+    }
 }
@@ -54,9 +54,10 @@ impl<const N: usize, S: Shape, E: Dtype, D: Device<E>> Module<Tensor<S, E, D, No
     for DropoutOneIn<N>
 {
     type Output = Tensor<S, E, D, NoneTape>;
+    type Error = D::Err;
     /// Does nothing
-    fn forward(&self, input: Tensor<S, E, D, NoneTape>) -> Self::Output {
-        input
+    fn try_forward(&self, input: Tensor<S, E, D, NoneTape>) -> Result<Self::Output, D::Err> {
+        Ok(input)
     }
 }
 
@@ -64,9 +65,13 @@ impl<const N: usize, S: Shape, E: Dtype, D: Device<E>> ModuleMut<Tensor<S, E, D,
     for DropoutOneIn<N>
 {
     type Output = Tensor<S, E, D, OwnedTape<D>>;
+    type Error = D::Err;
     /// Calls [dropout()] with `p=1/N` using `self.rng`.
-    fn forward_mut(&mut self, input: Tensor<S, E, D, OwnedTape<D>>) -> Self::Output {
-        dropout(input, 1.0 / N as f32)
+    fn try_forward_mut(
+        &mut self,
+        input: Tensor<S, E, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        input.try_dropout(1.0 / N as f32)
     }
 }
 
@@ -129,17 +134,22 @@ impl<D: Device<E>, E: Dtype> BuildModule<D, E> for Dropout {
 
 impl<S: Shape, E: Dtype, D: Device<E>> Module<Tensor<S, E, D, NoneTape>> for Dropout {
     type Output = Tensor<S, E, D, NoneTape>;
+    type Error = D::Err;
     /// Does nothing.
-    fn forward(&self, input: Tensor<S, E, D, NoneTape>) -> Self::Output {
-        input
+    fn try_forward(&self, input: Tensor<S, E, D, NoneTape>) -> Result<Self::Output, D::Err> {
+        Ok(input)
     }
 }
 
 impl<S: Shape, E: Dtype, D: Device<E>> ModuleMut<Tensor<S, E, D, OwnedTape<D>>> for Dropout {
     type Output = Tensor<S, E, D, OwnedTape<D>>;
+    type Error = D::Err;
     /// Calls [dropout()]
-    fn forward_mut(&mut self, input: Tensor<S, E, D, OwnedTape<D>>) -> Self::Output {
-        dropout(input, self.p)
+    fn try_forward_mut(
+        &mut self,
+        input: Tensor<S, E, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        input.try_dropout(self.p)
     }
 }
 
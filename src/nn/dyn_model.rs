@@ -0,0 +1,1316 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{Module, NonMutableModule, ToDevice};
+
+/// Configuration of a single layer of a [DynModel]. See [ModelConfig].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayerConfig {
+    /// A [DynLinear] from `inp` features to `out` features.
+    Linear {
+        inp: usize,
+        out: usize,
+    },
+    /// A [DynLayerNorm] over `dim` features.
+    LayerNorm {
+        dim: usize,
+    },
+    /// A [DynMultiHeadAttention] with `embed_dim` features split into `num_heads` heads.
+    MultiHeadAttention {
+        embed_dim: usize,
+        num_heads: usize,
+    },
+    ReLU,
+    GeLU,
+    Tanh,
+    Sigmoid,
+    /// A residual connection around the layers: `f(x) + x`.
+    Residual(std::vec::Vec<LayerConfig>),
+    /// Repeats the layers `times` times, each repetition with its own parameters.
+    Repeat {
+        times: usize,
+        layers: std::vec::Vec<LayerConfig>,
+    },
+}
+
+/// Configuration of a [DynModel], where all sizes are known at runtime.
+///
+/// With the `serde` feature enabled, this can be serialized and deserialized, so models can be
+/// configured from files or command line arguments.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let (dim, depth, heads) = (16, 3, 4);
+/// let block = std::vec![
+///     LayerConfig::Residual(std::vec![LayerConfig::MultiHeadAttention { embed_dim: dim, num_heads: heads }]),
+///     LayerConfig::LayerNorm { dim },
+///     LayerConfig::Residual(std::vec![
+///         LayerConfig::Linear { inp: dim, out: 2 * dim },
+///         LayerConfig::ReLU,
+///         LayerConfig::Linear { inp: 2 * dim, out: dim },
+///     ]),
+///     LayerConfig::LayerNorm { dim },
+/// ];
+/// let config = ModelConfig {
+///     input_dim: 8,
+///     layers: std::vec![
+///         LayerConfig::Linear { inp: 8, out: dim },
+///         LayerConfig::Repeat { times: depth, layers: block },
+///     ],
+/// };
+/// assert_eq!(config.validate(), Ok(dim));
+/// let model: DynModel<Cpu> = config.build_on_device(&dev);
+/// let x: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(10, 8));
+/// let y = model.forward(x);
+/// assert_eq!(y.shape(), &(10, dim));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelConfig {
+    /// The number of features of the input.
+    pub input_dim: usize,
+    pub layers: std::vec::Vec<LayerConfig>,
+}
+
+/// An invalid [ModelConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// A layer expected `expected` input features, but the previous layer produced `found`.
+    DimMismatch { expected: usize, found: usize },
+    /// `embed_dim` is not divisible by `num_heads`.
+    InvalidNumHeads { embed_dim: usize, num_heads: usize },
+    /// The layers inside of a residual connection changed the number of features.
+    ResidualDimChanged { inp: usize, out: usize },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::DimMismatch { expected, found } => {
+                write!(
+                    f,
+                    "ConfigError::DimMismatch {{ expected: {expected}, found: {found} }}"
+                )
+            }
+            Self::InvalidNumHeads {
+                embed_dim,
+                num_heads,
+            } => write!(
+                f,
+                "ConfigError::InvalidNumHeads {{ embed_dim: {embed_dim}, num_heads: {num_heads} }}"
+            ),
+            Self::ResidualDimChanged { inp, out } => {
+                write!(
+                    f,
+                    "ConfigError::ResidualDimChanged {{ inp: {inp}, out: {out} }}"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+fn validate_layers(layers: &[LayerConfig], mut dim: usize) -> Result<usize, ConfigError> {
+    for layer in layers {
+        dim = match layer {
+            LayerConfig::Linear { inp, out } => {
+                check_dim(*inp, dim)?;
+                *out
+            }
+            LayerConfig::LayerNorm { dim: expected } => check_dim(*expected, dim)?,
+            LayerConfig::MultiHeadAttention {
+                embed_dim,
+                num_heads,
+            } => {
+                if *num_heads == 0 || embed_dim % num_heads != 0 {
+                    return Err(ConfigError::InvalidNumHeads {
+                        embed_dim: *embed_dim,
+                        num_heads: *num_heads,
+                    });
+                }
+                check_dim(*embed_dim, dim)?
+            }
+            LayerConfig::ReLU | LayerConfig::GeLU | LayerConfig::Tanh | LayerConfig::Sigmoid => dim,
+            LayerConfig::Residual(inner) => {
+                let out = validate_layers(inner, dim)?;
+                if out != dim {
+                    return Err(ConfigError::ResidualDimChanged { inp: dim, out });
+                }
+                dim
+            }
+            LayerConfig::Repeat { times, layers } => {
+                for _ in 0..*times {
+                    dim = validate_layers(layers, dim)?;
+                }
+                dim
+            }
+        };
+    }
+    Ok(dim)
+}
+
+fn check_dim(expected: usize, found: usize) -> Result<usize, ConfigError> {
+    if expected == found {
+        Ok(found)
+    } else {
+        Err(ConfigError::DimMismatch { expected, found })
+    }
+}
+
+impl ModelConfig {
+    /// Checks that the sizes of all layers are consistent, and returns the number of output features.
+    pub fn validate(&self) -> Result<usize, ConfigError> {
+        validate_layers(&self.layers, self.input_dim)
+    }
+
+    /// Builds a [DynModel] with freshly initialized parameters on `device`.
+    ///
+    /// **Panics** if the config is invalid. See [ModelConfig::validate].
+    pub fn build_on_device<D: Device<f32>>(&self, device: &D) -> DynModel<D> {
+        self.try_build_on_device(device).unwrap()
+    }
+
+    /// Fallible version of [ModelConfig::build_on_device].
+    ///
+    /// **Panics** if the config is invalid. See [ModelConfig::validate].
+    pub fn try_build_on_device<D: Device<f32>>(&self, device: &D) -> Result<DynModel<D>, D::Err> {
+        if let Err(e) = self.validate() {
+            panic!("Invalid ModelConfig: {e}");
+        }
+        DynModel::try_build(&self.layers, device)
+    }
+}
+
+/// A model composed at runtime from a [ModelConfig]. This is a sequence of [DynLayer]s,
+/// and acts on `(rows, features)` shaped tensors.
+///
+/// Unlike models defined with types, all shapes are checked at runtime.
+#[derive(Debug, Clone)]
+pub struct DynModel<D: Device<f32> = Cpu> {
+    pub layers: std::vec::Vec<DynLayer<D>>,
+}
+
+/// A single layer of a [DynModel]. See [LayerConfig].
+#[derive(Debug, Clone)]
+pub enum DynLayer<D: Device<f32> = Cpu> {
+    Linear(DynLinear<D>),
+    LayerNorm(DynLayerNorm<D>),
+    MultiHeadAttention(DynMultiHeadAttention<D>),
+    ReLU,
+    GeLU,
+    Tanh,
+    Sigmoid,
+    Residual(DynModel<D>),
+    /// Applies the layers one after another. Useful for replacing one layer with many.
+    Sequential(DynModel<D>),
+    LoraLinear(DynLoraLinear<D>),
+    Hook(DynHook),
+    /// Like [DynLayer::Hook], but the function is called with the gradient of the input
+    /// during backprop instead of the input.
+    GradHook(DynHook),
+}
+
+impl<D: Device<f32>> DynModel<D> {
+    fn try_build(layers: &[LayerConfig], device: &D) -> Result<Self, D::Err> {
+        let mut model = Self {
+            layers: std::vec::Vec::with_capacity(layers.len()),
+        };
+        for layer in layers {
+            match layer {
+                LayerConfig::Linear { inp, out } => model
+                    .layers
+                    .push(DynLayer::Linear(DynLinear::try_build(device, *inp, *out)?)),
+                LayerConfig::LayerNorm { dim } => model
+                    .layers
+                    .push(DynLayer::LayerNorm(DynLayerNorm::try_build(device, *dim)?)),
+                LayerConfig::MultiHeadAttention {
+                    embed_dim,
+                    num_heads,
+                } => model.layers.push(DynLayer::MultiHeadAttention(
+                    DynMultiHeadAttention::try_build(device, *embed_dim, *num_heads)?,
+                )),
+                LayerConfig::ReLU => model.layers.push(DynLayer::ReLU),
+                LayerConfig::GeLU => model.layers.push(DynLayer::GeLU),
+                LayerConfig::Tanh => model.layers.push(DynLayer::Tanh),
+                LayerConfig::Sigmoid => model.layers.push(DynLayer::Sigmoid),
+                LayerConfig::Residual(inner) => model
+                    .layers
+                    .push(DynLayer::Residual(DynModel::try_build(inner, device)?)),
+                LayerConfig::Repeat { times, layers } => {
+                    for _ in 0..*times {
+                        let repeat = DynModel::try_build(layers, device)?;
+                        model.layers.extend(repeat.layers);
+                    }
+                }
+            }
+        }
+        Ok(model)
+    }
+}
+
+/// A [super::Linear] where the number of input and output features are known at runtime.
+#[derive(Debug, Clone)]
+pub struct DynLinear<D: Device<f32> = Cpu> {
+    /// Transposed weight matrix, shape (out, inp)
+    pub weight: Tensor<(usize, usize), f32, D>,
+
+    /// Bias vector, shape (out, )
+    pub bias: Tensor<(usize,), f32, D>,
+}
+
+impl<D: Device<f32>> DynLinear<D> {
+    /// Builds with the same initialization as [super::Linear].
+    pub fn try_build(device: &D, inp: usize, out: usize) -> Result<Self, D::Err> {
+        let bound: f32 = 1.0 / (inp as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            weight: device.try_sample_like(&(out, inp), distr)?,
+            bias: device.try_sample_like(&(out,), distr)?,
+        })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / (self.weight.shape().1 as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.weight.try_fill_with_distr(distr)?;
+        self.bias.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLinear<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Result<Self::Output, D::Err> {
+        let w = self.weight.retaped::<T>().try_permute::<_, Axes2<1, 0>>()?;
+        let o = x.try_matmul(w)?;
+        let shape = *o.shape();
+        self.bias
+            .retaped::<T>()
+            .try_broadcast_like::<_, Axis<0>>(&shape)?
+            .try_add(o)
+    }
+}
+
+/// A [DynLinear] with a frozen base, and a trainable low rank update. See [super::LoraLinear].
+///
+/// Only [Self::lora_a] and [Self::lora_b] are updated by optimizers, [Self::base] is never modified.
+#[derive(Debug, Clone)]
+pub struct DynLoraLinear<D: Device<f32> = Cpu> {
+    /// The frozen layer being adapted.
+    pub base: DynLinear<D>,
+
+    /// Down projection, shape (rank, inp)
+    pub lora_a: Tensor<(usize, usize), f32, D>,
+
+    /// Up projection, shape (out, rank)
+    pub lora_b: Tensor<(usize, usize), f32, D>,
+
+    /// Multiplier of the update, usually `alpha / rank`.
+    pub scale: f32,
+}
+
+impl<D: Device<f32>> DynLoraLinear<D> {
+    /// Wraps an existing (e.g. pretrained) [DynLinear] layer, with a scale of `alpha / rank`.
+    pub fn from_linear(base: DynLinear<D>, rank: usize, alpha: f32) -> Self {
+        Self::try_from_linear(base, rank, alpha).unwrap()
+    }
+
+    /// Fallible version of [DynLoraLinear::from_linear()]
+    pub fn try_from_linear(base: DynLinear<D>, rank: usize, alpha: f32) -> Result<Self, D::Err> {
+        let device = base.weight.device.clone();
+        let (out, inp) = *base.weight.shape();
+        let bound: f32 = 1.0 / (inp as f32).sqrt();
+        let lora_a =
+            device.try_sample_like(&(rank, inp), rand_distr::Uniform::new(-bound, bound))?;
+        let lora_b = device.try_zeros_like(&(out, rank))?;
+        Ok(Self {
+            base,
+            lora_a,
+            lora_b,
+            scale: alpha / rank as f32,
+        })
+    }
+
+    /// Resets only the adapter, [Self::base] is left unchanged.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / (self.lora_a.shape().1 as f32).sqrt();
+        self.lora_a
+            .try_fill_with_distr(rand_distr::Uniform::new(-bound, bound))?;
+        self.lora_b.try_fill_with_zeros()?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLoraLinear<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Result<Self::Output, D::Err> {
+        let (x, tape) = x.split_tape();
+        let a = self.lora_a.retaped::<T>().try_permute::<_, Axes2<1, 0>>()?;
+        let b = self.lora_b.retaped::<T>().try_permute::<_, Axes2<1, 0>>()?;
+        let (delta, tape) = x
+            .clone()
+            .put_tape(tape)
+            .try_matmul(a)?
+            .try_matmul(b)?
+            .try_mul(self.scale)?
+            .split_tape();
+        self.base.try_forward(x.put_tape(tape))?.try_add(delta)
+    }
+}
+
+/// Calls a function with the shape & data of its input, and passes the input through
+/// unchanged. Useful for inspecting the activations inside of a [DynModel], see
+/// [DynModel::insert_layer()]. As a [DynLayer::GradHook], the function is called with the
+/// gradient of the input instead.
+///
+/// The data is copied from the device every forward, so this is slow.
+#[derive(Clone)]
+pub struct DynHook(pub std::sync::Arc<HookFn>);
+
+/// The function called by a [DynHook] with the shape & data of its input.
+pub type HookFn = dyn Fn((usize, usize), &[f32]) + Send + Sync;
+
+impl DynHook {
+    pub fn new<F: 'static + Fn((usize, usize), &[f32]) + Send + Sync>(f: F) -> Self {
+        Self(std::sync::Arc::new(f))
+    }
+}
+
+impl std::fmt::Debug for DynHook {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("DynHook")
+    }
+}
+
+/// A [super::LayerNorm1D] where the number of features is known at runtime.
+#[derive(Debug, Clone)]
+pub struct DynLayerNorm<D: Device<f32> = Cpu> {
+    pub gamma: Tensor<(usize,), f32, D>,
+    pub beta: Tensor<(usize,), f32, D>,
+    pub epsilon: f32,
+}
+
+impl<D: Device<f32>> DynLayerNorm<D> {
+    /// Fills [Self::gamma] with 1s and [Self::beta] with 0s and sets [Self::epsilon] to `1e-5`.
+    pub fn try_build(device: &D, dim: usize) -> Result<Self, D::Err> {
+        Ok(Self {
+            gamma: device.try_ones_like(&(dim,))?,
+            beta: device.try_zeros_like(&(dim,))?,
+            epsilon: 1e-5,
+        })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.gamma.try_fill_with_ones()?;
+        self.beta.try_fill_with_zeros()?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLayerNorm<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Result<Self::Output, D::Err> {
+        x.try_normalize::<Axis<1>>(self.epsilon)?
+            .try_affine::<Axis<0>>(self.gamma.retaped::<T>(), self.beta.retaped::<T>())
+    }
+}
+
+/// Multi-head self attention where the embedding dimension and number of heads are known
+/// at runtime. The rows of the input are treated as the sequence.
+///
+/// Has the same parameters as [super::MultiHeadAttention].
+#[derive(Debug, Clone)]
+pub struct DynMultiHeadAttention<D: Device<f32> = Cpu> {
+    pub w_q: DynLinear<D>,
+    pub w_k: DynLinear<D>,
+    pub w_v: DynLinear<D>,
+    pub w_o: DynLinear<D>,
+    pub num_heads: usize,
+}
+
+impl<D: Device<f32>> DynMultiHeadAttention<D> {
+    /// **Panics** if `embed_dim` is not divisible by `num_heads`.
+    pub fn try_build(device: &D, embed_dim: usize, num_heads: usize) -> Result<Self, D::Err> {
+        assert!(num_heads > 0);
+        assert_eq!(embed_dim % num_heads, 0);
+        Ok(Self {
+            w_q: DynLinear::try_build(device, embed_dim, embed_dim)?,
+            w_k: DynLinear::try_build(device, embed_dim, embed_dim)?,
+            w_v: DynLinear::try_build(device, embed_dim, embed_dim)?,
+            w_o: DynLinear::try_build(device, embed_dim, embed_dim)?,
+            num_heads,
+        })
+    }
+
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.w_q.try_reset_params()?;
+        self.w_k.try_reset_params()?;
+        self.w_v.try_reset_params()?;
+        self.w_o.try_reset_params()?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>>
+    for DynMultiHeadAttention<D>
+{
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Result<Self::Output, D::Err> {
+        let embed_dim = self.w_q.weight.shape().0;
+        let head_dim = embed_dim / self.num_heads;
+        let scalar: f32 = 1.0 / (head_dim as f32).sqrt();
+
+        let k = self.w_k.try_forward(x.retaped::<T>())?;
+        let v = self.w_v.try_forward(x.retaped::<T>())?;
+        let q = self.w_q.try_forward(x)?;
+        let (q, q_tape) = q.split_tape();
+        let (k, k_tape) = k.split_tape();
+        let (v, v_tape) = v.split_tape();
+        let dev = q.device.clone();
+
+        // instead of reshaping into heads, each head's features are selected with a matmul,
+        // and the outputs of every head are summed into their place in the output.
+        let mut tokens: Option<Tensor<(usize, usize), f32, D, T>> = None;
+        for h in 0..self.num_heads {
+            let mut sel_buf = std::vec![0.0; embed_dim * head_dim];
+            for i in 0..head_dim {
+                sel_buf[(h * head_dim + i) * head_dim + i] = 1.0;
+            }
+            let mut sel: Tensor<(usize, usize), f32, D> =
+                dev.try_zeros_like(&(embed_dim, head_dim))?;
+            sel.copy_from(&sel_buf);
+
+            let q_h = q.retaped::<T>().try_matmul(sel.clone())?;
+            let k_h = k.retaped::<T>().try_matmul(sel.clone())?;
+            let v_h = v.retaped::<T>().try_matmul(sel.clone())?;
+            let weights = q_h
+                .try_matmul(k_h.try_permute::<_, Axes2<1, 0>>()?)?
+                .try_mul(scalar)?;
+            let weights = weights.try_softmax::<Axis<1>>()?;
+            let head = weights
+                .try_matmul(v_h)?
+                .try_matmul(sel.try_permute::<_, Axes2<1, 0>>()?)?;
+            tokens = Some(match tokens {
+                Some(tokens) => tokens.try_add(head)?,
+                None => head,
+            });
+        }
+        let (tokens, tokens_tape) = tokens.unwrap().split_tape();
+
+        // the tapes that produced q, k, and v must run after the heads during backprop
+        let tape = q_tape.merge(k_tape).merge(v_tape).merge(tokens_tape);
+        self.w_o.try_forward(tokens.put_tape(tape))
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynLayer<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(usize, usize), f32, D, T>) -> Result<Self::Output, D::Err> {
+        match self {
+            Self::Linear(m) => m.try_forward(x),
+            Self::LayerNorm(m) => m.try_forward(x),
+            Self::MultiHeadAttention(m) => m.try_forward(x),
+            Self::ReLU => x.try_relu(),
+            Self::GeLU => x.try_gelu(),
+            Self::Tanh => x.try_tanh(),
+            Self::Sigmoid => x.try_sigmoid(),
+            // the tape of `x` must come first, so its operations run after `m`'s during backprop
+            Self::Residual(m) => x.with_empty_tape().try_add(m.try_forward(x)?),
+            Self::Sequential(m) => m.try_forward(x),
+            Self::LoraLinear(m) => m.try_forward(x),
+            Self::Hook(hook) => {
+                (hook.0)(*x.shape(), &x.as_vec());
+                Ok(x)
+            }
+            Self::GradHook(hook) => {
+                let (x, mut tape) = x.split_tape();
+                let hook = hook.clone();
+                let inp = x.clone();
+                tape.try_alloc_grad(&x)?;
+                // runs after every op that uses `x`, so the gradient is complete
+                tape.add_backward_op(move |grads| {
+                    let grad = inp.device.upgrade(grads.get(&inp).clone());
+                    (hook.0)(*grad.shape(), &grad.as_vec());
+                    Ok(())
+                });
+                Ok(x.put_tape(tape))
+            }
+        }
+    }
+}
+
+impl<D: Device<f32>, T: Tape<D>> Module<Tensor<(usize, usize), f32, D, T>> for DynModel<D> {
+    type Output = Tensor<(usize, usize), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        mut x: Tensor<(usize, usize), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        for layer in self.layers.iter() {
+            x = layer.try_forward(x)?;
+        }
+        Ok(x)
+    }
+}
+
+impl<D: Device<f32>> NonMutableModule for DynModel<D> {}
+
+impl<D: Device<f32>> DynModel<D> {
+    /// Resets all parameters, using the same initialization as when building.
+    pub fn reset_params(&mut self) {
+        self.try_reset_params().unwrap()
+    }
+
+    /// Fallible version of [DynModel::reset_params].
+    pub fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        for layer in self.layers.iter_mut() {
+            match layer {
+                DynLayer::Linear(m) => m.try_reset_params()?,
+                DynLayer::LayerNorm(m) => m.try_reset_params()?,
+                DynLayer::MultiHeadAttention(m) => m.try_reset_params()?,
+                DynLayer::Residual(m) | DynLayer::Sequential(m) => m.try_reset_params()?,
+                DynLayer::LoraLinear(m) => m.try_reset_params()?,
+                DynLayer::ReLU
+                | DynLayer::GeLU
+                | DynLayer::Tanh
+                | DynLayer::Sigmoid
+                | DynLayer::Hook(_)
+                | DynLayer::GradHook(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An invalid path to a layer of a [DynModel]. See [DynModel::layer()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// A part of the path is not a layer index.
+    NotAnIndex(std::string::String),
+    /// There are only `len` layers at `path`.
+    OutOfBounds {
+        path: std::string::String,
+        len: usize,
+    },
+    /// The layer at the path doesn't contain other layers.
+    NotAContainer(std::string::String),
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotAnIndex(part) => write!(f, "PathError::NotAnIndex({part:?})"),
+            Self::OutOfBounds { path, len } => {
+                write!(f, "PathError::OutOfBounds {{ path: {path:?}, len: {len} }}")
+            }
+            Self::NotAContainer(path) => write!(f, "PathError::NotAContainer({path:?})"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PathError {}
+
+/// # Model surgery
+///
+/// Layers are addressed by their path: the indices of the layer and of every
+/// [DynLayer::Residual] or [DynLayer::Sequential] containing it, separated by `.`. For example
+/// `"2.1"` is the second layer inside of the residual connection at index 2. These are the
+/// same as the prefixes used when saving with `SaveToNpz`.
+///
+/// This is how pretrained models are adapted for fine-tuning, or layers ablated. Since the
+/// type of a model defined with types can't change, this is only possible with a [DynModel].
+///
+/// # Examples
+/// Swapping every linear layer for a [DynLoraLinear], and inspecting the output of the first one:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let config = ModelConfig {
+///     input_dim: 4,
+///     layers: std::vec![
+///         LayerConfig::Linear { inp: 4, out: 8 },
+///         LayerConfig::Residual(std::vec![LayerConfig::Linear { inp: 8, out: 8 }, LayerConfig::ReLU]),
+///         LayerConfig::Linear { inp: 8, out: 2 },
+///     ],
+/// };
+/// let mut model: DynModel<Cpu> = config.build_on_device(&dev);
+/// assert_eq!(model.layer_paths(), ["0", "1", "1.0", "1.1", "2"]);
+///
+/// model.map_layers(|_, layer| match layer {
+///     DynLayer::Linear(l) => DynLayer::LoraLinear(DynLoraLinear::from_linear(l, 2, 4.0)),
+///     layer => layer,
+/// });
+/// assert!(matches!(model.layer("1.0"), Ok(DynLayer::LoraLinear(_))));
+///
+/// model.insert_layer("1", DynLayer::Hook(DynHook::new(|shape, _| assert_eq!(shape, (3, 8))))).unwrap();
+/// let _ = model.forward(dev.zeros_like(&(3, 4)));
+/// ```
+impl<D: Device<f32>> DynModel<D> {
+    /// The paths of every layer, in the order they are applied. Layers inside of a
+    /// container come right after the container.
+    pub fn layer_paths(&self) -> std::vec::Vec<std::string::String> {
+        let mut paths = std::vec::Vec::new();
+        self.collect_paths("", &mut paths);
+        paths
+    }
+
+    fn collect_paths(&self, prefix: &str, paths: &mut std::vec::Vec<std::string::String>) {
+        for (i, layer) in self.layers.iter().enumerate() {
+            let path = std::format!("{prefix}{i}");
+            if let DynLayer::Residual(m) | DynLayer::Sequential(m) = layer {
+                paths.push(path.clone());
+                m.collect_paths(&std::format!("{path}."), paths);
+            } else {
+                paths.push(path);
+            }
+        }
+    }
+
+    /// Returns the layer at `path`.
+    pub fn layer(&self, path: &str) -> Result<&DynLayer<D>, PathError> {
+        let (parts, last) = split_path(path)?;
+        let mut model = self;
+        for (i, &index) in parts.iter().enumerate() {
+            model = match model.layers.get(index) {
+                Some(DynLayer::Residual(m) | DynLayer::Sequential(m)) => m,
+                Some(_) => return Err(PathError::NotAContainer(join_path(&parts[..=i]))),
+                None => return Err(out_of_bounds(&parts[..=i], model.layers.len())),
+            };
+        }
+        let len = model.layers.len();
+        model
+            .layers
+            .get(last)
+            .ok_or_else(|| out_of_bounds(&[parts.as_slice(), &[last]].concat(), len))
+    }
+
+    /// Returns the layer at `path` mutably.
+    pub fn layer_mut(&mut self, path: &str) -> Result<&mut DynLayer<D>, PathError> {
+        let (parent, index) = self.parent_mut(path)?;
+        let len = parent.layers.len();
+        parent
+            .layers
+            .get_mut(index)
+            .ok_or_else(|| out_of_bounds_path(path, len))
+    }
+
+    /// Replaces the layer at `path` with `layer`, and returns the old one.
+    pub fn replace_layer(
+        &mut self,
+        path: &str,
+        layer: DynLayer<D>,
+    ) -> Result<DynLayer<D>, PathError> {
+        Ok(std::mem::replace(self.layer_mut(path)?, layer))
+    }
+
+    /// Replaces the layer at `path` with `f(layer)`, e.g. to wrap it in a [DynLayer::Residual].
+    pub fn wrap_layer<F: FnOnce(DynLayer<D>) -> DynLayer<D>>(
+        &mut self,
+        path: &str,
+        f: F,
+    ) -> Result<(), PathError> {
+        let slot = self.layer_mut(path)?;
+        let layer = std::mem::replace(slot, DynLayer::ReLU);
+        *slot = f(layer);
+        Ok(())
+    }
+
+    /// Inserts `layer` before the layer at `path`, shifting it and all layers after it.
+    /// The last index of `path` may be the number of layers, to append `layer`.
+    pub fn insert_layer(&mut self, path: &str, layer: DynLayer<D>) -> Result<(), PathError> {
+        let (parent, index) = self.parent_mut(path)?;
+        if index > parent.layers.len() {
+            return Err(out_of_bounds_path(path, parent.layers.len()));
+        }
+        parent.layers.insert(index, layer);
+        Ok(())
+    }
+
+    /// Removes the layer at `path`, shifting all layers after it.
+    pub fn remove_layer(&mut self, path: &str) -> Result<DynLayer<D>, PathError> {
+        let (parent, index) = self.parent_mut(path)?;
+        if index >= parent.layers.len() {
+            return Err(out_of_bounds_path(path, parent.layers.len()));
+        }
+        Ok(parent.layers.remove(index))
+    }
+
+    /// Removes every [DynLayer::Hook] and [DynLayer::GradHook], for example before saving,
+    /// since hooks shift the paths of the layers after them.
+    pub fn remove_hooks(&mut self) {
+        self.layers
+            .retain(|layer| !matches!(layer, DynLayer::Hook(_) | DynLayer::GradHook(_)));
+        for layer in self.layers.iter_mut() {
+            if let DynLayer::Residual(m) | DynLayer::Sequential(m) = layer {
+                m.remove_hooks();
+            }
+        }
+    }
+
+    /// Replaces every layer with `f(path, layer)`. Layers inside of a container are
+    /// visited before the container, so layers returned by `f` are never visited again.
+    pub fn map_layers<F: FnMut(&str, DynLayer<D>) -> DynLayer<D>>(&mut self, mut f: F) {
+        self.map_layers_with_prefix("", &mut f)
+    }
+
+    fn map_layers_with_prefix<F: FnMut(&str, DynLayer<D>) -> DynLayer<D>>(
+        &mut self,
+        prefix: &str,
+        f: &mut F,
+    ) {
+        for (i, slot) in self.layers.iter_mut().enumerate() {
+            let path = std::format!("{prefix}{i}");
+            if let DynLayer::Residual(m) | DynLayer::Sequential(m) = slot {
+                m.map_layers_with_prefix(&std::format!("{path}."), f);
+            }
+            let layer = std::mem::replace(slot, DynLayer::ReLU);
+            *slot = f(&path, layer);
+        }
+    }
+
+    /// The model containing the layer at `path`, and the index of the layer in it.
+    fn parent_mut(&mut self, path: &str) -> Result<(&mut Self, usize), PathError> {
+        let (parts, last) = split_path(path)?;
+        let mut model = self;
+        for (i, &index) in parts.iter().enumerate() {
+            let len = model.layers.len();
+            model = match model.layers.get_mut(index) {
+                Some(DynLayer::Residual(m) | DynLayer::Sequential(m)) => m,
+                Some(_) => return Err(PathError::NotAContainer(join_path(&parts[..=i]))),
+                None => return Err(out_of_bounds(&parts[..=i], len)),
+            };
+        }
+        Ok((model, last))
+    }
+}
+
+/// Splits `path` into the indices of its containers, and the index of the layer.
+fn split_path(path: &str) -> Result<(std::vec::Vec<usize>, usize), PathError> {
+    let mut parts = path
+        .split('.')
+        .map(|part| {
+            part.parse::<usize>()
+                .map_err(|_| PathError::NotAnIndex(part.into()))
+        })
+        .collect::<Result<std::vec::Vec<usize>, _>>()?;
+    let last = parts.pop().unwrap();
+    Ok((parts, last))
+}
+
+fn join_path(parts: &[usize]) -> std::string::String {
+    let parts: std::vec::Vec<_> = parts.iter().map(|i| std::format!("{i}")).collect();
+    parts.join(".")
+}
+
+fn out_of_bounds(parts: &[usize], len: usize) -> PathError {
+    PathError::OutOfBounds {
+        path: join_path(parts),
+        len,
+    }
+}
+
+fn out_of_bounds_path(path: &str, len: usize) -> PathError {
+    PathError::OutOfBounds {
+        path: path.into(),
+        len,
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynLinear<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)?;
+        self.bias.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynLoraLinear<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.base.update(&mut AsBuffers(updater), unused)?;
+        self.lora_a.update(updater, unused)?;
+        self.lora_b.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynLayerNorm<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.gamma.update(updater, unused)?;
+        self.beta.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynMultiHeadAttention<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.w_q.update(updater, unused)?;
+        self.w_k.update(updater, unused)?;
+        self.w_v.update(updater, unused)?;
+        self.w_o.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> GradientUpdate<D, f32> for DynModel<D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        for layer in self.layers.iter_mut() {
+            match layer {
+                DynLayer::Linear(m) => m.update(updater, unused)?,
+                DynLayer::LayerNorm(m) => m.update(updater, unused)?,
+                DynLayer::MultiHeadAttention(m) => m.update(updater, unused)?,
+                DynLayer::Residual(m) | DynLayer::Sequential(m) => m.update(updater, unused)?,
+                DynLayer::LoraLinear(m) => m.update(updater, unused)?,
+                DynLayer::ReLU
+                | DynLayer::GeLU
+                | DynLayer::Tanh
+                | DynLayer::Sigmoid
+                | DynLayer::Hook(_)
+                | DynLayer::GradHook(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for DynLinear<D1> {
+    type Output = DynLinear<D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        DynLinear {
+            weight: self.weight.to_device(device),
+            bias: self.bias.to_device(device),
+        }
+    }
+}
+
+impl<D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for DynLoraLinear<D1> {
+    type Output = DynLoraLinear<D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        DynLoraLinear {
+            base: self.base.to_device(device),
+            lora_a: self.lora_a.to_device(device),
+            lora_b: self.lora_b.to_device(device),
+            scale: self.scale,
+        }
+    }
+}
+
+impl<D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for DynLayerNorm<D1> {
+    type Output = DynLayerNorm<D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        DynLayerNorm {
+            gamma: self.gamma.to_device(device),
+            beta: self.beta.to_device(device),
+            epsilon: self.epsilon,
+        }
+    }
+}
+
+impl<D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for DynMultiHeadAttention<D1> {
+    type Output = DynMultiHeadAttention<D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        DynMultiHeadAttention {
+            w_q: self.w_q.to_device(device),
+            w_k: self.w_k.to_device(device),
+            w_v: self.w_v.to_device(device),
+            w_o: self.w_o.to_device(device),
+            num_heads: self.num_heads,
+        }
+    }
+}
+
+impl<D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for DynModel<D1> {
+    type Output = DynModel<D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| match layer {
+                DynLayer::Linear(m) => DynLayer::Linear(m.to_device(device)),
+                DynLayer::LayerNorm(m) => DynLayer::LayerNorm(m.to_device(device)),
+                DynLayer::MultiHeadAttention(m) => {
+                    DynLayer::MultiHeadAttention(m.to_device(device))
+                }
+                DynLayer::ReLU => DynLayer::ReLU,
+                DynLayer::GeLU => DynLayer::GeLU,
+                DynLayer::Tanh => DynLayer::Tanh,
+                DynLayer::Sigmoid => DynLayer::Sigmoid,
+                DynLayer::Residual(m) => DynLayer::Residual(m.to_device(device)),
+                DynLayer::Sequential(m) => DynLayer::Sequential(m.to_device(device)),
+                DynLayer::LoraLinear(m) => DynLayer::LoraLinear(m.to_device(device)),
+                DynLayer::Hook(hook) => DynLayer::Hook(hook.clone()),
+                DynLayer::GradHook(hook) => DynLayer::GradHook(hook.clone()),
+            })
+            .collect();
+        DynModel { layers }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, BuildOnDevice, Linear},
+        tests::*,
+    };
+
+    fn transformer_config(dim: usize, heads: usize, depth: usize) -> ModelConfig {
+        ModelConfig {
+            input_dim: 3,
+            layers: std::vec![
+                LayerConfig::Linear { inp: 3, out: dim },
+                LayerConfig::Repeat {
+                    times: depth,
+                    layers: std::vec![
+                        LayerConfig::Residual(std::vec![LayerConfig::MultiHeadAttention {
+                            embed_dim: dim,
+                            num_heads: heads,
+                        }]),
+                        LayerConfig::LayerNorm { dim },
+                        LayerConfig::Residual(std::vec![
+                            LayerConfig::Linear {
+                                inp: dim,
+                                out: 2 * dim
+                            },
+                            LayerConfig::ReLU,
+                            LayerConfig::Linear {
+                                inp: 2 * dim,
+                                out: dim
+                            },
+                        ]),
+                        LayerConfig::LayerNorm { dim },
+                    ],
+                },
+                LayerConfig::Linear { inp: dim, out: 1 },
+                LayerConfig::Sigmoid,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(transformer_config(8, 2, 3).validate(), Ok(1));
+        assert_eq!(
+            transformer_config(8, 3, 3).validate(),
+            Err(ConfigError::InvalidNumHeads {
+                embed_dim: 8,
+                num_heads: 3
+            })
+        );
+        let config = ModelConfig {
+            input_dim: 3,
+            layers: std::vec![
+                LayerConfig::Linear { inp: 3, out: 4 },
+                LayerConfig::Linear { inp: 5, out: 4 },
+            ],
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::DimMismatch {
+                expected: 5,
+                found: 4
+            })
+        );
+        let config = ModelConfig {
+            input_dim: 3,
+            layers: std::vec![LayerConfig::Residual(std::vec![LayerConfig::Linear {
+                inp: 3,
+                out: 4
+            }])],
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::ResidualDimChanged { inp: 3, out: 4 })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_build_invalid_config() {
+        let dev: TestDevice = Default::default();
+        let _: DynModel<_> = transformer_config(8, 3, 1).build_on_device(&dev);
+    }
+
+    #[test]
+    fn test_repeat_has_independent_params() {
+        let dev: TestDevice = Default::default();
+        let model = transformer_config(4, 2, 3).build_on_device(&dev);
+        assert_eq!(model.layers.len(), 2 + 3 * 4 + 1);
+        let (DynLayer::LayerNorm(a), DynLayer::LayerNorm(b)) = (&model.layers[2], &model.layers[6])
+        else {
+            panic!("expected layer norms");
+        };
+        assert_ne!(a.gamma.id, b.gamma.id);
+    }
+
+    #[test]
+    fn test_dyn_linear_matches_linear() {
+        let dev: TestDevice = Default::default();
+        let linear = Linear::<3, 2>::build_on_device(&dev);
+        let dyn_linear = DynLinear {
+            weight: dev.tensor_from_vec(linear.weight.as_vec(), (2, 3)),
+            bias: dev.tensor_from_vec(linear.bias.as_vec(), (2,)),
+        };
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+        let x_dyn = dev.tensor_from_vec(x.as_vec(), (4, 3));
+        let y = linear.forward(x.trace());
+        let y_dyn = dyn_linear.forward(x_dyn.trace());
+        assert_close(&y_dyn.as_vec(), &y.as_vec());
+
+        let g = y.exp().mean().backward();
+        let g_dyn = y_dyn.exp().mean().backward();
+        assert_close(&g_dyn.get(&x_dyn).as_vec(), &g.get(&x).as_vec());
+        assert_close(
+            &g_dyn.get(&dyn_linear.weight).as_vec(),
+            &g.get(&linear.weight).as_vec(),
+        );
+    }
+
+    #[test]
+    fn test_dyn_mha_forward() {
+        let dev: TestDevice = Default::default();
+        let (seq, dim, heads) = (3, 4, 2);
+        let mha = DynMultiHeadAttention::try_build(&dev, dim, heads).unwrap();
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(seq, dim), rand_distr::StandardNormal);
+        let y = mha.forward(x.clone()).as_vec();
+
+        let q = mha.w_q.forward(x.clone()).as_vec();
+        let k = mha.w_k.forward(x.clone()).as_vec();
+        let v = mha.w_v.forward(x).as_vec();
+        let head_dim = dim / heads;
+        let mut tokens = std::vec![0.0; seq * dim];
+        for h in 0..heads {
+            for i in 0..seq {
+                let mut weights = std::vec![0.0f32; seq];
+                for (j, w) in weights.iter_mut().enumerate() {
+                    for d in h * head_dim..(h + 1) * head_dim {
+                        *w += q[i * dim + d] * k[j * dim + d];
+                    }
+                    *w /= (head_dim as f32).sqrt();
+                }
+                let total: f32 = weights.iter().map(|w| w.exp()).sum();
+                for (j, w) in weights.iter().enumerate() {
+                    for d in h * head_dim..(h + 1) * head_dim {
+                        tokens[i * dim + d] += w.exp() / total * v[j * dim + d];
+                    }
+                }
+            }
+        }
+        let tokens = dev.tensor_from_vec(tokens, (seq, dim));
+        assert_close(&y, &mha.w_o.forward(tokens).as_vec());
+    }
+
+    #[test]
+    fn test_dyn_model_input_grads() {
+        let dev: TestDevice = Default::default();
+        let model = transformer_config(4, 2, 2).build_on_device(&dev);
+        let x_buf: std::vec::Vec<f32> = dev
+            .sample_like(&(2, 3), rand_distr::StandardNormal)
+            .as_vec();
+        let x = dev.tensor_from_vec(x_buf.clone(), (2, 3));
+        let g = model.forward(x.trace()).sum::<Rank0, _>().backward();
+        let g = g.get(&x).as_vec();
+
+        let eps = 1e-2;
+        for i in 0..x_buf.len() {
+            let mut buf = x_buf.clone();
+            buf[i] += eps;
+            let hi = model.forward(dev.tensor_from_vec(buf.clone(), (2, 3)));
+            buf[i] -= 2.0 * eps;
+            let lo = model.forward(dev.tensor_from_vec(buf, (2, 3)));
+            let numerical =
+                (hi.sum::<Rank0, _>().array() - lo.sum::<Rank0, _>().array()) / (2.0 * eps);
+            assert!((numerical - g[i]).abs() < 1e-2, "{numerical} vs {}", g[i]);
+        }
+    }
+
+    #[test]
+    fn test_dyn_model_try_forward_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let model = transformer_config(4, 2, 1).build_on_device(&dev);
+        assert!(model.try_forward(dev.zeros_like(&(2, 3))).is_ok());
+        let err = model.try_forward(dev.zeros_like(&(2, 5))).unwrap_err();
+        assert!(std::format!("{err:?}").contains("matmul"));
+    }
+
+    #[test]
+    fn test_dyn_model_update() {
+        let dev: TestDevice = Default::default();
+        let mut model = transformer_config(4, 2, 2).build_on_device(&dev);
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(5, 3), rand_distr::StandardNormal);
+        let y = model.forward(x.trace());
+        assert_eq!(y.shape(), &(5, 1));
+        let g = y.mean().backward();
+
+        let mut g = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+
+    fn mlp_config() -> ModelConfig {
+        ModelConfig {
+            input_dim: 3,
+            layers: std::vec![
+                LayerConfig::Linear { inp: 3, out: 4 },
+                LayerConfig::Residual(std::vec![
+                    LayerConfig::Linear { inp: 4, out: 4 },
+                    LayerConfig::Tanh,
+                ]),
+                LayerConfig::Linear { inp: 4, out: 2 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_layer_paths() {
+        let dev: TestDevice = Default::default();
+        let mut model = mlp_config().build_on_device(&dev);
+        assert_eq!(model.layer_paths(), ["0", "1", "1.0", "1.1", "2"]);
+        assert!(matches!(model.layer("1.1"), Ok(DynLayer::Tanh)));
+
+        let old = model.replace_layer("1.1", DynLayer::ReLU).unwrap();
+        assert!(matches!(old, DynLayer::Tanh));
+        assert!(matches!(model.layer("1.1"), Ok(DynLayer::ReLU)));
+
+        model
+            .wrap_layer("2", |l| {
+                DynLayer::Sequential(DynModel {
+                    layers: std::vec![l, DynLayer::Sigmoid],
+                })
+            })
+            .unwrap();
+        model.insert_layer("1.2", DynLayer::GeLU).unwrap();
+        assert_eq!(
+            model.layer_paths(),
+            ["0", "1", "1.0", "1.1", "1.2", "2", "2.0", "2.1"]
+        );
+        assert!(matches!(model.remove_layer("1.2"), Ok(DynLayer::GeLU)));
+        let y = model.forward(dev.sample_like(&(5, 3), rand_distr::StandardNormal));
+        assert!(y.as_vec().iter().all(|v| (0.0..=1.0).contains(v)));
+
+        assert_eq!(
+            model.layer("1.x").unwrap_err(),
+            PathError::NotAnIndex("x".into())
+        );
+        assert_eq!(
+            model.layer("1.2").unwrap_err(),
+            PathError::OutOfBounds {
+                path: "1.2".into(),
+                len: 2
+            }
+        );
+        assert_eq!(
+            model.layer_mut("0.0").unwrap_err(),
+            PathError::NotAContainer("0".into())
+        );
+        assert_eq!(
+            model.insert_layer("4.0", DynLayer::ReLU).unwrap_err(),
+            PathError::OutOfBounds {
+                path: "4".into(),
+                len: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_map_linear_to_lora() {
+        let dev: TestDevice = Default::default();
+        let mut model = mlp_config().build_on_device(&dev);
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(5, 3), rand_distr::StandardNormal);
+        let y = model.forward(x.clone()).as_vec();
+
+        let mut visited = std::vec::Vec::new();
+        model.map_layers(|path, layer| {
+            visited.push(std::string::String::from(path));
+            match layer {
+                DynLayer::Linear(l) => DynLayer::LoraLinear(DynLoraLinear::from_linear(l, 2, 4.0)),
+                layer => layer,
+            }
+        });
+        assert_eq!(visited, ["0", "1.0", "1.1", "1", "2"]);
+        assert!(matches!(model.layer("1.0"), Ok(DynLayer::LoraLinear(_))));
+
+        // lora_b starts at zero, so the output is unchanged
+        assert_close(&model.forward(x.clone()).as_vec(), &y);
+
+        let Ok(DynLayer::LoraLinear(lora)) = model.layer("2") else {
+            panic!("expected lora")
+        };
+        let (base, lora_b) = (lora.base.weight.as_vec(), lora.lora_b.as_vec());
+        let g = model.forward(x.trace()).exp().mean().backward();
+        let mut updater = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+
+        let mut sgd = crate::optim::Sgd::new(&model, Default::default());
+        let g = model.forward(x.trace()).exp().mean().backward();
+        sgd.update(&mut model, g).expect("");
+        let Ok(DynLayer::LoraLinear(lora)) = model.layer("2") else {
+            panic!("expected lora")
+        };
+        assert_eq!(lora.base.weight.as_vec(), base);
+        assert_ne!(lora.lora_b.as_vec(), lora_b);
+    }
+
+    #[test]
+    fn test_hook_sees_activations() {
+        let dev: TestDevice = Default::default();
+        let mut model = mlp_config().build_on_device(&dev);
+        let seen = std::sync::Arc::new(std::sync::Mutex::new(std::vec::Vec::new()));
+        let hook_seen = seen.clone();
+        let hook =
+            DynHook::new(move |shape, data| hook_seen.lock().unwrap().push((shape, data.to_vec())));
+        model.insert_layer("1.2", DynLayer::Hook(hook)).unwrap();
+
+        let x: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(5, 3), rand_distr::StandardNormal);
+        let y = model.forward(x.trace());
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].0, (5, 4));
+        assert!(seen[0].1.iter().all(|v| (-1.0..=1.0).contains(v)));
+
+        // hooks don't change the output or gradients
+        let g = y.mean().backward();
+        model.remove_layer("1.2").unwrap();
+        let g2 = model.forward(x.trace()).mean().backward();
+        assert_close(&g.get(&x).as_vec(), &g2.get(&x).as_vec());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_serde() {
+        fn assert_serde<T: serde::Serialize + for<'de> serde::Deserialize<'de>>() {}
+        assert_serde::<ModelConfig>();
+        assert_serde::<LayerConfig>();
+    }
+}
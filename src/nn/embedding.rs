@@ -36,9 +36,10 @@ impl<const VOCAB: usize, const DIM: usize, const SEQ: usize, D: Device<f32>, T:
     Module<Tensor<Rank1<SEQ>, usize, D, T>> for Embedding<VOCAB, DIM, D>
 {
     type Output = Tensor<Rank2<SEQ, DIM>, f32, D, T>;
-    fn forward(&self, input: Tensor<Rank1<SEQ>, usize, D, T>) -> Self::Output {
+    type Error = D::Err;
+    fn try_forward(&self, input: Tensor<Rank1<SEQ>, usize, D, T>) -> Result<Self::Output, D::Err> {
         let (input, tape) = input.split_tape();
-        self.weight.clone().put_tape(tape).gather(input)
+        self.weight.clone().put_tape(tape).try_gather(input)
     }
 }
 
@@ -52,9 +53,13 @@ impl<
     > Module<Tensor<Rank2<BATCH, SEQ>, usize, D, T>> for Embedding<VOCAB, DIM, D>
 {
     type Output = Tensor<Rank3<BATCH, SEQ, DIM>, f32, D, T>;
-    fn forward(&self, input: Tensor<Rank2<BATCH, SEQ>, usize, D, T>) -> Self::Output {
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<Rank2<BATCH, SEQ>, usize, D, T>,
+    ) -> Result<Self::Output, D::Err> {
         let (input, tape) = input.split_tape();
-        self.weight.clone().put_tape(tape).gather(input)
+        self.weight.clone().put_tape(tape).try_gather(input)
     }
 }
 
@@ -64,8 +69,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-    fn forward_mut(&mut self, input: T) -> Self::Output {
-        self.forward(input)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
     }
 }
 
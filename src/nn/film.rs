@@ -0,0 +1,224 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{
+    module::{BuildModule, Module, ModuleMut, ResetParams, ToDevice},
+    Linear,
+};
+
+/// Feature-wise Linear Modulation, as introduced in
+/// [FiLM: Visual Reasoning with a General Conditioning Layer](https://arxiv.org/abs/1709.07871).
+///
+/// Predicts a per-channel scale & shift from a conditioning vector, and applies them to
+/// a feature map: `x * scale(cond) + shift(cond)`, with the scale & shift broadcast along
+/// every axis of `x` except the channel (and batch) axis.
+///
+/// Takes a tuple `(x, cond)` as input, where `x` is either `(C, H, W)` with `cond` of shape `(M, )`,
+/// or `(B, C, H, W)` with `cond` of shape `(B, M)`. `x` & `cond` must have the same tape type,
+/// and gradients flow into both of them.
+///
+/// # Generics
+/// - `M` The size of the conditioning vector.
+/// - `C` The number of channels of the feature map.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = FiLM<5, 3>;
+/// let model = Model::build_on_device(&dev);
+/// let x: Tensor<Rank4<2, 3, 8, 8>, f32, _> = dev.sample_normal();
+/// let cond: Tensor<Rank2<2, 5>, f32, _> = dev.sample_normal();
+/// let _: Tensor<Rank4<2, 3, 8, 8>, f32, _> = model.forward((x, cond));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FiLM<const M: usize, const C: usize, D: Device<f32> = Cpu> {
+    /// Predicts the per-channel scale from the conditioning vector.
+    pub scale: Linear<M, C, D>,
+
+    /// Predicts the per-channel shift from the conditioning vector.
+    pub shift: Linear<M, C, D>,
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> GradientUpdate<D, f32> for FiLM<M, C, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.scale.update(updater, unused)?;
+        self.shift.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> BuildModule<D, f32> for FiLM<M, C, D> {
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            scale: BuildModule::try_build(device)?,
+            shift: BuildModule::try_build(device)?,
+        })
+    }
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> ResetParams<D, f32> for FiLM<M, C, D> {
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.scale.try_reset_params()?;
+        self.shift.try_reset_params()?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const C: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2>
+    for FiLM<M, C, D1>
+{
+    type Output = FiLM<M, C, D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        FiLM {
+            scale: self.scale.to_device(device),
+            shift: self.shift.to_device(device),
+        }
+    }
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> FiLM<M, C, D> {
+    /// generic forward, where `Ax` are the axes of `x` that the scale & shift are broadcast along
+    fn try_modulate<S, Cond: Shape, Ax: Axes, T: Tape<D>>(
+        &self,
+        x: Tensor<S, f32, D, T>,
+        cond: Tensor<Cond, f32, D, T>,
+    ) -> Result<Tensor<S, f32, D, T>, D::Err>
+    where
+        S: Shape + ReduceShape<Ax>,
+        Linear<M, C, D>: Module<Tensor<Cond, f32, D, T>, Output = Tensor<S::Reduced, f32, D, T>>,
+        Linear<M, C, D>: Module<Tensor<Cond, f32, D, T>, Error = D::Err>,
+    {
+        let (cond, tape) = cond.split_tape();
+        let (scale, tape) = self
+            .scale
+            .try_forward(cond.clone().put_tape(tape))?
+            .split_tape();
+        let shift = self.shift.try_forward(cond.put_tape(tape))?;
+        x.try_affine::<Ax>(scale.put_tape(Default::default()), shift)
+    }
+}
+
+impl<H: Dim, W: Dim, const M: usize, const C: usize, D: Device<f32>, T: Tape<D>>
+    Module<(
+        Tensor<(Const<C>, H, W), f32, D, T>,
+        Tensor<Rank1<M>, f32, D, T>,
+    )> for FiLM<M, C, D>
+{
+    type Output = Tensor<(Const<C>, H, W), f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(
+        &self,
+        (x, cond): (
+            Tensor<(Const<C>, H, W), f32, D, T>,
+            Tensor<Rank1<M>, f32, D, T>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        self.try_modulate::<_, _, Axes2<1, 2>, _>(x, cond)
+    }
+}
+
+impl<B: Dim, H: Dim, W: Dim, const M: usize, const C: usize, D: Device<f32>, T: Tape<D>>
+    Module<(
+        Tensor<(B, Const<C>, H, W), f32, D, T>,
+        Tensor<(B, Const<M>), f32, D, T>,
+    )> for FiLM<M, C, D>
+{
+    type Output = Tensor<(B, Const<C>, H, W), f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(
+        &self,
+        (x, cond): (
+            Tensor<(B, Const<C>, H, W), f32, D, T>,
+            Tensor<(B, Const<M>), f32, D, T>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        self.try_modulate::<_, _, Axes2<2, 3>, _>(x, cond)
+    }
+}
+
+impl<T, const M: usize, const C: usize, D: Device<f32>> ModuleMut<T> for FiLM<M, C, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, BuildOnDevice},
+        tests::*,
+    };
+
+    #[test]
+    fn test_film_matches_manual() {
+        let dev: TestDevice = Default::default();
+        let m = FiLM::<4, 3>::build_on_device(&dev);
+        let x: Tensor<Rank3<3, 2, 5>, f32, _> = dev.sample_normal();
+        let cond: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+
+        let y = m.forward((x.clone(), cond.clone()));
+        let scale = m.scale.forward(cond.clone());
+        let shift = m.shift.forward(cond);
+        let expected = x * scale.broadcast() + shift.broadcast();
+        assert_close(&y.array(), &expected.array());
+    }
+
+    #[test]
+    fn test_batched_film_matches_unbatched() {
+        let dev: TestDevice = Default::default();
+        let m = FiLM::<4, 3>::build_on_device(&dev);
+        let x: Tensor<Rank4<2, 3, 2, 5>, f32, _> = dev.sample_normal();
+        let cond: Tensor<Rank2<2, 4>, f32, _> = dev.sample_normal();
+
+        let y = m.forward((x.clone(), cond.clone())).array();
+        for (i, y_i) in y.iter().enumerate() {
+            let x_i = x.clone().select(dev.tensor(i));
+            let cond_i = cond.clone().select(dev.tensor(i));
+            assert_close(&m.forward((x_i, cond_i)).array(), y_i);
+        }
+    }
+
+    #[test]
+    fn test_film_gradients() {
+        let dev: TestDevice = Default::default();
+        let mut m = FiLM::<4, 3>::build_on_device(&dev);
+        let x: Tensor<Rank4<2, 3, 2, 5>, f32, _> = dev.sample_normal();
+        let cond: Tensor<Rank2<2, 4>, f32, _> = dev.sample_normal();
+
+        let y = m.forward_mut((x.trace(), cond.trace()));
+        let g = y.square().mean().backward();
+        assert_ne!(g.get(&x).array(), [[[[0.0; 5]; 2]; 3]; 2]);
+        assert_ne!(g.get(&cond).array(), [[0.0; 4]; 2]);
+
+        let mut sgd = crate::optim::Sgd::new(&m, Default::default());
+        let scale_weight = m.scale.weight.clone();
+        let shift_bias = m.shift.bias.clone();
+        sgd.update(&mut m, g).expect("");
+        assert_ne!(m.scale.weight.array(), scale_weight.array());
+        assert_ne!(m.shift.bias.array(), shift_bias.array());
+    }
+
+    #[test]
+    fn test_film_all_params_used() {
+        let dev: TestDevice = Default::default();
+        let mut m = FiLM::<4, 3>::build_on_device(&dev);
+        let x: Tensor<Rank3<3, 2, 2>, f32, _> = dev.sample_normal();
+        let cond: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let y = m.forward((x.trace(), cond.trace()));
+        let mut updater = SimpleUpdater(y.exp().mean().backward());
+        let mut unused = Default::default();
+        m.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+}
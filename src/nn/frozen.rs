@@ -0,0 +1,124 @@
+use crate::{optim::*, shapes::*, tensor_ops::Device};
+
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
+
+/// Freezes the parameters of `M`: they are visited as buffers by [GradientUpdate],
+/// so optimizers will not update them, and
+/// [ModuleMut::forward_mut()] uses [Module::forward()], so things like [super::BatchNorm2D]
+/// running statistics and [super::Dropout] are in inference mode.
+///
+/// Useful for fine-tuning only part of a model, e.g. with [super::Adapter] or [super::LoraLinear]:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Frozen<(Linear<5, 5>, ReLU)>, Adapter<5, 2>, Linear<5, 2>);
+/// let model = Model::build_on_device(&dev);
+/// let _: Tensor<Rank1<2>, f32, _> = model.forward(dev.zeros::<Rank1<5>>());
+/// ```
+///
+/// [ResetParams] does not reset the frozen parameters.
+#[derive(Debug, Clone, Default)]
+pub struct Frozen<M>(pub M);
+
+impl<D: Device<E>, E: Dtype, M: GradientUpdate<D, E>> GradientUpdate<D, E> for Frozen<M> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        // the parameters of `M` are visited as buffers, so they are never trained
+        self.0.update(&mut AsBuffers(updater), unused)
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M: BuildModule<D, E>> BuildModule<D, E> for Frozen<M> {
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self(BuildModule::try_build(device)?))
+    }
+}
+
+impl<D: Device<E>, E: Dtype, M> ResetParams<D, E> for Frozen<M> {
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        Ok(())
+    }
+}
+
+impl<M: ToDevice<D>, D> ToDevice<D> for Frozen<M> {
+    type Output = Frozen<M::Output>;
+    fn to_device(&self, device: &D) -> Self::Output {
+        Frozen(self.0.to_device(device))
+    }
+}
+
+impl<M: ToDtype<E>, E> ToDtype<E> for Frozen<M> {
+    type Output = Frozen<M::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        Frozen(self.0.to_dtype())
+    }
+}
+
+impl<T, M: Module<T>> Module<T> for Frozen<M> {
+    type Output = M::Output;
+    type Error = M::Error;
+    fn try_forward(&self, x: T) -> Result<Self::Output, M::Error> {
+        self.0.try_forward(x)
+    }
+}
+
+impl<T, M: Module<T>> ModuleMut<T> for Frozen<M> {
+    type Output = M::Output;
+    type Error = M::Error;
+    fn try_forward_mut(&mut self, x: T) -> Result<Self::Output, M::Error> {
+        self.0.try_forward(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{
+            tests::{CountingUpdater, SimpleUpdater},
+            BatchNorm2D, Linear,
+        },
+        tensor::*,
+        tests::TestDevice,
+    };
+
+    #[test]
+    fn test_frozen_not_updated() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Frozen<Linear<3, 3, _>>, Linear<3, 2, _>) = BuildModule::build(&dev);
+        let mut g: SimpleUpdater = Default::default();
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert_eq!(unused.ids.len(), 2);
+
+        let w = model.0 .0.weight.array();
+        model.reset_params();
+        assert_eq!(model.0 .0.weight.array(), w);
+    }
+
+    #[test]
+    fn test_frozen_params_are_buffers() {
+        let dev: TestDevice = Default::default();
+        let mut model: (
+            Frozen<(Linear<3, 3, _>, BatchNorm2D<3, _>)>,
+            Linear<3, 2, _>,
+        ) = BuildModule::build(&dev);
+        let mut counter: CountingUpdater = Default::default();
+        let mut unused = Default::default();
+        model.update(&mut counter, &mut unused).unwrap();
+        assert_eq!((counter.params, counter.buffers), (2, 6));
+        assert!(unused.is_empty());
+    }
+
+    #[test]
+    fn test_frozen_batchnorm_uses_inference() {
+        let dev: TestDevice = Default::default();
+        let mut bn: Frozen<BatchNorm2D<2, _>> = BuildModule::build(&dev);
+        let x: Tensor<Rank3<2, 3, 3>, f32, _> = dev.sample_normal();
+        let _ = bn.forward_mut(x);
+        assert_eq!(bn.0.running_mean.array(), [0.0; 2]);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
 
 /// A residual connection `R` around `F`: `F(x) + R(x)`,
 /// as introduced in [Deep Residual Learning for Image Recognition](https://arxiv.org/abs/1512.03385).
@@ -59,6 +59,16 @@ impl<D: Device<E>, E: Dtype, F: ResetParams<D, E>, R: ResetParams<D, E>> ResetPa
     }
 }
 
+impl<E, F: ToDtype<E>, R: ToDtype<E>> ToDtype<E> for GeneralizedResidual<F, R> {
+    type Output = GeneralizedResidual<F::Output, R::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        GeneralizedResidual {
+            f: self.f.to_dtype(),
+            r: self.r.to_dtype(),
+        }
+    }
+}
+
 impl<D, F: ToDevice<D>, R: ToDevice<D>> ToDevice<D> for GeneralizedResidual<F, R> {
     type Output = GeneralizedResidual<F::Output, R::Output>;
     fn to_device(&self, device: &D) -> Self::Output {
@@ -69,25 +79,31 @@ impl<D, F: ToDevice<D>, R: ToDevice<D>> ToDevice<D> for GeneralizedResidual<F, R
     }
 }
 
-impl<T: SplitTape, F: Module<T>, R: Module<T, Output = F::Output>> Module<T>
+impl<T: SplitTape, F: Module<T>, R: Module<T, Output = F::Output, Error = F::Error>> Module<T>
     for GeneralizedResidual<F, R>
 where
-    F::Output: std::ops::Add<F::Output>,
+    F::Output: TryAdd<F::Output> + HasErr<Err = F::Error>,
 {
-    type Output = <F::Output as std::ops::Add<F::Output>>::Output;
-    fn forward(&self, x: T) -> Self::Output {
-        self.f.forward(x.with_empty_tape()) + self.r.forward(x)
+    type Output = F::Output;
+    type Error = F::Error;
+    fn try_forward(&self, x: T) -> Result<Self::Output, F::Error> {
+        self.f
+            .try_forward(x.with_empty_tape())?
+            .try_add(self.r.try_forward(x)?)
     }
 }
 
-impl<T: SplitTape, F: ModuleMut<T>, R: ModuleMut<T, Output = F::Output>> ModuleMut<T>
-    for GeneralizedResidual<F, R>
+impl<T: SplitTape, F: ModuleMut<T>, R: ModuleMut<T, Output = F::Output, Error = F::Error>>
+    ModuleMut<T> for GeneralizedResidual<F, R>
 where
-    F::Output: std::ops::Add<F::Output>,
+    F::Output: TryAdd<F::Output> + HasErr<Err = F::Error>,
 {
-    type Output = <F::Output as std::ops::Add<F::Output>>::Output;
-    fn forward_mut(&mut self, x: T) -> Self::Output {
-        self.f.forward_mut(x.with_empty_tape()) + self.r.forward_mut(x)
+    type Output = F::Output;
+    type Error = F::Error;
+    fn try_forward_mut(&mut self, x: T) -> Result<Self::Output, F::Error> {
+        self.f
+            .try_forward_mut(x.with_empty_tape())?
+            .try_add(self.r.try_forward_mut(x)?)
     }
 }
 
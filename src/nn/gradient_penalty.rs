@@ -0,0 +1,195 @@
+use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*};
+
+/// A penalty on the norm of the gradient of `f` with respect to its input, for training
+/// GANs or other models that should be Lipschitz: `mean((|∇f(x[b])| - target_norm)^2)`,
+/// where `b` is the first axis of `x`.
+///
+/// **This is an approximation**: `|∇f|` is a central difference of `f` along the direction
+/// `g / |g|` of the input gradient `g`, so `f` is evaluated three times.
+///
+/// - `target_norm = 0.0` is the R1 penalty from
+///   [Which Training Methods for GANs do actually Converge?](https://arxiv.org/abs/1801.04406),
+///   usually scaled by `gamma / 2`, with `x` the real samples.
+/// - `target_norm = 1.0` is the penalty of [WGAN-GP](https://arxiv.org/abs/1704.00028),
+///   usually scaled by `10.0`, with `x` random interpolations of real and fake samples.
+///
+/// `f` runs the model, and must return one output per item of the batch, treating each
+/// item independently (e.g. no batch norm). The result has a tape, so it can be added to
+/// the model's loss.
+///
+/// Differentiating a gradient norm usually needs a second backward pass through the
+/// backward pass, which the tape doesn't support. Instead, the exact input gradient `g`
+/// is computed with a normal backward pass, and the norm is the derivative of `f` along
+/// the unit direction `u = g / |g|`, approximated by the central difference
+/// `(f(x + εu) - f(x - εu)) / 2ε`. Since `∂|g|/∂θ = u · ∂g/∂θ`, the gradient of the
+/// penalty with respect to the parameters is also right up to `O(ε^2)`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let disc: (Linear<2, 16>, Tanh, Linear<16, 1>) = BuildModule::build(&dev);
+/// let real: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+/// let fake: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+/// let alpha: Tensor<Rank1<8>, f32, _> = dev.sample_uniform();
+/// let alpha = alpha.broadcast::<Rank2<8, 2>, _>();
+/// let x = real * alpha.clone() + fake * (alpha.negate() + 1.0);
+/// let penalty = gradient_penalty(|x| disc.forward(x), &x, 1.0, 1e-2);
+/// let grads = (penalty * 10.0).backward();
+/// ```
+///
+/// **Panics** if the output of `f` doesn't have one element per item of the batch.
+pub fn gradient_penalty<S: Shape, L: Shape, D: Device<f32>, F>(
+    f: F,
+    x: &Tensor<S, f32, D>,
+    target_norm: f32,
+    epsilon: f32,
+) -> Tensor<Rank0, f32, D, OwnedTape<D>>
+where
+    F: FnMut(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<L, f32, D, OwnedTape<D>>,
+{
+    try_gradient_penalty(f, x, target_norm, epsilon).unwrap()
+}
+
+/// Fallible version of [gradient_penalty()]
+pub fn try_gradient_penalty<S: Shape, L: Shape, D: Device<f32>, F>(
+    mut f: F,
+    x: &Tensor<S, f32, D>,
+    target_norm: f32,
+    epsilon: f32,
+) -> Result<Tensor<Rank0, f32, D, OwnedTape<D>>, D::Err>
+where
+    F: FnMut(Tensor<S, f32, D, OwnedTape<D>>) -> Tensor<L, f32, D, OwnedTape<D>>,
+{
+    assert!(S::NUM_DIMS > 0, "gradient_penalty: x needs a batch axis");
+    let batch = x.shape().concrete()[0];
+
+    let grads = f(x.trace()).try_sum()?.try_backward()?;
+    let g = x.device.upgrade(grads.get(x).clone());
+    let mut buf = g.as_vec();
+    if let Some(row_len) = buf.len().checked_div(batch).filter(|&n| n > 0) {
+        for row in buf.chunks_mut(row_len) {
+            let norm = row.iter().map(|v| v * v).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                row.iter_mut().for_each(|v| *v /= norm);
+            }
+        }
+    }
+    let mut u = x.device.try_zeros_like(x.shape())?;
+    u.copy_from(&buf);
+    let u = u.try_mul(epsilon)?;
+
+    let f_plus = f(x.clone().try_add(u.clone())?.traced());
+    let f_minus = f(x.clone().try_sub(u)?.traced());
+    assert_eq!(
+        f_plus.shape().num_elements(),
+        batch,
+        "gradient_penalty: f must have one output per item of the batch"
+    );
+    let norm = f_plus.try_sub(f_minus)?.try_div(2.0 * epsilon)?;
+    norm.try_sub(target_norm)?.try_square()?.try_mean()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, tests::*};
+
+    #[test]
+    fn test_gradient_penalty_of_linear() {
+        let dev: TestDevice = Default::default();
+        let mut model: Linear<3, 1, _> = BuildModule::build(&dev);
+        model.weight = dev.tensor([[1.0, -2.0, 2.0]]);
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+
+        // |∇f| is 3 everywhere
+        let r1 = gradient_penalty(|x| model.forward(x), &x, 0.0, 1e-2);
+        assert!((r1.array() - 9.0).abs() < 1e-3);
+        let gp = gradient_penalty(|x| model.forward(x), &x, 1.0, 1e-2);
+        assert!((gp.array() - 4.0).abs() < 1e-3);
+
+        // d/dw (|w| - 1)^2 = 2 (|w| - 1) w / |w|
+        let g = gp.backward();
+        let expected = [4.0 / 3.0, -8.0 / 3.0, 8.0 / 3.0];
+        for (g, e) in g.get(&model.weight).array()[0].iter().zip(expected) {
+            assert!((g - e).abs() < 1e-3, "{g} vs {e}");
+        }
+        assert!(g.get(&model.bias).array()[0].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_gradient_penalty_per_item() {
+        let dev: TestDevice = Default::default();
+        // f(x) = sum(x^2), so |∇f(x)| = 2|x|
+        let x = dev.tensor([[3.0, 4.0], [0.0, 1.0]]);
+        let f = |x: Tensor<Rank2<2, 2>, f32, _, _>| x.square().sum::<Rank1<2>, Axis<1>>();
+        let r1 = gradient_penalty(f, &x, 0.0, 1e-2);
+        assert!((r1.array() - (100.0 + 4.0) / 2.0).abs() < 1e-2);
+        let gp = gradient_penalty(f, &x, 1.0, 1e-2);
+        assert!((gp.array() - (81.0 + 1.0) / 2.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_gradient_penalty_matches_finite_differences() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<2, 4, _>, Tanh, Linear<4, 1, _>) = BuildModule::build(&dev);
+        let x: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+        let penalty = |m: &(Linear<2, 4, _>, Tanh, Linear<4, 1, _>)| {
+            gradient_penalty(|x| m.forward(x), &x, 1.0, 1e-2)
+        };
+        let g = penalty(&model).backward();
+        let g_w = g.get(&model.0.weight).array();
+
+        let h = 1e-2;
+        for i in 0..4 {
+            for j in 0..2 {
+                let mut plus = model.clone();
+                let mut minus = model.clone();
+                let mut w = model.0.weight.array();
+                w[i][j] += h;
+                plus.0.weight = dev.tensor(w);
+                w[i][j] -= 2.0 * h;
+                minus.0.weight = dev.tensor(w);
+                let fd = (penalty(&plus).array() - penalty(&minus).array()) / (2.0 * h);
+                assert!((g_w[i][j] - fd).abs() < 2e-2, "{} vs {fd}", g_w[i][j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_r1_matches_analytic() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<3, 5, _>, Tanh, Linear<5, 1, _>) = BuildModule::build(&dev);
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let r1 = gradient_penalty(|x| model.forward(x), &x, 0.0, 1e-2).array();
+
+        // ∇f(x) = W1^T (w2 * (1 - tanh(W1 x + b1)^2))
+        let w1 = model.0.weight.array();
+        let b1 = model.0.bias.array();
+        let w2 = model.2.weight.array()[0];
+        let mut expected = 0.0;
+        for x in x.array() {
+            let mut grad = [0.0; 3];
+            for h in 0..5 {
+                let z: f32 = b1[h] + (0..3).map(|i| w1[h][i] * x[i]).sum::<f32>();
+                let d = w2[h] * (1.0 - z.tanh().powi(2));
+                for i in 0..3 {
+                    grad[i] += w1[h][i] * d;
+                }
+            }
+            expected += grad.iter().map(|g| g * g).sum::<f32>() / 4.0;
+        }
+        assert!(
+            (r1 - expected).abs() < 1e-3 * expected.max(1.0),
+            "{r1} vs {expected}"
+        );
+    }
+
+    #[test]
+    #[should_panic = "gradient_penalty: f must have one output per item of the batch"]
+    fn test_gradient_penalty_wrong_output() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<3, 2, _> = BuildModule::build(&dev);
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let _ = gradient_penalty(|x| model.forward(x), &x, 0.0, 1e-2);
+    }
+}
@@ -1,6 +1,9 @@
 use crate::{optim::*, shapes::*, tensor_ops::*};
 
-use super::module::{BuildModule, Module, ModuleMut, OnDevice, ResetParams, ToDevice};
+use super::module::{
+    BuildModule, FunctionalModule, Module, ModuleMut, OnDevice, OnDtype, ResetParams, ToDevice,
+    ToDtype,
+};
 
 macro_rules! tuple_impls {
     ([$($name:ident),+] [$($idx:tt),+], $last:ident, [$($rev_tail:ident),+]) => {
@@ -37,6 +40,13 @@ macro_rules! tuple_impls {
             }
         }
 
+        impl<$($name: ToDtype<E>,)+ E> ToDtype<E> for ($($name,)+) {
+            type Output = ($(OnDtype<$name, E>,)+);
+            fn to_dtype(&self) -> Self::Output {
+                ($(self.$idx.to_dtype()),+)
+            }
+        }
+
         /*This macro expands like this for a 4-tuple:
 
         impl<
@@ -46,49 +56,74 @@ macro_rules! tuple_impls {
             D:
 
             // `$(Module::<$rev_tail ::Output>, $rev_tail: )+`
-            Module<C ::Output>, C:
-            Module<B ::Output>, B:
-            Module<A ::Output>, A:
+            Module<C ::Output, Error = M1::Error>, C:
+            Module<B ::Output, Error = M1::Error>, B:
+            Module<A ::Output, Error = M1::Error>, A:
 
             Module<Input>
         > Module<Input> for (A, B, C, D) {
             type Output = D::Output;
-            fn forward(&self, x: Input) -> Self::Output {
-                let x = self.0.forward(x);
-                let x = self.1.forward(x);
-                let x = self.2.forward(x);
-                let x = self.3.forward(x);
-                x
+            type Error = A::Error;
+            fn try_forward(&self, x: Input) -> Result<Self::Output, Self::Error> {
+                let x = self.0.try_forward(x)?;
+                let x = self.1.try_forward(x)?;
+                let x = self.2.try_forward(x)?;
+                let x = self.3.try_forward(x)?;
+                Ok(x)
             }
         }
         */
         impl<
             Input,
             $last:
-            $(Module::<$rev_tail ::Output>, $rev_tail: )+
+            $(Module::<$rev_tail ::Output, Error = M1::Error>, $rev_tail: )+
             Module<Input>
         > Module<Input> for ($($name,)+) {
             type Output = $last ::Output;
+            type Error = M1::Error;
 
             /// Calls forward sequentially on each module in the tuple.
-            fn forward(&self, x: Input) -> Self::Output {
-                $(let x = self.$idx.forward(x);)+
-                x
+            fn try_forward(&self, x: Input) -> Result<Self::Output, Self::Error> {
+                $(let x = self.$idx.try_forward(x)?;)+
+                Ok(x)
             }
         }
 
         impl<
             Input,
             $last:
-            $(ModuleMut::<$rev_tail ::Output>, $rev_tail: )+
+            $(ModuleMut::<$rev_tail ::Output, Error = M1::Error>, $rev_tail: )+
             ModuleMut<Input>
         > ModuleMut<Input> for ($($name,)+) {
             type Output = $last ::Output;
+            type Error = M1::Error;
 
             /// Calls forward sequentially on each module in the tuple.
-            fn forward_mut(&mut self, x: Input) -> Self::Output {
-                $(let x = self.$idx.forward_mut(x);)+
-                x
+            fn try_forward_mut(&mut self, x: Input) -> Result<Self::Output, Self::Error> {
+                $(let x = self.$idx.try_forward_mut(x)?;)+
+                Ok(x)
+            }
+        }
+    };
+}
+
+/// Each entry is `(module, params, input, output)`, where the input of each
+/// module is the output of the previous one.
+macro_rules! functional_tuple_impls {
+    ([$(($name:ident, $param:ident, $inp:ident, $out:ident)),+] [$($idx:tt),+], $last:ident) => {
+        impl<Input, Err: std::fmt::Debug, $($name, $param, $out,)+> FunctionalModule<($($param,)+), Input>
+            for ($($name,)+)
+        where
+            $($name: FunctionalModule<$param, $inp, Output = $out, Error = Err>,)+
+        {
+            type Output = $last;
+            type Error = Err;
+
+            /// Calls forward_with sequentially on each module in the tuple, with its
+            /// corresponding element of `params`.
+            fn try_forward_with(&self, params: ($($param,)+), x: Input) -> Result<Self::Output, Err> {
+                $(let x = self.$idx.try_forward_with(params.$idx, x)?;)+
+                Ok(x)
             }
         }
     };
@@ -100,6 +135,47 @@ tuple_impls!([M1, M2, M3, M4] [0, 1, 2, 3], M4, [M3, M2, M1]);
 tuple_impls!([M1, M2, M3, M4, M5] [0, 1, 2, 3, 4], M5, [M4, M3, M2, M1]);
 tuple_impls!([M1, M2, M3, M4, M5, M6] [0, 1, 2, 3, 4, 5], M6, [M5, M4, M3, M2, M1]);
 
+functional_tuple_impls!(
+    [(M1, P1, Input, O1),
+     (M2, P2, O1, O2)]
+    [0, 1],
+    O2
+);
+functional_tuple_impls!(
+    [(M1, P1, Input, O1),
+     (M2, P2, O1, O2),
+     (M3, P3, O2, O3)]
+    [0, 1, 2],
+    O3
+);
+functional_tuple_impls!(
+    [(M1, P1, Input, O1),
+     (M2, P2, O1, O2),
+     (M3, P3, O2, O3),
+     (M4, P4, O3, O4)]
+    [0, 1, 2, 3],
+    O4
+);
+functional_tuple_impls!(
+    [(M1, P1, Input, O1),
+     (M2, P2, O1, O2),
+     (M3, P3, O2, O3),
+     (M4, P4, O3, O4),
+     (M5, P5, O4, O5)]
+    [0, 1, 2, 3, 4],
+    O5
+);
+functional_tuple_impls!(
+    [(M1, P1, Input, O1),
+     (M2, P2, O1, O2),
+     (M3, P3, O2, O3),
+     (M4, P4, O3, O4),
+     (M5, P5, O4, O5),
+     (M6, P6, O5, O6)]
+    [0, 1, 2, 3, 4, 5],
+    O6
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,12 +236,24 @@ mod tests {
     impl<const I: usize, const N: usize> ZeroSizedModule for SetTo1<I, N> {}
     impl<const I: usize, const N: usize> Module<Tensor<Rank1<N>, f32, Cpu>> for SetTo1<I, N> {
         type Output = Tensor<Rank1<N>, f32, Cpu>;
-        fn forward(&self, mut input: Tensor<Rank1<N>, f32, Cpu>) -> Self::Output {
+        type Error = <Cpu as HasErr>::Err;
+        fn try_forward(
+            &self,
+            mut input: Tensor<Rank1<N>, f32, Cpu>,
+        ) -> Result<Self::Output, Self::Error> {
             std::sync::Arc::make_mut(&mut input.storage.data)[I] = 1.0;
-            input
+            Ok(input)
         }
     }
 
+    #[test]
+    fn test_tuple_to_dtype() {
+        let dev: TestDevice = Default::default();
+        let m = (dev.tensor([1.0f32, -2.0]), ReLU, Residual(Tanh));
+        let m64: (Tensor<Rank1<2>, f64, _>, ReLU, Residual<Tanh>) = ToDtype::<f64>::to_dtype(&m);
+        assert_eq!(m64.0.array(), [1.0, -2.0]);
+    }
+
     #[test]
     fn test_set_to_1() {
         let dev: Cpu = Default::default();
@@ -293,4 +381,20 @@ mod tests {
         model.update(&mut g, &mut unused).unwrap();
         assert!(unused.is_empty());
     }
+
+    #[test]
+    fn test_tuple_forward_with() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<2, 3, _>, ReLU, Linear<3, 4, _>) = BuildModule::build(&dev);
+        let other: (Linear<2, 3, _>, ReLU, Linear<3, 4, _>) = BuildModule::build(&dev);
+        let x: Tensor<Rank2<5, 2>, f32, _> = dev.sample_normal();
+
+        let params = (
+            (other.0.weight.clone(), other.0.bias.clone()),
+            (),
+            (other.2.weight.clone(), other.2.bias.clone()),
+        );
+        let y = model.forward_with(params, x.clone());
+        assert_eq!(y.array(), other.forward(x).array());
+    }
 }
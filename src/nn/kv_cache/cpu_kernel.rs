@@ -0,0 +1,148 @@
+use crate::{
+    shapes::Shape,
+    tensor::{
+        cpu::{Cpu, StridedArray},
+        AsVec,
+    },
+};
+
+use super::{KvCacheFormat, KvCacheKernel, KvCacheRows};
+use std::{sync::Arc, vec, vec::Vec};
+
+fn flat<E>(data: Vec<E>) -> StridedArray<(usize,), E> {
+    StridedArray {
+        shape: (data.len(),),
+        data: Arc::new(data),
+        strides: [1],
+    }
+}
+
+impl KvCacheKernel for Cpu {
+    fn alloc_rows(
+        &self,
+        format: KvCacheFormat,
+        batch: usize,
+        capacity: usize,
+        dim: usize,
+    ) -> Result<KvCacheRows<Self>, Self::Err> {
+        let (exact, quantized, scales) = match format {
+            KvCacheFormat::F32 => (batch * capacity * dim, 0, 0),
+            _ => (0, batch * capacity * dim, batch * capacity),
+        };
+        Ok(KvCacheRows {
+            format,
+            batch,
+            capacity,
+            dim,
+            exact: flat(vec![0.0; exact]),
+            quantized: flat(vec![0; quantized]),
+            scales: flat(vec![0.0; scales]),
+        })
+    }
+
+    fn copy_rows(
+        &self,
+        len: usize,
+        src: &KvCacheRows<Self>,
+        dst: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err> {
+        let dim = src.dim;
+        let exact = Arc::make_mut(&mut dst.exact.data);
+        let quantized = Arc::make_mut(&mut dst.quantized.data);
+        let scales = Arc::make_mut(&mut dst.scales.data);
+        for b in 0..src.batch {
+            let i_src = b * src.capacity;
+            let i_dst = b * dst.capacity;
+            if src.format == KvCacheFormat::F32 {
+                exact[i_dst * dim..(i_dst + len) * dim]
+                    .copy_from_slice(&src.exact.data[i_src * dim..(i_src + len) * dim]);
+            } else {
+                quantized[i_dst * dim..(i_dst + len) * dim]
+                    .copy_from_slice(&src.quantized.data[i_src * dim..(i_src + len) * dim]);
+                scales[i_dst..i_dst + len].copy_from_slice(&src.scales.data[i_src..i_src + len]);
+            }
+        }
+        Ok(())
+    }
+
+    fn write_rows<S: Shape>(
+        &self,
+        start: usize,
+        src: &Self::Storage<S, f32>,
+        rows: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err> {
+        let (format, capacity, dim) = (rows.format, rows.capacity, rows.dim);
+        let tokens = src.shape.concrete()[1];
+        let buf = src.as_vec();
+        for (i, row) in buf.chunks(dim).enumerate() {
+            let (b, t) = (i / tokens, i % tokens);
+            let i_dst = b * capacity + start + t;
+            if format == KvCacheFormat::F32 {
+                Arc::make_mut(&mut rows.exact.data)[i_dst * dim..(i_dst + 1) * dim]
+                    .copy_from_slice(row);
+                continue;
+            }
+            let amax = row.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+            let scale = if amax > 0.0 {
+                amax / format.max_level()
+            } else {
+                1.0
+            };
+            Arc::make_mut(&mut rows.scales.data)[i_dst] = scale;
+            let quantized = Arc::make_mut(&mut rows.quantized.data);
+            for (q, x) in quantized[i_dst * dim..(i_dst + 1) * dim]
+                .iter_mut()
+                .zip(row.iter())
+            {
+                *q = format.encode(x / scale);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_rows<S: Shape>(
+        &self,
+        shape: S,
+        rows: &KvCacheRows<Self>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let len = shape.concrete()[1];
+        let (format, dim) = (rows.format, rows.dim);
+        let mut data = Vec::with_capacity(shape.num_elements());
+        for b in 0..rows.batch {
+            let i_src = b * rows.capacity;
+            if format == KvCacheFormat::F32 {
+                data.extend_from_slice(&rows.exact.data[i_src * dim..(i_src + len) * dim]);
+                continue;
+            }
+            let quantized = rows.quantized.data[i_src * dim..(i_src + len) * dim].chunks(dim);
+            for (row, scale) in quantized.zip(rows.scales.data[i_src..i_src + len].iter()) {
+                data.extend(row.iter().map(|&q| format.decode(q) * scale));
+            }
+        }
+        Ok(StridedArray {
+            data: Arc::new(data),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn causal_mask<S: Shape>(&self, shape: S) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let [queries, len] = [shape.concrete()[0], shape.concrete()[1]];
+        let offset = len - queries;
+        let mut data = Vec::with_capacity(queries * len);
+        for i in 0..queries {
+            data.extend((0..len).map(|j| {
+                if j <= i + offset {
+                    0.0
+                } else {
+                    f32::NEG_INFINITY
+                }
+            }));
+        }
+        Ok(StridedArray {
+            data: Arc::new(data),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+}
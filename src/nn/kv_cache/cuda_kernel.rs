@@ -0,0 +1,173 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray, CudaError},
+};
+
+use super::{KvCacheFormat, KvCacheKernel, KvCacheRows};
+use cudarc::driver::{CudaFunction, CudaSlice, LaunchAsync, LaunchConfig, ValidAsZeroBits};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/kv_cache.ptx"));
+const MODULE_NAME: &str = "kv_cache";
+const COPY_FN_NAME: &str = "kv_cache_copy";
+const WRITE_FN_NAME: &str = "kv_cache_write";
+const READ_FN_NAME: &str = "kv_cache_read";
+const MASK_FN_NAME: &str = "kv_cache_causal_mask";
+const ALL_FN_NAMES: [&str; 4] = [COPY_FN_NAME, WRITE_FN_NAME, READ_FN_NAME, MASK_FN_NAME];
+
+fn alloc_flat<E: ValidAsZeroBits>(
+    dev: &Cuda,
+    len: usize,
+) -> Result<CudaArray<(usize,), E>, CudaError> {
+    Ok(CudaArray {
+        data: Arc::new(dev.dev.alloc_zeros_async::<E>(len)?),
+        shape: (len,),
+        strides: [1],
+    })
+}
+
+fn get_func(dev: &Cuda, fn_name: &str) -> Result<CudaFunction, CudaError> {
+    if !dev.dev.has_func(MODULE_NAME, fn_name) {
+        dev.dev
+            .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+    }
+    Ok(dev.dev.get_func(MODULE_NAME, fn_name).unwrap())
+}
+
+impl KvCacheKernel for Cuda {
+    fn alloc_rows(
+        &self,
+        format: KvCacheFormat,
+        batch: usize,
+        capacity: usize,
+        dim: usize,
+    ) -> Result<KvCacheRows<Self>, Self::Err> {
+        let (exact, quantized, scales) = match format {
+            KvCacheFormat::F32 => (batch * capacity * dim, 0, 0),
+            _ => (0, batch * capacity * dim, batch * capacity),
+        };
+        Ok(KvCacheRows {
+            format,
+            batch,
+            capacity,
+            dim,
+            exact: alloc_flat(self, exact)?,
+            quantized: alloc_flat(self, quantized)?,
+            scales: alloc_flat(self, scales)?,
+        })
+    }
+
+    fn copy_rows(
+        &self,
+        len: usize,
+        src: &KvCacheRows<Self>,
+        dst: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err> {
+        let numel = src.batch * len * src.dim;
+        if numel == 0 {
+            return Ok(());
+        }
+        let copy_fn = get_func(self, COPY_FN_NAME)?;
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                                  // const size_t numel,
+            src.format as i32,                      // const KvCacheFormat format,
+            len,                                    // const size_t len,
+            src.dim,                                // const size_t dim,
+            src.capacity,                           // const size_t src_capacity,
+            dst.capacity,                           // const size_t dst_capacity,
+            src.exact.data.as_ref(),                // const float *src_exact,
+            src.quantized.data.as_ref(),            // const unsigned char *src_quantized,
+            src.scales.data.as_ref(),               // const float *src_scales,
+            Arc::make_mut(&mut dst.exact.data),     // float *dst_exact,
+            Arc::make_mut(&mut dst.quantized.data), // unsigned char *dst_quantized,
+            Arc::make_mut(&mut dst.scales.data),    // float *dst_scales
+        );
+        unsafe { copy_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn write_rows<S: Shape>(
+        &self,
+        start: usize,
+        src: &Self::Storage<S, f32>,
+        rows: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err> {
+        let dims = src.shape.concrete();
+        let num_rows = dims[0] * dims[1];
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let src_strides: Vec<usize> = src.strides.into();
+        let src_strides: CudaSlice<usize> = self.dev.take_async(src_strides)?;
+
+        let write_fn = get_func(self, WRITE_FN_NAME)?;
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,                                // const size_t num_rows,
+            rows.format as i32,                      // const KvCacheFormat format,
+            dims[1],                                 // const size_t tokens,
+            rows.dim,                                // const size_t dim,
+            start,                                   // const size_t start,
+            rows.capacity,                           // const size_t capacity,
+            src.data.as_ref(),                       // const float *src,
+            &src_strides,                            // const size_t *src_strides,
+            Arc::make_mut(&mut rows.exact.data),     // float *exact,
+            Arc::make_mut(&mut rows.quantized.data), // unsigned char *quantized,
+            Arc::make_mut(&mut rows.scales.data),    // float *scales
+        );
+        unsafe { write_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn read_rows<S: Shape>(
+        &self,
+        shape: S,
+        rows: &KvCacheRows<Self>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let numel = shape.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel > 0 {
+            let read_fn = get_func(self, READ_FN_NAME)?;
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,                        // const size_t numel,
+                rows.format as i32,           // const KvCacheFormat format,
+                shape.concrete()[1],          // const size_t len,
+                rows.dim,                     // const size_t dim,
+                rows.capacity,                // const size_t capacity,
+                rows.exact.data.as_ref(),     // const float *exact,
+                rows.quantized.data.as_ref(), // const unsigned char *quantized,
+                rows.scales.data.as_ref(),    // const float *scales,
+                &mut storage,                 // float *out
+            );
+            unsafe { read_fn.launch_async(cfg, params) }?;
+        }
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn causal_mask<S: Shape>(&self, shape: S) -> Result<Self::Storage<S, f32>, Self::Err> {
+        let numel = shape.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel > 0 {
+            let mask_fn = get_func(self, MASK_FN_NAME)?;
+            let cfg = LaunchConfig::for_num_elems(numel as u32);
+            let params = (
+                numel,               // const size_t numel,
+                shape.concrete()[0], // const size_t queries,
+                shape.concrete()[1], // const size_t len,
+                &mut storage,        // float *out
+            );
+            unsafe { mask_fn.launch_async(cfg, params) }?;
+        }
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+}
@@ -0,0 +1,177 @@
+// These mirror `KvCacheFormat` & its `encode`/`decode` exactly, so the cache
+// holds the same bytes on every device.
+
+enum KvCacheFormat {
+    F32,
+    Int8,
+    Fp8
+};
+
+// The largest finite E4M3 value
+#define FP8_MAX 448.0f
+
+__device__ float max_level(const KvCacheFormat format) {
+    return format == Int8 ? 127.0f : FP8_MAX;
+}
+
+__device__ unsigned char encode(const KvCacheFormat format, float x) {
+    if (format == Int8) {
+        if (isnan(x)) {
+            return 0;
+        }
+        return (unsigned char)(signed char)fminf(fmaxf(roundf(x), -127.0f), 127.0f);
+    }
+
+    unsigned char sign = signbit(x) ? 0x80 : 0;
+    x = fminf(fabsf(x), FP8_MAX);
+    unsigned int bits;
+    if (x < ldexpf(1.0f, -6)) {
+        // subnormal, which rounds up to the smallest normal number (0x08) seamlessly
+        bits = (unsigned int)roundf(x * ldexpf(1.0f, 9));
+    } else {
+        int exp = (int)((__float_as_uint(x) >> 23) & 0xff) - 127;
+        unsigned int mantissa = (unsigned int)roundf((x / ldexpf(1.0f, exp) - 1.0f) * 8.0f);
+        // a mantissa that rounds to 8 carries into the exponent
+        bits = ((unsigned int)(exp + 7) << 3) + mantissa;
+    }
+    // 0x7f is NaN, so values are capped at 448 (0x7e)
+    return sign | (unsigned char)min(bits, 0x7eu);
+}
+
+__device__ float decode(const KvCacheFormat format, const unsigned char b) {
+    if (format == Int8) {
+        return (float)(signed char)b;
+    }
+
+    int exp = (b >> 3) & 0xf;
+    float mantissa = (float)(b & 0x7) / 8.0f;
+    float x = exp == 0 ? mantissa * ldexpf(1.0f, -6) : (1.0f + mantissa) * ldexpf(1.0f, exp - 7);
+    return (b & 0x80) ? -x : x;
+}
+
+// Each thread copies one value of the first `len` tokens of a sequence.
+extern "C" __global__ void kv_cache_copy(
+    const size_t numel,
+    const KvCacheFormat format,
+    const size_t len,
+    const size_t dim,
+    const size_t src_capacity,
+    const size_t dst_capacity,
+    const float *src_exact,
+    const unsigned char *src_quantized,
+    const float *src_scales,
+    float *dst_exact,
+    unsigned char *dst_quantized,
+    float *dst_scales
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t j = i % dim;
+    size_t t = (i / dim) % len;
+    size_t b = i / (dim * len);
+    size_t i_src = b * src_capacity + t;
+    size_t i_dst = b * dst_capacity + t;
+
+    if (format == F32) {
+        dst_exact[i_dst * dim + j] = src_exact[i_src * dim + j];
+        return;
+    }
+    dst_quantized[i_dst * dim + j] = src_quantized[i_src * dim + j];
+    if (j == 0) {
+        dst_scales[i_dst] = src_scales[i_src];
+    }
+}
+
+// Each thread stores one token of `src`, which has shape (batch, tokens, dim),
+// as token `start + t` of its sequence.
+extern "C" __global__ void kv_cache_write(
+    const size_t num_rows,
+    const KvCacheFormat format,
+    const size_t tokens,
+    const size_t dim,
+    const size_t start,
+    const size_t capacity,
+    const float *src,
+    const size_t *src_strides,
+    float *exact,
+    unsigned char *quantized,
+    float *scales
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t b = row / tokens;
+    size_t t = row % tokens;
+    const float *x = src + b * src_strides[0] + t * src_strides[1];
+    size_t i_dst = b * capacity + start + t;
+
+    if (format == F32) {
+        for (size_t j = 0; j < dim; j++) {
+            exact[i_dst * dim + j] = x[j * src_strides[2]];
+        }
+        return;
+    }
+
+    float amax = 0.0f;
+    for (size_t j = 0; j < dim; j++) {
+        amax = fmaxf(amax, fabsf(x[j * src_strides[2]]));
+    }
+    float scale = amax > 0.0f ? amax / max_level(format) : 1.0f;
+    scales[i_dst] = scale;
+    for (size_t j = 0; j < dim; j++) {
+        quantized[i_dst * dim + j] = encode(format, x[j * src_strides[2]] / scale);
+    }
+}
+
+// Each thread dequantizes one value of the first `len` tokens of a sequence
+// into the contiguous (batch, len, dim) output.
+extern "C" __global__ void kv_cache_read(
+    const size_t numel,
+    const KvCacheFormat format,
+    const size_t len,
+    const size_t dim,
+    const size_t capacity,
+    const float *exact,
+    const unsigned char *quantized,
+    const float *scales,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t j = i % dim;
+    size_t t = (i / dim) % len;
+    size_t b = i / (dim * len);
+    size_t i_src = b * capacity + t;
+
+    if (format == F32) {
+        out[i] = exact[i_src * dim + j];
+    } else {
+        out[i] = decode(format, quantized[i_src * dim + j]) * scales[i_src];
+    }
+}
+
+// The last `queries` of the `len` tokens can see the keys up to & including
+// their own position.
+extern "C" __global__ void kv_cache_causal_mask(
+    const size_t numel,
+    const size_t queries,
+    const size_t len,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t q = i / len;
+    size_t k = i % len;
+    out[i] = k <= q + len - queries ? 0.0f : -INFINITY;
+}
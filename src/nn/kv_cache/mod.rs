@@ -0,0 +1,622 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*, tensor_ops::*};
+
+/// How a [KvCache] stores its keys & values.
+///
+/// The quantized formats keep one `f32` scale per cached token (the max absolute value of
+/// its features), so the error of each value is relative to the largest value of its token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KvCacheFormat {
+    /// 4 bytes per value, exact.
+    #[default]
+    F32,
+    /// 1 byte per value, rounded to one of 255 evenly spaced levels.
+    Int8,
+    /// 1 byte per value, in the `E4M3` float format (4 exponent & 3 mantissa bits), which
+    /// keeps more precision for small values than [KvCacheFormat::Int8].
+    Fp8,
+}
+
+/// The largest finite `E4M3` value.
+const FP8_MAX: f32 = 448.0;
+
+impl KvCacheFormat {
+    /// The number of bytes needed to store a token with `dim` features.
+    pub fn bytes_per_token(&self, dim: usize) -> usize {
+        match self {
+            Self::F32 => 4 * dim,
+            Self::Int8 | Self::Fp8 => dim + 4,
+        }
+    }
+
+    fn encode(&self, x: f32) -> u8 {
+        match self {
+            Self::F32 => unreachable!(),
+            Self::Int8 => (x.round().clamp(-127.0, 127.0) as i8) as u8,
+            Self::Fp8 => {
+                let sign = if x.is_sign_negative() { 0x80 } else { 0 };
+                let x = x.abs().min(FP8_MAX);
+                let bits = if x < 2f32.powi(-6) {
+                    // subnormal, which rounds up to the smallest normal number (0x08) seamlessly
+                    (x * 2f32.powi(9)).round() as u8
+                } else {
+                    let exp = ((x.to_bits() >> 23) & 0xff) as i32 - 127;
+                    let mantissa = ((x / 2f32.powi(exp) - 1.0) * 8.0).round() as u8;
+                    // a mantissa that rounds to 8 carries into the exponent
+                    (((exp + 7) as u8) << 3) + mantissa
+                };
+                // 0x7f is NaN, so values are capped at 448 (0x7e)
+                sign | bits.min(0x7e)
+            }
+        }
+    }
+
+    fn decode(&self, b: u8) -> f32 {
+        match self {
+            Self::F32 => unreachable!(),
+            Self::Int8 => b as i8 as f32,
+            Self::Fp8 => {
+                let exp = ((b >> 3) & 0xf) as i32;
+                let mantissa = (b & 0x7) as f32 / 8.0;
+                let x = if exp == 0 {
+                    mantissa * 2f32.powi(-6)
+                } else {
+                    (1.0 + mantissa) * 2f32.powi(exp - 7)
+                };
+                if b & 0x80 != 0 {
+                    -x
+                } else {
+                    x
+                }
+            }
+        }
+    }
+
+    /// The value that the max absolute value of a token is scaled to.
+    fn max_level(&self) -> f32 {
+        match self {
+            Self::F32 => unreachable!(),
+            Self::Int8 => 127.0,
+            Self::Fp8 => FP8_MAX,
+        }
+    }
+}
+
+/// The keys or values of a [KvCache], stored on the device with room for `capacity` tokens
+/// in each of the `batch` sequences.
+///
+/// [KvCacheFormat::F32] rows are stored in `exact`, and the quantized formats store one byte
+/// per value in `quantized` and one scale per token in `scales`. The unused buffers are empty.
+#[derive(Debug, Clone)]
+pub struct KvCacheRows<D: DeviceStorage> {
+    format: KvCacheFormat,
+    batch: usize,
+    capacity: usize,
+    dim: usize,
+    exact: D::Storage<(usize,), f32>,
+    quantized: D::Storage<(usize,), u8>,
+    scales: D::Storage<(usize,), f32>,
+}
+
+/// Quantizes & dequantizes the rows of a [KvCache] without leaving the device.
+pub trait KvCacheKernel: DeviceStorage {
+    /// Empty rows with room for `capacity` tokens in each sequence.
+    fn alloc_rows(
+        &self,
+        format: KvCacheFormat,
+        batch: usize,
+        capacity: usize,
+        dim: usize,
+    ) -> Result<KvCacheRows<Self>, Self::Err>;
+
+    /// Copies the first `len` tokens of each sequence of `src` into `dst`.
+    fn copy_rows(
+        &self,
+        len: usize,
+        src: &KvCacheRows<Self>,
+        dst: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err>;
+
+    /// Stores `src`, which has shape `(batch, tokens, dim)`, as tokens `start..start + tokens`
+    /// of each sequence of `rows`.
+    fn write_rows<S: Shape>(
+        &self,
+        start: usize,
+        src: &Self::Storage<S, f32>,
+        rows: &mut KvCacheRows<Self>,
+    ) -> Result<(), Self::Err>;
+
+    /// The first `len` tokens of each sequence as `f32`, where `shape` is `(batch, len, dim)`.
+    fn read_rows<S: Shape>(
+        &self,
+        shape: S,
+        rows: &KvCacheRows<Self>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err>;
+
+    /// A mask that is `0` where a query can see a key and `-inf` elsewhere, where `shape` is
+    /// `(queries, len)` and the queries are the last `queries` of the `len` tokens.
+    fn causal_mask<S: Shape>(&self, shape: S) -> Result<Self::Storage<S, f32>, Self::Err>;
+}
+
+/// Caches the keys & values of previous tokens when generating one token at a time, so they
+/// don't have to be recomputed for every new token.
+///
+/// With [KvCacheFormat::Int8] or [KvCacheFormat::Fp8], each value takes 1 byte instead of 4,
+/// which makes the cache of long contexts about 4x smaller. The values are dequantized back to
+/// `f32` on the fly in [KvCache::attend()].
+///
+/// Like [crate::nn::local_attention()], the cache holds `batch` sequences, where the batch usually also
+/// includes the heads, and the cached keys & values don't have a tape.
+///
+/// The cache stays on the device in every format: [KvCache::append()] quantizes the new keys
+/// & values into the cache, and [KvCache::attend()] dequantizes it into `f32` tensors there.
+/// The room for tokens doubles whenever it runs out, so appending one token at a time doesn't
+/// reallocate the cache every step.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mut cache = KvCache::new(&dev, KvCacheFormat::Int8, 2, 8, 4);
+/// // the prompt
+/// let k: Tensor<Rank3<2, 5, 8>, f32, _> = dev.sample_normal();
+/// let v: Tensor<Rank3<2, 5, 4>, f32, _> = dev.sample_normal();
+/// cache.append(&k, &v);
+/// // one new token
+/// let k: Tensor<Rank3<2, 1, 8>, f32, _> = dev.sample_normal();
+/// let v: Tensor<Rank3<2, 1, 4>, f32, _> = dev.sample_normal();
+/// cache.append(&k, &v);
+/// let q: Tensor<Rank3<2, 1, 8>, f32, _> = dev.sample_normal();
+/// let out = cache.attend(q);
+/// assert_eq!(out.shape(), &(Const::<2>, Const::<1>, 4));
+/// assert_eq!(cache.len(), 6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct KvCache<D: Device<f32> + KvCacheKernel> {
+    len: usize,
+    keys: KvCacheRows<D>,
+    values: KvCacheRows<D>,
+    device: D,
+}
+
+impl<D: Device<f32> + KvCacheKernel> KvCache<D> {
+    /// An empty cache for `batch` sequences, with keys of size `k_dim` and values of size `v_dim`.
+    pub fn new(
+        device: &D,
+        format: KvCacheFormat,
+        batch: usize,
+        k_dim: usize,
+        v_dim: usize,
+    ) -> Self {
+        Self::try_new(device, format, batch, k_dim, v_dim).unwrap()
+    }
+
+    /// Fallible version of [KvCache::new()]
+    pub fn try_new(
+        device: &D,
+        format: KvCacheFormat,
+        batch: usize,
+        k_dim: usize,
+        v_dim: usize,
+    ) -> Result<Self, D::Err> {
+        Ok(Self {
+            len: 0,
+            keys: device.alloc_rows(format, batch, 0, k_dim)?,
+            values: device.alloc_rows(format, batch, 0, v_dim)?,
+            device: device.clone(),
+        })
+    }
+
+    pub fn format(&self) -> KvCacheFormat {
+        self.keys.format
+    }
+
+    /// The number of cached tokens in each sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all of the cached tokens, keeping the room for them on the device.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Keeps only the first `len` cached tokens of each sequence, e.g. to roll back the draft
+    /// tokens that were rejected during speculative decoding. Does nothing if there are
+    /// already `len` tokens or less.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// The number of bytes used by the cached keys & values. The device has room for up to
+    /// twice as many tokens.
+    pub fn memory_bytes(&self) -> usize {
+        let format = self.format();
+        let per_token =
+            format.bytes_per_token(self.keys.dim) + format.bytes_per_token(self.values.dim);
+        self.keys.batch * self.len * per_token
+    }
+
+    /// Appends the keys & values of `S` new tokens to the end of each sequence.
+    ///
+    /// **Panics** if the batch size, `k_dim` or `v_dim` don't match the cache,
+    /// or if `k` & `v` have a different number of tokens.
+    pub fn append<B: Dim, S: Dim, K: Dim, V: Dim>(
+        &mut self,
+        k: &Tensor<(B, S, K), f32, D>,
+        v: &Tensor<(B, S, V), f32, D>,
+    ) {
+        self.try_append(k, v).unwrap()
+    }
+
+    /// Fallible version of [KvCache::append()]
+    pub fn try_append<B: Dim, S: Dim, K: Dim, V: Dim>(
+        &mut self,
+        k: &Tensor<(B, S, K), f32, D>,
+        v: &Tensor<(B, S, V), f32, D>,
+    ) -> Result<(), D::Err> {
+        let (b, s, dk) = *k.shape();
+        let (b_v, s_v, dv) = *v.shape();
+        assert_eq!(
+            b.size(),
+            self.keys.batch,
+            "batch size doesn't match the cache"
+        );
+        assert_eq!(
+            b_v.size(),
+            self.keys.batch,
+            "batch size doesn't match the cache"
+        );
+        assert_eq!(
+            s.size(),
+            s_v.size(),
+            "k & v have a different number of tokens"
+        );
+        assert_eq!(dk.size(), self.keys.dim, "k_dim doesn't match the cache");
+        assert_eq!(dv.size(), self.values.dim, "v_dim doesn't match the cache");
+
+        let len = self.len + s.size();
+        if len > self.keys.capacity {
+            let capacity = len.max(2 * self.keys.capacity);
+            self.keys = self.try_grow(&self.keys, capacity)?;
+            self.values = self.try_grow(&self.values, capacity)?;
+        }
+        self.device
+            .write_rows(self.len, &k.storage, &mut self.keys)?;
+        self.device
+            .write_rows(self.len, &v.storage, &mut self.values)?;
+        self.len = len;
+        Ok(())
+    }
+
+    fn try_grow(&self, rows: &KvCacheRows<D>, capacity: usize) -> Result<KvCacheRows<D>, D::Err> {
+        let mut grown = self
+            .device
+            .alloc_rows(rows.format, rows.batch, capacity, rows.dim)?;
+        self.device.copy_rows(self.len, rows, &mut grown)?;
+        Ok(grown)
+    }
+
+    /// The dequantized keys, with shape `(batch, len, k_dim)`.
+    pub fn keys(&self) -> Tensor<(usize, usize, usize), f32, D> {
+        self.try_keys().unwrap()
+    }
+
+    /// Fallible version of [KvCache::keys()]
+    #[allow(clippy::type_complexity)]
+    pub fn try_keys(&self) -> Result<Tensor<(usize, usize, usize), f32, D>, D::Err> {
+        self.try_read(&self.keys)
+    }
+
+    /// The dequantized values, with shape `(batch, len, v_dim)`.
+    pub fn values(&self) -> Tensor<(usize, usize, usize), f32, D> {
+        self.try_values().unwrap()
+    }
+
+    /// Fallible version of [KvCache::values()]
+    #[allow(clippy::type_complexity)]
+    pub fn try_values(&self) -> Result<Tensor<(usize, usize, usize), f32, D>, D::Err> {
+        self.try_read(&self.values)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn try_read(
+        &self,
+        rows: &KvCacheRows<D>,
+    ) -> Result<Tensor<(usize, usize, usize), f32, D>, D::Err> {
+        let shape = (rows.batch, self.len, rows.dim);
+        Ok(self.device.upgrade(self.device.read_rows(shape, rows)?))
+    }
+
+    /// Scaled dot product attention of the queries `q` over all of the cached keys & values.
+    ///
+    /// The queries are the last `S` cached tokens, so query `i` only attends to the keys up to
+    /// and including its own position. Append the keys & values of the new tokens before calling this.
+    ///
+    /// **Panics** if the batch size or `k_dim` don't match the cache, or if there are more
+    /// queries than cached tokens.
+    pub fn attend<B: Dim, S: Dim, K: Dim, T: Tape<D>>(
+        &self,
+        q: Tensor<(B, S, K), f32, D, T>,
+    ) -> Tensor<(B, S, usize), f32, D, T> {
+        self.try_attend(q).unwrap()
+    }
+
+    /// Fallible version of [KvCache::attend()]
+    #[allow(clippy::type_complexity)]
+    pub fn try_attend<B: Dim, S: Dim, K: Dim, T: Tape<D>>(
+        &self,
+        q: Tensor<(B, S, K), f32, D, T>,
+    ) -> Result<Tensor<(B, S, usize), f32, D, T>, D::Err> {
+        let (b, s, dk) = *q.shape();
+        assert_eq!(
+            b.size(),
+            self.keys.batch,
+            "batch size doesn't match the cache"
+        );
+        assert_eq!(dk.size(), self.keys.dim, "k_dim doesn't match the cache");
+        assert!(
+            s.size() <= self.len,
+            "{} queries but only {} cached tokens",
+            s.size(),
+            self.len
+        );
+        let (len, dv) = (self.len, self.values.dim);
+        let scalar: f32 = 1.0 / (dk.size() as f32).sqrt();
+
+        let mask = self.device.upgrade(self.device.causal_mask((s, len))?);
+        let k = self
+            .device
+            .upgrade(self.device.read_rows((b, len, dk), &self.keys)?);
+        let v = self
+            .device
+            .upgrade(self.device.read_rows((b, len, dv), &self.values)?);
+
+        // (B, S, len, K) -> (B, S, len)
+        let weights = q
+            .try_broadcast_like::<_, Axis<2>>(&(b, s, len, dk))?
+            .try_mul(k.try_broadcast_like::<_, Axis<1>>(&(b, s, len, dk))?)?
+            .try_sum::<_, Axis<3>>()?
+            .try_mul(scalar)?
+            .try_add(mask.try_broadcast_like::<_, Axis<0>>(&(b, s, len))?)?
+            .try_softmax::<Axis<2>>()?;
+
+        // (B, S, len, V) -> (B, S, V)
+        weights
+            .try_broadcast_like::<_, Axis<3>>(&(b, s, len, dv))?
+            .try_mul(v.try_broadcast_like::<_, Axis<1>>(&(b, s, len, dv))?)?
+            .try_sum::<_, Axis<2>>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// Causal attention over `k` & `v` directly, for the last `S1` positions.
+    fn causal_attention<
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        const K: usize,
+        const V: usize,
+        T,
+    >(
+        q: Tensor<Rank3<B, S1, K>, f32, TestDevice, T>,
+        k: Tensor<Rank3<B, S2, K>, f32, TestDevice>,
+        v: Tensor<Rank3<B, S2, V>, f32, TestDevice>,
+    ) -> Tensor<Rank3<B, S1, V>, f32, TestDevice, T>
+    where
+        T: Tape<TestDevice>,
+    {
+        let dev = q.device.clone();
+        let mut mask = [[0.0; S2]; S1];
+        for (i, row) in mask.iter_mut().enumerate() {
+            for (j, m) in row.iter_mut().enumerate() {
+                if j > i + S2 - S1 {
+                    *m = f32::NEG_INFINITY;
+                }
+            }
+        }
+        let weights: Tensor<Rank3<B, S1, S2>, f32, _, T> =
+            q.matmul(k.retaped::<T>().permute::<_, Axes3<0, 2, 1>>()) * (1.0 / (K as f32).sqrt())
+                + dev.tensor(mask).broadcast();
+        weights.softmax::<Axis<2>>().matmul(v.retaped::<T>())
+    }
+
+    #[test]
+    fn test_kv_cache_f32_matches_attention() {
+        let dev: TestDevice = Default::default();
+        let k1: Tensor<Rank3<2, 4, 4>, f32, _> = dev.sample_normal();
+        let v1: Tensor<Rank3<2, 4, 3>, f32, _> = dev.sample_normal();
+        let k2: Tensor<Rank3<2, 2, 4>, f32, _> = dev.sample_normal();
+        let v2: Tensor<Rank3<2, 2, 3>, f32, _> = dev.sample_normal();
+        let q: Tensor<Rank3<2, 2, 4>, f32, _> = dev.sample_normal();
+
+        let mut cache = KvCache::new(&dev, KvCacheFormat::F32, 2, 4, 3);
+        cache.append(&k1, &v1);
+        cache.append(&k2, &v2);
+        let k = k1.concat_along::<Axis<1>, Const<6>>(k2);
+        let v = v1.concat_along::<Axis<1>, Const<6>>(v2);
+        assert_eq!(cache.len(), 6);
+        assert_eq!(cache.keys().as_vec(), k.as_vec());
+        assert_eq!(cache.values().as_vec(), v.as_vec());
+
+        let r = cache.attend(q.trace());
+        let e = causal_attention(q.trace(), k, v);
+        assert_close(&r.as_vec(), &e.as_vec());
+
+        let r_g = r.exp().mean().backward();
+        let e_g = e.exp().mean().backward();
+        assert_close(&r_g.get(&q).array(), &e_g.get(&q).array());
+    }
+
+    #[test]
+    fn test_kv_cache_append_permuted() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<3, 2, 4>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank3<3, 2, 5>, f32, _> = dev.sample_normal();
+        let k = k.permute::<Rank3<2, 3, 4>, Axes3<1, 0, 2>>();
+        let v = v.permute::<Rank3<2, 3, 5>, Axes3<1, 0, 2>>();
+
+        let mut cache = KvCache::new(&dev, KvCacheFormat::F32, 2, 4, 5);
+        cache.append(&k, &v);
+        assert_eq!(cache.keys().as_vec(), k.as_vec());
+        assert_eq!(cache.values().as_vec(), v.as_vec());
+    }
+
+    #[test]
+    fn test_kv_cache_append_one_at_a_time() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<2, 5, 4>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        for format in [KvCacheFormat::F32, KvCacheFormat::Int8, KvCacheFormat::Fp8] {
+            let mut full = KvCache::new(&dev, format, 2, 4, 3);
+            full.append(&k, &v);
+
+            let mut cache = KvCache::new(&dev, format, 2, 4, 3);
+            for i in 0..5 {
+                cache.append(
+                    &k.clone().slice::<Axis<1>>(i..i + 1),
+                    &v.clone().slice::<Axis<1>>(i..i + 1),
+                );
+            }
+            assert_eq!(cache.keys().as_vec(), full.keys().as_vec());
+            assert_eq!(cache.values().as_vec(), full.values().as_vec());
+
+            cache.clear();
+            cache.append(&k, &v);
+            assert_eq!(cache.keys().as_vec(), full.keys().as_vec());
+        }
+    }
+
+    #[test]
+    fn test_kv_cache_quantized_error() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<2, 16, 8>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank3<2, 16, 8>, f32, _> = dev.sample_normal();
+        let q: Tensor<Rank3<2, 1, 8>, f32, _> = dev.sample_normal();
+        let expected = causal_attention(q.clone(), k.clone(), v.clone()).as_vec();
+
+        for (format, tol) in [(KvCacheFormat::Int8, 0.05), (KvCacheFormat::Fp8, 0.1)] {
+            let mut cache = KvCache::new(&dev, format, 2, 8, 8);
+            cache.append(&k, &v);
+            // the error of each value is relative to the largest value of its token
+            let step = match format {
+                KvCacheFormat::Int8 => 0.5 / 127.0,
+                _ => 1.0 / 16.0,
+            };
+            let keys = cache.keys().as_vec();
+            for (a, e) in keys.chunks(8).zip(k.as_vec().chunks(8)) {
+                let amax = e.iter().fold(0.0f32, |m, x| m.max(x.abs()));
+                for (a, e) in a.iter().zip(e.iter()) {
+                    assert!(
+                        (a - e).abs() <= amax * step * 1.001,
+                        "{format:?}: {a} vs {e}"
+                    );
+                }
+            }
+            let r = cache.attend(q.clone()).as_vec();
+            for (a, e) in r.iter().zip(expected.iter()) {
+                assert!((a - e).abs() <= tol, "{format:?}: {a} vs {e}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fp8_round_trip() {
+        let f = KvCacheFormat::Fp8;
+        for x in [
+            0.0,
+            1.0,
+            -1.0,
+            1.125,
+            448.0,
+            -448.0,
+            2f32.powi(-6),
+            2f32.powi(-9),
+            0.5,
+        ] {
+            assert_eq!(f.decode(f.encode(x)), x);
+        }
+        assert_eq!(f.decode(f.encode(1000.0)), 448.0);
+        assert_eq!(f.decode(f.encode(1.0624)), 1.0);
+        assert_eq!(f.decode(f.encode(1.07)), 1.125);
+        assert_eq!(f.decode(f.encode(15.9)), 16.0);
+        for b in 0..=0xffu8 {
+            if b & 0x7f != 0x7f {
+                assert_eq!(f.encode(f.decode(b)) & 0x7f, b & 0x7f);
+            }
+        }
+    }
+
+    #[test]
+    fn test_kv_cache_memory() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<2, 10, 64>, f32, _> = dev.zeros();
+        let mut f32_cache = KvCache::new(&dev, KvCacheFormat::F32, 2, 64, 64);
+        let mut int8_cache = KvCache::new(&dev, KvCacheFormat::Int8, 2, 64, 64);
+        f32_cache.append(&k, &k);
+        int8_cache.append(&k, &k);
+        assert_eq!(f32_cache.memory_bytes(), 2 * 10 * 2 * 64 * 4);
+        assert_eq!(int8_cache.memory_bytes(), 2 * 10 * 2 * (64 + 4));
+        assert_eq!(int8_cache.keys().as_vec(), k.as_vec());
+
+        int8_cache.clear();
+        assert!(int8_cache.is_empty());
+        assert_eq!(int8_cache.memory_bytes(), 0);
+    }
+
+    #[test]
+    fn test_kv_cache_truncate() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<2, 5, 4>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        for format in [KvCacheFormat::F32, KvCacheFormat::Int8, KvCacheFormat::Fp8] {
+            let mut full = KvCache::new(&dev, format, 2, 4, 3);
+            full.append(&k, &v);
+
+            let mut cache = KvCache::new(&dev, format, 2, 4, 3);
+            let k3: Tensor<(Const<2>, usize, Const<4>), f32, _> = k.clone().slice::<Axis<1>>(..3);
+            let v3: Tensor<(Const<2>, usize, Const<3>), f32, _> = v.clone().slice::<Axis<1>>(..3);
+            cache.append(&k3, &v3);
+            let memory = cache.memory_bytes();
+            // a rejected draft token
+            cache.append(
+                &k3.clone().slice::<Axis<1>>(..1),
+                &v3.clone().slice::<Axis<1>>(..1),
+            );
+            cache.truncate(3);
+            assert_eq!(cache.len(), 3);
+            assert_eq!(cache.memory_bytes(), memory);
+            cache.truncate(4);
+            assert_eq!(cache.len(), 3);
+
+            cache.append(
+                &k.clone().slice::<Axis<1>>(3..),
+                &v.clone().slice::<Axis<1>>(3..),
+            );
+            assert_eq!(cache.keys().as_vec(), full.keys().as_vec());
+            assert_eq!(cache.values().as_vec(), full.values().as_vec());
+        }
+    }
+
+    #[test]
+    #[should_panic = "3 queries but only 2 cached tokens"]
+    fn test_kv_cache_too_many_queries() {
+        let dev: TestDevice = Default::default();
+        let k: Tensor<Rank3<1, 2, 4>, f32, _> = dev.zeros();
+        let mut cache = KvCache::new(&dev, KvCacheFormat::F32, 1, 4, 4);
+        cache.append(&k, &k);
+        let q: Tensor<Rank3<1, 3, 4>, f32, _> = dev.zeros();
+        let _ = cache.attend(q);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::{BuildModule, FunctionalModule, Module, ModuleMut, ResetParams, ToDevice};
 
 /// Implements layer normalization as described in [Layer Normalization](https://arxiv.org/abs/1607.06450).
 ///
@@ -73,8 +73,11 @@ impl<const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<M>, f32, D,
     for LayerNorm1D<M, D>
 {
     type Output = Tensor<Rank1<M>, f32, D, T>;
-    fn forward(&self, x: Tensor<Rank1<M>, f32, D, T>) -> Self::Output {
-        x.normalize(self.epsilon) * self.gamma.clone() + self.beta.clone()
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<Rank1<M>, f32, D, T>) -> Result<Self::Output, D::Err> {
+        x.try_normalize(self.epsilon)?
+            .try_mul(self.gamma.clone())?
+            .try_add(self.beta.clone())
     }
 }
 
@@ -82,10 +85,10 @@ impl<B: Dim, const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<(B, Const
     for LayerNorm1D<M, D>
 {
     type Output = Tensor<(B, Const<M>), f32, D, T>;
-    fn forward(&self, x: Tensor<(B, Const<M>), f32, D, T>) -> Self::Output {
-        let shape = *x.shape();
-        x.normalize::<Axis<1>>(self.epsilon) * self.gamma.retaped::<T>().broadcast_like(&shape)
-            + self.beta.retaped::<T>().broadcast_like(&shape)
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(B, Const<M>), f32, D, T>) -> Result<Self::Output, D::Err> {
+        x.try_normalize::<Axis<1>>(self.epsilon)?
+            .try_affine::<Axis<0>>(self.gamma.retaped::<T>(), self.beta.retaped::<T>())
     }
 }
 
@@ -93,10 +96,61 @@ impl<B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
     Module<Tensor<(B, S, Const<M>), f32, D, T>> for LayerNorm1D<M, D>
 {
     type Output = Tensor<(B, S, Const<M>), f32, D, T>;
-    fn forward(&self, x: Tensor<(B, S, Const<M>), f32, D, T>) -> Self::Output {
-        let shape = *x.shape();
-        x.normalize::<Axis<2>>(self.epsilon) * self.gamma.retaped::<T>().broadcast_like(&shape)
-            + self.beta.retaped::<T>().broadcast_like(&shape)
+    type Error = D::Err;
+    fn try_forward(&self, x: Tensor<(B, S, Const<M>), f32, D, T>) -> Result<Self::Output, D::Err> {
+        x.try_normalize::<Axis<2>>(self.epsilon)?
+            .try_affine::<Axes2<0, 1>>(self.gamma.retaped::<T>(), self.beta.retaped::<T>())
+    }
+}
+
+/// `(gamma, beta)` with the same shapes as [LayerNorm1D::gamma] & [LayerNorm1D::beta].
+type LayerNorm1DParams<const M: usize, D, T> =
+    (Tensor<Rank1<M>, f32, D, T>, Tensor<Rank1<M>, f32, D, T>);
+
+impl<const M: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LayerNorm1DParams<M, D, T>, Tensor<Rank1<M>, f32, D, T>>
+    for LayerNorm1D<M, D>
+{
+    type Output = Tensor<Rank1<M>, f32, D, T>;
+    type Error = D::Err;
+    fn try_forward_with(
+        &self,
+        (gamma, beta): LayerNorm1DParams<M, D, T>,
+        x: Tensor<Rank1<M>, f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        x.try_normalize(self.epsilon)?.try_mul(gamma)?.try_add(beta)
+    }
+}
+
+impl<B: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LayerNorm1DParams<M, D, T>, Tensor<(B, Const<M>), f32, D, T>>
+    for LayerNorm1D<M, D>
+{
+    type Output = Tensor<(B, Const<M>), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward_with(
+        &self,
+        (gamma, beta): LayerNorm1DParams<M, D, T>,
+        x: Tensor<(B, Const<M>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        x.try_normalize::<Axis<1>>(self.epsilon)?
+            .try_affine::<Axis<0>>(gamma, beta)
+    }
+}
+
+impl<B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LayerNorm1DParams<M, D, T>, Tensor<(B, S, Const<M>), f32, D, T>>
+    for LayerNorm1D<M, D>
+{
+    type Output = Tensor<(B, S, Const<M>), f32, D, T>;
+    type Error = D::Err;
+    fn try_forward_with(
+        &self,
+        (gamma, beta): LayerNorm1DParams<M, D, T>,
+        x: Tensor<(B, S, Const<M>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        x.try_normalize::<Axis<2>>(self.epsilon)?
+            .try_affine::<Axes2<0, 1>>(gamma, beta)
     }
 }
 
@@ -105,8 +159,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-    fn forward_mut(&mut self, input: T) -> Self::Output {
-        self.forward(input)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
     }
 }
 
@@ -204,4 +259,23 @@ mod tests {
         model.update(&mut g, &mut unused).unwrap();
         assert!(unused.is_empty());
     }
+
+    #[test]
+    fn test_layer_norm_forward_with() {
+        let dev: TestDevice = Default::default();
+        let m: LayerNorm1D<5, _> = BuildModule::build(&dev);
+        let gamma: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let beta: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let x: Tensor<Rank3<2, 3, 5>, f32, _> = dev.sample_normal();
+
+        let y = m.forward_with((gamma.trace(), beta.trace()), x.trace());
+        let mut expected = m.clone();
+        expected.gamma = gamma.clone();
+        expected.beta = beta.clone();
+        assert_close(&y.array(), &expected.forward(x.clone()).array());
+
+        let g = y.exp().mean().backward();
+        assert_ne!(g.get(&gamma).array(), [0.0; 5]);
+        assert_ne!(g.get(&beta).array(), [0.0; 5]);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
 
-use super::module::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::module::{BuildModule, FunctionalModule, Module, ModuleMut, ResetParams, ToDevice};
 
 /// A linear transformation of the form `weight * x + bias`, where `weight` is a matrix, `x` is a vector or matrix,
 /// and `bias` is a vector.
@@ -78,16 +78,17 @@ impl<const I: usize, const O: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<
 
 impl<const I: usize, const O: usize, D: Device<f32>, T> Module<T> for Linear<I, O, D>
 where
-    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>>,
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>, Err = D::Err>,
     T::Tape: Tape<D>,
-    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output>,
+    for<'a> Bias1D<'a, O, D>: Module<T::Output, Output = T::Output, Error = D::Err>,
 {
     type Output = T::Output;
+    type Error = D::Err;
 
     /// 1d forward using [matmul()] and [add()].
-    fn forward(&self, x: T) -> Self::Output {
-        let o = x.matmul(self.weight.retaped::<T::Tape>().permute());
-        Bias1D { beta: &self.bias }.forward(o)
+    fn try_forward(&self, x: T) -> Result<Self::Output, D::Err> {
+        let o = x.try_matmul(self.weight.retaped::<T::Tape>().try_permute()?)?;
+        Bias1D { beta: &self.bias }.try_forward(o)
     }
 }
 
@@ -96,8 +97,97 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-    fn forward_mut(&mut self, input: T) -> Self::Output {
-        self.forward(input)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+/// `(weight, bias)` with the same shapes as [Linear::weight] & [Linear::bias].
+type LinearParams<const I: usize, const O: usize, D, T> =
+    (Tensor<Rank2<O, I>, f32, D, T>, Tensor<Rank1<O>, f32, D, T>);
+
+impl<const I: usize, const O: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LinearParams<I, O, D, T>, Tensor<Rank1<I>, f32, D, T>> for Linear<I, O, D>
+{
+    type Output = Tensor<Rank1<O>, f32, D, T>;
+    type Error = D::Err;
+
+    /// 1d forward using [matmul()] and [add()], with the given `(weight, bias)`.
+    fn try_forward_with(
+        &self,
+        (weight, bias): LinearParams<I, O, D, T>,
+        x: Tensor<Rank1<I>, f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        x.try_matmul(weight.try_permute()?)?.try_add(bias)
+    }
+}
+
+impl<B: Dim, const I: usize, const O: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LinearParams<I, O, D, T>, Tensor<(B, Const<I>), f32, D, T>>
+    for Linear<I, O, D>
+{
+    type Output = Tensor<(B, Const<O>), f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched 2d forward using [matmul()] and [add()], with the given `(weight, bias)`.
+    fn try_forward_with(
+        &self,
+        (weight, bias): LinearParams<I, O, D, T>,
+        x: Tensor<(B, Const<I>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        let o = x.try_matmul(weight.try_permute()?)?;
+        let shape = *o.shape();
+        bias.try_broadcast_like(&shape)?.try_add(o)
+    }
+}
+
+impl<B: Dim, S: Dim, const I: usize, const O: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<LinearParams<I, O, D, T>, Tensor<(B, S, Const<I>), f32, D, T>>
+    for Linear<I, O, D>
+{
+    type Output = Tensor<(B, S, Const<O>), f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched 3d forward using [matmul()] and [add()], with the given `(weight, bias)`.
+    fn try_forward_with(
+        &self,
+        (weight, bias): LinearParams<I, O, D, T>,
+        x: Tensor<(B, S, Const<I>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        let o = x.try_matmul(weight.try_permute()?)?;
+        let shape = *o.shape();
+        bias.try_broadcast_like(&shape)?.try_add(o)
+    }
+}
+
+impl<B: Dim, const I: usize, const O: usize, D: Device<f32>, T: Tape<D>>
+    FunctionalModule<
+        (
+            Tensor<(B, Const<O>, Const<I>), f32, D, T>,
+            Tensor<(B, Const<O>), f32, D, T>,
+        ),
+        Tensor<(B, Const<I>), f32, D, T>,
+    > for Linear<I, O, D>
+{
+    type Output = Tensor<(B, Const<O>), f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched forward where every item in the batch has its own `(weight, bias)`, as is
+    /// usually the case when they are generated from the input by a hypernetwork.
+    fn try_forward_with(
+        &self,
+        (weight, bias): (
+            Tensor<(B, Const<O>, Const<I>), f32, D, T>,
+            Tensor<(B, Const<O>), f32, D, T>,
+        ),
+        x: Tensor<(B, Const<I>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        let shape = *weight.shape();
+        x.try_broadcast_like::<_, Axis<1>>(&shape)?
+            .try_mul(weight)?
+            .try_sum::<_, Axis<2>>()?
+            .try_add(bias)
     }
 }
 
@@ -110,8 +200,9 @@ impl<'a, const M: usize, D: Device<f32>, T: Tape<D>> Module<Tensor<Rank1<M>, f32
     for Bias1D<'a, M, D>
 {
     type Output = Tensor<Rank1<M>, f32, D, T>;
-    fn forward(&self, input: Tensor<Rank1<M>, f32, D, T>) -> Self::Output {
-        input + self.beta.clone()
+    type Error = D::Err;
+    fn try_forward(&self, input: Tensor<Rank1<M>, f32, D, T>) -> Result<Self::Output, D::Err> {
+        input.try_add(self.beta.clone())
     }
 }
 
@@ -119,8 +210,12 @@ impl<'a, B: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
     Module<Tensor<(B, Const<M>), f32, D, T>> for Bias1D<'a, M, D>
 {
     type Output = Tensor<(B, Const<M>), f32, D, T>;
-    fn forward(&self, input: Tensor<(B, Const<M>), f32, D, T>) -> Self::Output {
-        self.beta.retaped::<T>().broadcast_like(input.shape()) + input
+    type Error = D::Err;
+    fn try_forward(&self, input: Tensor<(B, Const<M>), f32, D, T>) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
     }
 }
 
@@ -128,8 +223,15 @@ impl<'a, B: Dim, S: Dim, const M: usize, D: Device<f32>, T: Tape<D>>
     Module<Tensor<(B, S, Const<M>), f32, D, T>> for Bias1D<'a, M, D>
 {
     type Output = Tensor<(B, S, Const<M>), f32, D, T>;
-    fn forward(&self, input: Tensor<(B, S, Const<M>), f32, D, T>) -> Self::Output {
-        self.beta.retaped::<T>().broadcast_like(input.shape()) + input
+    type Error = D::Err;
+    fn try_forward(
+        &self,
+        input: Tensor<(B, S, Const<M>), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(input.shape())?
+            .try_add(input)
     }
 }
 
@@ -299,4 +401,75 @@ mod tests {
         model.update(&mut g, &mut unused).unwrap();
         assert!(unused.is_empty());
     }
+
+    #[test]
+    fn test_forward_with_own_params_matches_forward() {
+        let dev: TestDevice = Default::default();
+        let m: Linear<5, 2, _> = BuildModule::build(&dev);
+        let params = || (m.weight.clone(), m.bias.clone());
+
+        let x: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        assert_close(
+            &m.forward_with(params(), x.clone()).array(),
+            &m.forward(x).array(),
+        );
+
+        let x: Tensor<Rank2<3, 5>, f32, _> = dev.sample_normal();
+        assert_close(
+            &m.forward_with(params(), x.clone()).array(),
+            &m.forward(x).array(),
+        );
+
+        let x: Tensor<Rank3<4, 3, 5>, f32, _> = dev.sample_normal();
+        assert_close(
+            &m.forward_with(params(), x.clone()).array(),
+            &m.forward(x).array(),
+        );
+    }
+
+    #[test]
+    fn test_forward_with_generated_params() {
+        let dev: TestDevice = Default::default();
+        let hyper: Linear<3, 2, _> = BuildModule::build(&dev);
+        let m: Linear<2, 2, _> = BuildModule::build(&dev);
+        let z: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let x: Tensor<Rank1<2>, f32, _> = dev.sample_normal();
+
+        let bias = hyper.forward(z.trace());
+        let weight = dev.tensor([[1.0, 0.0], [0.0, 1.0]]).traced();
+        let y = m.forward_with((weight, bias), x.trace());
+        let expected = x.clone() + hyper.forward(z.clone());
+        assert_close(&y.array(), &expected.array());
+
+        let g = y.sum().backward();
+        assert_eq!(g.get(&x).array(), [1.0; 2]);
+        assert_ne!(g.get(&z).array(), [0.0; 3]);
+        assert_ne!(g.get(&hyper.weight).array(), [[0.0; 3]; 2]);
+    }
+
+    #[test]
+    fn test_forward_with_per_sample_params() {
+        let dev: TestDevice = Default::default();
+        let m: Linear<5, 2, _> = BuildModule::build(&dev);
+        let weight: Tensor<Rank3<3, 2, 5>, f32, _> = dev.sample_normal();
+        let bias: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+        let x: Tensor<Rank2<3, 5>, f32, _> = dev.sample_normal();
+
+        let y = m.forward_with((weight.trace(), bias.trace()), x.trace());
+        let y_array = y.array();
+        for (i, y_i) in y_array.iter().enumerate() {
+            let w_i = weight.clone().select(dev.tensor(i));
+            let b_i = bias.clone().select(dev.tensor(i));
+            let x_i = x.clone().select(dev.tensor(i));
+            assert_close(&m.forward_with((w_i, b_i), x_i).array(), y_i);
+        }
+
+        let g = y.sum().backward();
+        assert_eq!(g.get(&bias).array(), [[1.0; 2]; 3]);
+        let x_array = x.array();
+        let g_weight = g.get(&weight).array();
+        for i in 0..3 {
+            assert_close(&g_weight[i], &[x_array[i]; 2]);
+        }
+    }
 }
@@ -0,0 +1,230 @@
+use crate::{gradients::Tape, shapes::*, tensor::*, tensor_ops::*};
+
+use std::vec::Vec;
+
+/// Sliding window attention, where each query only attends to the keys within `radius`
+/// positions of it, like in [Longformer](https://arxiv.org/abs/2004.05150).
+///
+/// `q`, `k` & `v` are `(batch, seq, features)`, where the batch usually also includes the heads.
+/// Scores are only computed inside the window of `2 * radius + 1` keys around each query,
+/// so memory grows like `seq * radius` instead of `seq * seq`.
+///
+/// The positions in `global` attend to every position, and every position attends to them.
+///
+/// Like [super::MultiHeadAttention], `k` & `v` don't have a tape, and the tape of `q` is
+/// used for all of them.
+///
+/// **Panics** if a position in `global` is out of bounds.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let q: Tensor<Rank3<2, 16, 8>, f32, _> = dev.sample_normal();
+/// let k: Tensor<Rank3<2, 16, 8>, f32, _> = dev.sample_normal();
+/// let v: Tensor<Rank3<2, 16, 4>, f32, _> = dev.sample_normal();
+/// // every query sees its 2 neighbors on each side, and the first token
+/// let out = local_attention(q, k, v, 2, &[0]);
+/// ```
+pub fn local_attention<B: Dim, S: Dim, K: Dim, V: Dim, D, T: Tape<D>>(
+    q: Tensor<(B, S, K), f32, D, T>,
+    k: Tensor<(B, S, K), f32, D>,
+    v: Tensor<(B, S, V), f32, D>,
+    radius: usize,
+    global: &[usize],
+) -> Tensor<(B, S, V), f32, D, T>
+where
+    D: Device<f32> + TensorFromVec<usize>,
+{
+    try_local_attention(q, k, v, radius, global).unwrap()
+}
+
+/// Fallible version of [local_attention()]
+#[allow(clippy::type_complexity)]
+pub fn try_local_attention<B: Dim, S: Dim, K: Dim, V: Dim, D, T: Tape<D>>(
+    q: Tensor<(B, S, K), f32, D, T>,
+    k: Tensor<(B, S, K), f32, D>,
+    v: Tensor<(B, S, V), f32, D>,
+    radius: usize,
+    global: &[usize],
+) -> Result<Tensor<(B, S, V), f32, D, T>, D::Err>
+where
+    D: Device<f32> + TensorFromVec<usize>,
+{
+    let (b, s, dk) = *q.shape();
+    let dv = v.shape().2;
+    let seq = s.size();
+    for &g in global {
+        assert!(g < seq, "global position {g} out of bounds");
+    }
+    let scalar: f32 = 1.0 / (dk.size() as f32).sqrt();
+    let dev = q.device.clone();
+
+    // the keys each query attends to: the window around it, followed by the global positions.
+    // keys that are outside of the sequence, or global keys that are also in the window, are masked.
+    let num_keys = 2 * radius + 1 + global.len();
+    let mut positions = Vec::with_capacity(seq * num_keys);
+    let mut mask = Vec::with_capacity(seq * num_keys);
+    for i in 0..seq {
+        for w in 0..2 * radius + 1 {
+            let j = (i + w).checked_sub(radius).filter(|&j| j < seq);
+            positions.push(j.unwrap_or(0));
+            mask.push(if j.is_some() { 0.0 } else { f32::NEG_INFINITY });
+        }
+        for &j in global {
+            positions.push(j);
+            let in_window = i.abs_diff(j) <= radius;
+            mask.push(if in_window { f32::NEG_INFINITY } else { 0.0 });
+        }
+    }
+    let mut mask_t: Tensor<(S, usize), f32, D> = dev.try_zeros_like(&(s, num_keys))?;
+    mask_t.copy_from(&mask);
+
+    let windowed = |dim: usize| {
+        let mut idx = Vec::with_capacity(b.size() * positions.len() * dim);
+        for _ in 0..b.size() {
+            for &j in positions.iter() {
+                idx.extend(std::iter::repeat_n(j, dim));
+            }
+        }
+        idx
+    };
+    let k_idx = dev.try_tensor_from_vec(windowed(dk.size()), (b, s, num_keys, dk))?;
+    let v_idx = dev.try_tensor_from_vec(windowed(dv.size()), (b, s, num_keys, dv))?;
+
+    let (q, tape) = q.split_tape();
+    let q_global = q.retaped::<T>();
+    let q = q.put_tape(tape);
+
+    // (B, S, num_keys, K) -> (B, S, num_keys)
+    let k_win = k
+        .retaped::<T>()
+        .try_broadcast_like::<_, Axis<2>>(&(b, s, num_keys, dk))?
+        .try_gather_along::<Axis<1>>(k_idx)?;
+    let weights = q
+        .try_broadcast_like::<_, Axis<2>>(&(b, s, num_keys, dk))?
+        .try_mul(k_win)?
+        .try_sum::<_, Axis<3>>()?
+        .try_mul(scalar)?
+        .try_add(mask_t.try_broadcast_like::<_, Axis<0>>(&(b, s, num_keys))?)?
+        .try_softmax::<Axis<2>>()?;
+
+    // (B, S, num_keys, V) -> (B, S, V)
+    let v_win = v
+        .retaped::<T>()
+        .try_broadcast_like::<_, Axis<2>>(&(b, s, num_keys, dv))?
+        .try_gather_along::<Axis<1>>(v_idx)?;
+    let tokens = weights
+        .try_broadcast_like::<_, Axis<3>>(&(b, s, num_keys, dv))?
+        .try_mul(v_win)?
+        .try_sum::<_, Axis<2>>()?;
+
+    if global.is_empty() {
+        return Ok(tokens);
+    }
+
+    // the global queries attend to every key, and replace the windowed tokens
+    let g = global.len();
+    let rows = |dim: usize| {
+        let mut idx = Vec::with_capacity(b.size() * g * dim);
+        for _ in 0..b.size() {
+            for &j in global {
+                idx.extend(std::iter::repeat_n(j, dim));
+            }
+        }
+        idx
+    };
+    let q_idx = dev.try_tensor_from_vec(rows(dk.size()), (b, g, dk))?;
+    let out_idx = dev.try_tensor_from_vec(rows(dv.size()), (b, g, dv))?;
+
+    let q_global = q_global.try_gather_along::<Axis<1>>(q_idx)?;
+    let weights = q_global
+        .try_broadcast_like::<_, Axis<2>>(&(b, g, s, dk))?
+        .try_mul(
+            k.retaped::<T>()
+                .try_broadcast_like::<_, Axis<1>>(&(b, g, s, dk))?,
+        )?
+        .try_sum::<_, Axis<3>>()?
+        .try_mul(scalar)?
+        .try_softmax::<Axis<2>>()?;
+    let global_tokens = weights
+        .try_broadcast_like::<_, Axis<3>>(&(b, g, s, dv))?
+        .try_mul(
+            v.retaped::<T>()
+                .try_broadcast_like::<_, Axis<1>>(&(b, g, s, dv))?,
+        )?
+        .try_sum::<_, Axis<2>>()?;
+
+    let mut keep = std::vec![1.0; seq];
+    for &j in global {
+        keep[j] = 0.0;
+    }
+    let mut keep_t: Tensor<(S,), f32, D> = dev.try_zeros_like(&(s,))?;
+    keep_t.copy_from(&keep);
+    tokens
+        .try_mul(keep_t.try_broadcast_like::<_, Axes2<0, 2>>(&(b, s, dv))?)?
+        .try_scatter_add::<Axis<1>>(out_idx, global_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    /// Attention over all of the keys, with everything outside of the window masked.
+    fn dense_attention<const B: usize, const S: usize, const K: usize, const V: usize, T>(
+        q: Tensor<Rank3<B, S, K>, f32, TestDevice, T>,
+        k: Tensor<Rank3<B, S, K>, f32, TestDevice>,
+        v: Tensor<Rank3<B, S, V>, f32, TestDevice>,
+        radius: usize,
+        global: &[usize],
+    ) -> Tensor<Rank3<B, S, V>, f32, TestDevice, T>
+    where
+        T: Tape<TestDevice>,
+    {
+        let dev = q.device.clone();
+        let mut mask = [[f32::NEG_INFINITY; S]; S];
+        for (i, row) in mask.iter_mut().enumerate() {
+            for (j, m) in row.iter_mut().enumerate() {
+                let g = global.contains(&i) || global.contains(&j);
+                if g || i.abs_diff(j) <= radius {
+                    *m = 0.0;
+                }
+            }
+        }
+        let weights: Tensor<Rank3<B, S, S>, f32, _, T> =
+            q.matmul(k.retaped::<T>().permute::<_, Axes3<0, 2, 1>>()) * (1.0 / (K as f32).sqrt())
+                + dev.tensor(mask).broadcast();
+        let weights = weights.softmax::<Axis<2>>();
+        weights.matmul(v.retaped::<T>())
+    }
+
+    #[test]
+    fn test_local_attention_matches_dense() {
+        let dev: TestDevice = Default::default();
+        let q: Tensor<Rank3<2, 7, 4>, f32, _> = dev.sample_normal();
+        let k: Tensor<Rank3<2, 7, 4>, f32, _> = dev.sample_normal();
+        let v: Tensor<Rank3<2, 7, 3>, f32, _> = dev.sample_normal();
+        for (radius, global) in [(0, &[][..]), (1, &[][..]), (2, &[0, 5][..]), (9, &[3][..])] {
+            let r = local_attention(q.trace(), k.clone(), v.clone(), radius, global);
+            let e = dense_attention(q.trace(), k.clone(), v.clone(), radius, global);
+            assert_close(&r.array(), &e.array());
+
+            let r_g = r.exp().mean().backward();
+            let e_g = e.exp().mean().backward();
+            assert_close(&r_g.get(&q).array(), &e_g.get(&q).array());
+            assert_close(&r_g.get(&k).array(), &e_g.get(&k).array());
+            assert_close(&r_g.get(&v).array(), &e_g.get(&v).array());
+        }
+    }
+
+    #[test]
+    fn test_local_attention_dyn_shapes() {
+        let dev: TestDevice = Default::default();
+        let normal = rand_distr::StandardNormal;
+        let q: Tensor<(usize, usize, Const<4>), f32, _> = dev.sample_like(&(3, 10, Const), normal);
+        let k: Tensor<(usize, usize, Const<4>), f32, _> = dev.sample_like(&(3, 10, Const), normal);
+        let v: Tensor<(usize, usize, Const<2>), f32, _> = dev.sample_like(&(3, 10, Const), normal);
+        let r = local_attention(q, k, v, 1, &[9]);
+        assert_eq!(r.shape(), &(3, 10, Const));
+    }
+}
@@ -0,0 +1,261 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{
+    module::{BuildModule, Module, ModuleMut, ResetParams, ToDevice},
+    Linear,
+};
+
+/// A [Linear] layer with a frozen base weight, and a trainable low rank update
+/// `scale * lora_b * lora_a`, as introduced in
+/// [LoRA: Low-Rank Adaptation of Large Language Models](https://arxiv.org/abs/2106.09685).
+///
+/// Only [Self::lora_a] and [Self::lora_b] are updated by optimizers, [Self::base] is never modified.
+/// [Self::lora_a] is initialized like a [Linear] weight, and [Self::lora_b] with zeros, so a
+/// freshly built [LoraLinear] computes the same thing as its base layer.
+///
+/// After training, [LoraLinear::merge()] folds the update into the base weight so inference
+/// costs the same as a [Linear]. [LoraLinear::unmerge()] undoes this.
+///
+/// # Generics
+/// - `I` The "input" size of vectors & matrices.
+/// - `O` The "output" size of vectors & matrices.
+/// - `R` The rank of the update.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let base: Linear<5, 3> = BuildModule::build(&dev);
+/// let mut model: LoraLinear<5, 3, 2> = LoraLinear::from_linear(base, 4.0);
+/// let y1 = model.forward(dev.ones::<Rank1<5>>());
+/// model.merge();
+/// let y2 = model.forward(dev.ones::<Rank1<5>>());
+/// assert_eq!(y1.array(), y2.array());
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoraLinear<const I: usize, const O: usize, const R: usize, D: Device<f32> = Cpu> {
+    /// The frozen layer being adapted.
+    pub base: Linear<I, O, D>,
+
+    /// Down projection, shape (R, I)
+    pub lora_a: Tensor<Rank2<R, I>, f32, D>,
+
+    /// Up projection, shape (O, R)
+    pub lora_b: Tensor<Rank2<O, R>, f32, D>,
+
+    /// Multiplier of the update, usually `alpha / R`.
+    pub scale: f32,
+
+    /// Whether the update is currently merged into `base.weight`.
+    pub merged: bool,
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> LoraLinear<I, O, R, D> {
+    /// Wraps an existing (e.g. pretrained) [Linear] layer, with a scale of `alpha / R`.
+    pub fn from_linear(base: Linear<I, O, D>, alpha: f32) -> Self {
+        Self::try_from_linear(base, alpha).unwrap()
+    }
+
+    /// Fallible version of [LoraLinear::from_linear()]
+    pub fn try_from_linear(base: Linear<I, O, D>, alpha: f32) -> Result<Self, D::Err> {
+        let device = base.weight.device.clone();
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        let lora_a = device.try_sample(rand_distr::Uniform::new(-bound, bound))?;
+        let lora_b = device.try_zeros()?;
+        Ok(Self {
+            base,
+            lora_a,
+            lora_b,
+            scale: alpha / R as f32,
+            merged: false,
+        })
+    }
+
+    /// The low rank update `scale * lora_b * lora_a`, shape (O, I).
+    pub fn delta_weight(&self) -> Tensor<Rank2<O, I>, f32, D> {
+        self.lora_b.clone().matmul(self.lora_a.clone()) * self.scale
+    }
+
+    /// Adds the update into `base.weight`. Does nothing if already merged.
+    ///
+    /// While merged, [Self::lora_a] and [Self::lora_b] are not used in forward, so they
+    /// should not be trained.
+    pub fn merge(&mut self) {
+        if !self.merged {
+            self.base.weight = self.base.weight.clone() + self.delta_weight();
+            self.merged = true;
+        }
+    }
+
+    /// Subtracts the update from `base.weight`. Does nothing if not merged.
+    pub fn unmerge(&mut self) {
+        if self.merged {
+            self.base.weight = self.base.weight.clone() - self.delta_weight();
+            self.merged = false;
+        }
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> GradientUpdate<D, f32>
+    for LoraLinear<I, O, R, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.base.update(&mut AsBuffers(updater), unused)?;
+        self.lora_a.update(updater, unused)?;
+        self.lora_b.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> BuildModule<D, f32>
+    for LoraLinear<I, O, R, D>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Self::try_from_linear(BuildModule::try_build(device)?, R as f32)
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> ResetParams<D, f32>
+    for LoraLinear<I, O, R, D>
+{
+    /// Resets only the adapter, [Self::base] is left unchanged.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        self.unmerge();
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.lora_a.try_fill_with_distr(distr)?;
+        self.lora_b.try_fill_with_zeros()?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2>
+    for LoraLinear<I, O, R, D1>
+{
+    type Output = LoraLinear<I, O, R, D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        LoraLinear {
+            base: self.base.to_device(device),
+            lora_a: self.lora_a.to_device(device),
+            lora_b: self.lora_b.to_device(device),
+            scale: self.scale,
+            merged: self.merged,
+        }
+    }
+}
+
+macro_rules! lora_forward {
+    ([$($Dims:tt),*], $In:ty, $Hidden:ty, $Out:ty) => {
+        impl<$($Dims: Dim, )* const I: usize, const O: usize, const R: usize, D: Device<f32>, T: Tape<D>>
+            Module<Tensor<$In, f32, D, T>> for LoraLinear<I, O, R, D>
+        {
+            type Output = Tensor<$Out, f32, D, T>;
+            type Error = D::Err;
+            fn try_forward(&self, x: Tensor<$In, f32, D, T>) -> Result<Self::Output, D::Err> {
+                if self.merged {
+                    return self.base.try_forward(x);
+                }
+                let (x, tape) = x.split_tape();
+                let h: Tensor<$Hidden, f32, D, T> =
+                    x.clone().put_tape(tape).try_matmul(self.lora_a.retaped::<T>().try_permute()?)?;
+                let (delta, tape) = h
+                    .try_matmul(self.lora_b.retaped::<T>().try_permute()?)?
+                    .try_mul(self.scale)?
+                    .split_tape();
+                self.base.try_forward(x.put_tape(tape))?.try_add(delta)
+            }
+        }
+    };
+}
+
+lora_forward!([], Rank1<I>, Rank1<R>, Rank1<O>);
+lora_forward!([B], (B, Const<I>), (B, Const<R>), (B, Const<O>));
+lora_forward!([B, S], (B, S, Const<I>), (B, S, Const<R>), (B, S, Const<O>));
+
+impl<T, const I: usize, const O: usize, const R: usize, D: Device<f32>> ModuleMut<T>
+    for LoraLinear<I, O, R, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::tests::{CountingUpdater, SimpleUpdater},
+        tests::*,
+        unique_id::HasUniqueId,
+    };
+
+    #[test]
+    fn test_lora_initially_matches_base() {
+        let dev: TestDevice = Default::default();
+        let base: Linear<5, 3, _> = BuildModule::build(&dev);
+        let lora: LoraLinear<5, 3, 2, _> = LoraLinear::from_linear(base.clone(), 2.0);
+        let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        assert_close(&lora.forward(x.clone()).array(), &base.forward(x).array());
+    }
+
+    #[test]
+    fn test_lora_forward_backward() {
+        let dev: TestDevice = Default::default();
+        let mut lora: LoraLinear<3, 2, 1, _> = BuildModule::build(&dev);
+        lora.base.weight = dev.tensor([[1.0, 0.0, -1.0], [0.5, 0.5, 0.5]]);
+        lora.base.bias = dev.tensor([0.1, -0.1]);
+        lora.lora_a = dev.tensor([[1.0, 2.0, 3.0]]);
+        lora.lora_b = dev.tensor([[1.0], [-1.0]]);
+        lora.scale = 0.5;
+
+        let x = dev.tensor([1.0, 1.0, 2.0]);
+        let y = lora.forward(x.trace());
+        // base: [-1.0 + 0.1, 2.0 - 0.1], h = 9.0, delta = [4.5, -4.5]
+        assert_close(&y.array(), &[3.6, -2.6]);
+
+        let g = y.sum().backward();
+        assert_close(&g.get(&lora.lora_b).array(), &[[4.5], [4.5]]);
+        assert_close(&g.get(&lora.lora_a).array(), &[[0.0, 0.0, 0.0]]);
+        assert_close(&g.get(&x).array(), &[1.5, 0.5, -0.5]);
+    }
+
+    #[test]
+    fn test_lora_merge_unmerge() {
+        let dev: TestDevice = Default::default();
+        let mut lora: LoraLinear<4, 3, 2, _> = BuildModule::build(&dev);
+        lora.lora_b = dev.sample_normal();
+        let base_weight = lora.base.weight.array();
+        let x: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let y = lora.forward(x.clone());
+
+        lora.merge();
+        assert!(lora.merged);
+        assert_close(&lora.forward(x.clone()).array(), &y.array());
+
+        lora.unmerge();
+        assert!(!lora.merged);
+        assert_close(&lora.base.weight.array(), &base_weight);
+        assert_close(&lora.forward(x).array(), &y.array());
+    }
+
+    #[test]
+    fn test_lora_only_updates_adapter() {
+        let dev: TestDevice = Default::default();
+        let mut lora: LoraLinear<5, 3, 2, _> = BuildModule::build(&dev);
+        let mut g: SimpleUpdater = Default::default();
+        let mut unused = Default::default();
+        lora.update(&mut g, &mut unused).unwrap();
+        assert_eq!(&unused.ids, &[*lora.lora_a.id(), *lora.lora_b.id()]);
+
+        let mut counter: CountingUpdater = Default::default();
+        lora.update(&mut counter, &mut Default::default()).unwrap();
+        assert_eq!((counter.params, counter.buffers), (2, 2));
+    }
+}
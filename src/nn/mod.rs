@@ -101,39 +101,81 @@
 //! mlp.load_state_dict(state_dict)
 //! ```
 
+mod activation_stats;
 mod activations;
+mod adapter;
 mod add_into;
+mod attribution;
 mod batchnorm2d;
+mod channels_last;
+mod checkpointed;
 mod conv;
 mod dropout;
+mod dyn_model;
 mod embedding;
+mod film;
 mod flatten;
+mod frozen;
 mod generalized_residual;
+mod gradient_penalty;
 mod impl_module_for_tuples;
+mod kv_cache;
 mod layer_norm;
 mod linear;
+mod local_attention;
+mod lora;
 mod module;
+mod plan_gradients;
 mod pool2d;
+mod pool_adaptive;
 mod pool_global;
+mod position_bias;
+mod preprocess;
 mod repeated;
 mod residual;
+mod sequence_packing;
 mod split_into;
 mod transformer;
+mod unbiased_linear;
+mod upscale;
+mod vector_quantizer;
+mod weight_diff;
 
+pub use activation_stats::*;
 pub use activations::*;
+pub use adapter::*;
 pub use add_into::*;
+pub use attribution::*;
 pub use batchnorm2d::*;
+pub use channels_last::*;
+pub use checkpointed::*;
 pub use dropout::*;
+pub use dyn_model::*;
 pub use embedding::*;
+pub use film::*;
+pub use frozen::*;
 pub use generalized_residual::*;
+pub use gradient_penalty::*;
 pub use impl_module_for_tuples::*;
+pub use kv_cache::*;
 pub use layer_norm::*;
 pub use linear::*;
+pub use local_attention::*;
+pub use lora::*;
 pub use module::*;
+pub use plan_gradients::*;
+pub use pool_adaptive::*;
 pub use pool_global::*;
+pub use position_bias::*;
+pub use preprocess::*;
 pub use repeated::*;
 pub use residual::*;
+pub use sequence_packing::*;
 pub use split_into::*;
+pub use unbiased_linear::*;
+pub use upscale::*;
+pub use vector_quantizer::*;
+pub use weight_diff::*;
 
 #[cfg(feature = "nightly")]
 pub use conv::*;
@@ -172,4 +214,30 @@ mod tests {
             Ok(())
         }
     }
+
+    /// Counts the number of parameters and buffers visited.
+    #[derive(Default)]
+    pub struct CountingUpdater {
+        pub params: usize,
+        pub buffers: usize,
+    }
+
+    impl<D: DeviceStorage, E: Dtype> ParamUpdater<D, E> for CountingUpdater {
+        fn update_param<S: crate::shapes::Shape>(
+            &mut self,
+            _: &mut crate::tensor::Tensor<S, E, D>,
+            _: &mut crate::optim::UnusedTensors,
+        ) -> Result<(), <D>::Err> {
+            self.params += 1;
+            Ok(())
+        }
+
+        fn update_buffer<S: crate::shapes::Shape>(
+            &mut self,
+            _: &mut crate::tensor::Tensor<S, E, D>,
+        ) -> Result<(), <D>::Err> {
+            self.buffers += 1;
+            Ok(())
+        }
+    }
 }
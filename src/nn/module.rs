@@ -2,7 +2,7 @@ use crate::{optim::GradientUpdate, shapes::Dtype, tensor_ops::Device};
 
 #[cfg(feature = "cuda")]
 pub use crate::tensor::OnCuda;
-pub use crate::tensor::{OnCpu, OnDevice, ToDevice};
+pub use crate::tensor::{OnCpu, OnDevice, OnDtype, ToDevice, ToDtype};
 
 /// Immutable forward of `Input` that produces [Module::Output].
 /// See [ModuleMut] for mutable forward.
@@ -10,10 +10,19 @@ pub trait Module<Input> {
     /// The type that this unit produces given `Input`.
     type Output;
 
+    /// The error that can happen during the forward pass, usually the device's error.
+    type Error: std::fmt::Debug;
+
     /// Forward `Input` through the module and produce [Module::Output].
     ///
     /// **See [ModuleMut::forward_mut()] for version that can mutate `self`.**
-    fn forward(&self, input: Input) -> Self::Output;
+    fn forward(&self, input: Input) -> Self::Output {
+        self.try_forward(input).unwrap()
+    }
+
+    /// Fallible version of [Module::forward()]. Errors like runtime shape mismatches
+    /// are returned instead of panicking.
+    fn try_forward(&self, input: Input) -> Result<Self::Output, Self::Error>;
 }
 
 /// Mutable forward of `Input` that produces [ModuleMut::Output].
@@ -22,10 +31,63 @@ pub trait ModuleMut<Input> {
     /// The type that this unit produces given `Input`.
     type Output;
 
+    /// The error that can happen during the forward pass, usually the device's error.
+    type Error: std::fmt::Debug;
+
     /// Forward `Input` through the module and produce [ModuleMut::Output].
     ///
     /// **See [Module::forward()] for immutable version**
-    fn forward_mut(&mut self, input: Input) -> Self::Output;
+    fn forward_mut(&mut self, input: Input) -> Self::Output {
+        self.try_forward_mut(input).unwrap()
+    }
+
+    /// Fallible version of [ModuleMut::forward_mut()].
+    fn try_forward_mut(&mut self, input: Input) -> Result<Self::Output, Self::Error>;
+}
+
+/// Forward of `Input` using externally supplied `Params` instead of the parameters
+/// stored in the module. Produces [FunctionalModule::Output].
+///
+/// The parameters are regular tensors that can carry a tape, so they can be produced by
+/// another network (e.g. a hypernetwork), and gradients flow back into whatever
+/// produced them. The parameters stored in `self` are **not** used, only
+/// non-learnable configuration like [super::LayerNorm1D::epsilon].
+///
+/// Tuples implement this with a tuple of each module's `Params`, and modules
+/// without parameters (see [ZeroSizedModule]) take `()`.
+///
+/// Example where a hypernetwork generates the weights of a linear layer from `z`:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let hyper: (Linear<2, 4>, Linear<2, 4>) = BuildModule::build(&dev);
+/// let target: Linear<3, 4> = BuildModule::build(&dev);
+/// let base: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+///
+/// let z: Tensor<Rank1<2>, f32, _> = dev.sample_normal();
+/// let row_scales = hyper.0.forward(z.trace());
+/// let weight = row_scales.broadcast::<Rank2<4, 3>, _>() * base;
+/// let bias = hyper.1.forward(z.trace());
+///
+/// let x: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+/// let y = target.forward_with((weight, bias), x.retaped());
+/// let grads = y.square().mean().backward();
+/// assert_ne!(grads.get(&z).array(), [0.0; 2]);
+/// ```
+pub trait FunctionalModule<Params, Input> {
+    /// The type that this unit produces given `Params` & `Input`.
+    type Output;
+
+    /// The error that can happen during the forward pass, usually the device's error.
+    type Error: std::fmt::Debug;
+
+    /// Forward `Input` through the module using `params`, and produce [FunctionalModule::Output].
+    fn forward_with(&self, params: Params, input: Input) -> Self::Output {
+        self.try_forward_with(params, input).unwrap()
+    }
+
+    /// Fallible version of [FunctionalModule::forward_with()].
+    fn try_forward_with(&self, params: Params, input: Input) -> Result<Self::Output, Self::Error>;
 }
 
 /// Something that can be built. Related to [BuildOnDevice]
@@ -72,7 +134,7 @@ pub trait ResetParams<D: Device<E>, E: Dtype>: Sized {
 }
 
 /// Marker trait for modules with no updatable parameters. These have
-/// blanket impls for [ResetParams], [GradientUpdate], and [ModuleMut]
+/// blanket impls for [ResetParams], [GradientUpdate], [FunctionalModule], and [ModuleMut]
 pub trait ZeroSizedModule: Default {}
 
 impl<T: ZeroSizedModule, D: Device<E>, E: Dtype> ResetParams<D, E> for T {
@@ -88,6 +150,21 @@ impl<T: ZeroSizedModule + Clone, D> ToDevice<D> for T {
     }
 }
 
+impl<T: ZeroSizedModule + Clone, E> ToDtype<E> for T {
+    type Output = T;
+    fn to_dtype(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<T: ZeroSizedModule + Module<Input>, Input> FunctionalModule<(), Input> for T {
+    type Output = T::Output;
+    type Error = T::Error;
+    fn try_forward_with(&self, _: (), input: Input) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
 impl<T: ZeroSizedModule, D: Device<E>, E: Dtype> GradientUpdate<D, E> for T {
     fn update<U>(&mut self, _: &mut U, _: &mut crate::optim::UnusedTensors) -> Result<(), <D>::Err>
     where
@@ -106,7 +183,8 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-    fn forward_mut(&mut self, input: T) -> Self::Output {
-        self.forward(input)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
     }
 }
@@ -2,7 +2,13 @@ use super::{
     npz::{LoadFromNpz, SaveToNpz},
     *,
 };
-use crate::{tensor::numpy::NpzError, tensor_ops::Device};
+#[cfg(feature = "nightly")]
+use crate::shapes::Const;
+use crate::{
+    shapes::Rank1,
+    tensor::{numpy::NpzError, AsArray, Cpu, Tensor, TensorFromArray, ZerosTensor},
+    tensor_ops::{Device, UpsampleMode},
+};
 use std::format;
 use std::io::{Read, Seek, Write};
 use zip::{result::ZipResult, ZipArchive, ZipWriter};
@@ -41,8 +47,85 @@ impl<
         const K: usize,
         const S: usize,
         const P: usize,
+        const G: usize,
+        D: Device<f32>,
+    > SaveToNpz for Conv2D<I, O, K, S, P, G, D>
+where
+    Const<{ I / G }>: Sized,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D: Device<f32>,
+    > LoadFromNpz for Conv2D<I, O, K, S, P, G, D>
+where
+    Const<{ I / G }>: Sized,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D: Device<f32>,
+    > SaveToNpz for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    Const<{ I / G }>: Sized,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const G: usize,
+        D: Device<f32>,
+    > LoadFromNpz for UnbiasedConv2D<I, O, K, S, P, G, D>
+where
+    Const<{ I / G }>: Sized,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
         D: Device<f32>,
-    > SaveToNpz for Conv2D<I, O, K, S, P, D>
+    > SaveToNpz for Conv1D<I, O, K, S, P, L, D>
 {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
@@ -58,8 +141,9 @@ impl<
         const K: usize,
         const S: usize,
         const P: usize,
+        const L: usize,
         D: Device<f32>,
-    > LoadFromNpz for Conv2D<I, O, K, S, P, D>
+    > LoadFromNpz for Conv1D<I, O, K, S, P, L, D>
 {
     fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
         self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
@@ -68,6 +152,80 @@ impl<
     }
 }
 
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D> SaveToNpz
+    for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const I: usize, const O: usize, const K: usize, const S: usize, const P: usize, D> LoadFromNpz
+    for Conv3D<I, O, K, S, P, D>
+where
+    D: Device<f32>,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D: Device<f32>,
+    > SaveToNpz for ConvTranspose2D<I, O, K, S, P, OP, D>
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const I: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D: Device<f32>,
+    > LoadFromNpz for ConvTranspose2D<I, O, K, S, P, OP, D>
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for ChannelsLast<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for ChannelsLast<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
 impl<F: SaveToNpz, R: SaveToNpz> SaveToNpz for GeneralizedResidual<F, R> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.f.write(&format!("{p}.f"), w)?;
@@ -114,6 +272,99 @@ impl<const I: usize, const O: usize, D: Device<f32>> LoadFromNpz for Linear<I, O
     }
 }
 
+impl<const I: usize, const O: usize, D: Device<f32>> SaveToNpz for UnbiasedLinear<I, O, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> LoadFromNpz for UnbiasedLinear<I, O, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> SaveToNpz
+    for LoraLinear<I, O, R, D>
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.base.write(p, w)?;
+        self.lora_a.write_to_npz(w, format!("{p}lora_a.npy"))?;
+        self.lora_b.write_to_npz(w, format!("{p}lora_b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const I: usize, const O: usize, const R: usize, D: Device<f32>> LoadFromNpz
+    for LoraLinear<I, O, R, D>
+{
+    fn read<R2: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R2>) -> Result<(), NpzError> {
+        self.base.read(p, r)?;
+        self.lora_a.read_from_npz(r, format!("{p}lora_a.npy"))?;
+        self.lora_b.read_from_npz(r, format!("{p}lora_b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> SaveToNpz for FiLM<M, C, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.scale.write(&format!("{p}scale."), w)?;
+        self.shift.write(&format!("{p}shift."), w)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const C: usize, D: Device<f32>> LoadFromNpz for FiLM<M, C, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.scale.read(&format!("{p}scale."), r)?;
+        self.shift.read(&format!("{p}shift."), r)?;
+        Ok(())
+    }
+}
+
+impl<const C: usize, D: Device<f32>> SaveToNpz for Normalize<C, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.mean.write_to_npz(w, format!("{p}mean.npy"))?;
+        self.std.write_to_npz(w, format!("{p}std.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const C: usize, D: Device<f32>> LoadFromNpz for Normalize<C, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.mean.read_from_npz(r, format!("{p}mean.npy"))?;
+        self.std.read_from_npz(r, format!("{p}std.npy"))?;
+        Ok(())
+    }
+}
+
+/// [Resize::mode] is saved as `[0, 0]` for nearest and `[1, align_corners]` for bilinear.
+impl<const OH: usize, const OW: usize> SaveToNpz for Resize<OH, OW> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        let mode = match self.mode {
+            UpsampleMode::Nearest => [0.0, 0.0],
+            UpsampleMode::Bilinear { align_corners } => [1.0, align_corners as u8 as f32],
+        };
+        Cpu::default()
+            .tensor(mode)
+            .write_to_npz(w, format!("{p}mode.npy"))
+    }
+}
+
+impl<const OH: usize, const OW: usize> LoadFromNpz for Resize<OH, OW> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        let mut mode: Tensor<Rank1<2>, f32, Cpu> = Cpu::default().zeros();
+        mode.read_from_npz(r, format!("{p}mode.npy"))?;
+        self.mode = match mode.array() {
+            [0.0, _] => UpsampleMode::Nearest,
+            [_, align] => UpsampleMode::Bilinear {
+                align_corners: align != 0.0,
+            },
+        };
+        Ok(())
+    }
+}
+
 macro_rules! tuple_npz_impl {
     ([$($name:ident),+], [$($idx:tt),+]) => {
 impl<$($name: SaveToNpz),+> SaveToNpz for ($($name,)+) {
@@ -156,6 +407,172 @@ impl<T: LoadFromNpz, const N: usize> LoadFromNpz for Repeated<T, N> {
     }
 }
 
+impl<T: SaveToNpz, const N: usize> SaveToNpz for TiedRepeated<T, N> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.module.write(p, w)
+    }
+}
+
+impl<T: LoadFromNpz, const N: usize> LoadFromNpz for TiedRepeated<T, N> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.module.read(p, r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for Frozen<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Frozen<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
+impl<M: SaveToNpz> SaveToNpz for Checkpointed<M> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.0.write(p, w)
+    }
+}
+
+impl<M: LoadFromNpz> LoadFromNpz for Checkpointed<M> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.0.read(p, r)
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynLinear<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.weight.write_to_npz(w, format!("{p}weight.npy"))?;
+        self.bias.write_to_npz(w, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynLinear<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.weight.read_from_npz(r, format!("{p}weight.npy"))?;
+        self.bias.read_from_npz(r, format!("{p}bias.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynLoraLinear<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.base.write(p, w)?;
+        self.lora_a.write_to_npz(w, format!("{p}lora_a.npy"))?;
+        self.lora_b.write_to_npz(w, format!("{p}lora_b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynLoraLinear<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.base.read(p, r)?;
+        self.lora_a.read_from_npz(r, format!("{p}lora_a.npy"))?;
+        self.lora_b.read_from_npz(r, format!("{p}lora_b.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynLayerNorm<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.gamma.write_to_npz(w, format!("{p}gamma.npy"))?;
+        self.beta.write_to_npz(w, format!("{p}beta.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynLayerNorm<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.gamma.read_from_npz(r, format!("{p}gamma.npy"))?;
+        self.beta.read_from_npz(r, format!("{p}beta.npy"))?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynMultiHeadAttention<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.w_q.write(&format!("{p}w_q."), w)?;
+        self.w_k.write(&format!("{p}w_k."), w)?;
+        self.w_v.write(&format!("{p}w_v."), w)?;
+        self.w_o.write(&format!("{p}w_o."), w)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynMultiHeadAttention<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.w_q.read(&format!("{p}w_q."), r)?;
+        self.w_k.read(&format!("{p}w_k."), r)?;
+        self.w_v.read(&format!("{p}w_v."), r)?;
+        self.w_o.read(&format!("{p}w_o."), r)?;
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> SaveToNpz for DynModel<D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        for (i, layer) in self.layers.iter().enumerate() {
+            let p = format!("{p}{i}.");
+            match layer {
+                DynLayer::Linear(m) => m.write(&p, w)?,
+                DynLayer::LayerNorm(m) => m.write(&p, w)?,
+                DynLayer::MultiHeadAttention(m) => m.write(&p, w)?,
+                DynLayer::Residual(m) | DynLayer::Sequential(m) => m.write(&p, w)?,
+                DynLayer::LoraLinear(m) => m.write(&p, w)?,
+                DynLayer::ReLU
+                | DynLayer::GeLU
+                | DynLayer::Tanh
+                | DynLayer::Sigmoid
+                | DynLayer::Hook(_)
+                | DynLayer::GradHook(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<D: Device<f32>> LoadFromNpz for DynModel<D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            let p = format!("{p}{i}.");
+            match layer {
+                DynLayer::Linear(m) => m.read(&p, r)?,
+                DynLayer::LayerNorm(m) => m.read(&p, r)?,
+                DynLayer::MultiHeadAttention(m) => m.read(&p, r)?,
+                DynLayer::Residual(m) | DynLayer::Sequential(m) => m.read(&p, r)?,
+                DynLayer::LoraLinear(m) => m.read(&p, r)?,
+                DynLayer::ReLU
+                | DynLayer::GeLU
+                | DynLayer::Tanh
+                | DynLayer::Sigmoid
+                | DynLayer::Hook(_)
+                | DynLayer::GradHook(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>> SaveToNpz for Adapter<M, B, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.down.write(&format!("{p}down."), w)?;
+        self.up.write(&format!("{p}up."), w)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const B: usize, D: Device<f32>> LoadFromNpz for Adapter<M, B, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.down.read(&format!("{p}down."), r)?;
+        self.up.read(&format!("{p}up."), r)?;
+        Ok(())
+    }
+}
+
 impl<F: SaveToNpz> SaveToNpz for Residual<F> {
     fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
         self.0.write(&format!("{p}.0"), w)
@@ -296,6 +713,64 @@ impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f
     }
 }
 
+#[cfg(feature = "nightly")]
+impl<const Q: usize, const C: usize, const H: usize, const K: usize, const V: usize, D> SaveToNpz
+    for CrossAttention<Q, C, H, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.w_q.write(&format!("{p}w_q."), w)?;
+        self.w_k.write(&format!("{p}w_k."), w)?;
+        self.w_v.write(&format!("{p}w_v."), w)?;
+        self.w_o.write(&format!("{p}w_o."), w)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const Q: usize, const C: usize, const H: usize, const K: usize, const V: usize, D> LoadFromNpz
+    for CrossAttention<Q, C, H, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.w_q.read(&format!("{p}w_q."), r)?;
+        self.w_k.read(&format!("{p}w_k."), r)?;
+        self.w_v.read(&format!("{p}w_v."), r)?;
+        self.w_o.read(&format!("{p}w_o."), r)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D> SaveToNpz
+    for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.attn.write(p, w)?;
+        self.prefix_k.write_to_npz(w, format!("{p}prefix_k.npy"))?;
+        self.prefix_v.write_to_npz(w, format!("{p}prefix_v.npy"))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D> LoadFromNpz
+    for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.attn.read(p, r)?;
+        self.prefix_k.read_from_npz(r, format!("{p}prefix_k.npy"))?;
+        self.prefix_v.read_from_npz(r, format!("{p}prefix_v.npy"))?;
+        Ok(())
+    }
+}
+
 #[cfg(feature = "nightly")]
 impl<
         const M: usize,
@@ -330,11 +805,31 @@ impl<
     }
 }
 
+impl<const K: usize, const DIM: usize, D: Device<f32>> SaveToNpz for VectorQuantizer<K, DIM, D> {
+    fn write<W: Write + Seek>(&self, p: &str, w: &mut ZipWriter<W>) -> ZipResult<()> {
+        self.codebook.write_to_npz(w, format!("{p}codebook.npy"))?;
+        self.cluster_size
+            .write_to_npz(w, format!("{p}cluster_size.npy"))?;
+        self.code_sum.write_to_npz(w, format!("{p}code_sum.npy"))?;
+        Ok(())
+    }
+}
+
+impl<const K: usize, const DIM: usize, D: Device<f32>> LoadFromNpz for VectorQuantizer<K, DIM, D> {
+    fn read<R: Read + Seek>(&mut self, p: &str, r: &mut ZipArchive<R>) -> Result<(), NpzError> {
+        self.codebook.read_from_npz(r, format!("{p}codebook.npy"))?;
+        self.cluster_size
+            .read_from_npz(r, format!("{p}cluster_size.npy"))?;
+        self.code_sum.read_from_npz(r, format!("{p}code_sum.npy"))?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         shapes::*,
-        tensor::{AsArray, SampleTensor, Tensor},
+        tensor::{AsArray, AsVec, SampleTensor, Tensor},
         tensor_ops::Device,
         tests::TestDevice,
     };
@@ -398,6 +893,30 @@ mod tests {
         test_save_load::<Rank3<2, 8, 8>, f32, TestDevice, T>(&dev);
     }
 
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_save_load_conv1d() {
+        type T = Conv1D<2, 4, 3, 1, 1, 2>;
+        let dev: TestDevice = Default::default();
+        test_save_load::<Rank2<2, 8>, f32, TestDevice, T>(&dev);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_save_load_conv3d() {
+        type T = Conv3D<2, 4, 3, 1, 1>;
+        let dev: TestDevice = Default::default();
+        test_save_load::<Rank4<2, 3, 4, 4>, f32, TestDevice, T>(&dev);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_save_load_conv_transpose2d() {
+        type T = ConvTranspose2D<2, 4, 3, 2, 1, 1>;
+        let dev: TestDevice = Default::default();
+        test_save_load::<Rank3<2, 4, 4>, f32, TestDevice, T>(&dev);
+    }
+
     #[test]
     fn test_save_load_generalized_residual() {
         let dev: TestDevice = Default::default();
@@ -414,6 +933,161 @@ mod tests {
         test_save_load::<Rank1<5>, f32, TestDevice, (T, T)>(&dev);
     }
 
+    #[test]
+    fn test_save_load_unbiased_linear() {
+        let dev: TestDevice = Default::default();
+        type T = UnbiasedLinear<5, 5>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+        test_save_load::<Rank1<5>, f32, TestDevice, (T, T)>(&dev);
+
+        // the weights of a biased linear can be loaded
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let linear = Linear::<5, 5>::build_on_device(&dev);
+        linear.save(file.path()).expect("");
+        let mut unbiased = T::build_on_device(&dev);
+        unbiased.load(file.path()).expect("");
+        assert_eq!(unbiased.weight.array(), linear.weight.array());
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn test_save_load_unbiased_conv() {
+        type T = UnbiasedConv2D<2, 4, 3>;
+        let dev: TestDevice = Default::default();
+        test_save_load::<Rank3<2, 8, 8>, f32, TestDevice, T>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_adapter() {
+        let dev: TestDevice = Default::default();
+        type T = (Frozen<Linear<5, 5>>, Adapter<5, 2>);
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_checkpointed() {
+        let dev: TestDevice = Default::default();
+        type T = CheckpointedRepeated<(Linear<5, 5>, ReLU), 3>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_dyn_model() {
+        let dev: TestDevice = Default::default();
+        let config = ModelConfig {
+            input_dim: 4,
+            layers: std::vec![
+                LayerConfig::Linear { inp: 4, out: 6 },
+                LayerConfig::Residual(std::vec![
+                    LayerConfig::MultiHeadAttention {
+                        embed_dim: 6,
+                        num_heads: 2
+                    },
+                    LayerConfig::LayerNorm { dim: 6 },
+                ]),
+                LayerConfig::GeLU,
+                LayerConfig::Repeat {
+                    times: 2,
+                    layers: std::vec![LayerConfig::Linear { inp: 6, out: 6 }],
+                },
+            ],
+        };
+        let x: Tensor<(usize, usize), f32, _> = dev.sample_like(&(3, 4), StandardNormal);
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let saved = config.build_on_device(&dev);
+        let mut loaded = config.build_on_device(&dev);
+
+        let y = saved.forward(x.clone());
+        assert_ne!(loaded.forward(x.clone()).as_vec(), y.as_vec());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+
+        assert_eq!(loaded.forward(x).as_vec(), y.as_vec());
+    }
+
+    #[test]
+    fn test_save_load_lora_linear() {
+        let dev: TestDevice = Default::default();
+        type T = LoraLinear<5, 5, 2>;
+        test_save_load::<Rank1<5>, f32, TestDevice, T>(&dev);
+        test_save_load::<Rank1<5>, f32, TestDevice, (T, T)>(&dev);
+    }
+
+    #[test]
+    fn test_save_load_film() {
+        let dev: TestDevice = Default::default();
+        type Model = FiLM<4, 3>;
+
+        let x: Tensor<Rank3<3, 2, 2>, f32, _> = dev.sample_normal();
+        let cond: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let saved = Model::build_on_device(&dev);
+        let mut loaded = Model::build_on_device(&dev);
+
+        let y = saved.forward((x.clone(), cond.clone()));
+        assert_ne!(loaded.forward((x.clone(), cond.clone())).array(), y.array());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.forward((x, cond)).array(), y.array());
+    }
+
+    #[test]
+    fn test_save_load_preprocessing() {
+        let dev: TestDevice = Default::default();
+        type Model = (Normalize<3>, Resize<5, 5>, CenterCrop<4, 4>);
+
+        let x: Tensor<Rank4<2, 3, 7, 6>, f32, _> = dev.sample_normal();
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved = Model::build_on_device(&dev);
+        let mut loaded = Model::build_on_device(&dev);
+        saved.0.mean = dev.tensor([0.485, 0.456, 0.406]);
+        saved.0.std = dev.tensor([0.229, 0.224, 0.225]);
+        saved.1.mode = UpsampleMode::Bilinear {
+            align_corners: true,
+        };
+
+        let y = saved.forward(x.clone());
+        assert_ne!(loaded.forward(x.clone()).array(), y.array());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+        assert_eq!(loaded.0.mean.array(), saved.0.mean.array());
+        assert_eq!(loaded.0.std.array(), saved.0.std.array());
+        assert_eq!(loaded.forward(x).array(), y.array());
+    }
+
+    #[test]
+    fn test_save_load_resize_modes() {
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        for mode in [
+            UpsampleMode::Nearest,
+            UpsampleMode::Bilinear {
+                align_corners: false,
+            },
+            UpsampleMode::Bilinear {
+                align_corners: true,
+            },
+        ] {
+            let saved: Resize<2, 2> = Resize { mode };
+            let mut loaded: Resize<2, 2> = Resize {
+                mode: UpsampleMode::Bilinear {
+                    align_corners: true,
+                },
+            };
+            if mode == loaded.mode {
+                loaded.mode = UpsampleMode::Nearest;
+            }
+            saved.save(file.path()).expect("");
+            loaded.load(file.path()).expect("");
+            assert_eq!(loaded.mode, mode);
+        }
+    }
+
     #[test]
     fn test_save_load_tuple() {
         let dev: TestDevice = Default::default();
@@ -447,6 +1121,30 @@ mod tests {
         assert_eq!(loaded.forward(x).array(), y.array());
     }
 
+    #[test]
+    fn test_save_load_vector_quantizer() {
+        type M = VectorQuantizer<5, 3>;
+        let dev: TestDevice = Default::default();
+        let x = dev.sample_normal::<Rank2<4, 3>>();
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut saved = M::build_on_device(&dev);
+        let mut loaded = M::build_on_device(&dev);
+
+        let _ = saved.forward_mut(x.trace());
+        let y = saved.forward(x.clone());
+
+        assert_ne!(loaded.forward(x.clone()).array(), y.array());
+
+        saved.save(file.path()).expect("");
+        loaded.load(file.path()).expect("");
+
+        assert_eq!(loaded.forward(x).array(), y.array());
+        assert_eq!(loaded.cluster_size.array(), saved.cluster_size.array());
+        assert_eq!(loaded.code_sum.array(), saved.code_sum.array());
+    }
+
     #[test]
     fn test_save_load_repeated() {
         type T = Repeated<Linear<3, 3>, 4>;
@@ -455,6 +1153,14 @@ mod tests {
         test_save_load::<Rank1<3>, f32, TestDevice, (T, T)>(&dev);
     }
 
+    #[test]
+    fn test_save_load_tied_repeated() {
+        type T = TiedRepeated<Linear<3, 3>, 4>;
+        let dev: TestDevice = Default::default();
+        test_save_load::<Rank1<3>, f32, TestDevice, T>(&dev);
+        test_save_load::<Rank1<3>, f32, TestDevice, (T, T)>(&dev);
+    }
+
     #[test]
     fn test_save_load_residual() {
         type T = Residual<Linear<5, 5>>;
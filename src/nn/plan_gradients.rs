@@ -0,0 +1,111 @@
+use crate::{
+    gradients::{Gradients, OwnedTape},
+    shapes::*,
+    tensor::Tensor,
+    tensor_ops::{Backward, Device, SumTo},
+};
+
+use super::Module;
+
+/// Preallocates every gradient needed to train `model` on inputs with `shape`, by
+/// running a forward & backward pass on zeros once.
+///
+/// The returned [Gradients] only contain spare buffers (see [Gradients::recycle()]).
+/// Passing them to [Tensor::trace_into()] means the backward pass reuses them
+/// instead of allocating gradients. Combined with [crate::optim::Optimizer::recycled_gradients()],
+/// the same buffers are used for every training step, as long as the shapes don't change.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<5, 16>, ReLU, Linear<16, 2>);
+/// let mut model = Model::build_on_device(&dev);
+/// let mut opt = Sgd::new(&model, Default::default());
+/// let mut grads = plan_gradients(&model, &dev, &Rank2::<4, 5>::default());
+/// for _ in 0..10 {
+///     let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+///     let loss = model.forward(x.traced_into(grads)).square().mean();
+///     opt.update(&mut model, loss.backward()).unwrap();
+///     grads = opt.recycled_gradients();
+/// }
+/// ```
+pub fn plan_gradients<M, S: Shape, O: Shape, E: Dtype, D: Device<E>>(
+    model: &M,
+    device: &D,
+    shape: &S,
+) -> Gradients
+where
+    M: Module<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<O, E, D, OwnedTape<D>>>,
+    M::Error: Into<D::Err>,
+{
+    try_plan_gradients(model, device, shape).unwrap()
+}
+
+/// Fallible version of [plan_gradients()]
+pub fn try_plan_gradients<M, S: Shape, O: Shape, E: Dtype, D: Device<E>>(
+    model: &M,
+    device: &D,
+    shape: &S,
+) -> Result<Gradients, D::Err>
+where
+    M: Module<Tensor<S, E, D, OwnedTape<D>>, Output = Tensor<O, E, D, OwnedTape<D>>>,
+    M::Error: Into<D::Err>,
+{
+    let x: Tensor<S, E, D> = device.try_zeros_like(shape)?;
+    let y = model.try_forward(x.traced()).map_err(Into::into)?;
+    let mut grads = y.try_sum()?.try_backward()?;
+    grads.recycle();
+    Ok(grads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, optim::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_planned_training_matches_unplanned() {
+        let dev: TestDevice = Default::default();
+        type Model = (Linear<3, 8>, ReLU, Linear<8, 2>);
+        let mut m1 = Model::build_on_device(&dev);
+        let mut m2 = m1.clone();
+        let mut opt1 = Sgd::new(&m1, Default::default());
+        let mut opt2 = Sgd::new(&m2, Default::default());
+
+        let mut grads = plan_gradients(&m2, &dev, &Rank2::<4, 3>::default());
+        let mut weight_grad_ptr = None;
+        for _ in 0..5 {
+            let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+
+            let loss = m1.forward(x.trace()).square().mean();
+            opt1.update(&mut m1, loss.backward()).unwrap();
+
+            let loss = m2.forward(x.traced_into(grads)).square().mean();
+            let g = loss.backward();
+            let ptr = g.get(&m2.0.weight).data.as_ptr();
+            assert_eq!(*weight_grad_ptr.get_or_insert(ptr), ptr);
+            opt2.update(&mut m2, g).unwrap();
+            grads = opt2.recycled_gradients();
+
+            assert_close(&m1.0.weight.array(), &m2.0.weight.array());
+            assert_close(&m1.2.bias.array(), &m2.2.bias.array());
+        }
+    }
+
+    #[test]
+    fn test_recycled_buffers_are_zeroed() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0, 3.0]);
+        let mut grads = a.trace().sum().backward();
+        assert_eq!(grads.get(&a).array(), [1.0; 3]);
+        let ptr = grads.get(&a).data.as_ptr();
+        grads.recycle();
+
+        // a different tensor with the same shape reuses the buffer of `a`
+        let b = dev.tensor([4.0, 5.0, 6.0]);
+        let grads = b.trace_into(grads).sum().backward();
+        assert_eq!(grads.get(&b).array(), [1.0; 3]);
+        assert_eq!(grads.get(&b).data.as_ptr(), ptr);
+    }
+}
@@ -1,5 +1,5 @@
 #[cfg(feature = "nightly")]
-use crate::tensor_ops::{ConstAvgPool2D, ConstMaxPool2D, ConstMinPool2D};
+use crate::tensor_ops::{ConstAvgPool2D, ConstMaxPool2D, ConstMaxUnpool2D, ConstMinPool2D};
 
 use crate::{shapes::Dtype, tensor_ops::Device};
 
@@ -52,8 +52,9 @@ macro_rules! impl_pools {
             for $PoolTy<K, S, P>
         {
             type Output = Img::Output;
-            fn forward(&self, x: Img) -> Self::Output {
-                x.try_pool2d().unwrap()
+            type Error = Img::Err;
+            fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+                x.try_pool2d()
             }
         }
     };
@@ -63,11 +64,43 @@ impl_pools!(AvgPool2D, ConstAvgPool2D);
 impl_pools!(MaxPool2D, ConstMaxPool2D);
 impl_pools!(MinPool2D, ConstMinPool2D);
 
+/// Max unpool that operates on images (3d) and batches of images (4d), using the indices from
+/// [crate::tensor_ops::TryMaxPool2DWithIndices]. Takes `(images, indices)` as input, and
+/// puts every value at the position of its index in zero images of size `(H, W)`.
+///
+/// Generics:
+/// - `H`: The height of the unpooled images, i.e. the height of the images that were pooled.
+/// - `W`: The width of the unpooled images, i.e. the width of the images that were pooled.
+#[derive(Debug, Default, Clone)]
+pub struct MaxUnpool2D<const H: usize, const W: usize>;
+
+impl<const H: usize, const W: usize> ZeroSizedModule for MaxUnpool2D<H, W> {}
+impl<const H: usize, const W: usize> NonMutableModule for MaxUnpool2D<H, W> {}
+
+impl<const H: usize, const W: usize, D: Device<E>, E: Dtype> BuildModule<D, E>
+    for MaxUnpool2D<H, W>
+{
+    fn try_build(_: &D) -> Result<Self, <D>::Err> {
+        Ok(Default::default())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<const H: usize, const W: usize, Img: ConstMaxUnpool2D<H, W>> Module<(Img, Img::Indices)>
+    for MaxUnpool2D<H, W>
+{
+    type Output = Img::Output;
+    type Error = Img::Err;
+    fn try_forward(&self, (x, indices): (Img, Img::Indices)) -> Result<Self::Output, Img::Err> {
+        x.try_unpool2d(indices)
+    }
+}
+
 #[cfg(feature = "nightly")]
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{shapes::*, tensor::*, tests::TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::TestDevice};
 
     #[test]
     fn test_max_forward_3d_sizes() {
@@ -191,4 +224,17 @@ mod tests {
         let _: Tensor<Rank3<1, 6, 6>, _, _> = <(A, A)>::default().forward(x.clone());
         let _: Tensor<Rank3<1, 8, 8>, _, _> = <(A, A, B)>::default().forward(x.clone());
     }
+
+    #[test]
+    fn test_max_unpool_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.sample_normal::<Rank4<5, 3, 10, 9>>();
+        let (y, idx) = x.clone().max_pool2d_with_indices::<2, 2, 0>();
+        let _: Tensor<Rank4<5, 3, 5, 4>, _, _> = y.clone();
+        let _: Tensor<Rank4<5, 3, 10, 9>, _, _> = MaxUnpool2D::<10, 9>.forward((y, idx));
+
+        let x = dev.sample_normal::<Rank3<3, 10, 10>>();
+        let (y, idx) = x.max_pool2d_with_indices::<3, 3, 1>();
+        let _: Tensor<Rank3<3, 10, 10>, _, _> = MaxUnpool2D::<10, 10>.forward((y, idx));
+    }
 }
@@ -0,0 +1,120 @@
+use crate::{
+    shapes::Dtype,
+    tensor_ops::{ConstAdaptiveAvgPool2D, ConstAdaptiveMaxPool2D, Device},
+};
+
+use super::{BuildModule, Module, NonMutableModule, ZeroSizedModule};
+
+/// Average pool that operates on images (3d) and batches of images (4d) of any height & width,
+/// and always produces images of size `(OH, OW)`. The input is split into `OH * OW` windows
+/// of (nearly) equal size, and each one reduces to its average.
+///
+/// Useful in front of classification heads, so they accept any input size.
+///
+/// **Pytorch equivalent**: `torch.nn.AdaptiveAvgPool2d((OH, OW))`
+///
+/// Generics:
+/// - `OH`: The height of the output images.
+/// - `OW`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: AdaptiveAvgPool2D<2, 3> = Default::default();
+/// let _: Tensor<Rank3<5, 2, 3>, f32, _> = m.forward(dev.zeros::<Rank3<5, 16, 8>>());
+/// let x: Tensor<(Const<10>, Const<5>, usize, usize), f32, _> = dev.zeros_like(&(Const, Const, 7, 9));
+/// let _: Tensor<Rank4<10, 5, 2, 3>, f32, _> = m.forward(x);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AdaptiveAvgPool2D<const OH: usize, const OW: usize>;
+
+/// Max pool that operates on images (3d) and batches of images (4d) of any height & width,
+/// and always produces images of size `(OH, OW)`. The input is split into `OH * OW` windows
+/// of (nearly) equal size, and each one reduces to its maximum.
+///
+/// **Pytorch equivalent**: `torch.nn.AdaptiveMaxPool2d((OH, OW))`
+///
+/// Generics:
+/// - `OH`: The height of the output images.
+/// - `OW`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: AdaptiveMaxPool2D<2, 3> = Default::default();
+/// let _: Tensor<Rank3<5, 2, 3>, f32, _> = m.forward(dev.zeros::<Rank3<5, 16, 8>>());
+/// let x: Tensor<(Const<10>, Const<5>, usize, usize), f32, _> = dev.zeros_like(&(Const, Const, 7, 9));
+/// let _: Tensor<Rank4<10, 5, 2, 3>, f32, _> = m.forward(x);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AdaptiveMaxPool2D<const OH: usize, const OW: usize>;
+
+macro_rules! impl_pools {
+    ($PoolTy:tt, $Trait:ident) => {
+        impl<const OH: usize, const OW: usize> ZeroSizedModule for $PoolTy<OH, OW> {}
+        impl<const OH: usize, const OW: usize> NonMutableModule for $PoolTy<OH, OW> {}
+
+        impl<const OH: usize, const OW: usize, D: Device<E>, E: Dtype> BuildModule<D, E>
+            for $PoolTy<OH, OW>
+        {
+            fn try_build(_: &D) -> Result<Self, <D>::Err> {
+                Ok(Default::default())
+            }
+        }
+
+        impl<const OH: usize, const OW: usize, Img: $Trait<OH, OW>> Module<Img>
+            for $PoolTy<OH, OW>
+        {
+            type Output = Img::Output;
+            type Error = Img::Err;
+            fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+                x.try_adaptive_pool2d()
+            }
+        }
+    };
+}
+
+impl_pools!(AdaptiveAvgPool2D, ConstAdaptiveAvgPool2D);
+impl_pools!(AdaptiveMaxPool2D, ConstAdaptiveMaxPool2D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gradients::*, nn::ModuleMut, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_adaptive_pool_sizes() {
+        let dev: TestDevice = Default::default();
+        let x = dev.zeros::<Rank4<2, 3, 10, 7>>();
+        let _: Tensor<Rank4<2, 3, 4, 4>, _, _> = AdaptiveAvgPool2D::<4, 4>.forward(x.clone());
+        let _: Tensor<Rank4<2, 3, 1, 1>, _, _> = AdaptiveMaxPool2D::<1, 1>.forward(x.clone());
+        let _: Tensor<Rank4<2, 3, 20, 14>, _, _> = AdaptiveMaxPool2D::<20, 14>.forward(x);
+
+        let x = dev.zeros::<Rank3<3, 5, 5>>();
+        let _: Tensor<Rank3<3, 2, 3>, _, _> = AdaptiveAvgPool2D::<2, 3>.forward(x.clone());
+        let _: Tensor<Rank3<3, 2, 3>, _, _> = AdaptiveMaxPool2D::<2, 3>.forward(x);
+    }
+
+    #[test]
+    fn test_adaptive_pool_variable_input_into_head() {
+        let dev: TestDevice = Default::default();
+        let mut head: AdaptiveAvgPool2D<2, 2> = Default::default();
+        for (h, w) in [(8, 8), (13, 5), (2, 2)] {
+            let x: Tensor<(Const<4>, Const<3>, usize, usize), f32, _> =
+                dev.sample_like(&(Const, Const, h, w), rand_distr::StandardNormal);
+            let y: Tensor<Rank4<4, 3, 2, 2>, f32, _, OwnedTape<_>> = head.forward_mut(x.trace());
+            let g = y.sum().backward();
+            // every input element is in exactly one window when the output divides the input
+            if h % 2 == 0 && w % 2 == 0 {
+                let expected = 4.0 / (h * w) as f32;
+                assert!(g
+                    .get(&x)
+                    .as_vec()
+                    .iter()
+                    .all(|&v| (v - expected).abs() < 1e-6));
+            }
+        }
+    }
+}
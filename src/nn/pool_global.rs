@@ -71,8 +71,12 @@ macro_rules! impl_pools {
             Module<Tensor<(C, H, W), f32, D, T>> for $PoolTy
         {
             type Output = Tensor<(C,), f32, D, T>;
-            fn forward(&self, input: Tensor<(C, H, W), f32, D, T>) -> Self::Output {
-                input.min()
+            type Error = D::Err;
+            fn try_forward(
+                &self,
+                input: Tensor<(C, H, W), f32, D, T>,
+            ) -> Result<Self::Output, D::Err> {
+                input.try_min()
             }
         }
 
@@ -80,13 +84,17 @@ macro_rules! impl_pools {
             Module<Tensor<(B, C, H, W), f32, D, T>> for $PoolTy
         {
             type Output = Tensor<(B, C), f32, D, T>;
-            fn forward(&self, input: Tensor<(B, C, H, W), f32, D, T>) -> Self::Output {
+            type Error = D::Err;
+            fn try_forward(
+                &self,
+                input: Tensor<(B, C, H, W), f32, D, T>,
+            ) -> Result<Self::Output, D::Err> {
                 input.$Method()
             }
         }
     };
 }
 
-impl_pools!(AvgPoolGlobal, mean);
-impl_pools!(MaxPoolGlobal, max);
-impl_pools!(MinPoolGlobal, min);
+impl_pools!(AvgPoolGlobal, try_mean);
+impl_pools!(MaxPoolGlobal, try_max);
+impl_pools!(MinPoolGlobal, try_min);
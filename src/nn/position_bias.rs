@@ -0,0 +1,277 @@
+use crate::{gradients::*, nn::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use std::vec::Vec;
+
+/// The ALiBi slope of each of `num_heads` heads, as introduced in
+/// [Train Short, Test Long: Attention with Linear Biases Enables Input Length Extrapolation](https://arxiv.org/abs/2108.12409).
+///
+/// For a power of 2 number of heads, these are the geometric sequence `2^(-8 / num_heads * (h + 1))`.
+/// Otherwise the slopes of the closest smaller power of 2 are followed by every other
+/// slope of the next power of 2, like the reference implementation.
+pub fn alibi_slopes(num_heads: usize) -> Vec<f32> {
+    fn power_of_2_slopes(n: usize) -> impl Iterator<Item = f32> {
+        let start = 2.0f32.powf(-8.0 / n as f32);
+        (1..=n).map(move |i| start.powi(i as i32))
+    }
+    if num_heads == 0 {
+        return Vec::new();
+    }
+    let closest = 1 << (usize::BITS - 1 - num_heads.leading_zeros());
+    let mut slopes: Vec<f32> = power_of_2_slopes(closest).collect();
+    slopes.extend(
+        power_of_2_slopes(2 * closest)
+            .step_by(2)
+            .take(num_heads - closest),
+    );
+    slopes
+}
+
+/// The ALiBi attention bias `-slope[h] * |i - j|` between query `i` and key `j`, see [alibi_slopes()].
+/// Pass this as the 4th input to [MultiHeadAttention].
+///
+/// When there are fewer queries than keys (e.g. when decoding with cached keys & values),
+/// the queries are the last `S1` positions, so the last query lines up with the last key.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let bias: Tensor<Rank3<2, 2, 3>, f32, _> = alibi_bias(&dev);
+/// assert_eq!(
+///     bias.array(),
+///     [
+///         [[-0.0625, 0.0, -0.0625], [-0.125, -0.0625, 0.0]],
+///         [[-0.00390625, 0.0, -0.00390625], [-0.0078125, -0.00390625, 0.0]],
+///     ]
+/// );
+/// ```
+pub fn alibi_bias<const H: usize, const S1: usize, const S2: usize, D: Device<f32>>(
+    dev: &D,
+) -> Tensor<Rank3<H, S1, S2>, f32, D> {
+    let slopes = alibi_slopes(H);
+    let offset = S2.saturating_sub(S1);
+    let mut data = Vec::with_capacity(H * S1 * S2);
+    for slope in slopes {
+        for i in 0..S1 {
+            for j in 0..S2 {
+                let distance = (i + offset).abs_diff(j) as f32;
+                data.push(-slope * distance);
+            }
+        }
+    }
+    let mut bias = dev.zeros();
+    bias.copy_from(&data);
+    bias
+}
+
+/// The bucket of `relative_position = key_position - query_position` in a
+/// [RelativePositionBias] table, like T5.
+///
+/// Half the buckets are exact distances, and the other half cover distances up to
+/// `max_distance` logarithmically. Anything further than `max_distance` shares the last bucket.
+/// If `bidirectional`, half the buckets are used for positive positions, otherwise
+/// keys after the query all go in bucket 0.
+pub fn relative_position_bucket(
+    relative_position: isize,
+    bidirectional: bool,
+    num_buckets: usize,
+    max_distance: usize,
+) -> usize {
+    let mut num_buckets = num_buckets;
+    let mut bucket = 0;
+    let distance = if bidirectional {
+        num_buckets /= 2;
+        if relative_position > 0 {
+            bucket += num_buckets;
+        }
+        relative_position.unsigned_abs()
+    } else {
+        (-relative_position).max(0) as usize
+    };
+    let max_exact = num_buckets / 2;
+    if distance < max_exact {
+        bucket + distance
+    } else {
+        let log_ratio = (distance as f32 / max_exact as f32).ln()
+            / (max_distance as f32 / max_exact as f32).ln();
+        let large = max_exact + (log_ratio * (num_buckets - max_exact) as f32) as usize;
+        bucket + large.min(num_buckets - 1)
+    }
+}
+
+/// A learned relative position bias for attention scores, like in T5
+/// ([Exploring the Limits of Transfer Learning with a Unified Text-to-Text Transformer](https://arxiv.org/abs/1910.10683)).
+///
+/// Every head has a learned bias for each bucket of relative positions,
+/// see [relative_position_bucket()]. Use [RelativePositionBias::bias()] to get the bias for
+/// `S1` queries and `S2` keys, and pass it as the 4th input to [MultiHeadAttention].
+///
+/// Generics:
+/// - `NUM_HEADS`: The number of attention heads.
+/// - *Optional* `NUM_BUCKETS`: The number of buckets of relative positions. Defaults to 32.
+/// - *Optional* `MAX_DISTANCE`: The distance covered by the buckets. Defaults to 128.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let rel: RelativePositionBias<4> = BuildModule::build(&dev);
+/// let bias: Tensor<Rank3<4, 5, 5>, f32, _> = rel.bias();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelativePositionBias<
+    const NUM_HEADS: usize,
+    const NUM_BUCKETS: usize = 32,
+    const MAX_DISTANCE: usize = 128,
+    D: Device<f32> = Cpu,
+> {
+    /// The bias of each bucket for each head.
+    pub table: Tensor<Rank2<NUM_BUCKETS, NUM_HEADS>, f32, D>,
+    /// Whether keys after the query get different buckets than keys before it.
+    /// `true` for encoders (the default), and `false` for causal decoders.
+    pub bidirectional: bool,
+}
+
+impl<const H: usize, const B: usize, const M: usize, D: Device<f32>>
+    RelativePositionBias<H, B, M, D>
+{
+    /// The bias for `S1` queries and `S2` keys, with positions lined up like [alibi_bias()].
+    pub fn bias<const S1: usize, const S2: usize, T: Tape<D>>(
+        &self,
+    ) -> Tensor<Rank3<H, S1, S2>, f32, D, T>
+    where
+        D: TensorFromVec<usize>,
+    {
+        self.try_bias().unwrap()
+    }
+
+    /// Fallible version of [RelativePositionBias::bias()]
+    pub fn try_bias<const S1: usize, const S2: usize, T: Tape<D>>(
+        &self,
+    ) -> Result<Tensor<Rank3<H, S1, S2>, f32, D, T>, D::Err>
+    where
+        D: TensorFromVec<usize>,
+    {
+        let offset = S2.saturating_sub(S1) as isize;
+        let mut buckets = Vec::with_capacity(S1 * S2);
+        for i in 0..S1 as isize {
+            for j in 0..S2 as isize {
+                buckets.push(relative_position_bucket(
+                    j - (i + offset),
+                    self.bidirectional,
+                    B,
+                    M,
+                ));
+            }
+        }
+        let idx = self
+            .table
+            .device
+            .try_tensor_from_vec(buckets, (Const::<S1>, Const::<S2>))?;
+        let bias: Tensor<Rank3<S1, S2, H>, f32, D, T> =
+            self.table.retaped::<T>().try_gather(idx)?;
+        bias.try_permute()
+    }
+}
+
+impl<const H: usize, const B: usize, const M: usize, D: Device<f32>> BuildModule<D, f32>
+    for RelativePositionBias<H, B, M, D>
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self {
+            table: device.try_zeros()?,
+            bidirectional: true,
+        })
+    }
+}
+
+impl<const H: usize, const B: usize, const M: usize, D: Device<f32>> ResetParams<D, f32>
+    for RelativePositionBias<H, B, M, D>
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.table.try_fill_with_zeros()
+    }
+}
+
+impl<const H: usize, const B: usize, const M: usize, D: Device<f32>> GradientUpdate<D, f32>
+    for RelativePositionBias<H, B, M, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.table.update(updater, unused)
+    }
+}
+
+impl<const H: usize, const B: usize, const M: usize, D1, D2> ToDevice<D2>
+    for RelativePositionBias<H, B, M, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = RelativePositionBias<H, B, M, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        RelativePositionBias {
+            table: self.table.to_device(device),
+            bidirectional: self.bidirectional,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestDevice;
+
+    #[test]
+    fn test_alibi_slopes() {
+        assert_eq!(alibi_slopes(4), [0.25, 0.0625, 0.015625, 0.00390625]);
+        let s8 = alibi_slopes(8);
+        let s16 = alibi_slopes(16);
+        let s12 = alibi_slopes(12);
+        assert_eq!(&s12[..8], &s8[..]);
+        assert_eq!(&s12[8..], [s16[0], s16[2], s16[4], s16[6]]);
+    }
+
+    #[test]
+    fn test_relative_position_bucket() {
+        // matches T5's _relative_position_bucket(num_buckets=32, max_distance=128)
+        let bucket = |p, bi| relative_position_bucket(p, bi, 32, 128);
+        assert_eq!(bucket(0, true), 0);
+        assert_eq!(bucket(-3, true), 3);
+        assert_eq!(bucket(3, true), 19);
+        assert_eq!(bucket(-8, true), 8);
+        assert_eq!(bucket(-20, true), 10);
+        assert_eq!(bucket(-1000, true), 15);
+        assert_eq!(bucket(1000, true), 31);
+        assert_eq!(bucket(5, false), 0);
+        assert_eq!(bucket(-5, false), 5);
+        assert_eq!(bucket(-20, false), 17);
+        assert_eq!(bucket(-1000, false), 31);
+    }
+
+    #[test]
+    fn test_relative_position_bias() {
+        let dev: TestDevice = Default::default();
+        let mut rel: RelativePositionBias<2, 8, 16, _> = BuildModule::build(&dev);
+        rel.table = dev.sample_normal();
+        let table = rel.table.array();
+        let bias = rel.bias::<2, 3, NoneTape>().array();
+        for (h, bias_h) in bias.iter().enumerate() {
+            for (i, bias_i) in bias_h.iter().enumerate() {
+                for (j, b) in bias_i.iter().enumerate() {
+                    let bucket =
+                        relative_position_bucket(j as isize - (i + 1) as isize, true, 8, 16);
+                    assert_eq!(*b, table[bucket][h]);
+                }
+            }
+        }
+
+        let g = rel.bias::<3, 3, OwnedTape<_>>().exp().sum().backward();
+        assert_ne!(g.get(&rel.table).array(), [[0.0; 2]; 8]);
+        let mut unused = Default::default();
+        rel.update(&mut crate::nn::tests::SimpleUpdater(g), &mut unused)
+            .unwrap();
+        assert!(unused.is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{
+    BuildModule, Module, NonMutableModule, ResetParams, ToDevice, ToDtype, ZeroSizedModule,
+};
+
+/// Normalizes the channels of images (3d) and batches of images (4d) with a fixed
+/// per channel [Self::mean] & [Self::std]: `(x - mean) / std`.
+/// **Pytorch equivalent**: `torchvision.transforms.Normalize(mean, std)`
+///
+/// Unlike doing this to the data before calling the model, [Self::mean] & [Self::std] are
+/// saved & loaded with the rest of the model (see [super::SaveToNpz]), so the
+/// preprocessing a model was trained with can't be forgotten or changed when it is
+/// deployed. Put it at the start of the model together with [Resize] and [CenterCrop].
+///
+/// Generics:
+/// - `C`: The number of channels.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Normalize<3>, Resize<8, 8>, CenterCrop<6, 6>);
+/// let mut model = Model::build_on_device(&dev);
+/// model.0.mean = dev.tensor([0.485, 0.456, 0.406]);
+/// model.0.std = dev.tensor([0.229, 0.224, 0.225]);
+/// let _: Tensor<Rank4<2, 3, 6, 6>, f32, _> = model.forward(dev.zeros::<Rank4<2, 3, 32, 24>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Normalize<const C: usize, D: Device<f32> = Cpu> {
+    /// Subtracted from each channel. Defaults to 0.0
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
+    pub mean: Tensor<Rank1<C>, f32, D>,
+    /// Each channel is divided by this. Defaults to 1.0
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
+    pub std: Tensor<Rank1<C>, f32, D>,
+}
+
+impl<const C: usize, D: Device<f32>> Normalize<C, D> {
+    fn try_normalize_fwd<S, T: Tape<D>, Ax: Axes>(
+        &self,
+        x: Tensor<S, f32, D, T>,
+    ) -> Result<Tensor<S, f32, D, T>, D::Err>
+    where
+        S: Shape + ReduceShapeTo<Rank1<C>, Ax>,
+    {
+        let shape = *x.shape();
+        x.try_sub(self.mean.clone().try_broadcast_like(&shape)?)?
+            .try_div(self.std.clone().try_broadcast_like(&shape)?)
+    }
+}
+
+impl<const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(Const<C>, H, W), f32, D, T>> for Normalize<C, D>
+{
+    type Output = Tensor<(Const<C>, H, W), f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(&self, x: Tensor<(Const<C>, H, W), f32, D, T>) -> Result<Self::Output, D::Err> {
+        self.try_normalize_fwd::<_, _, Axes2<1, 2>>(x)
+    }
+}
+
+impl<B: Dim, const C: usize, H: Dim, W: Dim, D: Device<f32>, T: Tape<D>>
+    Module<Tensor<(B, Const<C>, H, W), f32, D, T>> for Normalize<C, D>
+{
+    type Output = Tensor<(B, Const<C>, H, W), f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(
+        &self,
+        x: Tensor<(B, Const<C>, H, W), f32, D, T>,
+    ) -> Result<Self::Output, D::Err> {
+        self.try_normalize_fwd::<_, _, Axes3<0, 2, 3>>(x)
+    }
+}
+
+impl<const C: usize, D: Device<f32>> NonMutableModule for Normalize<C, D> {}
+
+impl<const C: usize, D: Device<f32>> BuildModule<D, f32> for Normalize<C, D> {
+    /// Fills [Self::mean] with 0s and [Self::std] with 1s, so the default is the identity.
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        Ok(Self {
+            mean: device.try_zeros()?,
+            std: device.try_ones()?,
+        })
+    }
+}
+
+impl<const C: usize, D: Device<f32>> ResetParams<D, f32> for Normalize<C, D> {
+    /// Does nothing, since [Self::mean] & [Self::std] are set from the training data,
+    /// not learned.
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        Ok(())
+    }
+}
+
+impl<const C: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2> for Normalize<C, D1> {
+    type Output = Normalize<C, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        Normalize {
+            mean: self.mean.to_device(device),
+            std: self.std.to_device(device),
+        }
+    }
+}
+
+impl<const C: usize, D: Device<f32>> GradientUpdate<D, f32> for Normalize<C, D> {
+    fn update<U>(&mut self, updater: &mut U, _: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        updater.update_buffer(&mut self.mean)?;
+        updater.update_buffer(&mut self.std)?;
+        Ok(())
+    }
+}
+
+/// Resizes images (3d) and batches of images (4d) of any height & width to `(OH, OW)`,
+/// by interpolating with [Self::mode]. Defaults to [UpsampleMode::Nearest].
+/// **Pytorch equivalent**: `torchvision.transforms.Resize((OH, OW))`
+///
+/// This does the same as [super::Upscale2D], except that [Self::mode] is saved & loaded
+/// with the rest of the model (see [super::SaveToNpz]), so a model that was trained on
+/// bilinearly resized images is also deployed with them.
+///
+/// Generics:
+/// - `OH`: The height of the output images.
+/// - `OW`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: Resize<4, 6> = Resize {
+///     mode: UpsampleMode::Bilinear { align_corners: false },
+/// };
+/// let _: Tensor<Rank3<3, 4, 6>, f32, _> = m.forward(dev.zeros::<Rank3<3, 9, 9>>());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Resize<const OH: usize, const OW: usize> {
+    pub mode: UpsampleMode,
+}
+
+impl<const OH: usize, const OW: usize, Img: ConstUpsample2D<OH, OW>> Module<Img>
+    for Resize<OH, OW>
+{
+    type Output = Img::Output;
+    type Error = Img::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+        x.try_upsample2d(self.mode)
+    }
+}
+
+impl<const OH: usize, const OW: usize> NonMutableModule for Resize<OH, OW> {}
+
+impl<const OH: usize, const OW: usize, D: Device<E>, E: Dtype> BuildModule<D, E>
+    for Resize<OH, OW>
+{
+    fn try_build(_: &D) -> Result<Self, <D>::Err> {
+        Ok(Default::default())
+    }
+}
+
+impl<const OH: usize, const OW: usize, D: Device<E>, E: Dtype> ResetParams<D, E>
+    for Resize<OH, OW>
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        Ok(())
+    }
+}
+
+impl<const OH: usize, const OW: usize, D> ToDevice<D> for Resize<OH, OW> {
+    type Output = Self;
+    fn to_device(&self, _: &D) -> Self {
+        *self
+    }
+}
+
+impl<const OH: usize, const OW: usize, E> ToDtype<E> for Resize<OH, OW> {
+    type Output = Self;
+    fn to_dtype(&self) -> Self {
+        *self
+    }
+}
+
+impl<const OH: usize, const OW: usize, D: Device<E>, E: Dtype> GradientUpdate<D, E>
+    for Resize<OH, OW>
+{
+    fn update<U>(&mut self, _: &mut U, _: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        Ok(())
+    }
+}
+
+/// Crops the `(H, W)` center of images (3d) and batches of images (4d) of any height &
+/// width. If a side can't be split evenly, the extra pixel is cropped from the bottom or
+/// right. **Pytorch equivalent**: `torchvision.transforms.CenterCrop((H, W))`
+///
+/// Generics:
+/// - `H`: The height of the output images.
+/// - `W`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: CenterCrop<2, 2> = Default::default();
+/// let x = dev.tensor([[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]]);
+/// assert_eq!(m.forward(x).array(), [[[1.0, 2.0], [4.0, 5.0]]]);
+/// ```
+///
+/// **Panics** if the images are smaller than `(H, W)`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CenterCrop<const H: usize, const W: usize>;
+
+impl<const H: usize, const W: usize> ZeroSizedModule for CenterCrop<H, W> {}
+impl<const H: usize, const W: usize> NonMutableModule for CenterCrop<H, W> {}
+
+impl<const H: usize, const W: usize, D: Device<E>, E: Dtype> BuildModule<D, E>
+    for CenterCrop<H, W>
+{
+    fn try_build(_: &D) -> Result<Self, <D>::Err> {
+        Ok(Default::default())
+    }
+}
+
+impl<C: Dim, Hi: Dim, Wi: Dim, const H: usize, const W: usize, E, D, T>
+    Module<Tensor<(C, Hi, Wi), E, D, T>> for CenterCrop<H, W>
+where
+    E: Dtype,
+    D: Device<E>,
+    T: Tape<D>,
+{
+    type Output = Tensor<(C, Const<H>, Const<W>), E, D, T>;
+    type Error = D::Err;
+
+    #[track_caller]
+    fn try_forward(&self, x: Tensor<(C, Hi, Wi), E, D, T>) -> Result<Self::Output, D::Err> {
+        let (_, h, w) = *x.shape();
+        x.try_narrow_to::<Axis<1>, _>(center_start(h.size(), H), Const::<H>)?
+            .try_narrow_to::<Axis<2>, _>(center_start(w.size(), W), Const::<W>)
+    }
+}
+
+impl<B: Dim, C: Dim, Hi: Dim, Wi: Dim, const H: usize, const W: usize, E, D, T>
+    Module<Tensor<(B, C, Hi, Wi), E, D, T>> for CenterCrop<H, W>
+where
+    E: Dtype,
+    D: Device<E>,
+    T: Tape<D>,
+{
+    type Output = Tensor<(B, C, Const<H>, Const<W>), E, D, T>;
+    type Error = D::Err;
+
+    #[track_caller]
+    fn try_forward(&self, x: Tensor<(B, C, Hi, Wi), E, D, T>) -> Result<Self::Output, D::Err> {
+        let (_, _, h, w) = *x.shape();
+        x.try_narrow_to::<Axis<2>, _>(center_start(h.size(), H), Const::<H>)?
+            .try_narrow_to::<Axis<3>, _>(center_start(w.size(), W), Const::<W>)
+    }
+}
+
+/// Where a crop of size `len` starts so it is centered in `size`.
+#[track_caller]
+fn center_start(size: usize, len: usize) -> usize {
+    assert!(
+        len <= size,
+        "CenterCrop: can't crop {len} pixels from an image of size {size}"
+    );
+    (size - len) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_normalize() {
+        let dev: TestDevice = Default::default();
+        let mut m: Normalize<2, _> = BuildModule::build(&dev);
+        let x = dev.tensor([[[1.0, 2.0]], [[3.0, 4.0]]]);
+        assert_eq!(m.forward(x.clone()).array(), x.array());
+
+        m.mean = dev.tensor([1.0, 2.0]);
+        m.std = dev.tensor([2.0, 0.5]);
+        assert_eq!(m.forward(x.clone()).array(), [[[0.0, 0.5]], [[2.0, 4.0]]]);
+        let xb: Tensor<Rank4<3, 2, 1, 2>, f32, _> = x.broadcast();
+        assert_eq!(m.forward(xb).array(), [[[[0.0, 0.5]], [[2.0, 4.0]]]; 3]);
+    }
+
+    #[test]
+    fn test_normalize_is_not_optimized() {
+        let dev: TestDevice = Default::default();
+        let mut m: Normalize<3, _> = BuildModule::build(&dev);
+        let x: Tensor<Rank4<2, 3, 2, 2>, f32, _> = dev.sample_normal();
+        let g = m.forward(x.trace()).exp().mean().backward();
+        let mut opt = Sgd::new(&m, Default::default());
+        opt.update(&mut m, g).expect("");
+        assert_eq!(m.mean.array(), [0.0; 3]);
+        assert_eq!(m.std.array(), [1.0; 3]);
+    }
+
+    #[test]
+    fn test_center_crop() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[
+            [0.0, 1.0, 2.0, 3.0, 4.0],
+            [10.0, 11.0, 12.0, 13.0, 14.0],
+            [20.0, 21.0, 22.0, 23.0, 24.0],
+            [30.0, 31.0, 32.0, 33.0, 34.0],
+        ]]);
+        let m: CenterCrop<2, 2> = Default::default();
+        assert_eq!(m.forward(x.clone()).array(), [[[11.0, 12.0], [21.0, 22.0]]]);
+        let m: CenterCrop<3, 3> = Default::default();
+        let xb: Tensor<Rank4<2, 1, 4, 5>, f32, _> = x.broadcast();
+        let expected = [[1.0, 2.0, 3.0], [11.0, 12.0, 13.0], [21.0, 22.0, 23.0]];
+        assert_eq!(m.forward(xb).array(), [[expected]; 2]);
+
+        let x: Tensor<(Const<1>, usize, usize), f32, _> = dev.zeros_like(&(Const, 7, 3));
+        let m: CenterCrop<3, 3> = Default::default();
+        let _: Tensor<Rank3<1, 3, 3>, f32, _> = m.forward(x);
+    }
+
+    #[test]
+    fn test_center_crop_gradients() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank4<1, 1, 3, 3>, f32, _> = dev.sample_normal();
+        let m: CenterCrop<1, 1> = Default::default();
+        let g = m.forward(x.trace()).exp().sum().backward();
+        let mut expected = [[[[0.0; 3]; 3]]];
+        expected[0][0][1][1] = x.array()[0][0][1][1].exp();
+        assert_eq!(g.get(&x).array(), expected);
+    }
+
+    #[test]
+    #[should_panic = "CenterCrop: can't crop 4 pixels from an image of size 3"]
+    fn test_center_crop_too_small() {
+        let dev: TestDevice = Default::default();
+        let m: CenterCrop<2, 4> = Default::default();
+        let _ = m.forward(dev.zeros::<Rank3<1, 3, 3>>());
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{optim::*, shapes::Dtype, tensor_ops::Device};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
 
 /// Repeats `T` `N` times. This requires that `T`'s input is the same as it's output.
 ///
@@ -57,6 +57,19 @@ impl<T: ToDevice<D>, const N: usize, D> ToDevice<D> for Repeated<T, N> {
     }
 }
 
+impl<T: ToDtype<E>, const N: usize, E> ToDtype<E> for Repeated<T, N> {
+    type Output = Repeated<T::Output, N>;
+    fn to_dtype(&self) -> Self::Output {
+        Repeated {
+            modules: self
+                .modules
+                .iter()
+                .map(|module| module.to_dtype())
+                .collect(),
+        }
+    }
+}
+
 impl<T, const N: usize> std::ops::Index<usize> for Repeated<T, N> {
     type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
@@ -80,11 +93,12 @@ impl<D: Device<E>, E: Dtype, T: GradientUpdate<D, E>, const N: usize> GradientUp
 
 impl<Input, T: Module<Input, Output = Input>, const N: usize> Module<Input> for Repeated<T, N> {
     type Output = T::Output;
-    fn forward(&self, mut x: Input) -> Self::Output {
+    type Error = T::Error;
+    fn try_forward(&self, mut x: Input) -> Result<Self::Output, T::Error> {
         for i in 0..N {
-            x = self.modules[i].forward(x);
+            x = self.modules[i].try_forward(x)?;
         }
-        x
+        Ok(x)
     }
 }
 
@@ -92,19 +106,118 @@ impl<Input, T: ModuleMut<Input, Output = Input>, const N: usize> ModuleMut<Input
     for Repeated<T, N>
 {
     type Output = T::Output;
-    fn forward_mut(&mut self, mut x: Input) -> Self::Output {
+    type Error = T::Error;
+    fn try_forward_mut(&mut self, mut x: Input) -> Result<Self::Output, T::Error> {
         for i in 0..N {
-            x = self.modules[i].forward_mut(x);
+            x = self.modules[i].try_forward_mut(x)?;
+        }
+        Ok(x)
+    }
+}
+
+/// Applies `T` `N` times, where all repetitions share the same parameters, as in
+/// [Universal Transformers](https://arxiv.org/abs/1807.03819) and [ALBERT](https://arxiv.org/abs/1909.11942).
+/// This requires that `T`'s input is the same as it's output.
+///
+/// Since every repetition uses the same tensors, their gradients are summed during backprop,
+/// and optimizers only update the single set of parameters.
+///
+/// See [Repeated] for repetitions with independent parameters. This can be combined with
+/// [super::Checkpointed] to share parameters with activation checkpointing.
+///
+/// # Generics
+/// - `T` the [Module] to repeat
+/// - `N` the number of times to apply `T`.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = TiedRepeated<(Linear<10, 10>, ReLU), 5>;
+/// let model = Model::build_on_device(&dev);
+/// let out: Tensor<Rank1<10>, f32, _> = model.forward(dev.zeros());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TiedRepeated<T, const N: usize> {
+    pub module: T,
+}
+
+impl<D: Device<E>, E: Dtype, T: BuildModule<D, E>, const N: usize> BuildModule<D, E>
+    for TiedRepeated<T, N>
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self {
+            module: BuildModule::try_build(device)?,
+        })
+    }
+}
+
+impl<D: Device<E>, E: Dtype, T: ResetParams<D, E>, const N: usize> ResetParams<D, E>
+    for TiedRepeated<T, N>
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.module.try_reset_params()
+    }
+}
+
+impl<T: ToDtype<E>, const N: usize, E> ToDtype<E> for TiedRepeated<T, N> {
+    type Output = TiedRepeated<T::Output, N>;
+    fn to_dtype(&self) -> Self::Output {
+        TiedRepeated {
+            module: self.module.to_dtype(),
+        }
+    }
+}
+
+impl<T: ToDevice<D>, const N: usize, D> ToDevice<D> for TiedRepeated<T, N> {
+    type Output = TiedRepeated<T::Output, N>;
+    fn to_device(&self, device: &D) -> Self::Output {
+        TiedRepeated {
+            module: self.module.to_device(device),
+        }
+    }
+}
+
+impl<D: Device<E>, E: Dtype, T: GradientUpdate<D, E>, const N: usize> GradientUpdate<D, E>
+    for TiedRepeated<T, N>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        self.module.update(updater, unused)
+    }
+}
+
+impl<Input, T: Module<Input, Output = Input>, const N: usize> Module<Input> for TiedRepeated<T, N> {
+    type Output = T::Output;
+    type Error = T::Error;
+    fn try_forward(&self, mut x: Input) -> Result<Self::Output, T::Error> {
+        for _ in 0..N {
+            x = self.module.try_forward(x)?;
+        }
+        Ok(x)
+    }
+}
+
+impl<Input, T: ModuleMut<Input, Output = Input>, const N: usize> ModuleMut<Input>
+    for TiedRepeated<T, N>
+{
+    type Output = T::Output;
+    type Error = T::Error;
+    fn try_forward_mut(&mut self, mut x: Input) -> Result<Self::Output, T::Error> {
+        for _ in 0..N {
+            x = self.module.try_forward_mut(x)?;
         }
-        x
+        Ok(x)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{nn::tests::SimpleUpdater, tests::TestDevice};
-    use crate::{nn::*, shapes::*, tensor::*, unique_id::HasUniqueId};
+    use crate::{nn::tests::SimpleUpdater, tests::*};
+    use crate::{nn::*, shapes::*, tensor::*, tensor_ops::*, unique_id::HasUniqueId};
 
     #[test]
     fn test_default_and_reset() {
@@ -182,4 +295,42 @@ mod tests {
         model.update(&mut g, &mut unused).unwrap();
         assert!(unused.is_empty());
     }
+
+    #[test]
+    fn test_tied_forward() {
+        let dev: TestDevice = Default::default();
+
+        let mut m: TiedRepeated<(Linear<3, 3, _>, ReLU), 3> = BuildModule::build(&dev);
+
+        let x = dev.sample_normal::<Rank1<3>>();
+        let y = m
+            .module
+            .forward(m.module.forward(m.module.forward(x.clone())));
+
+        assert_eq!(y.array(), m.forward_mut(x).array());
+    }
+
+    #[test]
+    fn test_tied_gradients_are_shared() {
+        let dev: TestDevice = Default::default();
+
+        let mut model: TiedRepeated<Linear<3, 3, _>, 4> = BuildModule::build(&dev);
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let g = model.forward(x.trace()).mean().backward();
+        let copies = (model.module.clone(), model.module.clone());
+        let g_expected = (copies, model.module.clone(), model.module.clone())
+            .forward(x.trace())
+            .mean()
+            .backward();
+        assert_close(
+            &g.get(&model.module.weight).array(),
+            &g_expected.get(&model.module.weight).array(),
+        );
+
+        let mut g = SimpleUpdater(g);
+        let mut unused = Default::default();
+        model.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
 }
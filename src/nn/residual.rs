@@ -1,8 +1,11 @@
-use crate::{optim::*, shapes::*, tensor::SplitTape, tensor_ops::Device};
+use crate::{
+    optim::*,
+    shapes::*,
+    tensor::{HasErr, SplitTape},
+    tensor_ops::{Device, TryAdd},
+};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
-
-use std::ops::Add;
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
 
 /// A residual connection around `F`: `F(x) + x`,
 /// as introduced in [Deep Residual Learning for Image Recognition](https://arxiv.org/abs/1512.03385).
@@ -51,17 +54,30 @@ impl<F: ToDevice<D>, D> ToDevice<D> for Residual<F> {
     }
 }
 
-impl<T: SplitTape + Add<T, Output = T>, F: Module<T, Output = T>> Module<T> for Residual<F> {
+impl<F: ToDtype<E>, E> ToDtype<E> for Residual<F> {
+    type Output = Residual<F::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        Residual(self.0.to_dtype())
+    }
+}
+
+impl<T: SplitTape + TryAdd<T> + HasErr<Err = F::Error>, F: Module<T, Output = T>> Module<T>
+    for Residual<F>
+{
     type Output = T;
-    fn forward(&self, x: T) -> Self::Output {
-        self.0.forward(x.with_empty_tape()) + x
+    type Error = F::Error;
+    fn try_forward(&self, x: T) -> Result<Self::Output, F::Error> {
+        self.0.try_forward(x.with_empty_tape())?.try_add(x)
     }
 }
 
-impl<T: SplitTape + Add<T, Output = T>, F: ModuleMut<T, Output = T>> ModuleMut<T> for Residual<F> {
+impl<T: SplitTape + TryAdd<T> + HasErr<Err = F::Error>, F: ModuleMut<T, Output = T>> ModuleMut<T>
+    for Residual<F>
+{
     type Output = T;
-    fn forward_mut(&mut self, x: T) -> Self::Output {
-        self.0.forward_mut(x.with_empty_tape()) + x
+    type Error = F::Error;
+    fn try_forward_mut(&mut self, x: T) -> Result<Self::Output, F::Error> {
+        self.0.try_forward_mut(x.with_empty_tape())?.try_add(x)
     }
 }
 
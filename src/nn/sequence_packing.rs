@@ -0,0 +1,234 @@
+use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+use std::vec::Vec;
+
+/// Where variable length sequences go when packed into a batch of `B` rows of `S` tokens,
+/// so short sequences don't waste most of each row on padding, like in
+/// [Efficient Sequence Packing without Cross-contamination](https://arxiv.org/abs/2107.02027).
+///
+/// Each sequence is placed in the first row it fits in, in order, until the next sequence
+/// doesn't fit in any row. The sequences that weren't packed (`lengths[num_packed()..]`)
+/// can go in the next batch.
+///
+/// To keep the packed sequences independent:
+/// - [SequencePacking::position_ids()] restart at `0` for each sequence.
+/// - [SequencePacking::attention_bias()] is block diagonal, so tokens only attend to their
+///   own sequence. Pass it to [MultiHeadAttention] along with the packed batch.
+/// - [SequencePacking::loss_mask()] is `0.0` for padding, for use with
+///   [crate::losses::WeightedMean].
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let sequences: [&[usize]; 3] = [&[5, 6, 7], &[8, 9, 10, 11], &[12]];
+/// let packing: SequencePacking<2, 4> = SequencePacking::new(&[3, 4, 1]);
+/// assert_eq!(packing.num_packed(), 3);
+/// let tokens = packing.tokens(&dev, &sequences, 0);
+/// assert_eq!(tokens.array(), [[5, 6, 7, 12], [8, 9, 10, 11]]);
+/// let positions = packing.position_ids(&dev);
+/// assert_eq!(positions.array(), [[0, 1, 2, 0], [0, 1, 2, 3]]);
+/// let bias: Tensor<Rank3<2, 4, 4>, f32, _> = packing.attention_bias(&dev, true);
+/// ```
+///
+/// **Panics** if a sequence is longer than `S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencePacking<const B: usize, const S: usize> {
+    /// The `(row, start)` of each packed sequence, in the order they were given.
+    pub placements: Vec<(usize, usize)>,
+    /// The length of each packed sequence.
+    pub lengths: Vec<usize>,
+}
+
+impl<const B: usize, const S: usize> SequencePacking<B, S> {
+    /// Packs as many of the sequences with `lengths` as fit. See [SequencePacking].
+    pub fn new(lengths: &[usize]) -> Self {
+        let mut used = [0; B];
+        let mut placements = Vec::new();
+        for &len in lengths {
+            assert!(
+                len <= S,
+                "sequence of length {len} doesn't fit in {S} tokens"
+            );
+            match used.iter().position(|&u| u + len <= S) {
+                Some(row) => {
+                    placements.push((row, used[row]));
+                    used[row] += len;
+                }
+                None => break,
+            }
+        }
+        let lengths = lengths[..placements.len()].to_vec();
+        Self {
+            placements,
+            lengths,
+        }
+    }
+
+    /// The number of sequences that were packed, from the start of the `lengths` passed
+    /// to [SequencePacking::new()].
+    pub fn num_packed(&self) -> usize {
+        self.placements.len()
+    }
+
+    /// The packed sequence of each token, or `None` for padding.
+    pub fn segment_ids(&self) -> Vec<Option<usize>> {
+        let mut segments = std::vec![None; B * S];
+        for (i, (&(row, start), &len)) in self.placements.iter().zip(&self.lengths).enumerate() {
+            segments[row * S + start..row * S + start + len].fill(Some(i));
+        }
+        segments
+    }
+
+    /// Copies the packed `sequences` into their rows, with `pad` after the last sequence
+    /// of each row. Only the first [SequencePacking::num_packed()] sequences are used.
+    ///
+    /// **Panics** if the length of a sequence is different than the length it was packed with.
+    pub fn tokens<E: Unit, D: TensorFromVec<E>>(
+        &self,
+        dev: &D,
+        sequences: &[&[E]],
+        pad: E,
+    ) -> Tensor<Rank2<B, S>, E, D> {
+        let mut data = std::vec![pad; B * S];
+        for ((&(row, start), &len), seq) in self.placements.iter().zip(&self.lengths).zip(sequences)
+        {
+            assert_eq!(seq.len(), len, "sequence length changed since packing");
+            data[row * S + start..row * S + start + len].copy_from_slice(seq);
+        }
+        dev.tensor_from_vec(data, Default::default())
+    }
+
+    /// The position of each token in its own sequence, which is `0` for padding.
+    pub fn position_ids<D: TensorFromVec<usize>>(&self, dev: &D) -> Tensor<Rank2<B, S>, usize, D> {
+        let mut data = std::vec![0; B * S];
+        for (&(row, start), &len) in self.placements.iter().zip(&self.lengths) {
+            for (i, p) in data[row * S + start..row * S + start + len]
+                .iter_mut()
+                .enumerate()
+            {
+                *p = i;
+            }
+        }
+        dev.tensor_from_vec(data, Default::default())
+    }
+
+    /// `1.0` for the tokens of packed sequences, and `0.0` for padding.
+    pub fn loss_mask<D: Device<f32>>(&self, dev: &D) -> Tensor<Rank2<B, S>, f32, D> {
+        let data = self
+            .segment_ids()
+            .into_iter()
+            .map(|s| if s.is_some() { 1.0 } else { 0.0 })
+            .collect::<Vec<f32>>();
+        let mut mask = dev.zeros();
+        mask.copy_from(&data);
+        mask
+    }
+
+    /// The block diagonal attention bias of each row, which is `0.0` where a query and key are
+    /// in the same sequence, and `-inf` everywhere else. With `causal`, queries also don't attend
+    /// to later keys. Padding only attends to itself, so its attention is still well defined.
+    ///
+    /// Broadcast this along the heads to pass to [MultiHeadAttention]:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// # let packing: SequencePacking<2, 4> = SequencePacking::new(&[3, 4, 1]);
+    /// let bias: Tensor<Rank4<2, 8, 4, 4>, f32, _> = packing.attention_bias(&dev, true).broadcast();
+    /// ```
+    pub fn attention_bias<D: Device<f32>>(
+        &self,
+        dev: &D,
+        causal: bool,
+    ) -> Tensor<Rank3<B, S, S>, f32, D> {
+        let segments = self.segment_ids();
+        let mut data = Vec::with_capacity(B * S * S);
+        for row in segments.chunks(S) {
+            for (i, query) in row.iter().enumerate() {
+                for (j, key) in row.iter().enumerate() {
+                    let same = match (query, key) {
+                        (Some(q), Some(k)) => q == k,
+                        _ => i == j,
+                    };
+                    let visible = same && (!causal || j <= i);
+                    data.push(if visible { 0.0 } else { f32::NEG_INFINITY });
+                }
+            }
+        }
+        let mut bias = dev.zeros();
+        bias.copy_from(&data);
+        bias
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_packing_first_fit() {
+        let packing: SequencePacking<2, 5> = SequencePacking::new(&[3, 4, 2, 1, 3]);
+        assert_eq!(packing.placements, [(0, 0), (1, 0), (0, 3), (1, 4)]);
+        assert_eq!(packing.num_packed(), 4);
+        assert_eq!(
+            packing.segment_ids(),
+            [0, 0, 0, 2, 2, 1, 1, 1, 1, 3].map(Some)
+        );
+
+        let packing: SequencePacking<2, 5> = SequencePacking::new(&[2]);
+        assert_eq!(packing.segment_ids()[2..5], [None; 3]);
+        assert_eq!(packing.segment_ids()[5..], [None; 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_packing_too_long() {
+        let _: SequencePacking<2, 3> = SequencePacking::new(&[1, 4]);
+    }
+
+    #[test]
+    fn test_packed_tensors() {
+        let dev: TestDevice = Default::default();
+        let packing: SequencePacking<1, 4> = SequencePacking::new(&[2, 1]);
+        let tokens = packing.tokens(&dev, &[&[1.0, 2.0], &[3.0]], -1.0);
+        assert_eq!(tokens.array(), [[1.0, 2.0, 3.0, -1.0]]);
+        assert_eq!(packing.position_ids(&dev).array(), [[0, 1, 0, 0]]);
+        assert_eq!(packing.loss_mask(&dev).array(), [[1.0, 1.0, 1.0, 0.0]]);
+
+        const X: f32 = f32::NEG_INFINITY;
+        let bias = packing.attention_bias(&dev, false);
+        assert_eq!(
+            bias.array(),
+            [[
+                [0.0, 0.0, X, X],
+                [0.0, 0.0, X, X],
+                [X, X, 0.0, X],
+                [X, X, X, 0.0]
+            ]]
+        );
+        let bias = packing.attention_bias(&dev, true);
+        assert_eq!(bias.array()[0][0], [0.0, X, X, X]);
+        assert_eq!(bias.array()[0][1], [0.0, 0.0, X, X]);
+    }
+
+    #[test]
+    fn test_packed_attention_matches_separate() {
+        let dev: TestDevice = Default::default();
+        let packing: SequencePacking<1, 5> = SequencePacking::new(&[3, 2]);
+        let q: Tensor<Rank2<5, 4>, f32, _> = dev.sample_normal();
+        let k: Tensor<Rank2<5, 4>, f32, _> = dev.sample_normal();
+        let bias = dev.tensor(packing.attention_bias(&dev, false).array()[0]);
+        let probs = (q.clone().matmul(k.clone().permute()) + bias).softmax::<Axis<1>>();
+
+        // the second sequence on its own
+        let (qa, ka) = (q.array(), k.array());
+        let q2 = dev.tensor([qa[3], qa[4]]);
+        let k2 = dev.tensor([ka[3], ka[4]]);
+        let alone = q2.matmul(k2.permute()).softmax::<Axis<1>>();
+        let probs = probs.array();
+        for i in 0..2 {
+            assert_close(&[probs[3 + i][3], probs[3 + i][4]], &alone.array()[i]);
+            assert_eq!(probs[3 + i][..3], [0.0; 3]);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{optim::*, shapes::Dtype, tensor::*, tensor_ops::Device};
 
-use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice, ToDtype};
 
 /// Splits input into multiple heads. `T` should be a tuple,
 /// where every element of the tuple accepts the same input type.
@@ -51,11 +51,18 @@ impl<T: ToDevice<D>, D> ToDevice<D> for SplitInto<T> {
     }
 }
 
+impl<T: ToDtype<E>, E> ToDtype<E> for SplitInto<T> {
+    type Output = SplitInto<T::Output>;
+    fn to_dtype(&self) -> Self::Output {
+        SplitInto(self.0.to_dtype())
+    }
+}
+
 macro_rules! tuple_impls {
     ([$($heads:ident),+] $tail:ident) => {
 impl<
     Input: SplitTape,
-    $($heads : Module<Input>,)+
+    $($heads : Module<Input, Error = $tail::Error>,)+
     $tail: Module<Input>
 > Module<Input> for SplitInto<($($heads,)+ $tail)>
 where
@@ -65,20 +72,21 @@ where
         $(<$heads::Output as SplitTape>::NoTape, )+
         $tail::Output
     );
+    type Error = $tail::Error;
 
     #[allow(non_snake_case)]
-    fn forward(&self, x: Input) -> Self::Output {
+    fn try_forward(&self, x: Input) -> Result<Self::Output, Self::Error> {
         let (x, tape) = x.split_tape();
         let ($($heads, )+ $tail) = &self.0;
-        $(let ($heads, tape) = $heads.forward(x.clone().put_tape(tape)).split_tape();)+
-        let $tail = $tail.forward(x.put_tape(tape));
-        ($($heads,)+ $tail)
+        $(let ($heads, tape) = $heads.try_forward(x.clone().put_tape(tape))?.split_tape();)+
+        let $tail = $tail.try_forward(x.put_tape(tape))?;
+        Ok(($($heads,)+ $tail))
     }
 }
 
 impl<
     Input: SplitTape,
-    $($heads : ModuleMut<Input>,)+
+    $($heads : ModuleMut<Input, Error = $tail::Error>,)+
     $tail: ModuleMut<Input>
 > ModuleMut<Input> for SplitInto<($($heads,)+ $tail)>
 where
@@ -88,14 +96,15 @@ where
         $(<$heads::Output as SplitTape>::NoTape, )+
         $tail::Output
     );
+    type Error = $tail::Error;
 
     #[allow(non_snake_case)]
-    fn forward_mut(&mut self, x: Input) -> Self::Output {
+    fn try_forward_mut(&mut self, x: Input) -> Result<Self::Output, Self::Error> {
         let (x, tape) = x.split_tape();
         let ($($heads, )+ $tail) = &mut self.0;
-        $(let ($heads, tape) = $heads.forward_mut(x.clone().put_tape(tape)).split_tape();)+
-        let $tail = $tail.forward_mut(x.put_tape(tape));
-        ($($heads,)+ $tail)
+        $(let ($heads, tape) = $heads.try_forward_mut(x.clone().put_tape(tape))?.split_tape();)+
+        let $tail = $tail.try_forward_mut(x.put_tape(tape))?;
+        Ok(($($heads,)+ $tail))
     }
 }
 }
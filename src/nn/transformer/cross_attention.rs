@@ -0,0 +1,296 @@
+use crate::{nn::*, optim::*, tensor::*, tensor_ops::*};
+
+#[cfg(feature = "nightly")]
+use crate::{gradients::Tape, shapes::*, Assert, ConstTrue};
+
+/// **Requires Nightly** A multi-head attention layer where the queries come from one sequence,
+/// and the keys and values come from another (the context), like in encoder-decoder models
+/// and Perceiver style architectures.
+///
+/// The two sequences can have different lengths and different feature dims.
+/// The output has the same shape as the queries.
+///
+/// Generics:
+/// - `Q_DIM`: The size of query vectors, and of the output.
+/// - `KV_DIM`: The size of context vectors.
+/// - `NUM_HEADS` The number of heads to split query/key/value into.
+/// - *Optional* `K_DIM`: The size of key vectors. Defaults to `Q_DIM`
+/// - *Optional* `V_DIM` The size of value vectors. Defaults to `Q_DIM`
+///
+/// **Pytorch equivalent**: `torch.nn.MultiheadAttention(Q_DIM, NUM_HEADS, kdim=KV_DIM, vdim=KV_DIM, batch_first=True)`
+///
+/// Examples
+/// - `CrossAttention<8, 16, 2>` attends from 8 dim queries to a 16 dim context with 2 heads.
+///
+/// Use it with a tuple of the queries and the context: `attn.forward((q, context))`.
+#[derive(Debug, Clone)]
+pub struct CrossAttention<
+    const Q_DIM: usize,
+    const KV_DIM: usize,
+    const NUM_HEADS: usize,
+    const K_DIM: usize = Q_DIM,
+    const V_DIM: usize = Q_DIM,
+    D: Device<f32> = Cpu,
+> {
+    pub w_q: Linear<Q_DIM, K_DIM, D>,
+    pub w_k: Linear<KV_DIM, K_DIM, D>,
+    pub w_v: Linear<KV_DIM, V_DIM, D>,
+    pub w_o: Linear<V_DIM, Q_DIM, D>,
+}
+
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+    > BuildModule<D, f32> for CrossAttention<Q, C, H, K, V, D>
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Ok(Self {
+            w_q: BuildModule::try_build(device)?,
+            w_k: BuildModule::try_build(device)?,
+            w_v: BuildModule::try_build(device)?,
+            w_o: BuildModule::try_build(device)?,
+        })
+    }
+}
+
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+    > ResetParams<D, f32> for CrossAttention<Q, C, H, K, V, D>
+{
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        self.w_q.try_reset_params()?;
+        self.w_k.try_reset_params()?;
+        self.w_v.try_reset_params()?;
+        self.w_o.try_reset_params()?;
+        Ok(())
+    }
+}
+
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+    > GradientUpdate<D, f32> for CrossAttention<Q, C, H, K, V, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.w_q.update(updater, unused)?;
+        self.w_k.update(updater, unused)?;
+        self.w_v.update(updater, unused)?;
+        self.w_o.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const Q: usize, const C: usize, const H: usize, const K: usize, const V: usize, D1, D2>
+    ToDevice<D2> for CrossAttention<Q, C, H, K, V, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = CrossAttention<Q, C, H, K, V, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        CrossAttention {
+            w_q: self.w_q.to_device(device),
+            w_k: self.w_k.to_device(device),
+            w_v: self.w_v.to_device(device),
+            w_o: self.w_o.to_device(device),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank2<S1, Q>, f32, D, T>,
+        Tensor<Rank2<S2, C>, f32, D>,
+    )> for CrossAttention<Q, C, H, K, V, D>
+where
+    Assert<{ S1 * K == S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * K == S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * V == S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ S1 * H * (V / H) == S1 * V }>: ConstTrue,
+{
+    type Output = Tensor<Rank2<S1, Q>, f32, D, T>;
+    type Error = D::Err;
+
+    /// Queries attending to a context, where the keys and values are both computed from the context
+    fn try_forward(
+        &self,
+        (q, ctx): (
+            Tensor<Rank2<S1, Q>, f32, D, T>,
+            Tensor<Rank2<S2, C>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let v: Tensor<Rank2<S2, V>, _, _, _> = self.w_v.try_forward(ctx.retaped::<T>())?;
+        let v = v.try_reshape::<Rank3<S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank3<H, S2, { V / H }>, _>()?;
+
+        let k: Tensor<Rank2<S2, K>, _, _, _> = self.w_k.try_forward(ctx.retaped::<T>())?;
+        let k = k.try_reshape::<Rank3<S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank3<H, { K / H }, S2>, _>()?;
+
+        let q: Tensor<Rank2<S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank3<S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank3<H, S1, { K / H }>, _>()?;
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank3<H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_softmax::<Axis<2>>()?;
+
+        // Get new tokens
+        let tokens: Tensor<Rank3<H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank3<S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank2<S1, V>>()?;
+
+        self.w_o.try_forward(tokens)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank3<B, S1, Q>, f32, D, T>,
+        Tensor<Rank3<B, S2, C>, f32, D>,
+    )> for CrossAttention<Q, C, H, K, V, D>
+where
+    Assert<{ B * S1 * K == B * S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * K == B * S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * V == B * S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ B * S1 * H * (V / H) == B * S1 * V }>: ConstTrue,
+{
+    type Output = Tensor<Rank3<B, S1, Q>, f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched queries attending to a context, where the keys and values are both computed from the context
+    fn try_forward(
+        &self,
+        (q, ctx): (
+            Tensor<Rank3<B, S1, Q>, f32, D, T>,
+            Tensor<Rank3<B, S2, C>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.try_forward(ctx.retaped::<T>())?;
+        let v = v.try_reshape::<Rank4<B, S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank4<B, H, S2, { V / H }>, _>()?;
+
+        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.try_forward(ctx.retaped::<T>())?;
+        let k = k.try_reshape::<Rank4<B, S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank4<B, H, { K / H }, S2>, _>()?;
+
+        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank4<B, S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank4<B, H, S1, { K / H }>, _>()?;
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_softmax::<Axis<3>>()?;
+
+        // Get new tokens
+        let tokens: Tensor<Rank4<B, H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank4<B, S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank3<B, S1, V>>()?;
+
+        self.w_o.try_forward(tokens)
+    }
+}
+
+impl<
+        const Q: usize,
+        const C: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        T,
+    > ModuleMut<T> for CrossAttention<Q, C, H, K, V, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
+    }
+}
+
+#[cfg(feature = "nightly")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::tests::SimpleUpdater, tests::*};
+
+    #[test]
+    fn test_cross_attention_matches_mha() {
+        let dev: TestDevice = Default::default();
+        let mha = MultiHeadAttention::<8, 2>::build_on_device(&dev);
+        let cross = CrossAttention::<8, 8, 2> {
+            w_q: mha.w_q.clone(),
+            w_k: mha.w_k.clone(),
+            w_v: mha.w_v.clone(),
+            w_o: mha.w_o.clone(),
+        };
+        let q = dev.sample_normal::<Rank3<2, 3, 8>>();
+        let ctx = dev.sample_normal::<Rank3<2, 5, 8>>();
+        let y1 = mha.forward((q.clone(), ctx.clone(), ctx.clone()));
+        let y2 = cross.forward((q, ctx));
+        assert_close(&y1.array(), &y2.array());
+    }
+
+    #[test]
+    fn test_cross_attention_different_dims() {
+        let dev: TestDevice = Default::default();
+        let mut attn = CrossAttention::<8, 6, 2, 4, 12>::build_on_device(&dev);
+
+        let q = dev.sample_normal::<Rank2<3, 8>>();
+        let ctx = dev.sample_normal::<Rank2<7, 6>>();
+        let y: Tensor<Rank2<3, 8>, _, _, _> = attn.forward((q.trace(), ctx));
+
+        let mut g = SimpleUpdater(y.mean().backward());
+        let mut unused = Default::default();
+        attn.update(&mut g, &mut unused).unwrap();
+        assert!(unused.is_empty());
+
+        let q = dev.sample_normal::<Rank3<4, 3, 8>>();
+        let ctx = dev.sample_normal::<Rank3<4, 7, 6>>();
+        let _: Tensor<Rank3<4, 3, 8>, _, _> = attn.forward((q, ctx));
+    }
+}
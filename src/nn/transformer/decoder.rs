@@ -1,8 +1,8 @@
 use crate::{
     nn::*,
     optim::{GradientUpdate, ParamUpdater, UnusedTensors},
-    tensor::{Cpu, PutTape, SplitTape},
-    tensor_ops::Device,
+    tensor::{Cpu, HasErr, PutTape, SplitTape},
+    tensor_ops::{Device, TryAdd},
 };
 
 use super::mha::MultiHeadAttention;
@@ -75,11 +75,12 @@ where
     TransformerDecoderBlock<M, H, F, D>: Module<(Tgt, Mem), Output = Tgt>,
 {
     type Output = Tgt;
-    fn forward(&self, (mut tgt, mem): (Tgt, Mem)) -> Self::Output {
+    type Error = <TransformerDecoderBlock<M, H, F, D> as Module<(Tgt, Mem)>>::Error;
+    fn try_forward(&self, (mut tgt, mem): (Tgt, Mem)) -> Result<Self::Output, Self::Error> {
         for block in self.0.modules.iter() {
-            tgt = block.forward((tgt, mem.clone()));
+            tgt = block.try_forward((tgt, mem.clone()))?;
         }
-        tgt
+        Ok(tgt)
     }
 }
 
@@ -89,9 +90,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-
-    fn forward_mut(&mut self, t: T) -> Self::Output {
-        self.forward(t)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
     }
 }
 
@@ -193,28 +194,31 @@ impl<const M: usize, const H: usize, const F: usize, D1: Device<f32>, D2: Device
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>, Tgt, Mem> Module<(Tgt, Mem)>
     for TransformerDecoderBlock<M, H, F, D>
 where
-    Tgt: SplitTape + std::ops::Add<Tgt::NoTape, Output = Tgt>,
+    Tgt: SplitTape + TryAdd<Tgt::NoTape> + HasErr<Err = D::Err>,
     Mem: Clone,
-    MultiHeadAttention<M, H, M, M, D>:
-        Module<Tgt, Output = Tgt> + Module<(Tgt, Mem, Mem), Output = Tgt>,
-    LayerNorm1D<M, D>: Module<Tgt, Output = Tgt>,
-    FF<M, F, D>: Module<Tgt, Output = Tgt>,
+    MultiHeadAttention<M, H, M, M, D>: Module<Tgt, Output = Tgt, Error = D::Err>
+        + Module<(Tgt, Mem, Mem), Output = Tgt, Error = D::Err>,
+    LayerNorm1D<M, D>: Module<Tgt, Output = Tgt, Error = D::Err>,
+    FF<M, F, D>: Module<Tgt, Output = Tgt, Error = D::Err>,
 {
     type Output = Tgt;
+    type Error = D::Err;
 
-    fn forward(&self, (tgt, mem): (Tgt, Mem)) -> Self::Output {
+    fn try_forward(&self, (tgt, mem): (Tgt, Mem)) -> Result<Self::Output, D::Err> {
         let (tgt, tape) = tgt.split_tape();
-        let x = self.self_attn.forward(tgt.clone().put_tape(tape));
-        let x = x + tgt;
-        let x = self.norm1.forward(x);
+        let x = self.self_attn.try_forward(tgt.clone().put_tape(tape))?;
+        let x = x.try_add(tgt)?;
+        let x = self.norm1.try_forward(x)?;
 
         let (x, tape) = x.split_tape();
         let x_residual = x.clone();
-        let x = self.mh_attn.forward((x.put_tape(tape), mem.clone(), mem));
-        let x = x + x_residual;
-        let x = self.norm2.forward(x);
-        let x = self.ff.forward(x);
-        self.norm3.forward(x)
+        let x = self
+            .mh_attn
+            .try_forward((x.put_tape(tape), mem.clone(), mem))?;
+        let x = x.try_add(x_residual)?;
+        let x = self.norm2.try_forward(x)?;
+        let x = self.ff.try_forward(x)?;
+        self.norm3.try_forward(x)
     }
 }
 
@@ -1,11 +1,11 @@
 use crate::{
     nn::*,
     optim::{GradientUpdate, ParamUpdater, UnusedTensors},
-    tensor::{Cpu, PutTape, SplitTape},
-    tensor_ops::Device,
+    tensor::{Cpu, HasErr, PutTape, SplitTape},
+    tensor_ops::{Device, TryAdd},
 };
 
-use super::mha::MultiHeadAttention;
+use super::mha::{ForwardWithAttention, MultiHeadAttention};
 
 /// **Requires Nightly** A transformer encoder.
 ///
@@ -110,20 +110,70 @@ impl<const M: usize, const H: usize, const F: usize, D1: Device<f32>, D2: Device
 impl<const M: usize, const H: usize, const F: usize, D: Device<f32>, Src> Module<Src>
     for TransformerEncoderBlock<M, H, F, D>
 where
-    Src: SplitTape + std::ops::Add<Src::NoTape, Output = Src>,
-    MultiHeadAttention<M, H, M, M, D>: Module<Src, Output = Src>,
-    LayerNorm1D<M, D>: Module<Src, Output = Src>,
-    FF<M, F, D>: Module<Src, Output = Src>,
+    Src: SplitTape + TryAdd<Src::NoTape> + HasErr<Err = D::Err>,
+    MultiHeadAttention<M, H, M, M, D>: Module<Src, Output = Src, Error = D::Err>,
+    LayerNorm1D<M, D>: Module<Src, Output = Src, Error = D::Err>,
+    FF<M, F, D>: Module<Src, Output = Src, Error = D::Err>,
 {
     type Output = Src;
+    type Error = D::Err;
 
-    fn forward(&self, src: Src) -> Self::Output {
+    fn try_forward(&self, src: Src) -> Result<Self::Output, D::Err> {
         let (src, tape) = src.split_tape();
-        let x = self.self_attn.forward(src.clone().put_tape(tape));
-        let x = x + src;
-        let x = self.norm1.forward(x);
-        let x = self.ff.forward(x);
-        self.norm2.forward(x)
+        let x = self.self_attn.try_forward(src.clone().put_tape(tape))?;
+        let x = x.try_add(src)?;
+        let x = self.norm1.try_forward(x)?;
+        let x = self.ff.try_forward(x)?;
+        self.norm2.try_forward(x)
+    }
+}
+
+impl<const M: usize, const H: usize, const F: usize, D: Device<f32>, Src> ForwardWithAttention<Src>
+    for TransformerEncoderBlock<M, H, F, D>
+where
+    Src: SplitTape + TryAdd<Src::NoTape> + HasErr<Err = D::Err>,
+    MultiHeadAttention<M, H, M, M, D>: ForwardWithAttention<Src, Output = Src, Error = D::Err>,
+    LayerNorm1D<M, D>: Module<Src, Output = Src, Error = D::Err>,
+    FF<M, F, D>: Module<Src, Output = Src, Error = D::Err>,
+{
+    type Attention = <MultiHeadAttention<M, H, M, M, D> as ForwardWithAttention<Src>>::Attention;
+
+    fn try_forward_with_attention(
+        &self,
+        src: Src,
+    ) -> Result<(Self::Output, Self::Attention), D::Err> {
+        let (src, tape) = src.split_tape();
+        let (x, attention) = self
+            .self_attn
+            .try_forward_with_attention(src.clone().put_tape(tape))?;
+        let x = x.try_add(src)?;
+        let x = self.norm1.try_forward(x)?;
+        let x = self.ff.try_forward(x)?;
+        Ok((self.norm2.try_forward(x)?, attention))
+    }
+}
+
+/// Returns the attention of every layer, in order.
+impl<const M: usize, const H: usize, const F: usize, const L: usize, D: Device<f32>, Src>
+    ForwardWithAttention<Src> for TransformerEncoder<M, H, F, L, D>
+where
+    TransformerEncoderBlock<M, H, F, D>: ForwardWithAttention<Src, Output = Src>,
+{
+    type Attention = std::vec::Vec<
+        <TransformerEncoderBlock<M, H, F, D> as ForwardWithAttention<Src>>::Attention,
+    >;
+
+    fn try_forward_with_attention(
+        &self,
+        mut x: Src,
+    ) -> Result<(Self::Output, Self::Attention), Self::Error> {
+        let mut attentions = std::vec::Vec::with_capacity(L);
+        for block in self.modules.iter() {
+            let (y, attention) = block.try_forward_with_attention(x)?;
+            attentions.push(attention);
+            x = y;
+        }
+        Ok((x, attentions))
     }
 }
 
@@ -133,9 +183,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-
-    fn forward_mut(&mut self, t: T) -> Self::Output {
-        self.forward(t)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
     }
 }
 
@@ -144,7 +194,7 @@ where
 mod tests {
     use super::*;
     use crate::{
-        shapes::Rank3,
+        shapes::{Const, HasShape, Rank3},
         tensor::{AsArray, SampleTensor},
         tests::*,
     };
@@ -198,4 +248,22 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_encoder_forward_with_attention() {
+        let dev: TestDevice = Default::default();
+        let encoder = TransformerEncoder::<9, 3, 16, 2>::build_on_device(&dev);
+
+        let x = dev.sample_normal::<Rank3<2, 5, 9>>();
+        let y = encoder.forward(x.clone());
+        let (y2, attentions) = encoder.forward_with_attention(x);
+        assert_eq!(y.array(), y2.array());
+        assert_eq!(attentions.len(), 2);
+        for attention in attentions {
+            assert_eq!(
+                attention.shape(),
+                &(Const::<2>, Const::<3>, Const::<5>, Const::<5>)
+            );
+        }
+    }
 }
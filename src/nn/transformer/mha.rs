@@ -1,7 +1,11 @@
 use crate::{nn::*, optim::*, tensor::*, tensor_ops::*};
 
 #[cfg(feature = "nightly")]
-use crate::{gradients::Tape, shapes::*, Assert, ConstTrue};
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::*,
+    Assert, ConstTrue,
+};
 
 /// **Requires Nightly** A multi-head attention layer.
 ///
@@ -32,6 +36,27 @@ pub struct MultiHeadAttention<
     pub w_o: Linear<V_DIM, EMBED_DIM, D>,
 }
 
+/// A module that can also return the attention probabilities it computed, for
+/// interpretability and debugging. The attention tensors don't have a tape, so
+/// inspecting them doesn't change what gets backpropagated.
+///
+/// For [MultiHeadAttention] the attention has shape `(NUM_HEADS, S1, S2)`, with a leading batch
+/// dimension for batched inputs. Each row sums to 1.
+pub trait ForwardWithAttention<Input>: Module<Input> {
+    type Attention;
+
+    /// Forward `input` through the module, and return the attention probabilities along with the output.
+    fn forward_with_attention(&self, input: Input) -> (Self::Output, Self::Attention) {
+        self.try_forward_with_attention(input).unwrap()
+    }
+
+    /// Fallible version of [ForwardWithAttention::forward_with_attention]
+    fn try_forward_with_attention(
+        &self,
+        input: Input,
+    ) -> Result<(Self::Output, Self::Attention), Self::Error>;
+}
+
 impl<const M: usize, const H: usize, const K: usize, const V: usize, D: Device<f32>>
     BuildModule<D, f32> for MultiHeadAttention<M, H, K, V, D>
 {
@@ -113,39 +138,79 @@ where
     Assert<{ S1 * H * (V / H) == S1 * V }>: ConstTrue,
 {
     type Output = Tensor<Rank2<S1, M>, f32, D, T>;
+    type Error = D::Err;
 
     /// Encoder-Decoder style self attention where one set of tensors is used for values and keys, and another is used for queries
-    fn forward(
+    fn try_forward(
+        &self,
+        qkv: (
+            Tensor<Rank2<S1, M>, f32, D, T>,
+            Tensor<Rank2<S2, M>, f32, D>,
+            Tensor<Rank2<S2, M>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        Ok(self.try_forward_with_attention(qkv)?.0)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    ForwardWithAttention<(
+        Tensor<Rank2<S1, M>, f32, D, T>,
+        Tensor<Rank2<S2, M>, f32, D>,
+        Tensor<Rank2<S2, M>, f32, D>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+where
+    Assert<{ S1 * K == S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * K == S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * V == S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ S1 * H * (V / H) == S1 * V }>: ConstTrue,
+{
+    type Attention = Tensor<Rank3<H, S1, S2>, f32, D>;
+
+    fn try_forward_with_attention(
         &self,
         (q, k, v): (
             Tensor<Rank2<S1, M>, f32, D, T>,
             Tensor<Rank2<S2, M>, f32, D>,
             Tensor<Rank2<S2, M>, f32, D>,
         ),
-    ) -> Self::Output {
-        let v: Tensor<Rank2<S2, V>, _, _, _> = self.w_v.forward(v.retaped::<T>());
-        let v = v.reshape::<Rank3<S2, H, { V / H }>>();
-        let v = v.permute::<Rank3<H, S2, { V / H }>, _>();
+    ) -> Result<(Self::Output, Self::Attention), D::Err> {
+        let v: Tensor<Rank2<S2, V>, _, _, _> = self.w_v.try_forward(v.retaped::<T>())?;
+        let v = v.try_reshape::<Rank3<S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank3<H, S2, { V / H }>, _>()?;
 
-        let k: Tensor<Rank2<S2, K>, _, _, _> = self.w_k.forward(k.retaped::<T>());
-        let k = k.reshape::<Rank3<S2, H, { K / H }>>();
-        let k = k.permute::<Rank3<H, { K / H }, S2>, _>();
+        let k: Tensor<Rank2<S2, K>, _, _, _> = self.w_k.try_forward(k.retaped::<T>())?;
+        let k = k.try_reshape::<Rank3<S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank3<H, { K / H }, S2>, _>()?;
 
-        let q: Tensor<Rank2<S1, K>, _, _, _> = self.w_q.forward(q);
-        let q = q.reshape::<Rank3<S1, H, { K / H }>>();
-        let q = q.permute::<Rank3<H, S1, { K / H }>, _>();
+        let q: Tensor<Rank2<S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank3<S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank3<H, S1, { K / H }>, _>()?;
 
         // Get weights
         let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
-        let weights: Tensor<Rank3<H, S1, S2>, _, _, _> = q.matmul(k) * scalar;
-        let weights = weights.softmax::<Axis<2>>();
+        let weights: Tensor<Rank3<H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_softmax::<Axis<2>>()?;
+        let (weights, tape) = weights.split_tape();
+        let attention = weights.clone();
+        let weights = weights.put_tape(tape);
 
         // Get new tokens
-        let tokens: Tensor<Rank3<H, S1, { V / H }>, _, _, _> = weights.matmul(v);
-        let tokens = tokens.permute::<Rank3<S1, H, { V / H }>, _>();
-        let tokens = tokens.reshape::<Rank2<S1, V>>();
+        let tokens: Tensor<Rank3<H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank3<S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank2<S1, V>>()?;
 
-        self.w_o.forward(tokens)
+        Ok((self.w_o.try_forward(tokens)?, attention))
     }
 }
 
@@ -173,39 +238,278 @@ where
     Assert<{ B * S1 * H * (V / H) == B * S1 * V }>: ConstTrue,
 {
     type Output = Tensor<Rank3<B, S1, M>, f32, D, T>;
+    type Error = D::Err;
 
     /// Batched Encoder-Decoder style self attention where one set of tensors is used for values and keys, and another is used for queries
-    fn forward(
+    fn try_forward(
+        &self,
+        qkv: (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        Ok(self.try_forward_with_attention(qkv)?.0)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    ForwardWithAttention<(
+        Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+where
+    Assert<{ B * S1 * K == B * S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * K == B * S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * V == B * S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ B * S1 * H * (V / H) == B * S1 * V }>: ConstTrue,
+{
+    type Attention = Tensor<Rank4<B, H, S1, S2>, f32, D>;
+
+    fn try_forward_with_attention(
         &self,
         (q, k, v): (
             Tensor<Rank3<B, S1, M>, f32, D, T>,
             Tensor<Rank3<B, S2, M>, f32, D>,
             Tensor<Rank3<B, S2, M>, f32, D>,
         ),
-    ) -> Self::Output {
-        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.forward(v.retaped::<T>());
-        let v = v.reshape::<Rank4<B, S2, H, { V / H }>>();
-        let v = v.permute::<Rank4<B, H, S2, { V / H }>, _>();
+    ) -> Result<(Self::Output, Self::Attention), D::Err> {
+        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.try_forward(v.retaped::<T>())?;
+        let v = v.try_reshape::<Rank4<B, S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank4<B, H, S2, { V / H }>, _>()?;
 
-        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.forward(k.retaped::<T>());
-        let k = k.reshape::<Rank4<B, S2, H, { K / H }>>();
-        let k = k.permute::<Rank4<B, H, { K / H }, S2>, _>();
+        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.try_forward(k.retaped::<T>())?;
+        let k = k.try_reshape::<Rank4<B, S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank4<B, H, { K / H }, S2>, _>()?;
 
-        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.forward(q);
-        let q = q.reshape::<Rank4<B, S1, H, { K / H }>>();
-        let q = q.permute::<Rank4<B, H, S1, { K / H }>, _>();
+        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank4<B, S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank4<B, H, S1, { K / H }>, _>()?;
 
         // Get weights
         let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
-        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.matmul(k) * scalar;
-        let weights = weights.softmax::<Axis<3>>();
+        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_softmax::<Axis<3>>()?;
+        let (weights, tape) = weights.split_tape();
+        let attention = weights.clone();
+        let weights = weights.put_tape(tape);
 
         // Get new tokens
-        let tokens: Tensor<Rank4<B, H, S1, { V / H }>, _, _, _> = weights.matmul(v);
-        let tokens = tokens.permute::<Rank4<B, S1, H, { V / H }>, _>();
-        let tokens = tokens.reshape::<Rank3<B, S1, V>>();
+        let tokens: Tensor<Rank4<B, H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank4<B, S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank3<B, S1, V>>()?;
 
-        self.w_o.forward(tokens)
+        Ok((self.w_o.try_forward(tokens)?, attention))
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D> + Merge<R>,
+        R: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank2<S1, M>, f32, D, T>,
+        Tensor<Rank2<S2, M>, f32, D>,
+        Tensor<Rank2<S2, M>, f32, D>,
+        Tensor<Rank3<H, S1, S2>, f32, D, R>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+where
+    Assert<{ S1 * K == S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * K == S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ S2 * V == S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ S1 * H * (V / H) == S1 * V }>: ConstTrue,
+{
+    type Output = Tensor<Rank2<S1, M>, f32, D, T>;
+    type Error = D::Err;
+
+    /// Encoder-Decoder style attention with a bias added to the attention scores of each head
+    /// before the softmax, like [alibi_bias()] or [RelativePositionBias].
+    fn try_forward(
+        &self,
+        (q, k, v, bias): (
+            Tensor<Rank2<S1, M>, f32, D, T>,
+            Tensor<Rank2<S2, M>, f32, D>,
+            Tensor<Rank2<S2, M>, f32, D>,
+            Tensor<Rank3<H, S1, S2>, f32, D, R>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let v: Tensor<Rank2<S2, V>, _, _, _> = self.w_v.try_forward(v.retaped::<T>())?;
+        let v = v.try_reshape::<Rank3<S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank3<H, S2, { V / H }>, _>()?;
+
+        let k: Tensor<Rank2<S2, K>, _, _, _> = self.w_k.try_forward(k.retaped::<T>())?;
+        let k = k.try_reshape::<Rank3<S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank3<H, { K / H }, S2>, _>()?;
+
+        let q: Tensor<Rank2<S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank3<S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank3<H, S1, { K / H }>, _>()?;
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank3<H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_add(bias)?;
+        let weights = weights.try_softmax::<Axis<2>>()?;
+
+        // Get new tokens
+        let tokens: Tensor<Rank3<H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank3<S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank2<S1, V>>()?;
+
+        self.w_o.try_forward(tokens)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D> + Merge<R>,
+        R: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<H, S1, S2>, f32, D, R>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+where
+    Assert<{ B * S1 * K == B * S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * K == B * S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * V == B * S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ B * S1 * H * (V / H) == B * S1 * V }>: ConstTrue,
+{
+    type Output = Tensor<Rank3<B, S1, M>, f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched Encoder-Decoder style attention with a bias added to the attention scores of each head
+    /// before the softmax. The same bias is used for every item in the batch.
+    fn try_forward(
+        &self,
+        (q, k, v, bias): (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<H, S1, S2>, f32, D, R>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.try_forward(v.retaped::<T>())?;
+        let v = v.try_reshape::<Rank4<B, S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank4<B, H, S2, { V / H }>, _>()?;
+
+        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.try_forward(k.retaped::<T>())?;
+        let k = k.try_reshape::<Rank4<B, S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank4<B, H, { K / H }, S2>, _>()?;
+
+        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank4<B, S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank4<B, H, S1, { K / H }>, _>()?;
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let bias: Tensor<Rank4<B, H, S1, S2>, _, _, _> = bias.try_broadcast()?;
+        let weights = weights.try_add(bias)?;
+        let weights = weights.try_softmax::<Axis<3>>()?;
+
+        // Get new tokens
+        let tokens: Tensor<Rank4<B, H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank4<B, S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank3<B, S1, V>>()?;
+
+        self.w_o.try_forward(tokens)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const M: usize,
+        const H: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D> + Merge<R>,
+        R: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank4<B, H, S1, S2>, f32, D, R>,
+    )> for MultiHeadAttention<M, H, K, V, D>
+where
+    Assert<{ B * S1 * K == B * S1 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * K == B * S2 * H * (K / H) }>: ConstTrue,
+    Assert<{ B * S2 * V == B * S2 * H * (V / H) }>: ConstTrue,
+    Assert<{ B * S1 * H * (V / H) == B * S1 * V }>: ConstTrue,
+{
+    type Output = Tensor<Rank3<B, S1, M>, f32, D, T>;
+    type Error = D::Err;
+
+    /// Batched Encoder-Decoder style attention with a separate bias for every item in the batch,
+    /// like the block diagonal masks of [SequencePacking::attention_bias()].
+    fn try_forward(
+        &self,
+        (q, k, v, bias): (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank4<B, H, S1, S2>, f32, D, R>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let v: Tensor<Rank3<B, S2, V>, _, _, _> = self.w_v.try_forward(v.retaped::<T>())?;
+        let v = v.try_reshape::<Rank4<B, S2, H, { V / H }>>()?;
+        let v = v.try_permute::<Rank4<B, H, S2, { V / H }>, _>()?;
+
+        let k: Tensor<Rank3<B, S2, K>, _, _, _> = self.w_k.try_forward(k.retaped::<T>())?;
+        let k = k.try_reshape::<Rank4<B, S2, H, { K / H }>>()?;
+        let k = k.try_permute::<Rank4<B, H, { K / H }, S2>, _>()?;
+
+        let q: Tensor<Rank3<B, S1, K>, _, _, _> = self.w_q.try_forward(q)?;
+        let q = q.try_reshape::<Rank4<B, S1, H, { K / H }>>()?;
+        let q = q.try_permute::<Rank4<B, H, S1, { K / H }>, _>()?;
+
+        // Get weights
+        let scalar: f32 = 1.0 / ((K / H) as f32).sqrt();
+        let weights: Tensor<Rank4<B, H, S1, S2>, _, _, _> = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = weights.try_add(bias)?;
+        let weights = weights.try_softmax::<Axis<3>>()?;
+
+        // Get new tokens
+        let tokens: Tensor<Rank4<B, H, S1, { V / H }>, _, _, _> = weights.try_matmul(v)?;
+        let tokens = tokens.try_permute::<Rank4<B, S1, H, { V / H }>, _>()?;
+        let tokens = tokens.try_reshape::<Rank3<B, S1, V>>()?;
+
+        self.w_o.try_forward(tokens)
     }
 }
 
@@ -217,9 +521,27 @@ where
     Self: Module<(Src, Src::NoTape, Src::NoTape), Output = Src>,
 {
     type Output = Src;
-    fn forward(&self, src: Src) -> Self::Output {
+    type Error = <Self as Module<(Src, Src::NoTape, Src::NoTape)>>::Error;
+    fn try_forward(&self, src: Src) -> Result<Self::Output, Self::Error> {
         let (src, tape) = src.split_tape();
-        self.forward((src.clone().put_tape(tape), src.clone(), src))
+        self.try_forward((src.clone().put_tape(tape), src.clone(), src))
+    }
+}
+
+impl<const M: usize, const H: usize, const K: usize, const V: usize, D, Src>
+    ForwardWithAttention<Src> for MultiHeadAttention<M, H, K, V, D>
+where
+    D: Device<f32>,
+    Src: SplitTape,
+    Self: ForwardWithAttention<(Src, Src::NoTape, Src::NoTape), Output = Src>,
+{
+    type Attention = <Self as ForwardWithAttention<(Src, Src::NoTape, Src::NoTape)>>::Attention;
+    fn try_forward_with_attention(
+        &self,
+        src: Src,
+    ) -> Result<(Self::Output, Self::Attention), Self::Error> {
+        let (src, tape) = src.split_tape();
+        self.try_forward_with_attention((src.clone().put_tape(tape), src.clone(), src))
     }
 }
 
@@ -229,9 +551,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-
-    fn forward_mut(&mut self, t: T) -> Self::Output {
-        self.forward(t)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
     }
 }
 
@@ -240,6 +562,7 @@ where
 mod tests {
     use super::*;
     use crate::{
+        gradients::OwnedTape,
         nn::tests::SimpleUpdater,
         tests::{assert_close, TestDevice},
     };
@@ -333,6 +656,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mha_forward_with_attention() {
+        let dev: TestDevice = Default::default();
+        let mha = MultiHeadAttention::<8, 2>::build_on_device(&dev);
+
+        let q = dev.sample_normal::<Rank3<2, 3, 8>>();
+        let k = dev.sample_normal::<Rank3<2, 4, 8>>();
+        let v = dev.sample_normal::<Rank3<2, 4, 8>>();
+        let y = mha.forward((q.clone(), k.clone(), v.clone()));
+        let (y2, attention) = mha.forward_with_attention((q.trace(), k, v));
+        assert_eq!(y.array(), y2.array());
+        assert_close(
+            &attention.sum::<Rank3<2, 2, 3>, _>().array(),
+            &[[[1.0; 3]; 2]; 2],
+        );
+
+        let x = dev.sample_normal::<Rank2<3, 8>>();
+        let (_, attention): (_, Tensor<Rank3<2, 3, 3>, f32, _>) = mha.forward_with_attention(x);
+        assert_close(&attention.sum::<Rank2<2, 3>, Axis<2>>().array(), &[[1.0; 3]; 2]);
+    }
+
     #[test]
     fn test_backward_updates_all() {
         let dev: TestDevice = Default::default();
@@ -349,4 +693,38 @@ mod tests {
         mha.update(&mut g, &mut unused).unwrap();
         assert!(unused.is_empty());
     }
+
+    #[test]
+    fn test_mha_with_bias() {
+        let dev: TestDevice = Default::default();
+
+        let mha = MultiHeadAttention::<12, 4>::build_on_device(&dev);
+
+        let q = dev.sample_normal::<Rank3<2, 3, 12>>();
+        let k = dev.sample_normal::<Rank3<2, 4, 12>>();
+        let v = dev.sample_normal::<Rank3<2, 4, 12>>();
+
+        let zero_bias: Tensor<Rank3<4, 3, 4>, f32, _> = dev.zeros();
+        let y1 = mha.forward((q.clone(), k.clone(), v.clone()));
+        let y2 = mha.forward((q.clone(), k.clone(), v.clone(), zero_bias));
+        assert_close(&y1.array(), &y2.array());
+
+        let rel: RelativePositionBias<4> = BuildModule::build(&dev);
+        let y = mha.forward((
+            q.trace(),
+            k.clone(),
+            v.clone(),
+            rel.bias::<3, 4, OwnedTape<_>>(),
+        ));
+        let g = y.mean().backward();
+        assert_ne!(g.get(&rel.table).array(), [[0.0; 4]; 32]);
+
+        let y = mha.forward((
+            dev.sample_normal::<Rank2<3, 12>>(),
+            k.select(dev.tensor(0)),
+            v.select(dev.tensor(0)),
+            alibi_bias(&dev),
+        ));
+        assert_eq!(y.array().len(), 3);
+    }
 }
@@ -1,10 +1,16 @@
+mod cross_attention;
 mod decoder;
 mod encoder;
 mod mha;
+#[cfg(feature = "nightly")]
+mod prefix;
 
+pub use cross_attention::*;
 pub use decoder::*;
 pub use encoder::*;
 pub use mha::*;
+#[cfg(feature = "nightly")]
+pub use prefix::*;
 
 use crate::{
     optim::{GradientUpdate, ParamUpdater, UnusedTensors},
@@ -117,17 +123,19 @@ impl<
         Tgt: PutTape<Src::Tape>,
     > Module<(Src, Tgt)> for Transformer<M, H, EL, DL, F, D>
 where
-    TransformerEncoder<M, H, F, EL, D>: Module<Src, Output = Src>,
+    TransformerEncoder<M, H, F, EL, D>: Module<Src, Output = Src, Error = D::Err>,
     TransformerDecoder<M, H, F, DL, D>: Module<
         (<Tgt as PutTape<Src::Tape>>::Output, Src::NoTape),
         Output = <Tgt as PutTape<Src::Tape>>::Output,
+        Error = D::Err,
     >,
 {
     type Output = <Tgt as PutTape<Src::Tape>>::Output;
+    type Error = D::Err;
 
-    fn forward(&self, (src, tgt): (Src, Tgt)) -> Self::Output {
-        let (mem, tape) = self.encoder.forward(src).split_tape();
-        self.decoder.forward((tgt.put_tape(tape), mem))
+    fn try_forward(&self, (src, tgt): (Src, Tgt)) -> Result<Self::Output, D::Err> {
+        let (mem, tape) = self.encoder.try_forward(src)?.split_tape();
+        self.decoder.try_forward((tgt.put_tape(tape), mem))
     }
 }
 
@@ -138,8 +146,9 @@ where
     Self: Module<T>,
 {
     type Output = <Self as Module<T>>::Output;
-    fn forward_mut(&mut self, t: T) -> Self::Output {
-        self.forward(t)
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
     }
 }
 
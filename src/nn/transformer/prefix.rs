@@ -0,0 +1,335 @@
+use crate::{gradients::*, nn::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::MultiHeadAttention;
+
+/// **Requires Nightly** Prefix tuning for a frozen [MultiHeadAttention], as introduced in
+/// [Prefix-Tuning: Optimizing Continuous Prompts for Generation](https://arxiv.org/abs/2101.00190).
+///
+/// `P` trainable virtual tokens are prepended to the keys & values before they are passed
+/// to [Self::attn]. Only [Self::prefix_k] and [Self::prefix_v] are updated by optimizers,
+/// [Self::attn] is never modified.
+///
+/// Generics:
+/// - `EMBED_DIM`: The size of query vectors.
+/// - `NUM_HEADS` The number of heads to split query/key/value into.
+/// - `P`: The number of virtual tokens.
+/// - *Optional* `K_DIM`: The size of key vectors. Defaults to `EMBED_DIM`
+/// - *Optional* `V_DIM` The size of value vectors. Defaults to `EMBED_DIM`
+#[derive(Debug, Clone)]
+pub struct PrefixTuning<
+    const EMBED_DIM: usize,
+    const NUM_HEADS: usize,
+    const P: usize,
+    const K_DIM: usize = EMBED_DIM,
+    const V_DIM: usize = EMBED_DIM,
+    D: Device<f32> = Cpu,
+> {
+    pub attn: MultiHeadAttention<EMBED_DIM, NUM_HEADS, K_DIM, V_DIM, D>,
+    pub prefix_k: Tensor<Rank2<P, EMBED_DIM>, f32, D>,
+    pub prefix_v: Tensor<Rank2<P, EMBED_DIM>, f32, D>,
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D>
+    PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    /// Adds `P` freshly initialized virtual tokens to an existing (e.g. pretrained) attention layer.
+    pub fn from_attn(attn: MultiHeadAttention<M, H, K, V, D>) -> Self {
+        Self::try_from_attn(attn).unwrap()
+    }
+
+    /// Fallible version of [PrefixTuning::from_attn()]
+    pub fn try_from_attn(attn: MultiHeadAttention<M, H, K, V, D>) -> Result<Self, D::Err> {
+        let device = attn.w_q.weight.device.clone();
+        let bound: f32 = 1.0 / (M as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        Ok(Self {
+            prefix_k: device.try_sample(distr)?,
+            prefix_v: device.try_sample(distr)?,
+            attn,
+        })
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D>
+    BuildModule<D, f32> for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn try_build(device: &D) -> Result<Self, <D>::Err> {
+        Self::try_from_attn(BuildModule::try_build(device)?)
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D>
+    ResetParams<D, f32> for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    /// Resets only the virtual tokens, [Self::attn] is left unchanged.
+    fn try_reset_params(&mut self) -> Result<(), <D>::Err> {
+        let bound: f32 = 1.0 / (M as f32).sqrt();
+        let distr = rand_distr::Uniform::new(-bound, bound);
+        self.prefix_k.try_fill_with_distr(distr)?;
+        self.prefix_v.try_fill_with_distr(distr)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D>
+    GradientUpdate<D, f32> for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.prefix_k.update(updater, unused)?;
+        self.prefix_v.update(updater, unused)?;
+        Ok(())
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D1, D2>
+    ToDevice<D2> for PrefixTuning<M, H, P, K, V, D1>
+where
+    D1: Device<f32>,
+    D2: Device<f32>,
+{
+    type Output = PrefixTuning<M, H, P, K, V, D2>;
+
+    fn to_device(&self, device: &D2) -> Self::Output {
+        PrefixTuning {
+            attn: self.attn.to_device(device),
+            prefix_k: self.prefix_k.to_device(device),
+            prefix_v: self.prefix_v.to_device(device),
+        }
+    }
+}
+
+/// Matrices that place `P` rows & `S` rows into `P + S` rows, transposed.
+fn prepend_selections<const P: usize, const S: usize, D: Device<f32>>(
+    dev: &D,
+) -> (
+    Tensor<Rank2<P, { P + S }>, f32, D>,
+    Tensor<Rank2<S, { P + S }>, f32, D>,
+) {
+    let mut sel_p = dev.zeros();
+    let mut data = std::vec![0.0; P * (P + S)];
+    for i in 0..P {
+        data[i * (P + S) + i] = 1.0;
+    }
+    sel_p.copy_from(&data);
+
+    let mut sel_s = dev.zeros();
+    let mut data = std::vec![0.0; S * (P + S)];
+    for i in 0..S {
+        data[i * (P + S) + P + i] = 1.0;
+    }
+    sel_s.copy_from(&data);
+    (sel_p, sel_s)
+}
+
+impl<
+        const M: usize,
+        const H: usize,
+        const P: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank2<S1, M>, f32, D, T>,
+        Tensor<Rank2<S2, M>, f32, D>,
+        Tensor<Rank2<S2, M>, f32, D>,
+    )> for PrefixTuning<M, H, P, K, V, D>
+where
+    MultiHeadAttention<M, H, K, V, D>: Module<
+        (
+            Tensor<Rank2<S1, M>, f32, D, T>,
+            Tensor<Rank2<{ P + S2 }, M>, f32, D>,
+            Tensor<Rank2<{ P + S2 }, M>, f32, D>,
+        ),
+        Output = Tensor<Rank2<S1, M>, f32, D, T>,
+        Error = D::Err,
+    >,
+{
+    type Output = Tensor<Rank2<S1, M>, f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(
+        &self,
+        (q, k, v): (
+            Tensor<Rank2<S1, M>, f32, D, T>,
+            Tensor<Rank2<S2, M>, f32, D>,
+            Tensor<Rank2<S2, M>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let (sel_p, sel_s) = prepend_selections::<P, S2, D>(&k.device);
+
+        let prefix_k = self
+            .prefix_k
+            .retaped::<T>()
+            .try_permute::<Rank2<M, P>, _>()?;
+        let k = prefix_k.try_matmul(sel_p.clone())?.try_add(
+            k.try_permute::<Rank2<M, S2>, _>()?
+                .try_matmul(sel_s.clone())?,
+        )?;
+        let (k, k_tape) = k.try_permute::<Rank2<{ P + S2 }, M>, _>()?.split_tape();
+
+        let prefix_v = self
+            .prefix_v
+            .retaped::<T>()
+            .try_permute::<Rank2<M, P>, _>()?;
+        let v = prefix_v
+            .try_matmul(sel_p)?
+            .try_add(v.try_permute::<Rank2<M, S2>, _>()?.try_matmul(sel_s)?)?;
+        let (v, v_tape) = v.try_permute::<Rank2<{ P + S2 }, M>, _>()?.split_tape();
+
+        let (q, tape) = q.split_tape();
+        let q = q.put_tape(tape.merge(k_tape).merge(v_tape));
+        self.attn.try_forward((q, k, v))
+    }
+}
+
+impl<
+        const M: usize,
+        const H: usize,
+        const P: usize,
+        const K: usize,
+        const V: usize,
+        D: Device<f32>,
+        const B: usize,
+        const S1: usize,
+        const S2: usize,
+        T: Tape<D>,
+    >
+    Module<(
+        Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+        Tensor<Rank3<B, S2, M>, f32, D>,
+    )> for PrefixTuning<M, H, P, K, V, D>
+where
+    MultiHeadAttention<M, H, K, V, D>: Module<
+        (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, { P + S2 }, M>, f32, D>,
+            Tensor<Rank3<B, { P + S2 }, M>, f32, D>,
+        ),
+        Output = Tensor<Rank3<B, S1, M>, f32, D, T>,
+        Error = D::Err,
+    >,
+{
+    type Output = Tensor<Rank3<B, S1, M>, f32, D, T>;
+    type Error = D::Err;
+
+    fn try_forward(
+        &self,
+        (q, k, v): (
+            Tensor<Rank3<B, S1, M>, f32, D, T>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+            Tensor<Rank3<B, S2, M>, f32, D>,
+        ),
+    ) -> Result<Self::Output, D::Err> {
+        let (sel_p, sel_s) = prepend_selections::<P, S2, D>(&k.device);
+
+        let prefix_k = self
+            .prefix_k
+            .retaped::<T>()
+            .try_permute::<Rank2<M, P>, _>()?;
+        let prefix_k = prefix_k.try_matmul(sel_p.clone())?.try_broadcast()?;
+        let k = prefix_k.try_add(
+            k.try_permute::<Rank3<B, M, S2>, _>()?
+                .try_matmul(sel_s.clone())?,
+        )?;
+        let (k, k_tape) = k.try_permute::<Rank3<B, { P + S2 }, M>, _>()?.split_tape();
+
+        let prefix_v = self
+            .prefix_v
+            .retaped::<T>()
+            .try_permute::<Rank2<M, P>, _>()?;
+        let prefix_v = prefix_v.try_matmul(sel_p)?.try_broadcast()?;
+        let v = prefix_v.try_add(v.try_permute::<Rank3<B, M, S2>, _>()?.try_matmul(sel_s)?)?;
+        let (v, v_tape) = v.try_permute::<Rank3<B, { P + S2 }, M>, _>()?.split_tape();
+
+        let (q, tape) = q.split_tape();
+        let q = q.put_tape(tape.merge(k_tape).merge(v_tape));
+        self.attn.try_forward((q, k, v))
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D, Src>
+    Module<Src> for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+    Src: SplitTape,
+    Self: Module<(Src, Src::NoTape, Src::NoTape), Output = Src>,
+{
+    type Output = Src;
+    type Error = <Self as Module<(Src, Src::NoTape, Src::NoTape)>>::Error;
+    fn try_forward(&self, src: Src) -> Result<Self::Output, Self::Error> {
+        let (src, tape) = src.split_tape();
+        self.try_forward((src.clone().put_tape(tape), src.clone(), src))
+    }
+}
+
+impl<const M: usize, const H: usize, const P: usize, const K: usize, const V: usize, D, T>
+    ModuleMut<T> for PrefixTuning<M, H, P, K, V, D>
+where
+    D: Device<f32>,
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, t: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_prefix_tuning_matches_attention_over_concatenated_kv() {
+        let dev = TestDevice::seed_from_u64(0);
+        let prefix: PrefixTuning<4, 2, 2> = BuildModule::build(&dev);
+
+        let q = dev.sample_normal::<Rank2<3, 4>>();
+        let k = dev.sample_normal::<Rank2<1, 4>>();
+        let v = dev.sample_normal::<Rank2<1, 4>>();
+        let y = prefix.forward((q.clone(), k.clone(), v.clone()));
+
+        let pk = prefix.prefix_k.array();
+        let pv = prefix.prefix_v.array();
+        let k_all = dev.tensor([pk[0], pk[1], k.array()[0]]);
+        let v_all = dev.tensor([pv[0], pv[1], v.array()[0]]);
+        let expected = prefix.attn.forward((q, k_all, v_all));
+        assert_close(&y.array(), &expected.array());
+    }
+
+    #[test]
+    fn test_prefix_tuning_batched_gradients() {
+        let dev = TestDevice::seed_from_u64(1);
+        let mut prefix: PrefixTuning<4, 2, 3> = BuildModule::build(&dev);
+
+        let q = dev.sample_normal::<Rank3<2, 3, 4>>();
+        let y = prefix.forward_mut(q.trace());
+        let g = y.square().mean().backward();
+        assert_ne!(g.get(&prefix.prefix_k).array(), [[0.0; 4]; 3]);
+        assert_ne!(g.get(&prefix.prefix_v).array(), [[0.0; 4]; 3]);
+
+        let mut opt = crate::optim::Sgd::new(&prefix, Default::default());
+        let w_q = prefix.attn.w_q.weight.array();
+        let prefix_k = prefix.prefix_k.array();
+        opt.update(&mut prefix, g).expect("");
+        assert_eq!(prefix.attn.w_q.weight.array(), w_q);
+        assert_ne!(prefix.prefix_k.array(), prefix_k);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{gradients::Tape, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::module::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+
+/// A linear transformation of the form `weight * x`, where `weight` is a matrix, `x` is a vector or matrix.
+/// This is a [super::Linear] without the bias, for when the layer is followed by a normalization
+/// that would cancel out the bias anyway.
+///
+/// Initializes [Self::weight] from a Uniform distribution between [-1 / sqrt(I), 1 / sqrt(I)].
+///
+/// [Self::weight] is saved with the same name as [super::Linear::weight], so the weights of a
+/// saved [super::Linear] can be loaded into this (the bias is ignored).
+///
+/// # Generics
+/// - `I` The "input" size of vectors & matrices.
+/// - `O` The "output" size of vectors & matrices.
+///
+/// # Examples
+/// `UnbiasedLinear<5, 2>` can act on vectors with 5 elements, and results in vectors with 2 elements.
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = UnbiasedLinear<5, 2>;
+/// let model = Model::build_on_device(&dev);
+/// // single item forward
+/// let _: Tensor<Rank1<2>, f32, _> = model.forward(dev.zeros::<Rank1<5>>());
+/// // batched forward
+/// let _: Tensor<Rank2<10, 2>, f32, _> = model.forward(dev.zeros::<Rank2<10, 5>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnbiasedLinear<const I: usize, const O: usize, D: Device<f32> = Cpu> {
+    /// Transposed weight matrix, shape (I, O)
+    pub weight: Tensor<Rank2<O, I>, f32, D>,
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> GradientUpdate<D, f32>
+    for UnbiasedLinear<I, O, D>
+{
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        self.weight.update(updater, unused)
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> BuildModule<D, f32>
+    for UnbiasedLinear<I, O, D>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        let weight = device.try_sample(rand_distr::Uniform::new(-bound, bound))?;
+        Ok(Self { weight })
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>> ResetParams<D, f32>
+    for UnbiasedLinear<I, O, D>
+{
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / (I as f32).sqrt();
+        self.weight
+            .try_fill_with_distr(rand_distr::Uniform::new(-bound, bound))
+    }
+}
+
+impl<const I: usize, const O: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2>
+    for UnbiasedLinear<I, O, D1>
+{
+    type Output = UnbiasedLinear<I, O, D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        UnbiasedLinear {
+            weight: self.weight.to_device(device),
+        }
+    }
+}
+
+impl<const I: usize, const O: usize, D: Device<f32>, T> Module<T> for UnbiasedLinear<I, O, D>
+where
+    T: SplitTape + TryMatMul<Tensor<Rank2<I, O>, f32, D, T::Tape>, Err = D::Err>,
+    T::Tape: Tape<D>,
+{
+    type Output = T::Output;
+    type Error = D::Err;
+
+    /// Forward using [matmul()].
+    fn try_forward(&self, x: T) -> Result<Self::Output, D::Err> {
+        x.try_matmul(self.weight.retaped::<T::Tape>().try_permute()?)
+    }
+}
+
+impl<T, const I: usize, const O: usize, D: Device<f32>> ModuleMut<T> for UnbiasedLinear<I, O, D>
+where
+    Self: Module<T>,
+{
+    type Output = <Self as Module<T>>::Output;
+    type Error = <Self as Module<T>>::Error;
+    fn try_forward_mut(&mut self, input: T) -> Result<Self::Output, Self::Error> {
+        self.try_forward(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        nn::{tests::SimpleUpdater, BuildOnDevice, Linear},
+        tests::*,
+    };
+
+    #[test]
+    fn test_unbiased_linear_matches_linear_with_zero_bias() {
+        let dev: TestDevice = Default::default();
+        let m = UnbiasedLinear::<5, 3>::build_on_device(&dev);
+        let linear = Linear {
+            weight: m.weight.clone(),
+            bias: dev.zeros(),
+        };
+
+        let x: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        assert_close(&m.forward(x.clone()).array(), &linear.forward(x).array());
+
+        let x: Tensor<Rank3<2, 4, 5>, f32, _> = dev.sample_normal();
+        let y = m.forward(x.trace());
+        assert_close(&y.array(), &linear.forward(x.clone()).array());
+
+        let g = y.exp().mean().backward();
+        let g2 = linear.forward(x.trace()).exp().mean().backward();
+        assert_close(&g.get(&m.weight).array(), &g2.get(&linear.weight).array());
+    }
+
+    #[test]
+    fn test_unbiased_linear_all_params_used() {
+        let dev: TestDevice = Default::default();
+        let mut m = UnbiasedLinear::<5, 3>::build_on_device(&dev);
+        let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        let mut updater = SimpleUpdater(m.forward(x.trace()).exp().mean().backward());
+        let mut unused = Default::default();
+        m.update(&mut updater, &mut unused).unwrap();
+        assert!(unused.is_empty());
+    }
+}
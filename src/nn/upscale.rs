@@ -0,0 +1,81 @@
+use crate::{
+    shapes::Dtype,
+    tensor_ops::{ConstUpsample2D, Device, UpsampleMode},
+};
+
+use super::{BuildModule, Module, NonMutableModule, ZeroSizedModule};
+
+/// Resizes images (3d) and batches of images (4d) of any height & width to `(OH, OW)`,
+/// by interpolating with [Self::mode]. Defaults to [UpsampleMode::Nearest].
+///
+/// Unlike a transposed convolution, this has no parameters, which is what UNet & FPN
+/// decoders usually use to bring coarse feature maps back up to the size of the skip
+/// connections.
+///
+/// **Pytorch equivalent**: `torch.nn.Upsample(size=(OH, OW), mode, align_corners)`
+///
+/// Generics:
+/// - `OH`: The height of the output images.
+/// - `OW`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: Upscale2D<16, 16> = Default::default();
+/// let _: Tensor<Rank4<2, 3, 16, 16>, f32, _> = m.forward(dev.zeros::<Rank4<2, 3, 8, 8>>());
+/// let m: Upscale2D<9, 9> = Upscale2D {
+///     mode: UpsampleMode::Bilinear { align_corners: true },
+/// };
+/// let _: Tensor<Rank3<3, 9, 9>, f32, _> = m.forward(dev.zeros::<Rank3<3, 5, 5>>());
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Upscale2D<const OH: usize, const OW: usize> {
+    pub mode: UpsampleMode,
+}
+
+impl<const OH: usize, const OW: usize> ZeroSizedModule for Upscale2D<OH, OW> {}
+impl<const OH: usize, const OW: usize> NonMutableModule for Upscale2D<OH, OW> {}
+
+impl<const OH: usize, const OW: usize, D: Device<E>, E: Dtype> BuildModule<D, E>
+    for Upscale2D<OH, OW>
+{
+    fn try_build(_: &D) -> Result<Self, <D>::Err> {
+        Ok(Default::default())
+    }
+}
+
+impl<const OH: usize, const OW: usize, Img: ConstUpsample2D<OH, OW>> Module<Img>
+    for Upscale2D<OH, OW>
+{
+    type Output = Img::Output;
+    type Error = Img::Err;
+    fn try_forward(&self, x: Img) -> Result<Self::Output, Img::Err> {
+        x.try_upsample2d(self.mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_upscale2d_modes() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank4<2, 3, 4, 5>, f32, _> = dev.sample_normal();
+
+        let m: Upscale2D<8, 10> = Default::default();
+        let y = m.forward(x.clone());
+        assert_eq!(y.array(), x.clone().upsample_nearest2d::<8, 10>().array());
+
+        for align_corners in [false, true] {
+            let m: Upscale2D<7, 3> = Upscale2D {
+                mode: UpsampleMode::Bilinear { align_corners },
+            };
+            let y = m.forward(x.clone());
+            let expected = x.clone().upsample_bilinear2d::<7, 3>(align_corners);
+            assert_eq!(y.array(), expected.array());
+        }
+    }
+}
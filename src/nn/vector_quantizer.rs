@@ -0,0 +1,491 @@
+use crate::{gradients::*, optim::*, shapes::*, tensor::*, tensor_ops::*};
+
+use super::{BuildModule, Module, ModuleMut, ResetParams, ToDevice};
+
+/// The codebook of a [VQ-VAE](https://arxiv.org/abs/1711.00937), which replaces each row of a
+/// `(batch, DIM)` input with the closest of its `K` codes. The codebook is learned with
+/// exponential moving averages, as described in the appendix of
+/// [Neural Discrete Representation Learning](https://arxiv.org/abs/1711.00937).
+///
+/// Generics:
+/// - `K` the number of codes.
+/// - `DIM` the size of each code, and of the rows of the input.
+///
+/// # Training vs Inference
+///
+/// VectorQuantizer supports the following cases (see sections below for more details):
+/// 1. **Training**: [VectorQuantizer::forward_with_loss()] or [ModuleMut], and [OwnedTape] on
+///    the input tensor
+/// 2. **Inference**: [Module] and [NoneTape] on the input tensor.
+///
+/// *NOTE: ModuleMut/NoneTape, and Module/OwnedTape will fail to compile.*
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let encoder: Linear<8, 4> = BuildModule::build(&dev);
+/// let mut vq: VectorQuantizer<16, 4> = BuildModule::build(&dev);
+/// let decoder: Linear<4, 8> = BuildModule::build(&dev);
+/// let x: Tensor<Rank2<10, 8>, f32, _> = dev.sample_normal();
+/// let (z, commitment_loss) = vq.forward_with_loss(encoder.forward(x.trace()));
+/// let loss = mse_loss(decoder.forward(z), x.clone(), ReduceMean) + commitment_loss;
+/// let grads = loss.backward();
+///
+/// // the indices of the codes, e.g. as tokens for a prior
+/// let latents = encoder.forward(x);
+/// let tokens: Tensor<(Const<10>,), usize, _> = vq.encode(&latents);
+/// let _: Tensor<Rank2<10, 4>, f32, _> = vq.decode(tokens);
+/// ```
+///
+/// ### Training
+/// - Output: the closest codes, with the *straight-through* gradient of the input
+///   `z + stop_grad(code - z)`, so the layers before are trained through the quantization.
+/// - Commitment loss: `commitment * mean((z - stop_grad(code))^2)`, returned by
+///   [VectorQuantizer::forward_with_loss()]. Add it to the loss the backward pass starts from
+///   to train the layers before toward the codes. [ModuleMut] doesn't compute it, so it can
+///   be used inside other modules when the commitment loss isn't needed.
+/// - Codebook: updated with moving averages of the number & sum of the rows assigned to each
+///   code. It isn't modified by optimizers.
+///
+/// ### Inference
+/// - Output: the closest codes
+/// - Codebook: **not** updated
+#[derive(Clone, Debug)]
+pub struct VectorQuantizer<const K: usize, const DIM: usize, D: Device<f32> = Cpu> {
+    /// The `K` codes, which are `code_sum / cluster_size`. Initialized from a Uniform
+    /// distribution between [-1 / K, 1 / K].
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
+    pub codebook: Tensor<Rank2<K, DIM>, f32, D>,
+    /// Moving average of the number of rows assigned to each code. Defaults to 1.0.
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
+    pub cluster_size: Tensor<Rank1<K>, f32, D>,
+    /// Moving average of the sum of the rows assigned to each code. Defaults to the codebook.
+    ///
+    /// This is a buffer, so it is never modified by optimizers.
+    pub code_sum: Tensor<Rank2<K, DIM>, f32, D>,
+    /// The weight of the commitment loss. Defaults to 0.25
+    pub commitment: f32,
+    /// Controls the moving averages. Defaults to 0.99
+    ///
+    /// `average * decay + batch_stat * (1.0 - decay)`.
+    pub decay: f32,
+    /// The smallest `cluster_size` divided by. Codes that aren't used keep their value until
+    /// their `cluster_size` decays below this, and then shrink toward 0. Defaults to 1e-5
+    pub epsilon: f32,
+}
+
+impl<const K: usize, const DIM: usize, D: Device<f32>> VectorQuantizer<K, DIM, D> {
+    /// The squared euclidean distance from each row of `z` to each code, with shape `(batch, K)`.
+    pub fn distances<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Tensor<(B, Const<K>), f32, D> {
+        self.try_distances(z).unwrap()
+    }
+
+    /// Fallible version of [VectorQuantizer::distances()]
+    pub fn try_distances<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Result<Tensor<(B, Const<K>), f32, D>, D::Err> {
+        let z = z.retaped::<NoneTape>();
+        let shape = (z.shape().0, Const::<K>);
+        let zz = z
+            .clone()
+            .try_square()?
+            .try_sum::<(B,), Axis<1>>()?
+            .try_broadcast_like::<_, Axis<1>>(&shape)?;
+        let cc = self
+            .codebook
+            .clone()
+            .try_square()?
+            .try_sum::<Rank1<K>, Axis<1>>()?
+            .try_broadcast_like::<_, Axis<0>>(&shape)?;
+        let zc = z.try_matmul(self.codebook.clone().try_permute::<_, Axes2<1, 0>>()?)?;
+        // rounding can make the distance from a code to itself slightly negative
+        zz.try_add(cc)?.try_sub(zc.try_mul(2.0)?)?.try_relu()
+    }
+
+    /// The index of the closest code to each row of `z`.
+    pub fn encode<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Tensor<(B,), usize, D> {
+        self.try_encode(z).unwrap()
+    }
+
+    /// Fallible version of [VectorQuantizer::encode()]
+    pub fn try_encode<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Result<Tensor<(B,), usize, D>, D::Err> {
+        self.try_distances(z)?.try_argmin::<Axis<1>>()
+    }
+
+    /// The codes at `indices`.
+    pub fn decode<B: Dim>(
+        &self,
+        indices: Tensor<(B,), usize, D>,
+    ) -> Tensor<(B, Const<DIM>), f32, D> {
+        self.try_decode(indices).unwrap()
+    }
+
+    /// Fallible version of [VectorQuantizer::decode()]
+    pub fn try_decode<B: Dim>(
+        &self,
+        indices: Tensor<(B,), usize, D>,
+    ) -> Result<Tensor<(B, Const<DIM>), f32, D>, D::Err> {
+        self.codebook.clone().try_gather(indices)
+    }
+
+    /// The commitment loss of `z`, `commitment * mean((z - code)^2)`, without a tape. This is
+    /// for logging, see [VectorQuantizer::forward_with_loss()] to train with it.
+    pub fn commitment_loss<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Tensor<Rank0, f32, D> {
+        self.try_commitment_loss(z).unwrap()
+    }
+
+    /// Fallible version of [VectorQuantizer::commitment_loss()]
+    pub fn try_commitment_loss<B: Dim, T: Tape<D>>(
+        &self,
+        z: &Tensor<(B, Const<DIM>), f32, D, T>,
+    ) -> Result<Tensor<Rank0, f32, D>, D::Err> {
+        let codes = self.try_decode(self.try_encode(z)?)?;
+        z.retaped::<NoneTape>()
+            .try_sub(codes)?
+            .try_square()?
+            .try_mean::<Rank0, _>()?
+            .try_mul(self.commitment)
+    }
+}
+
+impl<const K: usize, const DIM: usize, D> VectorQuantizer<K, DIM, D>
+where
+    D: Device<f32> + TensorFromVec<f32> + CopySlice<usize>,
+{
+    /// Training forward that also returns the commitment loss. Updates [Self::codebook] after
+    /// quantizing, like [ModuleMut].
+    ///
+    /// The loss doesn't have a tape, since its operations are recorded on the tape of the
+    /// output (like the heads of [crate::nn::SplitInto]). Add it to a loss computed from the
+    /// output, e.g. `reconstruction_loss + commitment_loss`, for its gradient to reach the input.
+    #[allow(clippy::type_complexity)]
+    pub fn forward_with_loss<B: Dim>(
+        &mut self,
+        z: Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+    ) -> (
+        Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+        Tensor<Rank0, f32, D>,
+    ) {
+        self.try_forward_with_loss(z).unwrap()
+    }
+
+    /// Fallible version of [VectorQuantizer::forward_with_loss()]
+    #[allow(clippy::type_complexity)]
+    pub fn try_forward_with_loss<B: Dim>(
+        &mut self,
+        z: Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+    ) -> Result<
+        (
+            Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+            Tensor<Rank0, f32, D>,
+        ),
+        D::Err,
+    > {
+        let (z, tape) = z.split_tape();
+        let indices = self.try_encode(&z)?;
+        let codes = self.try_decode(indices.clone())?;
+        // recorded before the straight-through op, so the loss's gradient reaches `z`
+        // before `z`'s gradient is used
+        let (loss, tape) = z
+            .clone()
+            .put_tape(tape)
+            .try_sub(codes.clone())?
+            .try_square()?
+            .try_mean::<Rank0, _>()?
+            .try_mul(self.commitment)?
+            .split_tape();
+        let output = self.try_quantize(z.put_tape(tape), codes, &indices)?;
+        Ok((output, loss))
+    }
+
+    /// Updates the codebook, and replaces `z` with `codes` using the straight-through gradient.
+    #[allow(clippy::type_complexity)]
+    fn try_quantize<B: Dim>(
+        &mut self,
+        z: Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+        codes: Tensor<(B, Const<DIM>), f32, D>,
+        indices: &Tensor<(B,), usize, D>,
+    ) -> Result<Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>, D::Err> {
+        let (z, tape) = z.split_tape();
+        self.try_update_codebook(&z, indices)?;
+
+        // straight-through: the value of the codes, and the gradient of `z`
+        let diff = codes.try_sub(z.clone())?;
+        z.put_tape(tape).try_add(diff)
+    }
+
+    /// Moves the averages toward the number & sum of the rows of `z` assigned to each code.
+    fn try_update_codebook<B: Dim>(
+        &mut self,
+        z: &Tensor<(B, Const<DIM>), f32, D>,
+        indices: &Tensor<(B,), usize, D>,
+    ) -> Result<(), D::Err> {
+        let b = z.shape().0;
+        let mut labels = std::vec![0; b.size()];
+        indices.copy_into(&mut labels);
+        let mut one_hot = std::vec![0.0; b.size() * K];
+        for (i, &label) in labels.iter().enumerate() {
+            one_hot[i * K + label] = 1.0;
+        }
+        let one_hot = z.device.try_tensor_from_vec(one_hot, (b, Const::<K>))?;
+        let counts = one_hot.clone().try_sum::<Rank1<K>, Axis<0>>()?;
+        let sums = one_hot
+            .try_permute::<_, Axes2<1, 0>>()?
+            .try_matmul(z.clone())?;
+
+        self.cluster_size = self
+            .cluster_size
+            .clone()
+            .try_mul(self.decay)?
+            .try_add(counts.try_mul(1.0 - self.decay)?)?;
+        self.code_sum = self
+            .code_sum
+            .clone()
+            .try_mul(self.decay)?
+            .try_add(sums.try_mul(1.0 - self.decay)?)?;
+        let size = self
+            .cluster_size
+            .clone()
+            .try_clamp(self.epsilon, f32::INFINITY)?
+            .try_broadcast::<Rank2<K, DIM>, Axis<1>>()?;
+        self.codebook = self.code_sum.clone().try_div(size)?;
+        Ok(())
+    }
+}
+
+impl<B: Dim, const K: usize, const DIM: usize, D: Device<f32>>
+    Module<Tensor<(B, Const<DIM>), f32, D, NoneTape>> for VectorQuantizer<K, DIM, D>
+{
+    type Output = Tensor<(B, Const<DIM>), f32, D, NoneTape>;
+    type Error = D::Err;
+
+    /// Inference forward - does **not** update [Self::codebook]
+    fn try_forward(
+        &self,
+        z: Tensor<(B, Const<DIM>), f32, D, NoneTape>,
+    ) -> Result<Self::Output, D::Err> {
+        self.try_decode(self.try_encode(&z)?)
+    }
+}
+
+impl<B: Dim, const K: usize, const DIM: usize, D>
+    ModuleMut<Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>> for VectorQuantizer<K, DIM, D>
+where
+    D: Device<f32> + TensorFromVec<f32> + CopySlice<usize>,
+{
+    type Output = Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>;
+    type Error = D::Err;
+
+    /// Training forward - updates [Self::codebook] after quantizing. Doesn't compute the
+    /// commitment loss, see [VectorQuantizer::forward_with_loss()].
+    fn try_forward_mut(
+        &mut self,
+        z: Tensor<(B, Const<DIM>), f32, D, OwnedTape<D>>,
+    ) -> Result<Self::Output, D::Err> {
+        let indices = self.try_encode(&z)?;
+        let codes = self.try_decode(indices.clone())?;
+        self.try_quantize(z, codes, &indices)
+    }
+}
+
+impl<const K: usize, const DIM: usize, D: Device<f32>> BuildModule<D, f32>
+    for VectorQuantizer<K, DIM, D>
+{
+    fn try_build(device: &D) -> Result<Self, D::Err> {
+        let bound: f32 = 1.0 / K as f32;
+        let codebook: Tensor<Rank2<K, DIM>, f32, D> =
+            device.try_sample(rand_distr::Uniform::new(-bound, bound))?;
+        Ok(Self {
+            code_sum: codebook.clone(),
+            codebook,
+            cluster_size: device.try_ones()?,
+            commitment: 0.25,
+            decay: 0.99,
+            epsilon: 1e-5,
+        })
+    }
+}
+
+impl<const K: usize, const DIM: usize, D: Device<f32>> ResetParams<D, f32>
+    for VectorQuantizer<K, DIM, D>
+{
+    fn try_reset_params(&mut self) -> Result<(), D::Err> {
+        let bound: f32 = 1.0 / K as f32;
+        self.codebook
+            .try_fill_with_distr(rand_distr::Uniform::new(-bound, bound))?;
+        self.code_sum = self.codebook.clone();
+        self.cluster_size.try_fill_with_ones()?;
+        Ok(())
+    }
+}
+
+impl<const K: usize, const DIM: usize, D1: Device<f32>, D2: Device<f32>> ToDevice<D2>
+    for VectorQuantizer<K, DIM, D1>
+{
+    type Output = VectorQuantizer<K, DIM, D2>;
+    fn to_device(&self, device: &D2) -> Self::Output {
+        VectorQuantizer {
+            codebook: self.codebook.to_device(device),
+            cluster_size: self.cluster_size.to_device(device),
+            code_sum: self.code_sum.to_device(device),
+            commitment: self.commitment,
+            decay: self.decay,
+            epsilon: self.epsilon,
+        }
+    }
+}
+
+impl<const K: usize, const DIM: usize, D: Device<f32>> GradientUpdate<D, f32>
+    for VectorQuantizer<K, DIM, D>
+{
+    fn update<U>(&mut self, updater: &mut U, _: &mut UnusedTensors) -> Result<(), <D>::Err>
+    where
+        U: ParamUpdater<D, f32>,
+    {
+        updater.update_buffer(&mut self.codebook)?;
+        updater.update_buffer(&mut self.cluster_size)?;
+        updater.update_buffer(&mut self.code_sum)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::Linear, tests::*};
+
+    fn build(dev: &TestDevice) -> VectorQuantizer<3, 2, TestDevice> {
+        let mut vq: VectorQuantizer<3, 2, _> = BuildModule::build(dev);
+        vq.codebook = dev.tensor([[0.0, 0.0], [1.0, 0.0], [0.0, 2.0]]);
+        vq.code_sum = vq.codebook.clone();
+        vq
+    }
+
+    #[test]
+    fn test_vq_forward() {
+        let dev: TestDevice = Default::default();
+        let vq = build(&dev);
+        let z = dev.tensor([[0.9, 0.2], [-0.1, 0.1], [0.2, 1.5], [0.6, 0.0]]);
+        assert_close(
+            &vq.distances(&z).array(),
+            &[
+                [0.85, 0.05, 4.05],
+                [0.02, 1.22, 3.62],
+                [2.29, 2.89, 0.29],
+                [0.36, 0.16, 4.36],
+            ],
+        );
+        assert_eq!(vq.encode(&z).array(), [1, 0, 2, 1]);
+        assert_close(
+            &vq.forward(z.clone()).array(),
+            &[[1.0, 0.0], [0.0, 0.0], [0.0, 2.0], [1.0, 0.0]],
+        );
+        // mean((z - codes)^2) = (0.01 + 0.04 + 0.01 + 0.01 + 0.04 + 0.25 + 0.16) / 8
+        assert_close(&vq.commitment_loss(&z).array(), &(0.25 * 0.065));
+    }
+
+    #[test]
+    fn test_vq_straight_through_and_commitment_grads() {
+        let dev: TestDevice = Default::default();
+        let mut vq = build(&dev);
+        vq.commitment = 0.5;
+        let z = dev.tensor([[0.9, 0.2], [0.2, 1.5]]);
+        let (y, loss) = vq.forward_with_loss(z.trace());
+        assert_close(&y.array(), &[[1.0, 0.0], [0.0, 2.0]]);
+        // 0.5 * (0.01 + 0.04 + 0.04 + 0.25) / 4
+        assert_close(&loss.array(), &0.0425);
+
+        let w = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let g = ((y * w.clone()).sum() + loss).backward();
+        // d/dz of 0.5 * mean((z - codes)^2) is (z - codes) / 4
+        let commitment = [[-0.1 / 4.0, 0.2 / 4.0], [0.2 / 4.0, -0.5 / 4.0]];
+        let w = w.array();
+        let mut expected = [[0.0; 2]; 2];
+        for i in 0..2 {
+            for j in 0..2 {
+                expected[i][j] = w[i][j] + commitment[i][j];
+            }
+        }
+        assert_close(&g.get(&z).array(), &expected);
+    }
+
+    #[test]
+    fn test_vq_forward_mut_has_no_commitment_grad() {
+        let dev: TestDevice = Default::default();
+        let mut vq = build(&dev);
+        let z = dev.tensor([[0.9, 0.2], [0.2, 1.5]]);
+        let y = vq.forward_mut(z.trace());
+        assert_close(&y.array(), &[[1.0, 0.0], [0.0, 2.0]]);
+
+        let w = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let g = (y * w.clone()).sum().backward();
+        assert_eq!(g.get(&z).array(), w.array());
+    }
+
+    #[test]
+    fn test_vq_commitment_grad_reaches_earlier_layers() {
+        let dev: TestDevice = Default::default();
+        let encoder: Linear<2, 2, _> = BuildModule::build(&dev);
+        let mut vq = build(&dev);
+        let x = dev.tensor([[0.9, 0.2], [0.2, 1.5]]);
+
+        // the codebook before it is updated
+        let z = encoder.forward(x.trace());
+        assert!(vq.commitment_loss(&z).array() > 0.0);
+        let codes = vq.forward(z.retaped::<NoneTape>());
+        let expected = (z - codes).square().mean().backward();
+        let expected = expected.get(&encoder.bias).array();
+
+        // with a loss that doesn't depend on the output, only the commitment loss is left
+        let (y, loss) = vq.forward_with_loss(encoder.forward(x.trace()));
+        let g = ((y * 0.0).sum() + loss).backward();
+        let grad_bias = g.get(&encoder.bias).array();
+        assert_close(&grad_bias, &expected.map(|g| g * vq.commitment));
+    }
+
+    #[test]
+    fn test_vq_ema_update() {
+        let dev: TestDevice = Default::default();
+        let mut vq = build(&dev);
+        vq.decay = 0.5;
+        let z = dev.tensor([[0.8, 0.2], [1.2, 0.0], [0.1, 2.4]]);
+        let _ = vq.forward_mut(z.trace());
+        // code 0 isn't used, so it keeps its value
+        assert_close(&vq.cluster_size.array(), &[0.5, 1.5, 1.0]);
+        assert_close(&vq.code_sum.array(), &[[0.0, 0.0], [1.5, 0.1], [0.05, 2.2]]);
+        assert_close(
+            &vq.codebook.array(),
+            &[[0.0, 0.0], [1.0, 0.1 / 1.5], [0.05, 2.2]],
+        );
+    }
+
+    #[test]
+    fn test_vq_unused_codes_shrink_below_epsilon() {
+        let dev: TestDevice = Default::default();
+        let mut vq = build(&dev);
+        vq.decay = 0.1;
+        vq.epsilon = 1e-3;
+        for _ in 0..4 {
+            let _ = vq.forward_mut(dev.tensor([[0.0, 0.1]]).trace());
+        }
+        let codebook = vq.codebook.array();
+        assert!(codebook[1][0] < 0.2);
+        assert!(codebook.iter().flatten().all(|v| v.is_finite()));
+    }
+}
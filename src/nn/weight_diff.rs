@@ -0,0 +1,178 @@
+use crate::{
+    optim::{GradientUpdate, ParamUpdater, UnusedTensors},
+    shapes::{HasShape, Shape},
+    tensor::{CopySlice, Tensor},
+};
+
+use std::vec::Vec;
+
+/// How much a single parameter differs between two models. See [weight_diff()].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamDiff {
+    /// The index of the parameter, in the order parameters & buffers are visited by
+    /// [GradientUpdate].
+    pub index: usize,
+    /// The number of elements in the parameter.
+    pub numel: usize,
+    /// `max(|a - b|)`
+    pub max_abs_diff: f32,
+    /// `||a - b|| / ||b||`, using the l2 norm. `0.0` if both are all zeros.
+    pub rel_diff: f32,
+    /// `a.b / (||a|| * ||b||)`. `1.0` if both are all zeros.
+    pub cosine_similarity: f32,
+}
+
+/// Compares the parameters of two models of the same architecture, one parameter
+/// at a time. Parameters are visited in the same order as optimizers visit them.
+/// Buffers, like the running statistics of [crate::nn::BatchNorm2D] or the weights
+/// of [crate::nn::Frozen] modules, are compared too.
+///
+/// Useful for debugging checkpoint conversions, or finding where two training runs diverge.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<2, 3>, ReLU, Linear<3, 1>);
+/// let a = Model::build_on_device(&dev);
+/// let mut b = a.clone();
+/// b.2.bias = dev.tensor([1.0]);
+/// let diffs = weight_diff(&a, &b);
+/// assert_eq!(diffs.len(), 4);
+/// assert_eq!(diffs[0].max_abs_diff, 0.0);
+/// assert!(diffs[3].max_abs_diff > 0.0);
+/// ```
+pub fn weight_diff<D, M>(a: &M, b: &M) -> Vec<ParamDiff>
+where
+    D: CopySlice<f32>,
+    M: Clone + GradientUpdate<D, f32>,
+{
+    let a = collect_params(a);
+    let b = collect_params(b);
+    assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b.iter())
+        .enumerate()
+        .map(|(index, (a, b))| param_diff(index, a, b))
+        .collect()
+}
+
+fn collect_params<D: CopySlice<f32>, M: Clone + GradientUpdate<D, f32>>(m: &M) -> Vec<Vec<f32>> {
+    let mut collector = ParamCollector(Vec::new());
+    let mut unused = Default::default();
+    // NOTE: this never errors, since ParamCollector does not return errors.
+    let _ = m.clone().update(&mut collector, &mut unused);
+    collector.0
+}
+
+struct ParamCollector(Vec<Vec<f32>>);
+
+impl<D: CopySlice<f32>> ParamUpdater<D, f32> for ParamCollector {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, f32, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.update_buffer(p)
+    }
+
+    fn update_buffer<S: Shape>(&mut self, b: &mut Tensor<S, f32, D>) -> Result<(), D::Err> {
+        let mut data = std::vec![0.0; b.shape().num_elements()];
+        b.copy_into(&mut data);
+        self.0.push(data);
+        Ok(())
+    }
+}
+
+fn param_diff(index: usize, a: &[f32], b: &[f32]) -> ParamDiff {
+    assert_eq!(a.len(), b.len());
+    let mut max_abs_diff: f32 = 0.0;
+    let mut diff_sq = 0.0f64;
+    let mut a_sq = 0.0f64;
+    let mut b_sq = 0.0f64;
+    let mut a_dot_b = 0.0f64;
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        let d = (x - y).abs();
+        max_abs_diff = max_abs_diff.max(d);
+        diff_sq += (d as f64) * (d as f64);
+        a_sq += (x as f64) * (x as f64);
+        b_sq += (y as f64) * (y as f64);
+        a_dot_b += (x as f64) * (y as f64);
+    }
+    let rel_diff = if b_sq > 0.0 {
+        (diff_sq / b_sq).sqrt()
+    } else if diff_sq > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    };
+    let cosine_similarity = if a_sq > 0.0 && b_sq > 0.0 {
+        a_dot_b / (a_sq.sqrt() * b_sq.sqrt())
+    } else if a_sq == b_sq {
+        1.0
+    } else {
+        0.0
+    };
+    ParamDiff {
+        index,
+        numel: a.len(),
+        max_abs_diff,
+        rel_diff: rel_diff as f32,
+        cosine_similarity: cosine_similarity as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, tensor::*, tests::*};
+
+    #[test]
+    fn test_weight_diff_identical() {
+        let dev: TestDevice = Default::default();
+        let a: (Linear<3, 4, _>, LayerNorm1D<4, _>) = BuildModule::build(&dev);
+        let diffs = weight_diff(&a, &a.clone());
+        assert_eq!(diffs.len(), 4);
+        assert_eq!(
+            diffs.iter().map(|d| d.numel).collect::<Vec<_>>(),
+            [12, 4, 4, 4]
+        );
+        for d in diffs {
+            assert_eq!(d.max_abs_diff, 0.0);
+            assert_eq!(d.rel_diff, 0.0);
+            assert_close(&d.cosine_similarity, &1.0);
+        }
+    }
+
+    #[test]
+    fn test_weight_diff_values() {
+        let dev: TestDevice = Default::default();
+        let mut a: Linear<2, 2, _> = BuildModule::build(&dev);
+        let mut b = a.clone();
+        a.weight = dev.tensor([[1.0, 0.0], [0.0, 1.0]]);
+        b.weight = dev.tensor([[0.0, 1.0], [0.0, 2.0]]);
+        b.bias = a.bias.clone() * 2.0;
+        let diffs = weight_diff(&a, &b);
+        assert_eq!(diffs[0].max_abs_diff, 1.0);
+        assert_close(&diffs[0].rel_diff, &(3.0f32 / 5.0).sqrt());
+        assert_close(
+            &diffs[0].cosine_similarity,
+            &(2.0 / (2.0f32.sqrt() * 5.0f32.sqrt())),
+        );
+        assert_close(&diffs[1].rel_diff, &0.5);
+        assert_close(&diffs[1].cosine_similarity, &1.0);
+    }
+
+    #[test]
+    fn test_weight_diff_buffers() {
+        let dev: TestDevice = Default::default();
+        let a: BatchNorm2D<3, _> = BuildModule::build(&dev);
+        let mut b = a.clone();
+        b.running_mean = dev.tensor([0.0, 1.0, 0.0]);
+        let diffs = weight_diff(&a, &b);
+        assert_eq!(diffs.len(), 4);
+        assert_eq!(diffs[0].max_abs_diff, 0.0);
+        assert_eq!(diffs[1].max_abs_diff, 0.0);
+        assert_eq!(diffs[2].max_abs_diff, 1.0);
+        assert_eq!(diffs[3].max_abs_diff, 0.0);
+    }
+}
@@ -0,0 +1,379 @@
+//! Differentiable ODE solvers such as [rk4()] and [dopri5()], for Neural ODEs.
+//!
+//! The solvers integrate `dx/dt = f(t, x)` from `t0` to `t1`. All operations are recorded on
+//! the tape of the initial state `x0`, so the solution can be backpropagated to both `x0` and
+//! any parameters used inside of `f`.
+//!
+//! ```rust
+//! # use dfdx::{prelude::*, ode::*};
+//! # let dev: Cpu = Default::default();
+//! let dynamics: (Linear<2, 16>, Tanh, Linear<16, 2>) = BuildModule::build(&dev);
+//! let x0: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+//! let x1 = rk4(|_t, x| dynamics.forward(x), x0.traced(), 0.0, 1.0, 10);
+//! let grads = x1.square().mean().backward();
+//! ```
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{PutTape, SplitTape, Tensor},
+    tensor_ops::*,
+};
+
+/// Fixed step [Runge-Kutta 4](https://en.wikipedia.org/wiki/Runge%E2%80%93Kutta_methods)
+/// solver. Integrates `dx/dt = f(t, x)` from `t0` to `t1` using `num_steps` steps of equal size.
+///
+/// Gradients are computed by backpropagating through every step of the solver.
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*, ode::*};
+/// # let dev: Cpu = Default::default();
+/// // dx/dt = -x, so x(1) = x(0) * e^-1
+/// let x0 = dev.tensor([1.0, 2.0]);
+/// let x1 = rk4(|_t, x| -x, x0, 0.0, 1.0, 10);
+/// ```
+pub fn rk4<S: Shape, D: Device<f32>, T: Tape<D>, F>(
+    mut f: F,
+    x0: Tensor<S, f32, D, T>,
+    t0: f32,
+    t1: f32,
+    num_steps: usize,
+) -> Tensor<S, f32, D, T>
+where
+    F: FnMut(f32, Tensor<S, f32, D, T>) -> Tensor<S, f32, D, T>,
+{
+    assert!(num_steps > 0);
+    let h = (t1 - t0) / num_steps as f32;
+    let mut x = x0;
+    for i in 0..num_steps {
+        let t = t0 + h * i as f32;
+        let (x_new, _) = rk_step(&mut f, &RK4, x, t, h);
+        x = x_new;
+    }
+    x
+}
+
+/// Configuration for the adaptive [dopri5()] solver.
+#[derive(Debug, Clone, Copy)]
+pub struct Dopri5Config {
+    /// Relative error tolerance per step.
+    pub rtol: f32,
+    /// Absolute error tolerance per step.
+    pub atol: f32,
+    /// Size of the first step that is attempted.
+    pub initial_step: f32,
+    /// Maximum number of attempted steps (including rejected steps).
+    pub max_steps: usize,
+}
+
+impl Default for Dopri5Config {
+    fn default() -> Self {
+        Self {
+            rtol: 1e-3,
+            atol: 1e-6,
+            initial_step: 0.1,
+            max_steps: 1000,
+        }
+    }
+}
+
+/// An error from an adaptive ODE solver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OdeError {
+    /// [Dopri5Config::max_steps] were attempted before reaching `t1`. Contains the time that was reached.
+    MaxStepsExceeded(f32),
+}
+
+impl std::fmt::Display for OdeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MaxStepsExceeded(t) => write!(f, "OdeError::MaxStepsExceeded({t})"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OdeError {}
+
+/// Adaptive step [Dormand-Prince](https://en.wikipedia.org/wiki/Dormand%E2%80%93Prince_method)
+/// 5(4) solver. Integrates `dx/dt = f(t, x)` from `t0` to `t1`, choosing step sizes
+/// so that the estimated error of each step is within [Dopri5Config::rtol] and [Dopri5Config::atol].
+///
+/// Gradients are computed by backpropagating through every accepted step of the solver.
+/// Rejected steps are still recorded on the tape, but don't contribute to the gradients.
+/// Step size selection itself is not differentiated.
+///
+/// # Example
+/// ```rust
+/// # use dfdx::{prelude::*, ode::*};
+/// # let dev: Cpu = Default::default();
+/// let x0 = dev.tensor([1.0, 2.0]);
+/// let x1 = dopri5(|_t, x| -x, x0, 0.0, 1.0, Default::default()).unwrap();
+/// ```
+pub fn dopri5<S: Shape, D: Device<f32>, T: Tape<D>, F>(
+    mut f: F,
+    x0: Tensor<S, f32, D, T>,
+    t0: f32,
+    t1: f32,
+    cfg: Dopri5Config,
+) -> Result<Tensor<S, f32, D, T>, OdeError>
+where
+    F: FnMut(f32, Tensor<S, f32, D, T>) -> Tensor<S, f32, D, T>,
+{
+    let dir = if t1 >= t0 { 1.0 } else { -1.0 };
+    let mut h = cfg.initial_step.abs() * dir;
+    let mut t = t0;
+    let mut x = x0;
+    let mut num_steps = 0;
+    while (t1 - t) * dir > 0.0 {
+        if num_steps == cfg.max_steps {
+            return Err(OdeError::MaxStepsExceeded(t));
+        }
+        num_steps += 1;
+
+        if (t + h - t1) * dir > 0.0 {
+            h = t1 - t;
+        }
+
+        let (x_prev, tape) = x.split_tape();
+        let (x_new, err) = rk_step(&mut f, &DOPRI5, x_prev.clone().put_tape(tape), t, h);
+        let err = err.unwrap();
+
+        let mut x_buf = std::vec![0.0; x_prev.shape().num_elements()];
+        let mut x_new_buf = x_buf.clone();
+        let mut err_buf = x_buf.clone();
+        x_prev.copy_into(&mut x_buf);
+        x_new.copy_into(&mut x_new_buf);
+        err.copy_into(&mut err_buf);
+        let mut sq_sum = 0.0;
+        for ((a, b), e) in x_buf.iter().zip(x_new_buf.iter()).zip(err_buf.iter()) {
+            let scale = cfg.atol + cfg.rtol * a.abs().max(b.abs());
+            sq_sum += (e / scale) * (e / scale);
+        }
+        let err_norm = (sq_sum / x_buf.len().max(1) as f32).sqrt();
+
+        if err_norm <= 1.0 {
+            t += h;
+            x = x_new;
+        } else {
+            let (_, tape) = x_new.split_tape();
+            x = x_prev.put_tape(tape);
+        }
+
+        let factor = if err_norm == 0.0 {
+            10.0
+        } else {
+            (0.9 * err_norm.powf(-0.2)).clamp(0.2, 10.0)
+        };
+        h *= factor;
+    }
+    Ok(x)
+}
+
+/// An explicit Runge-Kutta method described by its Butcher tableau.
+struct Tableau<const N: usize> {
+    a: [[f32; N]; N],
+    b: [f32; N],
+    c: [f32; N],
+    /// `b - b*`, where `b*` are the weights of the embedded lower order method.
+    b_err: Option<[f32; N]>,
+}
+
+const RK4: Tableau<4> = Tableau {
+    a: [
+        [0.0, 0.0, 0.0, 0.0],
+        [0.5, 0.0, 0.0, 0.0],
+        [0.0, 0.5, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+    ],
+    b: [1.0 / 6.0, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 6.0],
+    c: [0.0, 0.5, 0.5, 1.0],
+    b_err: None,
+};
+
+const DOPRI5: Tableau<7> = Tableau {
+    a: [
+        [0.0; 7],
+        [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0, 0.0],
+        [
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+            0.0,
+            0.0,
+            0.0,
+        ],
+        [
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+            0.0,
+            0.0,
+        ],
+        [
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+            0.0,
+        ],
+    ],
+    b: [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+        0.0,
+    ],
+    c: [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0],
+    b_err: Some([
+        35.0 / 384.0 - 5179.0 / 57600.0,
+        0.0,
+        500.0 / 1113.0 - 7571.0 / 16695.0,
+        125.0 / 192.0 - 393.0 / 640.0,
+        -2187.0 / 6784.0 + 92097.0 / 339200.0,
+        11.0 / 84.0 - 187.0 / 2100.0,
+        -1.0 / 40.0,
+    ]),
+};
+
+/// Takes a single step of size `h` with `tableau`. Returns the new state, and the
+/// (tapeless) error estimate if the tableau has one.
+#[allow(clippy::type_complexity)]
+fn rk_step<S: Shape, D: Device<f32>, T: Tape<D>, F, const N: usize>(
+    f: &mut F,
+    tableau: &Tableau<N>,
+    x: Tensor<S, f32, D, T>,
+    t: f32,
+    h: f32,
+) -> (Tensor<S, f32, D, T>, Option<Tensor<S, f32, D>>)
+where
+    F: FnMut(f32, Tensor<S, f32, D, T>) -> Tensor<S, f32, D, T>,
+{
+    let (x, mut tape) = x.split_tape();
+    let mut ks: std::vec::Vec<Tensor<S, f32, D>> = std::vec::Vec::with_capacity(N);
+    for i in 0..N {
+        let x_i = weighted_sum(&x, &ks, &tableau.a[i], h, tape);
+        let (k, next_tape) = f(t + tableau.c[i] * h, x_i).split_tape();
+        ks.push(k);
+        tape = next_tape;
+    }
+    let err = tableau.b_err.map(|b_err| {
+        let zeros = x.device.zeros_like(&x);
+        weighted_sum::<_, _, crate::gradients::NoneTape>(&zeros, &ks, &b_err, h, Default::default())
+    });
+    (weighted_sum(&x, &ks, &tableau.b, h, tape), err)
+}
+
+/// Computes `x + h * sum(w[i] * k[i])`, recording every operation on `tape`.
+fn weighted_sum<S: Shape, D: Device<f32>, T: Tape<D>>(
+    x: &Tensor<S, f32, D>,
+    ks: &[Tensor<S, f32, D>],
+    w: &[f32],
+    h: f32,
+    mut tape: T,
+) -> Tensor<S, f32, D, T> {
+    let mut acc = x.clone();
+    for (k, &w) in ks.iter().zip(w.iter()) {
+        if w == 0.0 {
+            continue;
+        }
+        // the tape must be on `k` so that the scalar multiplication is recorded
+        let (k, k_tape) = (k.clone().put_tape(tape) * (w * h)).split_tape();
+        (acc, tape) = (acc.put_tape(k_tape) + k).split_tape();
+    }
+    acc.put_tape(tape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, tensor::*, tests::*};
+
+    #[test]
+    fn test_rk4_exponential_decay() {
+        let dev: TestDevice = Default::default();
+        let x0 = dev.tensor([1.0, 2.0, -0.5]);
+        let x1 = rk4(|_, x| -x, x0, 0.0, 1.0, 20);
+        let e = (-1.0f32).exp();
+        assert_close(&x1.array(), &[e, 2.0 * e, -0.5 * e]);
+    }
+
+    #[test]
+    fn test_rk4_time_dependent() {
+        let dev: TestDevice = Default::default();
+        // dx/dt = 2t, so x(2) = x(0) + 4
+        let x0 = dev.tensor([1.0]);
+        let x1 = rk4(|t, x| x * 0.0 + 2.0 * t, x0, 0.0, 2.0, 4);
+        assert_close(&x1.array(), &[5.0]);
+    }
+
+    #[test]
+    fn test_rk4_gradients() {
+        let dev: TestDevice = Default::default();
+        let x0 = dev.tensor([1.0, 2.0]);
+        let w = dev.tensor([0.5, -1.0]);
+        // dx/dt = w * x, so x(1) = x(0) * e^w
+        let x1 = rk4(|_, x| x * w.clone(), x0.trace(), 0.0, 1.0, 20);
+        let g = x1.sum().backward();
+        assert_close(&g.get(&x0).array(), &[0.5f32.exp(), (-1.0f32).exp()]);
+    }
+
+    #[test]
+    fn test_rk4_module_gradients() {
+        let dev: TestDevice = Default::default();
+        let m: Linear<2, 2, _> = BuildModule::build(&dev);
+        let x0: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+        let x1 = rk4(|_, x| m.forward(x), x0.trace(), 0.0, 1.0, 5);
+        let g = x1.square().mean().backward();
+        assert_ne!(g.get(&m.weight).array(), [[0.0; 2]; 2]);
+        assert_ne!(g.get(&x0).array(), [[0.0; 2]; 3]);
+    }
+
+    #[test]
+    fn test_dopri5_exponential_decay() {
+        let dev: TestDevice = Default::default();
+        let x0 = dev.tensor([1.0, 2.0, -0.5]);
+        let cfg = Dopri5Config {
+            rtol: 1e-6,
+            atol: 1e-8,
+            ..Default::default()
+        };
+        let x1 = dopri5(|_, x| -x, x0.trace(), 0.0, 1.0, cfg).unwrap();
+        let e = (-1.0f32).exp();
+        assert_close(&x1.array(), &[e, 2.0 * e, -0.5 * e]);
+        let g = x1.sum().backward();
+        assert_close(&g.get(&x0).array(), &[e; 3]);
+    }
+
+    #[test]
+    fn test_dopri5_backwards_in_time() {
+        let dev: TestDevice = Default::default();
+        let x0 = dev.tensor([1.0]);
+        let x1 = dopri5(|_, x| -x, x0, 1.0, 0.0, Default::default()).unwrap();
+        assert_close_with_tolerance(&x1.array(), &[1.0f32.exp()], 1e-3);
+    }
+
+    #[test]
+    fn test_dopri5_max_steps() {
+        let dev: TestDevice = Default::default();
+        let x0 = dev.tensor([1.0]);
+        let cfg = Dopri5Config {
+            max_steps: 2,
+            initial_step: 0.01,
+            ..Default::default()
+        };
+        let r = dopri5(|_, x| -x, x0, 0.0, 1.0, cfg);
+        assert!(matches!(r, Err(OdeError::MaxStepsExceeded(_))));
+    }
+}
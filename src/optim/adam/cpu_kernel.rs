@@ -1,7 +1,12 @@
 use super::{AdamConfig, AdamKernel};
-use crate::{optim::WeightDecay, shapes::Shape, tensor::Cpu};
+use crate::{
+    optim::{optimizer::compensated_sub, WeightDecay},
+    shapes::Shape,
+    tensor::Cpu,
+};
 
 impl AdamKernel<f32> for Cpu {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: Shape>(
         &self,
         t: i32,
@@ -10,11 +15,14 @@ impl AdamKernel<f32> for Cpu {
         moment1: &mut Self::Storage<S, f32>,
         moment2: &mut Self::Storage<S, f32>,
         grad: Self::Storage<S, f32>,
+        compensation: Option<&mut Self::Storage<S, f32>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        let mut compensation = compensation.map(|c| c.buf_iter_mut());
+
         for ((p, mut g), (m, v)) in param
             .buf_iter_mut()
             .zip(grad.buf_iter().cloned())
@@ -34,7 +42,10 @@ impl AdamKernel<f32> for Cpu {
                 g += wd * cfg.lr * *p;
             }
 
-            *p -= g;
+            match compensation.as_mut().and_then(|c| c.next()) {
+                Some(c) => compensated_sub(p, g, c),
+                None => *p -= g,
+            }
         }
         Ok(())
     }
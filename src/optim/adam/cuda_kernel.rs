@@ -34,6 +34,7 @@ const FN_NAME: &str = "adam_update";
 const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adam.ptx"));
 
 impl super::AdamKernel<f32> for Cuda {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: Shape>(
         &self,
         t: i32,
@@ -42,11 +43,34 @@ impl super::AdamKernel<f32> for Cuda {
         moment1: &mut Self::Storage<S, f32>,
         moment2: &mut Self::Storage<S, f32>,
         grad: Self::Storage<S, f32>,
+        compensation: Option<&mut Self::Storage<S, f32>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        if let Some(compensation) = compensation {
+            let mut cpu_param = self.storage_to_cpu(param)?;
+            let mut cpu_moment1 = self.storage_to_cpu(moment1)?;
+            let mut cpu_moment2 = self.storage_to_cpu(moment2)?;
+            let mut cpu_compensation = self.storage_to_cpu(compensation)?;
+            let cpu_grad = self.storage_to_cpu(&grad)?;
+            super::AdamKernel::<f32>::update(
+                &self.cpu,
+                t,
+                cfg,
+                &mut cpu_param,
+                &mut cpu_moment1,
+                &mut cpu_moment2,
+                cpu_grad,
+                Some(&mut cpu_compensation),
+            )?;
+            self.storage_from_cpu(param, &cpu_param)?;
+            self.storage_from_cpu(moment1, &cpu_moment1)?;
+            self.storage_from_cpu(moment2, &cpu_moment2)?;
+            return self.storage_from_cpu(compensation, &cpu_compensation);
+        }
+
         if !self.dev.has_func(MODULE_NAME, FN_NAME) {
             self.dev.load_ptx(PTX_SRC.into(), MODULE_NAME, &[FN_NAME])?;
         }
@@ -78,6 +78,7 @@ pub struct Adam<M, E: Dtype = f32> {
     gradients: Gradients,
     moment1: Gradients,
     moment2: Gradients,
+    compensation: Gradients,
 
     marker: PhantomData<*const M>,
 }
@@ -91,12 +92,14 @@ impl<M, E: Dtype> Adam<M, E> {
             gradients: Default::default(),
             moment1: Default::default(),
             moment2: Default::default(),
+            compensation: Default::default(),
             marker: PhantomData,
         }
     }
 }
 
 pub(super) trait AdamKernel<E: Dtype>: DeviceStorage {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: Shape>(
         &self,
         t: i32,
@@ -105,6 +108,7 @@ pub(super) trait AdamKernel<E: Dtype>: DeviceStorage {
         moment1: &mut Self::Storage<S, E>,
         moment2: &mut Self::Storage<S, E>,
         grad: Self::Storage<S, E>,
+        compensation: Option<&mut Self::Storage<S, E>>,
     ) -> Result<(), Self::Err>;
 }
 
@@ -120,8 +124,13 @@ impl<M, D: DeviceStorage + AdamKernel<E>, E: Dtype> ParamUpdater<D, E> for Adam<
             Some(g) => {
                 let m_t = self.moment1.get_or_alloc_mut(p)?;
                 let v_t = self.moment2.get_or_alloc_mut(p)?;
+                let c = match p.device.compensated() {
+                    true => Some(self.compensation.get_or_alloc_mut(p)?),
+                    false => None,
+                };
                 p.device
-                    .update(self.t, &self.cfg, &mut p.storage, m_t, v_t, g)?;
+                    .update(self.t, &self.cfg, &mut p.storage, m_t, v_t, g.clone(), c)?;
+                self.gradients.recycle_grad(g);
             }
         }
         Ok(())
@@ -145,6 +154,12 @@ where
             Err(e) => Err(OptimizerUpdateError::DeviceError(e)),
         }
     }
+
+    fn recycled_gradients(&mut self) -> Gradients {
+        let mut gradients = std::mem::take(&mut self.gradients);
+        gradients.recycle();
+        gradients
+    }
 }
 
 #[cfg(test)]
@@ -278,4 +293,24 @@ mod tests {
             assert_close(&t.array(), e);
         }
     }
+
+    #[test]
+    fn test_adam_compensated() {
+        let dev: TestDevice = Default::default();
+        dev.set_compensated(true);
+        let mut t: Tensor<Rank1<2>, f32, _> = dev.ones() * 1000.0;
+        let mut opt = Adam::new(
+            &t,
+            AdamConfig {
+                lr: 1e-5,
+                ..Default::default()
+            },
+        );
+        // adam steps by about `lr` every update, which rounds away without compensation
+        for _ in 0..1000 {
+            let gradients = t.trace().sum().backward();
+            opt.update(&mut t, gradients).expect("");
+        }
+        assert_close(&t.array(), &[999.99; 2]);
+    }
 }
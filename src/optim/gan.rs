@@ -0,0 +1,335 @@
+use crate::{
+    gradients::OwnedTape,
+    losses::{binary_cross_entropy_with_logits_loss, ReduceMean},
+    nn::{gradient_penalty, Module, ModuleMut},
+    shapes::*,
+    tensor::{AsVec, Tensor},
+    tensor_ops::*,
+};
+
+use super::optimizer::{Optimizer, OptimizerUpdateError};
+
+/// The adversarial losses of [GanTrainer], as functions of the discriminator's logits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GanLoss {
+    /// The discriminator minimizes `softplus(-real) + softplus(fake)`, and the generator
+    /// minimizes `softplus(-fake)`, from [the original GAN paper](https://arxiv.org/abs/1406.2661).
+    #[default]
+    NonSaturating,
+
+    /// The discriminator minimizes `relu(1 - real) + relu(1 + fake)`, and the generator
+    /// minimizes `-fake`, like [SAGAN](https://arxiv.org/abs/1805.08318).
+    Hinge,
+}
+
+/// Configuration of [GanTrainer].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GanConfig {
+    /// Defaults to [GanLoss::NonSaturating].
+    pub loss: GanLoss,
+
+    /// The number of discriminator updates for each generator update. Defaults to `1`.
+    pub disc_steps: usize,
+
+    /// The weight `gamma` of the R1 penalty `gamma / 2 * |∇D(real)|^2` from
+    /// [Which Training Methods for GANs do actually Converge?](https://arxiv.org/abs/1801.04406).
+    /// `0.0` disables it. Defaults to `0.0`.
+    pub r1_gamma: f32,
+
+    /// The step of the finite difference used to estimate the R1 penalty. Defaults to `1e-2`.
+    pub r1_epsilon: f32,
+}
+
+impl Default for GanConfig {
+    fn default() -> Self {
+        Self {
+            loss: GanLoss::NonSaturating,
+            disc_steps: 1,
+            r1_gamma: 0.0,
+            r1_epsilon: 1e-2,
+        }
+    }
+}
+
+/// The losses of one [GanTrainer::step()].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GanLosses {
+    /// The adversarial loss of the discriminator, without the R1 penalty.
+    pub disc_loss: f32,
+
+    /// The R1 penalty, or `0.0` if it is disabled.
+    pub r1_penalty: f32,
+
+    /// The loss of the generator, or `None` if the generator wasn't updated this step.
+    pub gen_loss: Option<f32>,
+}
+
+/// Alternates discriminator & generator updates of a GAN, each with its own optimizer.
+///
+/// The tape makes it easy to get this wrong, so each update only traces what it trains:
+/// - [GanTrainer::discriminator_step()] runs the generator without a tape, so the fake
+///   samples are detached, and only the discriminator gets gradients.
+/// - [GanTrainer::generator_step()] backpropagates through the discriminator into the
+///   generator, and only updates the generator.
+///
+/// The module being trained is run with [ModuleMut::forward_mut()], and the other one
+/// with [Module::forward()].
+///
+/// The R1 penalty is computed with [crate::nn::gradient_penalty()], which costs three extra
+/// forwards and one extra backward of the discriminator.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Generator = (Linear<4, 16>, ReLU, Linear<16, 2>);
+/// type Discriminator = (Linear<2, 16>, ReLU, Linear<16, 1>);
+/// let mut gen = Generator::build_on_device(&dev);
+/// let mut disc = Discriminator::build_on_device(&dev);
+/// let mut trainer = GanTrainer::new(
+///     GanConfig { r1_gamma: 1.0, ..Default::default() },
+///     Adam::new(&gen, Default::default()),
+///     Adam::new(&disc, Default::default()),
+/// );
+/// for _ in 0..10 {
+///     let real: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+///     let z: Tensor<Rank2<8, 4>, f32, _> = dev.sample_normal();
+///     let losses = trainer.step(&mut gen, &mut disc, real, z).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GanTrainer<OG, OD> {
+    pub cfg: GanConfig,
+    pub gen_opt: OG,
+    pub disc_opt: OD,
+    num_disc_steps: usize,
+}
+
+impl<OG, OD> GanTrainer<OG, OD> {
+    pub fn new(cfg: GanConfig, gen_opt: OG, disc_opt: OD) -> Self {
+        assert!(
+            cfg.disc_steps > 0,
+            "GanConfig::disc_steps must be at least 1"
+        );
+        Self {
+            cfg,
+            gen_opt,
+            disc_opt,
+            num_disc_steps: 0,
+        }
+    }
+
+    /// Updates the discriminator with [GanTrainer::discriminator_step()], and then the
+    /// generator with [GanTrainer::generator_step()] once every [GanConfig::disc_steps] calls.
+    /// Both updates use the same noise `z`.
+    #[allow(clippy::type_complexity)]
+    pub fn step<G, Dis, Z: Shape, S: Shape, L: Shape, D: Device<f32>>(
+        &mut self,
+        generator: &mut G,
+        discriminator: &mut Dis,
+        real: Tensor<S, f32, D>,
+        z: Tensor<Z, f32, D>,
+    ) -> Result<GanLosses, OptimizerUpdateError<D>>
+    where
+        G: Module<Tensor<Z, f32, D>, Output = Tensor<S, f32, D>>
+            + ModuleMut<Tensor<Z, f32, D, OwnedTape<D>>, Output = Tensor<S, f32, D, OwnedTape<D>>>,
+        Dis: Module<Tensor<S, f32, D, OwnedTape<D>>, Output = Tensor<L, f32, D, OwnedTape<D>>>
+            + ModuleMut<Tensor<S, f32, D, OwnedTape<D>>, Output = Tensor<L, f32, D, OwnedTape<D>>>,
+        OG: Optimizer<G, D, f32>,
+        OD: Optimizer<Dis, D, f32>,
+    {
+        let (disc_loss, r1_penalty) =
+            self.discriminator_step(generator, discriminator, real, z.clone())?;
+        let gen_loss = if self.num_disc_steps >= self.cfg.disc_steps {
+            self.num_disc_steps = 0;
+            Some(self.generator_step(generator, discriminator, z)?)
+        } else {
+            None
+        };
+        Ok(GanLosses {
+            disc_loss,
+            r1_penalty,
+            gen_loss,
+        })
+    }
+
+    /// Updates the discriminator to tell `real` samples apart from the generator's samples
+    /// for noise `z`, which are detached. Returns the adversarial loss and the R1 penalty.
+    pub fn discriminator_step<G, Dis, Z: Shape, S: Shape, L: Shape, D: Device<f32>>(
+        &mut self,
+        generator: &G,
+        discriminator: &mut Dis,
+        real: Tensor<S, f32, D>,
+        z: Tensor<Z, f32, D>,
+    ) -> Result<(f32, f32), OptimizerUpdateError<D>>
+    where
+        G: Module<Tensor<Z, f32, D>, Output = Tensor<S, f32, D>>,
+        Dis: ModuleMut<Tensor<S, f32, D, OwnedTape<D>>, Output = Tensor<L, f32, D, OwnedTape<D>>>,
+        OD: Optimizer<Dis, D, f32>,
+    {
+        let fake = generator.forward(z);
+        let d_real = discriminator.forward_mut(real.clone().traced());
+        let d_fake = discriminator.forward_mut(fake.traced());
+        let loss = match self.cfg.loss {
+            GanLoss::NonSaturating => {
+                let ones = d_real.device.ones_like(d_real.shape());
+                let zeros = d_fake.device.zeros_like(d_fake.shape());
+                binary_cross_entropy_with_logits_loss(d_real, ones, ReduceMean)
+                    + binary_cross_entropy_with_logits_loss(d_fake, zeros, ReduceMean)
+            }
+            GanLoss::Hinge => (d_real.negate() + 1.0).relu().mean() + (d_fake + 1.0).relu().mean(),
+        };
+        let disc_loss = scalar(&loss);
+
+        let (loss, r1_penalty) = if self.cfg.r1_gamma > 0.0 {
+            let f = |x| discriminator.forward_mut(x);
+            let penalty = gradient_penalty(f, &real, 0.0, self.cfg.r1_epsilon);
+            let penalty = penalty * (self.cfg.r1_gamma / 2.0);
+            let r1_penalty = scalar(&penalty);
+            (loss + penalty, r1_penalty)
+        } else {
+            (loss, 0.0)
+        };
+
+        self.disc_opt.update(discriminator, loss.backward())?;
+        self.num_disc_steps += 1;
+        Ok((disc_loss, r1_penalty))
+    }
+
+    /// Updates the generator to fool the discriminator with its samples for noise `z`.
+    /// The discriminator isn't updated. Returns the generator's loss.
+    pub fn generator_step<G, Dis, Z: Shape, S: Shape, L: Shape, D: Device<f32>>(
+        &mut self,
+        generator: &mut G,
+        discriminator: &Dis,
+        z: Tensor<Z, f32, D>,
+    ) -> Result<f32, OptimizerUpdateError<D>>
+    where
+        G: ModuleMut<Tensor<Z, f32, D, OwnedTape<D>>, Output = Tensor<S, f32, D, OwnedTape<D>>>,
+        Dis: Module<Tensor<S, f32, D, OwnedTape<D>>, Output = Tensor<L, f32, D, OwnedTape<D>>>,
+        OG: Optimizer<G, D, f32>,
+    {
+        let fake = generator.forward_mut(z.traced());
+        let d_fake = discriminator.forward(fake);
+        let loss = match self.cfg.loss {
+            GanLoss::NonSaturating => {
+                let ones = d_fake.device.ones_like(d_fake.shape());
+                binary_cross_entropy_with_logits_loss(d_fake, ones, ReduceMean)
+            }
+            GanLoss::Hinge => d_fake.mean().negate(),
+        };
+        let gen_loss = scalar(&loss);
+        self.gen_opt.update(generator, loss.backward())?;
+        Ok(gen_loss)
+    }
+}
+
+fn scalar<D: Device<f32>, T>(t: &Tensor<(), f32, D, T>) -> f32 {
+    t.as_vec()[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, optim::*, tensor::*, tests::*};
+
+    #[test]
+    fn test_gan_learns_mean() {
+        let dev: TestDevice = TestDevice::seed_from_u64(0);
+        // a generator that can only shift its noise
+        let mut gen: Linear<1, 1, _> = BuildModule::build(&dev);
+        let mut disc: (Linear<1, 8, _>, Tanh, Linear<8, 1, _>) = BuildModule::build(&dev);
+        let cfg = AdamConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.999],
+            ..Default::default()
+        };
+        let mut trainer = GanTrainer::new(
+            Default::default(),
+            Adam::new(&gen, cfg),
+            Adam::new(&disc, cfg),
+        );
+        for _ in 0..500 {
+            let real: Tensor<Rank2<32, 1>, f32, _> = dev.sample_normal() * 0.5 + 3.0;
+            let z: Tensor<Rank2<32, 1>, f32, _> = dev.sample_normal();
+            trainer.step(&mut gen, &mut disc, real, z).unwrap();
+        }
+        let z: Tensor<Rank2<256, 1>, f32, _> = dev.sample_normal();
+        let mean = gen.forward(z).mean::<Rank0, _>().array();
+        assert!((mean - 3.0).abs() < 0.5, "{mean}");
+    }
+
+    #[test]
+    fn test_gan_disc_steps() {
+        let dev: TestDevice = Default::default();
+        let mut gen: Linear<2, 3, _> = BuildModule::build(&dev);
+        let mut disc: Linear<3, 1, _> = BuildModule::build(&dev);
+        let mut trainer = GanTrainer::new(
+            GanConfig {
+                loss: GanLoss::Hinge,
+                disc_steps: 2,
+                ..Default::default()
+            },
+            Sgd::new(&gen, Default::default()),
+            Sgd::new(&disc, Default::default()),
+        );
+        let gen_before = gen.weight.array();
+        for i in 0..4 {
+            let real: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+            let z: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+            let losses = trainer.step(&mut gen, &mut disc, real, z).unwrap();
+            assert_eq!(losses.gen_loss.is_some(), i % 2 == 1);
+            assert_eq!(losses.r1_penalty, 0.0);
+            if i == 0 {
+                // the generator wasn't updated by the discriminator's step
+                assert_eq!(gen.weight.array(), gen_before);
+            }
+        }
+        assert_ne!(gen.weight.array(), gen_before);
+    }
+
+    #[test]
+    fn test_generator_step_doesnt_update_disc() {
+        let dev: TestDevice = Default::default();
+        let mut gen: Linear<2, 3, _> = BuildModule::build(&dev);
+        let disc: Linear<3, 1, _> = BuildModule::build(&dev);
+        let mut trainer = GanTrainer::new(
+            Default::default(),
+            Sgd::new(&gen, Default::default()),
+            Sgd::new(&disc, Default::default()),
+        );
+        let disc_before = disc.weight.array();
+        let z: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+        trainer.generator_step(&mut gen, &disc, z).unwrap();
+        assert_eq!(disc.weight.array(), disc_before);
+    }
+
+    #[test]
+    fn test_r1_penalty_of_linear_disc() {
+        let dev: TestDevice = Default::default();
+        let gen: Linear<2, 3, _> = BuildModule::build(&dev);
+        let mut disc: Linear<3, 1, _> = BuildModule::build(&dev);
+        disc.weight = dev.tensor([[1.0, -2.0, 0.5]]);
+        let mut trainer = GanTrainer::new(
+            GanConfig {
+                r1_gamma: 10.0,
+                ..Default::default()
+            },
+            Sgd::new(&gen, Default::default()),
+            Sgd::new(
+                &disc,
+                SgdConfig {
+                    lr: 0.0,
+                    ..Default::default()
+                },
+            ),
+        );
+        // the gradient of a linear discriminator is its weight everywhere
+        let real: Tensor<Rank2<8, 3>, f32, _> = dev.sample_normal();
+        let z: Tensor<Rank2<8, 2>, f32, _> = dev.sample_normal();
+        let (_, r1) = trainer
+            .discriminator_step(&gen, &mut disc, real, z)
+            .unwrap();
+        let expected = 10.0 / 2.0 * (1.0 + 4.0 + 0.25);
+        assert!((r1 - expected).abs() < 1e-2, "{r1} vs {expected}");
+    }
+}
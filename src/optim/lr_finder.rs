@@ -0,0 +1,331 @@
+use std::vec::Vec;
+
+use crate::gradients::Gradients;
+use crate::shapes::Dtype;
+use crate::tensor::DeviceStorage;
+
+use super::optimizer::{Optimizer, OptimizerUpdateError};
+use super::{Adam, MasterWeights, RMSprop, Sgd};
+
+/// An optimizer whose learning rate can be changed between updates, e.g. by [LrFinder].
+pub trait LearningRate {
+    /// The current learning rate.
+    fn lr(&self) -> f64;
+
+    /// Sets the learning rate used by the next update.
+    fn set_lr(&mut self, lr: f64);
+}
+
+macro_rules! impl_learning_rate {
+    ($Opt:ident, $Dtype:ty) => {
+        impl<M> LearningRate for $Opt<M, $Dtype> {
+            fn lr(&self) -> f64 {
+                self.cfg.lr as f64
+            }
+            fn set_lr(&mut self, lr: f64) {
+                self.cfg.lr = lr as $Dtype;
+            }
+        }
+    };
+}
+
+impl_learning_rate!(Sgd, f32);
+impl_learning_rate!(Sgd, f64);
+impl_learning_rate!(Adam, f32);
+impl_learning_rate!(Adam, f64);
+impl_learning_rate!(RMSprop, f32);
+impl_learning_rate!(RMSprop, f64);
+
+impl<O: LearningRate, D: DeviceStorage, E: Dtype> LearningRate for MasterWeights<O, D, E> {
+    fn lr(&self) -> f64 {
+        self.opt.lr()
+    }
+    fn set_lr(&mut self, lr: f64) {
+        self.opt.set_lr(lr)
+    }
+}
+
+/// How [LrFinder] moves from [LrFinderConfig::start_lr] to [LrFinderConfig::end_lr].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LrSweep {
+    /// Multiplies the learning rate by the same factor every step, so that every order of
+    /// magnitude gets the same number of steps.
+    Exponential,
+
+    /// Adds the same amount to the learning rate every step.
+    Linear,
+}
+
+/// Configuration of [LrFinder::run()].
+///
+/// ```rust
+/// # use dfdx::optim::*;
+/// LrFinderConfig {
+///     start_lr: 1e-6,
+///     end_lr: 1.0,
+///     num_steps: 200,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LrFinderConfig {
+    /// The learning rate of the first step. Defaults to `1e-7`.
+    pub start_lr: f64,
+
+    /// The learning rate of the last step. Defaults to `10.0`.
+    pub end_lr: f64,
+
+    /// The maximum number of steps in the sweep. Defaults to `100`.
+    pub num_steps: usize,
+
+    /// How the learning rate moves between `start_lr` and `end_lr`. Defaults to
+    /// [LrSweep::Exponential].
+    pub sweep: LrSweep,
+
+    /// The decay of the exponential moving average of the loss, which is recorded in
+    /// [LrFinder::losses]. Defaults to `0.98`.
+    pub smoothing: f64,
+
+    /// Stops the sweep once the smoothed loss is more than `divergence` times the lowest
+    /// smoothed loss so far. Defaults to `4.0`.
+    pub divergence: f64,
+}
+
+impl Default for LrFinderConfig {
+    fn default() -> Self {
+        Self {
+            start_lr: 1e-7,
+            end_lr: 10.0,
+            num_steps: 100,
+            sweep: LrSweep::Exponential,
+            smoothing: 0.98,
+            divergence: 4.0,
+        }
+    }
+}
+
+impl LrFinderConfig {
+    /// The learning rate of step `i` of the sweep.
+    pub fn lr_at(&self, i: usize) -> f64 {
+        let frac = match self.num_steps {
+            0 | 1 => 0.0,
+            n => i as f64 / (n - 1) as f64,
+        };
+        match self.sweep {
+            LrSweep::Exponential => self.start_lr * (self.end_lr / self.start_lr).powf(frac),
+            LrSweep::Linear => self.start_lr + (self.end_lr - self.start_lr) * frac,
+        }
+    }
+}
+
+/// The learning rate range test from
+/// [Cyclical Learning Rates for Training Neural Networks](https://arxiv.org/abs/1506.01186):
+/// trains with an increasing learning rate and records the loss at each learning rate.
+///
+/// The loss falls slowly while the learning rate is too small, falls quickly around good
+/// learning rates, and blows up once it is too large. [LrFinder::suggestion()] picks the
+/// learning rate where the loss falls fastest, and [LrFinder::cyclical_bounds()] picks
+/// bounds for a cyclical schedule.
+///
+/// The sweep trains `model` and changes the state of `opt`, so run it on copies of both,
+/// then train the original model with the suggested learning rate:
+///
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// let target = dev.tensor([1.0, -2.0, 3.0]);
+/// let mut model: Tensor<Rank1<3>, f32, _> = dev.zeros();
+/// let mut opt = Sgd::new(&model, Default::default());
+/// let finder = LrFinder::run(Default::default(), &mut model, &mut opt, |w| {
+///     let loss = (w.trace() - target.clone()).square().mean();
+///     (loss.array(), loss.backward())
+/// })
+/// .unwrap();
+/// let lr = finder.suggestion().unwrap();
+/// assert!(lr > 1e-4 && lr < 1.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LrFinder {
+    /// The learning rate of each step of the sweep.
+    pub lrs: Vec<f64>,
+
+    /// The exponential moving average of the loss at each step of the sweep, corrected
+    /// for its bias towards `0.0` in the first steps.
+    pub losses: Vec<f64>,
+}
+
+impl LrFinder {
+    /// Sweeps the learning rate of `opt` as described by `cfg`. Each step calls `loss_fn`
+    /// for the loss of `model` and its gradients, then updates `model` with them.
+    ///
+    /// The sweep stops early when the loss is not finite, or more than
+    /// [LrFinderConfig::divergence] times the lowest loss. The learning rate of `opt` is
+    /// restored afterwards.
+    pub fn run<M, D: DeviceStorage, E: Dtype, O, F>(
+        cfg: LrFinderConfig,
+        model: &mut M,
+        opt: &mut O,
+        loss_fn: F,
+    ) -> Result<Self, OptimizerUpdateError<D>>
+    where
+        O: Optimizer<M, D, E> + LearningRate,
+        F: FnMut(&M) -> (f32, Gradients),
+    {
+        let initial_lr = opt.lr();
+        let finder = Self::sweep(cfg, model, opt, loss_fn);
+        opt.set_lr(initial_lr);
+        finder
+    }
+
+    fn sweep<M, D: DeviceStorage, E: Dtype, O, F>(
+        cfg: LrFinderConfig,
+        model: &mut M,
+        opt: &mut O,
+        mut loss_fn: F,
+    ) -> Result<Self, OptimizerUpdateError<D>>
+    where
+        O: Optimizer<M, D, E> + LearningRate,
+        F: FnMut(&M) -> (f32, Gradients),
+    {
+        let mut finder = Self::default();
+        let mut avg = 0.0;
+        let mut best = f64::INFINITY;
+        for i in 0..cfg.num_steps {
+            let lr = cfg.lr_at(i);
+            opt.set_lr(lr);
+            let (loss, gradients) = loss_fn(model);
+            let loss = loss as f64;
+            if !loss.is_finite() {
+                break;
+            }
+            avg = cfg.smoothing * avg + (1.0 - cfg.smoothing) * loss;
+            let smoothed = avg / (1.0 - cfg.smoothing.powi(i as i32 + 1));
+            finder.lrs.push(lr);
+            finder.losses.push(smoothed);
+            if smoothed > cfg.divergence * best {
+                break;
+            }
+            best = best.min(smoothed);
+            opt.update(model, gradients)?;
+        }
+        Ok(finder)
+    }
+
+    /// The learning rate where the smoothed loss decreases fastest with respect to the
+    /// log of the learning rate. `None` if the sweep recorded less than 2 steps.
+    pub fn suggestion(&self) -> Option<f64> {
+        let mut steepest: Option<(f64, f64)> = None;
+        for i in 0..self.lrs.len().saturating_sub(1) {
+            let slope =
+                (self.losses[i + 1] - self.losses[i]) / (self.lrs[i + 1].ln() - self.lrs[i].ln());
+            if steepest.is_none_or(|(s, _)| slope < s) {
+                steepest = Some((slope, self.lrs[i]));
+            }
+        }
+        steepest.map(|(_, lr)| lr)
+    }
+
+    /// The learning rate with the lowest smoothed loss. This is usually too large to train
+    /// with, but is a good upper bound. `None` if the sweep recorded no steps.
+    pub fn min_loss_lr(&self) -> Option<f64> {
+        let mut min: Option<(f64, f64)> = None;
+        for (&lr, &loss) in self.lrs.iter().zip(self.losses.iter()) {
+            if min.is_none_or(|(l, _)| loss < l) {
+                min = Some((loss, lr));
+            }
+        }
+        min.map(|(_, lr)| lr)
+    }
+
+    /// `(base_lr, max_lr)` bounds for a cyclical learning rate schedule. `max_lr` is
+    /// [LrFinder::min_loss_lr()], and `base_lr` is 4 times smaller, as suggested by the paper.
+    pub fn cyclical_bounds(&self) -> Option<(f64, f64)> {
+        self.min_loss_lr().map(|max_lr| (max_lr / 4.0, max_lr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optim::{SgdConfig, WeightDecay};
+    use crate::tests::TestDevice;
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_lr_at() {
+        let mut cfg = LrFinderConfig {
+            start_lr: 1e-4,
+            end_lr: 1.0,
+            num_steps: 5,
+            ..Default::default()
+        };
+        for (i, lr) in [1e-4, 1e-3, 1e-2, 1e-1, 1.0].into_iter().enumerate() {
+            assert!((cfg.lr_at(i) / lr - 1.0).abs() < 1e-9);
+        }
+        cfg.sweep = LrSweep::Linear;
+        cfg.start_lr = 0.0;
+        assert_eq!(cfg.lr_at(2), 0.5);
+        assert_eq!(cfg.lr_at(4), 1.0);
+    }
+
+    #[test]
+    fn test_lr_finder_linear_regression() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<3, 16>, f32, _> = dev.sample_normal();
+        let noise: Tensor<Rank2<1, 16>, f32, _> = dev.sample_normal();
+        let y = dev.tensor([[1.0, -2.0, 0.5]]).matmul(x.clone()) + noise * 0.1;
+        let mut model: Tensor<Rank2<1, 3>, f32, _> = dev.zeros();
+        let mut opt = Sgd::new(&model, SgdConfig::default());
+        let cfg = LrFinderConfig {
+            start_lr: 1e-4,
+            end_lr: 100.0,
+            num_steps: 120,
+            ..Default::default()
+        };
+        let finder = LrFinder::run(cfg, &mut model, &mut opt, |w| {
+            let loss = (w.trace().matmul(x.clone()) - y.clone()).square().mean();
+            (loss.array(), loss.backward())
+        })
+        .unwrap();
+
+        // sgd diverges once the learning rate is too large, which stops the sweep early
+        assert!(finder.lrs.len() < 120);
+        assert_eq!(finder.lrs.len(), finder.losses.len());
+        assert_eq!(opt.lr() as f32, 1e-2);
+
+        let lr = finder.suggestion().unwrap();
+        assert!(lr > 1e-3 && lr < 1.0, "{lr}");
+        let (base_lr, max_lr) = finder.cyclical_bounds().unwrap();
+        assert!(max_lr > lr && max_lr < 2.0, "{max_lr}");
+        assert_eq!(base_lr * 4.0, max_lr);
+    }
+
+    #[test]
+    fn test_lr_finder_unused_params() {
+        let dev: TestDevice = Default::default();
+        let mut model: (Tensor<Rank1<2>, f32, _>, Tensor<Rank1<2>, f32, _>) =
+            (dev.zeros(), dev.zeros());
+        let mut opt = Sgd::new(
+            &model,
+            SgdConfig {
+                lr: 1e-2,
+                momentum: None,
+                weight_decay: Some(WeightDecay::L2(1e-3)),
+            },
+        );
+        let r = LrFinder::run(Default::default(), &mut model, &mut opt, |(a, _)| {
+            let loss = a.trace().square().sum();
+            (loss.array(), loss.backward())
+        });
+        assert!(r.is_err());
+        assert_eq!(opt.lr() as f32, 1e-2);
+    }
+
+    #[test]
+    fn test_empty_lr_finder() {
+        let finder = LrFinder::default();
+        assert_eq!(finder.suggestion(), None);
+        assert_eq!(finder.min_loss_lr(), None);
+        assert_eq!(finder.cyclical_bounds(), None);
+    }
+}
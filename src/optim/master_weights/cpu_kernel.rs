@@ -0,0 +1,39 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+use super::{MasterDtype, MasterKernel};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype, P: MasterDtype<E>> MasterKernel<P, E> for Cpu {
+    fn to_master<S: Shape>(
+        &self,
+        param: &Self::Storage<S, P>,
+    ) -> Result<Self::Storage<(usize,), E>, Self::Err> {
+        let numel = param.shape.num_elements();
+        let mut data = Vec::with_capacity(numel);
+        let mut iter = param.iter();
+        while let Some(p) = iter.next() {
+            data.push(p.to_master());
+        }
+        Ok(StridedArray {
+            data: Arc::new(data),
+            shape: (numel,),
+            strides: [1],
+        })
+    }
+
+    fn copy_from_master<S: Shape>(
+        &self,
+        master: &Self::Storage<(usize,), E>,
+        param: &mut Self::Storage<S, P>,
+    ) -> Result<(), Self::Err> {
+        let mut iter = param.iter_mut();
+        let mut master_iter = master.buf_iter();
+        while let Some((p, m)) = iter.next().zip(master_iter.next()) {
+            *p = P::from_master(*m);
+        }
+        Ok(())
+    }
+}
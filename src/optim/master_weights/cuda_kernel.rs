@@ -0,0 +1,49 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+
+use super::MasterKernel;
+use std::sync::Arc;
+
+/// Cuda only supports `f32`, so the master copies are device side copies of the parameters.
+impl MasterKernel<f32, f32> for Cuda {
+    fn to_master<S: Shape>(
+        &self,
+        param: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<(usize,), f32>, Self::Err> {
+        let numel = param.shape.num_elements();
+        if param.strides != param.shape.strides() || param.data.len() != numel {
+            let cpu_param = self.storage_to_cpu(param)?;
+            let cpu_master = MasterKernel::<f32, f32>::to_master(&self.cpu, &cpu_param)?;
+            let mut master = CudaArray {
+                data: Arc::new(self.dev.alloc_zeros_async::<f32>(numel)?),
+                shape: (numel,),
+                strides: [1],
+            };
+            self.storage_from_cpu(&mut master, &cpu_master)?;
+            return Ok(master);
+        }
+        Ok(CudaArray {
+            data: Arc::new(param.data.clone_async()?),
+            shape: (numel,),
+            strides: [1],
+        })
+    }
+
+    fn copy_from_master<S: Shape>(
+        &self,
+        master: &Self::Storage<(usize,), f32>,
+        param: &mut Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let numel = param.shape.num_elements();
+        if param.strides != param.shape.strides() || param.data.len() != numel {
+            let cpu_master = self.storage_to_cpu(master)?;
+            let mut cpu_param = self.storage_to_cpu(param)?;
+            MasterKernel::<f32, f32>::copy_from_master(&self.cpu, &cpu_master, &mut cpu_param)?;
+            return self.storage_from_cpu(param, &cpu_param);
+        }
+        param.data = Arc::new(master.data.clone_async()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,407 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use std::vec::Vec;
+
+use crate::gradients::Gradients;
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::{DeviceStorage, Tensor};
+
+use super::optimizer::*;
+
+/// Converts between the dtype of a parameter and the dtype `E` of its master copy
+/// in [MasterWeights].
+pub trait MasterDtype<E>: Dtype {
+    fn to_master(self) -> E;
+    fn from_master(master: E) -> Self;
+}
+
+macro_rules! master_dtype {
+    ($Param:ty, $Master:ty) => {
+        impl MasterDtype<$Master> for $Param {
+            fn to_master(self) -> $Master {
+                self as $Master
+            }
+            fn from_master(master: $Master) -> Self {
+                master as $Param
+            }
+        }
+    };
+}
+
+master_dtype!(f32, f32);
+master_dtype!(f32, f64);
+master_dtype!(f64, f32);
+master_dtype!(f64, f64);
+
+/// Casts parameters to and from their master copies in [MasterWeights], without leaving the device.
+pub trait MasterKernel<P: Dtype, E: Dtype>: DeviceStorage {
+    /// A flat copy of `param` in the master dtype `E`.
+    fn to_master<S: Shape>(
+        &self,
+        param: &Self::Storage<S, P>,
+    ) -> Result<Self::Storage<(usize,), E>, Self::Err>;
+
+    /// Writes the flat master copy `master` back into `param` in its dtype `P`.
+    fn copy_from_master<S: Shape>(
+        &self,
+        master: &Self::Storage<(usize,), E>,
+        param: &mut Self::Storage<S, P>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The master copies of the parameters of a model in [MasterWeights]. Each parameter
+/// is flattened into one tensor of [Self::params], in the order parameters are visited
+/// by [GradientUpdate].
+///
+/// This is the model that the optimizer wrapped by [MasterWeights] updates.
+#[derive(Debug, Clone)]
+pub struct MasterParams<E: Dtype, D: DeviceStorage> {
+    pub params: Vec<Tensor<(usize,), E, D>>,
+}
+
+impl<E: Dtype, D: DeviceStorage> GradientUpdate<D, E> for MasterParams<E, D> {
+    fn update<U>(&mut self, updater: &mut U, unused: &mut UnusedTensors) -> Result<(), D::Err>
+    where
+        U: ParamUpdater<D, E>,
+    {
+        for p in self.params.iter_mut() {
+            p.update(updater, unused)?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps master copies of a model's parameters in the dtype `E` (`f32` by default), while the
+/// model itself stores its parameters in a lower precision dtype.
+///
+/// On every [Optimizer::update()]:
+/// 1. The gradients of the model are cast to `E`.
+/// 2. The wrapped optimizer `O` updates the master copies with these gradients.
+/// 3. The updated master copies are cast back into the model's parameters.
+///
+/// This means small updates that can't be represented in the model's dtype still accumulate
+/// in the master copies, and the optimizer state (e.g. momentum) is also kept in `E`.
+///
+/// The parameters are found with [GradientUpdate], so any model can be wrapped, and the
+/// casts run on the device. The master copies are created from the model in
+/// [MasterWeights::new()], so changes made to the model's parameters outside of
+/// [Optimizer::update()] are overwritten by the next update.
+///
+/// # Example Usage
+///
+/// ```rust
+/// # use dfdx::{prelude::*, optim::*};
+/// # let dev: Cpu = Default::default();
+/// type Model = (Linear<5, 3>, ReLU, Linear<3, 1>);
+/// let mut model = Model::build_on_device(&dev);
+/// let mut opt = MasterWeights::<_, _, f64>::new(&model, |master| {
+///     Sgd::new(master, SgdConfig { lr: 1e-2, momentum: None, weight_decay: None })
+/// });
+/// # let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+/// let loss = model.forward(x.trace()).square().mean();
+/// opt.update(&mut model, loss.backward()).expect("unused params");
+/// ```
+pub struct MasterWeights<O, D: DeviceStorage, E: Dtype = f32> {
+    /// The optimizer that updates the master copies.
+    pub opt: O,
+    master: MasterParams<E, D>,
+    gradients: Gradients,
+}
+
+impl<O, D: DeviceStorage, E: Dtype> MasterWeights<O, D, E> {
+    /// Creates master copies of `model`'s parameters, and constructs the wrapped optimizer
+    /// for them with `build_opt`.
+    pub fn new<M, P, F>(model: &M, build_opt: F) -> Self
+    where
+        M: Clone + GradientUpdate<D, P>,
+        P: Dtype,
+        D: MasterKernel<P, E>,
+        F: FnOnce(&MasterParams<E, D>) -> O,
+    {
+        let mut collect = CollectMaster { params: Vec::new() };
+        // NOTE: [GradientUpdate] needs a mutable model, so this visits a clone (which shares
+        // its storage with `model`) instead.
+        model
+            .clone()
+            .update(&mut collect, &mut Default::default())
+            .unwrap();
+        let master = MasterParams {
+            params: collect.params,
+        };
+        let opt = build_opt(&master);
+        Self {
+            opt,
+            master,
+            gradients: Default::default(),
+        }
+    }
+
+    /// The master copies of the model's parameters.
+    pub fn master(&self) -> &MasterParams<E, D> {
+        &self.master
+    }
+}
+
+impl<M, O, E, P, D> Optimizer<M, D, P> for MasterWeights<O, D, E>
+where
+    M: GradientUpdate<D, P>,
+    O: Optimizer<MasterParams<E, D>, D, E>,
+    E: Dtype,
+    P: Dtype,
+    D: MasterKernel<P, E>,
+{
+    fn update(
+        &mut self,
+        module: &mut M,
+        gradients: Gradients,
+    ) -> Result<(), OptimizerUpdateError<D>> {
+        let mut unused = Default::default();
+
+        let mut to_master = GradsToMaster {
+            gradients,
+            master: &self.master,
+            master_gradients: Default::default(),
+            index: 0,
+        };
+        module
+            .update(&mut to_master, &mut unused)
+            .map_err(OptimizerUpdateError::DeviceError)?;
+        let master_gradients = to_master.master_gradients;
+        self.gradients = to_master.gradients;
+
+        // parameters without gradients are reported below with the ids of the model's parameters
+        match self.opt.update(&mut self.master, master_gradients) {
+            Ok(()) | Err(OptimizerUpdateError::UnusedParams(_)) => {}
+            Err(e) => return Err(e),
+        }
+
+        let mut from_master = FromMaster {
+            master: &self.master,
+            index: 0,
+        };
+        module
+            .update(&mut from_master, &mut Default::default())
+            .map_err(OptimizerUpdateError::DeviceError)?;
+
+        unused.into()
+    }
+
+    fn recycled_gradients(&mut self) -> Gradients {
+        let mut gradients = std::mem::take(&mut self.gradients);
+        gradients.recycle();
+        gradients
+    }
+}
+
+/// Creates the master copy of every parameter, in the order they are visited.
+struct CollectMaster<E: Dtype, D: DeviceStorage> {
+    params: Vec<Tensor<(usize,), E, D>>,
+}
+
+impl<D: MasterKernel<P, E>, E: Dtype, P: Dtype> ParamUpdater<D, P> for CollectMaster<E, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, P, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let master = p.device.to_master(&p.storage)?;
+        self.params.push(p.device.upgrade(master));
+        Ok(())
+    }
+}
+
+/// Casts the gradient of every parameter to the master dtype `E`, and stores it as the
+/// gradient of the parameter's master copy.
+struct GradsToMaster<'a, E: Dtype, D: DeviceStorage> {
+    gradients: Gradients,
+    master: &'a MasterParams<E, D>,
+    master_gradients: Gradients,
+    index: usize,
+}
+
+impl<'a, D: MasterKernel<P, E>, E: Dtype, P: Dtype> ParamUpdater<D, P> for GradsToMaster<'a, E, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, P, D>,
+        unused: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let master = &self.master.params[self.index];
+        self.index += 1;
+        match self.gradients.remove(p) {
+            None => unused.add(p),
+            Some(g) => {
+                let master_g = p.device.to_master(&g)?;
+                self.master_gradients.insert(master, master_g);
+                self.gradients.recycle_grad(g);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Casts every master copy back into its parameter, in the order they are visited.
+struct FromMaster<'a, E: Dtype, D: DeviceStorage> {
+    master: &'a MasterParams<E, D>,
+    index: usize,
+}
+
+impl<'a, D: MasterKernel<P, E>, E: Dtype, P: Dtype> ParamUpdater<D, P> for FromMaster<'a, E, D> {
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, P, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        let master = &self.master.params[self.index];
+        self.index += 1;
+        p.device.copy_from_master(&master.storage, &mut p.storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nn::{BuildOnDevice, Linear, Module, ReLU};
+    use crate::optim::{Momentum, Sgd, SgdConfig};
+    use crate::tests::{assert_close, TestDevice};
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_master_weights_accumulate_small_updates() {
+        let dev: TestDevice = Default::default();
+        let cfg = SgdConfig {
+            lr: 1.0,
+            momentum: None,
+            weight_decay: None,
+        };
+
+        let mut plain: Tensor<Rank1<3>, f32, _> = dev.ones();
+        let mut opt = Sgd::new(&plain, cfg);
+        for _ in 0..1000 {
+            let g = (plain.trace() * 1e-8).sum().backward();
+            opt.update(&mut plain, g).expect("");
+        }
+        // each update is too small to change an f32 that is `1.0`
+        assert_eq!(plain.array(), [1.0; 3]);
+
+        let mut model: Tensor<Rank1<3>, f32, _> = dev.ones();
+        let mut opt = MasterWeights::<_, _, f64>::new(&model, |master| {
+            Sgd::new(
+                master,
+                SgdConfig {
+                    lr: 1.0,
+                    momentum: None,
+                    weight_decay: None,
+                },
+            )
+        });
+        for _ in 0..1000 {
+            let g = (model.trace() * 1e-8).sum().backward();
+            opt.update(&mut model, g).expect("");
+        }
+        assert_close(&model.array(), &[0.99999; 3]);
+        assert!(model.array().iter().all(|&x| x < 1.0));
+        for x in opt.master().params[0].as_vec() {
+            assert!((x - (1.0 - 1e-5)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_master_weights_same_dtype_matches_optimizer() {
+        let dev: TestDevice = Default::default();
+        let cfg = SgdConfig {
+            lr: 1e-1,
+            momentum: Some(Momentum::Nesterov(0.5)),
+            weight_decay: None,
+        };
+
+        let mut a: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let mut b = a.clone();
+        let mut opt_a = Sgd::new(&a, cfg);
+        let mut opt_b = MasterWeights::<_, _, f32>::new(&b, |m| Sgd::new(m, cfg));
+        for _ in 0..5 {
+            let g = a.trace().square().sum().backward();
+            opt_a.update(&mut a, g).expect("");
+            let g = b.trace().square().sum().backward();
+            opt_b.update(&mut b, g).expect("");
+            assert_close(&a.array(), &b.array());
+        }
+    }
+
+    #[test]
+    fn test_master_weights_unused_params() {
+        let dev: TestDevice = Default::default();
+        let mut model: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let mut opt = MasterWeights::<_, _, f64>::new(&model, |m| {
+            Sgd::new(
+                m,
+                SgdConfig {
+                    lr: 1e-2,
+                    momentum: None,
+                    weight_decay: None,
+                },
+            )
+        });
+        let unused: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        let g = unused.trace().square().sum().backward();
+        match opt.update(&mut model, g) {
+            Err(OptimizerUpdateError::UnusedParams(unused)) => assert_eq!(unused.ids, [model.id]),
+            _ => panic!("expected unused params"),
+        }
+    }
+
+    #[test]
+    fn test_master_weights_wraps_model() {
+        let dev: TestDevice = Default::default();
+        type Model = (Linear<5, 3>, ReLU, Linear<3, 2>);
+        let mut model = Model::build_on_device(&dev);
+        let mut plain = model.clone();
+        let mut opt = MasterWeights::<_, _, f64>::new(&model, |m| {
+            Sgd::new(
+                m,
+                SgdConfig {
+                    lr: 1e-1,
+                    momentum: None,
+                    weight_decay: None,
+                },
+            )
+        });
+        let mut plain_opt = Sgd::new(
+            &plain,
+            SgdConfig {
+                lr: 1e-1,
+                momentum: None,
+                weight_decay: None,
+            },
+        );
+        assert_eq!(opt.master().params.len(), 4);
+        assert_eq!(
+            opt.master().params[0].as_vec(),
+            model
+                .0
+                .weight
+                .as_vec()
+                .iter()
+                .map(|&x| x as f64)
+                .collect::<std::vec::Vec<_>>()
+        );
+
+        let x: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        for _ in 0..3 {
+            let g = model.forward(x.trace()).square().mean().backward();
+            opt.update(&mut model, g).expect("");
+            let g = plain.forward(x.trace()).square().mean().backward();
+            plain_opt.update(&mut plain, g).expect("");
+        }
+        assert_close(&model.0.weight.array(), &plain.0.weight.array());
+        assert_close(&model.0.bias.array(), &plain.0.bias.array());
+        assert_close(&model.2.weight.array(), &plain.2.weight.array());
+        assert_close(&model.2.bias.array(), &plain.2.bias.array());
+        assert_ne!(
+            model.2.weight.array(),
+            Model::build_on_device(&dev).2.weight.array()
+        );
+    }
+}
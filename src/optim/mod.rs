@@ -8,6 +8,21 @@
 //! - [Adam::new()] with [AdamConfig]
 //! - [RMSprop::new()] with [RMSpropConfig]
 //!
+//! To keep master copies of low precision parameters in a higher precision dtype, wrap any
+//! of these optimizers in [MasterWeights]. Alternatively, `Cpu::set_compensated()` makes
+//! every optimizer keep the rounding error of each parameter update, and add it back
+//! in the next one.
+//!
+//! # Choosing a learning rate
+//!
+//! [LrFinder] sweeps the learning rate of any optimizer that implements [LearningRate], and
+//! suggests a learning rate from how the loss changed.
+//!
+//! # Training GANs
+//!
+//! [GanTrainer] alternates discriminator & generator updates with their own optimizers,
+//! detaching the fake samples of the discriminator's update, with an optional R1 penalty.
+//!
 //! # Updating network parameters
 //!
 //! This is done via [Optimizer::update()], where you pass in a mutable [crate::nn::Module], and
@@ -20,7 +35,7 @@
 //! let mut model = MyModel::build_on_device(&dev);
 //! let mut opt = Sgd::new(&model, Default::default());
 //! # let y = model.forward(dev.zeros::<Rank1<5>>().traced());
-//! # let loss = losses::mse_loss(y, dev.zeros());
+//! # let loss = losses::mse_loss(y, dev.zeros(), losses::ReduceMean);
 //! // -- snip loss computation --
 //!
 //! let gradients: Gradients = loss.backward();
@@ -28,16 +43,24 @@
 //! ```
 
 mod adam;
+mod gan;
+mod lr_finder;
+mod master_weights;
 mod optimizer;
 mod rmsprop;
 mod sgd;
 
 pub use adam::{Adam, AdamConfig};
+pub use gan::{GanConfig, GanLoss, GanLosses, GanTrainer};
+pub use lr_finder::{LearningRate, LrFinder, LrFinderConfig, LrSweep};
+pub use master_weights::{MasterDtype, MasterKernel, MasterParams, MasterWeights};
 pub use optimizer::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
 pub use optimizer::{Momentum, WeightDecay};
 pub use rmsprop::{RMSprop, RMSpropConfig};
 pub use sgd::{Sgd, SgdConfig};
 
+pub(crate) use optimizer::AsBuffers;
+
 pub mod prelude {
     pub use super::{GradientUpdate, Optimizer, OptimizerUpdateError, ParamUpdater, UnusedTensors};
 }
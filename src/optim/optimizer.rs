@@ -64,6 +64,16 @@ pub(super) fn momentum_to_cuda<E: Default>(wd: Option<Momentum<E>>) -> (Momentum
     }
 }
 
+/// `p -= g` with Kahan summation, for when the device is `compensated()`. `c` is how much
+/// more than intended the previous updates subtracted from `p` due to rounding, and is
+/// subtracted from this update.
+pub(super) fn compensated_sub<E: Dtype>(p: &mut E, g: E, c: &mut E) {
+    let y = g - *c;
+    let t = *p - y;
+    *c = (*p - t) - y;
+    *p = t;
+}
+
 /// All optimizers must implement the update function, which takes an object
 /// that implements [GradientUpdate], and calls [GradientUpdate::update].
 ///
@@ -89,6 +99,16 @@ pub trait Optimizer<M, D: DeviceStorage, E: Dtype> {
         module: &mut M,
         gradients: Gradients,
     ) -> Result<(), OptimizerUpdateError<D>>;
+
+    /// Takes the [Gradients] from the last call to [Optimizer::update()], with all of
+    /// their buffers recycled (see [Gradients::recycle()]). Pass these to
+    /// [crate::tensor::Tensor::trace_into()] so the next backward pass reuses them
+    /// instead of allocating new gradients.
+    ///
+    /// Returns empty [Gradients] by default.
+    fn recycled_gradients(&mut self) -> Gradients {
+        Default::default()
+    }
 }
 
 /// Represents something that can be updated with a [ParamUpdater].
@@ -113,6 +133,10 @@ impl<S: Shape, E: Dtype, D: DeviceStorage> GradientUpdate<D, E> for Tensor<S, E,
 
 /// Represents something that can update a tensor.
 ///
+/// [GradientUpdate] visits trainable parameters with [ParamUpdater::update_param()], and
+/// non-trainable buffers (e.g. running statistics, cached constants, or frozen weights)
+/// with [ParamUpdater::update_buffer()].
+///
 /// See [crate::optim::Sgd] and [crate::optim::Adam] for examples on implementing this.
 pub trait ParamUpdater<D: DeviceStorage, E: Dtype> {
     /// Retrieves the data associated with `p` if there is any.
@@ -123,6 +147,35 @@ pub trait ParamUpdater<D: DeviceStorage, E: Dtype> {
         p: &mut Tensor<S, E, D>,
         unused: &mut UnusedTensors,
     ) -> Result<(), D::Err>;
+
+    /// Visits a buffer, which is part of a module's state but never has a gradient.
+    /// Buffers are never added to [UnusedTensors].
+    ///
+    /// Does nothing by default, so optimizers never modify buffers.
+    fn update_buffer<S: Shape>(&mut self, b: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        let _ = b;
+        Ok(())
+    }
+}
+
+/// A [ParamUpdater] that passes every parameter to [ParamUpdater::update_buffer()]
+/// of the wrapped updater. Used to visit the parameters of frozen modules as buffers.
+pub(crate) struct AsBuffers<'a, U>(pub &'a mut U);
+
+impl<'a, D: DeviceStorage, E: Dtype, U: ParamUpdater<D, E>> ParamUpdater<D, E>
+    for AsBuffers<'a, U>
+{
+    fn update_param<S: Shape>(
+        &mut self,
+        p: &mut Tensor<S, E, D>,
+        _: &mut UnusedTensors,
+    ) -> Result<(), D::Err> {
+        self.0.update_buffer(p)
+    }
+
+    fn update_buffer<S: Shape>(&mut self, b: &mut Tensor<S, E, D>) -> Result<(), D::Err> {
+        self.0.update_buffer(b)
+    }
 }
 
 /// Holds [UniqueId] of tensors that were missing gradients during
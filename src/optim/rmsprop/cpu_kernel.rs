@@ -1,11 +1,12 @@
 use crate::{
-    optim::WeightDecay,
+    optim::{optimizer::compensated_sub, WeightDecay},
     tensor::cpu::{Cpu, StridedArray},
 };
 
 use super::{RMSpropConfig, RMSpropKernel};
 
 impl RMSpropKernel<f32> for Cpu {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: crate::shapes::Shape>(
         &self,
         cfg: &RMSpropConfig<f32>,
@@ -14,11 +15,14 @@ impl RMSpropKernel<f32> for Cpu {
         square_avg: &mut StridedArray<S, f32>,
         grad_avg: &mut StridedArray<S, f32>,
         grad: StridedArray<S, f32>,
+        compensation: Option<&mut StridedArray<S, f32>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        let mut compensation = compensation.map(|c| c.buf_iter_mut());
+
         for ((p, mut g), (s_avg, (g_avg, m))) in
             param.buf_iter_mut().zip(grad.buf_iter().cloned()).zip(
                 square_avg
@@ -57,7 +61,10 @@ impl RMSpropKernel<f32> for Cpu {
                 g += wd * cfg.lr * *p;
             }
 
-            *p -= g;
+            match compensation.as_mut().and_then(|c| c.next()) {
+                Some(c) => compensated_sub(p, g, c),
+                None => *p -= g,
+            }
         }
         Ok(())
     }
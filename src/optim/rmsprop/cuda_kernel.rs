@@ -43,6 +43,7 @@ const FN_NAME: &str = "rmsprop_update";
 const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/rmsprop.ptx"));
 
 impl super::RMSpropKernel<f32> for Cuda {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: Shape>(
         &self,
         cfg: &RMSpropConfig<f32>,
@@ -51,11 +52,36 @@ impl super::RMSpropKernel<f32> for Cuda {
         square_avg: &mut Self::Storage<S, f32>,
         grad_avg: &mut Self::Storage<S, f32>,
         grad: Self::Storage<S, f32>,
+        compensation: Option<&mut Self::Storage<S, f32>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        if let Some(compensation) = compensation {
+            let mut cpu_param = self.storage_to_cpu(param)?;
+            let mut cpu_momentum = self.storage_to_cpu(momentum)?;
+            let mut cpu_square_avg = self.storage_to_cpu(square_avg)?;
+            let mut cpu_grad_avg = self.storage_to_cpu(grad_avg)?;
+            let mut cpu_compensation = self.storage_to_cpu(compensation)?;
+            let cpu_grad = self.storage_to_cpu(&grad)?;
+            super::RMSpropKernel::<f32>::update(
+                &self.cpu,
+                cfg,
+                &mut cpu_param,
+                &mut cpu_momentum,
+                &mut cpu_square_avg,
+                &mut cpu_grad_avg,
+                cpu_grad,
+                Some(&mut cpu_compensation),
+            )?;
+            self.storage_from_cpu(param, &cpu_param)?;
+            self.storage_from_cpu(momentum, &cpu_momentum)?;
+            self.storage_from_cpu(square_avg, &cpu_square_avg)?;
+            self.storage_from_cpu(grad_avg, &cpu_grad_avg)?;
+            return self.storage_from_cpu(compensation, &cpu_compensation);
+        }
+
         if !self.dev.has_func(MODULE_NAME, FN_NAME) {
             self.dev.load_ptx(PTX_SRC.into(), MODULE_NAME, &[FN_NAME])?;
         }
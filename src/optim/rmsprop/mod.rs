@@ -87,6 +87,7 @@ pub struct RMSprop<M, E: Dtype = f32> {
     momentums: Gradients,
     square_avg: Gradients,
     grad_avg: Gradients,
+    compensation: Gradients,
     gradients: Gradients,
 
     marker: PhantomData<*const M>,
@@ -101,6 +102,7 @@ impl<M, E: Dtype> RMSprop<M, E> {
             momentums: Default::default(),
             square_avg: Default::default(),
             grad_avg: Default::default(),
+            compensation: Default::default(),
             gradients: Default::default(),
             marker: PhantomData,
         }
@@ -108,6 +110,7 @@ impl<M, E: Dtype> RMSprop<M, E> {
 }
 
 pub(super) trait RMSpropKernel<E: Dtype>: DeviceStorage {
+    #[allow(clippy::too_many_arguments)]
     fn update<S: Shape>(
         &self,
         cfg: &RMSpropConfig<E>,
@@ -116,6 +119,7 @@ pub(super) trait RMSpropKernel<E: Dtype>: DeviceStorage {
         square_avg: &mut Self::Storage<S, E>,
         grad_avg: &mut Self::Storage<S, E>,
         grad: Self::Storage<S, E>,
+        compensation: Option<&mut Self::Storage<S, E>>,
     ) -> Result<(), Self::Err>;
 }
 
@@ -132,12 +136,18 @@ impl<M, D: RMSpropKernel<f32> + OneFillStorage<f32>> ParamUpdater<D, f32> for RM
                 let m = self.momentums.get_or_alloc_mut(p)?;
                 let sa = self.square_avg.get_or_alloc_mut(p)?;
                 let ga = self.grad_avg.get_or_alloc_mut(p)?;
+                let c = match p.device.compensated() {
+                    true => Some(self.compensation.get_or_alloc_mut(p)?),
+                    false => None,
+                };
 
                 if self.step == 0 {
                     p.device.try_fill_with_ones(sa)?;
                 }
 
-                p.device.update(&self.cfg, &mut p.storage, m, sa, ga, g)?;
+                p.device
+                    .update(&self.cfg, &mut p.storage, m, sa, ga, g.clone(), c)?;
+                self.gradients.recycle_grad(g);
             }
         }
         Ok(())
@@ -162,6 +172,12 @@ where
         self.step += 1;
         r
     }
+
+    fn recycled_gradients(&mut self) -> Gradients {
+        let mut gradients = std::mem::take(&mut self.gradients);
+        gradients.recycle();
+        gradients
+    }
 }
 
 #[cfg(test)]
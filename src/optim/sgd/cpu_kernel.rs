@@ -1,5 +1,5 @@
 use crate::{
-    optim::optimizer::{Momentum, WeightDecay},
+    optim::optimizer::{compensated_sub, Momentum, WeightDecay},
     shapes::{Dtype, Shape},
     tensor::cpu::*,
 };
@@ -13,11 +13,14 @@ impl<E: Dtype> SgdKernel<E> for Cpu {
         param: &mut StridedArray<S, E>,
         velocity: &mut StridedArray<S, E>,
         grad: StridedArray<S, E>,
+        compensation: Option<&mut StridedArray<S, E>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        let mut compensation = compensation.map(|c| c.buf_iter_mut());
+
         for ((p, mut g), v) in param
             .buf_iter_mut()
             .zip(grad.buf_iter().cloned())
@@ -43,7 +46,10 @@ impl<E: Dtype> SgdKernel<E> for Cpu {
                 g += wd * cfg.lr * *p;
             }
 
-            *p -= g;
+            match compensation.as_mut().and_then(|c| c.next()) {
+                Some(c) => compensated_sub(p, g, c),
+                None => *p -= g,
+            }
         }
 
         Ok(())
@@ -39,11 +39,30 @@ impl super::SgdKernel<f32> for Cuda {
         param: &mut Self::Storage<S, f32>,
         velocity: &mut Self::Storage<S, f32>,
         grad: Self::Storage<S, f32>,
+        compensation: Option<&mut Self::Storage<S, f32>>,
     ) -> Result<(), Self::Err> {
         debug_assert_eq!(param.data.len(), grad.data.len());
         debug_assert_eq!(param.shape, grad.shape);
         debug_assert_eq!(param.strides, grad.strides);
 
+        if let Some(compensation) = compensation {
+            let mut cpu_param = self.storage_to_cpu(param)?;
+            let mut cpu_velocity = self.storage_to_cpu(velocity)?;
+            let mut cpu_compensation = self.storage_to_cpu(compensation)?;
+            let cpu_grad = self.storage_to_cpu(&grad)?;
+            super::SgdKernel::<f32>::update(
+                &self.cpu,
+                cfg,
+                &mut cpu_param,
+                &mut cpu_velocity,
+                cpu_grad,
+                Some(&mut cpu_compensation),
+            )?;
+            self.storage_from_cpu(param, &cpu_param)?;
+            self.storage_from_cpu(velocity, &cpu_velocity)?;
+            return self.storage_from_cpu(compensation, &cpu_compensation);
+        }
+
         if !self.dev.has_func(MODULE_NAME, FN_NAME) {
             self.dev.load_ptx(PTX_SRC.into(), MODULE_NAME, &[FN_NAME])?;
         }
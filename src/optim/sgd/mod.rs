@@ -114,6 +114,7 @@ pub struct Sgd<M, E: Dtype = f32> {
     pub cfg: SgdConfig<E>,
 
     velocity: Gradients,
+    compensation: Gradients,
     gradients: Gradients,
 
     marker: PhantomData<*const M>,
@@ -125,6 +126,7 @@ impl<M, E: Dtype> Sgd<M, E> {
         Self {
             cfg,
             velocity: Default::default(),
+            compensation: Default::default(),
             gradients: Default::default(),
             marker: PhantomData,
         }
@@ -138,6 +140,7 @@ pub(super) trait SgdKernel<E: Dtype>: DeviceStorage {
         param: &mut Self::Storage<S, E>,
         velocity: &mut Self::Storage<S, E>,
         grad: Self::Storage<S, E>,
+        compensation: Option<&mut Self::Storage<S, E>>,
     ) -> Result<(), Self::Err>;
 }
 
@@ -152,7 +155,13 @@ impl<M, D: SgdKernel<E>, E: Dtype> ParamUpdater<D, E> for Sgd<M, E> {
             None => unused.add(p),
             Some(g) => {
                 let v = self.velocity.get_or_alloc_mut(p)?;
-                p.device.update(&self.cfg, &mut p.storage, v, g)?;
+                let c = match p.device.compensated() {
+                    true => Some(self.compensation.get_or_alloc_mut(p)?),
+                    false => None,
+                };
+                p.device
+                    .update(&self.cfg, &mut p.storage, v, g.clone(), c)?;
+                self.gradients.recycle_grad(g);
             }
         }
         Ok(())
@@ -175,6 +184,12 @@ where
             Err(e) => Err(OptimizerUpdateError::DeviceError(e)),
         }
     }
+
+    fn recycled_gradients(&mut self) -> Gradients {
+        let mut gradients = std::mem::take(&mut self.gradients);
+        gradients.recycle();
+        gradients
+    }
 }
 
 #[cfg(test)]
@@ -411,4 +426,30 @@ mod tests {
             assert_close(&t.array(), e);
         }
     }
+
+    #[test]
+    fn test_sgd_compensated() {
+        let dev: TestDevice = Default::default();
+        // each update is less than half the distance between f32s around 1000
+        let cfg = SgdConfig {
+            lr: 1e-5,
+            momentum: None,
+            weight_decay: None,
+        };
+        let mut t: Tensor<Rank1<2>, f32, _> = dev.ones() * 1000.0;
+        let mut sgd = Sgd::new(&t, cfg);
+        for _ in 0..1000 {
+            let gradients = t.trace().sum().backward();
+            sgd.update(&mut t, gradients).expect("");
+        }
+        assert_eq!(t.array(), [1000.0; 2]);
+
+        dev.set_compensated(true);
+        let mut sgd = Sgd::new(&t, cfg);
+        for _ in 0..1000 {
+            let gradients = t.trace().sum().backward();
+            sgd.update(&mut t, gradients).expect("");
+        }
+        assert_close(&t.array(), &[999.99; 2]);
+    }
 }
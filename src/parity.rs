@@ -0,0 +1,221 @@
+//! Parity tests against reference values shipped as fixtures in `fixtures/parity/*.npz`.
+//!
+//! Run them with `cargo test --features parity`.
+//!
+//! Each fixture holds the inputs of an op, a weight `w`, the expected output `y`, and the
+//! expected gradients of `(y * w).sum()` with respect to each input (`dx`, or `da` & `db`).
+//! The inputs cover the edge cases that tend to differ between frameworks: signed zeros,
+//! denormals, infinities, and values close to overflowing.
+//!
+//! The fixtures are plain `.npy` arrays in a `.npz` archive, so they can be exported from
+//! NumPy or PyTorch with `np.savez`. `fixtures/parity/generate.py` regenerates the
+//! ones in this repo.
+
+use crate::{
+    gradients::{NoneTape, OwnedTape},
+    shapes::*,
+    tensor::*,
+    tensor_ops::*,
+    tests::TestDevice,
+};
+use std::{fs::File, io::Read, string::String, vec::Vec};
+
+/// Outputs must be within `ATOL + RTOL * |expected|` of the reference.
+const RTOL: f32 = 1e-5;
+const ATOL: f32 = 1e-6;
+
+type Vector<T = NoneTape> = Tensor<(usize,), f32, TestDevice, T>;
+type Matrix<T = NoneTape> = Tensor<(usize, usize), f32, TestDevice, T>;
+
+struct Fixture {
+    name: &'static str,
+    dev: TestDevice,
+    zip: zip::ZipArchive<File>,
+}
+
+impl Fixture {
+    fn open(name: &'static str) -> Self {
+        let path = std::format!("{}/fixtures/parity/{name}.npz", env!("CARGO_MANIFEST_DIR"));
+        let file = File::open(&path).unwrap_or_else(|e| panic!("can't open {path}: {e}"));
+        Self {
+            name,
+            dev: Default::default(),
+            zip: zip::ZipArchive::new(file).unwrap(),
+        }
+    }
+
+    /// The shape of array `key`, parsed from its `.npy` header.
+    fn dims(&mut self, key: &str) -> Vec<usize> {
+        let mut f = self.zip.by_name(&std::format!("{key}.npy")).unwrap();
+        let mut prefix = [0; 10];
+        f.read_exact(&mut prefix).unwrap();
+        let mut header = std::vec![0; u16::from_le_bytes([prefix[8], prefix[9]]) as usize];
+        f.read_exact(&mut header).unwrap();
+        let header = String::from_utf8(header).unwrap();
+        let start = header.find("'shape': (").unwrap() + "'shape': (".len();
+        let end = start + header[start..].find(')').unwrap();
+        header[start..end]
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.parse().unwrap())
+            .collect()
+    }
+
+    fn load<S: Shape>(&mut self, key: &str, shape: S) -> Tensor<S, f32, TestDevice> {
+        let mut t = self.dev.zeros_like(&shape);
+        t.read_from_npz(&mut self.zip, std::format!("{key}.npy"))
+            .unwrap_or_else(|e| panic!("{}: can't read {key}: {e}", self.name));
+        t
+    }
+
+    fn vector(&mut self, key: &str) -> Vector {
+        let dims = self.dims(key);
+        self.load(key, (dims[0],))
+    }
+
+    fn matrix(&mut self, key: &str) -> Matrix {
+        let dims = self.dims(key);
+        self.load(key, (dims[0], dims[1]))
+    }
+
+    /// Panics with every element of `actual` that doesn't match the reference `key`.
+    fn check(&mut self, key: &str, actual: Vec<f32>) {
+        let expected = match self.dims(key)[..] {
+            [m, n] => self.load(key, (m, n)).as_vec(),
+            _ => self.vector(key).as_vec(),
+        };
+        assert_eq!(
+            actual.len(),
+            expected.len(),
+            "{}: {key} has the wrong size",
+            self.name
+        );
+        let mismatches: Vec<String> = actual
+            .iter()
+            .zip(expected.iter())
+            .enumerate()
+            .filter(|(_, (&a, &e))| !is_close(a, e))
+            .map(|(i, (a, e))| std::format!("{key}[{i}] = {a:e}, expected {e:e}"))
+            .collect();
+        assert!(
+            mismatches.is_empty(),
+            "{} differs from the reference:\n{}",
+            self.name,
+            mismatches.join("\n")
+        );
+    }
+}
+
+fn is_close(actual: f32, expected: f32) -> bool {
+    if expected.is_nan() {
+        actual.is_nan()
+    } else if expected.is_infinite() {
+        actual == expected
+    } else {
+        (actual - expected).abs() <= ATOL + RTOL * expected.abs()
+    }
+}
+
+fn check_unary(
+    name: &'static str,
+    f: impl FnOnce(Vector<OwnedTape<TestDevice>>) -> Vector<OwnedTape<TestDevice>>,
+) {
+    crate::keep_denormals();
+    let mut fixture = Fixture::open(name);
+    let x = fixture.vector("x");
+    let w = fixture.vector("w");
+    let y = f(x.trace());
+    fixture.check("y", y.as_vec());
+    let g = (y * w).sum().backward();
+    fixture.check("dx", g.get(&x).as_vec());
+}
+
+fn check_binary(
+    name: &'static str,
+    f: impl FnOnce(Vector<OwnedTape<TestDevice>>, Vector) -> Vector<OwnedTape<TestDevice>>,
+) {
+    crate::keep_denormals();
+    let mut fixture = Fixture::open(name);
+    let a = fixture.vector("a");
+    let b = fixture.vector("b");
+    let w = fixture.vector("w");
+    let y = f(a.trace(), b.clone());
+    fixture.check("y", y.as_vec());
+    let g = (y * w).sum().backward();
+    fixture.check("da", g.get(&a).as_vec());
+    fixture.check("db", g.get(&b).as_vec());
+}
+
+#[test]
+fn parity_exp() {
+    check_unary("exp", |x| x.exp());
+}
+
+#[test]
+fn parity_ln() {
+    check_unary("ln", |x| x.ln());
+}
+
+#[test]
+fn parity_sqrt() {
+    check_unary("sqrt", |x| x.sqrt());
+}
+
+#[test]
+fn parity_sigmoid() {
+    check_unary("sigmoid", |x| x.sigmoid());
+}
+
+#[test]
+fn parity_tanh() {
+    check_unary("tanh", |x| x.tanh());
+}
+
+#[test]
+fn parity_relu() {
+    check_unary("relu", |x| x.relu());
+}
+
+#[test]
+fn parity_abs() {
+    check_unary("abs", |x| x.abs());
+}
+
+#[test]
+fn parity_square() {
+    check_unary("square", |x| x.square());
+}
+
+#[test]
+fn parity_softmax() {
+    check_unary("softmax", |x| x.softmax::<Axis<0>>());
+}
+
+#[test]
+fn parity_log_softmax() {
+    check_unary("log_softmax", |x| x.log_softmax::<Axis<0>>());
+}
+
+#[test]
+fn parity_mul() {
+    check_binary("mul", |a, b| a * b);
+}
+
+#[test]
+fn parity_div() {
+    check_binary("div", |a, b| a / b);
+}
+
+#[test]
+fn parity_matmul() {
+    let mut fixture = Fixture::open("matmul");
+    let a = fixture.matrix("a");
+    let b = fixture.matrix("b");
+    let w = fixture.matrix("w");
+    let y = a.trace().matmul(b.clone());
+    fixture.check("y", y.as_vec());
+    let g = (y * w).sum().backward();
+    fixture.check("da", g.get(&a).as_vec());
+    fixture.check("db", g.get(&b).as_vec());
+}
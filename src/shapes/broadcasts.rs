@@ -74,6 +74,40 @@ broadcast_to!(3, (M, N, P), 4, (M, N, O, P), Axis<2>);
 broadcast_to!(3, (M, O, P), 4, (M, N, O, P), Axis<1>);
 broadcast_to!(3, (N, O, P), 4, (M, N, O, P), Axis<0>);
 
+broadcast_to!(1, (M), 5, (M, N, O, P, Q), Axes4<1, 2, 3, 4>);
+broadcast_to!(1, (N), 5, (M, N, O, P, Q), Axes4<0, 2, 3, 4>);
+broadcast_to!(1, (O), 5, (M, N, O, P, Q), Axes4<0, 1, 3, 4>);
+broadcast_to!(1, (P), 5, (M, N, O, P, Q), Axes4<0, 1, 2, 4>);
+broadcast_to!(1, (Q), 5, (M, N, O, P, Q), Axes4<0, 1, 2, 3>);
+
+broadcast_to!(2, (M, N), 5, (M, N, O, P, Q), Axes3<2, 3, 4>);
+broadcast_to!(2, (M, O), 5, (M, N, O, P, Q), Axes3<1, 3, 4>);
+broadcast_to!(2, (M, P), 5, (M, N, O, P, Q), Axes3<1, 2, 4>);
+broadcast_to!(2, (M, Q), 5, (M, N, O, P, Q), Axes3<1, 2, 3>);
+broadcast_to!(2, (N, O), 5, (M, N, O, P, Q), Axes3<0, 3, 4>);
+broadcast_to!(2, (N, P), 5, (M, N, O, P, Q), Axes3<0, 2, 4>);
+broadcast_to!(2, (N, Q), 5, (M, N, O, P, Q), Axes3<0, 2, 3>);
+broadcast_to!(2, (O, P), 5, (M, N, O, P, Q), Axes3<0, 1, 4>);
+broadcast_to!(2, (O, Q), 5, (M, N, O, P, Q), Axes3<0, 1, 3>);
+broadcast_to!(2, (P, Q), 5, (M, N, O, P, Q), Axes3<0, 1, 2>);
+
+broadcast_to!(3, (M, N, O), 5, (M, N, O, P, Q), Axes2<3, 4>);
+broadcast_to!(3, (M, N, P), 5, (M, N, O, P, Q), Axes2<2, 4>);
+broadcast_to!(3, (M, N, Q), 5, (M, N, O, P, Q), Axes2<2, 3>);
+broadcast_to!(3, (M, O, P), 5, (M, N, O, P, Q), Axes2<1, 4>);
+broadcast_to!(3, (M, O, Q), 5, (M, N, O, P, Q), Axes2<1, 3>);
+broadcast_to!(3, (M, P, Q), 5, (M, N, O, P, Q), Axes2<1, 2>);
+broadcast_to!(3, (N, O, P), 5, (M, N, O, P, Q), Axes2<0, 4>);
+broadcast_to!(3, (N, O, Q), 5, (M, N, O, P, Q), Axes2<0, 3>);
+broadcast_to!(3, (N, P, Q), 5, (M, N, O, P, Q), Axes2<0, 2>);
+broadcast_to!(3, (O, P, Q), 5, (M, N, O, P, Q), Axes2<0, 1>);
+
+broadcast_to!(4, (M, N, O, P), 5, (M, N, O, P, Q), Axis<4>);
+broadcast_to!(4, (M, N, O, Q), 5, (M, N, O, P, Q), Axis<3>);
+broadcast_to!(4, (M, N, P, Q), 5, (M, N, O, P, Q), Axis<2>);
+broadcast_to!(4, (M, O, P, Q), 5, (M, N, O, P, Q), Axis<1>);
+broadcast_to!(4, (N, O, P, Q), 5, (M, N, O, P, Q), Axis<0>);
+
 /// Internal implementation for broadcasting strides
 pub trait BroadcastStridesTo<S: Shape, Ax>: Shape + BroadcastShapeTo<S, Ax> {
     fn broadcast_strides(&self, strides: Self::Concrete) -> S::Concrete;
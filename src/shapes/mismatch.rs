@@ -0,0 +1,191 @@
+use super::{HasShape, Shape};
+
+use core::panic::Location;
+
+/// Error for when the runtime dimensions of the inputs to an operation don't match.
+///
+/// Only [super::Dim]s known at runtime (i.e. `usize`) can produce this error,
+/// since mismatched [super::Const] dimensions fail to compile.
+///
+/// In debug builds, this also stores where each of the mismatched tensors was created, and
+/// where the operation was called. Tensors record the caller of the device method that
+/// created them (e.g. [crate::tensor::ZerosTensor::zeros()]), or of the op that produced them.
+/// The locations are `None` in release builds, and for plain shapes.
+///
+/// Example message:
+/// ```text
+/// ShapeMismatch in `matmul` at src/main.rs:10:13: lhs axis 1 has size 3, but rhs axis 0 has size 4 (lhs shape: [2, 3], rhs shape: [4, 5]) (lhs created at src/main.rs:8:13, rhs created at src/main.rs:9:13)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMismatch {
+    /// The name of the operation
+    pub op: &'static str,
+    /// The offending axis of the left hand side
+    pub lhs_axis: usize,
+    /// The offending axis of the right hand side
+    pub rhs_axis: usize,
+    /// The full shape of the left hand side
+    pub lhs_shape: std::vec::Vec<usize>,
+    /// The full shape of the right hand side
+    pub rhs_shape: std::vec::Vec<usize>,
+    /// Where the left hand side was created
+    pub lhs_location: Option<&'static Location<'static>>,
+    /// Where the right hand side was created
+    pub rhs_location: Option<&'static Location<'static>>,
+    /// Where the operation was called from
+    pub location: Option<&'static Location<'static>>,
+}
+
+/// Something whose shape [ShapeMismatch] can check. Tensors also know where they were
+/// created, so the error can point at them.
+pub trait CheckShape: HasShape {
+    /// Where this was created, if known. Only tensors know this, and only in debug builds.
+    fn created_at(&self) -> Option<&'static Location<'static>> {
+        None
+    }
+}
+
+impl<S: Shape> CheckShape for S {}
+
+/// The caller of the current function in debug builds, and `None` in release builds.
+#[track_caller]
+pub(crate) fn debug_caller() -> Option<&'static Location<'static>> {
+    if cfg!(debug_assertions) {
+        Some(Location::caller())
+    } else {
+        None
+    }
+}
+
+impl ShapeMismatch {
+    /// Checks that `lhs` and `rhs` are the same shape.
+    #[track_caller]
+    pub fn check_same<L: CheckShape, R: CheckShape<Shape = L::Shape>>(
+        op: &'static str,
+        lhs: &L,
+        rhs: &R,
+    ) -> Result<(), Self> {
+        let (l, r) = (lhs.shape().concrete(), rhs.shape().concrete());
+        for i in 0..L::Shape::NUM_DIMS {
+            if l[i] != r[i] {
+                return Err(Self::new(op, (lhs, i), (rhs, i)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that axis `lhs.1` of `lhs.0` is the same size as axis `rhs.1` of `rhs.0`.
+    #[track_caller]
+    pub fn check_axes<L: CheckShape, R: CheckShape>(
+        op: &'static str,
+        lhs: (&L, usize),
+        rhs: (&R, usize),
+    ) -> Result<(), Self> {
+        if lhs.0.shape().concrete()[lhs.1] != rhs.0.shape().concrete()[rhs.1] {
+            Err(Self::new(op, lhs, rhs))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[track_caller]
+    fn new<L: CheckShape, R: CheckShape>(
+        op: &'static str,
+        lhs: (&L, usize),
+        rhs: (&R, usize),
+    ) -> Self {
+        Self {
+            op,
+            lhs_axis: lhs.1,
+            rhs_axis: rhs.1,
+            lhs_shape: lhs.0.shape().concrete().into(),
+            rhs_shape: rhs.0.shape().concrete().into(),
+            lhs_location: lhs.0.created_at(),
+            rhs_location: rhs.0.created_at(),
+            location: debug_caller(),
+        }
+    }
+}
+
+impl std::fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ShapeMismatch in `{}`", self.op)?;
+        if let Some(location) = self.location {
+            write!(f, " at {location}")?;
+        }
+        write!(
+            f,
+            ": lhs axis {} has size {}, but rhs axis {} has size {} (lhs shape: {:?}, rhs shape: {:?})",
+            self.lhs_axis,
+            self.lhs_shape[self.lhs_axis],
+            self.rhs_axis,
+            self.rhs_shape[self.rhs_axis],
+            self.lhs_shape,
+            self.rhs_shape
+        )?;
+        match (self.lhs_location, self.rhs_location) {
+            (Some(l), Some(r)) => write!(f, " (lhs created at {l}, rhs created at {r})"),
+            (Some(l), None) => write!(f, " (lhs created at {l})"),
+            (None, Some(r)) => write!(f, " (rhs created at {r})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ShapeMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gradients::NoneTape, shapes::Const, tensor::*, tests::*};
+
+    #[test]
+    fn test_check_same() {
+        let a: (usize, Const<3>) = (2, Const);
+        let b: (usize, Const<3>) = (4, Const);
+        assert!(ShapeMismatch::check_same("add", &a, &a).is_ok());
+        let err = ShapeMismatch::check_same("add", &a, &b).unwrap_err();
+        assert_eq!(err.op, "add");
+        assert_eq!((err.lhs_axis, err.rhs_axis), (0, 0));
+        assert_eq!(err.lhs_shape, [2, 3]);
+        assert_eq!(err.rhs_shape, [4, 3]);
+        assert_eq!((err.lhs_location, err.rhs_location), (None, None));
+        assert_eq!(err.location.is_some(), cfg!(debug_assertions));
+    }
+
+    #[test]
+    fn test_tensor_locations() {
+        let dev: TestDevice = Default::default();
+        let (a, a_line): (Tensor<(usize,), f32, _>, _) = (dev.zeros_like(&(2,)), line!());
+        let (b, b_line): (Tensor<(usize,), f32, _>, _) = (dev.ones_like(&(3,)), line!());
+        let err = ShapeMismatch::check_same("add", &a, &b).unwrap_err();
+        if cfg!(debug_assertions) {
+            let (l, r) = (err.lhs_location.unwrap(), err.rhs_location.unwrap());
+            assert_eq!((l.file(), l.line()), (file!(), a_line));
+            assert_eq!((r.file(), r.line()), (file!(), b_line));
+            let msg = std::format!("{err}");
+            assert!(msg.contains(&std::format!("lhs created at {l}")), "{msg}");
+        } else {
+            assert_eq!((err.lhs_location, err.rhs_location), (None, None));
+        }
+
+        // the location is kept when the tape changes
+        let a = a.traced().retaped::<NoneTape>();
+        assert_eq!(a.created_at(), err.lhs_location);
+    }
+
+    #[test]
+    fn test_display() {
+        let err =
+            ShapeMismatch::check_axes("matmul", (&(2usize, 3usize), 1), (&(4usize, 5usize), 0))
+                .unwrap_err();
+        let msg = std::format!("{err}");
+        assert!(msg.starts_with("ShapeMismatch in `matmul`"));
+        #[cfg(debug_assertions)]
+        assert!(msg.contains(file!()));
+        assert!(msg.ends_with(
+            "lhs axis 1 has size 3, but rhs axis 0 has size 4 (lhs shape: [2, 3], rhs shape: [4, 5])"
+        ));
+    }
+}
@@ -24,6 +24,23 @@ pub trait RemoveDimTo<Dst: Shape, Idx: Shape>: Shape {
     }
 }
 
+/// Marker for shapes that can have a new dimension added in front of them,
+/// e.g. `(M, N)` becomes `(New, M, N)`.
+pub trait AddDim<New: Dim>: Shape {
+    type Larger: Shape;
+
+    #[inline]
+    fn add_dim(&self, new: New) -> Self::Larger {
+        let src_dims = self.concrete();
+        let mut dst_dims: <Self::Larger as Shape>::Concrete = Default::default();
+        dst_dims[0] = new.size();
+        for i in 0..Self::NUM_DIMS {
+            dst_dims[i + 1] = src_dims[i];
+        }
+        Self::Larger::from_concrete(&dst_dims).unwrap()
+    }
+}
+
 /// Marker for shapes that can be indexed and have a dimension replaced with a new one
 pub trait ReplaceDimTo<Dst: Shape, Idx: Shape>: Shape {
     type Ax: Axes<Array = [isize; 1]>;
@@ -60,6 +77,74 @@ pub trait ReplaceDimTo<Dst: Shape, Idx: Shape>: Shape {
     }
 }
 
+/// Marker for shapes that can have the dimension of axis `Ax` replaced with `New`,
+/// e.g. `(M, N, O)` with `Axis<1>` becomes `(M, New, O)`.
+pub trait ReplaceAxis<Ax: Axes<Array = [isize; 1]>, New: Dim>: Shape {
+    type Output: Shape;
+
+    #[inline]
+    fn replace_axis(&self, new: New) -> Self::Output {
+        let ax = Ax::as_array()[0] as usize;
+        let src_dims = self.concrete();
+        let mut dst_dims: <Self::Output as Shape>::Concrete = Default::default();
+        for i in 0..Self::NUM_DIMS {
+            dst_dims[i] = src_dims[i];
+        }
+        dst_dims[ax] = new.size();
+        Self::Output::from_concrete(&dst_dims).unwrap()
+    }
+}
+
+/// Marker for shapes that can be concatenated with `Rhs` along `Ax`, which requires every other
+/// dimension to be the same. `New` is the dimension of `Ax` in the result.
+pub trait ConcatShape<Rhs: Shape, Ax: Axes<Array = [isize; 1]>, New: Dim>:
+    ReplaceAxis<Ax, New>
+{
+}
+
+impl<Lhs, Rhs, Ax, New> ConcatShape<Rhs, Ax, New> for Lhs
+where
+    Lhs: ReplaceAxis<Ax, New>,
+    Rhs: ReplaceAxis<Ax, New, Output = Lhs::Output>,
+    Ax: Axes<Array = [isize; 1]>,
+    New: Dim,
+{
+}
+
+macro_rules! replace_axis {
+    (($($DimVars:tt),*), $Ax:literal, $Dst:ty) => {
+impl<$($DimVars: Dim, )* New: Dim> ReplaceAxis<Axis<$Ax>, New> for ($($DimVars, )*) {
+    type Output = $Dst;
+}
+    };
+}
+
+replace_axis!((D1), 0, (New,));
+replace_axis!((D1, D2), 0, (New, D2));
+replace_axis!((D1, D2), 1, (D1, New));
+replace_axis!((D1, D2, D3), 0, (New, D2, D3));
+replace_axis!((D1, D2, D3), 1, (D1, New, D3));
+replace_axis!((D1, D2, D3), 2, (D1, D2, New));
+replace_axis!((D1, D2, D3, D4), 0, (New, D2, D3, D4));
+replace_axis!((D1, D2, D3, D4), 1, (D1, New, D3, D4));
+replace_axis!((D1, D2, D3, D4), 2, (D1, D2, New, D4));
+replace_axis!((D1, D2, D3, D4), 3, (D1, D2, D3, New));
+
+macro_rules! add_dim {
+    (($($DimVars:tt),*)) => {
+impl<$($DimVars: Dim, )* New: Dim> AddDim<New> for ($($DimVars, )*) {
+    type Larger = (New, $($DimVars, )*);
+}
+    };
+}
+
+add_dim!(());
+add_dim!((D1));
+add_dim!((D1, D2));
+add_dim!((D1, D2, D3));
+add_dim!((D1, D2, D3, D4));
+add_dim!((D1, D2, D3, D4, D5));
+
 macro_rules! replace {
     (($($DimVars:tt),*), $Ax:ty, $Dst:ty, $Idx:ty) => {
 impl<$($DimVars: Dim, )* New: Dim> ReplaceDimTo<$Dst, $Idx> for ($($DimVars, )*) {
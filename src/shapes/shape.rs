@@ -18,6 +18,34 @@ impl Unit for usize {
 impl Unit for bool {
     const ONE: Self = true;
 }
+impl Unit for u8 {
+    const ONE: Self = 1;
+}
+
+/// A [Unit] with a fixed bit pattern, used for bit exact comparisons & hashing.
+pub trait ToBits: Unit {
+    fn to_bits(self) -> u64;
+}
+impl ToBits for f32 {
+    fn to_bits(self) -> u64 {
+        f32::to_bits(self) as u64
+    }
+}
+impl ToBits for f64 {
+    fn to_bits(self) -> u64 {
+        f64::to_bits(self)
+    }
+}
+impl ToBits for usize {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+}
+impl ToBits for bool {
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+}
 
 /// Represents something that has a [Unit].
 pub trait HasUnitType {
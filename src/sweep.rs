@@ -0,0 +1,406 @@
+//! Hyperparameter sweeps: runs a short training for each combination of hyperparameters in
+//! a search space, and reports which combination had the best metric.
+//!
+//! A [Sweep] is a list of named [Param]s, searched with a [SearchStrategy]. Every [Trial]
+//! has its own seed, derived from the seed of the sweep and the index of the trial, so
+//! seeding the device with [Trial::seed] makes any trial reproducible on its own with
+//! [Sweep::trial()].
+//!
+//! ```rust
+//! # use dfdx::{prelude::*, optim::*, sweep::*};
+//! let sweep = Sweep::random(8, 0)
+//!     .with("lr", Param::LogUniform { low: 1e-3, high: 1e-1 })
+//!     .with("momentum", Param::Values(vec![0.0, 0.9]));
+//!
+//! let report = sweep
+//!     .run(|trial| {
+//!         let dev = Cpu::seed_from_u64(trial.seed);
+//!         let mut model: Linear<2, 1> = BuildModule::build(&dev);
+//!         let mut opt = Sgd::new(&model, SgdConfig {
+//!             lr: trial.get("lr") as f32,
+//!             momentum: Some(Momentum::Classic(trial.get("momentum") as f32)),
+//!             weight_decay: None,
+//!         });
+//!         let x: Tensor<Rank2<16, 2>, f32, _> = dev.sample_normal();
+//!         let y = dev.zeros::<Rank2<16, 1>>();
+//!         let mut loss = 0.0;
+//!         for _ in 0..10 {
+//!             let l = mse_loss(model.forward(x.trace()), y.clone(), ReduceMean);
+//!             loss = l.array();
+//!             opt.update(&mut model, l.backward())?;
+//!         }
+//!         Ok::<_, OptimizerUpdateError<Cpu>>(loss as f64)
+//!     })
+//!     .unwrap();
+//!
+//! let (best, loss) = report.best(Goal::Minimize).unwrap();
+//! println!("best trial {best} had loss {loss}");
+//! ```
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::vec::Vec;
+
+/// The values a hyperparameter of a [Sweep] can take.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Param {
+    /// One of a list of values. The only kind of parameter [SearchStrategy::Grid] can search.
+    Values(Vec<f64>),
+
+    /// Uniformly distributed in `[low, high)`.
+    Uniform { low: f64, high: f64 },
+
+    /// Uniformly distributed in `[ln(low), ln(high))` after taking the log, so every order of
+    /// magnitude is equally likely. Usually used for learning rates and weight decays.
+    LogUniform { low: f64, high: f64 },
+}
+
+impl Param {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match self {
+            Self::Values(values) => values[rng.gen_range(0..values.len())],
+            &Self::Uniform { low, high } => low + (high - low) * rng.gen::<f64>(),
+            &Self::LogUniform { low, high } => {
+                (low.ln() + (high.ln() - low.ln()) * rng.gen::<f64>()).exp()
+            }
+        }
+    }
+}
+
+/// How a [Sweep] chooses the hyperparameters of its trials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStrategy {
+    /// One trial for every combination of values, where the last parameter changes
+    /// fastest. Every parameter must be a [Param::Values].
+    Grid,
+
+    /// `num_trials` trials, each with every parameter sampled independently.
+    Random { num_trials: usize },
+}
+
+/// Whether a higher or lower metric is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Minimize,
+    Maximize,
+}
+
+/// One combination of hyperparameters in a [Sweep].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trial {
+    /// The index of the trial in the sweep.
+    pub index: usize,
+
+    /// The seed to use for anything random in this trial, like initializing the model.
+    pub seed: u64,
+
+    /// The value of each parameter, in the order they were added to the sweep.
+    pub values: Vec<(&'static str, f64)>,
+}
+
+impl Trial {
+    /// The value of the parameter `name`.
+    ///
+    /// **Panics** if the sweep has no parameter called `name`.
+    pub fn get(&self, name: &str) -> f64 {
+        match self.values.iter().find(|(n, _)| *n == name) {
+            Some(&(_, value)) => value,
+            None => panic!("trial has no parameter called `{name}`"),
+        }
+    }
+}
+
+impl core::fmt::Display for Trial {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "#{}", self.index)?;
+        for (name, value) in self.values.iter() {
+            write!(f, " {name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A search space of named hyperparameters, and how to search it. See the
+/// [module documentation](self) for an example.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    pub params: Vec<(&'static str, Param)>,
+    pub strategy: SearchStrategy,
+    /// The seed that the seeds of all the trials are derived from.
+    pub seed: u64,
+}
+
+impl Sweep {
+    /// A [SearchStrategy::Grid] sweep with no parameters.
+    pub fn grid(seed: u64) -> Self {
+        Self {
+            params: Vec::new(),
+            strategy: SearchStrategy::Grid,
+            seed,
+        }
+    }
+
+    /// A [SearchStrategy::Random] sweep of `num_trials` trials with no parameters.
+    pub fn random(num_trials: usize, seed: u64) -> Self {
+        Self {
+            params: Vec::new(),
+            strategy: SearchStrategy::Random { num_trials },
+            seed,
+        }
+    }
+
+    /// Adds the parameter `name` to the search space.
+    ///
+    /// **Panics** if there already is a parameter called `name`, if `param` has no values,
+    /// or if [SearchStrategy::Grid] is used with a parameter that isn't a [Param::Values].
+    pub fn with(mut self, name: &'static str, param: Param) -> Self {
+        assert!(
+            self.params.iter().all(|(n, _)| *n != name),
+            "the sweep already has a parameter called `{name}`"
+        );
+        match &param {
+            Param::Values(values) => assert!(!values.is_empty(), "`{name}` has no values"),
+            Param::Uniform { .. } | Param::LogUniform { .. } => assert!(
+                self.strategy != SearchStrategy::Grid,
+                "grid search needs a list of values for `{name}`"
+            ),
+        }
+        self.params.push((name, param));
+        self
+    }
+
+    /// The number of trials in the sweep.
+    pub fn num_trials(&self) -> usize {
+        match self.strategy {
+            SearchStrategy::Grid => self
+                .params
+                .iter()
+                .map(|(name, p)| grid_values(name, p).len())
+                .product(),
+            SearchStrategy::Random { num_trials } => num_trials,
+        }
+    }
+
+    /// The trial at `index`, which only depends on the parameters, strategy and seed of the
+    /// sweep, so a trial can be rerun without running the ones before it.
+    ///
+    /// **Panics** if `index >= self.num_trials()`.
+    pub fn trial(&self, index: usize) -> Trial {
+        assert!(index < self.num_trials());
+        let seed = trial_seed(self.seed, index);
+        let values = match self.strategy {
+            SearchStrategy::Grid => {
+                let mut rem = index;
+                let mut values: Vec<_> = self
+                    .params
+                    .iter()
+                    .rev()
+                    .map(|(name, p)| {
+                        let values = grid_values(name, p);
+                        let value = values[rem % values.len()];
+                        rem /= values.len();
+                        (*name, value)
+                    })
+                    .collect();
+                values.reverse();
+                values
+            }
+            SearchStrategy::Random { .. } => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                self.params
+                    .iter()
+                    .map(|(name, p)| (*name, p.sample(&mut rng)))
+                    .collect()
+            }
+        };
+        Trial {
+            index,
+            seed,
+            values,
+        }
+    }
+
+    /// All the trials of the sweep, in order.
+    pub fn trials(&self) -> Vec<Trial> {
+        (0..self.num_trials()).map(|i| self.trial(i)).collect()
+    }
+
+    /// Calls `train` with each trial in order, which returns the metric of the trial. Stops
+    /// at the first error.
+    pub fn run<Err, F>(&self, mut train: F) -> Result<SweepReport, Err>
+    where
+        F: FnMut(&Trial) -> Result<f64, Err>,
+    {
+        let mut results = Vec::with_capacity(self.num_trials());
+        for trial in self.trials() {
+            let metric = train(&trial)?;
+            results.push((trial, metric));
+        }
+        Ok(SweepReport { results })
+    }
+}
+
+fn grid_values<'a>(name: &str, param: &'a Param) -> &'a [f64] {
+    match param {
+        Param::Values(values) => values,
+        _ => panic!("grid search needs a list of values for `{name}`"),
+    }
+}
+
+/// [splitmix64](https://prng.di.unimi.it/splitmix64.c), so that the seeds of consecutive
+/// trials are unrelated.
+fn trial_seed(seed: u64, index: usize) -> u64 {
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// The metric of every trial of a [Sweep::run()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepReport {
+    /// Each trial and its metric, in the order they ran.
+    pub results: Vec<(Trial, f64)>,
+}
+
+impl SweepReport {
+    /// The trials sorted from best to worst metric. NaN metrics are the worst.
+    pub fn ranked(&self, goal: Goal) -> Vec<&(Trial, f64)> {
+        let key = |m: f64| match (m.is_nan(), goal) {
+            (true, _) => f64::INFINITY,
+            (false, Goal::Minimize) => m,
+            (false, Goal::Maximize) => -m,
+        };
+        let mut ranked: Vec<_> = self.results.iter().collect();
+        ranked.sort_by(|(_, a), (_, b)| key(*a).total_cmp(&key(*b)));
+        ranked
+    }
+
+    /// The trial with the best metric, and its metric. `None` if no trials ran.
+    pub fn best(&self, goal: Goal) -> Option<(&Trial, f64)> {
+        self.ranked(goal).first().map(|(trial, m)| (trial, *m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{optim::*, shapes::*, tensor::*, tensor_ops::*, tests::TestDevice};
+    use std::{format, vec};
+
+    #[test]
+    fn test_grid_sweep_order() {
+        let sweep = Sweep::grid(0)
+            .with("a", Param::Values(vec![1.0, 2.0]))
+            .with("b", Param::Values(vec![10.0, 20.0, 30.0]));
+        assert_eq!(sweep.num_trials(), 6);
+        let values: Vec<_> = sweep
+            .trials()
+            .into_iter()
+            .map(|t| (t.get("a"), t.get("b")))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                (1.0, 10.0),
+                (1.0, 20.0),
+                (1.0, 30.0),
+                (2.0, 10.0),
+                (2.0, 20.0),
+                (2.0, 30.0)
+            ]
+        );
+        assert_eq!(format!("{}", sweep.trial(4)), "#4 a=2 b=20");
+    }
+
+    #[test]
+    fn test_random_sweep_is_deterministic() {
+        let sweep = Sweep::random(20, 7)
+            .with(
+                "lr",
+                Param::LogUniform {
+                    low: 1e-4,
+                    high: 1e-1,
+                },
+            )
+            .with(
+                "dropout",
+                Param::Uniform {
+                    low: 0.0,
+                    high: 0.5,
+                },
+            )
+            .with("layers", Param::Values(vec![1.0, 2.0, 3.0]));
+        let trials = sweep.trials();
+        assert_eq!(trials, sweep.clone().trials());
+        assert_eq!(trials[13], sweep.trial(13));
+        for t in trials.iter() {
+            assert!((1e-4..1e-1).contains(&t.get("lr")));
+            assert!((0.0..0.5).contains(&t.get("dropout")));
+            assert!([1.0, 2.0, 3.0].contains(&t.get("layers")));
+        }
+        assert_ne!(trials[0].seed, trials[1].seed);
+        assert_ne!(trials[0].values, trials[1].values);
+
+        let other = Sweep { seed: 8, ..sweep }.trials();
+        assert_ne!(trials[0].values, other[0].values);
+    }
+
+    #[test]
+    #[should_panic = "grid search needs a list of values for `lr`"]
+    fn test_grid_sweep_continuous_param() {
+        let _ = Sweep::grid(0).with(
+            "lr",
+            Param::Uniform {
+                low: 0.0,
+                high: 1.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_sweep_report() {
+        let sweep = Sweep::grid(0).with("x", Param::Values(vec![-1.0, 0.5, f64::NAN, 2.0]));
+        let report = sweep
+            .run(|t| Ok::<_, ()>((t.get("x") - 0.4).abs()))
+            .unwrap();
+        assert_eq!(report.best(Goal::Minimize).unwrap().0.get("x"), 0.5);
+        assert_eq!(report.best(Goal::Maximize).unwrap().0.get("x"), 2.0);
+        let ranked: Vec<_> = report
+            .ranked(Goal::Minimize)
+            .iter()
+            .map(|r| r.0.index)
+            .collect();
+        assert_eq!(ranked, [1, 0, 3, 2]);
+        assert!(sweep
+            .run(|t| if t.index == 1 { Err(()) } else { Ok(0.0) })
+            .is_err());
+    }
+
+    #[test]
+    fn test_sweep_sgd_lr() {
+        let sweep = Sweep::grid(0).with("lr", Param::Values(vec![1e-3, 1e-1, 1.5]));
+        let report = sweep
+            .run(|trial| {
+                let dev = TestDevice::seed_from_u64(trial.seed);
+                let mut w: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+                let mut opt = Sgd::new(
+                    &w,
+                    SgdConfig {
+                        lr: trial.get("lr") as f32,
+                        momentum: None,
+                        weight_decay: None,
+                    },
+                );
+                let mut loss = 0.0;
+                for _ in 0..20 {
+                    let l = w.trace().square().sum();
+                    loss = l.array();
+                    opt.update(&mut w, l.backward())?;
+                }
+                Ok::<_, OptimizerUpdateError<TestDevice>>(loss as f64)
+            })
+            .unwrap();
+        // `1e-3` is too slow, and `1.5` diverges
+        assert_eq!(report.best(Goal::Minimize).unwrap().0.get("lr"), 1e-1);
+    }
+}
@@ -0,0 +1,109 @@
+use super::{storage_traits::AsVec, DeviceStorage, Tensor};
+use crate::shapes::{HasShape, Shape, ToBits};
+
+/// 64 bit FNV-1a, which is stable across platforms & runs.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn write_u64(&mut self, x: u64) {
+        for byte in x.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl<S: Shape, E: ToBits, D: DeviceStorage, T> Tensor<S, E, D, T>
+where
+    Self: AsVec<Unit = E>,
+{
+    /// Whether `self` and `other` have the same shape and bit identical values.
+    ///
+    /// Unlike `==` on floats, `NaN`s with the same bit pattern are equal, and `0.0` is
+    /// not equal to `-0.0`.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a = dev.tensor([1.0, f32::NAN]);
+    /// assert!(a.bytewise_eq(&a.clone()));
+    /// assert!(!a.bytewise_eq(&dev.tensor([1.0, -f32::NAN])));
+    /// ```
+    pub fn bytewise_eq<T2>(&self, other: &Tensor<S, E, D, T2>) -> bool
+    where
+        Tensor<S, E, D, T2>: AsVec<Unit = E>,
+    {
+        if self.shape().concrete() != other.shape().concrete() {
+            return false;
+        }
+        let a = self.as_vec();
+        let b = other.as_vec();
+        a.len() == b.len()
+            && a.into_iter()
+                .zip(b)
+                .all(|(a, b)| a.to_bits() == b.to_bits())
+    }
+
+    /// A hash of the shape & bit patterns of the values of this tensor, suitable for
+    /// deduplication & caching. Tensors that are [Tensor::bytewise_eq] have the same hash.
+    ///
+    /// The hash is stable across runs and platforms, but not guaranteed to be stable
+    /// across versions of this crate.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a = dev.tensor([1.0, 2.0, 3.0]);
+    /// let b = dev.tensor([1.0, 2.0, 3.0]);
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// ```
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        let dims = self.shape().concrete();
+        hasher.write_u64(S::NUM_DIMS as u64);
+        for i in 0..S::NUM_DIMS {
+            hasher.write_u64(dims[i] as u64);
+        }
+        for x in self.as_vec() {
+            hasher.write_u64(x.to_bits());
+        }
+        hasher.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tests::TestDevice};
+
+    #[test]
+    fn test_bytewise_eq() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, f32::NAN]]);
+        let b = dev.tensor([[1.0, 2.0], [3.0, f32::NAN]]);
+        assert!(a.bytewise_eq(&b));
+        assert!(!a.bytewise_eq(&dev.tensor([[1.0, 2.0], [3.0, 4.0]])));
+        assert!(!dev.tensor([0.0f32]).bytewise_eq(&dev.tensor([-0.0])));
+        let c: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(1, 4));
+        let d: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 2));
+        assert!(!c.bytewise_eq(&d));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<8, 16>, f32, _> = dev.sample_normal();
+        let b = dev.tensor(a.array());
+        assert_eq!(a.content_hash(), b.content_hash());
+        let mut c = a.array();
+        c[3][4] += 1e-6;
+        assert_ne!(a.content_hash(), dev.tensor(c).content_hash());
+
+        let e: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(1, 4));
+        let f: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 2));
+        assert_ne!(e.content_hash(), f.content_hash());
+    }
+}
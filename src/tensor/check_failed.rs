@@ -0,0 +1,112 @@
+/// A property that every element of a tensor is expected to have.
+///
+/// Checked by ops when checked mode is enabled on their device (see [super::Cpu::set_checked()]),
+/// and by [crate::tensor_ops::assert_all()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Invariant {
+    /// Not `NaN` or infinite.
+    Finite,
+    /// Greater than or equal to zero, and not `NaN`.
+    NonNegative,
+    /// In `[0, 1]`, and not `NaN`.
+    Probability,
+}
+
+impl Invariant {
+    /// Whether `x` has this property.
+    pub fn holds(&self, x: f32) -> bool {
+        match self {
+            Self::Finite => x.is_finite(),
+            Self::NonNegative => x >= 0.0,
+            Self::Probability => (0.0..=1.0).contains(&x),
+        }
+    }
+}
+
+impl std::fmt::Display for Invariant {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::Finite => "finite",
+            Self::NonNegative => "non-negative",
+            Self::Probability => "in [0, 1]",
+        })
+    }
+}
+
+/// Error for when a tensor has elements that break an [Invariant], for example
+/// a `NaN` in the input of softmax.
+///
+/// In debug builds, this also stores the location of the call to the operation.
+///
+/// Example message:
+/// ```text
+/// CheckFailed in `log_softmax` at src/main.rs:10:13: input has elements that are not finite
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckFailed {
+    /// The name of the operation
+    pub op: &'static str,
+    /// Which tensor of the operation was checked, e.g. `"input"` or `"output"`
+    pub tensor: &'static str,
+    /// The property that some elements of the tensor don't have
+    pub invariant: Invariant,
+    /// Where the operation was called from
+    #[cfg(debug_assertions)]
+    pub location: &'static core::panic::Location<'static>,
+}
+
+impl CheckFailed {
+    #[track_caller]
+    pub(crate) fn new(op: &'static str, tensor: &'static str, invariant: Invariant) -> Self {
+        Self {
+            op,
+            tensor,
+            invariant,
+            #[cfg(debug_assertions)]
+            location: core::panic::Location::caller(),
+        }
+    }
+}
+
+impl std::fmt::Display for CheckFailed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "CheckFailed in `{}`", self.op)?;
+        #[cfg(debug_assertions)]
+        write!(f, " at {}", self.location)?;
+        write!(
+            f,
+            ": {} has elements that are not {}",
+            self.tensor, self.invariant
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CheckFailed {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invariants() {
+        assert!(Invariant::Finite.holds(-1e30));
+        assert!(!Invariant::Finite.holds(f32::NAN));
+        assert!(!Invariant::Finite.holds(f32::NEG_INFINITY));
+        assert!(Invariant::NonNegative.holds(0.0));
+        assert!(Invariant::NonNegative.holds(f32::INFINITY));
+        assert!(!Invariant::NonNegative.holds(-1e-30));
+        assert!(!Invariant::NonNegative.holds(f32::NAN));
+        assert!(Invariant::Probability.holds(1.0));
+        assert!(!Invariant::Probability.holds(1.0 + 1e-6));
+        assert!(!Invariant::Probability.holds(f32::NAN));
+    }
+
+    #[test]
+    fn test_display() {
+        let err = CheckFailed::new("softmax", "output", Invariant::Probability);
+        let msg = std::format!("{err}");
+        assert!(msg.starts_with("CheckFailed in `softmax`"));
+        assert!(msg.ends_with("output has elements that are not in [0, 1]"));
+    }
+}
@@ -71,6 +71,43 @@ impl<E: Unit> OnesTensor<E> for Cpu {
     }
 }
 
+impl Cpu {
+    #[track_caller]
+    fn try_triangular_like<S: HasShape, E: Unit>(
+        &self,
+        src: &S,
+        upper: bool,
+    ) -> Result<Tensor<S::Shape, E, Self>, CpuError> {
+        let n = S::Shape::NUM_DIMS;
+        assert!(n >= 2, "triangular tensors need at least 2 dimensions");
+        let mut storage = StridedArray::try_new_with(*src.shape(), Default::default())?;
+        let mut iter = storage.iter_mut_with_index();
+        while let Some((x, i)) = iter.next() {
+            let (row, col) = (i[n - 2], i[n - 1]);
+            if (upper && col >= row) || (!upper && col <= row) {
+                *x = E::ONE;
+            }
+        }
+        Ok(self.upgrade(storage))
+    }
+}
+
+impl<E: Unit> TriangleTensor<E> for Cpu {
+    fn try_lower_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err> {
+        self.try_triangular_like(src, false)
+    }
+
+    fn try_upper_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err> {
+        self.try_triangular_like(src, true)
+    }
+}
+
 impl<E: Unit> OneFillStorage<E> for Cpu {
     fn try_fill_with_ones<S: Shape>(
         &self,
@@ -194,6 +231,39 @@ impl<E: Unit, const M: usize, const N: usize, const O: usize, const P: usize>
     }
 }
 
+impl<E: Unit> TensorFromVec<E> for Cpu {
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        data: Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err> {
+        ShapeMismatch::check_axes(
+            "tensor_from_vec",
+            (&(data.len(),), 0),
+            (&(shape.num_elements(),), 0),
+        )?;
+        Ok(self.upgrade(StridedArray {
+            data: Arc::new(data),
+            shape,
+            strides: shape.strides(),
+        }))
+    }
+
+    fn tensor_into_vec<S: Shape, T>(t: Tensor<S, E, Self, T>) -> Vec<E> {
+        let storage = t.storage;
+        if storage.strides == storage.shape.strides()
+            && storage.data.len() == storage.shape.num_elements()
+        {
+            match Arc::try_unwrap(storage.data) {
+                Ok(data) => data,
+                Err(data) => data.as_ref().clone(),
+            }
+        } else {
+            storage.as_vec()
+        }
+    }
+}
+
 impl<S: Shape, E: Unit> AsVec for StridedArray<S, E> {
     fn as_vec(&self) -> Vec<E> {
         let mut out = Vec::with_capacity(self.shape.num_elements());
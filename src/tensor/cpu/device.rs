@@ -1,8 +1,12 @@
-use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, Unit};
+use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, ShapeMismatch, Unit};
 use crate::tensor::storage_traits::*;
+use crate::tensor::{CheckFailed, DeviceMismatch, KernelOverrides};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::{
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
     vec::Vec,
 };
 
@@ -14,12 +18,20 @@ use std::{
 #[derive(Clone, Debug)]
 pub struct Cpu {
     pub(crate) rng: Arc<Mutex<StdRng>>,
+    pub(crate) overrides: KernelOverrides,
+    pub(crate) backward_threads: Arc<AtomicUsize>,
+    pub(crate) checked: Arc<AtomicBool>,
+    pub(crate) compensated: Arc<AtomicBool>,
 }
 
 impl Default for Cpu {
     fn default() -> Self {
         Self {
             rng: Arc::new(Mutex::new(StdRng::seed_from_u64(0))),
+            overrides: Default::default(),
+            backward_threads: Arc::new(AtomicUsize::new(1)),
+            checked: Arc::new(AtomicBool::new(false)),
+            compensated: Arc::new(AtomicBool::new(false)),
         }
     }
 }
@@ -29,8 +41,83 @@ impl Cpu {
     pub fn seed_from_u64(seed: u64) -> Self {
         Self {
             rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+            overrides: Default::default(),
+            backward_threads: Arc::new(AtomicUsize::new(1)),
+            checked: Arc::new(AtomicBool::new(false)),
+            compensated: Arc::new(AtomicBool::new(false)),
         }
     }
+
+    /// Same as [Cpu::seed_from_u64], which never fails. This matches
+    /// `Cuda::try_seed_from_u64`, so code using [crate::tensor::AutoDevice] can handle
+    /// a missing GPU the same way with every set of features.
+    pub fn try_seed_from_u64(seed: u64) -> Result<Self, CpuError> {
+        Ok(Self::seed_from_u64(seed))
+    }
+
+    /// Does nothing, since all [Cpu] kernels always accumulate in a deterministic order.
+    /// Provided so code can be generic over devices, see `Cuda::set_deterministic()`.
+    pub fn set_deterministic(&self, deterministic: bool) {
+        let _ = deterministic;
+    }
+
+    /// Always `true`. See [Cpu::set_deterministic()].
+    pub fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    /// Runs independent parts of the backward pass on up to `num_threads` threads.
+    /// For example, the two branches of a residual, or the heads of an attention layer.
+    ///
+    /// Defaults to 1, which runs the backward pass sequentially. Gradients are accumulated
+    /// in the same order no matter how many threads are used, so the results are always the same.
+    /// Only worth it when the independent parts are big enough to make up for starting threads.
+    ///
+    /// Applies to every backward pass that starts from a tensor on this device (or a clone of it).
+    pub fn set_backward_threads(&self, num_threads: usize) {
+        self.backward_threads
+            .store(num_threads.max(1), Ordering::Relaxed);
+    }
+
+    /// Makes ops validate the values of their inputs & outputs, e.g. that the input of softmax
+    /// is finite, or that the targets of `bce_with_logits` are in `[0, 1]`. Ops return
+    /// [CpuError::CheckFailed] with the name of the op when a check fails, instead of silently
+    /// propagating `NaN`s.
+    ///
+    /// Each check is a reduction of a whole tensor to a single boolean, so this slows down
+    /// the checked ops, but can be left on to catch bad data in production.
+    ///
+    /// This applies to every clone of this device, including the ones stored in tensors.
+    /// Defaults to `false`.
+    pub fn set_checked(&self, checked: bool) {
+        self.checked.store(checked, Ordering::Relaxed);
+    }
+
+    /// Whether [Cpu::set_checked()] is enabled.
+    pub fn is_checked(&self) -> bool {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    /// Makes long accumulations use [Kahan summation](https://en.wikipedia.org/wiki/Kahan_summation_algorithm),
+    /// which carries the rounding error of each addition into the next one, so the error
+    /// doesn't grow with the number of elements. This applies to:
+    /// 1. Sum reductions, and everything built on them like `mean`, see
+    ///    [crate::tensor_ops::SumTo::sum_compensated()].
+    /// 2. The parameter updates of optimizers, which keep the rounding error of each
+    ///    parameter in an extra buffer, so many small updates to a large parameter aren't lost.
+    ///
+    /// This is slower, and each optimizer uses the memory of another copy of the parameters.
+    ///
+    /// This applies to every clone of this device, including the ones stored in tensors.
+    /// Defaults to `false`.
+    pub fn set_compensated(&self, compensated: bool) {
+        self.compensated.store(compensated, Ordering::Relaxed);
+    }
+
+    /// The kernels that replace the built in ones of this device, see [KernelOverrides].
+    pub fn overrides(&self) -> &KernelOverrides {
+        &self.overrides
+    }
 }
 
 /// The storage for the cpu device
@@ -41,16 +128,43 @@ pub struct StridedArray<S: Shape, E> {
     pub(crate) strides: S::Concrete,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum CpuError {
     /// Device is out of memory
     OutOfMemory,
+    /// The runtime dimensions of the inputs to an operation don't match
+    ShapeMismatch(ShapeMismatch),
+    /// The inputs to an operation are stored on different devices
+    DeviceMismatch(DeviceMismatch),
+    /// A tensor has values that an operation doesn't allow, see [Cpu::set_checked()]
+    CheckFailed(CheckFailed),
+}
+
+impl From<ShapeMismatch> for CpuError {
+    fn from(value: ShapeMismatch) -> Self {
+        Self::ShapeMismatch(value)
+    }
+}
+
+impl From<DeviceMismatch> for CpuError {
+    fn from(value: DeviceMismatch) -> Self {
+        Self::DeviceMismatch(value)
+    }
+}
+
+impl From<CheckFailed> for CpuError {
+    fn from(value: CheckFailed) -> Self {
+        Self::CheckFailed(value)
+    }
 }
 
 impl std::fmt::Display for CpuError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::OutOfMemory => f.write_str("CpuError::OutOfMemory"),
+            Self::ShapeMismatch(e) => write!(f, "CpuError::{e}"),
+            Self::DeviceMismatch(e) => write!(f, "CpuError::{e}"),
+            Self::CheckFailed(e) => write!(f, "CpuError::{e}"),
         }
     }
 }
@@ -66,6 +180,12 @@ impl<S: Shape, E> HasShape for StridedArray<S, E> {
     }
 }
 
+impl<S: Shape, E> HasStrides for StridedArray<S, E> {
+    fn strides(&self) -> S::Concrete {
+        self.strides
+    }
+}
+
 impl<S: Shape, E: Unit> HasUnitType for StridedArray<S, E> {
     type Unit = E;
 }
@@ -88,7 +208,39 @@ impl DeviceStorage for Cpu {
         StridedArray::try_new_like(storage, Default::default())
     }
 
+    fn try_reuse_grad<S: Shape, E: Dtype>(
+        &self,
+        storage: &Self::Storage<S, E>,
+        grad: &mut Self::Storage<S, E>,
+    ) -> Result<bool, Self::Err> {
+        if grad.shape.concrete() != storage.shape.concrete()
+            || grad.strides != storage.strides
+            || grad.data.len() != storage.data.len()
+        {
+            return Ok(false);
+        }
+        self.try_fill_with_zeros(grad)?;
+        Ok(true)
+    }
+
     fn random_u64(&self) -> u64 {
         self.rng.lock().unwrap().gen()
     }
+
+    fn backward_threads(&self) -> usize {
+        self.backward_threads.load(Ordering::Relaxed)
+    }
+
+    fn compensated(&self) -> bool {
+        self.compensated.load(Ordering::Relaxed)
+    }
+
+    /// All [Cpu]s share the same memory, so this is always `true`.
+    fn same_device(&self, _: &Self) -> bool {
+        true
+    }
+
+    fn device_name(&self) -> std::string::String {
+        "Cpu".into()
+    }
 }
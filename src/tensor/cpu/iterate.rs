@@ -18,11 +18,14 @@ impl<S: Shape> NdIndex<S> {
             .zip(indices.into_iter())
             .map(|(a, b)| a * b)
             .sum();
+        let shape = shape.concrete();
+        // there is nothing to iterate over if any axis is empty
+        let next = shape.into_iter().all(|d| d > 0).then_some(i);
         Self {
             indices,
-            shape: shape.concrete(),
+            shape,
             strides,
-            next: Some(i),
+            next,
         }
     }
 }
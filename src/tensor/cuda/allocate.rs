@@ -33,6 +33,7 @@ impl Cuda {
             storage,
             tape: Default::default(),
             device: self.clone(),
+            location: t_cpu.location,
         })
     }
 }
@@ -108,6 +109,22 @@ where
     }
 }
 
+impl<E: Unit> TriangleTensor<E> for Cuda {
+    fn try_lower_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err> {
+        self.take_cpu_tensor(self.cpu.try_lower_triangular_like(src)?)
+    }
+
+    fn try_upper_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err> {
+        self.take_cpu_tensor(self.cpu.try_upper_triangular_like(src)?)
+    }
+}
+
 impl<E: Unit> CopySlice<E> for Cuda {
     fn copy_from<S: Shape, T>(dst: &mut Tensor<S, E, Self, T>, src: &[E]) {
         dst.device
@@ -123,9 +140,33 @@ impl<E: Unit> CopySlice<E> for Cuda {
     }
 }
 
+impl<E: Unit> TensorFromVec<E> for Cuda {
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        src: Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err> {
+        self.take_cpu_tensor(self.cpu.try_tensor_from_vec(src, shape)?)
+    }
+
+    fn tensor_into_vec<S: Shape, T>(t: Tensor<S, E, Self, T>) -> Vec<E> {
+        let host = StridedArray {
+            data: Arc::new(t.storage.data.clone_async().unwrap().try_into().unwrap()),
+            shape: t.storage.shape,
+            strides: t.storage.strides,
+        };
+        host.as_vec()
+    }
+}
+
 impl<S: Shape, E: Unit> AsVec for CudaArray<S, E> {
     fn as_vec(&self) -> Vec<E> {
-        self.data.clone_async().unwrap().try_into().unwrap()
+        let host = StridedArray {
+            data: Arc::new(self.data.clone_async().unwrap().try_into().unwrap()),
+            shape: self.shape,
+            strides: self.strides,
+        };
+        host.as_vec()
     }
 }
 
@@ -1,12 +1,16 @@
-use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, Unit};
-use crate::tensor::cpu::{Cpu, CpuError};
-use crate::tensor::storage_traits::{DeviceStorage, HasErr};
+use crate::shapes::{Dtype, HasDtype, HasShape, HasUnitType, Shape, ShapeMismatch, Unit};
+use crate::tensor::cpu::{Cpu, CpuError, StridedArray};
+use crate::tensor::storage_traits::{DeviceStorage, HasErr, HasStrides, ZeroFillStorage};
+use crate::tensor::{CheckFailed, DeviceMismatch};
 
 use cudarc::{
     cublas::{result::CublasError, CudaBlas},
     driver::{result::DriverError, BuildError, CudaDevice, CudaDeviceBuilder, CudaSlice},
 };
-use std::sync::Arc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
 
 #[derive(Debug)]
 pub enum CudaError {
@@ -22,6 +26,24 @@ impl From<CpuError> for CudaError {
     }
 }
 
+impl From<ShapeMismatch> for CudaError {
+    fn from(value: ShapeMismatch) -> Self {
+        Self::Cpu(value.into())
+    }
+}
+
+impl From<DeviceMismatch> for CudaError {
+    fn from(value: DeviceMismatch) -> Self {
+        Self::Cpu(value.into())
+    }
+}
+
+impl From<CheckFailed> for CudaError {
+    fn from(value: CheckFailed) -> Self {
+        Self::Cpu(value.into())
+    }
+}
+
 impl From<BuildError> for CudaError {
     fn from(value: BuildError) -> Self {
         Self::Build(value)
@@ -45,6 +67,10 @@ pub struct Cuda {
     pub(crate) cpu: Cpu,
     pub(crate) dev: Arc<CudaDevice>,
     pub(crate) blas: Arc<CudaBlas>,
+    pub(crate) ordinal: usize,
+    pub(crate) deterministic: Arc<AtomicBool>,
+    pub(crate) checked: Arc<AtomicBool>,
+    pub(crate) compensated: Arc<AtomicBool>,
 }
 
 impl Default for Cuda {
@@ -68,7 +94,83 @@ impl Cuda {
         let cpu = Cpu::seed_from_u64(seed);
         let dev = CudaDeviceBuilder::new(ordinal).build()?;
         let blas = Arc::new(CudaBlas::new(dev.clone())?);
-        Ok(Self { cpu, dev, blas })
+        Ok(Self {
+            cpu,
+            dev,
+            blas,
+            ordinal,
+            deterministic: Arc::new(AtomicBool::new(false)),
+            checked: Arc::new(AtomicBool::new(false)),
+            compensated: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    /// The index of the cuda device this is attached to.
+    pub fn ordinal(&self) -> usize {
+        self.ordinal
+    }
+
+    /// Forces kernels that accumulate in a nondeterministic order (i.e. with atomic adds,
+    /// like reductions and the backward pass of indexing ops) to accumulate in a fixed
+    /// order, so results are bitwise reproducible between runs. This is much slower,
+    /// since those kernels are run on the host.
+    ///
+    /// This applies to every clone of this device, including the ones stored in tensors.
+    /// Defaults to `false`.
+    pub fn set_deterministic(&self, deterministic: bool) {
+        self.deterministic.store(deterministic, Ordering::Relaxed);
+    }
+
+    /// Whether [Cuda::set_deterministic()] is enabled.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic.load(Ordering::Relaxed)
+    }
+
+    /// Makes ops validate the values of their inputs & outputs, see [Cpu::set_checked()].
+    /// Each check is a reduction on the device, so only a single boolean is copied
+    /// back to the host.
+    ///
+    /// This applies to every clone of this device, including the ones stored in tensors.
+    /// Defaults to `false`.
+    pub fn set_checked(&self, checked: bool) {
+        self.checked.store(checked, Ordering::Relaxed);
+    }
+
+    /// Whether [Cuda::set_checked()] is enabled.
+    pub fn is_checked(&self) -> bool {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    /// Makes sums & optimizer updates use Kahan summation, see [Cpu::set_compensated()].
+    /// These run on the host when enabled, so are much slower.
+    ///
+    /// This applies to every clone of this device, including the ones stored in tensors.
+    /// Defaults to `false`.
+    pub fn set_compensated(&self, compensated: bool) {
+        self.compensated.store(compensated, Ordering::Relaxed);
+    }
+
+    /// Copies `storage` to the host, for running a [Cpu] kernel instead of a cuda kernel.
+    pub(crate) fn storage_to_cpu<S: Shape, E: Unit>(
+        &self,
+        storage: &CudaArray<S, E>,
+    ) -> Result<StridedArray<S, E>, CudaError> {
+        Ok(StridedArray {
+            data: Arc::new(storage.data.clone_async()?.try_into()?),
+            shape: storage.shape,
+            strides: storage.strides,
+        })
+    }
+
+    /// Copies `src` back into `dst` after running a [Cpu] kernel. See [Cuda::storage_to_cpu()].
+    pub(crate) fn storage_from_cpu<S: Shape, E: Unit>(
+        &self,
+        dst: &mut CudaArray<S, E>,
+        src: &StridedArray<S, E>,
+    ) -> Result<(), CudaError> {
+        self.dev
+            .sync_copy_into(src.data.as_ref(), Arc::make_mut(&mut dst.data))?;
+        Ok(())
     }
 }
 
@@ -87,6 +189,12 @@ impl<S: Shape, E> HasShape for CudaArray<S, E> {
     }
 }
 
+impl<S: Shape, E> HasStrides for CudaArray<S, E> {
+    fn strides(&self) -> S::Concrete {
+        self.strides
+    }
+}
+
 impl<S: Shape, E: Unit> HasUnitType for CudaArray<S, E> {
     type Unit = E;
 }
@@ -121,7 +229,39 @@ impl DeviceStorage for Cuda {
         })
     }
 
+    fn try_reuse_grad<S: Shape, E: Dtype>(
+        &self,
+        storage: &Self::Storage<S, E>,
+        grad: &mut Self::Storage<S, E>,
+    ) -> Result<bool, Self::Err> {
+        if grad.shape.concrete() != storage.shape.concrete()
+            || grad.strides != storage.strides
+            || grad.data.len() != storage.shape.num_elements()
+        {
+            return Ok(false);
+        }
+        self.try_fill_with_zeros(grad)?;
+        Ok(true)
+    }
+
     fn random_u64(&self) -> u64 {
         self.cpu.random_u64()
     }
+
+    fn compensated(&self) -> bool {
+        self.compensated.load(Ordering::Relaxed)
+    }
+
+    fn same_device(&self, other: &Self) -> bool {
+        self.ordinal == other.ordinal
+    }
+
+    fn device_name(&self) -> std::string::String {
+        std::format!("Cuda({})", self.ordinal)
+    }
+
+    fn try_synchronize(&self) -> Result<(), Self::Err> {
+        self.dev.synchronize()?;
+        Ok(())
+    }
 }
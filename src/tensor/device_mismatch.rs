@@ -0,0 +1,98 @@
+use super::DeviceStorage;
+
+/// Error for when the inputs to an operation are stored on different devices, for example
+/// two [super::Cuda] devices with different ordinals.
+///
+/// Tensors must be moved onto the same device with [super::ToDevice] before they are used together.
+///
+/// In debug builds, this also stores the location of the call to the operation.
+///
+/// Example message:
+/// ```text
+/// DeviceMismatch in `add` at src/main.rs:10:13: lhs is on Cuda(0), but rhs is on Cuda(1)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceMismatch {
+    /// The name of the operation
+    pub op: &'static str,
+    /// Description of the device of the left hand side
+    pub lhs_device: std::string::String,
+    /// Description of the device of the right hand side
+    pub rhs_device: std::string::String,
+    /// Where the operation was called from
+    #[cfg(debug_assertions)]
+    pub location: &'static core::panic::Location<'static>,
+}
+
+impl DeviceMismatch {
+    /// Checks that `lhs` and `rhs` are the same device, using [DeviceStorage::same_device()].
+    #[track_caller]
+    pub fn check_same<D: DeviceStorage>(op: &'static str, lhs: &D, rhs: &D) -> Result<(), Self> {
+        if lhs.same_device(rhs) {
+            Ok(())
+        } else {
+            Err(Self {
+                op,
+                lhs_device: lhs.device_name(),
+                rhs_device: rhs.device_name(),
+                #[cfg(debug_assertions)]
+                location: core::panic::Location::caller(),
+            })
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "DeviceMismatch in `{}`", self.op)?;
+        #[cfg(debug_assertions)]
+        write!(f, " at {}", self.location)?;
+        write!(
+            f,
+            ": lhs is on {}, but rhs is on {}",
+            self.lhs_device, self.rhs_device
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeviceMismatch {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        shapes::Rank1,
+        tensor::{Cpu, Tensor, ZerosTensor},
+        tests::TestDevice,
+    };
+
+    #[test]
+    fn test_cpus_are_same_device() {
+        let a = Cpu::seed_from_u64(0);
+        let b = Cpu::seed_from_u64(1);
+        assert!(DeviceMismatch::check_same("add", &a, &b).is_ok());
+    }
+
+    #[test]
+    fn test_tensor_device() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        assert!(t.device().same_device(&dev));
+        assert_eq!(t.device().device_name(), dev.device_name());
+    }
+
+    #[test]
+    fn test_display() {
+        let err = DeviceMismatch {
+            op: "add",
+            lhs_device: "Cuda(0)".into(),
+            rhs_device: "Cuda(1)".into(),
+            #[cfg(debug_assertions)]
+            location: core::panic::Location::caller(),
+        };
+        let msg = std::format!("{err}");
+        assert!(msg.starts_with("DeviceMismatch in `add`"));
+        assert!(msg.ends_with("lhs is on Cuda(0), but rhs is on Cuda(1)"));
+    }
+}
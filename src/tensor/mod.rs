@@ -19,6 +19,18 @@
 //! let dev: Cpu = Default::default();
 //! ```
 //!
+//! To use the best device enabled by the crate's features without any `#[cfg]`s,
+//! use [AutoDevice]:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! let dev: AutoDevice = Default::default();
+//! let t: Tensor<Rank1<3>, f32, _> = dev.zeros();
+//! ```
+//!
+//! [AutoDevice] is picked at compile time, see [RuntimeDevice] to fall back to [Cpu]
+//! when no GPU is available at runtime.
+//!
 //! # Creating tensors
 //!
 //! ### From arrays
@@ -110,7 +122,12 @@
 //! You can also use [Tensor::write_to_npz] and [Tensor::read_from_npz] when working with
 //! zip archives.
 
+mod bytewise;
+mod check_failed;
 pub(crate) mod cpu;
+mod device_mismatch;
+mod overrides;
+mod runtime_device;
 mod tensor_impls;
 
 #[cfg(feature = "cuda")]
@@ -123,18 +140,48 @@ pub(crate) mod storage_traits;
 
 pub(crate) use storage_traits::{OneFillStorage, ZeroFillStorage};
 
+pub use check_failed::{CheckFailed, Invariant};
 pub use cpu::{Cpu, CpuError};
+pub use device_mismatch::DeviceMismatch;
+pub use overrides::{CpuGemm, GemmArgs, KernelOverrides, OverridableOp};
+pub use runtime_device::{RunOnDevice, RuntimeDevice};
 
 #[cfg(feature = "cuda")]
 pub use cuda::{Cuda, CudaError};
 
-pub use storage_traits::{AsArray, AsVec, CopySlice, TensorFromArray};
-pub use storage_traits::{DeviceStorage, HasErr};
-pub use storage_traits::{OnesTensor, SampleTensor, ZerosTensor};
+macro_rules! auto_device {
+    ($Dev:ty) => {
+        /// The best device available with the enabled features. This is [Cuda] when
+        /// the `cuda` feature is enabled, and [Cpu] otherwise. Note that [Cpu] uses blas
+        /// for matrix multiplications when the `cblas` or `intel-mkl` features are enabled.
+        ///
+        /// Code written against this alias works on every backend, so binaries can pick the
+        /// backend purely through features:
+        /// ```rust
+        /// # use dfdx::prelude::*;
+        /// let dev = AutoDevice::seed_from_u64(0);
+        /// let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        /// ```
+        ///
+        /// The backend is picked at compile time, so with the `cuda` feature on a machine
+        /// without a GPU, `AutoDevice::seed_from_u64` & `AutoDevice::default()` panic. Use
+        /// [RuntimeDevice] to fall back to [Cpu] at runtime instead.
+        pub type AutoDevice = $Dev;
+    };
+}
+
+#[cfg(feature = "cuda")]
+auto_device!(Cuda);
+#[cfg(not(feature = "cuda"))]
+auto_device!(Cpu);
+
+pub use storage_traits::{AsArray, AsVec, CopySlice, TensorFromArray, TensorFromVec};
+pub use storage_traits::{DeviceStorage, HasErr, HasStrides};
+pub use storage_traits::{OnesTensor, SampleTensor, TriangleTensor, ZerosTensor};
 
 #[cfg(feature = "cuda")]
 pub use tensor_impls::OnCuda;
-pub use tensor_impls::{OnCpu, OnDevice, PutTape, SplitTape, Tensor, ToDevice};
+pub use tensor_impls::{OnCpu, OnDevice, OnDtype, PutTape, SplitTape, Tensor, ToDevice, ToDtype};
 pub use tensor_impls::{Tensor0D, Tensor1D, Tensor2D, Tensor3D, Tensor4D, Tensor5D, Tensor6D};
 
 #[cfg(test)]
@@ -173,6 +220,44 @@ mod tests {
         ids.insert(x.id);
     }
 
+    #[test]
+    fn test_auto_device() {
+        let dev = AutoDevice::seed_from_u64(0);
+        let x: Tensor<Rank2<2, 3>, f32, _> = dev.ones();
+        assert_eq!(x.to_device(&Cpu::default()).array(), [[1.0; 3]; 2]);
+    }
+
+    #[test]
+    fn test_deterministic_gather_backward() {
+        use crate::tensor_ops::*;
+        let dev: TestDevice = Default::default();
+        dev.set_deterministic(true);
+        assert!(dev.is_deterministic());
+        assert!(dev.clone().is_deterministic());
+
+        let t: Tensor<Rank1<4>, f32, _> = dev.sample_normal();
+        let idx = dev.tensor([0, 1, 1, 3, 3, 3, 3, 0, 3, 3, 3, 3]);
+        let w: Tensor<Rank1<12>, f32, _> = dev.sample_normal();
+        let grad = |t: &Tensor<Rank1<4>, f32, _>| {
+            let r = t.trace().gather(idx.clone());
+            (r * w.clone()).sum().backward().get(t).array()
+        };
+        assert_eq!(grad(&t), grad(&t));
+    }
+
+    #[test]
+    fn test_to_dtype() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<2, 3>, f64, _> = a.to_dtype::<f64>();
+        let c: Tensor<Rank2<2, 3>, f32, _> = b.to_dtype::<f32>();
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.array(), c.array());
+        for (x, y) in a.as_vec().iter().zip(b.as_vec().iter()) {
+            assert_eq!(*x as f64, *y);
+        }
+    }
+
     #[test]
     fn test_ids_with_clone() {
         let dev: TestDevice = Default::default();
@@ -192,6 +277,52 @@ mod tests {
         assert_eq!(t3.id, t1_id);
     }
 
+    #[test]
+    fn test_tensor_from_vec() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor_from_vec(
+            std::vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            (Const::<2>, Const::<3>),
+        );
+        assert_eq!(x.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let y = dev.tensor_from_vec(std::vec![1.0, 2.0], (2,));
+        assert_eq!(y.into_vec(), std::vec![1.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_tensor_from_vec_wrong_len() {
+        let dev: TestDevice = Default::default();
+        let _ = dev.tensor_from_vec(std::vec![1.0f32; 5], (Const::<2>, Const::<3>));
+    }
+
+    #[test]
+    fn test_try_tensor_from_vec_wrong_len() {
+        let dev: TestDevice = Default::default();
+        let r = dev.try_tensor_from_vec(std::vec![1.0f32; 5], (2, Const::<3>));
+        let err = std::format!("{:?}", r.unwrap_err());
+        assert!(err.contains("tensor_from_vec"), "{err}");
+    }
+
+    #[test]
+    fn test_into_vec_non_contiguous() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([1.0, 2.0]);
+        let y: Tensor<Rank2<2, 2>, f32, _> =
+            crate::tensor_ops::BroadcastTo::broadcast::<_, Axis<0>>(x);
+        assert_eq!(y.into_vec(), std::vec![1.0, 2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_cpu_vec_round_trip_no_copy() {
+        let dev: Cpu = Default::default();
+        let data = std::vec![1.0f32, 2.0, 3.0];
+        let ptr = data.as_ptr();
+        let x = dev.tensor_from_vec(data, (Const::<3>,));
+        let data = x.into_vec();
+        assert_eq!(data.as_ptr(), ptr);
+    }
+
     #[test]
     fn test_zeros() {
         let dev: TestDevice = Default::default();
@@ -0,0 +1,170 @@
+use std::{
+    any::{Any, TypeId},
+    boxed::Box,
+    collections::BTreeMap,
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
+
+/// An op whose kernel can be replaced at runtime with [KernelOverrides::set()].
+///
+/// Implementors are marker types, one for each op & dtype, and [OverridableOp::Kernel]
+/// is the signature the replacement kernel has to have.
+pub trait OverridableOp: 'static {
+    /// The type of the replacement kernel, usually a `dyn Fn(...) + Send + Sync`.
+    type Kernel: ?Sized + Send + Sync + 'static;
+}
+
+/// The matrix multiplication `c += a * b` used by every [crate::tensor::Cpu] matmul & conv2d
+/// kernel. Overriding this is the way to swap in a hand tuned or ffi gemm on the cpu.
+///
+/// The replacement kernel is called with [GemmArgs].
+pub struct CpuGemm<E>(PhantomData<E>);
+
+impl OverridableOp for CpuGemm<f32> {
+    type Kernel = dyn for<'a> Fn(GemmArgs<'a, f32>) + Send + Sync;
+}
+
+/// The arguments of [CpuGemm]. The kernel should compute `c += a * b`, where `a` is `(m, k)`,
+/// `b` is `(k, n)`, and `c` is `(m, n)`.
+///
+/// Element `[i, j]` of `a` is `a[i * a_strides[0] + j * a_strides[1]]`, and the same for `b` & `c`.
+/// Strides may be 0 for broadcasted inputs, and matrices may be transposed
+/// (i.e. have `strides[0] < strides[1]`).
+#[derive(Debug)]
+pub struct GemmArgs<'a, E> {
+    pub m: usize,
+    pub k: usize,
+    pub n: usize,
+    pub a: &'a [E],
+    pub a_strides: [usize; 2],
+    pub b: &'a [E],
+    pub b_strides: [usize; 2],
+    pub c: &'a mut [E],
+    pub c_strides: [usize; 2],
+}
+
+/// A table of kernels that replace the ones built into a device, for a given op & dtype.
+/// Get the one for a device with `Cpu::overrides()`.
+///
+/// The table is shared by every clone of the device, including the ones stored in tensors,
+/// so overrides can be set & removed at any time.
+///
+/// Only the ops that implement [OverridableOp] can be overridden, see [CpuGemm].
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+/// let dev: Cpu = Default::default();
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let counter = calls.clone();
+/// dev.overrides().set::<CpuGemm<f32>>(Arc::new(move |args: GemmArgs<f32>| {
+///     counter.fetch_add(1, Ordering::Relaxed);
+///     for i in 0..args.m {
+///         for j in 0..args.n {
+///             for k in 0..args.k {
+///                 let a = args.a[i * args.a_strides[0] + k * args.a_strides[1]];
+///                 let b = args.b[k * args.b_strides[0] + j * args.b_strides[1]];
+///                 args.c[i * args.c_strides[0] + j * args.c_strides[1]] += a * b;
+///             }
+///         }
+///     }
+/// }));
+/// let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+/// let b = dev.tensor([[1.0, 0.0], [0.0, 1.0]]);
+/// assert_eq!(a.matmul(b).array(), [[1.0, 2.0], [3.0, 4.0]]);
+/// assert_eq!(calls.load(Ordering::Relaxed), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct KernelOverrides {
+    kernels: Arc<RwLock<BTreeMap<TypeId, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl std::fmt::Debug for KernelOverrides {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KernelOverrides")
+            .field("len", &self.kernels.read().unwrap().len())
+            .finish()
+    }
+}
+
+impl KernelOverrides {
+    /// Uses `kernel` for `Op` instead of the built in kernel, replacing any previous override.
+    pub fn set<Op: OverridableOp>(&self, kernel: Arc<Op::Kernel>) {
+        let mut kernels = self.kernels.write().unwrap();
+        kernels.insert(TypeId::of::<Op>(), Box::new(kernel));
+    }
+
+    /// Goes back to the built in kernel for `Op`. Returns whether `Op` was overridden.
+    pub fn remove<Op: OverridableOp>(&self) -> bool {
+        let mut kernels = self.kernels.write().unwrap();
+        kernels.remove(&TypeId::of::<Op>()).is_some()
+    }
+
+    /// Goes back to the built in kernels for every op.
+    pub fn clear(&self) {
+        self.kernels.write().unwrap().clear();
+    }
+
+    /// The kernel that overrides `Op`, if there is one.
+    pub fn get<Op: OverridableOp>(&self) -> Option<Arc<Op::Kernel>> {
+        let kernels = self.kernels.read().unwrap();
+        kernels
+            .get(&TypeId::of::<Op>())
+            .map(|k| k.downcast_ref::<Arc<Op::Kernel>>().unwrap().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    #[test]
+    fn test_override_cpu_gemm() {
+        let dev: Cpu = Default::default();
+        let a: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<4, 5>, f32, _> = dev.sample_normal();
+        let expected = a.clone().matmul(b.clone()).array();
+
+        // doubles the product, so we can tell it was used
+        dev.overrides()
+            .set::<CpuGemm<f32>>(Arc::new(|args: GemmArgs<f32>| {
+                for i in 0..args.m {
+                    for j in 0..args.n {
+                        let mut sum = 0.0;
+                        for k in 0..args.k {
+                            sum += args.a[i * args.a_strides[0] + k * args.a_strides[1]]
+                                * args.b[k * args.b_strides[0] + j * args.b_strides[1]];
+                        }
+                        args.c[i * args.c_strides[0] + j * args.c_strides[1]] += 2.0 * sum;
+                    }
+                }
+            }));
+        let r = a.clone().matmul(b.clone()).array();
+        for (r, e) in r
+            .iter()
+            .flatten()
+            .flatten()
+            .zip(expected.iter().flatten().flatten())
+        {
+            assert!((r - 2.0 * e).abs() < 1e-5);
+        }
+
+        assert!(dev.overrides().remove::<CpuGemm<f32>>());
+        assert!(!dev.overrides().remove::<CpuGemm<f32>>());
+        assert_eq!(a.matmul(b).array(), expected);
+    }
+
+    #[test]
+    fn test_overrides_shared_between_clones() {
+        let dev: Cpu = Default::default();
+        let t: Tensor<Rank2<2, 2>, f32, _> = dev.zeros();
+        t.device
+            .overrides()
+            .set::<CpuGemm<f32>>(Arc::new(|_: GemmArgs<f32>| {}));
+        assert!(dev.overrides().get::<CpuGemm<f32>>().is_some());
+        dev.overrides().clear();
+        assert!(t.device.overrides().get::<CpuGemm<f32>>().is_none());
+    }
+}
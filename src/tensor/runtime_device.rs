@@ -0,0 +1,100 @@
+use super::{Cpu, DeviceStorage};
+use crate::tensor_ops::Device;
+
+#[cfg(feature = "cuda")]
+use super::Cuda;
+
+/// Code that is generic over the device, so it can run on whichever device a
+/// [RuntimeDevice] picked. See [RuntimeDevice::run()].
+pub trait RunOnDevice {
+    type Output;
+    fn run<D: Device<f32>>(self, dev: D) -> Self::Output;
+}
+
+/// A device picked at runtime: `Cuda` if the `cuda` feature is enabled and a GPU can be
+/// initialized, and [Cpu] otherwise.
+///
+/// [super::AutoDevice] picks the backend at compile time, so a binary built with the `cuda`
+/// feature panics on a machine without a GPU. This tries to initialize `Cuda` instead, and falls
+/// back to [Cpu] if that fails. Each device has its own tensor types, so the code that uses the
+/// device is generic over it, and runs through [RunOnDevice]:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// struct Total;
+/// impl RunOnDevice for Total {
+///     type Output = f32;
+///     fn run<D: Device<f32>>(self, dev: D) -> f32 {
+///         let x: Tensor<Rank1<3>, f32, D> = dev.ones();
+///         x.sum().as_vec()[0]
+///     }
+/// }
+/// let dev = RuntimeDevice::seed_from_u64(0);
+/// assert_eq!(dev.run(Total), 3.0);
+/// ```
+#[derive(Debug, Clone)]
+pub enum RuntimeDevice {
+    Cpu(Cpu),
+    #[cfg(feature = "cuda")]
+    Cuda(Cuda),
+}
+
+impl Default for RuntimeDevice {
+    fn default() -> Self {
+        Self::seed_from_u64(0)
+    }
+}
+
+impl RuntimeDevice {
+    /// Tries to initialize `Cuda` with the given seed, and falls back to a [Cpu] with the
+    /// same seed.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        #[cfg(feature = "cuda")]
+        if let Ok(dev) = Cuda::try_seed_from_u64(seed) {
+            return Self::Cuda(dev);
+        }
+        Self::Cpu(Cpu::seed_from_u64(seed))
+    }
+
+    /// A short description of the device that was picked, see [DeviceStorage::device_name()].
+    pub fn device_name(&self) -> std::string::String {
+        match self {
+            Self::Cpu(dev) => dev.device_name(),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(dev) => dev.device_name(),
+        }
+    }
+
+    /// Runs `f` on the device that was picked.
+    pub fn run<F: RunOnDevice>(&self, f: F) -> F::Output {
+        match self {
+            Self::Cpu(dev) => f.run(dev.clone()),
+            #[cfg(feature = "cuda")]
+            Self::Cuda(dev) => f.run(dev.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*};
+
+    struct Grad(f32);
+
+    impl RunOnDevice for Grad {
+        type Output = std::vec::Vec<f32>;
+        fn run<D: Device<f32>>(self, dev: D) -> Self::Output {
+            let x: Tensor<Rank1<3>, f32, D> = dev.ones();
+            let g = (x.trace() * self.0).square().sum().backward();
+            g.get(&x).as_vec()
+        }
+    }
+
+    #[test]
+    fn test_runtime_device() {
+        let dev = RuntimeDevice::seed_from_u64(0);
+        assert_eq!(dev.run(Grad(2.0)), [8.0; 3]);
+        #[cfg(not(feature = "cuda"))]
+        assert!(matches!(dev, RuntimeDevice::Cpu(_)));
+    }
+}
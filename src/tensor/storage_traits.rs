@@ -2,51 +2,118 @@ use rand::distributions::Distribution;
 use rand_distr::{Standard, StandardNormal};
 
 use crate::{
-    shapes::{ConstShape, Dtype, HasShape, HasUnitType, Shape, Unit},
+    shapes::{debug_caller, ConstShape, Dtype, HasShape, HasUnitType, Shape, ShapeMismatch, Unit},
     unique_id::unique_id,
 };
 
-use super::Tensor;
+use super::{CheckFailed, DeviceMismatch, Tensor};
 
 /// Represents something that has an error associated type
 pub trait HasErr: Sized {
-    type Err: std::fmt::Debug + std::fmt::Display;
+    type Err: std::fmt::Debug
+        + std::fmt::Display
+        + Send
+        + Sync
+        + From<ShapeMismatch>
+        + From<DeviceMismatch>
+        + From<CheckFailed>;
+}
+
+/// Something that has a stride for each dimension of its [Shape]. Strides
+/// are the number of elements to move in the underlying buffer when
+/// incrementing an index along that dimension.
+pub trait HasStrides: HasShape {
+    fn strides(&self) -> <Self::Shape as Shape>::Concrete;
+
+    /// Whether the underlying buffer is laid out in row major order, without any
+    /// broadcasted or permuted dimensions.
+    fn is_contiguous(&self) -> bool {
+        self.strides() == self.shape().strides()
+    }
 }
 
 /// Something that can store nd arrays for a given [Shape] and [Dtype]
-pub trait DeviceStorage: 'static + Default + Clone + HasErr {
+pub trait DeviceStorage: 'static + Default + Clone + Send + Sync + HasErr {
     /// Generic storage type
     type Storage<S: Shape, E: Unit>: 'static
         + std::fmt::Debug
         + Clone
         + Send
         + Sync
-        + HasShape<Shape = S>;
+        + HasShape<Shape = S>
+        + HasStrides
+        + HasUnitType<Unit = E>
+        + AsVec;
 
     /// Generates a random u64 number
     fn random_u64(&self) -> u64;
 
+    /// Whether tensors stored on `self` can be used in the same operation as tensors
+    /// stored on `other`.
+    fn same_device(&self, other: &Self) -> bool;
+
+    /// A short description of the device, used in [DeviceMismatch] errors.
+    fn device_name(&self) -> std::string::String;
+
     /// Allocates a gradient for the given nd array
     fn try_alloc_grad<S: Shape, E: Dtype>(
         &self,
         storage: &Self::Storage<S, E>,
     ) -> Result<Self::Storage<S, E>, Self::Err>;
 
-    /// Upgrades the device storage into a tensor
+    /// Fills `grad` with zeros, so it can be used as the gradient of `storage` instead of
+    /// allocating a new one with [DeviceStorage::try_alloc_grad()].
+    ///
+    /// Returns `false` and leaves `grad` untouched if it has a different layout than `storage`.
+    fn try_reuse_grad<S: Shape, E: Dtype>(
+        &self,
+        storage: &Self::Storage<S, E>,
+        grad: &mut Self::Storage<S, E>,
+    ) -> Result<bool, Self::Err>;
+
+    /// The maximum number of threads used to run independent parts of the backward pass.
+    /// Defaults to 1, which runs the backward pass sequentially.
+    fn backward_threads(&self) -> usize {
+        1
+    }
+
+    /// Whether sums & optimizer updates use Kahan summation, see `Cpu::set_compensated()`.
+    /// Defaults to `false`.
+    fn compensated(&self) -> bool {
+        false
+    }
+
+    /// Blocks until all the work queued on the device has finished. Devices that run
+    /// operations as soon as they are called, like [crate::tensor::Cpu], return immediately.
+    fn try_synchronize(&self) -> Result<(), Self::Err> {
+        Ok(())
+    }
+
+    /// Upgrades the device storage into a tensor, recording the caller as where the tensor
+    /// was created in debug builds.
+    #[track_caller]
     fn upgrade<S: Shape, E: Unit>(&self, storage: Self::Storage<S, E>) -> Tensor<S, E, Self> {
         Tensor {
             id: unique_id(),
             storage,
             device: self.clone(),
             tape: Default::default(),
+            location: debug_caller(),
         }
     }
 }
 
+impl<S: Shape, E: Unit, D: DeviceStorage, T> HasStrides for Tensor<S, E, D, T> {
+    fn strides(&self) -> S::Concrete {
+        self.storage.strides()
+    }
+}
+
 /// Internal trait - Represents something that can allocate its own gradient.
 pub trait AllocGrad: HasErr {
-    type Gradient: 'static;
+    type Gradient: 'static + Send + Sync;
     fn try_alloc_grad(&self) -> Result<Self::Gradient, Self::Err>;
+    fn try_reuse_grad(&self, grad: &mut Self::Gradient) -> Result<bool, Self::Err>;
 }
 
 impl<S: Shape, E: Dtype, D: DeviceStorage, T> AllocGrad for Tensor<S, E, D, T> {
@@ -54,6 +121,9 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T> AllocGrad for Tensor<S, E, D, T> {
     fn try_alloc_grad(&self) -> Result<Self::Gradient, D::Err> {
         self.device.try_alloc_grad(&self.storage)
     }
+    fn try_reuse_grad(&self, grad: &mut Self::Gradient) -> Result<bool, D::Err> {
+        self.device.try_reuse_grad(&self.storage, grad)
+    }
 }
 
 /// Enables copying data into and out of tensors
@@ -100,11 +170,13 @@ pub trait ZerosTensor<E: Unit>: DeviceStorage {
     /// # let dev: Cpu = Default::default();
     /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
     /// ```
+    #[track_caller]
     fn zeros<S: ConstShape>(&self) -> Tensor<S, E, Self> {
         self.try_zeros_like::<S>(&Default::default()).unwrap()
     }
 
     /// Fallible version of [ZerosTensor::zeros]
+    #[track_caller]
     fn try_zeros<S: ConstShape>(&self) -> Result<Tensor<S, E, Self>, Self::Err> {
         self.try_zeros_like::<S>(&Default::default())
     }
@@ -125,11 +197,13 @@ pub trait ZerosTensor<E: Unit>: DeviceStorage {
     /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
     /// let b: Tensor<Rank2<2, 3>, f32, _> = dev.zeros_like(&a);
     /// ```
+    #[track_caller]
     fn zeros_like<S: HasShape>(&self, src: &S) -> Tensor<S::Shape, E, Self> {
         self.try_zeros_like(src).unwrap()
     }
 
     /// Fallible version of [ZerosTensor::zeros_like]
+    #[track_caller]
     fn try_zeros_like<S: HasShape>(&self, src: &S) -> Result<Tensor<S::Shape, E, Self>, Self::Err>;
 }
 
@@ -148,11 +222,13 @@ pub trait OnesTensor<E: Unit>: DeviceStorage {
     /// # let dev: Cpu = Default::default();
     /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.ones();
     /// ```
+    #[track_caller]
     fn ones<S: ConstShape>(&self) -> Tensor<S, E, Self> {
         self.try_ones_like::<S>(&Default::default()).unwrap()
     }
 
     /// Fallible version of [OnesTensor::ones]
+    #[track_caller]
     fn try_ones<S: ConstShape>(&self) -> Result<Tensor<S, E, Self>, Self::Err> {
         self.try_ones_like::<S>(&Default::default())
     }
@@ -173,11 +249,13 @@ pub trait OnesTensor<E: Unit>: DeviceStorage {
     /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.ones();
     /// let b: Tensor<_, f32, _> = dev.ones_like(&a);
     /// ```
+    #[track_caller]
     fn ones_like<S: HasShape>(&self, src: &S) -> Tensor<S::Shape, E, Self> {
         self.try_ones_like(src).unwrap()
     }
 
     /// Fallible version of [OnesTensor::ones_like]
+    #[track_caller]
     fn try_ones_like<S: HasShape>(&self, src: &S) -> Result<Tensor<S::Shape, E, Self>, Self::Err>;
 }
 
@@ -188,8 +266,86 @@ pub trait OneFillStorage<E: Unit>: DeviceStorage {
     ) -> Result<(), Self::Err>;
 }
 
+/// Construct tensors where the last two dimensions are triangular: ones inside the triangle
+/// (including the diagonal), and zeros everywhere else. Useful for building causal attention masks.
+pub trait TriangleTensor<E: Unit>: DeviceStorage {
+    /// Creates a tensor with ones on and below the diagonal.
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.lower_triangular();
+    /// assert_eq!(a.array(), [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0]]);
+    /// ```
+    #[track_caller]
+    fn lower_triangular<S: ConstShape>(&self) -> Tensor<S, E, Self> {
+        self.try_lower_triangular_like::<S>(&Default::default())
+            .unwrap()
+    }
+
+    /// Fallible version of [TriangleTensor::lower_triangular]
+    #[track_caller]
+    fn try_lower_triangular<S: ConstShape>(&self) -> Result<Tensor<S, E, Self>, Self::Err> {
+        self.try_lower_triangular_like::<S>(&Default::default())
+    }
+
+    /// Build the lower triangular tensor with a shape given by something else.
+    ///
+    /// A causal attention mask, where each position can't see the future:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let mask: Tensor<(usize, usize), bool, _> = dev.lower_triangular_like(&(3, 3));
+    /// let scores: Tensor<(usize, usize), f32, _> = dev.ones_like(&(3, 3));
+    /// let scores = scores.masked_fill(bool_not(&mask), f32::NEG_INFINITY);
+    /// ```
+    #[track_caller]
+    fn lower_triangular_like<S: HasShape>(&self, src: &S) -> Tensor<S::Shape, E, Self> {
+        self.try_lower_triangular_like(src).unwrap()
+    }
+
+    /// Fallible version of [TriangleTensor::lower_triangular_like]
+    #[track_caller]
+    fn try_lower_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err>;
+
+    /// Creates a tensor with ones on and above the diagonal.
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a: Tensor<Rank2<2, 3>, f32, _> = dev.upper_triangular();
+    /// assert_eq!(a.array(), [[1.0, 1.0, 1.0], [0.0, 1.0, 1.0]]);
+    /// ```
+    #[track_caller]
+    fn upper_triangular<S: ConstShape>(&self) -> Tensor<S, E, Self> {
+        self.try_upper_triangular_like::<S>(&Default::default())
+            .unwrap()
+    }
+
+    /// Fallible version of [TriangleTensor::upper_triangular]
+    #[track_caller]
+    fn try_upper_triangular<S: ConstShape>(&self) -> Result<Tensor<S, E, Self>, Self::Err> {
+        self.try_upper_triangular_like::<S>(&Default::default())
+    }
+
+    /// Build the upper triangular tensor with a shape given by something else.
+    #[track_caller]
+    fn upper_triangular_like<S: HasShape>(&self, src: &S) -> Tensor<S::Shape, E, Self> {
+        self.try_upper_triangular_like(src).unwrap()
+    }
+
+    /// Fallible version of [TriangleTensor::upper_triangular_like]
+    #[track_caller]
+    fn try_upper_triangular_like<S: HasShape>(
+        &self,
+        src: &S,
+    ) -> Result<Tensor<S::Shape, E, Self>, Self::Err>;
+}
+
 /// Constructs tensors filled with random values from a given distribution.
 pub trait SampleTensor<E: Unit>: DeviceStorage {
+    #[track_caller]
     fn sample_uniform<S: ConstShape>(&self) -> Tensor<S, E, Self>
     where
         Standard: Distribution<E>,
@@ -197,6 +353,7 @@ pub trait SampleTensor<E: Unit>: DeviceStorage {
         self.sample::<S, _>(Standard)
     }
 
+    #[track_caller]
     fn sample_normal<S: ConstShape>(&self) -> Tensor<S, E, Self>
     where
         StandardNormal: Distribution<E>,
@@ -204,16 +361,19 @@ pub trait SampleTensor<E: Unit>: DeviceStorage {
         self.sample::<S, _>(StandardNormal)
     }
 
+    #[track_caller]
     fn sample<S: ConstShape, D: Distribution<E>>(&self, distr: D) -> Tensor<S, E, Self> {
         self.try_sample_like::<S, D>(&Default::default(), distr)
             .unwrap()
     }
+    #[track_caller]
     fn try_sample<S: ConstShape, D: Distribution<E>>(
         &self,
         distr: D,
     ) -> Result<Tensor<S, E, Self>, Self::Err> {
         self.try_sample_like::<S, D>(&Default::default(), distr)
     }
+    #[track_caller]
     fn sample_like<S: HasShape, D: Distribution<E>>(
         &self,
         src: &S,
@@ -221,6 +381,7 @@ pub trait SampleTensor<E: Unit>: DeviceStorage {
     ) -> Tensor<S::Shape, E, Self> {
         self.try_sample_like(src, distr).unwrap()
     }
+    #[track_caller]
     fn try_sample_like<S: HasShape, D: Distribution<E>>(
         &self,
         src: &S,
@@ -242,13 +403,69 @@ pub trait TensorFromArray<Src, S: Shape, E: Unit>: DeviceStorage {
     /// # let dev: Cpu = Default::default();
     /// let _: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
     /// ```
+    #[track_caller]
     fn tensor(&self, src: Src) -> Tensor<S, E, Self> {
         self.try_tensor(src).unwrap()
     }
     /// Fallible version of [TensorFromArray::tensor]
+    #[track_caller]
     fn try_tensor(&self, src: Src) -> Result<Tensor<S, E, Self>, Self::Err>;
 }
 
+/// Construct tensors from [std::vec::Vec]s, taking ownership of the buffer.
+///
+/// Borrowed `&mut [E]` buffers are not supported: tensors share ownership of their
+/// storage (e.g. when cloned or recorded on a tape), so they can't borrow it for a
+/// limited lifetime. Move the data into a `Vec` instead, and recover it with
+/// [Tensor::into_vec].
+pub trait TensorFromVec<E: Unit>: DeviceStorage {
+    /// Create a tensor using `src` as its storage. On [crate::tensor::Cpu] the buffer is used
+    /// directly, no data is copied.
+    ///
+    /// **Panics** if `src.len()` is not the number of elements in `shape`. See
+    /// [TensorFromVec::try_tensor_from_vec] for a version that returns an error instead.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor_from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], (Const::<2>, Const::<3>));
+    /// assert_eq!(t.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// ```
+    #[track_caller]
+    fn tensor_from_vec<S: Shape>(&self, src: std::vec::Vec<E>, shape: S) -> Tensor<S, E, Self> {
+        self.try_tensor_from_vec(src, shape).unwrap()
+    }
+
+    /// Fallible version of [TensorFromVec::tensor_from_vec]. Returns a [ShapeMismatch]
+    /// error if `src.len()` is not the number of elements in `shape`.
+    #[track_caller]
+    fn try_tensor_from_vec<S: Shape>(
+        &self,
+        src: std::vec::Vec<E>,
+        shape: S,
+    ) -> Result<Tensor<S, E, Self>, Self::Err>;
+
+    /// Moves the data of `t` into a [std::vec::Vec] in row major order. See [Tensor::into_vec].
+    fn tensor_into_vec<S: Shape, T>(t: Tensor<S, E, Self, T>) -> std::vec::Vec<E>;
+}
+
+impl<S: Shape, E: Unit, D: TensorFromVec<E>, T> Tensor<S, E, D, T> {
+    /// Recovers the underlying buffer of the tensor in row major order.
+    ///
+    /// On [crate::tensor::Cpu], if this tensor is the only owner of its data and is contiguous,
+    /// the buffer is returned without copying. Otherwise the data is copied.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor_from_vec(vec![1.0, 2.0, 3.0], (Const::<3>,));
+    /// assert_eq!(t.into_vec(), vec![1.0, 2.0, 3.0]);
+    /// ```
+    pub fn into_vec(self) -> std::vec::Vec<E> {
+        D::tensor_into_vec(self)
+    }
+}
+
 /// Convert tensors to rust arrays
 pub trait AsArray {
     type Array: std::fmt::Debug + PartialEq;
@@ -3,7 +3,7 @@ use rand::distributions::Distribution;
 use super::storage_traits::{CopySlice, DeviceStorage, HasErr, ZerosTensor};
 use super::{Cpu, OneFillStorage, SampleTensor, ZeroFillStorage};
 use crate::{
-    gradients::{NoneTape, OwnedTape, Tape},
+    gradients::{Gradients, NoneTape, OwnedTape, Tape},
     shapes::*,
     unique_id::{HasUniqueId, UniqueId},
 };
@@ -37,6 +37,8 @@ pub struct Tensor<S: Shape, E: Unit, D: DeviceStorage, T = NoneTape> {
     pub(crate) storage: D::Storage<S, E>,
     pub(crate) device: D,
     pub(crate) tape: T,
+    /// Where the tensor was created, in debug builds. See [ShapeMismatch].
+    pub(crate) location: Option<&'static core::panic::Location<'static>>,
 }
 
 impl<S: Shape, E: Unit, D: DeviceStorage, T> HasShape for Tensor<S, E, D, T> {
@@ -47,6 +49,12 @@ impl<S: Shape, E: Unit, D: DeviceStorage, T> HasShape for Tensor<S, E, D, T> {
     }
 }
 
+impl<S: Shape, E: Unit, D: DeviceStorage, T> CheckShape for Tensor<S, E, D, T> {
+    fn created_at(&self) -> Option<&'static core::panic::Location<'static>> {
+        self.location
+    }
+}
+
 impl<S: Shape, E: Unit, D: DeviceStorage, T> HasUnitType for Tensor<S, E, D, T> {
     type Unit = E;
 }
@@ -65,6 +73,13 @@ impl<S: Shape, E: Unit, D: DeviceStorage, T> HasErr for Tensor<S, E, D, T> {
     type Err = D::Err;
 }
 
+impl<S: Shape, E: Unit, D: DeviceStorage, T> Tensor<S, E, D, T> {
+    /// The device this tensor is stored on.
+    pub fn device(&self) -> &D {
+        &self.device
+    }
+}
+
 impl<S: Shape, E: Dtype, D: DeviceStorage> Tensor<S, E, D, NoneTape> {
     /// Clone and put a [OwnedTape] into the tensor
     pub fn trace(&self) -> Tensor<S, E, D, OwnedTape<D>> {
@@ -74,6 +89,18 @@ impl<S: Shape, E: Dtype, D: DeviceStorage> Tensor<S, E, D, NoneTape> {
     pub fn traced(self) -> Tensor<S, E, D, OwnedTape<D>> {
         self.put_tape(Default::default())
     }
+    /// Clone and put a [OwnedTape] into the tensor that stores gradients in `gradients`.
+    ///
+    /// Gradients that are already in `gradients` are accumulated into, and
+    /// spare buffers from [Gradients::recycle()] are reused instead of allocating new ones.
+    pub fn trace_into(&self, gradients: Gradients) -> Tensor<S, E, D, OwnedTape<D>> {
+        self.clone().traced_into(gradients)
+    }
+    /// Put a [OwnedTape] into the tensor that stores gradients in `gradients`.
+    /// See [Tensor::trace_into()].
+    pub fn traced_into(self, gradients: Gradients) -> Tensor<S, E, D, OwnedTape<D>> {
+        self.put_tape(OwnedTape::with_gradients(gradients))
+    }
 }
 
 impl<S: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>> Tensor<S, E, D, T> {
@@ -84,6 +111,7 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T: Tape<D>> Tensor<S, E, D, T> {
             storage: self.storage.clone(),
             device: self.device.clone(),
             tape: Default::default(),
+            location: self.location,
         }
     }
 }
@@ -108,6 +136,7 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T> PutTape<T> for Tensor<S, E, D> {
             storage: self.storage,
             device: self.device,
             tape,
+            location: self.location,
         }
     }
 }
@@ -140,6 +169,7 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T: Default> SplitTape for Tensor<S, E
                 storage: self.storage,
                 device: self.device,
                 tape: NoneTape,
+                location: self.location,
             },
             self.tape,
         )
@@ -151,6 +181,7 @@ impl<S: Shape, E: Dtype, D: DeviceStorage, T: Default> SplitTape for Tensor<S, E
             storage: self.storage.clone(),
             device: self.device.clone(),
             tape: Default::default(),
+            location: self.location,
         }
     }
 }
@@ -249,6 +280,102 @@ pub type OnCuda<M> = OnDevice<M, crate::prelude::Cuda>;
 /// Equivalent to `OnDevice<M, Cpu>`
 pub type OnCpu<M> = OnDevice<M, Cpu>;
 
+/// Something that can be converted to use the [Dtype] `E`, and can be used with the
+/// [OnDtype] type alias. This is the [Dtype] equivalent of [ToDevice].
+///
+/// Tensors, modules without parameters, and containers of modules (like tuples) implement this.
+///
+/// Layers with parameters (like [crate::nn::Linear]) do **not** implement this, since they
+/// only support `f32`, so there is no `f64` (or half precision) version to convert them to.
+/// This means whole models can't be converted yet. To train `f32` models with higher
+/// precision copies of the parameters, use [crate::optim::MasterWeights], which collects the
+/// parameters of any model with [crate::optim::GradientUpdate].
+///
+/// Here's an example of how this can be implemented for a custom struct:
+/// ```rust
+/// use dfdx::prelude::*;
+///
+/// struct Scale<E: Dtype, D: DeviceStorage> {
+///     scale: Tensor<Rank1<5>, E, D>,
+///     a1: ReLU,
+/// }
+///
+/// impl<E1: Dtype, E2: Dtype, D: DeviceStorage> ToDtype<E2> for Scale<E1, D>
+/// where
+///     Tensor<Rank1<5>, E1, D>: ToDtype<E2, Output = Tensor<Rank1<5>, E2, D>>,
+/// {
+///     type Output = Scale<E2, D>;
+///
+///     fn to_dtype(&self) -> Self::Output {
+///         Scale {
+///             scale: self.scale.to_dtype::<E2>(),
+///             a1: self.a1,
+///         }
+///     }
+/// }
+///
+/// let dev: Cpu = Default::default();
+/// let m: Scale<f32, Cpu> = Scale { scale: dev.ones(), a1: ReLU };
+/// // since the trait is generic over the dtype, specify it with `ToDtype::<E>`
+/// let m: Scale<f64, Cpu> = ToDtype::<f64>::to_dtype(&m);
+/// ```
+pub trait ToDtype<E> {
+    type Output;
+    fn to_dtype(&self) -> Self::Output;
+}
+
+/// A type alias that yields the type of a module `M` as it would exist with dtype `E`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// type Model = (ReLU, Tanh);
+/// type Model64 = OnDtype<Model, f64>;
+/// ```
+pub type OnDtype<M, E> = <M as ToDtype<E>>::Output;
+
+impl<S: Shape, E: Unit, D: DeviceStorage, T> Tensor<S, E, D, T> {
+    /// Converts the elements of this tensor to `E2`. The result has no tape.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+    /// let b: Tensor<Rank1<3>, f64, _> = a.to_dtype::<f64>();
+    /// assert_eq!(b.array(), [1.0, 2.0, 3.0]);
+    /// ```
+    pub fn to_dtype<E2>(&self) -> OnDtype<Self, E2>
+    where
+        Self: ToDtype<E2>,
+    {
+        ToDtype::<E2>::to_dtype(self)
+    }
+}
+
+macro_rules! tensor_to_dtype {
+    ($Src:ty, $Dst:ty) => {
+        impl<S: Shape, T, D: ZerosTensor<$Dst> + CopySlice<$Src> + CopySlice<$Dst>> ToDtype<$Dst>
+            for Tensor<S, $Src, D, T>
+        {
+            type Output = Tensor<S, $Dst, D, NoneTape>;
+
+            /// Converts every element with `as`.
+            fn to_dtype(&self) -> Self::Output {
+                let mut buf = std::vec![0.0; self.shape().num_elements()];
+                self.copy_into(&mut buf);
+                let buf: std::vec::Vec<$Dst> = buf.into_iter().map(|x| x as $Dst).collect();
+                let mut out: Self::Output = self.device.zeros_like(self.shape());
+                out.copy_from(&buf);
+                out
+            }
+        }
+    };
+}
+
+tensor_to_dtype!(f32, f32);
+tensor_to_dtype!(f32, f64);
+tensor_to_dtype!(f64, f32);
+tensor_to_dtype!(f64, f64);
+
 impl<
         S: Shape,
         E: Dtype + Unit,
@@ -0,0 +1,173 @@
+struct AdaptivePool2DOp {
+    size_t batch;
+    size_t chan;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+// the half open range of input positions that output position `i` pools over
+__device__ void window(size_t i, size_t n_in, size_t n_out, size_t *start, size_t *end) {
+    *start = i * n_in / n_out;
+    *end = ((i + 1) * n_in + n_out - 1) / n_out;
+}
+
+extern "C" __global__ void adaptive_avg_pool2d_forward(
+    const AdaptivePool2DOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t y0, y1, x0, x1;
+    window(oh, op.h_in, op.h_out, &y0, &y1);
+    window(ow, op.w_in, op.w_out, &x0, &x1);
+
+    float tmp = 0.0;
+    for (size_t y = y0; y < y1; y++) {
+        for (size_t x = x0; x < x1; x++) {
+            tmp += inp[b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3]];
+        }
+    }
+
+    tmp /= static_cast<float>((y1 - y0) * (x1 - x0));
+    out[b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3]] = tmp;
+}
+
+extern "C" __global__ void adaptive_avg_pool2d_backward(
+    const AdaptivePool2DOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *grad_inp,
+    const float *out, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t y0, y1, x0, x1;
+    window(oh, op.h_in, op.h_out, &y0, &y1);
+    window(ow, op.w_in, op.w_out, &x0, &x1);
+
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    const float g = grad_out[out_i] / static_cast<float>((y1 - y0) * (x1 - x0));
+
+    // windows overlap when the output size doesn't divide the input size
+    for (size_t y = y0; y < y1; y++) {
+        for (size_t x = x0; x < x1; x++) {
+            auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+            atomicAdd(grad_inp + inp_i, g);
+        }
+    }
+}
+
+extern "C" __global__ void adaptive_max_pool2d_forward(
+    const AdaptivePool2DOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t y0, y1, x0, x1;
+    window(oh, op.h_in, op.h_out, &y0, &y1);
+    window(ow, op.w_in, op.w_out, &x0, &x1);
+
+    float tmp = -INFINITY;
+    for (size_t y = y0; y < y1; y++) {
+        for (size_t x = x0; x < x1; x++) {
+            tmp = fmaxf(tmp, inp[b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3]]);
+        }
+    }
+
+    out[b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3]] = tmp;
+}
+
+extern "C" __global__ void adaptive_max_pool2d_backward(
+    const AdaptivePool2DOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *grad_inp,
+    const float *out, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t y0, y1, x0, x1;
+    window(oh, op.h_in, op.h_out, &y0, &y1);
+    window(ow, op.w_in, op.w_out, &x0, &x1);
+
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    const float go = grad_out[out_i];
+    const float vo = out[out_i];
+
+    for (size_t y = y0; y < y1; y++) {
+        for (size_t x = x0; x < x1; x++) {
+            auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+            if (inp[inp_i] == vo) {
+                atomicAdd(grad_inp + inp_i, go);
+            }
+        }
+    }
+}
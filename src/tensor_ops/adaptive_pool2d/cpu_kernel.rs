@@ -0,0 +1,157 @@
+use crate::shapes::*;
+use crate::tensor::cpu::Cpu;
+
+use super::AdaptivePool2DOp;
+
+use std::sync::Arc;
+
+/// Returns strides in (batch, channel, height, width) order.
+fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
+    match S::NUM_DIMS {
+        3 => [0, strides[0], strides[1], strides[2]],
+        4 => [strides[0], strides[1], strides[2], strides[3]],
+        _ => panic!("Only implemented for 3d & 4d arrays"),
+    }
+}
+
+impl super::AdaptiveAvgPool2DKernel<f32> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let (y0, y1) = AdaptivePool2DOp::window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1) = AdaptivePool2DOp::window(ow, op.w_in, op.w_out);
+                        let mut tmp = 0.0;
+                        for y in y0..y1 {
+                            for x in x0..x1 {
+                                tmp += buf[b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3]];
+                            }
+                        }
+                        tmp /= ((y1 - y0) * (x1 - x0)) as f32;
+                        out_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]] = tmp;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Self::Storage<I, f32>,
+        grad_inp: &mut Self::Storage<I, f32>,
+        out: &Self::Storage<O, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let ginp_buf = Arc::make_mut(&mut grad_inp.data);
+        let buf = grad_out.data.as_ref();
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let (y0, y1) = AdaptivePool2DOp::window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1) = AdaptivePool2DOp::window(ow, op.w_in, op.w_out);
+                        let g = buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]]
+                            / ((y1 - y0) * (x1 - x0)) as f32;
+                        for y in y0..y1 {
+                            for x in x0..x1 {
+                                ginp_buf[b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3]] +=
+                                    g;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::AdaptiveMaxPool2DKernel<f32> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let (y0, y1) = AdaptivePool2DOp::window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1) = AdaptivePool2DOp::window(ow, op.w_in, op.w_out);
+                        let mut tmp = f32::NEG_INFINITY;
+                        for y in y0..y1 {
+                            for x in x0..x1 {
+                                tmp = tmp.max(
+                                    buf[b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3]],
+                                );
+                            }
+                        }
+                        out_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]] = tmp;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Self::Storage<I, f32>,
+        grad_inp: &mut Self::Storage<I, f32>,
+        out: &Self::Storage<O, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let inp_buf = inp.data.as_ref();
+        let ginp_buf = Arc::make_mut(&mut grad_inp.data);
+        let out_buf = out.data.as_ref();
+        let gout_buf = grad_out.data.as_ref();
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let (y0, y1) = AdaptivePool2DOp::window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1) = AdaptivePool2DOp::window(ow, op.w_in, op.w_out);
+                        let out_idx = b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3];
+                        let go = gout_buf[out_idx];
+                        let vo = out_buf[out_idx];
+                        for y in y0..y1 {
+                            for x in x0..x1 {
+                                let inp_idx = b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3];
+                                if inp_buf[inp_idx] == vo {
+                                    ginp_buf[inp_idx] += go;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct AdaptivePool2DOp {
+    pub batch: usize,
+    pub chan: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+impl AdaptivePool2DOp {
+    fn new([b, c, h_in, w_in]: [usize; 4], [h_out, w_out]: [usize; 2]) -> Self {
+        Self {
+            batch: b,
+            chan: c,
+            h_in,
+            h_out,
+            w_in,
+            w_out,
+        }
+    }
+
+    /// The half open range of the `n_in` input positions that output position `i` of `n_out`
+    /// pools over. Neighboring windows overlap when `n_out` doesn't divide `n_in`.
+    #[inline(always)]
+    pub(super) fn window(i: usize, n_in: usize, n_out: usize) -> (usize, usize) {
+        (i * n_in / n_out, ((i + 1) * n_in).div_ceil(n_out))
+    }
+}
+
+macro_rules! adaptive_pool2d {
+    (Kernel=$Kernel:ident, ConstTrait=$ConstTrait:ident, TryTrait=$TryTrait:ident, Meth=$Meth:ident, TryMeth=$TryMeth:ident) => {
+        pub trait $Kernel<E: Dtype>: DeviceStorage {
+            fn forward<I: Shape, O: Shape>(
+                &self,
+                op: AdaptivePool2DOp,
+                inp: &Self::Storage<I, E>,
+                out: &mut Self::Storage<O, E>,
+            ) -> Result<(), Self::Err>;
+
+            fn backward<I: Shape, O: Shape>(
+                &self,
+                op: AdaptivePool2DOp,
+                inp: &Self::Storage<I, E>,
+                grad_inp: &mut Self::Storage<I, E>,
+                out: &Self::Storage<O, E>,
+                grad_out: &Self::Storage<O, E>,
+            ) -> Result<(), Self::Err>;
+        }
+
+        pub trait $ConstTrait<const OH: usize, const OW: usize>: HasErr {
+            type Output;
+            fn try_adaptive_pool2d(self) -> Result<Self::Output, Self::Err>;
+        }
+
+        pub trait $TryTrait {
+            fn $Meth<const OH: usize, const OW: usize>(self) -> Self::Output
+            where
+                Self: $ConstTrait<OH, OW>,
+            {
+                self.try_adaptive_pool2d().unwrap()
+            }
+            fn $TryMeth<const OH: usize, const OW: usize>(self) -> Result<Self::Output, Self::Err>
+            where
+                Self: $ConstTrait<OH, OW>,
+            {
+                self.try_adaptive_pool2d()
+            }
+        }
+        impl<T> $TryTrait for T {}
+
+        impl<
+                C: Dim,
+                H: Dim,
+                W: Dim,
+                D: $Kernel<f32> + ZerosTensor<f32>,
+                T: 'static + Tape<D>,
+                const OH: usize,
+                const OW: usize,
+            > $ConstTrait<OH, OW> for Tensor<(C, H, W), f32, D, T>
+        {
+            type Output = Tensor<(C, Const<OH>, Const<OW>), f32, D, T>;
+
+            fn try_adaptive_pool2d(self) -> Result<Self::Output, Self::Err> {
+                let &(chan, h, w) = self.shape();
+                let op = AdaptivePool2DOp::new([1, chan.size(), h.size(), w.size()], [OH, OW]);
+                let (inp, mut tape) = self.split_tape();
+                let mut out = inp.device.try_zeros_like(&(chan, Const, Const))?;
+                inp.device.forward(op, &inp.storage, &mut out.storage)?;
+                let phantom_out = out.clone();
+                tape.try_alloc_grad(&inp)?;
+                tape.try_alloc_grad(&out)?;
+                tape.add_backward_op(move |grads| {
+                    let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+                    inp.device
+                        .backward(op, &inp.storage, grad_inp, &phantom_out.storage, grad_out)
+                });
+                Ok(out.put_tape(tape))
+            }
+        }
+
+        impl<
+                B: Dim,
+                C: Dim,
+                H: Dim,
+                W: Dim,
+                D: $Kernel<f32> + ZerosTensor<f32>,
+                T: 'static + Tape<D>,
+                const OH: usize,
+                const OW: usize,
+            > $ConstTrait<OH, OW> for Tensor<(B, C, H, W), f32, D, T>
+        {
+            type Output = Tensor<(B, C, Const<OH>, Const<OW>), f32, D, T>;
+
+            fn try_adaptive_pool2d(self) -> Result<Self::Output, Self::Err> {
+                let &(batch, chan, h, w) = self.shape();
+                let op = AdaptivePool2DOp::new(
+                    [batch.size(), chan.size(), h.size(), w.size()],
+                    [OH, OW],
+                );
+                let (inp, mut tape) = self.split_tape();
+                let mut out = inp.device.try_zeros_like(&(batch, chan, Const, Const))?;
+                inp.device.forward(op, &inp.storage, &mut out.storage)?;
+                let phantom_out = out.clone();
+                tape.try_alloc_grad(&inp)?;
+                tape.try_alloc_grad(&out)?;
+                tape.add_backward_op(move |grads| {
+                    let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+                    inp.device
+                        .backward(op, &inp.storage, grad_inp, &phantom_out.storage, grad_out)
+                });
+                Ok(out.put_tape(tape))
+            }
+        }
+    };
+}
+
+adaptive_pool2d!(
+    Kernel = AdaptiveAvgPool2DKernel,
+    ConstTrait = ConstAdaptiveAvgPool2D,
+    TryTrait = TryAdaptiveAvgPool2D,
+    Meth = adaptive_avg_pool2d,
+    TryMeth = try_adaptive_avg_pool2d
+);
+
+adaptive_pool2d!(
+    Kernel = AdaptiveMaxPool2DKernel,
+    ConstTrait = ConstAdaptiveMaxPool2D,
+    TryTrait = TryAdaptiveMaxPool2D,
+    Meth = adaptive_max_pool2d,
+    TryMeth = try_adaptive_max_pool2d
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_adaptive_windows() {
+        use super::AdaptivePool2DOp;
+        let windows: std::vec::Vec<_> = (0..3).map(|i| AdaptivePool2DOp::window(i, 5, 3)).collect();
+        assert_eq!(windows, [(0, 2), (1, 4), (3, 5)]);
+        let windows: std::vec::Vec<_> = (0..4).map(|i| AdaptivePool2DOp::window(i, 2, 4)).collect();
+        assert_eq!(windows, [(0, 1), (0, 1), (1, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_adaptive_avg_pool2d_overlapping() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<1, 5, 5>, f32, _> = dev
+            .tensor([[0.0, 1., 2., 3., 4.]; 5])
+            .broadcast::<_, Axis<0>>();
+        let x = x + dev
+            .tensor([0.0, 5., 10., 15., 20.])
+            .broadcast::<Rank3<1, 5, 5>, Axes2<0, 2>>();
+        let r = x.trace().adaptive_avg_pool2d::<3, 3>();
+        // row means are [0.5, 2, 3.5], column means are the same
+        assert_close(
+            &r.array(),
+            &[[[3.0, 4.5, 6.0], [10.5, 12.0, 13.5], [18.0, 19.5, 21.0]]],
+        );
+        let g = r.sum().backward();
+        #[rustfmt::skip]
+        assert_close(
+            &g.get(&x).array(),
+            &[[
+                [0.25, 0.4166667, 0.16666667, 0.4166667, 0.25],
+                [0.4166667, 0.6944444, 0.2777778, 0.6944444, 0.4166667],
+                [0.16666667, 0.2777778, 0.11111111, 0.2777778, 0.16666667],
+                [0.4166667, 0.6944444, 0.2777778, 0.6944444, 0.4166667],
+                [0.25, 0.4166667, 0.16666667, 0.4166667, 0.25],
+            ]],
+        );
+    }
+
+    #[test]
+    fn test_adaptive_max_pool2d_overlapping() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<1, 5, 5>, f32, _> = dev
+            .tensor([[0.0, 1., 2., 3., 4.]; 5])
+            .broadcast::<_, Axis<0>>();
+        let x = x + dev
+            .tensor([0.0, 5., 10., 15., 20.])
+            .broadcast::<Rank3<1, 5, 5>, Axes2<0, 2>>();
+        let r = x.trace().adaptive_max_pool2d::<3, 3>();
+        assert_eq!(
+            r.array(),
+            [[[6., 8., 9.], [16., 18., 19.], [21., 23., 24.]]]
+        );
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&x).array(),
+            [[
+                [0., 0., 0., 0., 0.],
+                [0., 1., 0., 1., 1.],
+                [0., 0., 0., 0., 0.],
+                [0., 1., 0., 1., 1.],
+                [0., 1., 0., 1., 1.],
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_adaptive_pool2d_global_matches_reductions() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank4<2, 3, 5, 7>, f32, _> = dev.sample_normal();
+        let r = x
+            .trace()
+            .adaptive_avg_pool2d::<1, 1>()
+            .sum::<Rank2<2, 3>, _>();
+        let r2 = x.trace().mean::<Rank2<2, 3>, _>();
+        assert_close(&r.array(), &r2.array());
+        let g = r.exp().sum().backward();
+        let g2 = r2.exp().sum().backward();
+        assert_close(&g.get(&x).array(), &g2.get(&x).array());
+
+        let r = x
+            .trace()
+            .adaptive_max_pool2d::<1, 1>()
+            .sum::<Rank2<2, 3>, _>();
+        let r2 = x.trace().max::<Rank2<2, 3>, _>();
+        assert_eq!(r.array(), r2.array());
+        let g = r.exp().sum().backward();
+        let g2 = r2.exp().sum().backward();
+        assert_close(&g.get(&x).array(), &g2.get(&x).array());
+    }
+
+    #[test]
+    fn test_adaptive_pool2d_dynamic_input_sizes() {
+        let dev: TestDevice = Default::default();
+        for (h, w) in [(7, 9), (4, 4), (2, 3)] {
+            let x: Tensor<(Const<2>, Const<3>, usize, usize), f32, _> =
+                dev.sample_like(&(Const, Const, h, w), rand_distr::StandardNormal);
+            let r: Tensor<Rank4<2, 3, 4, 4>, f32, _> = x.clone().adaptive_avg_pool2d::<4, 4>();
+            assert!(r.as_vec().iter().all(|v| v.is_finite()));
+            let r: Tensor<Rank4<2, 3, 4, 4>, f32, _> = x.adaptive_max_pool2d::<4, 4>();
+            assert!(r.as_vec().iter().all(|v| v.is_finite()));
+        }
+    }
+}
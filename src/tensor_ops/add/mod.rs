@@ -39,6 +39,7 @@ pub struct ScalarAddKernelOp<E> {
 /// let r = a + 1.0;
 /// assert_eq!(r.array(), [[2.0, 3.0, 4.0], [0.0, -1.0, -2.0]]);
 /// ```
+#[track_caller]
 pub fn add<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
@@ -57,8 +58,9 @@ where
     LhsTape: Merge<RhsTape>,
 {
     /// See [add]
+    #[track_caller]
     fn try_add(self, rhs: Tensor<S, E, D, RhsTape>) -> Result<Self, Self::Err> {
-        try_binary_op(BinaryAddKernelOp, self, rhs)
+        try_binary_op("add", BinaryAddKernelOp, self, rhs)
     }
 }
 
@@ -76,6 +78,7 @@ where
 {
     type Output = Self;
     /// See [add]
+    #[track_caller]
     fn add(self, rhs: Rhs) -> Self::Output {
         self.try_add(rhs).unwrap()
     }
@@ -168,6 +171,17 @@ mod tests {
         assert_eq!(g.get(&b2).array(), [[[1.0 / 6.0; 3]; 2]; 4]);
     }
 
+    #[test]
+    fn test_add_runtime_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, Const<2>), f32, _> = dev.zeros_like(&(3, Const));
+        let b: Tensor<(usize, Const<2>), f32, _> = dev.zeros_like(&(4, Const));
+        let err = a.clone().try_add(b).unwrap_err();
+        #[cfg(debug_assertions)]
+        assert!(std::format!("{err}").contains(file!()));
+        assert!(a.clone().try_add(a).is_ok());
+    }
+
     #[test]
     fn test_scalar_add_0d() {
         let dev: TestDevice = Default::default();
@@ -0,0 +1,55 @@
+#include "cuda_utils.cuh"
+
+// `strides` holds the strides of `x`, `scale`, `shift` and `out` one after another, where
+// the strides of `scale` & `shift` are already broadcast to the shape of `x`.
+
+extern "C" __global__ void affine_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *x,
+    const float *scale,
+    const float *shift,
+    float *out,
+    const size_t *strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int x_i = get_strided_index(i, num_dims, dims, strides);
+    unsigned int scale_i = get_strided_index(i, num_dims, dims, strides + num_dims);
+    unsigned int shift_i = get_strided_index(i, num_dims, dims, strides + 2 * num_dims);
+    unsigned int out_i = get_strided_index(i, num_dims, dims, strides + 3 * num_dims);
+
+    out[out_i] = fmaf(x[x_i], scale[scale_i], shift[shift_i]);
+}
+
+extern "C" __global__ void affine_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *x,
+    float *grad_x,
+    const float *scale,
+    float *grad_scale,
+    float *grad_shift,
+    const float *grad_out,
+    const size_t *strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int x_i = get_strided_index(i, num_dims, dims, strides);
+    unsigned int scale_i = get_strided_index(i, num_dims, dims, strides + num_dims);
+    unsigned int shift_i = get_strided_index(i, num_dims, dims, strides + 2 * num_dims);
+    unsigned int out_i = get_strided_index(i, num_dims, dims, strides + 3 * num_dims);
+
+    auto go = grad_out[out_i];
+    atomicAdd(grad_x + x_i, scale[scale_i] * go);
+    atomicAdd(grad_scale + scale_i, x[x_i] * go);
+    atomicAdd(grad_shift + shift_i, go);
+}
@@ -0,0 +1,58 @@
+use crate::{
+    shapes::{Axes, Dtype, ReduceShape, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+impl<E: Dtype> super::AffineKernel<E> for Cpu {
+    fn forward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, E>,
+        scale: &Self::Storage<S::Reduced, E>,
+        shift: &Self::Storage<S::Reduced, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>,
+    {
+        let mut out: Self::Storage<S, E> = StridedArray::new(x.shape)?;
+        let mut x_iter = x.iter();
+        let mut scale_iter = scale.iter_as(&x.shape);
+        let mut shift_iter = shift.iter_as(&x.shape);
+        let mut out_iter = out.iter_mut();
+        while let Some((o, (x, (a, b)))) = out_iter
+            .next()
+            .zip(x_iter.next().zip(scale_iter.next().zip(shift_iter.next())))
+        {
+            *o = *x * *a + *b;
+        }
+        Ok(out)
+    }
+
+    fn backward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, E>,
+        grad_x: &mut Self::Storage<S, E>,
+        scale: &Self::Storage<S::Reduced, E>,
+        grad_scale: &mut Self::Storage<S::Reduced, E>,
+        grad_shift: &mut Self::Storage<S::Reduced, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>,
+    {
+        let mut x_iter = x.iter();
+        let mut scale_iter = scale.iter_as(&x.shape);
+        let mut grad_x_iter = grad_x.iter_mut();
+        let mut grad_scale_iter = grad_scale.iter_mut_as(&x.shape);
+        let mut grad_shift_iter = grad_shift.iter_mut_as(&x.shape);
+        let mut grad_out_iter = grad_out.iter();
+        for _ in 0..x.shape.num_elements() {
+            let v = *x_iter.next().unwrap();
+            let a = *scale_iter.next().unwrap();
+            let go = *grad_out_iter.next().unwrap();
+            *grad_x_iter.next().unwrap() += a * go;
+            *grad_scale_iter.next().unwrap() += v * go;
+            *grad_shift_iter.next().unwrap() += go;
+        }
+        Ok(())
+    }
+}
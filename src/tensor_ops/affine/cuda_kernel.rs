@@ -0,0 +1,115 @@
+use crate::{
+    shapes::{Axes, BroadcastStridesTo, ReduceShape, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/affine.ptx"));
+const MODULE_NAME: &str = "affine";
+const FWD_FN_NAME: &str = "affine_forward";
+const BWD_FN_NAME: &str = "affine_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+/// Concatenates the strides of `x`, `scale`, `shift` & `out`, with the strides of `scale`
+/// & `shift` broadcast to the shape of `x`.
+fn affine_strides<S, Ax: Axes>(
+    x: &CudaArray<S, f32>,
+    scale: &CudaArray<S::Reduced, f32>,
+    shift: &CudaArray<S::Reduced, f32>,
+    out_strides: S::Concrete,
+) -> Vec<usize>
+where
+    S: Shape + ReduceShape<Ax>,
+{
+    let mut strides: Vec<usize> = x.strides.into();
+    let scale_strides = BroadcastStridesTo::<S, Ax>::broadcast_strides(&scale.shape, scale.strides);
+    strides.append(&mut scale_strides.into());
+    let shift_strides = BroadcastStridesTo::<S, Ax>::broadcast_strides(&shift.shape, shift.strides);
+    strides.append(&mut shift_strides.into());
+    strides.append(&mut out_strides.into());
+    strides
+}
+
+impl super::AffineKernel<f32> for Cuda {
+    fn forward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, f32>,
+        scale: &Self::Storage<S::Reduced, f32>,
+        shift: &Self::Storage<S::Reduced, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>,
+    {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = x.shape;
+        let strides = shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let all_strides = affine_strides::<S, Ax>(x, scale, shift, strides);
+        let all_strides: CudaSlice<usize> = self.dev.take_async(all_strides)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,               // const size_t numel,
+            S::NUM_DIMS,         // const size_t num_dims,
+            &dims,               // const size_t *dims,
+            x.data.as_ref(),     // const float *x,
+            scale.data.as_ref(), // const float *scale,
+            shift.data.as_ref(), // const float *shift,
+            &mut storage,        // float *out,
+            &all_strides,        // const size_t *strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn backward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, f32>,
+        grad_x: &mut Self::Storage<S, f32>,
+        scale: &Self::Storage<S::Reduced, f32>,
+        grad_scale: &mut Self::Storage<S::Reduced, f32>,
+        grad_shift: &mut Self::Storage<S::Reduced, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>,
+    {
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = x.shape.num_elements();
+
+        // the gradients have the same strides as the tensors they are for
+        let dims: CudaSlice<usize> = self.dev.take_async(x.shape.concrete().into())?;
+        let all_strides = affine_strides::<S, Ax>(x, scale, grad_shift, grad_out.strides);
+        let all_strides: CudaSlice<usize> = self.dev.take_async(all_strides)?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                               // const size_t numel,
+            S::NUM_DIMS,                         // const size_t num_dims,
+            &dims,                               // const size_t *dims,
+            x.data.as_ref(),                     // const float *x,
+            Arc::make_mut(&mut grad_x.data),     // float *grad_x,
+            scale.data.as_ref(),                 // const float *scale,
+            Arc::make_mut(&mut grad_scale.data), // float *grad_scale,
+            Arc::make_mut(&mut grad_shift.data), // float *grad_shift,
+            grad_out.data.as_ref(),              // const float *grad_out,
+            &all_strides,                        // const size_t *strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
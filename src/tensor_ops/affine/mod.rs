@@ -0,0 +1,173 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+use super::Device;
+
+pub trait AffineKernel<E: Dtype>: DeviceStorage {
+    fn forward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, E>,
+        scale: &Self::Storage<S::Reduced, E>,
+        shift: &Self::Storage<S::Reduced, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<S, Ax: Axes>(
+        &self,
+        x: &Self::Storage<S, E>,
+        grad_x: &mut Self::Storage<S, E>,
+        scale: &Self::Storage<S::Reduced, E>,
+        grad_scale: &mut Self::Storage<S::Reduced, E>,
+        grad_shift: &mut Self::Storage<S::Reduced, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>
+    where
+        S: Shape + ReduceShape<Ax>;
+}
+
+/// Scales `x` by `scale` and shifts it by `shift`, where `scale` & `shift` are broadcast
+/// along the axes `Ax`. Computes `x * scale.broadcast() + shift.broadcast()` in a single
+/// pass, without creating any broadcasted temporaries on the tape.
+///
+/// This is the per-channel affine transform at the end of normalization layers, and
+/// the feature-wise transform of FiLM layers.
+///
+/// Example for a per-channel scale & shift of `(B, C, H, W)` images:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank4<2, 3, 4, 4>, f32, _> = dev.ones();
+/// let scale = dev.tensor([1.0, 2.0, 3.0]);
+/// let shift = dev.tensor([0.5, 0.0, -0.5]);
+/// let r = x.affine::<Axes3<0, 2, 3>>(scale, shift);
+/// assert_eq!(r.array()[1][2], [[2.5; 4]; 4]);
+/// ```
+pub fn affine<Ax: Axes, S: Shape + ReduceShape<Ax>, E: Dtype, D: Device<E>, T: Tape<D>>(
+    x: Tensor<S, E, D, T>,
+    scale: Tensor<S::Reduced, E, D, T>,
+    shift: Tensor<S::Reduced, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    x.affine::<Ax>(scale, shift)
+}
+
+impl<S: Shape, E: Dtype, D: AffineKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [affine]
+    #[track_caller]
+    pub fn affine<Ax: Axes>(
+        self,
+        scale: Tensor<S::Reduced, E, D, T>,
+        shift: Tensor<S::Reduced, E, D, T>,
+    ) -> Self
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_affine::<Ax>(scale, shift).unwrap()
+    }
+
+    /// See [affine]
+    #[track_caller]
+    pub fn try_affine<Ax: Axes>(
+        self,
+        scale: Tensor<S::Reduced, E, D, T>,
+        shift: Tensor<S::Reduced, E, D, T>,
+    ) -> Result<Self, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        DeviceMismatch::check_same("affine", &self.device, &scale.device)?;
+        DeviceMismatch::check_same("affine", &self.device, &shift.device)?;
+        ShapeMismatch::check_same("affine", &scale, &shift)?;
+        // every axis of `scale` must match the axis of `x` it is broadcast into
+        let mut i = 0;
+        for j in 0..S::NUM_DIMS {
+            if !Ax::as_array().into_iter().any(|a| a == j as isize) {
+                ShapeMismatch::check_axes("affine", (&scale, i), (&self, j))?;
+                i += 1;
+            }
+        }
+
+        let (x, x_tape) = self.split_tape();
+        let (scale, scale_tape) = scale.split_tape();
+        let (shift, shift_tape) = shift.split_tape();
+        let mut tape = x_tape.merge(scale_tape).merge(shift_tape);
+        let storage = x
+            .device
+            .forward::<S, Ax>(&x.storage, &scale.storage, &shift.storage)?;
+        let out = x.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&x)?;
+        tape.try_alloc_grad(&scale)?;
+        tape.try_alloc_grad(&shift)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_x, grad_scale, grad_shift, grad_out) =
+                grads.three_muts_and_ref(&x, &scale, &shift, &phantom_out);
+            x.device.backward::<S, Ax>(
+                &x.storage,
+                grad_x,
+                &scale.storage,
+                grad_scale,
+                grad_shift,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_affine_matches_broadcast_mul_add() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let scale: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let shift: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+
+        let r1 = x
+            .trace()
+            .affine::<Axes2<0, 2>>(scale.trace(), shift.trace());
+        let r2 = x.trace() * scale.trace().broadcast() + shift.trace().broadcast();
+        assert_close(&r1.array(), &r2.array());
+
+        let g1 = r1.exp().mean().backward();
+        let g2 = r2.exp().mean().backward();
+        assert_close(&g1.get(&x).array(), &g2.get(&x).array());
+        assert_close(&g1.get(&scale).array(), &g2.get(&scale).array());
+        assert_close(&g1.get(&shift).array(), &g2.get(&shift).array());
+    }
+
+    #[test]
+    fn test_affine_last_axis() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.0, 1.0]]);
+        let scale = dev.tensor([2.0, -1.0, 0.5]);
+        let shift = dev.tensor([0.0, 1.0, -1.0]);
+        let r = x.trace().affine::<Axis<0>>(scale.trace(), shift.trace());
+        assert_eq!(r.array(), [[2.0, -1.0, 0.5], [-2.0, 1.0, -0.5]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [[2.0, -1.0, 0.5]; 2]);
+        assert_eq!(g.get(&scale).array(), [0.0, 2.0, 4.0]);
+        assert_eq!(g.get(&shift).array(), [2.0; 3]);
+    }
+
+    #[test]
+    fn test_affine_broadcasted_input() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<3, 2>, f32, _> = dev.tensor([1.0, -2.0]).broadcast();
+        let r = x.affine::<Axis<1>>(dev.tensor([1.0, 2.0, 3.0]), dev.tensor([0.0, 1.0, 2.0]));
+        assert_eq!(r.array(), [[1.0, -2.0], [3.0, -3.0], [5.0, -4.0]]);
+    }
+}
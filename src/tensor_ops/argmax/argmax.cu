@@ -0,0 +1,40 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles a single row along `ax`, and writes the index of its
+// max (or min) to the contiguous output. NaN is the largest and smallest value,
+// and ties go to the first index.
+
+extern "C" __global__ void argmax_forward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const int max,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    size_t *out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t n = dims[ax];
+    if (n == 0) {
+        out[row] = 0;
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+
+    size_t best = 0;
+    float best_value = inp[i_inp];
+    for (size_t j = 1; j < n && !isnan(best_value); j++) {
+        float v = inp[i_inp + j * inp_strides[ax]];
+        if (isnan(v) || (max && v > best_value) || (!max && v < best_value)) {
+            best = j;
+            best_value = v;
+        }
+    }
+    out[row] = best;
+}
@@ -0,0 +1,46 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+/// `true` for NaN, which isn't equal to itself.
+fn is_nan<E: PartialOrd>(x: E) -> bool {
+    x.partial_cmp(&x).is_none()
+}
+
+impl<E: Dtype> super::ArgMaxKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        max: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err> {
+        let mut idx: StridedArray<Dst, usize> = StridedArray::new(dst)?;
+        let n = inp.shape.concrete()[ax];
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        // the output doesn't have `ax`, so give it a stride of 0 there
+        let mut dst_strides: Vec<usize> = idx.strides.into();
+        dst_strides.insert(ax, 0);
+        let idx_buf = Arc::make_mut(&mut idx.data);
+        for_each_row(&dims, ax, [&inp_strides, &dst_strides], |[i_inp, i_dst]| {
+            let mut best = 0;
+            let mut best_value = inp.data[i_inp];
+            for j in 1..n {
+                if is_nan(best_value) {
+                    break;
+                }
+                let v = inp.data[i_inp + j * inp_strides[ax]];
+                if is_nan(v) || (max && v > best_value) || (!max && v < best_value) {
+                    best = j;
+                    best_value = v;
+                }
+            }
+            idx_buf[i_dst] = best;
+        });
+        Ok(idx)
+    }
+}
@@ -0,0 +1,53 @@
+use super::ArgMaxKernel;
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/argmax.ptx"));
+const MODULE_NAME: &str = "argmax";
+const FWD_FN_NAME: &str = "argmax_forward";
+const ALL_FN_NAMES: [&str; 1] = [FWD_FN_NAME];
+
+impl ArgMaxKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        max: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        // the rows of `inp` along `ax` are in the same order as the elements of `dst`
+        let num_rows = dst.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<usize>(num_rows)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            ax,                // const size_t ax,
+            max as i32,        // const int max,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // size_t *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+}
@@ -0,0 +1,172 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    shapes::{Axes, Dtype, HasShape, ReduceShape, ReduceStridesTo, Shape},
+    tensor::{DeviceStorage, Tensor},
+};
+
+pub trait ArgMaxKernel<E: Dtype>: DeviceStorage {
+    /// The index along `ax` of the largest (or smallest if `!max`) value of each row.
+    /// Ties go to the first index, and NaNs are larger and smaller than every number.
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        max: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: ArgMaxKernel<E>, T> Tensor<S, E, D, T> {
+    /// The index of the largest value along axis `Ax`, as a `usize` tensor on the same device.
+    /// If the largest value appears more than once, the first index is returned. A NaN
+    /// is counted as the largest value, like in pytorch.
+    ///
+    /// This isn't differentiable, so it borrows the tensor, and doesn't record anything on
+    /// its tape.
+    ///
+    /// **Panics** if the axis is empty.
+    ///
+    /// **Pytorch equivalent**: `t.argmax(dim=Ax)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let logits = dev.tensor([[0.1, 2.0, -1.0], [3.0, 0.0, 3.0]]);
+    /// let predictions = logits.argmax::<Axis<1>>();
+    /// assert_eq!(predictions.array(), [1, 0]);
+    ///
+    /// // accuracy
+    /// let labels = dev.tensor([1, 2]);
+    /// let correct = predictions.as_vec().into_iter().zip(labels.as_vec());
+    /// assert_eq!(correct.filter(|(p, l)| p == l).count(), 1);
+    /// ```
+    pub fn argmax<Ax: Axes<Array = [isize; 1]>>(&self) -> Tensor<S::Reduced, usize, D>
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_argmax::<Ax>().unwrap()
+    }
+
+    /// See [Tensor::argmax()]
+    pub fn try_argmax<Ax: Axes<Array = [isize; 1]>>(
+        &self,
+    ) -> Result<Tensor<S::Reduced, usize, D>, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        try_arg_reduce::<_, _, _, _, Ax>(self, true)
+    }
+
+    /// The index of the smallest value along axis `Ax`, as a `usize` tensor on the same device.
+    /// If the smallest value appears more than once, the first index is returned. A NaN
+    /// is counted as the smallest value, like in pytorch.
+    ///
+    /// This isn't differentiable, so it borrows the tensor, and doesn't record anything on
+    /// its tape.
+    ///
+    /// **Panics** if the axis is empty.
+    ///
+    /// **Pytorch equivalent**: `t.argmin(dim=Ax)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[0.1, 2.0, -1.0], [3.0, 0.0, 3.0]]);
+    /// assert_eq!(t.argmin::<Axis<1>>().array(), [2, 1]);
+    /// assert_eq!(t.argmin::<Axis<0>>().array(), [0, 1, 0]);
+    /// ```
+    pub fn argmin<Ax: Axes<Array = [isize; 1]>>(&self) -> Tensor<S::Reduced, usize, D>
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_argmin::<Ax>().unwrap()
+    }
+
+    /// See [Tensor::argmin()]
+    pub fn try_argmin<Ax: Axes<Array = [isize; 1]>>(
+        &self,
+    ) -> Result<Tensor<S::Reduced, usize, D>, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        try_arg_reduce::<_, _, _, _, Ax>(self, false)
+    }
+}
+
+fn try_arg_reduce<S: ReduceShape<Ax>, E: Dtype, D: ArgMaxKernel<E>, T, Ax: Axes>(
+    t: &Tensor<S, E, D, T>,
+    max: bool,
+) -> Result<Tensor<S::Reduced, usize, D>, D::Err> {
+    let ax = Ax::as_array().into_iter().next().unwrap() as usize;
+    assert!(
+        t.shape().concrete()[ax] > 0,
+        "can't take the argmax or argmin of the empty axis {ax}"
+    );
+    let dst: S::Reduced = ReduceStridesTo::<S::Reduced, Ax>::reduced(t.shape());
+    let idx = ArgMaxKernel::<E>::forward(&t.device, ax, max, dst, &t.storage)?;
+    Ok(t.device.upgrade(idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_argmax_argmin_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -3.0, 4.0, 4.0, -3.0]);
+        assert_eq!(t.argmax::<Axis<0>>().array(), 2);
+        assert_eq!(t.argmin::<Axis<0>>().array(), 1);
+
+        let t = dev.tensor([1.0, f32::NAN, 4.0, f32::NAN]);
+        assert_eq!(t.argmax::<Axis<0>>().array(), 1);
+        assert_eq!(t.argmin::<Axis<0>>().array(), 1);
+    }
+
+    #[test]
+    fn test_argmax_3d_matches_max() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let i = t.argmax::<Axis<1>>().array();
+        let m = t.clone().max::<Rank2<2, 3>, _>().array();
+        let j = t.argmin::<Axis<2>>().array();
+        let n = t.clone().min::<Rank2<2, 5>, _>().array();
+        let t = t.array();
+        for b in 0..2 {
+            for c in 0..3 {
+                assert_eq!(t[b][i[b][c]][c], m[b][c]);
+            }
+            for r in 0..5 {
+                assert_eq!(t[b][r][j[b][r]], n[b][r]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_argmax_broadcasted_and_runtime_shapes() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.tensor([1.0, 5.0, 2.0, 0.0]).broadcast();
+        assert_eq!(t.argmax::<Axis<1>>().array(), [1; 3]);
+        assert_eq!(t.argmax::<Axis<0>>().array(), [0; 4]);
+
+        let t: Tensor<(usize, usize), f32, _> =
+            dev.tensor_from_vec(std::vec![0.0, 1.0, 2.0, 9.0, 1.0, 0.0], (2, 3));
+        let i = t.argmax::<Axis<1>>();
+        assert_eq!(i.shape(), &(2,));
+        assert_eq!(i.as_vec(), [2, 0]);
+    }
+
+    #[test]
+    fn test_argmax_keeps_tape() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[0.0, 1.0], [2.0, -1.0]]);
+        let logits = t.trace();
+        assert_eq!(logits.argmax::<Axis<1>>().array(), [1, 0]);
+        let g = logits.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0; 2]; 2]);
+    }
+}
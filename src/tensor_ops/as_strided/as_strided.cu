@@ -0,0 +1,33 @@
+#include "cuda_utils.cuh"
+
+extern "C" __global__ void as_strided_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides,
+    const float *inp,
+    float *out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    out[i] = inp[get_strided_index(i, num_dims, dims, strides)];
+}
+
+extern "C" __global__ void as_strided_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *strides,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    atomicAdd(grad_inp + get_strided_index(i, num_dims, dims, strides), grad_out[i]);
+}
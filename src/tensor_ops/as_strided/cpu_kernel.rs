@@ -0,0 +1,36 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::cpu::{Cpu, StridedArray};
+
+use std::sync::Arc;
+
+impl<E: Dtype> super::AsStridedKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        strides: Dst::Concrete,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        super::check_strided_bounds(&dst, &strides, inp.data.len())?;
+        Ok(StridedArray {
+            data: inp.data.clone(),
+            shape: dst,
+            strides,
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        _strides: Dst::Concrete,
+    ) -> Result<(), Self::Err> {
+        // grad_out shares the layout of the input buffer, so elements referred to
+        // multiple times have already been summed into a single location.
+        debug_assert_eq!(grad_inp.data.len(), grad_out.data.len());
+        let grad_inp = Arc::make_mut(&mut grad_inp.data);
+        for (i, o) in grad_inp.iter_mut().zip(grad_out.data.iter()) {
+            *i += *o;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/as_strided.ptx"));
+const MODULE_NAME: &str = "as_strided";
+const FWD_FN_NAME: &str = "as_strided_forward";
+const BWD_FN_NAME: &str = "as_strided_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::AsStridedKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        dst: Dst,
+        strides: Dst::Concrete,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err> {
+        super::check_strided_bounds(&dst, &strides, inp.data.len())?;
+
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        // NOTE: unlike Cpu, the output is copied into a contiguous buffer, since
+        // many cuda kernels expect contiguous inputs.
+        let numel = dst.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(dst.concrete().into())?;
+        let buf_strides: CudaSlice<usize> = self.dev.take_async(strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            Dst::NUM_DIMS,     // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            &buf_strides,      // const size_t *strides,
+            inp.data.as_ref(), // const float *inp,
+            &mut storage,      // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+        strides: Dst::Concrete,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            super::AsStridedKernel::<f32>::backward(
+                &self.cpu,
+                &mut cpu_grad_inp,
+                &cpu_grad_out,
+                strides,
+            )?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = grad_out.shape.num_elements();
+
+        let dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let buf_strides: CudaSlice<usize> = self.dev.take_async(strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            Dst::NUM_DIMS,                     // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            &buf_strides,                      // const size_t *strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
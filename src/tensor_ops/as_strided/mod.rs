@@ -0,0 +1,159 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait AsStridedKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        dst: Dst,
+        strides: Dst::Concrete,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+        strides: Dst::Concrete,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Returns an error if an index of `shape` with `strides` is outside of a buffer with `len`
+/// elements. The error compares the number of elements the view needs with `len`.
+pub(super) fn check_strided_bounds<S: Shape>(
+    shape: &S,
+    strides: &S::Concrete,
+    len: usize,
+) -> Result<(), ShapeMismatch> {
+    let dims = shape.concrete();
+    if dims.into_iter().any(|d| d == 0) {
+        return Ok(());
+    }
+    let mut max_idx = 0;
+    for i in 0..S::NUM_DIMS {
+        max_idx += (dims[i] - 1) * strides[i];
+    }
+    if max_idx < len {
+        return Ok(());
+    }
+    ShapeMismatch::check_axes("as_strided", (&(max_idx + 1,), 0), (&(len,), 0))
+}
+
+/// Creates a view into the underlying buffer of a tensor with a new shape and strides.
+/// Strides are in terms of the underlying buffer, not of the shape of the tensor, so
+/// multiple elements of the output may refer to the same element of the input (e.g. sliding windows).
+///
+/// Gradients of elements referred to multiple times are summed.
+///
+/// **Panics** if any index of `shape` would reach outside of the underlying buffer. See
+/// [AsStrided::try_as_strided] for a version that returns a [ShapeMismatch] error instead.
+///
+/// Sliding windows of size 3 over 5 elements:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+/// let r = a.as_strided((Const::<3>, Const::<3>), [1, 1]);
+/// assert_eq!(r.array(), [[1.0, 2.0, 3.0], [2.0, 3.0, 4.0], [3.0, 4.0, 5.0]]);
+/// ```
+///
+/// On [Cpu] this does not copy any data.
+pub trait AsStrided: HasErr + HasShape {
+    #[allow(clippy::wrong_self_convention)]
+    fn as_strided<Dst: Shape>(self, shape: Dst, strides: Dst::Concrete) -> Self::WithShape<Dst> {
+        self.try_as_strided(shape, strides).unwrap()
+    }
+    fn try_as_strided<Dst: Shape>(
+        self,
+        shape: Dst,
+        strides: Dst::Concrete,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: AsStridedKernel<E>, T: Tape<D>> AsStrided for Tensor<S, E, D, T> {
+    fn try_as_strided<Dst: Shape>(
+        self,
+        shape: Dst,
+        strides: Dst::Concrete,
+    ) -> Result<Self::WithShape<Dst>, Self::Err> {
+        let (inp, mut tape) = self.split_tape();
+        let out = inp
+            .device
+            .upgrade(inp.device.forward(&inp.storage, shape, strides)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(grad_inp, grad_out, strides)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_strides_of_permuted() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, f32, _> = dev.zeros();
+        assert_eq!(t.strides(), [12, 4, 1]);
+        assert!(t.is_contiguous());
+        let r = t.permute::<Rank3<4, 2, 3>, _>();
+        assert!(!r.is_contiguous());
+    }
+
+    #[test]
+    fn test_as_strided_sliding_window() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+        let r = t.trace().as_strided((Const::<3>, Const::<3>), [1, 1]);
+        assert_eq!(
+            r.array(),
+            [[1.0, 2.0, 3.0], [2.0, 3.0, 4.0], [3.0, 4.0, 5.0]]
+        );
+        let g = r.exp().sum().backward();
+        let e = [1.0f32, 2.0, 3.0, 4.0, 5.0].map(f32::exp);
+        assert_close(
+            &g.get(&t).array(),
+            &[e[0], 2.0 * e[1], 3.0 * e[2], 2.0 * e[3], e[4]],
+        );
+    }
+
+    #[test]
+    fn test_as_strided_non_overlapping() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let r = t.trace().as_strided((Const::<2>,), [4]);
+        assert_eq!(r.array(), [1.0, 5.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_as_strided_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<5>, f32, _> = dev.zeros();
+        let _ = t.as_strided((Const::<3>, Const::<3>), [2, 1]);
+    }
+
+    #[test]
+    fn test_try_as_strided_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<5>, f32, _> = dev.zeros();
+        let r = t.clone().try_as_strided((Const::<3>, Const::<3>), [2, 1]);
+        let err = std::format!("{}", r.unwrap_err());
+        assert!(err.contains("as_strided"), "{err}");
+        assert!(
+            err.contains("has size 7, but rhs axis 0 has size 5"),
+            "{err}"
+        );
+        assert!(t.try_as_strided((Const::<3>,), [2]).is_ok());
+    }
+}
@@ -3,8 +3,15 @@ mod cpu_kernel;
 #[cfg(feature = "cuda")]
 mod cuda_kernel;
 
-use super::ops::{try_binary_op, BinaryKernel};
-use crate::{gradients::*, shapes::*, tensor::Tensor};
+use super::{
+    checks::CheckKernel,
+    ops::{try_binary_op, BinaryKernel},
+};
+use crate::{
+    gradients::*,
+    shapes::*,
+    tensor::{Invariant, Tensor},
+};
 
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
@@ -17,13 +24,21 @@ pub struct BCEKernelOp;
 ///
 /// # Inputs
 /// - `logits` - unnormalized inputs. **NOT** output of sigmoid
-/// - `target_probs` - target values between 0 and 1.
+/// - `target_probs` - target values between 0 and 1. In checked mode, returns an error if
+///   they are not.
 ///
 /// # Numerically Stable Derivation
 ///
 /// See <https://www.tensorflow.org/api_docs/python/tf/nn/sigmoid_cross_entropy_with_logits>
 /// for more information on this.
-pub fn bce_with_logits<S: Shape, E: Dtype, D: BinaryKernel<BCEKernelOp, E>, LTape, RTape>(
+#[track_caller]
+pub fn bce_with_logits<
+    S: Shape,
+    E: Dtype,
+    D: BinaryKernel<BCEKernelOp, E> + CheckKernel<E>,
+    LTape,
+    RTape,
+>(
     logits: Tensor<S, E, D, LTape>,
     probs: Tensor<S, E, D, RTape>,
 ) -> Tensor<S, E, D, LTape>
@@ -34,8 +49,11 @@ where
     logits.bce_with_logits(probs)
 }
 
-impl<S: Shape, E: Dtype, D: BinaryKernel<BCEKernelOp, E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
+impl<S: Shape, E: Dtype, D: BinaryKernel<BCEKernelOp, E> + CheckKernel<E>, LTape: Tape<D>>
+    Tensor<S, E, D, LTape>
+{
     /// See [bce_with_logits]
+    #[track_caller]
     pub fn bce_with_logits<RTape: Tape<D>>(self, prob: Tensor<S, E, D, RTape>) -> Self
     where
         LTape: Merge<RTape>,
@@ -43,12 +61,14 @@ impl<S: Shape, E: Dtype, D: BinaryKernel<BCEKernelOp, E>, LTape: Tape<D>> Tensor
         self.try_bce_with_logits(prob).unwrap()
     }
     /// See [bce_with_logits]
+    #[track_caller]
     pub fn try_bce_with_logits<RTape>(self, prob: Tensor<S, E, D, RTape>) -> Result<Self, D::Err>
     where
         RTape: Tape<D>,
         LTape: Merge<RTape>,
     {
-        try_binary_op(BCEKernelOp, self, prob)
+        prob.try_check("bce_with_logits", "target_probs", Invariant::Probability)?;
+        try_binary_op("bce_with_logits", BCEKernelOp, self, prob)
     }
 }
 
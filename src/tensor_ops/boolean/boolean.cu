@@ -35,6 +35,34 @@ extern "C" __global__ void boolean_not(
     out[i] = !(bool)(inp[i]);
 }
 
+// Each thread computes one element of the contiguous output. `dims` and `strides` are
+// permuted so that the `chunk_len` elements reduced into each output are next to each other.
+#define BOOLEAN_REDUCE(NAME, INIT, OP) \
+extern "C" __global__ void NAME( \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t chunk_len, \
+    const size_t *dims, \
+    const bool *inp, \
+    const size_t *strides, \
+    bool *out \
+) { \
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (out_i >= numel) { \
+        return; \
+    } \
+\
+    bool acc = INIT; \
+    for (size_t j = 0; j < chunk_len; j++) { \
+        unsigned int inp_i = get_strided_index(out_i * chunk_len + j, num_dims, dims, strides); \
+        acc = acc OP (bool)(inp[inp_i]); \
+    } \
+    out[out_i] = acc; \
+}
+
 BOOLEAN_OP(boolean_and, &&);
 BOOLEAN_OP(boolean_or, ||);
 BOOLEAN_OP(boolean_xor, ^);
+
+BOOLEAN_REDUCE(boolean_any, false, ||);
+BOOLEAN_REDUCE(boolean_all, true, &&);
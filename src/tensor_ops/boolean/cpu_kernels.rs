@@ -3,7 +3,7 @@ use crate::{
         cpu::{LendingIterator, StridedArray},
         Cpu, HasErr,
     },
-    shapes::{Shape, Unit},
+    shapes::{Axes, BroadcastShapeTo, BroadcastStridesTo, ReduceShapeTo, Shape, Unit},
 };
 
 use super::BooleanKernel;
@@ -24,6 +24,25 @@ impl Cpu {
         }
         Ok(out)
     }
+
+    fn eval_reduce<Src: Shape, Dst: Shape, Ax: Axes, O: Fn(bool, bool) -> bool>(
+        &self,
+        dst: Dst,
+        init: bool,
+        op: O,
+        inp: &StridedArray<Src, bool>,
+    ) -> Result<StridedArray<Dst, bool>, <Self as HasErr>::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out: StridedArray<Dst, bool> = StridedArray::try_new_with(dst, init)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some((o, i)) = out_iter.next().zip(inp_iter.next()) {
+            *o = op(*o, *i);
+        }
+        Ok(out)
+    }
 }
 
 impl BooleanKernel for Cpu {
@@ -61,4 +80,41 @@ impl BooleanKernel for Cpu {
     ) -> Result<Self::Storage<S, bool>, Self::Err> {
         self.eval_binary(|l, r| l ^ r, lhs, rhs)
     }
+
+    fn any<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        self.eval_reduce(dst, false, |o, i| o || i, inp)
+    }
+
+    fn all<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        self.eval_reduce(dst, true, |o, i| o && i, inp)
+    }
+
+    fn broadcast<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: BroadcastShapeTo<Dst, Ax>,
+    {
+        Ok(StridedArray {
+            data: inp.data.clone(),
+            shape: dst,
+            strides: inp.shape.broadcast_strides(inp.strides),
+        })
+    }
 }
@@ -6,7 +6,14 @@ use std::sync::Arc;
 
 const MODULE_NAME: &str = "boolean";
 const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/boolean.ptx"));
-const ALL_FN_NAMES: [&str; 4] = ["boolean_not", "boolean_and", "boolean_or", "boolean_xor"];
+const ALL_FN_NAMES: [&str; 6] = [
+    "boolean_not",
+    "boolean_and",
+    "boolean_or",
+    "boolean_xor",
+    "boolean_any",
+    "boolean_all",
+];
 
 impl Cuda {
     fn call_binary<S: Shape>(
@@ -51,6 +58,61 @@ impl Cuda {
             strides,
         })
     }
+
+    fn call_reduce<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        fn_name: &str,
+        dst: Dst,
+        inp: &CudaArray<Src, bool>,
+    ) -> Result<CudaArray<Dst, bool>, <Self as HasErr>::Err> {
+        if !self.dev.has_func(MODULE_NAME, fn_name) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = dst.num_elements();
+        let chunk_len = inp.shape.num_elements().checked_div(numel).unwrap_or(0);
+
+        // move the reduced axes last, keeping the order of the others. Unlike sums,
+        // broadcasted axes are kept, so every output has exactly `chunk_len` inputs.
+        let mut axes: std::vec::Vec<(bool, usize, usize)> = inp
+            .shape
+            .concrete()
+            .into_iter()
+            .zip(inp.strides)
+            .map(|(d, s)| (false, d, s))
+            .collect();
+        for i in Ax::as_array().into_iter() {
+            axes[i as usize].0 = true;
+        }
+        axes.sort_by_key(|x| x.0);
+        let (dims, strides): (std::vec::Vec<usize>, std::vec::Vec<usize>) =
+            axes.into_iter().map(|(_, d, s)| (d, s)).unzip();
+
+        // TODO: modify this to be `self.dev.alloc_zeros_async(numel)?` once cudarc implements
+        // ValidAsZeroBits for bool
+        let mut storage = self.dev.take_async(std::vec![false; numel])?;
+        let dims: CudaSlice<usize> = self.dev.take_async(dims)?;
+        let strides: CudaSlice<usize> = self.dev.take_async(strides)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, fn_name).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            chunk_len,         // const size_t chunk_len,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const bool *inp,
+            &strides,          // const size_t *strides,
+            &mut storage,      // bool *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
 }
 
 impl BooleanKernel for Cuda {
@@ -107,4 +169,41 @@ impl BooleanKernel for Cuda {
     ) -> Result<Self::Storage<S, bool>, Self::Err> {
         self.call_binary("boolean_xor", lhs, rhs)
     }
+
+    fn any<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        self.call_reduce::<_, _, Ax>("boolean_any", dst, inp)
+    }
+
+    fn all<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        self.call_reduce::<_, _, Ax>("boolean_all", dst, inp)
+    }
+
+    fn broadcast<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: BroadcastShapeTo<Dst, Ax>,
+    {
+        Ok(CudaArray {
+            data: inp.data.clone(),
+            shape: dst,
+            strides: inp.shape.broadcast_strides(inp.strides),
+        })
+    }
 }
@@ -4,14 +4,15 @@ mod cpu_kernels;
 mod cuda_kernels;
 
 use crate::{
+    gradients::NoneTape,
     prelude::{OnesTensor, Tensor, ZerosTensor},
     shapes::*,
-    tensor::DeviceStorage,
+    tensor::{DeviceMismatch, DeviceStorage},
 };
 
 use std::ops::{BitAnd, BitOr, BitXor, Not};
 
-use super::Device;
+use super::{BroadcastTo, Device};
 
 pub trait BooleanKernel: DeviceStorage + OnesTensor<bool> + ZerosTensor<bool> {
     fn not<S: Shape>(
@@ -36,6 +37,30 @@ pub trait BooleanKernel: DeviceStorage + OnesTensor<bool> + ZerosTensor<bool> {
         lhs: &Self::Storage<S, bool>,
         rhs: &Self::Storage<S, bool>,
     ) -> Result<Self::Storage<S, bool>, Self::Err>;
+
+    fn any<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+
+    fn all<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+
+    fn broadcast<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, bool>,
+    ) -> Result<Self::Storage<Dst, bool>, Self::Err>
+    where
+        Src: BroadcastShapeTo<Dst, Ax>;
 }
 
 fn scalar_and<D: BooleanKernel, S: Shape>(
@@ -92,8 +117,16 @@ macro_rules! boolean_op_impl {
         impl<S: Shape, D: BooleanKernel> $op for Tensor<S, bool, D> {
             type Output = Self;
 
+            #[track_caller]
             fn $op_method(self, rhs: Self) -> Self {
-                assert_eq!(self.shape(), rhs.shape());
+                if let Err(e) =
+                    DeviceMismatch::check_same(stringify!($op_method), &self.device, &rhs.device)
+                {
+                    panic!("{e}");
+                }
+                if let Err(e) = ShapeMismatch::check_same(stringify!($op_method), &self, &rhs) {
+                    panic!("{e}");
+                }
                 self.device.upgrade(
                     self.device
                         .$binary_kernel_method(&self.storage, &rhs.storage)
@@ -105,8 +138,16 @@ macro_rules! boolean_op_impl {
         impl<S: Shape, D: BooleanKernel> $op for &Tensor<S, bool, D> {
             type Output = Tensor<S, bool, D>;
 
+            #[track_caller]
             fn $op_method(self, rhs: Self) -> Self::Output {
-                assert_eq!(self.shape(), rhs.shape());
+                if let Err(e) =
+                    DeviceMismatch::check_same(stringify!($op_method), &self.device, &rhs.device)
+                {
+                    panic!("{e}");
+                }
+                if let Err(e) = ShapeMismatch::check_same(stringify!($op_method), self, rhs) {
+                    panic!("{e}");
+                }
                 self.device.upgrade(
                     self.device
                         .$binary_kernel_method(&self.storage, &rhs.storage)
@@ -260,9 +301,100 @@ pub fn bool_xor<S: Shape, E: Dtype, D: Device<E>>(
     lhs ^ rhs
 }
 
+impl<S: Shape, D: BooleanKernel> Tensor<S, bool, D> {
+    /// Whether any value along the axes `Ax` is `true`. **Pytorch equivalent**: `t.any(Ax)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a = dev.tensor([[false, true, false], [false, false, false]]);
+    /// assert_eq!(a.clone().any::<Rank1<2>, _>().array(), [true, false]);
+    /// assert_eq!(a.any::<Rank0, _>().array(), true);
+    /// ```
+    pub fn any<Dst: Shape, Ax: Axes>(self) -> Tensor<Dst, bool, D>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_any().unwrap()
+    }
+
+    /// Fallible version of [Tensor::any()]
+    pub fn try_any<Dst: Shape, Ax: Axes>(self) -> Result<Tensor<Dst, bool, D>, D::Err>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        Ok(self.device.upgrade(self.device.any(dst, &self.storage)?))
+    }
+
+    /// Whether all values along the axes `Ax` are `true`. **Pytorch equivalent**: `t.all(Ax)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a = dev.tensor([[true, true, true], [true, false, true]]);
+    /// assert_eq!(a.clone().all::<Rank1<2>, _>().array(), [true, false]);
+    /// assert_eq!(a.all::<Rank1<3>, _>().array(), [true, false, true]);
+    /// ```
+    pub fn all<Dst: Shape, Ax: Axes>(self) -> Tensor<Dst, bool, D>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_all().unwrap()
+    }
+
+    /// Fallible version of [Tensor::all()]
+    pub fn try_all<Dst: Shape, Ax: Axes>(self) -> Result<Tensor<Dst, bool, D>, D::Err>
+    where
+        S: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        Ok(self.device.upgrade(self.device.all(dst, &self.storage)?))
+    }
+}
+
+/// Broadcasting a boolean tensor only changes its strides, so masks can be combined with
+/// `&`, `|` and `^` after broadcasting them to the same shape:
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// // which keys are real tokens for each sequence in the batch
+/// let padding = dev.tensor([[true, true, true], [true, true, false]]);
+/// // which keys each query can attend to
+/// let causal = dev.tensor([[true, false, false], [true, true, false], [true, true, true]]);
+///
+/// let mask: Tensor<Rank3<2, 3, 3>, bool, _> =
+///     padding.broadcast::<_, Axis<1>>() & causal.broadcast::<_, Axis<0>>();
+/// assert_eq!(mask.array()[1][2], [true, true, false]);
+/// ```
+impl<S: Shape, D: BooleanKernel> BroadcastTo for Tensor<S, bool, D, NoneTape> {
+    fn try_broadcast_like<Dst: Shape, Ax: Axes>(
+        self,
+        dst: &Dst,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: BroadcastShapeTo<Dst, Ax>,
+    {
+        // every axis of the input must match the axis of `dst` it is placed in
+        let mut i = 0;
+        for j in 0..Dst::NUM_DIMS {
+            if !Ax::as_array().into_iter().any(|a| a == j as isize) {
+                ShapeMismatch::check_axes("broadcast", (&self, i), (dst, j))?;
+                i += 1;
+            }
+        }
+        Ok(self
+            .device
+            .upgrade(self.device.broadcast(*dst, &self.storage)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{tensor::*, tests::*};
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
 
     const TRUTH_TABLE_1: [bool; 4] = [false, false, true, true];
     const TRUTH_TABLE_2: [bool; 4] = [false, true, false, true];
@@ -320,4 +452,58 @@ mod tests {
         assert_eq!(r2.array(), (!&a).array());
         assert_eq!(r3.array(), a.array());
     }
+
+    #[test]
+    fn test_boolean_any_all() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[false, true, true], [false, false, true]]);
+        assert_eq!(a.clone().any::<Rank1<2>, _>().array(), [true, true]);
+        assert_eq!(a.clone().all::<Rank1<2>, _>().array(), [false, false]);
+        assert_eq!(a.clone().any::<Rank1<3>, _>().array(), [false, true, true]);
+        assert_eq!(a.clone().all::<Rank1<3>, _>().array(), [false, false, true]);
+        assert!(a.clone().any::<Rank0, _>().array());
+        assert!(!a.all::<Rank0, _>().array());
+    }
+
+    #[test]
+    fn test_boolean_broadcast_masks() {
+        let dev: TestDevice = Default::default();
+        let padding = dev.tensor([[true, true, false], [true, false, false]]);
+        let causal = dev.tensor([
+            [true, false, false],
+            [true, true, false],
+            [true, true, true],
+        ]);
+        let mask: Tensor<Rank3<2, 3, 3>, bool, _> =
+            padding.broadcast::<_, Axis<1>>() & causal.broadcast::<_, Axis<0>>();
+        assert_eq!(
+            mask.array(),
+            [
+                [
+                    [true, false, false],
+                    [true, true, false],
+                    [true, true, false]
+                ],
+                [
+                    [true, false, false],
+                    [true, false, false],
+                    [true, false, false]
+                ],
+            ]
+        );
+        // the reductions read the broadcasted strides
+        assert_eq!(
+            mask.any::<Rank2<2, 3>, Axis<1>>().array(),
+            [[true, true, false], [true, false, false]]
+        );
+    }
+
+    #[test]
+    fn test_boolean_broadcast_runtime_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize,), bool, _> = dev.ones_like(&(3,));
+        assert!(a.clone().try_broadcast_like::<_, Axis<0>>(&(2, 4)).is_err());
+        let r = a.try_broadcast_like::<_, Axis<0>>(&(2, 3)).unwrap();
+        assert_eq!(r.as_vec(), std::vec![true; 6]);
+    }
 }
@@ -36,6 +36,7 @@ pub trait BroadcastTo: HasErr + HasShape {
     /// // broadcast axis 0 and axis 2
     /// let _ = a.clone().broadcast::<Rank4<1, 3, 5, 7>, _>();
     /// ```
+    #[track_caller]
     fn broadcast<Dst: Shape + Default, Ax: Axes>(self) -> Self::WithShape<Dst>
     where
         Self::Shape: BroadcastShapeTo<Dst, Ax>,
@@ -43,6 +44,7 @@ pub trait BroadcastTo: HasErr + HasShape {
         self.try_broadcast_like(&Default::default()).unwrap()
     }
     /// Fallible version of [BroadcastTo::broadcast]
+    #[track_caller]
     fn try_broadcast<Dst: Shape + Default, Ax: Axes>(
         self,
     ) -> Result<Self::WithShape<Dst>, Self::Err>
@@ -52,6 +54,7 @@ pub trait BroadcastTo: HasErr + HasShape {
         self.try_broadcast_like(&Default::default())
     }
     /// Same as [BroadcastTo::broadcast], but the target shape is given
+    #[track_caller]
     fn broadcast_like<Dst: Shape, Ax: Axes>(self, dst: &Dst) -> Self::WithShape<Dst>
     where
         Self::Shape: BroadcastShapeTo<Dst, Ax>,
@@ -68,6 +71,7 @@ pub trait BroadcastTo: HasErr + HasShape {
 }
 
 impl<S: Shape, E: Dtype, D: BroadcastKernel<E>, T: Tape<D>> BroadcastTo for Tensor<S, E, D, T> {
+    #[track_caller]
     fn try_broadcast_like<Dst: Shape, Ax: Axes>(
         self,
         dst: &Dst,
@@ -75,6 +79,15 @@ impl<S: Shape, E: Dtype, D: BroadcastKernel<E>, T: Tape<D>> BroadcastTo for Tens
     where
         Self::Shape: BroadcastShapeTo<Dst, Ax>,
     {
+        // every axis of the input must match the axis of `dst` it is placed in
+        let mut i = 0;
+        for j in 0..Dst::NUM_DIMS {
+            if !Ax::as_array().into_iter().any(|a| a == j as isize) {
+                ShapeMismatch::check_axes("broadcast", (&self, i), (dst, j))?;
+                i += 1;
+            }
+        }
+
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.upgrade(inp.device.forward(*dst, &inp.storage)?);
         let phantom_out = out.clone();
@@ -135,6 +148,16 @@ mod tests {
         let _: Tensor<Rank4<3, 5, 7, 9>, f32, _> = dev.zeros::<Rank1<9>>().broadcast();
     }
 
+    #[test]
+    fn test_broadcast_runtime_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize,), f32, _> = dev.zeros_like(&(3,));
+        let r = a.clone().try_broadcast_like::<_, Axis<0>>(&(2, 4));
+        assert!(r.is_err());
+        let r = a.try_broadcast_like::<_, Axis<0>>(&(2, 3));
+        assert_eq!(r.unwrap().shape(), &(2, 3));
+    }
+
     #[test]
     fn test_broadcast_backwards() {
         let dev: TestDevice = Default::default();
@@ -0,0 +1,39 @@
+#include "cuda_utils.cuh"
+
+// Must match the order of `Invariant`
+enum Invariant {
+    Finite = 0,
+    NonNegative = 1,
+    Probability = 2,
+};
+
+__device__ bool holds(const size_t invariant, const float x) {
+    switch (invariant) {
+        case Finite: return isfinite(x);
+        case NonNegative: return x >= 0.0;
+        case Probability: return x >= 0.0 && x <= 1.0;
+        default: return false;
+    }
+}
+
+// Sets `failed` if any element of `inp` doesn't satisfy `invariant`.
+// All threads that find such an element write the same value, so no atomics are needed.
+extern "C" __global__ void check_all(
+    const size_t invariant,
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    const size_t *strides,
+    bool *failed
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(i, num_dims, dims, strides);
+    if (!holds(invariant, inp[inp_i])) {
+        *failed = true;
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{
+    shapes::Shape,
+    tensor::{cpu::LendingIterator, Cpu, Invariant},
+};
+
+use std::sync::atomic::Ordering;
+
+impl super::CheckKernel<f32> for Cpu {
+    fn is_checked(&self) -> bool {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    fn all<S: Shape>(
+        &self,
+        invariant: Invariant,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<bool, Self::Err> {
+        let mut iter = inp.iter();
+        while let Some(x) = iter.next() {
+            if !invariant.holds(*x) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    shapes::Shape,
+    tensor::{Cuda, Invariant},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::atomic::Ordering, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/checks.ptx"));
+const MODULE_NAME: &str = "checks";
+const FN_NAME: &str = "check_all";
+const ALL_FN_NAMES: [&str; 1] = [FN_NAME];
+
+impl super::CheckKernel<f32> for Cuda {
+    fn is_checked(&self) -> bool {
+        self.checked.load(Ordering::Relaxed)
+    }
+
+    fn all<S: Shape>(
+        &self,
+        invariant: Invariant,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<bool, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = inp.shape.num_elements();
+        if numel == 0 {
+            return Ok(true);
+        }
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let mut failed = self.dev.take_async(std::vec![false])?;
+
+        let f = self.dev.get_func(MODULE_NAME, FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            invariant as usize, // const size_t invariant,
+            numel,              // const size_t numel,
+            S::NUM_DIMS,        // const size_t num_dims,
+            &dims,              // const size_t *dims,
+            inp.data.as_ref(),  // const float *inp,
+            &strides,           // const size_t *strides,
+            &mut failed,        // bool *failed
+        );
+        unsafe { f.launch_async(cfg, params) }?;
+
+        let failed: Vec<bool> = failed.try_into()?;
+        Ok(!failed[0])
+    }
+}
@@ -0,0 +1,142 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    shapes::*,
+    tensor::{CheckFailed, DeviceStorage, Invariant, Tensor},
+};
+
+pub trait CheckKernel<E: Unit>: DeviceStorage {
+    /// Whether ops should check their inputs & outputs, see [crate::tensor::Cpu::set_checked()].
+    fn is_checked(&self) -> bool;
+
+    /// Whether every element of `inp` satisfies `invariant`.
+    fn all<S: Shape>(
+        &self,
+        invariant: Invariant,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<bool, Self::Err>;
+}
+
+/// Returns [CheckFailed] if any element of `t` doesn't satisfy `invariant`, and `t` otherwise.
+/// The tape of `t` is kept, so this can be put anywhere in a graph. The check is always done,
+/// no matter if checked mode is enabled on the device (see [crate::tensor::Cpu::set_checked()]).
+///
+/// The check is a reduction done on the device of `t`, so only a single boolean
+/// is copied back to the host.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([0.5, 1.0, f32::NAN]);
+/// assert!(t.clone().try_assert_all(Invariant::NonNegative).is_err());
+/// let t = t.nans_to(0.0).assert_all(Invariant::Probability);
+/// ```
+#[track_caller]
+pub fn assert_all<S: Shape, E: Unit, D: CheckKernel<E>, T>(
+    t: Tensor<S, E, D, T>,
+    invariant: Invariant,
+) -> Tensor<S, E, D, T> {
+    t.assert_all(invariant)
+}
+
+impl<S: Shape, E: Unit, D: CheckKernel<E>, T> Tensor<S, E, D, T> {
+    /// See [assert_all]
+    #[track_caller]
+    pub fn assert_all(self, invariant: Invariant) -> Self {
+        self.try_assert_all(invariant).unwrap()
+    }
+
+    /// See [assert_all]
+    #[track_caller]
+    pub fn try_assert_all(self, invariant: Invariant) -> Result<Self, D::Err> {
+        if !self.device.all(invariant, &self.storage)? {
+            return Err(CheckFailed::new("assert_all", "input", invariant).into());
+        }
+        Ok(self)
+    }
+
+    /// When checked mode is enabled on the device, returns [CheckFailed] for `op`
+    /// if any element of this tensor doesn't satisfy `invariant`.
+    #[track_caller]
+    pub(crate) fn try_check(
+        &self,
+        op: &'static str,
+        tensor: &'static str,
+        invariant: Invariant,
+    ) -> Result<(), D::Err> {
+        if self.device.is_checked() && !self.device.all(invariant, &self.storage)? {
+            return Err(CheckFailed::new(op, tensor, invariant).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_assert_all() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[0.0, 0.5], [1.0, 0.25]]);
+        let t = t.try_assert_all(Invariant::Probability).unwrap();
+        let t = t.try_assert_all(Invariant::NonNegative).unwrap();
+        let t = t.try_assert_all(Invariant::Finite).unwrap();
+        assert!((t - 0.5).try_assert_all(Invariant::NonNegative).is_err());
+
+        let t = dev.tensor([1.0, f32::INFINITY]);
+        assert!(t.clone().try_assert_all(Invariant::NonNegative).is_ok());
+        assert!(t.clone().try_assert_all(Invariant::Probability).is_err());
+        assert!(t.try_assert_all(Invariant::Finite).is_err());
+    }
+
+    #[test]
+    fn test_assert_all_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 2>, f32, _> = dev.tensor([0.5, -0.5]).broadcast();
+        assert!(t.try_assert_all(Invariant::NonNegative).is_err());
+    }
+
+    #[test]
+    fn test_assert_all_keeps_tape() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([0.25, 0.5]);
+        let g = t
+            .trace()
+            .assert_all(Invariant::Finite)
+            .square()
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_checked_ops() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, f32::NAN, 2.0]);
+        assert!(t.clone().try_softmax().is_ok());
+
+        dev.set_checked(true);
+        assert!(t.device().is_checked());
+        match t.clone().try_log_softmax() {
+            Err(e) => assert!(std::format!("{e}").contains("CheckFailed in `log_softmax`")),
+            Ok(_) => panic!("expected log_softmax to fail"),
+        }
+        assert!(dev.tensor([1.0, 2.0]).try_softmax().is_ok());
+
+        let logits = dev.tensor([0.5, -0.5]);
+        assert!(logits
+            .clone()
+            .try_bce_with_logits(dev.tensor([0.0, 1.0]))
+            .is_ok());
+        assert!(logits.try_bce_with_logits(dev.tensor([0.0, 2.0])).is_err());
+
+        dev.set_checked(false);
+        assert!(t.try_softmax().is_ok());
+    }
+}
@@ -64,6 +64,22 @@ impl ChooseKernel<f32> for Cuda {
         grad_rhs: &mut Self::Storage<S, f32>,
         grad_out: &Self::Storage<S, f32>,
     ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let cpu_cond = self.storage_to_cpu(cond)?;
+            let mut cpu_grad_lhs = self.storage_to_cpu(grad_lhs)?;
+            let mut cpu_grad_rhs = self.storage_to_cpu(grad_rhs)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            ChooseKernel::<f32>::backward(
+                &self.cpu,
+                &cpu_cond,
+                &mut cpu_grad_lhs,
+                &mut cpu_grad_rhs,
+                &cpu_grad_out,
+            )?;
+            self.storage_from_cpu(grad_lhs, &cpu_grad_lhs)?;
+            return self.storage_from_cpu(grad_rhs, &cpu_grad_rhs);
+        }
+
         let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
         let numel = cond.shape.num_elements();
 
@@ -5,8 +5,8 @@ mod cuda_kernel;
 
 use crate::{
     gradients::{Merge, Tape},
-    prelude::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
-    shapes::{Dtype, HasShape, Shape},
+    prelude::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+    shapes::{Dtype, Shape, ShapeMismatch},
 };
 
 pub trait ChooseKernel<E: Dtype>: DeviceStorage {
@@ -32,6 +32,7 @@ pub trait ChooseFrom<Lhs, Rhs>: HasErr {
 
     /// Construct a new tensor, where the output tensor contains the elements of lhs where self is
     /// true, and rhs where self is false.
+    #[track_caller]
     fn choose(self, lhs: Lhs, rhs: Rhs) -> Self::Output {
         self.try_choose(lhs, rhs).unwrap()
     }
@@ -50,13 +51,16 @@ impl<
 {
     type Output = Tensor<S, E, D, LhsTape>;
 
+    #[track_caller]
     fn try_choose(
         self,
         lhs: Tensor<S, E, D, LhsTape>,
         rhs: Tensor<S, E, D, RhsTape>,
     ) -> Result<Self::Output, Self::Err> {
-        assert_eq!(self.shape(), lhs.shape());
-        assert_eq!(lhs.shape(), rhs.shape());
+        DeviceMismatch::check_same("choose", &self.device, &lhs.device)?;
+        DeviceMismatch::check_same("choose", &lhs.device, &rhs.device)?;
+        ShapeMismatch::check_same("choose", &self, &lhs)?;
+        ShapeMismatch::check_same("choose", &lhs, &rhs)?;
 
         let (lhs, tape) = lhs.split_tape();
         let (rhs, rhs_tape) = rhs.split_tape();
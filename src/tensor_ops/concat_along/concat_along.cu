@@ -0,0 +1,46 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles one element of `inp`, which lives in `out` shifted by
+// `offset` along the concatenated axis. `offset` is already multiplied by the
+// stride of `out` along that axis.
+
+extern "C" __global__ void concat_along_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    const size_t offset,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_inp = get_strided_index(i, num_dims, dims, inp_strides);
+    size_t i_out = offset + get_strided_index(i, num_dims, dims, out_strides);
+    out[i_out] += inp[i_inp];
+}
+
+extern "C" __global__ void concat_along_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const size_t offset,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_inp = get_strided_index(i, num_dims, dims, inp_strides);
+    size_t i_out = offset + get_strided_index(i, num_dims, dims, out_strides);
+    // broadcasted inputs share their gradient between threads
+    atomicAdd(grad_inp + i_inp, grad_out[i_out]);
+}
@@ -0,0 +1,48 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::Cpu,
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype> super::ConcatAlongKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        inp: &Self::Storage<Src, E>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let buf = Arc::make_mut(&mut out.data);
+        for_each_row(&dims, ax, [&inp_strides, &out_strides], |[i_inp, i_out]| {
+            for j in 0..dims[ax] {
+                buf[i_out + (offset + j) * out_strides[ax]] +=
+                    inp.data[i_inp + j * inp_strides[ax]];
+            }
+        });
+        Ok(())
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = grad_inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = grad_inp.strides.into();
+        let out_strides: Vec<usize> = grad_out.strides.into();
+        let buf = Arc::make_mut(&mut grad_inp.data);
+        for_each_row(&dims, ax, [&inp_strides, &out_strides], |[i_inp, i_out]| {
+            for j in 0..dims[ax] {
+                buf[i_inp + j * inp_strides[ax]] +=
+                    grad_out.data[i_out + (offset + j) * out_strides[ax]];
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/concat_along.ptx"));
+const MODULE_NAME: &str = "concat_along";
+const FWD_FN_NAME: &str = "concat_along_forward";
+const BWD_FN_NAME: &str = "concat_along_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+/// Like stack, the slices are copied with a kernel since cudarc doesn't expose stream
+/// ordered device to device copies into part of a buffer.
+impl super::ConcatAlongKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        inp: &Self::Storage<Src, f32>,
+        out: &mut Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = inp.shape.num_elements();
+        let out_strides: Vec<usize> = out.strides.into();
+        let offset = offset * out_strides[ax];
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out_strides)?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                        // const size_t numel,
+            Src::NUM_DIMS,                // const size_t num_dims,
+            &dims,                        // const size_t *dims,
+            inp.data.as_ref(),            // const float *inp,
+            &inp_strides,                 // const size_t *inp_strides,
+            offset,                       // const size_t offset,
+            Arc::make_mut(&mut out.data), // float *out,
+            &out_strides,                 // const size_t *out_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+
+        let numel = grad_inp.shape.num_elements();
+        let out_strides: Vec<usize> = grad_out.strides.into();
+        let offset = offset * out_strides[ax];
+
+        let dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out_strides)?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            Src::NUM_DIMS,                     // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            offset,                            // const size_t offset,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+pub trait ConcatAlongKernel<E: Dtype>: DeviceStorage {
+    /// Adds `inp` to the slice of `out` that starts at `offset` along `ax`.
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        inp: &Self::Storage<Src, E>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds the slice of `grad_out` that starts at `offset` along `ax` to `grad_inp`.
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        offset: usize,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Concatenates two tensors along `Ax`. **Pytorch equivalent**: `torch.cat((a, b), Ax)`
+///
+/// The two tensors must have the same dimensions along every other axis, and `New` is the
+/// dimension of `Ax` in the result. Use `usize` if either side is only known at runtime, or
+/// a [Const] holding the sum of the two sizes. Each input gets the slice of the gradient
+/// that its values were copied to.
+///
+/// Returns [ShapeMismatch] if the tensors have different sizes along an axis other than `Ax`.
+/// **Panics** if `New` is a [Const] that isn't the sum of the sizes of `Ax`.
+///
+/// Concatenating feature maps along the channel axis, like the skip connections of a UNet:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let up: Tensor<Rank3<2, 4, 4>, f32, _> = dev.zeros();
+/// let skip: Tensor<Rank3<3, 4, 4>, f32, _> = dev.ones();
+/// let r = up.concat_along::<Axis<0>, Const<5>>(skip);
+/// assert_eq!(r.array()[1], [[0.0; 4]; 4]);
+/// assert_eq!(r.array()[2], [[1.0; 4]; 4]);
+/// ```
+///
+/// With runtime dimensions:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<(usize, Const<2>), f32, _> = dev.zeros_like(&(3, Const));
+/// let b: Tensor<(usize, Const<2>), f32, _> = dev.ones_like(&(1, Const));
+/// let r = a.concat_along::<Axis<0>, usize>(b);
+/// assert_eq!(r.shape(), &(4, Const::<2>));
+/// assert_eq!(r.as_vec(), [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0]);
+/// ```
+pub trait ConcatAlong<Rhs: HasShape>: HasErr + HasShape {
+    /// See [ConcatAlong]
+    #[track_caller]
+    fn concat_along<Ax: Axes<Array = [isize; 1]>, New: Dim>(
+        self,
+        rhs: Rhs,
+    ) -> Self::WithShape<<Self::Shape as ReplaceAxis<Ax, New>>::Output>
+    where
+        Self::Shape: ConcatShape<Rhs::Shape, Ax, New>,
+    {
+        self.try_concat_along::<Ax, New>(rhs).unwrap()
+    }
+
+    /// Fallible version of [ConcatAlong::concat_along]
+    #[allow(clippy::type_complexity)]
+    fn try_concat_along<Ax: Axes<Array = [isize; 1]>, New: Dim>(
+        self,
+        rhs: Rhs,
+    ) -> Result<Self::WithShape<<Self::Shape as ReplaceAxis<Ax, New>>::Output>, Self::Err>
+    where
+        Self::Shape: ConcatShape<Rhs::Shape, Ax, New>;
+}
+
+impl<A, B, E, D, T, R> ConcatAlong<Tensor<B, E, D, R>> for Tensor<A, E, D, T>
+where
+    A: Shape,
+    B: Shape,
+    E: Dtype,
+    D: ConcatAlongKernel<E> + ZerosTensor<E>,
+    T: Tape<D> + Merge<R>,
+    R: Tape<D>,
+{
+    #[track_caller]
+    fn try_concat_along<Ax: Axes<Array = [isize; 1]>, New: Dim>(
+        self,
+        rhs: Tensor<B, E, D, R>,
+    ) -> Result<Self::WithShape<<Self::Shape as ReplaceAxis<Ax, New>>::Output>, Self::Err>
+    where
+        A: ConcatShape<B, Ax, New>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        DeviceMismatch::check_same("concat_along", &self.device, &rhs.device)?;
+        for i in (0..A::NUM_DIMS).filter(|&i| i != ax) {
+            ShapeMismatch::check_axes("concat_along", (&self, i), (&rhs, i))?;
+        }
+        let (n_lhs, n_rhs) = (self.shape().concrete()[ax], rhs.shape().concrete()[ax]);
+        let new = New::from_size(n_lhs + n_rhs).unwrap_or_else(|| {
+            panic!(
+                "concat_along: axis {ax} of the result has size {n_lhs} + {n_rhs}, which doesn't fit in {:?}",
+                core::any::type_name::<New>()
+            )
+        });
+        let dst = self.shape().replace_axis(new);
+
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = rhs.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let mut out = lhs.device.try_zeros_like(&dst)?;
+        lhs.device.forward(ax, 0, &lhs.storage, &mut out.storage)?;
+        lhs.device
+            .forward(ax, n_lhs, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        // separate ops, so a tensor can be concatenated with itself. each op's gradients are
+        // allocated right before it, since an op can only use those gradients.
+        let lhs_out = phantom_out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_out) = grads.mut_and_ref(&lhs, &lhs_out);
+            lhs.device.backward(ax, 0, grad_lhs, grad_out)
+        });
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_rhs, grad_out) = grads.mut_and_ref(&rhs, &phantom_out);
+            rhs.device.backward(ax, n_lhs, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_concat_along_axis_0() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = dev.tensor([[5.0, 6.0]]);
+        let r = a.trace().concat_along::<Axis<0>, Const<3>>(b.trace());
+        assert_eq!(r.array(), [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+        let g = (r * dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&a).array(), [[1.0, 2.0], [3.0, 4.0]]);
+        assert_eq!(g.get(&b).array(), [[5.0, 6.0]]);
+    }
+
+    #[test]
+    fn test_concat_along_last_axis() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 2, 1>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank3<2, 2, 3>, f32, _> = dev.sample_normal();
+        let r = a.trace().concat_along::<Axis<2>, Const<4>>(b.trace());
+        let (a_arr, b_arr, r_arr) = (a.array(), b.array(), r.array());
+        for i in 0..2 {
+            for j in 0..2 {
+                assert_eq!(r_arr[i][j][0], a_arr[i][j][0]);
+                assert_eq!(r_arr[i][j][1..], b_arr[i][j]);
+            }
+        }
+        let g = r.exp().sum().backward();
+        assert_close(&g.get(&a).array(), &a.exp().array());
+        assert_close(&g.get(&b).array(), &b.exp().array());
+    }
+
+    #[test]
+    fn test_concat_along_runtime_dims() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor_from_vec(std::vec![1.0, 2.0, 3.0, 4.0], (Const::<2>, 2));
+        let b = dev.tensor_from_vec(std::vec![5.0, 6.0], (Const::<2>, 1));
+        let r = a.trace().concat_along::<Axis<1>, usize>(b.trace());
+        assert_eq!(r.shape(), &(Const::<2>, 3));
+        assert_eq!(r.as_vec(), [1.0, 2.0, 5.0, 3.0, 4.0, 6.0]);
+        let g = (r * 2.0).sum().backward();
+        assert_eq!(g.get(&a).as_vec(), [2.0; 4]);
+        assert_eq!(g.get(&b).as_vec(), [2.0; 2]);
+    }
+
+    #[test]
+    fn test_concat_along_with_itself() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0]);
+        let r = a.trace().concat_along::<Axis<0>, Const<4>>(a.trace());
+        assert_eq!(r.array(), [1.0, 2.0, 1.0, 2.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0, 4.0])).sum().backward();
+        assert_eq!(g.get(&a).array(), [4.0, 6.0]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concat_along_parallel_backward() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0]);
+        let b = dev.tensor([3.0]);
+        let w = dev.tensor([1.0, 2.0, 3.0]);
+        let loss = || {
+            let r = a
+                .trace()
+                .exp()
+                .concat_along::<Axis<0>, Const<3>>(b.trace().sin());
+            (r * w.clone()).sum().backward()
+        };
+        let g1 = loss();
+        dev.set_backward_threads(2);
+        let g2 = loss();
+        dev.set_backward_threads(1);
+        assert_eq!(g1.get(&a).array(), g2.get(&a).array());
+        assert_eq!(g1.get(&b).array(), g2.get(&b).array());
+    }
+
+    #[test]
+    fn test_concat_along_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 3));
+        let b: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 4));
+        let err = a.try_concat_along::<Axis<0>, usize>(b).unwrap_err();
+        assert!(std::format!("{err}").contains("ShapeMismatch in `concat_along`"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_concat_along_wrong_const_size() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<2>, f32, _> = dev.zeros();
+        let b: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let _ = a.concat_along::<Axis<0>, Const<4>>(b);
+    }
+}
@@ -0,0 +1,138 @@
+struct Conv1DOp {
+    size_t stride;
+    size_t padding;
+    size_t dilation;
+    size_t kernel;
+    size_t batch;
+    size_t chan_in;
+    size_t chan_out;
+    size_t l_in;
+    size_t l_out;
+};
+
+extern "C" __global__ void unfold_input_into_patches(
+    const Conv1DOp op,
+    const float *image, // 3d (Batch, Channels, Length)
+    float *patches // 4d (Batch, Channels, KernelSize, LengthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_in * op.kernel * op.l_out;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    // patches shape is (B, C, K, l_out)
+    unsigned int idx = i;
+    const size_t ol = idx % op.l_out;
+    idx /= op.l_out;
+    const size_t k = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t x_plus_p = ol * op.stride + k * op.dilation;
+    if (x_plus_p < op.padding) {
+        return;
+    }
+    const size_t x = x_plus_p - op.padding;
+    if (x >= op.l_in) {
+        return;
+    }
+
+    patches[i] = image[b * (op.chan_in * op.l_in) + c * op.l_in + x];
+}
+
+extern "C" __global__ void unfold_output_into_patches(
+    const Conv1DOp op,
+    const float *image_out, // 3d (Batch, ChanOut, LengthOut)
+    float *patches // 4d (Batch, ChanOut, KernelSize, Length)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_out * op.kernel * op.l_in;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t x = idx % op.l_in;
+    idx /= op.l_in;
+    const size_t k = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t ol = x + op.padding;
+    if (ol < k * op.dilation) {
+        return;
+    }
+    ol -= k * op.dilation;
+    if (ol % op.stride != 0) {
+        return;
+    }
+    ol /= op.stride;
+    if (ol >= op.l_out) {
+        return;
+    }
+
+    patches[i] = image_out[b * (op.chan_out * op.l_out) + o * op.l_out + ol];
+}
+
+extern "C" __global__ void transpose_and_broadcast_filters(
+    const Conv1DOp op,
+    const float *filters, // 3d (ChanOut, ChanIn, KernelSize)
+    float *filters_tr // 4d (Batch, ChanIn, ChanOut, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    auto numel = op.chan_in * op.chan_out * op.kernel;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t k = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+
+    auto i_tr = c * (op.chan_out * op.kernel) + o * op.kernel + k;
+
+    const float f = filters[i];
+    for (auto b = 0; b < op.batch; b++) {
+        filters_tr[b * numel + i_tr] = f;
+    }
+}
+
+extern "C" __global__ void sum_transposed_filters(
+    const Conv1DOp op,
+    const float *filters_tr, // 4d (Batch, ChanIn, ChanOut, KernelSize)
+    float *filters // 3d (ChanOut, ChanIn, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    auto numel = op.chan_out * op.chan_in * op.kernel;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t k = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+
+    auto i_tr = c * (op.chan_out * op.kernel) + o * op.kernel + k;
+
+    float tmp = 0.0;
+    for (auto b = 0; b < op.batch; b++) {
+        tmp += filters_tr[b * numel + i_tr];
+    }
+
+    filters[i] += tmp;
+}
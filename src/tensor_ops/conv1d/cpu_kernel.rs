@@ -0,0 +1,206 @@
+use crate::shapes::Shape;
+use crate::tensor::cpu::*;
+
+use super::{Conv1DKernel, Conv1DOp};
+
+use std::sync::Arc;
+
+impl Conv1DOp {
+    #[inline(always)]
+    fn unfold_idx(&self, [k, x]: [usize; 2]) -> Option<usize> {
+        let mut ol = x + self.padding;
+        if ol < k * self.dilation {
+            return None;
+        }
+        ol -= k * self.dilation;
+        if ol % self.stride != 0 {
+            return None;
+        }
+        ol /= self.stride;
+        if ol >= self.l_out {
+            return None;
+        }
+        Some(ol)
+    }
+}
+
+impl Cpu {
+    #[inline]
+    fn conv1d_forward<P: Shape<Concrete = [usize; 3]>>(
+        &self,
+        op: &Conv1DOp,
+        img: &[f32],
+        filters: &[f32],
+        out: &mut [f32],
+        inp_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let buf = Arc::make_mut(&mut inp_patches_buf.data);
+            let mut i = 0;
+            for c in 0..op.chan_in {
+                for k in 0..op.kernel {
+                    for ol in 0..op.l_out {
+                        let x = (ol * op.stride + k * op.dilation).wrapping_sub(op.padding);
+                        if x < op.l_in {
+                            buf[i] = img[c * op.l_in + x];
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        // (O, C * K) * (C * K, OL) = (O, OL)
+        let m = op.chan_out;
+        let k = op.chan_in * op.kernel;
+        let n = op.l_out;
+        self.gemm(
+            View::new(filters, (m, k)),
+            View::new(inp_patches_buf.view().data, (k, n)),
+            &mut ViewMut::new(out, (m, n)),
+        );
+        Ok(())
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn conv1d_backward<P: Shape<Concrete = [usize; 3]>>(
+        &self,
+        op: &Conv1DOp,
+        img: &[f32],
+        grad_img: &mut [f32],
+        filters_tr: &[f32],
+        grad_filters_tr: &mut [f32],
+        grad_out: &[f32],
+        out_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let mut i = 0;
+            let buf = Arc::make_mut(&mut out_patches_buf.data);
+            for o in 0..op.chan_out {
+                for k in 0..op.kernel {
+                    for x in 0..op.l_in {
+                        if let Some(ol) = op.unfold_idx([k, x]) {
+                            buf[i] = grad_out[o * op.l_out + ol];
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        {
+            // img_g += filters^T * unfold(grad_out)
+            // (C, L) += (C, O * K) * (O * K, L)
+            let m = op.chan_in;
+            let k = op.chan_out * op.kernel;
+            let n = op.l_in;
+            self.gemm(
+                View::new(filters_tr, (m, k)),
+                View::new(out_patches_buf.view().data, (k, n)),
+                &mut ViewMut::new(grad_img, (m, n)),
+            );
+        }
+
+        {
+            // weight_g^T += img * patches^T
+            // (C, O * K) += (C, L) * (L, O * K)
+            let m = op.chan_in;
+            let k = op.l_in;
+            let n = op.chan_out * op.kernel;
+            self.gemm(
+                View::new(img, (m, k)),
+                View::new(out_patches_buf.view().data, (n, k)).tr(),
+                &mut ViewMut::new(grad_filters_tr, (m, n)),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Conv1DKernel<f32> for Cpu {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv1DOp,
+        lhs: &Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.inp_patches_shape())?;
+        let [lstride, ostride] = match L::NUM_DIMS {
+            2 => [0; 2],
+            3 => [lhs.strides[0], out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let rhs = rhs.data.as_ref();
+        let out = Arc::make_mut(&mut out.data);
+        for i_batch in 0..op.batch {
+            self.conv1d_forward(
+                &op,
+                &lhs[i_batch * lstride..],
+                rhs,
+                &mut out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv1DOp,
+        lhs: &Self::Storage<L, f32>,
+        grad_lhs: &mut Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        grad_rhs: &mut Self::Storage<R, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.out_patches_shape())?;
+        let mut f102: StridedArray<_, f32> = StridedArray::new(op.filters_tr_shape())?;
+        let mut grad_f102: StridedArray<_, f32> = StridedArray::new(op.filters_tr_shape())?;
+
+        {
+            // transpose filters in f102
+            let buf = rhs.data.as_ref();
+            let mut f_iter = f102.iter_mut_with_index();
+            while let Some((f, [c, o, k])) = f_iter.next() {
+                *f = buf[o * rhs.strides[0] + c * rhs.strides[1] + k * rhs.strides[2]];
+            }
+        }
+
+        let [lstride, ostride] = match L::NUM_DIMS {
+            2 => [0; 2],
+            3 => [lhs.strides[0], grad_out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let grad_lhs = Arc::make_mut(&mut grad_lhs.data);
+        let f = f102.data.as_ref();
+        let grad_f = Arc::make_mut(&mut grad_f102.data);
+        let grad_out = grad_out.data.as_ref();
+
+        for i_batch in 0..op.batch {
+            self.conv1d_backward(
+                &op,
+                &lhs[i_batch * lstride..],
+                &mut grad_lhs[i_batch * lstride..],
+                f,
+                grad_f,
+                &grad_out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+
+        {
+            // untranspose filters
+            let buf = Arc::make_mut(&mut grad_rhs.data);
+            let mut f_iter = grad_f102.iter_with_index();
+            while let Some((f, [c, o, k])) = f_iter.next() {
+                buf[o * rhs.strides[0] + c * rhs.strides[1] + k * rhs.strides[2]] += *f;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Conv1DOp {
+    pub stride: usize,
+    pub padding: usize,
+    pub dilation: usize,
+    pub kernel: usize,
+    pub batch: usize,
+    pub chan_in: usize,
+    pub chan_out: usize,
+    pub l_in: usize,
+    pub l_out: usize,
+}
+
+impl Conv1DOp {
+    fn new(s: usize, p: usize, d: usize, k: usize, [b, c, l_in]: [usize; 3], o: usize) -> Self {
+        Self {
+            stride: s,
+            padding: p,
+            dilation: d,
+            kernel: k,
+            batch: b,
+            chan_in: c,
+            chan_out: o,
+            l_in,
+            l_out: (l_in + 2 * p - d * (k - 1) - 1) / s + 1,
+        }
+    }
+
+    pub(super) fn inp_patches_shape(&self) -> (usize, usize, usize) {
+        (self.chan_in, self.kernel, self.l_out)
+    }
+
+    pub(super) fn out_patches_shape(&self) -> (usize, usize, usize) {
+        (self.chan_out, self.kernel, self.l_in)
+    }
+
+    pub(super) fn filters_tr_shape(&self) -> (usize, usize, usize) {
+        (self.chan_in, self.chan_out, self.kernel)
+    }
+}
+
+pub(super) trait Conv1DKernel<E: Dtype>: DeviceStorage {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv1DOp,
+        lhs: &Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv1DOp,
+        lhs: &Self::Storage<L, E>,
+        grad_lhs: &mut Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        grad_rhs: &mut Self::Storage<R, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Like [super::conv2d::ConvAlgebra], but with the kernel elements spaced `L` apart.
+pub trait DilatedConvAlgebra<const K: usize, const S: usize, const P: usize, const L: usize>:
+    ConstDim
+{
+    type Convolved: ConstDim;
+}
+
+impl<const D: usize, const K: usize, const S: usize, const P: usize, const L: usize>
+    DilatedConvAlgebra<K, S, P, L> for Const<D>
+where
+    Const<{ (D + 2 * P - L * (K - 1) - 1) / S + 1 }>: Sized,
+{
+    type Convolved = Const<{ (D + 2 * P - L * (K - 1) - 1) / S + 1 }>;
+}
+
+pub trait TryConv1DTo<F, const S: usize, const P: usize, const L: usize>: HasErr {
+    type Output;
+    fn conv1d_to(self, filters: F) -> Self::Output {
+        self.try_conv1d_to(filters).unwrap()
+    }
+    fn try_conv1d_to(self, filters: F) -> Result<Self::Output, Self::Err>;
+}
+
+/// **Requires Nightly** 1d convolution over sequences of shape `(C, L)` or `(B, C, L)`,
+/// with filters of shape `(O, C, K)`.
+///
+/// Generics:
+/// - `S`: The stride.
+/// - `P`: How much zero padding to add to both ends of the sequence.
+/// - `L`: The dilation, i.e. the spacing between the elements of the kernel.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.conv1d(x, filters, stride=S, padding=P, dilation=L)`
+pub trait TryConv1D<F> {
+    fn conv1d<const S: usize, const P: usize, const L: usize>(self, filters: F) -> Self::Output
+    where
+        Self: TryConv1DTo<F, S, P, L>,
+    {
+        self.conv1d_to(filters)
+    }
+    fn try_conv1d<const S: usize, const P: usize, const L: usize>(
+        self,
+        filters: F,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: TryConv1DTo<F, S, P, L>,
+    {
+        self.try_conv1d_to(filters)
+    }
+}
+
+impl<T, F> TryConv1D<F> for T {}
+
+impl<
+        const C: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D: Conv1DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv1DTo<Tensor<Rank3<O, C, K>, f32, D>, S, P, L> for Tensor<Rank2<C, W>, f32, D, T>
+where
+    Const<W>: DilatedConvAlgebra<K, S, P, L>,
+{
+    type Output = Tensor<
+        (
+            Const<O>,
+            <Const<W> as DilatedConvAlgebra<K, S, P, L>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv1d_to(
+        self,
+        filters: Tensor<Rank3<O, C, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let op = Conv1DOp::new(S, P, L, K, [1, C, W], O);
+        DeviceMismatch::check_same("conv1d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let mut out = lhs.device.try_zeros()?;
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        const C: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const L: usize,
+        D: Conv1DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv1DTo<Tensor<Rank3<O, C, K>, f32, D>, S, P, L>
+    for Tensor<(B, Const<C>, Const<W>), f32, D, T>
+where
+    Const<W>: DilatedConvAlgebra<K, S, P, L>,
+{
+    type Output = Tensor<
+        (
+            B,
+            Const<O>,
+            <Const<W> as DilatedConvAlgebra<K, S, P, L>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv1d_to(
+        self,
+        filters: Tensor<Rank3<O, C, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let batch = self.shape().0;
+        let op = Conv1DOp::new(S, P, L, K, [batch.size(), C, W], O);
+        DeviceMismatch::check_same("conv1d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut out = lhs
+            .device
+            .try_zeros_like(&(batch, Const::<O>, Default::default()))?;
+        let mut tape = ltape.merge(rtape);
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_conv1d_default_stride_and_padding() {
+        let dev: TestDevice = Default::default();
+        let weight = dev.tensor([[[0.1, -0.2], [0.3, 0.4]], [[-0.5, 0.6], [0.7, -0.8]]]);
+        let x = dev.tensor([[1.0, 2.0, -1.0, 0.5], [0.0, -2.0, 3.0, 1.0]]);
+        let result = x.trace().conv1d::<1, 0, 1>(weight.clone());
+        assert_close(&result.array(), &[[-1.1, 1.0, 1.1], [2.3, -5.4, 2.1]]);
+        let g = result.sum().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[[-0.4, 0.0, 0.0, 0.4], [1.0, 0.6, 0.6, -0.4]],
+        );
+        assert_close(&g.get(&weight).array(), &[[[2.0, 1.5], [1.0, 2.0]]; 2]);
+    }
+
+    /// Naive reference implementation of a (C, L) conv1d, used to check the kernels.
+    fn naive_conv1d<const C: usize, const W: usize, const O: usize, const K: usize>(
+        x: [[f32; W]; C],
+        w: [[[f32; K]; C]; O],
+        s: usize,
+        p: usize,
+        l: usize,
+    ) -> std::vec::Vec<std::vec::Vec<f32>> {
+        let l_out = (W + 2 * p - l * (K - 1) - 1) / s + 1;
+        let mut out = std::vec![std::vec![0.0; l_out]; O];
+        for (o, out_o) in out.iter_mut().enumerate() {
+            for (i, y) in out_o.iter_mut().enumerate() {
+                for c in 0..C {
+                    for k in 0..K {
+                        let j = (i * s + k * l).wrapping_sub(p);
+                        if j < W {
+                            *y += w[o][c][k] * x[c][j];
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_conv1d_stride_padding_dilation() {
+        let dev = TestDevice::seed_from_u64(61);
+        let weight = dev.sample_normal::<Rank3<3, 2, 3>>();
+        let x = dev.sample_normal::<Rank2<2, 9>>();
+        let y: Tensor<Rank2<3, 4>, _, _, _> = x.trace().conv1d::<2, 1, 2>(weight.clone());
+        let expected = naive_conv1d(x.array(), weight.array(), 2, 1, 2);
+        for (a, b) in y.array().iter().zip(expected.iter()) {
+            for (a, b) in a.iter().zip(b.iter()) {
+                assert!((a - b).abs() < 1e-5);
+            }
+        }
+
+        // each input element is used once for each (out channel, kernel element) that lands on it
+        let g = y.sum().backward();
+        let (w, gx) = (weight.array(), g.get(&x).array());
+        for c in 0..2 {
+            for j in 0..9 {
+                let mut expected = 0.0;
+                for i in 0..4usize {
+                    for k in 0..3 {
+                        if (i * 2 + k * 2).wrapping_sub(1) == j {
+                            expected += (0..3).map(|o| w[o][c][k]).sum::<f32>();
+                        }
+                    }
+                }
+                assert!((gx[c][j] - expected).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_batched_conv1d_matches_unbatched() {
+        let dev = TestDevice::seed_from_u64(5);
+        let weight = dev.sample_normal::<Rank3<4, 3, 2>>();
+        let x = dev.sample_normal::<Rank3<2, 3, 7>>();
+
+        let y: Tensor<Rank3<2, 4, 3>, _, _, _> = x.trace().conv1d::<2, 0, 2>(weight.clone());
+        let y_array = y.array();
+        let g = y.exp().mean().backward();
+
+        let mut grad_w = [[[0.0; 2]; 3]; 4];
+        for i in 0..2 {
+            let x_i = x.clone().select(dev.tensor(i));
+            let y_i = x_i.trace().conv1d::<2, 0, 2>(weight.clone());
+            assert_close(&y_array[i], &y_i.array());
+            let g_i = (y_i.exp().sum() / 24.0).backward();
+            assert_close(&g.get(&x).array()[i], &g_i.get(&x_i).array());
+            let w_i = g_i.get(&weight).array();
+            for o in 0..4 {
+                for c in 0..3 {
+                    for k in 0..2 {
+                        grad_w[o][c][k] += w_i[o][c][k];
+                    }
+                }
+            }
+        }
+        assert_close(&g.get(&weight).array(), &grad_w);
+    }
+}
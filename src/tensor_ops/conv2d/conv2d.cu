@@ -9,11 +9,13 @@ struct Conv2DOp {
     size_t h_out;
     size_t w_in;
     size_t w_out;
+    size_t groups;
+    bool channels_last;
 };
 
 extern "C" __global__ void unfold_input_into_patches(
     const Conv2DOp op,
-    const float *image, // 4d (Batch, Channels, Height, Width)
+    const float *image, // 4d (Batch, Channels, Height, Width) or (Batch, Height, Width, Channels)
     float *patches // 6d (Batch, Channels, KernelSize, KernelSize, HeightOut, WidthOut)
 ) {
     unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
@@ -55,13 +57,15 @@ extern "C" __global__ void unfold_input_into_patches(
         return;
     }
 
-    const size_t i_image = b * (op.chan_in * op.h_in * op.w_in) + c * (op.h_in * op.w_in) + y * (op.w_in) + x;
+    const size_t i_image = b * (op.chan_in * op.h_in * op.w_in) + (op.channels_last
+        ? (y * op.w_in + x) * op.chan_in + c
+        : c * (op.h_in * op.w_in) + y * (op.w_in) + x);
     patches[i] = image[i_image];
 }
 
 extern "C" __global__ void unfold_output_into_patches(
     const Conv2DOp op,
-    const float *image_out, // 4d (Batch, ChanOut, HeightOut, WidthOut)
+    const float *image_out, // 4d (Batch, ChanOut, HeightOut, WidthOut) or (Batch, HeightOut, WidthOut, ChanOut)
     float *patches // 6d (Batch, ChanOut, KernelSize, KernelSize, HeightIn, WidthIn)
 ) {
     unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
@@ -110,32 +114,40 @@ extern "C" __global__ void unfold_output_into_patches(
         return;
     }
 
-    size_t image_i = b * (op.chan_out * op.h_out * op.w_out) + o * (op.h_out * op.w_out) + oh * (op.w_out)  + ow;
+    size_t image_i = b * (op.chan_out * op.h_out * op.w_out) + (op.channels_last
+        ? (oh * op.w_out + ow) * op.chan_out + o
+        : o * (op.h_out * op.w_out) + oh * (op.w_out) + ow);
     patches[i] = image_out[image_i];
 }
 
 extern "C" __global__ void transpose_and_broadcast_filters(
     const Conv2DOp op,
-    const float *filters, // 4d (ChanOut, ChanIn, KernelSize, KernelSize)
-    float *filters_tr // 5d (Batch, ChanIn, ChanOut, KernelSize, KernelSize)
+    const float *filters, // 4d (ChanOut, ChanIn / Groups, KernelSize, KernelSize)
+    float *filters_tr // 5d (Batch, ChanIn, ChanOut / Groups, KernelSize, KernelSize)
 ) {
     unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
-    auto numel = op.chan_in * op.chan_out * op.kernel * op.kernel;
+    auto numel = op.chan_in * (op.chan_out / op.groups) * op.kernel * op.kernel;
     if (i >= numel) {
         return;
     }
 
+    const size_t chan_in_group = op.chan_in / op.groups;
+    const size_t chan_out_group = op.chan_out / op.groups;
+
     unsigned int idx = i;
     const size_t k2 = idx % op.kernel;
     idx /= op.kernel;
     const size_t k1 = idx % op.kernel;
     idx /= op.kernel;
-    const size_t c = idx % op.chan_in;
-    idx /= op.chan_in;
+    const size_t c_group = idx % chan_in_group;
+    idx /= chan_in_group;
     const size_t o = idx % op.chan_out;
     idx /= op.chan_out;
 
-    auto i_tr = c * (op.chan_out * op.kernel * op.kernel) + o * (op.kernel * op.kernel) + k1 * (op.kernel) + k2;
+    const size_t g = o / chan_out_group;
+    const size_t c = g * chan_in_group + c_group;
+    const size_t o_group = o % chan_out_group;
+    auto i_tr = c * (chan_out_group * op.kernel * op.kernel) + o_group * (op.kernel * op.kernel) + k1 * (op.kernel) + k2;
 
     const float f = filters[i];
     for (auto b = 0; b < op.batch; b++) {
@@ -145,26 +157,32 @@ extern "C" __global__ void transpose_and_broadcast_filters(
 
 extern "C" __global__ void sum_transposed_filters(
     const Conv2DOp op,
-    const float *filters_tr, // 5d (Batch, ChanIn, ChanOut, KernelSize, KernelSize)
-    float *filters // 4d (ChanOut, ChanIn, KernelSize, KernelSize)
+    const float *filters_tr, // 5d (Batch, ChanIn, ChanOut / Groups, KernelSize, KernelSize)
+    float *filters // 4d (ChanOut, ChanIn / Groups, KernelSize, KernelSize)
 ) {
     unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
-    auto numel = op.chan_out * op.chan_in * op.kernel * op.kernel;
+    auto numel = op.chan_out * (op.chan_in / op.groups) * op.kernel * op.kernel;
     if (i >= numel) {
         return;
     }
 
+    const size_t chan_in_group = op.chan_in / op.groups;
+    const size_t chan_out_group = op.chan_out / op.groups;
+
     unsigned int idx = i;
     const size_t k2 = idx % op.kernel;
     idx /= op.kernel;
     const size_t k1 = idx % op.kernel;
     idx /= op.kernel;
-    const size_t c = idx % op.chan_in;
-    idx /= op.chan_in;
+    const size_t c_group = idx % chan_in_group;
+    idx /= chan_in_group;
     const size_t o = idx % op.chan_out;
     idx /= op.chan_out;
 
-    auto i_tr = c * (op.chan_out * op.kernel * op.kernel) + o * (op.kernel * op.kernel) + k1 * (op.kernel) + k2;
+    const size_t g = o / chan_out_group;
+    const size_t c = g * chan_in_group + c_group;
+    const size_t o_group = o % chan_out_group;
+    auto i_tr = c * (chan_out_group * op.kernel * op.kernel) + o_group * (op.kernel * op.kernel) + k1 * (op.kernel) + k2;
 
     float tmp = 0.0;
     for (auto b = 0; b < op.batch; b++) {
@@ -1,6 +1,5 @@
 use crate::shapes::Shape;
 use crate::tensor::cpu::*;
-use crate::tensor_ops::matmul::cpu_kernel::matmul;
 
 use super::{Conv2DKernel, Conv2DOp};
 
@@ -37,6 +36,30 @@ impl Conv2DOp {
 
         Some([oh, ow])
     }
+
+    /// Converts an index into the transposed `(C, O / G, K, K)` filters into
+    /// an index into the `(O, C / G, K, K)` filters.
+    #[inline(always)]
+    fn untranspose_filters_idx(&self, [c, o, k1, k2]: [usize; 4]) -> [usize; 4] {
+        let chan_in_group = self.chan_in / self.groups;
+        let g = c / chan_in_group;
+        [
+            g * (self.chan_out / self.groups) + o,
+            c % chan_in_group,
+            k1,
+            k2,
+        ]
+    }
+
+    /// Strides of the channel & flattened spatial dims of a single image.
+    #[inline(always)]
+    fn chan_spatial_strides(&self, chan: usize, spatial: usize) -> [usize; 2] {
+        if self.channels_last {
+            [1, chan]
+        } else {
+            [spatial, 1]
+        }
+    }
 }
 
 impl Cpu {
@@ -49,6 +72,7 @@ impl Cpu {
         out: &mut [f32],
         inp_patches_buf: &mut StridedArray<P, f32>,
     ) -> Result<(), CpuError> {
+        let [img_c, img_s] = op.chan_spatial_strides(op.chan_in, op.h_in * op.w_in);
         {
             let buf = Arc::make_mut(&mut inp_patches_buf.data);
             let mut i = 0;
@@ -60,7 +84,7 @@ impl Cpu {
                                 let y = (oh * op.stride + k1).wrapping_sub(op.padding);
                                 let x = (ow * op.stride + k2).wrapping_sub(op.padding);
                                 if y < op.h_in && x < op.w_in {
-                                    buf[i] = img[c * (op.w_in * op.h_in) + y * op.w_in + x];
+                                    buf[i] = img[c * img_c + (y * op.w_in + x) * img_s];
                                 }
                                 i += 1;
                             }
@@ -70,15 +94,24 @@ impl Cpu {
             }
         }
 
-        // (O, C * K * K) * (C * K * K, OH * OW) = (O, OH * OW)
-        let m = op.chan_out;
-        let k = op.chan_in * op.kernel * op.kernel;
+        // for each group:
+        // (O / G, C / G * K * K) * (C / G * K * K, OH * OW) = (O / G, OH * OW)
+        let m = op.chan_out / op.groups;
+        let k = (op.chan_in / op.groups) * op.kernel * op.kernel;
         let n = op.w_out * op.h_out;
-        matmul(
-            View::new(filters, (m, k)),
-            View::new(inp_patches_buf.view().data, (k, n)),
-            &mut ViewMut::new(out, (m, n)),
-        );
+        let strides = op.chan_spatial_strides(op.chan_out, n);
+        let patches = inp_patches_buf.view().data;
+        for g in 0..op.groups {
+            self.gemm(
+                View::new(&filters[g * m * k..], (m, k)),
+                View::new(&patches[g * k * n..], (k, n)),
+                &mut ViewMut {
+                    data: &mut out[g * m * strides[0]..],
+                    shape: (m, n),
+                    strides,
+                },
+            );
+        }
         Ok(())
     }
 
@@ -94,6 +127,8 @@ impl Cpu {
         grad_out: &[f32],
         out_patches_buf: &mut StridedArray<P, f32>,
     ) -> Result<(), CpuError> {
+        let img_strides = op.chan_spatial_strides(op.chan_in, op.h_in * op.w_in);
+        let [out_c, out_s] = op.chan_spatial_strides(op.chan_out, op.h_out * op.w_out);
         {
             let mut i = 0;
             let buf = Arc::make_mut(&mut out_patches_buf.data);
@@ -103,8 +138,7 @@ impl Cpu {
                         for y in 0..op.h_in {
                             for x in 0..op.w_in {
                                 if let Some([oh, ow]) = op.unfold_idx([k1, k2, y, x]) {
-                                    buf[i] =
-                                        grad_out[o * (op.h_out * op.w_out) + oh * op.w_out + ow];
+                                    buf[i] = grad_out[o * out_c + (oh * op.w_out + ow) * out_s];
                                 }
                                 i += 1;
                             }
@@ -114,30 +148,44 @@ impl Cpu {
             }
         }
 
+        let patches = out_patches_buf.view().data;
+
         {
-            // img_g += filters^T * unfold(grad_out)
-            // (C, H * W) += (C, O * K * K) * (O * K * K, H * W)
-            let m = op.chan_in;
-            let k = op.chan_out * op.kernel * op.kernel;
+            // img_g += filters^T * unfold(grad_out), for each group:
+            // (C / G, H * W) += (C / G, O / G * K * K) * (O / G * K * K, H * W)
+            let m = op.chan_in / op.groups;
+            let k = (op.chan_out / op.groups) * op.kernel * op.kernel;
             let n = op.h_in * op.w_in;
-            matmul(
-                View::new(filters_tr, (m, k)),
-                View::new(out_patches_buf.view().data, (k, n)),
-                &mut ViewMut::new(grad_img, (m, n)),
-            );
+            for g in 0..op.groups {
+                self.gemm(
+                    View::new(&filters_tr[g * m * k..], (m, k)),
+                    View::new(&patches[g * k * n..], (k, n)),
+                    &mut ViewMut {
+                        data: &mut grad_img[g * m * img_strides[0]..],
+                        shape: (m, n),
+                        strides: img_strides,
+                    },
+                );
+            }
         }
 
         {
-            // weight_g^T += img * patches^T
-            // (C, O * K * K) += (C, H * W) * (H * W, O * K * K)
-            let m = op.chan_in;
+            // weight_g^T += img * patches^T, for each group:
+            // (C / G, O / G * K * K) += (C / G, H * W) * (H * W, O / G * K * K)
+            let m = op.chan_in / op.groups;
             let k = op.h_in * op.w_in;
-            let n = op.chan_out * op.kernel * op.kernel;
-            matmul(
-                View::new(img, (m, k)),
-                View::new(out_patches_buf.view().data, (n, k)).tr(),
-                &mut ViewMut::new(grad_filters_tr, (m, n)),
-            );
+            let n = (op.chan_out / op.groups) * op.kernel * op.kernel;
+            for g in 0..op.groups {
+                self.gemm(
+                    View {
+                        data: &img[g * m * img_strides[0]..],
+                        shape: (m, k),
+                        strides: img_strides,
+                    },
+                    View::new(&patches[g * n * k..], (n, k)).tr(),
+                    &mut ViewMut::new(&mut grad_filters_tr[g * m * n..], (m, n)),
+                );
+            }
         }
         Ok(())
     }
@@ -189,7 +237,8 @@ impl Conv2DKernel<f32> for Cpu {
             // transpose filters in f1023
             let buf = rhs.data.as_ref();
             let mut f_iter = f1023.iter_mut_with_index();
-            while let Some((f, [c, o, k1, k2])) = f_iter.next() {
+            while let Some((f, idx)) = f_iter.next() {
+                let [o, c, k1, k2] = op.untranspose_filters_idx(idx);
                 let idx = o * rhs.strides[0]
                     + c * rhs.strides[1]
                     + k1 * rhs.strides[2]
@@ -225,7 +274,8 @@ impl Conv2DKernel<f32> for Cpu {
             // untranspose filters
             let buf = Arc::make_mut(&mut grad_rhs.data);
             let mut f_iter = grad_f1023.iter_with_index();
-            while let Some((f, [c, o, k1, k2])) = f_iter.next() {
+            while let Some((f, idx)) = f_iter.next() {
+                let [o, c, k1, k2] = op.untranspose_filters_idx(idx);
                 let idx = o * rhs.strides[0]
                     + c * rhs.strides[1]
                     + k1 * rhs.strides[2]
@@ -20,6 +20,17 @@ const ALL_FN_NAMES: [&str; 4] = [
 
 unsafe impl AsKernelParam for super::Conv2DOp {}
 
+impl super::Conv2DOp {
+    /// Batched strides of a `(B, chan, spatial)` view into an image.
+    fn batched_image_strides(&self, chan: usize, spatial: usize) -> [usize; 3] {
+        if self.channels_last {
+            [chan * spatial, 1, chan]
+        } else {
+            [chan * spatial, spatial, 1]
+        }
+    }
+}
+
 impl super::Conv2DKernel<f32> for Cuda {
     fn forward<L: Shape, R: Shape, O: Shape>(
         &self,
@@ -47,23 +58,28 @@ impl super::Conv2DKernel<f32> for Cuda {
         let params = (op, lhs.data.as_ref(), &mut patches);
         unsafe { unfold_fn.launch_async(cfg, params) }?;
 
-        // (O, C * K * K) * (B, C * K * K, OH * OW) = (B, O, OH * OW)
-        let m = op.chan_out;
-        let k = op.chan_in * op.kernel * op.kernel;
+        // for each group:
+        // (O / G, C / G * K * K) * (B, C / G * K * K, OH * OW) = (B, O / G, OH * OW)
+        let m = op.chan_out / op.groups;
+        let k = (op.chan_in / op.groups) * op.kernel * op.kernel;
         let n = op.h_out * op.w_out;
-        unsafe {
-            sgemm_batch(
-                self.blas.as_ref(),
-                (op.batch, m, k, n),
-                rhs.data.as_ref(),
-                [0, k, 1],
-                &patches,
-                [k * n, n, 1],
-                0.0,
-                Arc::make_mut(&mut out.data),
-                [m * n, n, 1],
-            )
-            .unwrap();
+        let out_strides = op.batched_image_strides(op.chan_out, n);
+        let out = Arc::make_mut(&mut out.data);
+        for g in 0..op.groups {
+            unsafe {
+                sgemm_batch(
+                    self.blas.as_ref(),
+                    (op.batch, m, k, n),
+                    &rhs.data.try_slice(g * m * k..).unwrap(),
+                    [0, k, 1],
+                    &patches.try_slice(g * k * n..).unwrap(),
+                    [op.groups * k * n, n, 1],
+                    0.0,
+                    &mut out.try_slice_mut(g * m * out_strides[1]..).unwrap(),
+                    out_strides,
+                )
+                .unwrap();
+            }
         }
 
         Ok(())
@@ -89,7 +105,8 @@ impl super::Conv2DKernel<f32> for Cuda {
             unsafe { unfold_fn.launch_async(cfg, params) }?;
         }
 
-        let filters_numel = op.batch * op.chan_in * op.chan_out * op.kernel * op.kernel;
+        let filters_numel =
+            op.batch * op.chan_in * (op.chan_out / op.groups) * op.kernel * op.kernel;
         let mut f_b1023 = self.dev.alloc_zeros_async::<f32>(filters_numel)?;
         let mut grad_f_b1023 = self.dev.alloc_zeros_async::<f32>(filters_numel)?;
 
@@ -103,46 +120,53 @@ impl super::Conv2DKernel<f32> for Cuda {
         }
 
         {
-            // img_g += filters * patches
-            // (B, C, H * W) += (B, C, O * K * K) * (B, O * K * K, H * W)
-            let m = op.chan_in;
-            let k = op.chan_out * op.kernel * op.kernel;
+            // img_g += filters * patches, for each group:
+            // (B, C / G, H * W) += (B, C / G, O / G * K * K) * (B, O / G * K * K, H * W)
+            let m = op.chan_in / op.groups;
+            let k = (op.chan_out / op.groups) * op.kernel * op.kernel;
             let n = op.h_in * op.w_in;
-            unsafe {
-                sgemm_batch(
-                    self.blas.as_ref(),
-                    (op.batch, m, k, n),
-                    &f_b1023,
-                    [m * k, k, 1],
-                    &patches,
-                    [k * n, n, 1],
-                    1.0,
-                    Arc::make_mut(&mut grad_lhs.data),
-                    [m * n, n, 1],
-                )
-                .unwrap();
+            let img_strides = op.batched_image_strides(op.chan_in, n);
+            let grad_lhs = Arc::make_mut(&mut grad_lhs.data);
+            for g in 0..op.groups {
+                unsafe {
+                    sgemm_batch(
+                        self.blas.as_ref(),
+                        (op.batch, m, k, n),
+                        &f_b1023.try_slice(g * m * k..).unwrap(),
+                        [op.groups * m * k, k, 1],
+                        &patches.try_slice(g * k * n..).unwrap(),
+                        [op.groups * k * n, n, 1],
+                        1.0,
+                        &mut grad_lhs.try_slice_mut(g * m * img_strides[1]..).unwrap(),
+                        img_strides,
+                    )
+                    .unwrap();
+                }
             }
         }
 
         {
-            // weight_g += img * patches^T
-            // (B, C, O * K * K) += (B, C, H * W) * (B, H * W, O * K * K)
-            let m = op.chan_in;
+            // weight_g += img * patches^T, for each group:
+            // (B, C / G, O / G * K * K) += (B, C / G, H * W) * (B, H * W, O / G * K * K)
+            let m = op.chan_in / op.groups;
             let k = op.h_in * op.w_in;
-            let n = op.chan_out * op.kernel * op.kernel;
-            unsafe {
-                sgemm_batch(
-                    self.blas.as_ref(),
-                    (op.batch, m, k, n),
-                    lhs.data.as_ref(),
-                    [m * k, k, 1],
-                    &patches,
-                    [k * n, 1, k],
-                    1.0,
-                    &mut grad_f_b1023,
-                    [m * n, n, 1],
-                )
-                .unwrap();
+            let n = (op.chan_out / op.groups) * op.kernel * op.kernel;
+            let img_strides = op.batched_image_strides(op.chan_in, k);
+            for g in 0..op.groups {
+                unsafe {
+                    sgemm_batch(
+                        self.blas.as_ref(),
+                        (op.batch, m, k, n),
+                        &lhs.data.try_slice(g * m * img_strides[1]..).unwrap(),
+                        img_strides,
+                        &patches.try_slice(g * n * k..).unwrap(),
+                        [op.groups * k * n, 1, k],
+                        1.0,
+                        &mut grad_f_b1023.try_slice_mut(g * m * n..).unwrap(),
+                        [op.groups * m * n, n, 1],
+                    )
+                    .unwrap();
+                }
             }
 
             // sum all the gradients collected in our broadcasted grad_f
@@ -6,7 +6,7 @@ mod cuda_kernel;
 use crate::{
     gradients::Tape,
     shapes::*,
-    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
 };
 
 #[repr(C)]
@@ -22,6 +22,8 @@ pub(super) struct Conv2DOp {
     pub h_out: usize,
     pub w_in: usize,
     pub w_out: usize,
+    pub groups: usize,
+    pub channels_last: bool,
 }
 
 impl Conv2DOp {
@@ -37,9 +39,23 @@ impl Conv2DOp {
             h_out: (h_in + 2 * p - k) / s + 1,
             w_in,
             w_out: (w_in + 2 * p - k) / s + 1,
+            groups: 1,
+            channels_last: false,
         }
     }
 
+    /// Splits the input & output channels into `groups` independent convolutions.
+    fn with_groups(mut self, groups: usize) -> Self {
+        self.groups = groups;
+        self
+    }
+
+    /// Marks the images as stored in (batch, height, width, channel) order.
+    fn with_channels_last(mut self) -> Self {
+        self.channels_last = true;
+        self
+    }
+
     #[rustfmt::skip]
     pub(super) fn inp_patches_shape(&self) -> (usize, usize, usize, usize, usize) {
         (self.chan_in, self.kernel, self.kernel, self.h_out, self.w_out)
@@ -51,7 +67,12 @@ impl Conv2DOp {
     }
 
     pub(super) fn filters_tr_shape(&self) -> (usize, usize, usize, usize) {
-        (self.chan_in, self.chan_out, self.kernel, self.kernel)
+        (
+            self.chan_in,
+            self.chan_out / self.groups,
+            self.kernel,
+            self.kernel,
+        )
     }
 }
 
@@ -87,6 +108,12 @@ where
     type Convolved = Const<{ (D + 2 * P - K) / S + 1 }>;
 }
 
+/// Convolution over `(C, H, W)` or `(B, C, H, W)` images with `(O, C / G, K, K)` filters.
+///
+/// When the filters have fewer input channels than the image, the convolution is grouped:
+/// the `C` input channels & `O` output channels are split into `G` groups, and each group of
+/// output channels only sees its own group of input channels. A depthwise convolution
+/// has filters with a single input channel, i.e. `G == C`.
 pub trait TryConv2DTo<F, const S: usize, const P: usize>: HasErr {
     type Output;
     fn conv2d_to(self, filters: F) -> Self::Output {
@@ -113,19 +140,60 @@ pub trait TryConv2D<F> {
     }
 }
 
+/// Convolution over channels last images, i.e. `(H, W, C)` or `(B, H, W, C)`.
+/// Filters keep the same `(O, C / G, K, K)` layout as [TryConv2DTo].
+pub trait TryConv2DNhwcTo<F, const S: usize, const P: usize>: HasErr {
+    type Output;
+    fn conv2d_nhwc_to(self, filters: F) -> Self::Output {
+        self.try_conv2d_nhwc_to(filters).unwrap()
+    }
+    fn try_conv2d_nhwc_to(self, filters: F) -> Result<Self::Output, Self::Err>;
+}
+
+pub trait TryConv2DNhwc<F> {
+    fn conv2d_nhwc<const S: usize, const P: usize>(self, filters: F) -> Self::Output
+    where
+        Self: TryConv2DNhwcTo<F, S, P>,
+    {
+        self.conv2d_nhwc_to(filters)
+    }
+    fn try_conv2d_nhwc<const S: usize, const P: usize>(
+        self,
+        filters: F,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: TryConv2DNhwcTo<F, S, P>,
+    {
+        self.try_conv2d_nhwc_to(filters)
+    }
+}
+
+/// Fails to compile unless the `C` input channels & `O` output channels can be split
+/// into groups of `CG` input channels.
+struct Groups<const C: usize, const CG: usize, const O: usize>;
+
+impl<const C: usize, const CG: usize, const O: usize> Groups<C, CG, O> {
+    const CHECK: () = assert!(
+        CG > 0 && C % CG == 0 && O % (C / CG) == 0,
+        "channels must be divisible by the number of groups"
+    );
+}
+
 impl<T, F> TryConv2D<F> for T {}
+impl<T, F> TryConv2DNhwc<F> for T {}
 
 impl<
         const C: usize,
         const H: usize,
         const W: usize,
         const O: usize,
+        const CG: usize,
         const K: usize,
         const S: usize,
         const P: usize,
         D: Conv2DKernel<f32> + ZerosTensor<f32>,
         T: 'static + Tape<D>,
-    > TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P> for Tensor<Rank3<C, H, W>, f32, D, T>
+    > TryConv2DTo<Tensor<Rank4<O, CG, K, K>, f32, D>, S, P> for Tensor<Rank3<C, H, W>, f32, D, T>
 where
     Const<H>: ConvAlgebra<K, S, P>,
     Const<W>: ConvAlgebra<K, S, P>,
@@ -141,11 +209,14 @@ where
         T,
     >;
 
+    #[track_caller]
     fn try_conv2d_to(
         self,
-        filters: Tensor<Rank4<O, C, K, K>, f32, D>,
+        filters: Tensor<Rank4<O, CG, K, K>, f32, D>,
     ) -> Result<Self::Output, Self::Err> {
-        let op = Conv2DOp::new(S, P, K, [1, C, H, W], O);
+        let () = Groups::<C, CG, O>::CHECK;
+        let op = Conv2DOp::new(S, P, K, [1, C, H, W], O).with_groups(C / CG);
+        DeviceMismatch::check_same("conv2d", &self.device, &filters.device)?;
         let (lhs, ltape) = self.split_tape();
         let (rhs, rtape) = filters.split_tape();
         let mut tape = ltape.merge(rtape);
@@ -171,12 +242,13 @@ impl<
         const H: usize,
         const W: usize,
         const O: usize,
+        const CG: usize,
         const K: usize,
         const S: usize,
         const P: usize,
         D: Conv2DKernel<f32> + ZerosTensor<f32>,
         T: 'static + Tape<D>,
-    > TryConv2DTo<Tensor<Rank4<O, C, K, K>, f32, D>, S, P>
+    > TryConv2DTo<Tensor<Rank4<O, CG, K, K>, f32, D>, S, P>
     for Tensor<(B, Const<C>, Const<H>, Const<W>), f32, D, T>
 where
     Const<H>: ConvAlgebra<K, S, P>,
@@ -193,12 +265,15 @@ where
         D,
         T,
     >;
+    #[track_caller]
     fn try_conv2d_to(
         self,
-        filters: Tensor<Rank4<O, C, K, K>, f32, D>,
+        filters: Tensor<Rank4<O, CG, K, K>, f32, D>,
     ) -> Result<Self::Output, Self::Err> {
         let batch = self.shape().0;
-        let op = Conv2DOp::new(S, P, K, [batch.size(), C, H, W], O);
+        let () = Groups::<C, CG, O>::CHECK;
+        let op = Conv2DOp::new(S, P, K, [batch.size(), C, H, W], O).with_groups(C / CG);
+        DeviceMismatch::check_same("conv2d", &self.device, &filters.device)?;
         let (lhs, ltape) = self.split_tape();
         let (rhs, rtape) = filters.split_tape();
         let mut out =
@@ -221,10 +296,128 @@ where
     }
 }
 
+impl<
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const CG: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D: Conv2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv2DNhwcTo<Tensor<Rank4<O, CG, K, K>, f32, D>, S, P>
+    for Tensor<Rank3<H, W, C>, f32, D, T>
+where
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+            Const<O>,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv2d_nhwc_to(
+        self,
+        filters: Tensor<Rank4<O, CG, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let () = Groups::<C, CG, O>::CHECK;
+        let op = Conv2DOp::new(S, P, K, [1, C, H, W], O)
+            .with_groups(C / CG)
+            .with_channels_last();
+        DeviceMismatch::check_same("conv2d_nhwc", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let mut out = lhs.device.try_zeros()?;
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const CG: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D: Conv2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv2DNhwcTo<Tensor<Rank4<O, CG, K, K>, f32, D>, S, P>
+    for Tensor<(B, Const<H>, Const<W>, Const<C>), f32, D, T>
+where
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            B,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+            Const<O>,
+        ),
+        f32,
+        D,
+        T,
+    >;
+    #[track_caller]
+    fn try_conv2d_nhwc_to(
+        self,
+        filters: Tensor<Rank4<O, CG, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let batch = self.shape().0;
+        let () = Groups::<C, CG, O>::CHECK;
+        let op = Conv2DOp::new(S, P, K, [batch.size(), C, H, W], O)
+            .with_groups(C / CG)
+            .with_channels_last();
+        DeviceMismatch::check_same("conv2d_nhwc", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut out =
+            lhs.device
+                .try_zeros_like(&(batch, Default::default(), Default::default(), Const))?;
+        let mut tape = ltape.merge(rtape);
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{tensor::*, tensor_ops::*, tests::*};
+    use crate::{gradients::NoneTape, tensor::*, tensor_ops::*, tests::*};
 
     #[test]
     /// Produced by
@@ -472,4 +665,127 @@ mod tests {
             [[-0.19717735, -0.19717735, -0.19717735],[-0.19717735, 1.3412137, 2.9476144],[-0.19717735, 4.247249, -2.1779637]],
         ]);
     }
+
+    #[test]
+    fn test_conv2d_grouped_matches_conv2d_per_group() {
+        let dev = TestDevice::seed_from_u64(12);
+        let weight = dev.sample_normal::<Rank4<6, 2, 2, 2>>();
+        let x = dev.sample_normal::<Rank3<4, 5, 4>>();
+        let y = x.trace().conv2d::<1, 1>(weight.clone());
+        let [y0, y1, y2, y3, y4, y5] = y.array();
+        let g = y.exp().sum().backward();
+
+        let [x0, x1, x2, x3] = x.array();
+        let [w0, w1, w2, w3, w4, w5] = weight.array();
+        let xs = [dev.tensor([x0, x1]), dev.tensor([x2, x3])];
+        let ws = [dev.tensor([w0, w1, w2]), dev.tensor([w3, w4, w5])];
+        let ys = [
+            xs[0].trace().conv2d::<1, 1>(ws[0].clone()),
+            xs[1].trace().conv2d::<1, 1>(ws[1].clone()),
+        ];
+
+        assert_close(&[y0, y1, y2], &ys[0].array());
+        assert_close(&[y3, y4, y5], &ys[1].array());
+
+        let [ya, yb] = ys;
+        let gs = (ya.exp().sum() + yb.exp().sum()).backward();
+
+        let [gx0, gx1, gx2, gx3] = g.get(&x).array();
+        assert_close(&[gx0, gx1], &gs.get(&xs[0]).array());
+        assert_close(&[gx2, gx3], &gs.get(&xs[1]).array());
+        let [gw0, gw1, gw2, gw3, gw4, gw5] = g.get(&weight).array();
+        assert_close(&[gw0, gw1, gw2], &gs.get(&ws[0]).array());
+        assert_close(&[gw3, gw4, gw5], &gs.get(&ws[1]).array());
+    }
+
+    #[test]
+    fn test_conv2d_depthwise() {
+        let dev = TestDevice::seed_from_u64(5);
+        let weight = dev.sample_normal::<Rank4<3, 1, 3, 3>>();
+        let x = dev.sample_normal::<Rank4<2, 3, 4, 4>>();
+        let y = x.trace().conv2d::<2, 1>(weight.clone());
+        let y_arr = y.array();
+        let g = y.exp().sum().backward();
+
+        let x_arr = x.array();
+        let w_arr = weight.array();
+        let gx = g.get(&x).array();
+        let gw = g.get(&weight).array();
+        for c in 0..3 {
+            let xc = dev.tensor([[x_arr[0][c]], [x_arr[1][c]]]);
+            let wc = dev.tensor([w_arr[c]]);
+            let yc = xc.trace().conv2d::<2, 1>(wc.clone());
+            assert_close(&[[y_arr[0][c]], [y_arr[1][c]]], &yc.array());
+            let gc = yc.exp().sum().backward();
+            assert_close(&[[gx[0][c]], [gx[1][c]]], &gc.get(&xc).array());
+            assert_close(&[gw[c]], &gc.get(&wc).array());
+        }
+    }
+
+    #[test]
+    fn test_conv2d_nhwc_matches_nchw() {
+        let dev = TestDevice::seed_from_u64(233);
+        let weight = dev.sample_normal::<Rank4<3, 2, 2, 2>>();
+        let x: Tensor<Rank4<2, 5, 4, 2>, f32, _> = dev.sample_normal();
+        let x_nchw = dev.tensor(x.clone().permute::<_, Axes4<0, 3, 1, 2>>().array());
+
+        let y = x.trace().conv2d_nhwc::<2, 1>(weight.clone());
+        let y_nchw = x_nchw.trace().conv2d::<2, 1>(weight.clone());
+        assert_close(
+            &y.array(),
+            &y_nchw
+                .retaped::<NoneTape>()
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+
+        let g = y.exp().mean().backward();
+        let g_nchw = y_nchw.exp().mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &dev.tensor(g_nchw.get(&x_nchw).array())
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+        assert_close(&g.get(&weight).array(), &g_nchw.get(&weight).array());
+    }
+
+    #[test]
+    fn test_conv2d_nhwc_grouped_matches_nchw() {
+        let dev = TestDevice::seed_from_u64(7);
+        let weight = dev.sample_normal::<Rank4<4, 3, 3, 3>>();
+        let x: Tensor<Rank4<2, 5, 5, 6>, f32, _> = dev.sample_normal();
+        let x_nchw = dev.tensor(x.clone().permute::<_, Axes4<0, 3, 1, 2>>().array());
+
+        let y = x.trace().conv2d_nhwc::<1, 1>(weight.clone());
+        let y_nchw = x_nchw.trace().conv2d::<1, 1>(weight.clone());
+        assert_close(
+            &y.array(),
+            &y_nchw
+                .retaped::<NoneTape>()
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+
+        let g = y.exp().mean().backward();
+        let g_nchw = y_nchw.exp().mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &dev.tensor(g_nchw.get(&x_nchw).array())
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+        assert_close(&g.get(&weight).array(), &g_nchw.get(&weight).array());
+    }
+
+    #[test]
+    fn test_conv2d_nhwc_3d() {
+        let dev = TestDevice::seed_from_u64(2);
+        let weight = dev.sample_normal::<Rank4<2, 3, 3, 3>>();
+        let x: Tensor<Rank3<4, 4, 3>, f32, _> = dev.sample_normal();
+        let x_nchw = dev.tensor(x.clone().permute::<_, Axes3<2, 0, 1>>().array());
+        let y: Tensor<Rank3<4, 4, 2>, _, _> = x.conv2d_nhwc::<1, 1>(weight.clone());
+        let y_nchw = x_nchw.conv2d::<1, 1>(weight);
+        assert_close(&y.array(), &y_nchw.permute::<_, Axes3<1, 2, 0>>().array());
+    }
 }
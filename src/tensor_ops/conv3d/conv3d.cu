@@ -0,0 +1,191 @@
+struct Conv3DOp {
+    size_t stride;
+    size_t padding;
+    size_t kernel;
+    size_t batch;
+    size_t chan_in;
+    size_t chan_out;
+    size_t d_in;
+    size_t d_out;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+// maps input position x to its output position along a single axis, returns false
+// if kernel element k doesn't map x to any output position.
+__device__ bool unfold_idx(const Conv3DOp &op, size_t k, size_t x, size_t n_out, size_t *out) {
+    size_t o = x + op.padding;
+    if (o < k) {
+        return false;
+    }
+    o -= k;
+    if (o % op.stride != 0) {
+        return false;
+    }
+    o /= op.stride;
+    if (o >= n_out) {
+        return false;
+    }
+    *out = o;
+    return true;
+}
+
+extern "C" __global__ void unfold_input_into_patches(
+    const Conv3DOp op,
+    const float *image, // 5d (Batch, Channels, Depth, Height, Width)
+    float *patches // 8d (Batch, Channels, KernelSize, KernelSize, KernelSize, DepthOut, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_in * op.kernel * op.kernel * op.kernel * op.d_out * op.h_out * op.w_out;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t od = idx % op.d_out;
+    idx /= op.d_out;
+    const size_t k3 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k2 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k1 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t z_plus_p = od * op.stride + k1;
+    if (z_plus_p < op.padding) {
+        return;
+    }
+    const size_t z = z_plus_p - op.padding;
+    if (z >= op.d_in) {
+        return;
+    }
+
+    const size_t y_plus_p = oh * op.stride + k2;
+    if (y_plus_p < op.padding) {
+        return;
+    }
+    const size_t y = y_plus_p - op.padding;
+    if (y >= op.h_in) {
+        return;
+    }
+
+    const size_t x_plus_p = ow * op.stride + k3;
+    if (x_plus_p < op.padding) {
+        return;
+    }
+    const size_t x = x_plus_p - op.padding;
+    if (x >= op.w_in) {
+        return;
+    }
+
+    const size_t vol_in = op.d_in * op.h_in * op.w_in;
+    patches[i] = image[b * (op.chan_in * vol_in) + c * vol_in + (z * op.h_in + y) * op.w_in + x];
+}
+
+extern "C" __global__ void unfold_output_into_patches(
+    const Conv3DOp op,
+    const float *image_out, // 5d (Batch, ChanOut, DepthOut, HeightOut, WidthOut)
+    float *patches // 8d (Batch, ChanOut, KernelSize, KernelSize, KernelSize, Depth, Height, Width)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_out * op.kernel * op.kernel * op.kernel * op.d_in * op.h_in * op.w_in;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t x = idx % op.w_in;
+    idx /= op.w_in;
+    const size_t y = idx % op.h_in;
+    idx /= op.h_in;
+    const size_t z = idx % op.d_in;
+    idx /= op.d_in;
+    const size_t k3 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k2 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k1 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t od, oh, ow;
+    if (!unfold_idx(op, k1, z, op.d_out, &od)
+        || !unfold_idx(op, k2, y, op.h_out, &oh)
+        || !unfold_idx(op, k3, x, op.w_out, &ow)) {
+        return;
+    }
+
+    const size_t vol_out = op.d_out * op.h_out * op.w_out;
+    patches[i] = image_out[b * (op.chan_out * vol_out) + o * vol_out + (od * op.h_out + oh) * op.w_out + ow];
+}
+
+extern "C" __global__ void transpose_and_broadcast_filters(
+    const Conv3DOp op,
+    const float *filters, // 5d (ChanOut, ChanIn, KernelSize, KernelSize, KernelSize)
+    float *filters_tr // 6d (Batch, ChanIn, ChanOut, KernelSize, KernelSize, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto kernel_numel = op.kernel * op.kernel * op.kernel;
+    auto numel = op.chan_in * op.chan_out * kernel_numel;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t k = idx % kernel_numel;
+    idx /= kernel_numel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+
+    auto i_tr = c * (op.chan_out * kernel_numel) + o * kernel_numel + k;
+
+    const float f = filters[i];
+    for (auto b = 0; b < op.batch; b++) {
+        filters_tr[b * numel + i_tr] = f;
+    }
+}
+
+extern "C" __global__ void sum_transposed_filters(
+    const Conv3DOp op,
+    const float *filters_tr, // 6d (Batch, ChanIn, ChanOut, KernelSize, KernelSize, KernelSize)
+    float *filters // 5d (ChanOut, ChanIn, KernelSize, KernelSize, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto kernel_numel = op.kernel * op.kernel * op.kernel;
+    auto numel = op.chan_out * op.chan_in * kernel_numel;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t k = idx % kernel_numel;
+    idx /= kernel_numel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+
+    auto i_tr = c * (op.chan_out * kernel_numel) + o * kernel_numel + k;
+
+    float tmp = 0.0;
+    for (auto b = 0; b < op.batch; b++) {
+        tmp += filters_tr[b * numel + i_tr];
+    }
+
+    filters[i] += tmp;
+}
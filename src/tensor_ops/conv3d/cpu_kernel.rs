@@ -0,0 +1,242 @@
+use crate::shapes::Shape;
+use crate::tensor::cpu::*;
+
+use super::{Conv3DKernel, Conv3DOp};
+
+use std::sync::Arc;
+
+impl Conv3DOp {
+    /// The position in the output along a single axis of length `n_out` that kernel
+    /// element `k` maps input position `x` to.
+    #[inline(always)]
+    fn unfold_idx(&self, k: usize, x: usize, n_out: usize) -> Option<usize> {
+        let mut o = x + self.padding;
+        if o < k {
+            return None;
+        }
+        o -= k;
+        if o % self.stride != 0 {
+            return None;
+        }
+        o /= self.stride;
+        if o >= n_out {
+            return None;
+        }
+        Some(o)
+    }
+
+    /// Splits the index of an element in a `(K, K, K)` kernel into `[kz, ky, kx]`.
+    #[inline(always)]
+    fn kernel_idx(&self, k: usize) -> [usize; 3] {
+        let kx = k % self.kernel;
+        let ky = (k / self.kernel) % self.kernel;
+        let kz = k / (self.kernel * self.kernel);
+        [kz, ky, kx]
+    }
+}
+
+impl Cpu {
+    #[inline]
+    fn conv3d_forward<P: Shape<Concrete = [usize; 3]>>(
+        &self,
+        op: &Conv3DOp,
+        img: &[f32],
+        filters: &[f32],
+        out: &mut [f32],
+        inp_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let buf = Arc::make_mut(&mut inp_patches_buf.data);
+            let mut i = 0;
+            for c in 0..op.chan_in {
+                for k in 0..op.kernel_numel() {
+                    let [kz, ky, kx] = op.kernel_idx(k);
+                    for oz in 0..op.d_out {
+                        let z = (oz * op.stride + kz).wrapping_sub(op.padding);
+                        for oy in 0..op.h_out {
+                            let y = (oy * op.stride + ky).wrapping_sub(op.padding);
+                            for ox in 0..op.w_out {
+                                let x = (ox * op.stride + kx).wrapping_sub(op.padding);
+                                if z < op.d_in && y < op.h_in && x < op.w_in {
+                                    buf[i] = img[c * op.vol_in() + (z * op.h_in + y) * op.w_in + x];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // (O, C * K^3) * (C * K^3, OD * OH * OW) = (O, OD * OH * OW)
+        let m = op.chan_out;
+        let k = op.chan_in * op.kernel_numel();
+        let n = op.vol_out();
+        self.gemm(
+            View::new(filters, (m, k)),
+            View::new(inp_patches_buf.view().data, (k, n)),
+            &mut ViewMut::new(out, (m, n)),
+        );
+        Ok(())
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn conv3d_backward<P: Shape<Concrete = [usize; 3]>>(
+        &self,
+        op: &Conv3DOp,
+        img: &[f32],
+        grad_img: &mut [f32],
+        filters_tr: &[f32],
+        grad_filters_tr: &mut [f32],
+        grad_out: &[f32],
+        out_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let mut i = 0;
+            let buf = Arc::make_mut(&mut out_patches_buf.data);
+            for o in 0..op.chan_out {
+                for k in 0..op.kernel_numel() {
+                    let [kz, ky, kx] = op.kernel_idx(k);
+                    for z in 0..op.d_in {
+                        let oz = op.unfold_idx(kz, z, op.d_out);
+                        for y in 0..op.h_in {
+                            let oy = op.unfold_idx(ky, y, op.h_out);
+                            for x in 0..op.w_in {
+                                let ox = op.unfold_idx(kx, x, op.w_out);
+                                if let (Some(oz), Some(oy), Some(ox)) = (oz, oy, ox) {
+                                    buf[i] = grad_out
+                                        [o * op.vol_out() + (oz * op.h_out + oy) * op.w_out + ox];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        {
+            // img_g += filters^T * unfold(grad_out)
+            // (C, D * H * W) += (C, O * K^3) * (O * K^3, D * H * W)
+            let m = op.chan_in;
+            let k = op.chan_out * op.kernel_numel();
+            let n = op.vol_in();
+            self.gemm(
+                View::new(filters_tr, (m, k)),
+                View::new(out_patches_buf.view().data, (k, n)),
+                &mut ViewMut::new(grad_img, (m, n)),
+            );
+        }
+
+        {
+            // weight_g^T += img * patches^T
+            // (C, O * K^3) += (C, D * H * W) * (D * H * W, O * K^3)
+            let m = op.chan_in;
+            let k = op.vol_in();
+            let n = op.chan_out * op.kernel_numel();
+            self.gemm(
+                View::new(img, (m, k)),
+                View::new(out_patches_buf.view().data, (n, k)).tr(),
+                &mut ViewMut::new(grad_filters_tr, (m, n)),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Conv3DKernel<f32> for Cpu {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.inp_patches_shape())?;
+        let [lstride, ostride] = match L::NUM_DIMS {
+            4 => [0; 2],
+            5 => [lhs.strides[0], out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let rhs = rhs.data.as_ref();
+        let out = Arc::make_mut(&mut out.data);
+        for i_batch in 0..op.batch {
+            self.conv3d_forward(
+                &op,
+                &lhs[i_batch * lstride..],
+                rhs,
+                &mut out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Self::Storage<L, f32>,
+        grad_lhs: &mut Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        grad_rhs: &mut Self::Storage<R, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.out_patches_shape())?;
+        let mut f102: StridedArray<_, f32> = StridedArray::new(op.filters_tr_shape())?;
+        let mut grad_f102: StridedArray<_, f32> = StridedArray::new(op.filters_tr_shape())?;
+
+        let filter_idx = |[c, o, k]: [usize; 3]| {
+            let [kz, ky, kx] = op.kernel_idx(k);
+            o * rhs.strides[0]
+                + c * rhs.strides[1]
+                + kz * rhs.strides[2]
+                + ky * rhs.strides[3]
+                + kx * rhs.strides[4]
+        };
+
+        {
+            // transpose filters in f102
+            let buf = rhs.data.as_ref();
+            let mut f_iter = f102.iter_mut_with_index();
+            while let Some((f, idx)) = f_iter.next() {
+                *f = buf[filter_idx(idx)];
+            }
+        }
+
+        let [lstride, ostride] = match L::NUM_DIMS {
+            4 => [0; 2],
+            5 => [lhs.strides[0], grad_out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let grad_lhs = Arc::make_mut(&mut grad_lhs.data);
+        let f = f102.data.as_ref();
+        let grad_f = Arc::make_mut(&mut grad_f102.data);
+        let grad_out = grad_out.data.as_ref();
+
+        for i_batch in 0..op.batch {
+            self.conv3d_backward(
+                &op,
+                &lhs[i_batch * lstride..],
+                &mut grad_lhs[i_batch * lstride..],
+                f,
+                grad_f,
+                &grad_out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+
+        {
+            // untranspose filters
+            let buf = Arc::make_mut(&mut grad_rhs.data);
+            let mut f_iter = grad_f102.iter_with_index();
+            while let Some((f, idx)) = f_iter.next() {
+                buf[filter_idx(idx)] += *f;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,389 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+use super::conv2d::ConvAlgebra;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Conv3DOp {
+    pub stride: usize,
+    pub padding: usize,
+    pub kernel: usize,
+    pub batch: usize,
+    pub chan_in: usize,
+    pub chan_out: usize,
+    pub d_in: usize,
+    pub d_out: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+impl Conv3DOp {
+    fn new(s: usize, p: usize, k: usize, [b, c, d_in, h_in, w_in]: [usize; 5], o: usize) -> Self {
+        Self {
+            stride: s,
+            padding: p,
+            kernel: k,
+            batch: b,
+            chan_in: c,
+            chan_out: o,
+            d_in,
+            d_out: (d_in + 2 * p - k) / s + 1,
+            h_in,
+            h_out: (h_in + 2 * p - k) / s + 1,
+            w_in,
+            w_out: (w_in + 2 * p - k) / s + 1,
+        }
+    }
+
+    /// The number of elements in a single `(K, K, K)` kernel.
+    pub(super) fn kernel_numel(&self) -> usize {
+        self.kernel * self.kernel * self.kernel
+    }
+
+    /// The number of elements in a single channel of an input volume.
+    pub(super) fn vol_in(&self) -> usize {
+        self.d_in * self.h_in * self.w_in
+    }
+
+    /// The number of elements in a single channel of an output volume.
+    pub(super) fn vol_out(&self) -> usize {
+        self.d_out * self.h_out * self.w_out
+    }
+
+    pub(super) fn inp_patches_shape(&self) -> (usize, usize, usize) {
+        (self.chan_in, self.kernel_numel(), self.vol_out())
+    }
+
+    pub(super) fn out_patches_shape(&self) -> (usize, usize, usize) {
+        (self.chan_out, self.kernel_numel(), self.vol_in())
+    }
+
+    pub(super) fn filters_tr_shape(&self) -> (usize, usize, usize) {
+        (self.chan_in, self.chan_out, self.kernel_numel())
+    }
+}
+
+pub(super) trait Conv3DKernel<E: Dtype>: DeviceStorage {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Self::Storage<L, E>,
+        grad_lhs: &mut Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        grad_rhs: &mut Self::Storage<R, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+pub trait TryConv3DTo<F, const S: usize, const P: usize>: HasErr {
+    type Output;
+    fn conv3d_to(self, filters: F) -> Self::Output {
+        self.try_conv3d_to(filters).unwrap()
+    }
+    fn try_conv3d_to(self, filters: F) -> Result<Self::Output, Self::Err>;
+}
+
+/// **Requires Nightly** 3d convolution over volumes of shape `(C, D, H, W)` or `(B, C, D, H, W)`,
+/// with filters of shape `(O, C, K, K, K)`.
+///
+/// Generics:
+/// - `S`: The stride, the same along depth, height & width.
+/// - `P`: How much zero padding to add to both ends of the depth, height & width.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.conv3d(x, filters, stride=S, padding=P)`
+pub trait TryConv3D<F> {
+    fn conv3d<const S: usize, const P: usize>(self, filters: F) -> Self::Output
+    where
+        Self: TryConv3DTo<F, S, P>,
+    {
+        self.conv3d_to(filters)
+    }
+    fn try_conv3d<const S: usize, const P: usize>(
+        self,
+        filters: F,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: TryConv3DTo<F, S, P>,
+    {
+        self.try_conv3d_to(filters)
+    }
+}
+
+impl<T, F> TryConv3D<F> for T {}
+
+impl<
+        const C: usize,
+        const Z: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D: Conv3DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv3DTo<Tensor<Rank5<O, C, K, K, K>, f32, D>, S, P>
+    for Tensor<Rank4<C, Z, H, W>, f32, D, T>
+where
+    Const<Z>: ConvAlgebra<K, S, P>,
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            Const<O>,
+            <Const<Z> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv3d_to(
+        self,
+        filters: Tensor<Rank5<O, C, K, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let op = Conv3DOp::new(S, P, K, [1, C, Z, H, W], O);
+        DeviceMismatch::check_same("conv3d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let mut out = lhs.device.try_zeros()?;
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        const C: usize,
+        const Z: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        D: Conv3DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConv3DTo<Tensor<Rank5<O, C, K, K, K>, f32, D>, S, P>
+    for Tensor<(B, Const<C>, Const<Z>, Const<H>, Const<W>), f32, D, T>
+where
+    Const<Z>: ConvAlgebra<K, S, P>,
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            B,
+            Const<O>,
+            <Const<Z> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv3d_to(
+        self,
+        filters: Tensor<Rank5<O, C, K, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let batch = self.shape().0;
+        let op = Conv3DOp::new(S, P, K, [batch.size(), C, Z, H, W], O);
+        DeviceMismatch::check_same("conv3d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut out = lhs.device.try_zeros_like(&(
+            batch,
+            Const::<O>,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ))?;
+        let mut tape = ltape.merge(rtape);
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+    use std::vec::Vec;
+
+    /// Naive reference implementation of a `(C, D, H, W)` conv3d on flattened volumes,
+    /// used to check the kernels.
+    fn naive_conv3d(
+        x: &[f32],
+        w: &[f32],
+        [c, d, h, wd]: [usize; 4],
+        o: usize,
+        k: usize,
+        s: usize,
+        p: usize,
+    ) -> Vec<f32> {
+        let [d_out, h_out, w_out] = [d, h, wd].map(|n| (n + 2 * p - k) / s + 1);
+        let mut out = std::vec![0.0; o * d_out * h_out * w_out];
+        let mut i = 0;
+        for i_o in 0..o {
+            for oz in 0..d_out {
+                for oy in 0..h_out {
+                    for ox in 0..w_out {
+                        for i_c in 0..c {
+                            for kz in 0..k {
+                                for ky in 0..k {
+                                    for kx in 0..k {
+                                        let z = (oz * s + kz).wrapping_sub(p);
+                                        let y = (oy * s + ky).wrapping_sub(p);
+                                        let x_ = (ox * s + kx).wrapping_sub(p);
+                                        if z < d && y < h && x_ < wd {
+                                            let w_i =
+                                                (((i_o * c + i_c) * k + kz) * k + ky) * k + kx;
+                                            let x_i = ((i_c * d + z) * h + y) * wd + x_;
+                                            out[i] += w[w_i] * x[x_i];
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        i += 1;
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_conv3d_default_stride_and_padding() {
+        let dev: TestDevice = Default::default();
+        let weight = dev.tensor_from_vec(
+            std::vec![0.1, -0.2, 0.3, 0.4, -0.5, 0.6, 0.7, -0.8],
+            (Const::<1>, Const::<1>, Const::<2>, Const::<2>, Const::<2>),
+        );
+        let x = dev.tensor_from_vec(
+            (0..12).map(|i| i as f32).collect::<Vec<_>>(),
+            (Const::<1>, Const::<2>, Const::<2>, Const::<3>),
+        );
+        let y = x.trace().conv3d::<1, 0>(weight.clone());
+        assert_close(&y.array(), &[[[[1.8, 2.4]]]]);
+        let g = y.sum().backward();
+        assert_close(
+            &g.get(&x).as_vec(),
+            &std::vec![0.1, -0.1, -0.2, 0.3, 0.7, 0.4, -0.5, 0.1, 0.6, 0.7, -0.1, -0.8,],
+        );
+        assert_close(
+            &g.get(&weight).as_vec(),
+            &std::vec![1.0, 3.0, 7.0, 9.0, 13.0, 15.0, 19.0, 21.0],
+        );
+    }
+
+    #[test]
+    fn test_conv3d_stride_and_padding() {
+        let dev = TestDevice::seed_from_u64(3);
+        let weight = dev.sample_normal::<Rank5<3, 2, 3, 3, 3>>();
+        let x = dev.sample_normal::<Rank4<2, 5, 4, 6>>();
+        let y: Tensor<Rank4<3, 3, 2, 3>, _, _, _> = x.trace().conv3d::<2, 1>(weight.clone());
+        let expected = naive_conv3d(&x.as_vec(), &weight.as_vec(), [2, 5, 4, 6], 3, 3, 2, 1);
+        assert_close_with_tolerance(&y.as_vec(), &expected, 1e-5);
+
+        // the output is linear in both the input and the filters, so the gradient of
+        // `sum(y * r)` w.r.t. the input is found by convolving unit volumes
+        let r = dev.sample_normal::<Rank4<3, 3, 2, 3>>();
+        let g = (y * r.clone()).sum().backward();
+        let r = r.as_vec();
+        let grad_x = g.get(&x).as_vec();
+        for i in 0..grad_x.len() {
+            let mut unit = std::vec![0.0; grad_x.len()];
+            unit[i] = 1.0;
+            let y_i = naive_conv3d(&unit, &weight.as_vec(), [2, 5, 4, 6], 3, 3, 2, 1);
+            let expected: f32 = y_i.iter().zip(r.iter()).map(|(a, b)| a * b).sum();
+            assert!((grad_x[i] - expected).abs() < 1e-4);
+        }
+        let grad_w = g.get(&weight).as_vec();
+        for i in 0..grad_w.len() {
+            let mut unit = std::vec![0.0; grad_w.len()];
+            unit[i] = 1.0;
+            let y_i = naive_conv3d(&x.as_vec(), &unit, [2, 5, 4, 6], 3, 3, 2, 1);
+            let expected: f32 = y_i.iter().zip(r.iter()).map(|(a, b)| a * b).sum();
+            assert!((grad_w[i] - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_batched_conv3d_matches_unbatched() {
+        let dev = TestDevice::seed_from_u64(7);
+        let weight = dev.sample_normal::<Rank5<2, 3, 2, 2, 2>>();
+        let x = dev.sample_normal::<Rank5<2, 3, 4, 3, 5>>();
+
+        let y: Tensor<Rank5<2, 2, 3, 2, 4>, _, _, _> = x.trace().conv3d::<1, 0>(weight.clone());
+        let y_vec = y.as_vec();
+        let g = y.exp().sum().backward();
+        let (grad_x, grad_w) = (g.get(&x).as_vec(), g.get(&weight).as_vec());
+
+        let x_vec = x.as_vec();
+        let mut expected_grad_w = std::vec![0.0; grad_w.len()];
+        for (i, x_i) in x_vec.chunks(x_vec.len() / 2).enumerate() {
+            let x_i: Tensor<Rank4<3, 4, 3, 5>, _, _> =
+                dev.tensor_from_vec(x_i.to_vec(), Default::default());
+            let y_i: Tensor<Rank4<2, 3, 2, 4>, _, _, _> =
+                x_i.trace().conv3d::<1, 0>(weight.clone());
+            assert_close(&y_vec[i * 48..(i + 1) * 48].to_vec(), &y_i.as_vec());
+            let g_i = y_i.exp().sum().backward();
+            assert_close(
+                &grad_x[i * 180..(i + 1) * 180].to_vec(),
+                &g_i.get(&x_i).as_vec(),
+            );
+            for (a, b) in expected_grad_w.iter_mut().zip(g_i.get(&weight).as_vec()) {
+                *a += b;
+            }
+        }
+        assert_close(&grad_w, &expected_grad_w);
+    }
+}
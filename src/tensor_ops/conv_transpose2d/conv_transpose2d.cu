@@ -0,0 +1,156 @@
+struct ConvTrans2DOp {
+    size_t stride;
+    size_t padding;
+    size_t kernel;
+    size_t batch;
+    size_t chan_in;
+    size_t chan_out;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+extern "C" __global__ void unfold_input_into_patches(
+    const ConvTrans2DOp op,
+    const float *image, // 4d (Batch, ChanIn, Height, Width)
+    float *patches // 6d (Batch, ChanIn, KernelSize, KernelSize, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_in * op.kernel * op.kernel * op.h_out * op.w_out;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t x = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t y = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t k2 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k1 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    size_t h = y + op.padding;
+    if (h < k1) {
+        return;
+    }
+    h -= k1;
+    if (h % op.stride != 0) {
+        return;
+    }
+    h /= op.stride;
+    if (h >= op.h_in) {
+        return;
+    }
+
+    size_t w = x + op.padding;
+    if (w < k2) {
+        return;
+    }
+    w -= k2;
+    if (w % op.stride != 0) {
+        return;
+    }
+    w /= op.stride;
+    if (w >= op.w_in) {
+        return;
+    }
+
+    patches[i] = image[b * (op.chan_in * op.h_in * op.w_in) + c * (op.h_in * op.w_in) + h * op.w_in + w];
+}
+
+extern "C" __global__ void unfold_output_into_patches(
+    const ConvTrans2DOp op,
+    const float *image_out, // 4d (Batch, ChanOut, HeightOut, WidthOut)
+    float *patches // 6d (Batch, ChanOut, KernelSize, KernelSize, Height, Width)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const auto patches_numel = op.batch * op.chan_out * op.kernel * op.kernel * op.h_in * op.w_in;
+    if (i >= patches_numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t w = idx % op.w_in;
+    idx /= op.w_in;
+    const size_t h = idx % op.h_in;
+    idx /= op.h_in;
+    const size_t k2 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k1 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t y_plus_p = h * op.stride + k1;
+    if (y_plus_p < op.padding) {
+        return;
+    }
+    const size_t y = y_plus_p - op.padding;
+    if (y >= op.h_out) {
+        return;
+    }
+
+    const size_t x_plus_p = w * op.stride + k2;
+    if (x_plus_p < op.padding) {
+        return;
+    }
+    const size_t x = x_plus_p - op.padding;
+    if (x >= op.w_out) {
+        return;
+    }
+
+    patches[i] = image_out[b * (op.chan_out * op.h_out * op.w_out) + o * (op.h_out * op.w_out) + y * op.w_out + x];
+}
+
+extern "C" __global__ void transpose_filters(
+    const ConvTrans2DOp op,
+    const float *filters, // 4d (ChanIn, ChanOut, KernelSize, KernelSize)
+    float *filters_tr // 4d (ChanOut, ChanIn, KernelSize, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    auto numel = op.chan_in * op.chan_out * op.kernel * op.kernel;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t k2 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t k1 = idx % op.kernel;
+    idx /= op.kernel;
+    const size_t o = idx % op.chan_out;
+    idx /= op.chan_out;
+    const size_t c = idx % op.chan_in;
+    idx /= op.chan_in;
+
+    auto i_tr = o * (op.chan_in * op.kernel * op.kernel) + c * (op.kernel * op.kernel) + k1 * (op.kernel) + k2;
+    filters_tr[i_tr] = filters[i];
+}
+
+extern "C" __global__ void sum_batched_filters(
+    const ConvTrans2DOp op,
+    const float *filters_b, // 5d (Batch, ChanIn, ChanOut, KernelSize, KernelSize)
+    float *filters // 4d (ChanIn, ChanOut, KernelSize, KernelSize)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    auto numel = op.chan_in * op.chan_out * op.kernel * op.kernel;
+    if (i >= numel) {
+        return;
+    }
+
+    float tmp = 0.0;
+    for (auto b = 0; b < op.batch; b++) {
+        tmp += filters_b[b * numel + i];
+    }
+
+    filters[i] += tmp;
+}
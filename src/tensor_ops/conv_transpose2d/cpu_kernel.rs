@@ -0,0 +1,223 @@
+use crate::shapes::Shape;
+use crate::tensor::cpu::*;
+
+use super::{ConvTrans2DKernel, ConvTrans2DOp};
+
+use std::sync::Arc;
+
+impl ConvTrans2DOp {
+    /// The input pixel that kernel element `[k1, k2]` maps onto output pixel `[y, x]`, if any.
+    #[inline(always)]
+    fn fold_idx(&self, [k1, k2, y, x]: [usize; 4]) -> Option<[usize; 2]> {
+        let mut h = y + self.padding;
+        if h < k1 {
+            return None;
+        }
+        h -= k1;
+        if h % self.stride != 0 {
+            return None;
+        }
+        h /= self.stride;
+        if h >= self.h_in {
+            return None;
+        }
+
+        let mut w = x + self.padding;
+        if w < k2 {
+            return None;
+        }
+        w -= k2;
+        if w % self.stride != 0 {
+            return None;
+        }
+        w /= self.stride;
+        if w >= self.w_in {
+            return None;
+        }
+
+        Some([h, w])
+    }
+}
+
+impl Cpu {
+    #[inline]
+    fn conv_transpose2d_forward<P: Shape<Concrete = [usize; 5]>>(
+        &self,
+        op: &ConvTrans2DOp,
+        img: &[f32],
+        filters_tr: &[f32],
+        out: &mut [f32],
+        inp_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let buf = Arc::make_mut(&mut inp_patches_buf.data);
+            let mut i = 0;
+            for c in 0..op.chan_in {
+                for k1 in 0..op.kernel {
+                    for k2 in 0..op.kernel {
+                        for y in 0..op.h_out {
+                            for x in 0..op.w_out {
+                                if let Some([h, w]) = op.fold_idx([k1, k2, y, x]) {
+                                    buf[i] = img[c * (op.h_in * op.w_in) + h * op.w_in + w];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // (O, C * K * K) * (C * K * K, OH * OW) = (O, OH * OW)
+        let m = op.chan_out;
+        let k = op.chan_in * op.kernel * op.kernel;
+        let n = op.h_out * op.w_out;
+        self.gemm(
+            View::new(filters_tr, (m, k)),
+            View::new(inp_patches_buf.view().data, (k, n)),
+            &mut ViewMut::new(out, (m, n)),
+        );
+        Ok(())
+    }
+
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    fn conv_transpose2d_backward<P: Shape<Concrete = [usize; 5]>>(
+        &self,
+        op: &ConvTrans2DOp,
+        img: &[f32],
+        grad_img: &mut [f32],
+        filters: &[f32],
+        grad_filters: &mut [f32],
+        grad_out: &[f32],
+        out_patches_buf: &mut StridedArray<P, f32>,
+    ) -> Result<(), CpuError> {
+        {
+            let buf = Arc::make_mut(&mut out_patches_buf.data);
+            let mut i = 0;
+            for o in 0..op.chan_out {
+                for k1 in 0..op.kernel {
+                    for k2 in 0..op.kernel {
+                        for h in 0..op.h_in {
+                            for w in 0..op.w_in {
+                                let y = (h * op.stride + k1).wrapping_sub(op.padding);
+                                let x = (w * op.stride + k2).wrapping_sub(op.padding);
+                                if y < op.h_out && x < op.w_out {
+                                    buf[i] = grad_out[o * (op.h_out * op.w_out) + y * op.w_out + x];
+                                }
+                                i += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        {
+            // img_g += filters * unfold(grad_out)
+            // (C, H * W) += (C, O * K * K) * (O * K * K, H * W)
+            let m = op.chan_in;
+            let k = op.chan_out * op.kernel * op.kernel;
+            let n = op.h_in * op.w_in;
+            self.gemm(
+                View::new(filters, (m, k)),
+                View::new(out_patches_buf.view().data, (k, n)),
+                &mut ViewMut::new(grad_img, (m, n)),
+            );
+        }
+
+        {
+            // weight_g += img * unfold(grad_out)^T
+            // (C, O * K * K) += (C, H * W) * (H * W, O * K * K)
+            let m = op.chan_in;
+            let k = op.h_in * op.w_in;
+            let n = op.chan_out * op.kernel * op.kernel;
+            self.gemm(
+                View::new(img, (m, k)),
+                View::new(out_patches_buf.view().data, (n, k)).tr(),
+                &mut ViewMut::new(grad_filters, (m, n)),
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ConvTrans2DKernel<f32> for Cpu {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: ConvTrans2DOp,
+        lhs: &Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.inp_patches_shape())?;
+        let mut f1023: StridedArray<_, f32> = StridedArray::new(op.filters_tr_shape())?;
+
+        {
+            // transpose filters in f1023
+            let buf = rhs.data.as_ref();
+            let mut f_iter = f1023.iter_mut_with_index();
+            while let Some((f, [o, c, k1, k2])) = f_iter.next() {
+                let idx = c * rhs.strides[0]
+                    + o * rhs.strides[1]
+                    + k1 * rhs.strides[2]
+                    + k2 * rhs.strides[3];
+                *f = buf[idx];
+            }
+        }
+
+        let [lstride, ostride] = match L::NUM_DIMS {
+            3 => [0; 2],
+            4 => [lhs.strides[0], out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let f = f1023.data.as_ref();
+        let out = Arc::make_mut(&mut out.data);
+        for i_batch in 0..op.batch {
+            self.conv_transpose2d_forward(
+                &op,
+                &lhs[i_batch * lstride..],
+                f,
+                &mut out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: ConvTrans2DOp,
+        lhs: &Self::Storage<L, f32>,
+        grad_lhs: &mut Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        grad_rhs: &mut Self::Storage<R, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut patches: StridedArray<_, f32> = StridedArray::new(op.out_patches_shape())?;
+        let [lstride, ostride] = match L::NUM_DIMS {
+            3 => [0; 2],
+            4 => [lhs.strides[0], grad_out.strides[0]],
+            _ => unreachable!(),
+        };
+        let lhs = lhs.data.as_ref();
+        let grad_lhs = Arc::make_mut(&mut grad_lhs.data);
+        let f = rhs.data.as_ref();
+        let grad_f = Arc::make_mut(&mut grad_rhs.data);
+        let grad_out = grad_out.data.as_ref();
+
+        for i_batch in 0..op.batch {
+            self.conv_transpose2d_backward(
+                &op,
+                &lhs[i_batch * lstride..],
+                &mut grad_lhs[i_batch * lstride..],
+                f,
+                grad_f,
+                &grad_out[i_batch * ostride..],
+                &mut patches,
+            )?;
+        }
+        Ok(())
+    }
+}
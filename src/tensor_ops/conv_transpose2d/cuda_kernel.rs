@@ -0,0 +1,154 @@
+use cudarc::driver::{AsKernelParam, LaunchAsync, LaunchConfig};
+
+use crate::tensor_ops::matmul::cuda_kernel::sgemm_batch;
+use crate::{shapes::*, tensor::cuda::Cuda};
+
+use std::sync::Arc;
+
+const MODULE_NAME: &str = "conv_transpose2d";
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/conv_transpose2d.ptx"));
+const UNFOLD_INPUT_FN: &str = "unfold_input_into_patches";
+const UNFOLD_OUTPUT_FN: &str = "unfold_output_into_patches";
+const TR_FILTERS_FN: &str = "transpose_filters";
+const COLLECT_GRADS_FN: &str = "sum_batched_filters";
+const ALL_FN_NAMES: [&str; 4] = [
+    UNFOLD_INPUT_FN,
+    UNFOLD_OUTPUT_FN,
+    TR_FILTERS_FN,
+    COLLECT_GRADS_FN,
+];
+
+unsafe impl AsKernelParam for super::ConvTrans2DOp {}
+
+impl super::ConvTrans2DKernel<f32> for Cuda {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: super::ConvTrans2DOp,
+        lhs: &Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        assert_eq!(
+            lhs.shape().strides(),
+            lhs.strides,
+            "Only works with contiguous image strides"
+        );
+
+        if !self.dev.has_func(MODULE_NAME, ALL_FN_NAMES[0]) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let patches_numel = op.batch * op.chan_in * op.kernel * op.kernel * op.h_out * op.w_out;
+        let mut patches = self.dev.alloc_zeros_async::<f32>(patches_numel)?;
+
+        let unfold_fn = self.dev.get_func(MODULE_NAME, UNFOLD_INPUT_FN).unwrap();
+        let cfg = LaunchConfig::for_num_elems(patches.len() as u32);
+        let params = (op, lhs.data.as_ref(), &mut patches);
+        unsafe { unfold_fn.launch_async(cfg, params) }?;
+
+        let mut f1023 = self
+            .dev
+            .alloc_zeros_async::<f32>(rhs.shape.num_elements())?;
+        let tr_fn = self.dev.get_func(MODULE_NAME, TR_FILTERS_FN).unwrap();
+        let cfg = LaunchConfig::for_num_elems(rhs.shape.num_elements() as u32);
+        let params = (op, rhs.data.as_ref(), &mut f1023);
+        unsafe { tr_fn.launch_async(cfg, params) }?;
+
+        // (O, C * K * K) * (B, C * K * K, OH * OW) = (B, O, OH * OW)
+        let m = op.chan_out;
+        let k = op.chan_in * op.kernel * op.kernel;
+        let n = op.h_out * op.w_out;
+        unsafe {
+            sgemm_batch(
+                self.blas.as_ref(),
+                (op.batch, m, k, n),
+                &f1023,
+                [0, k, 1],
+                &patches,
+                [k * n, n, 1],
+                0.0,
+                Arc::make_mut(&mut out.data),
+                [m * n, n, 1],
+            )
+            .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: super::ConvTrans2DOp,
+        lhs: &Self::Storage<L, f32>,
+        grad_lhs: &mut Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        grad_rhs: &mut Self::Storage<R, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let patches_numel = op.batch * op.chan_out * op.kernel * op.kernel * op.h_in * op.w_in;
+        let mut patches = self.dev.alloc_zeros_async::<f32>(patches_numel)?;
+
+        {
+            // unfold grad_out into patches
+            let unfold_fn = self.dev.get_func(MODULE_NAME, UNFOLD_OUTPUT_FN).unwrap();
+            let cfg = LaunchConfig::for_num_elems(patches_numel as u32);
+            let params = (op, grad_out.data.as_ref(), &mut patches);
+            unsafe { unfold_fn.launch_async(cfg, params) }?;
+        }
+
+        {
+            // img_g += filters * patches
+            // (B, C, H * W) += (C, O * K * K) * (B, O * K * K, H * W)
+            let m = op.chan_in;
+            let k = op.chan_out * op.kernel * op.kernel;
+            let n = op.h_in * op.w_in;
+            unsafe {
+                sgemm_batch(
+                    self.blas.as_ref(),
+                    (op.batch, m, k, n),
+                    rhs.data.as_ref(),
+                    [0, k, 1],
+                    &patches,
+                    [k * n, n, 1],
+                    1.0,
+                    Arc::make_mut(&mut grad_lhs.data),
+                    [m * n, n, 1],
+                )
+                .unwrap();
+            }
+        }
+
+        {
+            // weight_g += img * patches^T
+            // (B, C, O * K * K) += (B, C, H * W) * (B, H * W, O * K * K)
+            let m = op.chan_in;
+            let k = op.h_in * op.w_in;
+            let n = op.chan_out * op.kernel * op.kernel;
+            let mut grad_f_b0123 = self.dev.alloc_zeros_async::<f32>(op.batch * m * n)?;
+            unsafe {
+                sgemm_batch(
+                    self.blas.as_ref(),
+                    (op.batch, m, k, n),
+                    lhs.data.as_ref(),
+                    [m * k, k, 1],
+                    &patches,
+                    [k * n, 1, k],
+                    1.0,
+                    &mut grad_f_b0123,
+                    [m * n, n, 1],
+                )
+                .unwrap();
+            }
+
+            // sum all the gradients collected in our batched grad_f
+            // into grad_rhs
+            let sum_fn = self.dev.get_func(MODULE_NAME, COLLECT_GRADS_FN).unwrap();
+            let cfg = LaunchConfig::for_num_elems(rhs.shape.num_elements() as u32);
+            let params = (op, &grad_f_b0123, Arc::make_mut(&mut grad_rhs.data));
+            unsafe { sum_fn.launch_async(cfg, params) }?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,397 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct ConvTrans2DOp {
+    pub stride: usize,
+    pub padding: usize,
+    pub kernel: usize,
+    pub batch: usize,
+    pub chan_in: usize,
+    pub chan_out: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+impl ConvTrans2DOp {
+    fn new(
+        s: usize,
+        p: usize,
+        op: usize,
+        k: usize,
+        [b, c, h_in, w_in]: [usize; 4],
+        o: usize,
+    ) -> Self {
+        Self {
+            stride: s,
+            padding: p,
+            kernel: k,
+            batch: b,
+            chan_in: c,
+            chan_out: o,
+            h_in,
+            h_out: (h_in - 1) * s + k + op - 2 * p,
+            w_in,
+            w_out: (w_in - 1) * s + k + op - 2 * p,
+        }
+    }
+
+    /// The patches of the input image that each output pixel is made from.
+    pub(super) fn inp_patches_shape(&self) -> (usize, usize, usize, usize, usize) {
+        (
+            self.chan_in,
+            self.kernel,
+            self.kernel,
+            self.h_out,
+            self.w_out,
+        )
+    }
+
+    /// The patches of the output image that each input pixel contributes to.
+    pub(super) fn out_patches_shape(&self) -> (usize, usize, usize, usize, usize) {
+        (
+            self.chan_out,
+            self.kernel,
+            self.kernel,
+            self.h_in,
+            self.w_in,
+        )
+    }
+
+    pub(super) fn filters_tr_shape(&self) -> (usize, usize, usize, usize) {
+        (self.chan_out, self.chan_in, self.kernel, self.kernel)
+    }
+}
+
+pub(super) trait ConvTrans2DKernel<E: Dtype>: DeviceStorage {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: ConvTrans2DOp,
+        lhs: &Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: ConvTrans2DOp,
+        lhs: &Self::Storage<L, E>,
+        grad_lhs: &mut Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        grad_rhs: &mut Self::Storage<R, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The size of a dimension after a transposed convolution with kernel `K`, stride `S`,
+/// padding `P` and output padding `OP`.
+pub trait ConvTransAlgebra<const K: usize, const S: usize, const P: usize, const OP: usize>:
+    ConstDim
+{
+    type Convolved: ConstDim;
+}
+
+impl<const D: usize, const K: usize, const S: usize, const P: usize, const OP: usize>
+    ConvTransAlgebra<K, S, P, OP> for Const<D>
+where
+    Const<{ (D - 1) * S + K + OP - 2 * P }>: Sized,
+{
+    type Convolved = Const<{ (D - 1) * S + K + OP - 2 * P }>;
+}
+
+pub trait TryConvTrans2DTo<F, const S: usize, const P: usize, const OP: usize>: HasErr {
+    type Output;
+    fn conv_transpose2d_to(self, filters: F) -> Self::Output {
+        self.try_conv_transpose2d_to(filters).unwrap()
+    }
+    fn try_conv_transpose2d_to(self, filters: F) -> Result<Self::Output, Self::Err>;
+}
+
+/// **Requires Nightly** Transposed 2d convolution (sometimes called a deconvolution) over
+/// images of shape `(C, H, W)` or `(B, C, H, W)`, with filters of shape `(C, O, K, K)`.
+/// This is the gradient of [super::TryConv2D::conv2d()] with respect to its input, and is
+/// mostly used for upsampling.
+///
+/// Generics:
+/// - `S`: The stride.
+/// - `P`: How much of the zero padding of the equivalent convolution to remove from each side.
+/// - `OP`: How much extra size to add to one side of the output. Since convolutions with
+///   `S > 1` map multiple image sizes to the same output size, this picks which one to get back.
+///
+/// The output height is `(H - 1) * S + K + OP - 2 * P`, and likewise for the width.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.conv_transpose2d(x, filters, stride=S, padding=P, output_padding=OP)`
+pub trait TryConvTrans2D<F> {
+    fn conv_transpose2d<const S: usize, const P: usize, const OP: usize>(
+        self,
+        filters: F,
+    ) -> Self::Output
+    where
+        Self: TryConvTrans2DTo<F, S, P, OP>,
+    {
+        self.conv_transpose2d_to(filters)
+    }
+    fn try_conv_transpose2d<const S: usize, const P: usize, const OP: usize>(
+        self,
+        filters: F,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: TryConvTrans2DTo<F, S, P, OP>,
+    {
+        self.try_conv_transpose2d_to(filters)
+    }
+}
+
+impl<T, F> TryConvTrans2D<F> for T {}
+
+impl<
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D: ConvTrans2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConvTrans2DTo<Tensor<Rank4<C, O, K, K>, f32, D>, S, P, OP>
+    for Tensor<Rank3<C, H, W>, f32, D, T>
+where
+    Const<H>: ConvTransAlgebra<K, S, P, OP>,
+    Const<W>: ConvTransAlgebra<K, S, P, OP>,
+{
+    type Output = Tensor<
+        (
+            Const<O>,
+            <Const<H> as ConvTransAlgebra<K, S, P, OP>>::Convolved,
+            <Const<W> as ConvTransAlgebra<K, S, P, OP>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv_transpose2d_to(
+        self,
+        filters: Tensor<Rank4<C, O, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let op = ConvTrans2DOp::new(S, P, OP, K, [1, C, H, W], O);
+        DeviceMismatch::check_same("conv_transpose2d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let mut out = lhs.device.try_zeros()?;
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+        const OP: usize,
+        D: ConvTrans2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > TryConvTrans2DTo<Tensor<Rank4<C, O, K, K>, f32, D>, S, P, OP>
+    for Tensor<(B, Const<C>, Const<H>, Const<W>), f32, D, T>
+where
+    Const<H>: ConvTransAlgebra<K, S, P, OP>,
+    Const<W>: ConvTransAlgebra<K, S, P, OP>,
+{
+    type Output = Tensor<
+        (
+            B,
+            Const<O>,
+            <Const<H> as ConvTransAlgebra<K, S, P, OP>>::Convolved,
+            <Const<W> as ConvTransAlgebra<K, S, P, OP>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+
+    #[track_caller]
+    fn try_conv_transpose2d_to(
+        self,
+        filters: Tensor<Rank4<C, O, K, K>, f32, D>,
+    ) -> Result<Self::Output, Self::Err> {
+        let batch = self.shape().0;
+        let op = ConvTrans2DOp::new(S, P, OP, K, [batch.size(), C, H, W], O);
+        DeviceMismatch::check_same("conv_transpose2d", &self.device, &filters.device)?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut out = lhs.device.try_zeros_like(&(
+            batch,
+            Const::<O>,
+            Default::default(),
+            Default::default(),
+        ))?;
+        let mut tape = ltape.merge(rtape);
+        lhs.device
+            .forward(op, &lhs.storage, &rhs.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device
+                .backward(op, &lhs.storage, grad_lhs, &rhs.storage, grad_rhs, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    /// Naive reference implementation, where each input pixel adds a scaled copy of
+    /// the kernel to the output.
+    fn naive_conv_transpose2d<
+        const C: usize,
+        const H: usize,
+        const W: usize,
+        const O: usize,
+        const K: usize,
+        const OH: usize,
+        const OW: usize,
+    >(
+        x: [[[f32; W]; H]; C],
+        f: [[[[f32; K]; K]; O]; C],
+        s: usize,
+        p: usize,
+    ) -> [[[f32; OW]; OH]; O] {
+        let mut out = [[[0.0; OW]; OH]; O];
+        for c in 0..C {
+            for h in 0..H {
+                for w in 0..W {
+                    for o in 0..O {
+                        for k1 in 0..K {
+                            for k2 in 0..K {
+                                let y = (h * s + k1).wrapping_sub(p);
+                                let x_ = (w * s + k2).wrapping_sub(p);
+                                if y < OH && x_ < OW {
+                                    out[o][y][x_] += x[c][h][w] * f[c][o][k1][k2];
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_conv_transpose2d_default_stride_and_padding() {
+        let dev: TestDevice = Default::default();
+        let weight = dev.tensor([[[[1.0, 2.0], [3.0, 4.0]], [[-1.0, 0.5], [0.0, 2.0]]]]);
+        let x = dev.tensor([[[1.0, -1.0], [2.0, 0.5]]]);
+        let y = x.trace().conv_transpose2d::<1, 0, 0>(weight.clone());
+        assert_close(
+            &y.array(),
+            &[
+                [[1.0, 1.0, -2.0], [5.0, 5.5, -3.0], [6.0, 9.5, 2.0]],
+                [[-1.0, 1.5, -0.5], [-2.0, 2.5, -1.75], [0.0, 4.0, 1.0]],
+            ],
+        );
+        let g = y.sum().backward();
+        assert_close(&g.get(&x).array(), &[[[11.5; 2]; 2]]);
+        assert_close(&g.get(&weight).array(), &[[[[2.5; 2]; 2], [[2.5; 2]; 2]]]);
+    }
+
+    #[test]
+    fn test_conv_transpose2d_stride_padding_output_padding() {
+        let dev = TestDevice::seed_from_u64(3);
+        let weight = dev.sample_normal::<Rank4<2, 3, 3, 3>>();
+        let x = dev.sample_normal::<Rank3<2, 4, 3>>();
+        let y: Tensor<Rank3<3, 8, 6>, _, _, _> =
+            x.trace().conv_transpose2d::<2, 1, 1>(weight.clone());
+        let expected: [[[f32; 6]; 8]; 3] = naive_conv_transpose2d(x.array(), weight.array(), 2, 1);
+        assert_close(&y.array(), &expected);
+
+        // the gradient of a transposed convolution is a convolution with the same filters
+        let grad_y = dev.sample_normal::<Rank3<3, 8, 6>>();
+        let g = (y * grad_y.clone()).sum().backward();
+        let filters: Tensor<Rank4<2, 3, 3, 3>, f32, _> = weight.clone();
+        let grad_x = grad_y.conv2d::<2, 1>(filters);
+        assert_close(&g.get(&x).array(), &grad_x.array());
+    }
+
+    #[test]
+    fn test_conv_transpose2d_filter_gradient() {
+        let dev = TestDevice::seed_from_u64(7);
+        let weight = dev.sample_normal::<Rank4<3, 2, 2, 2>>();
+        let x = dev.sample_normal::<Rank3<3, 3, 3>>();
+        let y: Tensor<Rank3<2, 6, 6>, _, _, _> =
+            x.trace().conv_transpose2d::<2, 0, 0>(weight.clone());
+        let g = y.sum().backward();
+        // every input pixel touches every kernel element exactly once
+        let x_sums = x.clone().sum::<Rank1<3>, Axes2<1, 2>>().array();
+        let expected = x_sums.map(|s| [[[s; 2]; 2]; 2]);
+        assert_close(&g.get(&weight).array(), &expected);
+    }
+
+    #[test]
+    fn test_batched_conv_transpose2d() {
+        let dev = TestDevice::seed_from_u64(11);
+        let weight = dev.sample_normal::<Rank4<2, 3, 3, 3>>();
+        let x = dev.sample_normal::<Rank4<3, 2, 3, 3>>();
+
+        let y: Tensor<Rank4<3, 3, 6, 6>, _, _, _> =
+            x.trace().conv_transpose2d::<2, 1, 1>(weight.clone());
+        let y_array = y.array();
+        let g = y.exp().mean().backward();
+
+        let mut grad_w = [[[[0.0; 3]; 3]; 3]; 2];
+        for i in 0..3 {
+            let x_i = x.clone().select(dev.tensor(i));
+            let y_i = x_i.trace().conv_transpose2d::<2, 1, 1>(weight.clone());
+            assert_close(&y_array[i], &y_i.array());
+            let g_i = (y_i.exp().sum() / 324.0).backward();
+            assert_close(&g.get(&x).array()[i], &g_i.get(&x_i).array());
+            let w_i = g_i.get(&weight).array();
+            for c in 0..2 {
+                for o in 0..3 {
+                    for k1 in 0..3 {
+                        for k2 in 0..3 {
+                            grad_w[c][o][k1][k2] += w_i[c][o][k1][k2];
+                        }
+                    }
+                }
+            }
+        }
+        assert_close(&g.get(&weight).array(), &grad_w);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype> super::CumProdKernel<E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: StridedArray<S, E> = StridedArray::new(inp.shape)?;
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let buf = Arc::make_mut(&mut out.data);
+        for_each_row(&dims, ax, [&inp_strides, &out_strides], |[i_inp, i_out]| {
+            let mut prod = E::ONE;
+            for j in 0..dims[ax] {
+                prod *= inp.data[i_inp + j * inp_strides[ax]];
+                buf[i_out + j * out_strides[ax]] = prod;
+            }
+        });
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        out: &Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let grad_inp_strides: Vec<usize> = grad_inp.strides.into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let grad_out_strides: Vec<usize> = grad_out.strides.into();
+        let buf = Arc::make_mut(&mut grad_inp.data);
+        for_each_row(
+            &dims,
+            ax,
+            [
+                &inp_strides,
+                &grad_inp_strides,
+                &out_strides,
+                &grad_out_strides,
+            ],
+            |[i_inp, i_grad_inp, i_out, i_grad_out]| {
+                // grad_inp[j] = out[j - 1] * sum_{k >= j} grad_out[k] * inp[j + 1] * ... * inp[k],
+                // where the sum is accumulated backwards, so we never divide by inp[j].
+                let mut sum = E::default();
+                for j in (0..dims[ax]).rev() {
+                    if j + 1 < dims[ax] {
+                        sum *= inp.data[i_inp + (j + 1) * inp_strides[ax]];
+                    }
+                    sum += grad_out.data[i_grad_out + j * grad_out_strides[ax]];
+                    let prev = if j == 0 {
+                        E::ONE
+                    } else {
+                        out.data[i_out + (j - 1) * out_strides[ax]]
+                    };
+                    buf[i_grad_inp + j * grad_inp_strides[ax]] += prev * sum;
+                }
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/cumprod.ptx"));
+const MODULE_NAME: &str = "cumprod";
+const FWD_FN_NAME: &str = "cumprod_forward";
+const BWD_FN_NAME: &str = "cumprod_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::CumProdKernel<f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = inp.shape;
+        let strides = shape.strides();
+        let num_rows = shape.num_elements() / shape.concrete()[ax].max(1);
+        let mut storage = self.dev.alloc_zeros_async::<f32>(shape.num_elements())?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            S::NUM_DIMS,       // const size_t num_dims,
+            ax,                // const size_t ax,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // float *out,
+            &out_strides,      // const size_t *out_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        out: &Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+
+        let shape = inp.shape;
+        let num_rows = shape.num_elements() / shape.concrete()[ax].max(1);
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let grad_inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out.strides.into())?;
+        let grad_out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,                          // const size_t num_rows,
+            S::NUM_DIMS,                       // const size_t num_dims,
+            ax,                                // const size_t ax,
+            &dims,                             // const size_t *dims,
+            inp.data.as_ref(),                 // const float *inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &grad_inp_strides,                 // const size_t *grad_inp_strides,
+            out.data.as_ref(),                 // const float *out,
+            &out_strides,                      // const size_t *out_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &grad_out_strides,                 // const size_t *grad_out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
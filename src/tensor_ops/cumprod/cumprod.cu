@@ -0,0 +1,65 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles a single row along `ax`.
+
+extern "C" __global__ void cumprod_forward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_out = get_row_index(row, num_dims, ax, dims, out_strides);
+
+    float prod = 1.0;
+    for (size_t j = 0; j < dims[ax]; j++) {
+        prod *= inp[i_inp + j * inp_strides[ax]];
+        out[i_out + j * out_strides[ax]] = prod;
+    }
+}
+
+// See the cpu kernel for how the gradient is accumulated without dividing by `inp`.
+extern "C" __global__ void cumprod_backward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *grad_inp,
+    const size_t *grad_inp_strides,
+    const float *out,
+    const size_t *out_strides,
+    const float *grad_out,
+    const size_t *grad_out_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_grad_inp = get_row_index(row, num_dims, ax, dims, grad_inp_strides);
+    size_t i_out = get_row_index(row, num_dims, ax, dims, out_strides);
+    size_t i_grad_out = get_row_index(row, num_dims, ax, dims, grad_out_strides);
+
+    const size_t n = dims[ax];
+    float sum = 0.0;
+    for (size_t j = n; j > 0; j--) {
+        if (j < n) {
+            sum *= inp[i_inp + j * inp_strides[ax]];
+        }
+        sum += grad_out[i_grad_out + (j - 1) * grad_out_strides[ax]];
+        float prev = j == 1 ? 1.0 : out[i_out + (j - 2) * out_strides[ax]];
+        grad_inp[i_grad_inp + (j - 1) * grad_inp_strides[ax]] += prev * sum;
+    }
+}
@@ -0,0 +1,131 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait CumProdKernel<E: Dtype>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        out: &Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Cumulative product along `Ax`. Each element of the result is the product of all
+/// the elements before and including it along `Ax`.
+///
+/// The gradient is computed without dividing by the input, so it is correct
+/// even when the input contains zeros.
+///
+/// **Pytorch equivalent**: `t.cumprod(Ax)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+/// let r = t.cumprod::<Axis<1>>();
+/// assert_eq!(r.array(), [[1.0, 2.0, 6.0], [-1.0, -0.5, -2.0]]);
+/// ```
+pub fn cumprod<Ax: Axes<Array = [isize; 1]>, S, E: Dtype, D: CumProdKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T>
+where
+    S: Shape + HasAxes<Ax>,
+{
+    t.cumprod::<Ax>()
+}
+
+impl<S: Shape, E: Dtype, D: CumProdKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [cumprod]
+    pub fn cumprod<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        S: HasAxes<Ax>,
+    {
+        self.try_cumprod::<Ax>().unwrap()
+    }
+
+    /// See [cumprod]
+    pub fn try_cumprod<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, D::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(ax, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(ax, &inp.storage, grad_inp, &phantom_out.storage, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_cumprod_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([2.0, -1.0, 3.0, 0.5]);
+        let r = t.trace().cumprod::<Axis<0>>();
+        assert_eq!(r.array(), [2.0, -2.0, -6.0, -3.0]);
+        let g = r.sum().backward();
+        // d/dx0 = 1 + x1 + x1x2 + x1x2x3, etc.
+        assert_close(&g.get(&t).array(), &[-4.5, 11.0, -3.0, -6.0]);
+    }
+
+    #[test]
+    fn test_cumprod_with_zeros() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[2.0, 0.0, 3.0], [0.0, 0.0, 5.0]]);
+        let r = t.trace().cumprod::<Axis<1>>();
+        assert_eq!(r.array(), [[2.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[[1.0, 2.0 + 6.0, 0.0], [1.0, 0.0, 0.0]],
+        );
+    }
+
+    #[test]
+    fn test_cumprod_2d_axis_0() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, -1.0], [0.5, 2.0]]);
+        let r = t.trace().cumprod::<Axis<0>>();
+        assert_eq!(r.array(), [[1.0, 2.0], [3.0, -2.0], [1.5, -4.0]]);
+        let g = r.exp().sum().backward();
+        let e = f32::exp;
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [
+                    e(1.0) + 3.0 * e(3.0) + 1.5 * e(1.5),
+                    e(2.0) - e(-2.0) - 2.0 * e(-4.0),
+                ],
+                [e(3.0) + 0.5 * e(1.5), 2.0 * e(-2.0) + 4.0 * e(-4.0)],
+                [3.0 * e(1.5), -2.0 * e(-4.0)],
+            ],
+        );
+    }
+}
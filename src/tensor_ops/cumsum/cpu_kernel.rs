@@ -0,0 +1,49 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype> super::CumSumKernel<E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: StridedArray<S, E> = StridedArray::new(inp.shape)?;
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let buf = Arc::make_mut(&mut out.data);
+        for_each_row(&dims, ax, [&inp_strides, &out_strides], |[i_inp, i_out]| {
+            let mut sum = E::default();
+            for j in 0..dims[ax] {
+                sum += inp.data[i_inp + j * inp_strides[ax]];
+                buf[i_out + j * out_strides[ax]] = sum;
+            }
+        });
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = grad_out.shape.concrete().into();
+        let inp_strides: Vec<usize> = grad_inp.strides.into();
+        let out_strides: Vec<usize> = grad_out.strides.into();
+        let buf = Arc::make_mut(&mut grad_inp.data);
+        for_each_row(&dims, ax, [&inp_strides, &out_strides], |[i_inp, i_out]| {
+            // each input contributes to every output after it
+            let mut sum = E::default();
+            for j in (0..dims[ax]).rev() {
+                sum += grad_out.data[i_out + j * out_strides[ax]];
+                buf[i_inp + j * inp_strides[ax]] += sum;
+            }
+        });
+        Ok(())
+    }
+}
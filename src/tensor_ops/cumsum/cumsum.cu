@@ -0,0 +1,53 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles a single row along `ax`.
+
+extern "C" __global__ void cumsum_forward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_out = get_row_index(row, num_dims, ax, dims, out_strides);
+
+    float sum = 0.0;
+    for (size_t j = 0; j < dims[ax]; j++) {
+        sum += inp[i_inp + j * inp_strides[ax]];
+        out[i_out + j * out_strides[ax]] = sum;
+    }
+}
+
+extern "C" __global__ void cumsum_backward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_out = get_row_index(row, num_dims, ax, dims, out_strides);
+
+    float sum = 0.0;
+    for (size_t j = dims[ax]; j > 0; j--) {
+        sum += grad_out[i_out + (j - 1) * out_strides[ax]];
+        grad_inp[i_inp + (j - 1) * inp_strides[ax]] += sum;
+    }
+}
@@ -0,0 +1,109 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait CumSumKernel<E: Dtype>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Cumulative sum along `Ax`. Each element of the result is the sum of all
+/// the elements before and including it along `Ax`.
+///
+/// **Pytorch equivalent**: `t.cumsum(Ax)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+/// let r = t.cumsum::<Axis<1>>();
+/// assert_eq!(r.array(), [[1.0, 3.0, 6.0], [-1.0, -0.5, 3.5]]);
+/// ```
+pub fn cumsum<Ax: Axes<Array = [isize; 1]>, S, E: Dtype, D: CumSumKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T>
+where
+    S: Shape + HasAxes<Ax>,
+{
+    t.cumsum::<Ax>()
+}
+
+impl<S: Shape, E: Dtype, D: CumSumKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [cumsum]
+    pub fn cumsum<Ax: Axes<Array = [isize; 1]>>(self) -> Self
+    where
+        S: HasAxes<Ax>,
+    {
+        self.try_cumsum::<Ax>().unwrap()
+    }
+
+    /// See [cumsum]
+    pub fn try_cumsum<Ax: Axes<Array = [isize; 1]>>(self) -> Result<Self, D::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(ax, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(ax, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_cumsum_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 3.0, 0.5]);
+        let r = t.trace().cumsum::<Axis<0>>();
+        assert_eq!(r.array(), [1.0, -1.0, 2.0, 2.5]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0, 4.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [10.0, 9.0, 7.0, 4.0]);
+    }
+
+    #[test]
+    fn test_cumsum_3d_axis_1() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 2>, f32, _> = dev.sample_normal();
+        let r = t.trace().cumsum::<Axis<1>>();
+        let (a, b) = (t.array(), r.array());
+        for i in 0..2 {
+            for k in 0..2 {
+                let mut sum = 0.0;
+                for j in 0..3 {
+                    sum += a[i][j][k];
+                    assert_eq!(b[i][j][k], sum);
+                }
+            }
+        }
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[[3.0; 2], [2.0; 2], [1.0; 2]]; 2]);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::{
+    shapes::{Dim, Dtype},
+    tensor::cpu::Cpu,
+};
+
+impl<E: Dtype> super::DiagKernel<E> for Cpu {
+    fn get_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        mat: &Self::Storage<(M, N), E>,
+        diag: &mut Self::Storage<(L,), E>,
+    ) -> Result<(), Self::Err> {
+        let (r0, c0) = super::diag_start(offset);
+        for i in 0..diag.shape.0.size() {
+            diag[[i]] += mat[[r0 + i, c0 + i]];
+        }
+        Ok(())
+    }
+
+    fn add_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        diag: &Self::Storage<(L,), E>,
+        mat: &mut Self::Storage<(M, N), E>,
+    ) -> Result<(), Self::Err> {
+        let (r0, c0) = super::diag_start(offset);
+        for i in 0..diag.shape.0.size() {
+            mat[[r0 + i, c0 + i]] += diag[[i]];
+        }
+        Ok(())
+    }
+}
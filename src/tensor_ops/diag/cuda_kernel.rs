@@ -0,0 +1,28 @@
+use crate::{shapes::Dim, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the copies are done on the host.
+impl super::DiagKernel<f32> for Cuda {
+    fn get_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        mat: &Self::Storage<(M, N), f32>,
+        diag: &mut Self::Storage<(L,), f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_mat = self.storage_to_cpu(mat)?;
+        let mut cpu_diag = self.storage_to_cpu(diag)?;
+        super::DiagKernel::<f32>::get_diag(&self.cpu, offset, &cpu_mat, &mut cpu_diag)?;
+        self.storage_from_cpu(diag, &cpu_diag)
+    }
+
+    fn add_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        diag: &Self::Storage<(L,), f32>,
+        mat: &mut Self::Storage<(M, N), f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_diag = self.storage_to_cpu(diag)?;
+        let mut cpu_mat = self.storage_to_cpu(mat)?;
+        super::DiagKernel::<f32>::add_diag(&self.cpu, offset, &cpu_diag, &mut cpu_mat)?;
+        self.storage_from_cpu(mat, &cpu_mat)
+    }
+}
@@ -0,0 +1,200 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+pub trait DiagKernel<E: Dtype>: DeviceStorage {
+    /// Adds diagonal `offset` of `mat` to `diag`.
+    fn get_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        mat: &Self::Storage<(M, N), E>,
+        diag: &mut Self::Storage<(L,), E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds `diag` to diagonal `offset` of `mat`.
+    fn add_diag<M: Dim, N: Dim, L: Dim>(
+        &self,
+        offset: isize,
+        diag: &Self::Storage<(L,), E>,
+        mat: &mut Self::Storage<(M, N), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The row & column of the first element of diagonal `offset`.
+fn diag_start(offset: isize) -> (usize, usize) {
+    if offset >= 0 {
+        (0, offset as usize)
+    } else {
+        (offset.unsigned_abs(), 0)
+    }
+}
+
+/// The number of elements on diagonal `offset` of an `m x n` matrix.
+fn diag_len(m: usize, n: usize, offset: isize) -> usize {
+    let (r0, c0) = diag_start(offset);
+    m.saturating_sub(r0).min(n.saturating_sub(c0))
+}
+
+impl<M: Dim, N: Dim, E: Dtype, D: DiagKernel<E> + ZerosTensor<E>, T: Tape<D>>
+    Tensor<(M, N), E, D, T>
+{
+    /// The elements on diagonal `offset` of a matrix. **Pytorch equivalent**: `t.diag(offset)`
+    ///
+    /// `offset = 0` is the main diagonal, positive offsets are above it, and negative offsets
+    /// are below it. The result is empty if the diagonal is outside of the matrix.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    /// assert_eq!(t.clone().diag(0).as_vec(), [1.0, 5.0]);
+    /// assert_eq!(t.clone().diag(1).as_vec(), [2.0, 6.0]);
+    /// assert_eq!(t.diag(-1).as_vec(), [4.0]);
+    /// ```
+    pub fn diag(self, offset: isize) -> Tensor<(usize,), E, D, T> {
+        self.try_diag(offset).unwrap()
+    }
+
+    /// Fallible version of [Tensor::diag]
+    pub fn try_diag(self, offset: isize) -> Result<Tensor<(usize,), E, D, T>, D::Err> {
+        let (m, n) = (self.shape().0.size(), self.shape().1.size());
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&(diag_len(m, n, offset),))?;
+        inp.device
+            .get_diag(offset, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.add_diag(offset, grad_out, grad_inp)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<N: Dim, E: Dtype, D: DiagKernel<E> + ZerosTensor<E>, T: Tape<D>> Tensor<(N,), E, D, T> {
+    /// A square matrix with the elements of `self` on diagonal `offset`, and zeros everywhere
+    /// else. The matrix has `N + |offset|` rows. **Pytorch equivalent**: `torch.diagflat(t, offset)`
+    ///
+    /// This is the inverse of [Tensor::diag], e.g. to build the cholesky factor of a covariance
+    /// matrix from a positive diagonal and a strictly lower triangle:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let a: Tensor<(usize, usize), f32, _> = dev.ones_like(&(3, 3)) * 3.0;
+    /// let log_d: Tensor<(usize,), f32, _> = dev.zeros_like(&(3,));
+    /// let below = a.clone().tril() - a.diag(0).diagflat(0);
+    /// let l = below + log_d.exp().diagflat(0);
+    /// assert_eq!(l.as_vec(), [1.0, 0.0, 0.0, 3.0, 1.0, 0.0, 3.0, 3.0, 1.0]);
+    /// ```
+    pub fn diagflat(self, offset: isize) -> Tensor<(usize, usize), E, D, T> {
+        self.try_diagflat(offset).unwrap()
+    }
+
+    /// Fallible version of [Tensor::diagflat]
+    #[allow(clippy::type_complexity)]
+    pub fn try_diagflat(self, offset: isize) -> Result<Tensor<(usize, usize), E, D, T>, D::Err> {
+        let size = self.shape().0.size() + offset.unsigned_abs();
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&(size, size))?;
+        inp.device
+            .add_diag(offset, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.get_diag(offset, grad_out, grad_inp)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_diag_offsets() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        assert_eq!(t.clone().diag(0).as_vec(), [1.0, 5.0, 9.0]);
+        assert_eq!(t.clone().diag(2).as_vec(), [3.0]);
+        assert_eq!(t.clone().diag(-1).as_vec(), [4.0, 8.0]);
+        assert_eq!(t.clone().diag(3).shape(), &(0,));
+        assert_eq!(t.diag(-5).shape(), &(0,));
+    }
+
+    #[test]
+    fn test_diag_out_of_range() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let r = t.trace().try_diag(5).unwrap();
+        assert_eq!(r.as_vec(), []);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[0.0; 2]; 2]);
+    }
+
+    #[test]
+    fn test_diag_of_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let r = t.permute::<Rank2<3, 2>, _>();
+        assert_eq!(r.clone().diag(0).as_vec(), [1.0, 5.0]);
+        assert_eq!(r.diag(-1).as_vec(), [2.0, 6.0]);
+    }
+
+    #[test]
+    fn test_diag_gradients() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let g = t.trace().diag(1).exp().sum().backward();
+        let t_a = t.array();
+        let mut expected = [[0.0; 4]; 3];
+        for i in 0..3 {
+            expected[i][i + 1] = t_a[i][i + 1].exp();
+        }
+        assert_close(&g.get(&t).array(), &expected);
+    }
+
+    #[test]
+    fn test_diagflat() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0]);
+        let r = t.clone().diagflat(0);
+        assert_eq!(r.shape(), &(2, 2));
+        assert_eq!(r.as_vec(), [1.0, 0.0, 0.0, 2.0]);
+        let r = t.clone().diagflat(1);
+        assert_eq!(r.as_vec(), [0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0]);
+        let r = t.diagflat(-1);
+        assert_eq!(r.as_vec(), [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_diagflat_gradients() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let w: Tensor<(usize, usize), f32, _> =
+            dev.sample_like(&(4, 4), rand_distr::StandardNormal);
+        let r = t.trace().diagflat(-1) * w.clone();
+        let g = r.sum().backward();
+        let w_v = w.as_vec();
+        assert_eq!(g.get(&t).array(), [w_v[4], w_v[9], w_v[14]]);
+    }
+
+    #[test]
+    fn test_diag_of_diagflat() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<5>, f32, _> = dev.sample_normal();
+        assert_eq!(t.clone().diagflat(2).diag(2).as_vec(), t.as_vec());
+        assert_eq!(t.clone().diagflat(2).diag(0).as_vec(), [0.0; 7]);
+    }
+}
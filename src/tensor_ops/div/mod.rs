@@ -36,6 +36,7 @@ pub struct BinaryDivKernelOp;
 /// let r = a / 2.0;
 /// assert_eq!(r.array(), [[0.5, 1.0, 1.5], [-0.5, -1.0, -1.5]]);
 /// ```
+#[track_caller]
 pub fn div<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
@@ -54,8 +55,9 @@ where
     LhsTape: Merge<RhsTape>,
 {
     /// See [div]
+    #[track_caller]
     fn try_div(self, rhs: Tensor<S, E, D, RhsTape>) -> Result<Self, Self::Err> {
-        try_binary_op(BinaryDivKernelOp, self, rhs)
+        try_binary_op("div", BinaryDivKernelOp, self, rhs)
     }
 }
 
@@ -73,6 +75,7 @@ where
 {
     type Output = Self;
     /// See [div]
+    #[track_caller]
     fn div(self, rhs: Rhs) -> Self::Output {
         self.try_div(rhs).unwrap()
     }
@@ -0,0 +1,57 @@
+use super::{EinsumKernel, EinsumSpec};
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+};
+use std::sync::Arc;
+
+impl<E: Dtype> EinsumKernel<E> for Cpu {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        out_shape: O,
+    ) -> Result<Self::Storage<O, E>, Self::Err> {
+        let mut out: StridedArray<O, E> = StridedArray::new(out_shape)?;
+        let lhs_strides = spec.lhs_strides(lhs.strides);
+        let rhs_strides = spec.rhs_strides(rhs.strides);
+        let out_strides = spec.out_strides(out.strides);
+        let out_buf = Arc::make_mut(&mut out.data);
+        spec.for_each_offset([&lhs_strides, &rhs_strides, &out_strides], |[l, r, o]| {
+            out_buf[o] += lhs.data[l] * rhs.data[r];
+        });
+        Ok(out)
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, E>,
+        grad_lhs: &mut Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        grad_rhs: &mut Self::Storage<R, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err> {
+        let lhs_strides = spec.lhs_strides(lhs.strides);
+        let grad_lhs_strides = spec.lhs_strides(grad_lhs.strides);
+        let rhs_strides = spec.rhs_strides(rhs.strides);
+        let grad_rhs_strides = spec.rhs_strides(grad_rhs.strides);
+        let grad_out_strides = spec.out_strides(grad_out.strides);
+        let grad_lhs_buf = Arc::make_mut(&mut grad_lhs.data);
+        let grad_rhs_buf = Arc::make_mut(&mut grad_rhs.data);
+        let strides = [
+            &lhs_strides,
+            &grad_lhs_strides,
+            &rhs_strides,
+            &grad_rhs_strides,
+            &grad_out_strides,
+        ];
+        spec.for_each_offset(strides.map(|s| s.as_slice()), |[l, gl, r, gr, go]| {
+            let g = grad_out.data[go];
+            grad_lhs_buf[gl] += g * rhs.data[r];
+            grad_rhs_buf[gr] += g * lhs.data[l];
+        });
+        Ok(())
+    }
+}
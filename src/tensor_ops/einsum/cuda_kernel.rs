@@ -0,0 +1,115 @@
+use super::{EinsumKernel, EinsumSpec};
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/einsum.ptx"));
+const MODULE_NAME: &str = "einsum";
+const FWD_FN_NAME: &str = "einsum_forward";
+const BWD_FN_NAME: &str = "einsum_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl EinsumKernel<f32> for Cuda {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        out_shape: O,
+    ) -> Result<Self::Storage<O, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let out_strides = out_shape.strides();
+        let out_numel = spec.out_numel();
+        let mut storage = self.dev.alloc_zeros_async::<f32>(out_numel)?;
+
+        let sizes: CudaSlice<usize> = self.dev.take_async(spec.sizes.clone())?;
+        let lhs_strides: CudaSlice<usize> = self.dev.take_async(spec.lhs_strides(lhs.strides))?;
+        let rhs_strides: CudaSlice<usize> = self.dev.take_async(spec.rhs_strides(rhs.strides))?;
+        let label_out_strides: CudaSlice<usize> =
+            self.dev.take_async(spec.out_strides(out_strides))?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(out_numel as u32);
+        let params = (
+            out_numel,          // const size_t out_numel,
+            spec.sum_numel(),   // const size_t sum_numel,
+            spec.sizes.len(),   // const size_t num_labels,
+            spec.num_out,       // const size_t num_out,
+            &sizes,             // const size_t *sizes,
+            lhs.data.as_ref(),  // const float *lhs,
+            &lhs_strides,       // const size_t *lhs_strides,
+            rhs.data.as_ref(),  // const float *rhs,
+            &rhs_strides,       // const size_t *rhs_strides,
+            &mut storage,       // float *out,
+            &label_out_strides, // const size_t *out_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: out_shape,
+            strides: out_strides,
+        })
+    }
+
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, f32>,
+        grad_lhs: &mut Self::Storage<L, f32>,
+        rhs: &Self::Storage<R, f32>,
+        grad_rhs: &mut Self::Storage<R, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let cpu_lhs = self.storage_to_cpu(lhs)?;
+            let mut cpu_grad_lhs = self.storage_to_cpu(grad_lhs)?;
+            let cpu_rhs = self.storage_to_cpu(rhs)?;
+            let mut cpu_grad_rhs = self.storage_to_cpu(grad_rhs)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            EinsumKernel::<f32>::backward(
+                &self.cpu,
+                spec,
+                &cpu_lhs,
+                &mut cpu_grad_lhs,
+                &cpu_rhs,
+                &mut cpu_grad_rhs,
+                &cpu_grad_out,
+            )?;
+            self.storage_from_cpu(grad_lhs, &cpu_grad_lhs)?;
+            return self.storage_from_cpu(grad_rhs, &cpu_grad_rhs);
+        }
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = spec.out_numel() * spec.sum_numel();
+
+        let sizes: CudaSlice<usize> = self.dev.take_async(spec.sizes.clone())?;
+        let mut strides = spec.lhs_strides(lhs.strides);
+        strides.extend(spec.lhs_strides(grad_lhs.strides));
+        strides.extend(spec.rhs_strides(rhs.strides));
+        strides.extend(spec.rhs_strides(grad_rhs.strides));
+        strides.extend(spec.out_strides(grad_out.strides));
+        let strides: CudaSlice<usize> = self.dev.take_async(strides)?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            spec.sizes.len(),                  // const size_t num_labels,
+            &sizes,                            // const size_t *sizes,
+            lhs.data.as_ref(),                 // const float *lhs,
+            Arc::make_mut(&mut grad_lhs.data), // float *grad_lhs,
+            rhs.data.as_ref(),                 // const float *rhs,
+            Arc::make_mut(&mut grad_rhs.data), // float *grad_rhs,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &strides,                          // const size_t *strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+// Labels are ordered with the `num_out` output labels first, followed by the summed over labels.
+// Each `*_strides` has a stride for every label, which is 0 if the label isn't in that tensor.
+
+__device__ void add_label_offsets(
+    size_t idx,
+    const size_t first_label,
+    const size_t last_label,
+    const size_t *sizes,
+    const size_t *lhs_strides,
+    const size_t *rhs_strides,
+    const size_t *out_strides,
+    size_t *lhs_i,
+    size_t *rhs_i,
+    size_t *out_i
+) {
+    for (size_t l = last_label; l > first_label; l--) {
+        size_t label_idx = idx % sizes[l - 1];
+        idx /= sizes[l - 1];
+        *lhs_i += label_idx * lhs_strides[l - 1];
+        *rhs_i += label_idx * rhs_strides[l - 1];
+        *out_i += label_idx * out_strides[l - 1];
+    }
+}
+
+extern "C" __global__ void einsum_forward(
+    const size_t out_numel,
+    const size_t sum_numel,
+    const size_t num_labels,
+    const size_t num_out,
+    const size_t *sizes,
+    const float *lhs,
+    const size_t *lhs_strides,
+    const float *rhs,
+    const size_t *rhs_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= out_numel) {
+        return;
+    }
+
+    size_t lhs_i = 0;
+    size_t rhs_i = 0;
+    size_t out_i = 0;
+    add_label_offsets(i, 0, num_out, sizes, lhs_strides, rhs_strides, out_strides, &lhs_i, &rhs_i, &out_i);
+
+    float sum = 0.0;
+    for (size_t j = 0; j < sum_numel; j++) {
+        size_t lhs_j = lhs_i;
+        size_t rhs_j = rhs_i;
+        size_t unused = 0;
+        add_label_offsets(j, num_out, num_labels, sizes, lhs_strides, rhs_strides, out_strides, &lhs_j, &rhs_j, &unused);
+        sum += lhs[lhs_j] * rhs[rhs_j];
+    }
+    out[out_i] = sum;
+}
+
+// `strides` holds the label strides of lhs, grad_lhs, rhs, grad_rhs, and grad_out, one after the other.
+extern "C" __global__ void einsum_backward(
+    const size_t numel,
+    const size_t num_labels,
+    const size_t *sizes,
+    const float *lhs,
+    float *grad_lhs,
+    const float *rhs,
+    float *grad_rhs,
+    const float *grad_out,
+    const size_t *strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    const size_t *lhs_strides = strides;
+    const size_t *grad_lhs_strides = strides + num_labels;
+    const size_t *rhs_strides = strides + 2 * num_labels;
+    const size_t *grad_rhs_strides = strides + 3 * num_labels;
+    const size_t *out_strides = strides + 4 * num_labels;
+
+    size_t lhs_i = 0;
+    size_t rhs_i = 0;
+    size_t out_i = 0;
+    add_label_offsets(i, 0, num_labels, sizes, lhs_strides, rhs_strides, out_strides, &lhs_i, &rhs_i, &out_i);
+    size_t grad_lhs_i = 0;
+    size_t grad_rhs_i = 0;
+    size_t unused = 0;
+    add_label_offsets(i, 0, num_labels, sizes, grad_lhs_strides, grad_rhs_strides, out_strides, &grad_lhs_i, &grad_rhs_i, &unused);
+
+    auto go = grad_out[out_i];
+    atomicAdd(grad_lhs + grad_lhs_i, go * rhs[rhs_i]);
+    atomicAdd(grad_rhs + grad_rhs_i, go * lhs[lhs_i]);
+}
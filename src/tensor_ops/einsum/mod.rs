@@ -0,0 +1,415 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::{Dtype, HasShape, Shape, ShapeMismatch},
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+};
+use std::vec::Vec;
+
+/// A parsed einsum equation, where every label has been resolved to the size of its dimension.
+///
+/// The labels of the output come first (in the order of the output axes), followed by
+/// the summed over labels. So the output of `"bij,bjk->bik"` has labels `[b, i, k, j]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EinsumSpec {
+    /// The size of each label
+    sizes: Vec<usize>,
+    /// The label of each axis of the left hand side
+    lhs: Vec<usize>,
+    /// The label of each axis of the right hand side
+    rhs: Vec<usize>,
+    /// The number of labels in the output
+    num_out: usize,
+}
+
+impl EinsumSpec {
+    /// **Panics** if `equation` is malformed or doesn't match the number of dimensions of
+    /// `L`, `R` and `O`, or if the resulting output shape doesn't fit into `O`.
+    #[track_caller]
+    fn parse<L: Shape, R: Shape, O: Shape>(
+        equation: &str,
+        lhs: &L,
+        rhs: &R,
+    ) -> Result<(Self, O), ShapeMismatch> {
+        let equation: std::string::String =
+            equation.chars().filter(|c| !c.is_whitespace()).collect();
+        let (inputs, out) = equation
+            .split_once("->")
+            .unwrap_or_else(|| panic!("einsum: expected `->` in equation {equation:?}"));
+        let (lhs_labels, rhs_labels) = inputs
+            .split_once(',')
+            .unwrap_or_else(|| panic!("einsum: expected two inputs in equation {equation:?}"));
+        for (labels, num_dims) in [
+            (lhs_labels, L::NUM_DIMS),
+            (rhs_labels, R::NUM_DIMS),
+            (out, O::NUM_DIMS),
+        ] {
+            assert!(
+                labels.chars().all(|c| c.is_ascii_alphabetic()),
+                "einsum: labels must be ascii letters, found {labels:?} in equation {equation:?}"
+            );
+            assert_eq!(
+                labels.len(),
+                num_dims,
+                "einsum: {labels:?} has {} labels, but its tensor has {num_dims} dimensions",
+                labels.len(),
+            );
+        }
+
+        // output labels first, then the summed over labels in order of appearance
+        let mut labels: Vec<char> = Vec::new();
+        for c in out.chars() {
+            assert!(
+                !labels.contains(&c),
+                "einsum: label {c:?} appears more than once in output {out:?}"
+            );
+            assert!(
+                inputs.contains(c),
+                "einsum: output label {c:?} doesn't appear in the inputs {inputs:?}"
+            );
+            labels.push(c);
+        }
+        for c in inputs.chars().filter(|&c| c != ',') {
+            if !labels.contains(&c) {
+                labels.push(c);
+            }
+        }
+        let label_of = |c: char| labels.iter().position(|&l| l == c).unwrap();
+        let lhs_labels: Vec<usize> = lhs_labels.chars().map(label_of).collect();
+        let rhs_labels: Vec<usize> = rhs_labels.chars().map(label_of).collect();
+
+        // every label's first occurrence, and whether it was in lhs
+        let mut first: Vec<Option<(bool, usize)>> = std::vec![None; labels.len()];
+        let mut sizes = std::vec![0; labels.len()];
+        let (lhs_dims, rhs_dims) = (lhs.concrete(), rhs.concrete());
+        for (i, &l) in lhs_labels.iter().enumerate() {
+            match first[l] {
+                Some((_, j)) => ShapeMismatch::check_axes("einsum", (lhs, j), (lhs, i))?,
+                None => {
+                    first[l] = Some((true, i));
+                    sizes[l] = lhs_dims[i];
+                }
+            }
+        }
+        for (i, &l) in rhs_labels.iter().enumerate() {
+            match first[l] {
+                Some((true, j)) => ShapeMismatch::check_axes("einsum", (lhs, j), (rhs, i))?,
+                Some((false, j)) => ShapeMismatch::check_axes("einsum", (rhs, j), (rhs, i))?,
+                None => {
+                    first[l] = Some((false, i));
+                    sizes[l] = rhs_dims[i];
+                }
+            }
+        }
+
+        let mut out_dims: O::Concrete = Default::default();
+        for (i, &size) in sizes.iter().take(O::NUM_DIMS).enumerate() {
+            out_dims[i] = size;
+        }
+        let out_shape = O::from_concrete(&out_dims).unwrap_or_else(|| {
+            panic!(
+                "einsum: output of {equation:?} has dimensions {out_dims:?}, which doesn't fit into {}",
+                std::any::type_name::<O>()
+            )
+        });
+
+        let spec = Self {
+            sizes,
+            lhs: lhs_labels,
+            rhs: rhs_labels,
+            num_out: O::NUM_DIMS,
+        };
+        Ok((spec, out_shape))
+    }
+
+    /// The stride of each label in a tensor whose axes have `labels` and `strides`.
+    /// Labels that appear multiple times have their strides summed (i.e. a diagonal),
+    /// and labels that don't appear have a stride of 0.
+    fn label_strides<S: IntoIterator<Item = usize>>(
+        &self,
+        labels: &[usize],
+        strides: S,
+    ) -> Vec<usize> {
+        let mut label_strides = std::vec![0; self.sizes.len()];
+        for (&l, s) in labels.iter().zip(strides) {
+            label_strides[l] += s;
+        }
+        label_strides
+    }
+
+    fn lhs_strides<S: IntoIterator<Item = usize>>(&self, strides: S) -> Vec<usize> {
+        self.label_strides(&self.lhs, strides)
+    }
+
+    fn rhs_strides<S: IntoIterator<Item = usize>>(&self, strides: S) -> Vec<usize> {
+        self.label_strides(&self.rhs, strides)
+    }
+
+    fn out_strides<S: IntoIterator<Item = usize>>(&self, strides: S) -> Vec<usize> {
+        let labels: Vec<usize> = (0..self.num_out).collect();
+        self.label_strides(&labels, strides)
+    }
+
+    /// The number of elements in the output.
+    #[cfg(feature = "cuda")]
+    fn out_numel(&self) -> usize {
+        self.sizes[..self.num_out].iter().product()
+    }
+
+    /// The number of terms summed into each element of the output.
+    #[cfg(feature = "cuda")]
+    fn sum_numel(&self) -> usize {
+        self.sizes[self.num_out..].iter().product()
+    }
+
+    /// Calls `f` with the offsets of every combination of label indices, where
+    /// the offsets are computed with each of `strides` (see [Self::label_strides()]).
+    ///
+    /// The last label changes fastest, so all the summed over terms for an element of the
+    /// output are visited in a row.
+    fn for_each_offset<const N: usize>(
+        &self,
+        strides: [&[usize]; N],
+        mut f: impl FnMut([usize; N]),
+    ) {
+        if self.sizes.contains(&0) {
+            return;
+        }
+        let mut idx = std::vec![0; self.sizes.len()];
+        let mut offsets = [0; N];
+        loop {
+            f(offsets);
+            let mut l = self.sizes.len();
+            loop {
+                if l == 0 {
+                    return;
+                }
+                l -= 1;
+                idx[l] += 1;
+                for (o, s) in offsets.iter_mut().zip(strides.iter()) {
+                    *o += s[l];
+                }
+                if idx[l] < self.sizes[l] {
+                    break;
+                }
+                for (o, s) in offsets.iter_mut().zip(strides.iter()) {
+                    *o -= s[l] * idx[l];
+                }
+                idx[l] = 0;
+            }
+        }
+    }
+}
+
+pub trait EinsumKernel<E: Dtype>: DeviceStorage {
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        out_shape: O,
+    ) -> Result<Self::Storage<O, E>, Self::Err>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        spec: &EinsumSpec,
+        lhs: &Self::Storage<L, E>,
+        grad_lhs: &mut Self::Storage<L, E>,
+        rhs: &Self::Storage<R, E>,
+        grad_rhs: &mut Self::Storage<R, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Einstein summation of two tensors, described by an equation like `"bij,bjk->bik"`.
+///
+/// The equation has a letter for every axis of `lhs`, `rhs`, and the output. Axes with the same
+/// letter are multiplied together, and letters that don't appear in the output are summed over.
+/// Letters can appear multiple times in the same input, which takes the diagonal.
+/// The output shape `Dst` is usually inferred, and can have either [crate::shapes::Const]
+/// or runtime (`usize`) dimensions.
+///
+/// Unlike composing [super::PermuteTo], [super::BroadcastTo], and [super::TryMatMul],
+/// this does not allocate any intermediate tensors.
+///
+/// **Panics** if the equation is malformed, e.g. it has the wrong number of letters for
+/// one of the tensors, or if the output size doesn't match `Dst`. Runtime dimensions
+/// with the same letter that have different sizes return [ShapeMismatch] from [TryEinsum::try_einsum()].
+///
+/// Batched matrix multiplication:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+/// let b: Tensor<Rank3<2, 4, 5>, f32, _> = dev.sample_normal();
+/// let c: Tensor<Rank3<2, 3, 5>, f32, _> = einsum("bij,bjk->bik", a, b);
+/// ```
+///
+/// Transposed matmul, and a dot product along the last axis:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+/// let b = dev.tensor([[1.0, 0.0], [0.0, 1.0]]);
+/// let c: Tensor<Rank2<2, 2>, f32, _> = a.clone().einsum("ji,jk->ik", b.clone());
+/// assert_eq!(c.array(), [[1.0, 3.0], [2.0, 4.0]]);
+/// let d: Tensor<Rank1<2>, f32, _> = a.einsum("ij,ij->i", b);
+/// assert_eq!(d.array(), [1.0, 4.0]);
+/// ```
+#[track_caller]
+pub fn einsum<Dst: Shape, Lhs: TryEinsum<Rhs>, Rhs>(
+    equation: &str,
+    lhs: Lhs,
+    rhs: Rhs,
+) -> Lhs::WithShape<Dst> {
+    lhs.einsum(equation, rhs)
+}
+
+/// Fallible einsum. See [einsum] for examples.
+pub trait TryEinsum<Rhs>: HasErr + HasShape {
+    #[track_caller]
+    fn einsum<Dst: Shape>(self, equation: &str, rhs: Rhs) -> Self::WithShape<Dst> {
+        self.try_einsum(equation, rhs).unwrap()
+    }
+    fn try_einsum<Dst: Shape>(
+        self,
+        equation: &str,
+        rhs: Rhs,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>;
+}
+
+impl<
+        L: Shape,
+        R: Shape,
+        E: Dtype,
+        D: EinsumKernel<E>,
+        LhsTape: Tape<D> + Merge<RhsTape>,
+        RhsTape: Tape<D>,
+    > TryEinsum<Tensor<R, E, D, RhsTape>> for Tensor<L, E, D, LhsTape>
+{
+    #[track_caller]
+    fn try_einsum<Dst: Shape>(
+        self,
+        equation: &str,
+        rhs: Tensor<R, E, D, RhsTape>,
+    ) -> Result<Self::WithShape<Dst>, Self::Err> {
+        DeviceMismatch::check_same("einsum", &self.device, &rhs.device)?;
+        let (spec, shape) = EinsumSpec::parse::<L, R, Dst>(equation, self.shape(), rhs.shape())?;
+        let (lhs, ltape) = self.split_tape();
+        let (rhs, rtape) = rhs.split_tape();
+        let mut tape = ltape.merge(rtape);
+        let storage = lhs
+            .device
+            .forward(&spec, &lhs.storage, &rhs.storage, shape)?;
+        let out = lhs.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&lhs)?;
+        tape.try_alloc_grad(&rhs)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs, &rhs, &phantom_out);
+            lhs.device.backward(
+                &spec,
+                &lhs.storage,
+                grad_lhs,
+                &rhs.storage,
+                grad_rhs,
+                grad_out,
+            )
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_einsum_batched_matmul() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank3<2, 4, 5>, f32, _> = dev.sample_normal();
+        let w: Tensor<Rank3<2, 3, 5>, f32, _> = dev.sample_normal();
+
+        let r = einsum("bij,bjk->bik", a.trace(), b.trace());
+        let r_expected = a.trace().matmul(b.trace());
+        assert_close(&r.array(), &r_expected.array());
+
+        let g = (r * w.clone()).sum().backward();
+        let g_expected = (r_expected * w).sum().backward();
+        assert_close(&g.get(&a).array(), &g_expected.get(&a).array());
+        assert_close(&g.get(&b).array(), &g_expected.get(&b).array());
+    }
+
+    #[test]
+    fn test_einsum_permuted_output() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+        let r: Tensor<Rank2<2, 3>, f32, _, _> = a.trace().einsum("ij,jk->ki", b.trace());
+        let r_expected = a.trace().matmul(b.trace()).permute::<Rank2<2, 3>, _>();
+        assert_close(&r.array(), &r_expected.array());
+
+        let g = r.exp().mean().backward();
+        let g_expected = r_expected.exp().mean().backward();
+        assert_close(&g.get(&a).array(), &g_expected.get(&a).array());
+        assert_close(&g.get(&b).array(), &g_expected.get(&b).array());
+    }
+
+    #[test]
+    fn test_einsum_outer_and_dot() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0, 3.0]);
+        let b = dev.tensor([4.0, 5.0]);
+        let r: Tensor<Rank2<3, 2>, f32, _> = einsum("i,j->ij", a.clone(), b.clone());
+        assert_eq!(r.array(), [[4.0, 5.0], [8.0, 10.0], [12.0, 15.0]]);
+
+        let c = dev.tensor([4.0, 5.0, 6.0]);
+        let r: Tensor<Rank0, f32, _, _> = einsum("i,i->", a.trace(), c.clone());
+        assert_eq!(r.array(), 32.0);
+        let g = r.backward();
+        assert_eq!(g.get(&a).array(), [4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_einsum_diagonal_and_broadcast() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = dev.tensor([10.0, 20.0, 30.0]);
+        let r: Tensor<Rank2<2, 3>, f32, _, _> = einsum("ii,k->ik", a.trace(), b.clone());
+        assert_eq!(r.array(), [[10.0, 20.0, 30.0], [40.0, 80.0, 120.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[60.0, 0.0], [0.0, 60.0]]);
+    }
+
+    #[test]
+    fn test_einsum_runtime_dims() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, Const<3>), f32, _> =
+            dev.sample_like(&(2, Const), rand_distr::StandardNormal);
+        let b: Tensor<(Const<3>, usize), f32, _> =
+            dev.sample_like(&(Const, 4), rand_distr::StandardNormal);
+        let r: Tensor<(usize, usize), f32, _> = a.clone().einsum("ij,jk->ik", b.clone());
+        assert_eq!(r.shape(), &(2, 4));
+
+        let c: Tensor<(usize, Const<3>), f32, _> = dev.zeros_like(&(5, Const));
+        let r = a.try_einsum::<(Const<3>,)>("ij,ik->k", c);
+        assert!(r.is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_einsum_wrong_number_of_labels() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let b: Tensor<Rank2<3, 4>, f32, _> = dev.zeros();
+        let _: Tensor<Rank2<2, 4>, f32, _> = a.einsum("ijk,jk->ik", b);
+    }
+}
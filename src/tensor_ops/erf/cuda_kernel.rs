@@ -0,0 +1,18 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::ErfKernelOp {}
+unsafe impl cudarc::driver::AsKernelParam for super::NormalCdfKernelOp {}
+
+impl UnaryOpCudaKernel for super::ErfKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/erf.ptx"));
+    const MODULE_NAME: &'static str = "erf";
+    const FWD_FN_NAME: &'static str = "erf_forward";
+    const BWD_FN_NAME: &'static str = "erf_backward";
+}
+
+impl UnaryOpCudaKernel for super::NormalCdfKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/erf.ptx"));
+    const MODULE_NAME: &'static str = "normal_cdf";
+    const FWD_FN_NAME: &'static str = "normal_cdf_forward";
+    const BWD_FN_NAME: &'static str = "normal_cdf_backward";
+}
@@ -0,0 +1,14 @@
+#include "unary_op_macros.cuh"
+#define _USE_MATH_DEFINES
+#include <math.h>
+
+struct ErfKernelOp {};
+struct NormalCdfKernelOp {};
+
+UNARY_OP(erf_forward, erf_backward, ErfKernelOp,
+        erff(x),
+        M_2_SQRTPI * expf(-x * x))
+
+UNARY_OP(normal_cdf_forward, normal_cdf_backward, NormalCdfKernelOp,
+        normcdff(x),
+        M_2_SQRTPI * M_SQRT1_2 * 0.5 * expf(-0.5 * x * x))
@@ -0,0 +1,155 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ErfKernelOp;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NormalCdfKernelOp;
+
+/// The [error function](https://en.wikipedia.org/wiki/Error_function) `2 / sqrt(pi) * integral(exp(-t^2), 0, x)`.
+///
+/// The derivative is `2 / sqrt(pi) * exp(-x^2)`.
+///
+/// **Pytorch equivalent**: `t.erf()`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 0.5, 3.0]);
+/// let r = t.erf();
+/// assert_eq!(r.array(), [-0.8427008, 0.0, 0.5204999, 0.9999779]);
+/// ```
+pub fn erf<S: Shape, E: Dtype, D: UnaryKernel<ErfKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.erf()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<ErfKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [erf]
+    pub fn erf(self) -> Self {
+        self.try_erf().unwrap()
+    }
+    /// See [erf]
+    pub fn try_erf(self) -> Result<Self, D::Err> {
+        try_unary_op(ErfKernelOp, self)
+    }
+}
+
+/// The [cumulative distribution function](https://en.wikipedia.org/wiki/Normal_distribution#Cumulative_distribution_function)
+/// of the standard normal distribution, `0.5 * (1 + erf(x / sqrt(2)))`. It is computed
+/// without cancellation, so it stays accurate far into the lower tail.
+///
+/// The derivative is the standard normal density. For example, `x * normal_cdf(x)` is the
+/// exact GeLU, which [gelu()] approximates, and `normal_cdf` is the link function of probit models.
+///
+/// **Pytorch equivalent**: `torch.special.ndtr(t)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0]);
+/// let r = t.normal_cdf();
+/// assert_eq!(r.array(), [0.15865526, 0.5, 0.8413447]);
+/// ```
+pub fn normal_cdf<S: Shape, E: Dtype, D: UnaryKernel<NormalCdfKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.normal_cdf()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<NormalCdfKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [normal_cdf]
+    pub fn normal_cdf(self) -> Self {
+        self.try_normal_cdf().unwrap()
+    }
+    /// See [normal_cdf]
+    pub fn try_normal_cdf(self) -> Result<Self, D::Err> {
+        try_unary_op(NormalCdfKernelOp, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tensor::*,
+        tensor_ops::*,
+        tests::{assert_close, TestDevice},
+    };
+    use std::f32::consts::FRAC_2_SQRT_PI;
+
+    #[test]
+    fn test_erf() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.0, -0.5, 0.0, 1e-4, 0.25, 1.0, 4.0]);
+        let r = x.trace().erf();
+        assert_close(
+            &r.array(),
+            &[
+                -0.9953223,
+                -0.5204999,
+                0.0,
+                1.1283792e-4,
+                0.2763264,
+                0.8427008,
+                1.0,
+            ],
+        );
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[
+                0.020666985,
+                0.8787826,
+                FRAC_2_SQRT_PI,
+                FRAC_2_SQRT_PI,
+                1.0600141,
+                0.4151075,
+                1.2698234e-7,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_normal_cdf() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-10.0, -2.0, 0.0, 0.5, 3.0]);
+        let r = x.trace().normal_cdf();
+        assert_close(
+            &r.array(),
+            &[7.619853e-24, 0.022750132, 0.5, 0.69146246, 0.9986501],
+        );
+        // relative precision in the lower tail
+        let tail = r.array()[0];
+        assert!((tail - 7.619853e-24).abs() < 1e-29);
+
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &[7.694599e-23, 0.05399097, 0.3989423, 0.35206533, 0.004431848],
+        );
+    }
+
+    #[test]
+    fn test_exact_gelu() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+        let exact = x.clone() * x.clone().normal_cdf();
+        assert_close(&exact.array(), &[-0.15865526, 0.0, 0.8413447, 1.9544997]);
+        // the tanh approximation is close
+        let approx = x.gelu().array();
+        for (a, e) in approx.iter().zip(exact.array().iter()) {
+            assert!((a - e).abs() < 1e-3);
+        }
+    }
+}
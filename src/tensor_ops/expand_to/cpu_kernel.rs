@@ -0,0 +1,30 @@
+use crate::{
+    shapes::*,
+    tensor::cpu::{Cpu, StridedArray},
+};
+
+impl<E: Dtype> super::ExpandKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err> {
+        Ok(StridedArray {
+            data: inp.data.clone(),
+            shape: dst,
+            strides: super::expanded_strides(&inp.shape, inp.strides, &dst),
+        })
+    }
+
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        debug_assert_eq!(grad_out.data.len(), grad_inp.data.len());
+        for (i, o) in grad_inp.buf_iter_mut().zip(grad_out.buf_iter()) {
+            *i += *o;
+        }
+        Ok(())
+    }
+}
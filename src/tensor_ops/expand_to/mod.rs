@@ -0,0 +1,188 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait ExpandKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>;
+    fn backward<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+        &self,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The strides of `src` expanded to `dst`, which are `0` along the expanded axes.
+fn expanded_strides<Src: Shape, Dst: Shape<Concrete = Src::Concrete>>(
+    src: &Src,
+    mut strides: Src::Concrete,
+    dst: &Dst,
+) -> Src::Concrete {
+    let (s, d) = (src.concrete(), dst.concrete());
+    for i in 0..Src::NUM_DIMS {
+        if s[i] != d[i] {
+            strides[i] = 0;
+        }
+    }
+    strides
+}
+
+/// Expand axes of size 1 into a larger shape with the same number of dimensions.
+/// **Pytorch equivalent**: `t.expand(*shape)` or `t.expand_as(other)`
+///
+/// Unlike [super::BroadcastTo], which inserts new axes, this repeats existing axes of size 1,
+/// like the results of reductions with `keepdim`. Neither op copies any data: the strides of
+/// the expanded axes are set to `0`, so every element along them refers to the same element
+/// of the input. The gradients of the repeated elements are summed.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let bias: Tensor<Rank2<2, 1>, f32, _> = dev.tensor([[1.0], [2.0]]);
+/// let r = bias.expand::<Rank2<2, 3>>();
+/// assert_eq!(r.array(), [[1.0; 3], [2.0; 3]]);
+/// assert_eq!(r.strides(), [1, 0]);
+/// ```
+///
+/// Expanding to the runtime shape of another tensor:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mask: Tensor<(usize, Const<1>), f32, _> = dev.ones_like(&(4, Const));
+/// let x: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(4, 6));
+/// let r = mask.expand_like(&(4, 6)) * x;
+/// assert_eq!(r.shape(), &(4, 6));
+/// ```
+pub trait ExpandTo: HasErr + HasShape {
+    /// Expand into shape `Dst`. See [ExpandTo].
+    #[track_caller]
+    fn expand<Dst: Shape<Concrete = <Self::Shape as Shape>::Concrete> + Default>(
+        self,
+    ) -> Self::WithShape<Dst> {
+        self.try_expand_like(&Default::default()).unwrap()
+    }
+    /// Fallible version of [ExpandTo::expand]
+    #[track_caller]
+    fn try_expand<Dst: Shape<Concrete = <Self::Shape as Shape>::Concrete> + Default>(
+        self,
+    ) -> Result<Self::WithShape<Dst>, Self::Err> {
+        self.try_expand_like(&Default::default())
+    }
+    /// Same as [ExpandTo::expand], but the target shape is given
+    #[track_caller]
+    fn expand_like<Dst: Shape<Concrete = <Self::Shape as Shape>::Concrete>>(
+        self,
+        dst: &Dst,
+    ) -> Self::WithShape<Dst> {
+        self.try_expand_like(dst).unwrap()
+    }
+    /// Fallible version of [ExpandTo::expand_like]. Returns a [ShapeMismatch] error if an
+    /// axis of `self` isn't `1` and doesn't match the size of the axis in `dst`.
+    fn try_expand_like<Dst: Shape<Concrete = <Self::Shape as Shape>::Concrete>>(
+        self,
+        dst: &Dst,
+    ) -> Result<Self::WithShape<Dst>, Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: ExpandKernel<E>, T: Tape<D>> ExpandTo for Tensor<S, E, D, T> {
+    #[track_caller]
+    fn try_expand_like<Dst: Shape<Concrete = S::Concrete>>(
+        self,
+        dst: &Dst,
+    ) -> Result<Self::WithShape<Dst>, Self::Err> {
+        let src = self.shape().concrete();
+        for i in 0..S::NUM_DIMS {
+            if src[i] != 1 {
+                ShapeMismatch::check_axes("expand", (&self, i), (dst, i))?;
+            }
+        }
+
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(*dst, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_expand_values_and_strides() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0]]);
+        let r = t.clone().expand::<Rank2<2, 3>>();
+        assert_eq!(r.array(), [[1.0, 2.0, 3.0]; 2]);
+        assert_eq!(r.strides(), [0, 1]);
+        assert!(!r.is_contiguous());
+
+        let t: Tensor<Rank3<2, 1, 1>, f32, _> = dev.tensor([[[1.0]], [[2.0]]]);
+        let r = t.expand::<Rank3<2, 2, 3>>();
+        assert_eq!(r.array(), [[[1.0; 3]; 2], [[2.0; 3]; 2]]);
+        assert_eq!(r.strides(), [1, 0, 0]);
+    }
+
+    #[test]
+    fn test_expand_same_shape_is_identity() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let r = t.clone().expand::<Rank2<3, 4>>();
+        assert_eq!(r.array(), t.array());
+        assert_eq!(r.strides(), t.strides());
+    }
+
+    #[test]
+    fn test_expand_gradients() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 1>, f32, _> = dev.tensor([[1.0], [2.0], [3.0]]);
+        let x: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let r = t.trace().expand::<Rank2<3, 4>>() * x.clone();
+        let g = r.sum().backward();
+        let x_a = x.array();
+        let expected = x_a.map(|row| [row.iter().sum::<f32>()]);
+        assert_close(&g.get(&t).array(), &expected);
+    }
+
+    #[test]
+    fn test_expand_matches_broadcast() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, f32, _> = dev.sample_normal();
+        let t2: Tensor<Rank2<1, 3>, f32, _> = dev.tensor([t.array()]);
+        let a = t.trace().broadcast::<Rank2<5, 3>, _>();
+        let b = t2.trace().expand::<Rank2<5, 3>>();
+        assert_eq!(a.array(), b.array());
+        let g_a = a.exp().sum().backward();
+        let g_b = b.exp().sum().backward();
+        assert_close(&g_b.get(&t2).array(), &[g_a.get(&t).array()]);
+    }
+
+    #[test]
+    fn test_expand_like_dyn() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, usize), f32, _> = dev.tensor_from_vec(std::vec![1.0, 2.0], (2, 1));
+        let r = t.expand_like(&(2, 3));
+        assert_eq!(r.as_vec(), [1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_expand_mismatch() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 3));
+        let err = t.try_expand_like(&(2, 4)).unwrap_err();
+        assert!(std::format!("{err:?}").contains("expand"), "{err:?}");
+    }
+}
@@ -0,0 +1,320 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+use super::FftOp;
+
+use std::{f64::consts::PI, vec, vec::Vec};
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Complex {
+    re: f64,
+    im: f64,
+}
+
+impl Complex {
+    fn cis(theta: f64) -> Self {
+        Self {
+            re: theta.cos(),
+            im: theta.sin(),
+        }
+    }
+
+    fn conj(self) -> Self {
+        Self {
+            re: self.re,
+            im: -self.im,
+        }
+    }
+
+    fn scale(self, s: f64) -> Self {
+        Self {
+            re: self.re * s,
+            im: self.im * s,
+        }
+    }
+
+    fn add(self, o: Self) -> Self {
+        Self {
+            re: self.re + o.re,
+            im: self.im + o.im,
+        }
+    }
+
+    fn sub(self, o: Self) -> Self {
+        Self {
+            re: self.re - o.re,
+            im: self.im - o.im,
+        }
+    }
+
+    fn mul(self, o: Self) -> Self {
+        Self {
+            re: self.re * o.re - self.im * o.im,
+            im: self.re * o.im + self.im * o.re,
+        }
+    }
+}
+
+/// Unnormalized complex DFT of `buf` in place. The forward transform uses `e^(-i theta)`,
+/// and the inverse `e^(i theta)`.
+fn fft(buf: &mut [Complex], inverse: bool) {
+    if buf.len() <= 1 {
+    } else if buf.len().is_power_of_two() {
+        radix2(buf, inverse);
+    } else {
+        bluestein(buf, inverse);
+    }
+}
+
+fn radix2(buf: &mut [Complex], inverse: bool) {
+    let n = buf.len();
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let twiddles: Vec<Complex> = (0..len / 2)
+            .map(|j| Complex::cis(sign * 2.0 * PI * j as f64 / len as f64))
+            .collect();
+        for chunk in buf.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            for ((u, v), w) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles.iter()) {
+                let t = v.mul(*w);
+                *v = u.sub(t);
+                *u = u.add(t);
+            }
+        }
+        len *= 2;
+    }
+}
+
+/// Rewrites the DFT as a convolution with a chirp, which is computed with
+/// power of two FFTs.
+fn bluestein(buf: &mut [Complex], inverse: bool) {
+    let n = buf.len();
+    let m = (2 * n - 1).next_power_of_two();
+    let sign = if inverse { 1.0 } else { -1.0 };
+    // `k * k` is reduced mod `2n` to keep the angles small
+    let chirp: Vec<Complex> = (0..n)
+        .map(|k| Complex::cis(sign * PI * ((k * k) % (2 * n)) as f64 / n as f64))
+        .collect();
+
+    let mut a = vec![Complex::default(); m];
+    for ((a, x), w) in a.iter_mut().zip(buf.iter()).zip(chirp.iter()) {
+        *a = x.mul(*w);
+    }
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+    for (a, b) in a.iter_mut().zip(b.iter()) {
+        *a = a.mul(*b);
+    }
+    radix2(&mut a, true);
+
+    for ((x, a), w) in buf.iter_mut().zip(a.iter()).zip(chirp.iter()) {
+        *x = a.mul(*w).scale(1.0 / m as f64);
+    }
+}
+
+/// Transforms each of the `freqs` columns of a `(h, freqs, 2)` block.
+fn fft_columns(block: &mut [f64], op: &FftOp, inverse: bool, scale: f64) {
+    let mut col = vec![Complex::default(); op.h];
+    for k in 0..op.freqs {
+        for (r, c) in col.iter_mut().enumerate() {
+            let i = (r * op.freqs + k) * 2;
+            *c = Complex {
+                re: block[i],
+                im: block[i + 1],
+            };
+        }
+        fft(&mut col, inverse);
+        for (r, c) in col.iter().enumerate() {
+            let i = (r * op.freqs + k) * 2;
+            block[i] = c.re * scale;
+            block[i + 1] = c.im * scale;
+        }
+    }
+}
+
+/// Weight of frequency `k` in a real signal of length `n`, and whether its imaginary part is
+/// used. Frequencies past `n / 2` are the conjugates of the ones before it, so aren't used.
+fn irfft_weight(k: usize, n: usize) -> (f64, bool) {
+    if k == 0 || 2 * k == n {
+        (1.0, false)
+    } else if 2 * k < n {
+        (2.0, true)
+    } else {
+        (0.0, false)
+    }
+}
+
+fn rfft(op: &FftOp, x: &[f64]) -> Vec<f64> {
+    let mut out = vec![0.0; op.batch * op.h * op.freqs * 2];
+    let mut row = vec![Complex::default(); op.n];
+    for (x, y) in x
+        .chunks(op.h * op.n)
+        .zip(out.chunks_mut(op.h * op.freqs * 2))
+    {
+        for (x, y) in x.chunks(op.n).zip(y.chunks_mut(op.freqs * 2)) {
+            for (c, &v) in row.iter_mut().zip(x.iter()) {
+                *c = Complex { re: v, im: 0.0 };
+            }
+            fft(&mut row, false);
+            for (y, c) in y.chunks_mut(2).zip(row.iter()) {
+                y[0] = c.re;
+                y[1] = c.im;
+            }
+        }
+        if op.two_dim {
+            fft_columns(y, op, false, 1.0);
+        }
+    }
+    out
+}
+
+fn rfft_backward(op: &FftOp, grad_out: &[f64]) -> Vec<f64> {
+    let mut grad_out = grad_out.to_vec();
+    let mut grad_inp = vec![0.0; op.batch * op.h * op.n];
+    let mut row = vec![Complex::default(); op.n];
+    for (g_inp, g_out) in grad_inp
+        .chunks_mut(op.h * op.n)
+        .zip(grad_out.chunks_mut(op.h * op.freqs * 2))
+    {
+        // the adjoint of the forward DFT is the unnormalized inverse DFT
+        if op.two_dim {
+            fft_columns(g_out, op, true, 1.0);
+        }
+        for (g_inp, g_out) in g_inp.chunks_mut(op.n).zip(g_out.chunks(op.freqs * 2)) {
+            row.fill(Complex::default());
+            for (c, g) in row.iter_mut().zip(g_out.chunks(2)) {
+                *c = Complex { re: g[0], im: g[1] };
+            }
+            fft(&mut row, true);
+            for (g, c) in g_inp.iter_mut().zip(row.iter()) {
+                *g = c.re;
+            }
+        }
+    }
+    grad_inp
+}
+
+fn irfft(op: &FftOp, y: &[f64]) -> Vec<f64> {
+    let mut y = y.to_vec();
+    let mut out = vec![0.0; op.batch * op.h * op.n];
+    let mut row = vec![Complex::default(); op.n];
+    for (y, x) in y
+        .chunks_mut(op.h * op.freqs * 2)
+        .zip(out.chunks_mut(op.h * op.n))
+    {
+        if op.two_dim {
+            fft_columns(y, op, true, 1.0 / op.h as f64);
+        }
+        for (y, x) in y.chunks(op.freqs * 2).zip(x.chunks_mut(op.n)) {
+            row.fill(Complex::default());
+            for (k, (c, y)) in row.iter_mut().zip(y.chunks(2)).enumerate() {
+                let (w, imag) = irfft_weight(k, op.n);
+                let im = if imag { y[1] } else { 0.0 };
+                *c = Complex { re: y[0], im }.scale(w);
+            }
+            fft(&mut row, true);
+            for (x, c) in x.iter_mut().zip(row.iter()) {
+                *x = c.re / op.n as f64;
+            }
+        }
+    }
+    out
+}
+
+fn irfft_backward(op: &FftOp, grad_out: &[f64]) -> Vec<f64> {
+    let mut grad_inp = vec![0.0; op.batch * op.h * op.freqs * 2];
+    let mut row = vec![Complex::default(); op.n];
+    for (g_inp, g_out) in grad_inp
+        .chunks_mut(op.h * op.freqs * 2)
+        .zip(grad_out.chunks(op.h * op.n))
+    {
+        for (g_inp, g_out) in g_inp.chunks_mut(op.freqs * 2).zip(g_out.chunks(op.n)) {
+            for (c, &g) in row.iter_mut().zip(g_out.iter()) {
+                *c = Complex { re: g, im: 0.0 };
+            }
+            fft(&mut row, false);
+            for (k, (g, c)) in g_inp.chunks_mut(2).zip(row.iter()).enumerate() {
+                let (w, imag) = irfft_weight(k, op.n);
+                g[0] = c.re * w / op.n as f64;
+                g[1] = if imag { c.im * w / op.n as f64 } else { 0.0 };
+            }
+        }
+        // the adjoint of the normalized inverse DFT is the normalized forward DFT
+        if op.two_dim {
+            fft_columns(g_inp, op, false, 1.0 / op.h as f64);
+        }
+    }
+    grad_inp
+}
+
+macro_rules! impl_fft {
+    ($Ty:ty) => {
+        impl super::FftKernel<$Ty> for Cpu {
+            fn forward<I: Shape, O: Shape>(
+                &self,
+                op: FftOp,
+                inp: &Self::Storage<I, $Ty>,
+                out: &mut Self::Storage<O, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let inp = to_vec(inp);
+                let y = match op.inverse {
+                    false => rfft(&op, &inp),
+                    true => irfft(&op, &inp),
+                };
+                for (o, y) in out.buf_iter_mut().zip(y.into_iter()) {
+                    *o = y as $Ty;
+                }
+                Ok(())
+            }
+
+            fn backward<I: Shape, O: Shape>(
+                &self,
+                op: FftOp,
+                grad_inp: &mut Self::Storage<I, $Ty>,
+                grad_out: &Self::Storage<O, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let grad_out = to_vec(grad_out);
+                let g = match op.inverse {
+                    false => rfft_backward(&op, &grad_out),
+                    true => irfft_backward(&op, &grad_out),
+                };
+                let mut g = g.into_iter();
+                let mut grad_inp = grad_inp.iter_mut();
+                while let Some(gi) = grad_inp.next() {
+                    *gi += g.next().unwrap() as $Ty;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// The elements of `arr` in logical order.
+fn to_vec<S: Shape, E: Copy + Into<f64>>(arr: &StridedArray<S, E>) -> Vec<f64> {
+    let mut buf = Vec::with_capacity(arr.shape.num_elements());
+    let mut iter = arr.iter();
+    while let Some(v) = iter.next() {
+        buf.push((*v).into());
+    }
+    buf
+}
+
+impl_fft!(f32);
+impl_fft!(f64);
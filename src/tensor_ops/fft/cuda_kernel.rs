@@ -0,0 +1,29 @@
+use super::{FftKernel, FftOp};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the transforms run on the host.
+impl FftKernel<f32> for Cuda {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: FftOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_out = self.storage_to_cpu(out)?;
+        FftKernel::<f32>::forward(&self.cpu, op, &cpu_inp, &mut cpu_out)?;
+        self.storage_from_cpu(out, &cpu_out)
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: FftOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+        FftKernel::<f32>::backward(&self.cpu, op, &mut cpu_grad_inp, &cpu_grad_out)?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
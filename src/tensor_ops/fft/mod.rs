@@ -0,0 +1,368 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+use std::vec::Vec;
+
+/// Which transform [FftKernel] computes, and the sizes of the signal & spectrum.
+///
+/// Signals are laid out as `(batch, h, n)` and spectrums as `(batch, h, freqs, 2)`,
+/// where `h` is `1` for 1d transforms.
+#[derive(Debug, Copy, Clone)]
+pub struct FftOp {
+    /// Whether this is [irfft()] instead of [rfft()].
+    pub inverse: bool,
+    /// Whether the columns are transformed as well, as in [Tensor::rfft2()].
+    pub two_dim: bool,
+    pub batch: usize,
+    pub h: usize,
+    pub n: usize,
+    pub freqs: usize,
+}
+
+pub trait FftKernel<E: Dtype>: DeviceStorage {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: FftOp,
+        inp: &Self::Storage<I, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: FftOp,
+        grad_inp: &mut Self::Storage<I, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Shapes whose last axis holds real signals that can be transformed with [rfft()].
+pub trait RfftShape: Shape {
+    /// The last axis replaced by the `n / 2 + 1` frequencies, followed by an axis
+    /// with the real & imaginary parts.
+    type Spectrum: Shape;
+    fn spectrum(&self) -> Self::Spectrum;
+}
+
+/// Shapes `(..., F, 2)` holding complex spectrums that can be transformed back into
+/// real signals of length `N` with [irfft()].
+pub trait IrfftShape<N: Dim>: Shape {
+    /// The last two axes replaced by the signal of length `N`.
+    type Signal: Shape;
+    fn signal(&self, n: N) -> Self::Signal;
+}
+
+macro_rules! fft_shapes {
+    ([$($Vars:tt),*], [$($Idx:tt),*], $Last:tt) => {
+        impl<$($Vars: Dim, )* N: Dim> RfftShape for ($($Vars, )* N,) {
+            type Spectrum = ($($Vars, )* usize, Const<2>);
+            fn spectrum(&self) -> Self::Spectrum {
+                ($(self.$Idx, )* self.$Last.size() / 2 + 1, Const)
+            }
+        }
+
+        impl<$($Vars: Dim, )* F: Dim, N: Dim> IrfftShape<N> for ($($Vars, )* F, Const<2>) {
+            type Signal = ($($Vars, )* N,);
+            fn signal(&self, n: N) -> Self::Signal {
+                ($(self.$Idx, )* n,)
+            }
+        }
+    };
+}
+
+fft_shapes!([], [], 0);
+fft_shapes!([A], [0], 1);
+fft_shapes!([A, B], [0, 1], 2);
+fft_shapes!([A, B, C], [0, 1, 2], 3);
+
+/// Discrete Fourier transform of the real signals along the last axis.
+///
+/// The result has the `n / 2 + 1` non-negative frequencies in place of the last axis,
+/// followed by an axis of size 2 with the real & imaginary parts. The transform is
+/// unnormalized, see [irfft()] for the inverse. The gradients are exact.
+///
+/// Power of two lengths use radix-2, and other lengths use Bluestein's algorithm, so
+/// any length is `O(n log n)`. This always runs on the host, even for `Cuda` tensors.
+///
+/// **Pytorch equivalent**: `torch.view_as_real(torch.fft.rfft(t))`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([1.0, 2.0, 3.0, 4.0]);
+/// let r = t.rfft();
+/// assert_eq!(r.as_vec(), [10.0, 0.0, -2.0, 2.0, -2.0, 0.0]);
+/// ```
+///
+/// The magnitude of each frequency, as used by spectral losses:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank2<3, 16>, f32, _> = dev.sample_normal();
+/// let mag: Tensor<(Const<3>, usize), f32, _> = t.rfft().square().sum::<_, Axis<2>>().sqrt();
+/// assert_eq!(mag.shape(), &(Const, 9));
+/// ```
+pub fn rfft<S: RfftShape, E: Dtype, D: FftKernel<E> + ZerosTensor<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S::Spectrum, E, D, T> {
+    t.rfft()
+}
+
+/// Inverse of [rfft()], transforming the complex spectrums in the last two axes back into
+/// real signals of length `n`.
+///
+/// Only the first `n / 2 + 1` frequencies are used, and missing ones are treated as zero.
+/// The imaginary parts of the zero frequency (and of the `n / 2` frequency for even `n`)
+/// are ignored, since they are always zero for real signals.
+///
+/// **Pytorch equivalent**: `torch.fft.irfft(torch.view_as_complex(t), n)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+/// let r: Tensor<Rank1<5>, f32, _> = t.clone().rfft().irfft(Const);
+/// assert!(r.array().iter().zip(t.array()).all(|(a, b)| (a - b).abs() < 1e-5));
+/// ```
+pub fn irfft<N: Dim, S: IrfftShape<N>, E: Dtype, D: FftKernel<E> + ZerosTensor<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    n: N,
+) -> Tensor<S::Signal, E, D, T> {
+    t.irfft(n)
+}
+
+impl<S: Shape, E: Dtype, D: FftKernel<E> + ZerosTensor<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [rfft]
+    pub fn rfft(self) -> Tensor<S::Spectrum, E, D, T>
+    where
+        S: RfftShape,
+    {
+        self.try_rfft().unwrap()
+    }
+
+    /// See [rfft]
+    pub fn try_rfft(self) -> Result<Tensor<S::Spectrum, E, D, T>, D::Err>
+    where
+        S: RfftShape,
+    {
+        let dst = self.shape().spectrum();
+        self.try_fft(false, false, dst)
+    }
+
+    /// 2d version of [rfft()], transforming the last two axes. Only the last axis is
+    /// halved, so `(..., H, W)` becomes `(..., H, W / 2 + 1, 2)`.
+    ///
+    /// **Pytorch equivalent**: `torch.view_as_real(torch.fft.rfft2(t))`
+    pub fn rfft2(self) -> Tensor<S::Spectrum, E, D, T>
+    where
+        S: RfftShape + HasAxes<Axis<1>>,
+    {
+        self.try_rfft2().unwrap()
+    }
+
+    /// See [Tensor::rfft2()]
+    pub fn try_rfft2(self) -> Result<Tensor<S::Spectrum, E, D, T>, D::Err>
+    where
+        S: RfftShape + HasAxes<Axis<1>>,
+    {
+        let dst = self.shape().spectrum();
+        self.try_fft(false, true, dst)
+    }
+
+    /// See [irfft]
+    pub fn irfft<N: Dim>(self, n: N) -> Tensor<S::Signal, E, D, T>
+    where
+        S: IrfftShape<N>,
+    {
+        self.try_irfft(n).unwrap()
+    }
+
+    /// See [irfft]
+    pub fn try_irfft<N: Dim>(self, n: N) -> Result<Tensor<S::Signal, E, D, T>, D::Err>
+    where
+        S: IrfftShape<N>,
+    {
+        let dst = self.shape().signal(n);
+        self.try_fft(true, false, dst)
+    }
+
+    /// Inverse of [Tensor::rfft2()], `(..., H, F, 2)` becomes `(..., H, n)`.
+    ///
+    /// **Pytorch equivalent**: `torch.fft.irfft2(torch.view_as_complex(t), (H, n))`
+    pub fn irfft2<N: Dim>(self, n: N) -> Tensor<S::Signal, E, D, T>
+    where
+        S: IrfftShape<N> + HasAxes<Axis<2>>,
+    {
+        self.try_irfft2(n).unwrap()
+    }
+
+    /// See [Tensor::irfft2()]
+    pub fn try_irfft2<N: Dim>(self, n: N) -> Result<Tensor<S::Signal, E, D, T>, D::Err>
+    where
+        S: IrfftShape<N> + HasAxes<Axis<2>>,
+    {
+        let dst = self.shape().signal(n);
+        self.try_fft(true, true, dst)
+    }
+
+    fn try_fft<Dst: Shape>(
+        self,
+        inverse: bool,
+        two_dim: bool,
+        dst: Dst,
+    ) -> Result<Tensor<Dst, E, D, T>, D::Err> {
+        let (src, dst_dims): (Vec<usize>, Vec<usize>) =
+            (self.shape().concrete().into(), dst.concrete().into());
+        let (signal, spectrum) = match inverse {
+            false => (src, dst_dims),
+            true => (dst_dims, src),
+        };
+        let num_dims = signal.len();
+        let h = if two_dim { signal[num_dims - 2] } else { 1 };
+        let n = signal[num_dims - 1];
+        let op = FftOp {
+            inverse,
+            two_dim,
+            batch: signal[..num_dims - 1].iter().product::<usize>() / h.max(1),
+            h,
+            n,
+            freqs: spectrum[spectrum.len() - 2],
+        };
+
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&dst)?;
+        inp.device.forward(op, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(op, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    /// `(re, im)` of the naive DFT of `x`
+    fn naive_dft(x: &[f32]) -> std::vec::Vec<[f32; 2]> {
+        let n = x.len();
+        (0..n / 2 + 1)
+            .map(|k| {
+                let mut c = [0.0f64; 2];
+                for (m, &v) in x.iter().enumerate() {
+                    let theta = -2.0 * std::f64::consts::PI * ((k * m) % n) as f64 / n as f64;
+                    c[0] += v as f64 * theta.cos();
+                    c[1] += v as f64 * theta.sin();
+                }
+                [c[0] as f32, c[1] as f32]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rfft_matches_naive_dft() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 8>, f32, _> = dev.sample_normal();
+        let r = t.clone().rfft();
+        assert_eq!(r.shape(), &(Const, 5, Const));
+        for (x, y) in t.array().iter().zip(r.as_vec().chunks(10)) {
+            assert_close(&y.to_vec(), &naive_dft(x).concat());
+        }
+
+        // bluestein
+        for n in [1, 3, 6, 7, 12] {
+            let x: Tensor<(usize,), f32, _> = dev.sample_like(&(n,), rand_distr::StandardNormal);
+            let y = x.clone().rfft();
+            assert_close(&y.as_vec(), &naive_dft(&x.as_vec()).concat());
+        }
+    }
+
+    #[test]
+    fn test_irfft_inverts_rfft() {
+        let dev: TestDevice = Default::default();
+        for n in [4, 5, 8, 9] {
+            let x: Tensor<(Const<3>, usize), f32, _> =
+                dev.sample_like(&(Const, n), rand_distr::StandardNormal);
+            let y = x.clone().rfft().irfft(n);
+            assert_close(&y.as_vec(), &x.as_vec());
+        }
+    }
+
+    #[test]
+    fn test_irfft_ignores_imaginary_dc_and_nyquist() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[4.0, 1.0], [0.0, 0.0], [2.0, 3.0]]);
+        assert_eq!(t.clone().irfft(4usize).as_vec(), [1.5, 0.5, 1.5, 0.5]);
+        // frequencies past `n / 2` are dropped, and missing ones are zero
+        assert_eq!(t.clone().irfft(2usize).as_vec(), [2.0, 2.0]);
+        // `2 + 3i` is not the `n / 2` frequency for odd `n`
+        let r: Tensor<Rank1<5>, f32, _> = t.irfft(Const);
+        let expected = [0, 1, 2, 3, 4].map(|m| {
+            let theta = 2.0 * std::f32::consts::PI * (2 * m) as f32 / 5.0;
+            (4.0 + 2.0 * (2.0 * theta.cos() - 3.0 * theta.sin())) / 5.0
+        });
+        assert_close(&r.array(), &expected);
+    }
+
+    #[test]
+    fn test_rfft2() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let r = t.rfft2();
+        assert_eq!(r.as_vec(), [10.0, 0.0, -2.0, 0.0, -4.0, 0.0, 0.0, 0.0]);
+
+        let x: Tensor<Rank3<2, 3, 5>, f32, _> = dev.sample_normal();
+        let y: Tensor<Rank3<2, 3, 5>, f32, _> = x.clone().rfft2().irfft2(Const);
+        assert_close(&y.array(), &x.array());
+    }
+
+    type Tr = OwnedTape<TestDevice>;
+
+    /// The transforms are linear, so the gradients are correct if `<f(x), g> == <x, f'(g)>`
+    /// for any `x`, where `f'(g)` is the gradient of `<f(x), g>`.
+    fn assert_adjoint<X: Shape, Y: Shape>(
+        x_shape: X,
+        y_shape: Y,
+        f: impl Fn(Tensor<X, f32, TestDevice, Tr>) -> Tensor<Y, f32, TestDevice, Tr>,
+    ) {
+        let dev: TestDevice = Default::default();
+        let x = dev.sample_like(&x_shape, rand_distr::StandardNormal);
+        let x2 = dev.sample_like(&x_shape, rand_distr::StandardNormal);
+        let g = dev.sample_like(&y_shape, rand_distr::StandardNormal);
+        let dot = |a: std::vec::Vec<f32>, b: std::vec::Vec<f32>| -> f32 {
+            a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+        };
+        let loss = (f(x.trace()) * g.clone()).sum::<Rank0, Y::AllAxes>();
+        let grads = loss.backward();
+        let lhs = dot(f(x2.trace()).as_vec(), g.as_vec());
+        let rhs = dot(x2.as_vec(), grads.get(&x).as_vec());
+        assert!((lhs - rhs).abs() < 1e-3, "{lhs} vs {rhs}");
+    }
+
+    #[test]
+    fn test_fft_gradients_are_adjoint() {
+        for n in [6, 7, 8] {
+            let signal = (Const::<2>, Const::<3>, n);
+            let spectrum = (Const::<2>, Const::<3>, n / 2 + 1, Const::<2>);
+            assert_adjoint(signal, spectrum, |x| x.rfft());
+            assert_adjoint(signal, spectrum, |x| x.rfft2());
+            assert_adjoint(spectrum, signal, |y| y.irfft(n));
+            assert_adjoint(spectrum, signal, |y| y.irfft2(n));
+            // fewer & more frequencies than needed
+            assert_adjoint((Const::<3>, 2, Const::<2>), (Const::<3>, n), |y| y.irfft(n));
+            assert_adjoint((Const::<3>, n, Const::<2>), (Const::<3>, n), |y| y.irfft(n));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+/// The index of the element that ends up at `i` after reversing `axes`.
+#[inline(always)]
+fn flipped<S: Shape>(shape: &S, axes: &[usize], mut i: S::Concrete) -> S::Concrete {
+    let dims = shape.concrete();
+    for &ax in axes {
+        i[ax] = dims[ax] - 1 - i[ax];
+    }
+    i
+}
+
+impl<E: Dtype> super::FlipKernel<E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        axes: &[usize],
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: Self::Storage<S, E> = StridedArray::new(inp.shape)?;
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            *o = inp[flipped(&inp.shape, axes, i)];
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        axes: &[usize],
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err> {
+        let mut out_iter = grad_out.iter_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            grad_inp[flipped(&grad_out.shape, axes, i)] += *o;
+        }
+        Ok(())
+    }
+}
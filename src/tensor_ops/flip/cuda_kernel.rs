@@ -0,0 +1,95 @@
+use super::FlipKernel;
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/flip.ptx"));
+const MODULE_NAME: &str = "flip";
+const FWD_FN_NAME: &str = "flip_forward";
+const BWD_FN_NAME: &str = "flip_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+/// Whether each dimension is flipped, as 0 or 1.
+fn flip_mask(num_dims: usize, axes: &[usize]) -> Vec<usize> {
+    (0..num_dims).map(|d| axes.contains(&d) as usize).collect()
+}
+
+impl FlipKernel<f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        axes: &[usize],
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let shape = inp.shape;
+        let strides = shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let flip: CudaSlice<usize> = self.dev.take_async(flip_mask(S::NUM_DIMS, axes))?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            &flip,             // const size_t *flip,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // float *out,
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        axes: &[usize],
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            FlipKernel::<f32>::backward(&self.cpu, axes, &mut cpu_grad_inp, &cpu_grad_out)?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let numel = grad_out.shape.num_elements();
+
+        let dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+        let flip: CudaSlice<usize> = self.dev.take_async(flip_mask(S::NUM_DIMS, axes))?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            S::NUM_DIMS,                       // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            &flip,                             // const size_t *flip,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+#include "cuda_utils.cuh"
+
+// the contiguous index of the element that ends up at `i` after reversing the flipped dims
+__device__ unsigned int flipped_index(
+    unsigned int i,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *flip
+) {
+    unsigned int idx = 0;
+    unsigned int stride = 1;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        unsigned int j = i % dims[dim_idx];
+        i /= dims[dim_idx];
+        if (flip[dim_idx]) {
+            j = dims[dim_idx] - 1 - j;
+        }
+        idx += j * stride;
+        stride *= dims[dim_idx];
+    }
+    return idx;
+}
+
+extern "C" __global__ void flip_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *flip,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int src = flipped_index(out_i, num_dims, dims, flip);
+    unsigned int inp_i = get_strided_index(src, num_dims, dims, inp_strides);
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void flip_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *flip,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int src = flipped_index(out_i, num_dims, dims, flip);
+    unsigned int inp_i = get_strided_index(src, num_dims, dims, inp_strides);
+    unsigned int grad_out_i = get_strided_index(out_i, num_dims, dims, out_strides);
+    atomicAdd(grad_inp + inp_i, grad_out[grad_out_i]);
+}
@@ -0,0 +1,129 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait FlipKernel<E: Dtype>: DeviceStorage {
+    /// Copies `inp`, with the order of the elements along each of `axes` reversed.
+    fn forward<S: Shape>(
+        &self,
+        axes: &[usize],
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    /// Adds `grad_out`, with the order of the elements along each of `axes` reversed, to `grad_inp`.
+    fn backward<S: Shape>(
+        &self,
+        axes: &[usize],
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Reverses the order of the elements along `Ax`.
+///
+/// **Pytorch equivalent**: `t.flip(Ax)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// assert_eq!(t.clone().flip::<Axis<1>>().array(), [[3.0, 2.0, 1.0], [6.0, 5.0, 4.0]]);
+/// assert_eq!(t.flip::<Axes2<0, 1>>().array(), [[6.0, 5.0, 4.0], [3.0, 2.0, 1.0]]);
+/// ```
+pub fn flip<Ax: Axes, S, E: Dtype, D: FlipKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T>
+where
+    S: Shape + HasAxes<Ax>,
+{
+    t.flip::<Ax>()
+}
+
+impl<S: Shape, E: Dtype, D: FlipKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [flip]
+    pub fn flip<Ax: Axes>(self) -> Self
+    where
+        S: HasAxes<Ax>,
+    {
+        self.try_flip::<Ax>().unwrap()
+    }
+
+    /// See [flip]
+    pub fn try_flip<Ax: Axes>(self) -> Result<Self, D::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let axes: std::vec::Vec<usize> = Ax::as_array().into_iter().map(|ax| ax as usize).collect();
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(&axes, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(&axes, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_flip_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 3.0, 0.5]);
+        let r = t.trace().flip::<Axis<0>>();
+        assert_eq!(r.array(), [0.5, 3.0, -2.0, 1.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0, 4.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [4.0, 3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_flip_3d_two_axes() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let r = t.trace().flip::<Axes2<0, 2>>();
+        let (a, b) = (t.array(), r.array());
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    assert_eq!(b[i][j][k], a[1 - i][j][3 - k]);
+                }
+            }
+        }
+        let w: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let g = (r * w.clone()).sum().backward();
+        assert_eq!(g.get(&t).array(), w.flip::<Axes2<0, 2>>().array());
+    }
+
+    #[test]
+    fn test_flip_twice_is_identity() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 5>, f32, _> = dev.sample_normal();
+        let r = t.clone().flip::<Axis<1>>().flip::<Axis<1>>();
+        assert_eq!(r.array(), t.array());
+    }
+
+    #[test]
+    fn test_flip_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t.trace().broadcast::<Rank2<2, 3>, _>().flip::<Axis<1>>();
+        assert_eq!(r.array(), [[3.0, 2.0, 1.0]; 2]);
+        let g = (r * dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [9.0, 7.0, 5.0]);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::tensor_ops::cpu_kernels::TernaryDerivative;
+
+impl TernaryDerivative<f32> for super::FmaKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32, y: &f32, z: &f32) -> f32 {
+        x.mul_add(*y, *z)
+    }
+    #[inline(always)]
+    fn dfdx(&self, _x: &f32, y: &f32, _z: &f32) -> f32 {
+        *y
+    }
+    #[inline(always)]
+    fn dfdy(&self, x: &f32, _y: &f32, _z: &f32) -> f32 {
+        *x
+    }
+    #[inline(always)]
+    fn dfdz(&self, _x: &f32, _y: &f32, _z: &f32) -> f32 {
+        1.0
+    }
+}
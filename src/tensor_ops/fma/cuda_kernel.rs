@@ -0,0 +1,10 @@
+use crate::tensor_ops::cuda_kernels::TernaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::FmaKernelOp {}
+
+impl TernaryOpCudaKernel for super::FmaKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/fma.ptx"));
+    const MODULE_NAME: &'static str = "fma";
+    const FWD_FN_NAME: &'static str = "fma_forward";
+    const BWD_FN_NAME: &'static str = "fma_backward";
+}
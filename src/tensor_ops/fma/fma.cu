@@ -0,0 +1,9 @@
+#include "ternary_op_macros.cuh"
+
+struct FmaKernelOp {};
+
+TERNARY_OP(fma_forward, fma_backward, FmaKernelOp,
+    fmaf(x, y, z),
+    y,
+    x,
+    1.0)
@@ -0,0 +1,107 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{ops::try_ternary_op, Device};
+use crate::{gradients::*, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FmaKernelOp;
+
+/// Fused multiply add `a * b + c`, computed in a single pass without any temporaries.
+///
+/// **Pytorch equivalent**: `torch.addcmul(c, a, b)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([1.0, 2.0, 3.0]);
+/// let b = dev.tensor([2.0, -1.0, 0.5]);
+/// let c = dev.tensor([1.0, 1.0, 1.0]);
+/// let r = a.fma(b, c);
+/// assert_eq!(r.array(), [3.0, -1.0, 2.5]);
+/// ```
+#[track_caller]
+pub fn fma<
+    S: Shape,
+    E: Dtype,
+    D: Device<E>,
+    A: Tape<D> + Merge<B> + Merge<C>,
+    B: Tape<D>,
+    C: Tape<D>,
+>(
+    a: Tensor<S, E, D, A>,
+    b: Tensor<S, E, D, B>,
+    c: Tensor<S, E, D, C>,
+) -> Tensor<S, E, D, A> {
+    a.fma(b, c)
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, A: Tape<D>> Tensor<S, E, D, A> {
+    /// See [fma]
+    #[track_caller]
+    pub fn fma<B: Tape<D>, C: Tape<D>>(self, b: Tensor<S, E, D, B>, c: Tensor<S, E, D, C>) -> Self
+    where
+        A: Merge<B> + Merge<C>,
+    {
+        self.try_fma(b, c).unwrap()
+    }
+
+    /// See [fma]
+    #[track_caller]
+    pub fn try_fma<B: Tape<D>, C: Tape<D>>(
+        self,
+        b: Tensor<S, E, D, B>,
+        c: Tensor<S, E, D, C>,
+    ) -> Result<Self, D::Err>
+    where
+        A: Merge<B> + Merge<C>,
+    {
+        try_ternary_op("fma", FmaKernelOp, self, b, c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_fma() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[-1.0, 0.0, 1.0], [3.0, 4.0, -5.0]]);
+        let b = dev.tensor([[0.5, 2.0, -1.0], [1.5, -2.0, 3.0]]);
+        let c = dev.tensor([[1.0, -1.0, 0.0], [2.0, 0.5, 1.0]]);
+
+        let r = a.trace().fma(b.trace(), c.trace());
+        assert_eq!(r.array(), [[0.5, -1.0, -1.0], [6.5, -7.5, -14.0]]);
+
+        let g = r.square().sum().backward();
+        assert_eq!(g.get(&a).array(), [[0.5, -4.0, 2.0], [19.5, 30.0, -84.0]]);
+        assert_eq!(g.get(&b).array(), [[-1.0, 0.0, -2.0], [39.0, -60.0, 140.0]]);
+        assert_eq!(g.get(&c).array(), [[1.0, -2.0, -2.0], [13.0, -15.0, -28.0]]);
+    }
+
+    #[test]
+    fn test_fma_matches_mul_add() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let c: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+
+        let r1 = fma(a.trace(), b.trace(), c.trace());
+        let r2 = a.trace() * b.clone() + c.clone();
+        assert_close(&r1.array(), &r2.array());
+
+        let g1 = r1.square().mean().backward();
+        let g2 = (a.trace() * b.trace() + c.trace())
+            .square()
+            .mean()
+            .backward();
+        assert_close(&g1.get(&a).array(), &g2.get(&a).array());
+        assert_close(&g1.get(&b).array(), &g2.get(&b).array());
+        assert_close(&g1.get(&c).array(), &g2.get(&c).array());
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::Cpu,
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype> super::GatherAlongKernel<E> for Cpu {
+    fn gather_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = idx.shape.concrete().into();
+        let inp_dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let idx_strides: Vec<usize> = idx.strides.into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let buf = Arc::make_mut(&mut out.data);
+        for_each_row(
+            &dims,
+            ax,
+            [&inp_strides, &idx_strides, &out_strides],
+            |[i_inp, i_idx, i_out]| {
+                for j in 0..dims[ax] {
+                    let k = idx.data[i_idx + j * idx_strides[ax]];
+                    assert!(k < inp_dims[ax], "index {k} out of bounds");
+                    buf[i_out + j * out_strides[ax]] += inp.data[i_inp + k * inp_strides[ax]];
+                }
+            },
+        );
+        Ok(())
+    }
+
+    fn scatter_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        out: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        src: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let dims: Vec<usize> = idx.shape.concrete().into();
+        let out_dims: Vec<usize> = out.shape.concrete().into();
+        let out_strides: Vec<usize> = out.strides.into();
+        let idx_strides: Vec<usize> = idx.strides.into();
+        let src_strides: Vec<usize> = src.strides.into();
+        let buf = Arc::make_mut(&mut out.data);
+        for_each_row(
+            &dims,
+            ax,
+            [&out_strides, &idx_strides, &src_strides],
+            |[i_out, i_idx, i_src]| {
+                for j in 0..dims[ax] {
+                    let k = idx.data[i_idx + j * idx_strides[ax]];
+                    assert!(k < out_dims[ax], "index {k} out of bounds");
+                    buf[i_out + k * out_strides[ax]] += src.data[i_src + j * src_strides[ax]];
+                }
+            },
+        );
+        Ok(())
+    }
+}
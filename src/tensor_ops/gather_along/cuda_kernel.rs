@@ -0,0 +1,83 @@
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/gather_along.ptx"));
+const MODULE_NAME: &str = "gather_along";
+const GATHER_FN_NAME: &str = "gather_add";
+const SCATTER_FN_NAME: &str = "scatter_add";
+const ALL_FN_NAMES: [&str; 2] = [GATHER_FN_NAME, SCATTER_FN_NAME];
+
+impl super::GatherAlongKernel<f32> for Cuda {
+    fn gather_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<Src, f32>,
+        idx: &Self::Storage<Dst, usize>,
+        out: &mut Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, GATHER_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = idx.shape.num_elements();
+        let dims: CudaSlice<usize> = self.dev.take_async(idx.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let idx_strides: CudaSlice<usize> = self.dev.take_async(idx.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out.strides.into())?;
+
+        let gather_fn = self.dev.get_func(MODULE_NAME, GATHER_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                        // const size_t numel,
+            Dst::NUM_DIMS,                // const size_t num_dims,
+            ax,                           // const size_t ax,
+            &dims,                        // const size_t *dims,
+            inp.data.as_ref(),            // const float *inp,
+            &inp_strides,                 // const size_t *inp_strides,
+            idx.data.as_ref(),            // const size_t *idx,
+            &idx_strides,                 // const size_t *idx_strides,
+            Arc::make_mut(&mut out.data), // float *out,
+            &out_strides,                 // const size_t *out_strides
+        );
+        unsafe { gather_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn scatter_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        out: &mut Self::Storage<Src, f32>,
+        idx: &Self::Storage<Dst, usize>,
+        src: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, SCATTER_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = idx.shape.num_elements();
+        let dims: CudaSlice<usize> = self.dev.take_async(idx.shape.concrete().into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(out.strides.into())?;
+        let idx_strides: CudaSlice<usize> = self.dev.take_async(idx.strides.into())?;
+        let src_strides: CudaSlice<usize> = self.dev.take_async(src.strides.into())?;
+
+        let scatter_fn = self.dev.get_func(MODULE_NAME, SCATTER_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                        // const size_t numel,
+            Dst::NUM_DIMS,                // const size_t num_dims,
+            ax,                           // const size_t ax,
+            &dims,                        // const size_t *dims,
+            Arc::make_mut(&mut out.data), // float *out,
+            &out_strides,                 // const size_t *out_strides,
+            idx.data.as_ref(),            // const size_t *idx,
+            &idx_strides,                 // const size_t *idx_strides,
+            src.data.as_ref(),            // const float *src,
+            &src_strides,                 // const size_t *src_strides
+        );
+        unsafe { scatter_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
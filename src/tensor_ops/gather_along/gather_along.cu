@@ -0,0 +1,77 @@
+// Each thread handles a single element of `idx`.
+
+// The offsets of the `i`th element of `idx` into `inp`, `idx` & `out`,
+// where the index along `ax` into `inp` is read from `idx`.
+__device__ void gather_along_offsets(
+    size_t i,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const size_t *inp_strides,
+    const size_t *idx,
+    const size_t *idx_strides,
+    const size_t *out_strides,
+    size_t *i_inp,
+    size_t *i_out
+) {
+    size_t i_idx = 0;
+    *i_inp = 0;
+    *i_out = 0;
+    for (size_t d = 0; d < num_dims; d++) {
+        size_t dim_idx = num_dims - 1 - d;
+        size_t c = i % dims[dim_idx];
+        i /= dims[dim_idx];
+        i_idx += c * idx_strides[dim_idx];
+        *i_out += c * out_strides[dim_idx];
+        if (dim_idx != ax) {
+            *i_inp += c * inp_strides[dim_idx];
+        }
+    }
+    *i_inp += idx[i_idx] * inp_strides[ax];
+}
+
+// Adds the values of `inp` gathered along `ax` to `out`.
+extern "C" __global__ void gather_add(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    const size_t *idx,
+    const size_t *idx_strides,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_inp, i_out;
+    gather_along_offsets(i, num_dims, ax, dims, inp_strides, idx, idx_strides, out_strides, &i_inp, &i_out);
+    out[i_out] += inp[i_inp];
+}
+
+// Adds the values of `src` to the positions of `out` they are gathered from.
+extern "C" __global__ void scatter_add(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    float *out,
+    const size_t *out_strides,
+    const size_t *idx,
+    const size_t *idx_strides,
+    const float *src,
+    const size_t *src_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_out, i_src;
+    gather_along_offsets(i, num_dims, ax, dims, out_strides, idx, idx_strides, src_strides, &i_out, &i_src);
+    atomicAdd(out + i_out, src[i_src]);
+}
@@ -0,0 +1,195 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+pub trait GatherAlongKernel<E: Dtype>: DeviceStorage {
+    /// Adds the values of `inp` gathered along `ax` to `out`.
+    fn gather_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        inp: &Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds the values of `src` to the positions of `out` they are gathered from.
+    fn scatter_add<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        out: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        src: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Checks that `idx` is no bigger than `inp` along every axis other than `ax`.
+#[track_caller]
+pub(crate) fn check_idx_shape<Src: Shape, Dst: Shape>(
+    op: &'static str,
+    ax: usize,
+    inp: &Src,
+    idx: &Dst,
+) -> Result<(), ShapeMismatch> {
+    let (inp_dims, idx_dims) = (inp.concrete(), idx.concrete());
+    for d in (0..Src::NUM_DIMS).filter(|&d| d != ax) {
+        if idx_dims[d] > inp_dims[d] {
+            ShapeMismatch::check_axes(op, (idx, d), (inp, d))?;
+        }
+    }
+    Ok(())
+}
+
+/// Gathers values along `Ax` with an index tensor of the same rank as `self`.
+/// The result has the shape of `idx`, and is:
+///
+/// ```text
+/// out[i][j][k] = t[idx[i][j][k]][j][k] # if Ax is 0
+/// out[i][j][k] = t[i][idx[i][j][k]][k] # if Ax is 1
+/// out[i][j][k] = t[i][j][idx[i][j][k]] # if Ax is 2
+/// ```
+///
+/// `idx` can have any size along `Ax`, and must not be bigger than `self` along any other axis.
+/// Unlike [crate::tensor_ops::SelectTo::gather], every element of the result
+/// picks its own index. The gradient is added back to the picked elements.
+///
+/// **Pytorch equivalent**: `torch.gather(t, Ax, idx)`
+///
+/// Returns [ShapeMismatch] if `idx` is bigger than `self` along an axis other than `Ax`.
+/// **Panics** if an index is out of bounds.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// let idx = dev.tensor([[2, 0, 0, 1], [1, 1, 2, 2]]);
+/// let r = t.gather_along::<Axis<1>>(idx);
+/// assert_eq!(r.array(), [[3.0, 1.0, 1.0, 2.0], [5.0, 5.0, 6.0, 6.0]]);
+/// ```
+pub trait GatherAlong<Idx>: HasErr + HasShape {
+    type Output;
+
+    /// See [GatherAlong]
+    #[track_caller]
+    fn gather_along<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx) -> Self::Output
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_gather_along::<Ax>(idx).unwrap()
+    }
+
+    /// Fallible version of [GatherAlong::gather_along]
+    fn try_gather_along<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Idx,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax>;
+}
+
+impl<S, Dst, E, D, T> GatherAlong<Tensor<Dst, usize, D>> for Tensor<S, E, D, T>
+where
+    S: Shape,
+    Dst: Shape<Concrete = S::Concrete>,
+    E: Dtype,
+    D: GatherAlongKernel<E> + ZerosTensor<E>,
+    T: Tape<D>,
+{
+    type Output = Tensor<Dst, E, D, T>;
+
+    #[track_caller]
+    fn try_gather_along<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Tensor<Dst, usize, D>,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        DeviceMismatch::check_same("gather_along", &self.device, &idx.device)?;
+        check_idx_shape("gather_along", ax, self.shape(), idx.shape())?;
+
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(idx.shape())?;
+        inp.device
+            .gather_add(ax, &inp.storage, &idx.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.scatter_add(ax, grad_inp, &idx.storage, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_gather_along_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t
+            .trace()
+            .gather_along::<Axis<0>>(dev.tensor([2, 2, 0, 1, 2]));
+        assert_eq!(r.array(), [3.0, 3.0, 1.0, 2.0, 3.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [3.0, 4.0, 8.0]);
+    }
+
+    #[test]
+    fn test_gather_along_2d_axis_0() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let idx = dev.tensor([[1, 0], [1, 1], [0, 0], [0, 1]]);
+        let r = t.trace().gather_along::<Axis<0>>(idx);
+        assert_eq!(r.array(), [[4.0, 2.0], [4.0, 5.0], [1.0, 2.0], [1.0, 5.0]]);
+        let g = r.exp().sum().backward();
+        let e = |x: f32| x.exp();
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [2.0 * e(1.0), 2.0 * e(2.0), 0.0],
+                [2.0 * e(4.0), 2.0 * e(5.0), 0.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_gather_along_3d_matches_loop() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let idx = dev.tensor([
+            [[3, 0, 1, 1, 2], [0, 0, 0, 3, 2], [1, 2, 3, 0, 0]],
+            [[2, 2, 2, 2, 2], [3, 1, 0, 0, 1], [0, 1, 2, 3, 3]],
+        ]);
+        let r = t.clone().gather_along::<Axis<2>>(idx.clone());
+        let (t, idx, r) = (t.array(), idx.array(), r.array());
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..5 {
+                    assert_eq!(r[i][j][k], t[i][j][idx[i][j][k]]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gather_along_too_big() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let idx: Tensor<Rank2<3, 3>, usize, _> = dev.zeros();
+        let err = t.try_gather_along::<Axis<1>>(idx).unwrap_err();
+        assert!(std::format!("{err}").contains("ShapeMismatch in `gather_along`"));
+    }
+}
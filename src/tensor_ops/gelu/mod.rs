@@ -12,6 +12,8 @@ pub struct GeLUKernelOp;
 
 /// [Gaussian Linear Unit (GeLU)](https://paperswithcode.com/method/gelu). `0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3)))`
 ///
+/// This is the tanh approximation of the exact GeLU, `x * normal_cdf(x)`, see [normal_cdf()].
+///
 /// Examples:
 /// ```rust
 /// # use dfdx::prelude::*;
@@ -28,6 +28,7 @@ pub struct HuberErrorKernelOp<E: Dtype> {
 /// let r = a.huber_error(b, 1.0);
 /// assert_eq!(r.array(), [0.125, 0.28125, 1.0]);
 /// ```
+#[track_caller]
 pub fn huber_error<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<R>, R: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, R>,
@@ -38,6 +39,7 @@ pub fn huber_error<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<R>, R: T
 
 impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     /// See [huber_error]
+    #[track_caller]
     pub fn huber_error<R: Tape<D>>(self, rhs: Tensor<S, E, D, R>, delta: E) -> Self
     where
         T: Merge<R>,
@@ -46,6 +48,7 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     }
 
     /// See [huber_error]
+    #[track_caller]
     pub fn try_huber_error<R: Tape<D>>(
         self,
         rhs: Tensor<S, E, D, R>,
@@ -54,7 +57,7 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     where
         T: Merge<R>,
     {
-        try_binary_op(HuberErrorKernelOp { delta }, self, rhs)
+        try_binary_op("huber_error", HuberErrorKernelOp { delta }, self, rhs)
     }
 }
 
@@ -0,0 +1,130 @@
+#![allow(clippy::type_complexity)]
+
+use crate::{gradients::OwnedTape, shapes::*, tensor::Tensor};
+
+use super::{Backward, Device, SumTo, TryMul};
+
+/// Computes the jacobian `∂f(x)/∂x` of a batched function `f` for every item in the batch `x`.
+///
+/// `f` must treat each row of its input independently (e.g. no batch norm), and is called
+/// **once** with a batch of `O * B` rows, where row `o * B + b` is a copy of `x[b]`. Output
+/// `o` of row `o * B + b` is then backpropagated, so all `O` output dimensions are computed with
+/// a single forward & backward pass. This makes it well suited for small output dimensions,
+/// like the residuals of physics-informed neural networks.
+///
+/// Returns a tensor of shape `(B, O, I)`, where `result[b][o][i] = ∂f(x[b])[o] / ∂x[b][i]`.
+///
+/// **The result does not have a tape**, so it can't be differentiated again.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let model: (Linear<2, 8>, Tanh, Linear<8, 3>) = BuildModule::build(&dev);
+/// let x: Tensor<Rank2<5, 2>, f32, _> = dev.sample_normal();
+/// let j: Tensor<Rank3<5, 3, 2>, f32, _> = jacobian(|x| model.forward(x), &x);
+/// ```
+pub fn jacobian<B: Dim, const I: usize, const O: usize, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<(B, Const<I>), E, D>,
+) -> Tensor<(B, Const<O>, Const<I>), E, D>
+where
+    F: FnOnce(
+        Tensor<(usize, Const<I>), E, D, OwnedTape<D>>,
+    ) -> Tensor<(usize, Const<O>), E, D, OwnedTape<D>>,
+{
+    try_jacobian(f, x).unwrap()
+}
+
+/// Fallible version of [jacobian()]
+pub fn try_jacobian<B: Dim, const I: usize, const O: usize, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: &Tensor<(B, Const<I>), E, D>,
+) -> Result<Tensor<(B, Const<O>, Const<I>), E, D>, D::Err>
+where
+    F: FnOnce(
+        Tensor<(usize, Const<I>), E, D, OwnedTape<D>>,
+    ) -> Tensor<(usize, Const<O>), E, D, OwnedTape<D>>,
+{
+    let batch = x.shape().0;
+    let b = batch.size();
+    let dev = x.device.clone();
+
+    let mut x_buf = std::vec![Default::default(); b * I];
+    x.copy_into(&mut x_buf);
+
+    // stack `O` copies of `x`, one for each output dimension
+    let mut x_rep: Tensor<(usize, Const<I>), E, D> = dev.try_zeros_like(&(O * b, Const))?;
+    x_rep.copy_from(&x_buf.repeat(O));
+
+    // select output `o` for each copy `o`
+    let mut mask_buf = std::vec![Default::default(); O * b * O];
+    for o in 0..O {
+        for i in 0..b {
+            mask_buf[(o * b + i) * O + o] = E::ONE;
+        }
+    }
+    let mut mask: Tensor<(usize, Const<O>), E, D> = dev.try_zeros_like(&(O * b, Const))?;
+    mask.copy_from(&mask_buf);
+
+    let y = f(x_rep.trace());
+    let grads = y.try_mul(mask)?.try_sum::<Rank0, _>()?.try_backward()?;
+
+    // grads are ordered (O, B, I), but the jacobian is (B, O, I)
+    let mut g_buf = std::vec![Default::default(); O * b * I];
+    dev.upgrade(grads.get(&x_rep).clone()).copy_into(&mut g_buf);
+    let mut j_buf = std::vec::Vec::with_capacity(b * O * I);
+    for i in 0..b {
+        for o in 0..O {
+            let start = (o * b + i) * I;
+            j_buf.extend_from_slice(&g_buf[start..start + I]);
+        }
+    }
+    let mut j = dev.try_zeros_like(&(batch, Const, Const))?;
+    j.copy_from(&j_buf);
+    Ok(j)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_jacobian_linear() {
+        let dev: TestDevice = Default::default();
+        let model: Linear<3, 2, _> = BuildModule::build(&dev);
+        let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let j = jacobian(|x| model.forward(x), &x);
+        let w = model.weight.array();
+        assert_eq!(j.array(), [w; 4]);
+    }
+
+    #[test]
+    fn test_jacobian_matches_backward() {
+        let dev: TestDevice = Default::default();
+        let model: (Linear<2, 8, _>, Tanh, Linear<8, 3, _>) = BuildModule::build(&dev);
+        let x: Tensor<Rank2<4, 2>, f32, _> = dev.sample_normal();
+        let j = jacobian(|x| model.forward(x), &x).array();
+        for o in 0..3 {
+            let mut mask = [[0.0; 3]; 4];
+            for row in mask.iter_mut() {
+                row[o] = 1.0;
+            }
+            let y = model.forward(x.trace()) * dev.tensor(mask);
+            let g = y.sum::<Rank0, _>().backward();
+            let g = g.get(&x).array();
+            for b in 0..4 {
+                assert_close(&j[b][o], &g[b]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobian_runtime_batch() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize, Const<2>), f32, _> = dev.zeros_like(&(5, Const));
+        let j = jacobian(|x| x.square(), &x);
+        assert_eq!(j.shape(), &(5, Const::<2>, Const::<2>));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::tensor_ops::cpu_kernels::{BinaryDerivative, TernaryDerivative};
+
+impl BinaryDerivative<f32> for super::ScalarLerpKernelOp<f32> {
+    #[inline(always)]
+    fn f(&self, x: &f32, y: &f32) -> f32 {
+        self.weight.mul_add(y - x, *x)
+    }
+    #[inline(always)]
+    fn dfdx(&self, _x: &f32, _y: &f32) -> f32 {
+        1.0 - self.weight
+    }
+    #[inline(always)]
+    fn dfdy(&self, _x: &f32, _y: &f32) -> f32 {
+        self.weight
+    }
+}
+
+impl TernaryDerivative<f32> for super::LerpKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32, y: &f32, z: &f32) -> f32 {
+        z.mul_add(y - x, *x)
+    }
+    #[inline(always)]
+    fn dfdx(&self, _x: &f32, _y: &f32, z: &f32) -> f32 {
+        1.0 - z
+    }
+    #[inline(always)]
+    fn dfdy(&self, _x: &f32, _y: &f32, z: &f32) -> f32 {
+        *z
+    }
+    #[inline(always)]
+    fn dfdz(&self, x: &f32, y: &f32, _z: &f32) -> f32 {
+        y - x
+    }
+}
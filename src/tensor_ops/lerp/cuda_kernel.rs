@@ -0,0 +1,18 @@
+use crate::tensor_ops::cuda_kernels::{BinaryOpCudaKernel, TernaryOpCudaKernel};
+
+unsafe impl cudarc::driver::AsKernelParam for super::ScalarLerpKernelOp<f32> {}
+unsafe impl cudarc::driver::AsKernelParam for super::LerpKernelOp {}
+
+impl BinaryOpCudaKernel for super::ScalarLerpKernelOp<f32> {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/scalar_lerp.ptx"));
+    const MODULE_NAME: &'static str = "scalar_lerp";
+    const FWD_FN_NAME: &'static str = "scalar_lerp_forward";
+    const BWD_FN_NAME: &'static str = "scalar_lerp_backward";
+}
+
+impl TernaryOpCudaKernel for super::LerpKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/lerp.ptx"));
+    const MODULE_NAME: &'static str = "lerp";
+    const FWD_FN_NAME: &'static str = "lerp_forward";
+    const BWD_FN_NAME: &'static str = "lerp_backward";
+}
@@ -0,0 +1,9 @@
+#include "ternary_op_macros.cuh"
+
+struct LerpKernelOp {};
+
+TERNARY_OP(lerp_forward, lerp_backward, LerpKernelOp,
+    fmaf(z, y - x, x),
+    1.0 - z,
+    z,
+    y - x)
@@ -0,0 +1,139 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{ops::*, Device};
+use crate::{gradients::*, shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LerpKernelOp;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ScalarLerpKernelOp<E> {
+    weight: E,
+}
+
+/// Linear interpolation `start + weight * (end - start)`, computed in a single pass
+/// without any temporaries. `weight` can either be a scalar or a tensor.
+///
+/// **Pytorch equivalent**: `torch.lerp(start, end, weight)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([0.0, 1.0, 2.0]);
+/// let b = dev.tensor([4.0, 5.0, -2.0]);
+/// let r = a.clone().lerp(b.clone(), 0.25);
+/// assert_eq!(r.array(), [1.0, 2.0, 1.0]);
+/// let r = a.lerp(b, dev.tensor([0.0, 0.5, 1.0]));
+/// assert_eq!(r.array(), [0.0, 3.0, -2.0]);
+/// ```
+#[track_caller]
+pub fn lerp<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<R>, R: Tape<D>, W>(
+    start: Tensor<S, E, D, T>,
+    end: Tensor<S, E, D, R>,
+    weight: W,
+) -> Tensor<S, E, D, T>
+where
+    Tensor<S, E, D, T>: TryLerp<Tensor<S, E, D, R>, W>,
+{
+    start.lerp(end, weight)
+}
+
+/// Linear interpolation between two tensors. See [lerp].
+pub trait TryLerp<End, Weight>: HasErr {
+    /// Interpolates between `self` and `end` using `weight`. See [lerp].
+    #[track_caller]
+    fn lerp(self, end: End, weight: Weight) -> Self {
+        self.try_lerp(end, weight).unwrap()
+    }
+
+    /// Fallible version of [TryLerp::lerp]
+    fn try_lerp(self, end: End, weight: Weight) -> Result<Self, Self::Err>;
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>, R: Tape<D>> TryLerp<Tensor<S, E, D, R>, E>
+    for Tensor<S, E, D, T>
+where
+    T: Merge<R>,
+{
+    #[track_caller]
+    fn try_lerp(self, end: Tensor<S, E, D, R>, weight: E) -> Result<Self, Self::Err> {
+        try_binary_op("lerp", ScalarLerpKernelOp { weight }, self, end)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>, R: Tape<D>, W: Tape<D>>
+    TryLerp<Tensor<S, E, D, R>, Tensor<S, E, D, W>> for Tensor<S, E, D, T>
+where
+    T: Merge<R> + Merge<W>,
+{
+    #[track_caller]
+    fn try_lerp(
+        self,
+        end: Tensor<S, E, D, R>,
+        weight: Tensor<S, E, D, W>,
+    ) -> Result<Self, Self::Err> {
+        try_ternary_op("lerp", LerpKernelOp, self, end, weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_scalar_lerp() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[-1.0, 0.0, 1.0], [3.0, 4.0, -5.0]]);
+        let b = dev.tensor([[1.0, 2.0, -1.0], [1.0, 0.0, 3.0]]);
+
+        let r = a.trace().lerp(b.trace(), 0.25);
+        assert_eq!(r.array(), [[-0.5, 0.5, 0.5], [2.5, 3.0, -3.0]]);
+
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[0.75; 3]; 2]);
+        assert_eq!(g.get(&b).array(), [[0.25; 3]; 2]);
+    }
+
+    #[test]
+    fn test_tensor_lerp() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[-1.0, 0.0, 1.0], [3.0, 4.0, -5.0]]);
+        let b = dev.tensor([[1.0, 2.0, -1.0], [1.0, 0.0, 3.0]]);
+        let w = dev.tensor([[0.0, 0.5, 1.0], [0.25, 0.75, 2.0]]);
+
+        let r = lerp(a.trace(), b.trace(), w.trace());
+        assert_eq!(r.array(), [[-1.0, 1.0, -1.0], [2.5, 1.0, 11.0]]);
+
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[1.0, 0.5, 0.0], [0.75, 0.25, -1.0]]);
+        assert_eq!(g.get(&b).array(), w.array());
+        assert_eq!(g.get(&w).array(), [[2.0, 2.0, -2.0], [-2.0, -4.0, 8.0]]);
+    }
+
+    #[test]
+    fn test_lerp_matches_unfused() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        let w: Tensor<Rank2<2, 3>, f32, _> = dev.sample_uniform();
+
+        let r1 = a.trace().lerp(b.trace(), w.trace());
+        let r2 = a.trace() + w.trace() * (b.trace() - a.clone());
+        assert_close(&r1.array(), &r2.array());
+
+        let g1 = r1.exp().mean().backward();
+        let g2 = (a.trace() + w.trace() * (b.trace() - a.trace()))
+            .exp()
+            .mean()
+            .backward();
+        assert_close(&g1.get(&a).array(), &g2.get(&a).array());
+        assert_close(&g1.get(&b).array(), &g2.get(&b).array());
+        assert_close(&g1.get(&w).array(), &g2.get(&w).array());
+    }
+}
@@ -0,0 +1,10 @@
+#include "binary_op_macros.cuh"
+
+struct ScalarLerpKernelOp {
+    float weight;
+};
+
+BINARY_OP(scalar_lerp_forward, scalar_lerp_backward, ScalarLerpKernelOp,
+    fmaf(op.weight, y - x, x),
+    1.0 - op.weight,
+    op.weight)
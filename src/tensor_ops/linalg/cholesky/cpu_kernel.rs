@@ -0,0 +1,107 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, LendingIterator},
+};
+
+use super::{super::cpu_kernel::to_vec, CholeskyOp};
+
+use std::{vec, vec::Vec};
+
+/// Cholesky–Banachiewicz, reading only the lower triangle of `a`. Non positive definite
+/// matrices end up with `NaN`s from the square root of a negative number.
+fn cholesky(n: usize, a: &[f64], l: &mut [f64]) {
+    for i in 0..n {
+        for j in 0..=i {
+            let dot: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+            let s = a[i * n + j] - dot;
+            l[i * n + j] = if i == j { s.sqrt() } else { s / l[j * n + j] };
+        }
+    }
+}
+
+/// The symmetric gradient of `a` given `L` & the gradient of `L`, from
+/// "Differentiation of the Cholesky decomposition" (Murray, 2016):
+/// `S = L^-T phi(L^T grad_l) L^-1` and `grad_a = (S + S^T) / 2`,
+/// where `phi` takes the lower triangle and halves the diagonal.
+fn cholesky_backward(n: usize, l: &[f64], grad_l: &[f64]) -> Vec<f64> {
+    let mut p = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            // (L^T grad_l)[i][j], where L[k][i] is zero for k < i
+            let v: f64 = (i..n).map(|k| l[k * n + i] * grad_l[k * n + j]).sum();
+            p[i * n + j] = if i == j { v / 2.0 } else { v };
+        }
+    }
+
+    // X = P L^-1, by solving X L = P one row at a time, from the last column to the first
+    for r in 0..n {
+        for c in (0..n).rev() {
+            let dot: f64 = (c + 1..n).map(|k| p[r * n + k] * l[k * n + c]).sum();
+            p[r * n + c] = (p[r * n + c] - dot) / l[c * n + c];
+        }
+    }
+
+    // S = L^-T X, by solving L^T S = X one column at a time, from the last row to the first
+    for c in 0..n {
+        for r in (0..n).rev() {
+            let dot: f64 = (r + 1..n).map(|k| l[k * n + r] * p[k * n + c]).sum();
+            p[r * n + c] = (p[r * n + c] - dot) / l[r * n + r];
+        }
+    }
+
+    let mut grad_a = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..n {
+            grad_a[i * n + j] = (p[i * n + j] + p[j * n + i]) / 2.0;
+        }
+    }
+    grad_a
+}
+
+macro_rules! impl_cholesky {
+    ($Ty:ty) => {
+        impl super::CholeskyKernel<$Ty> for Cpu {
+            fn forward<S: Shape>(
+                &self,
+                op: CholeskyOp,
+                inp: &Self::Storage<S, $Ty>,
+                out: &mut Self::Storage<S, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let inp = to_vec(inp);
+                let mut l = vec![0.0; inp.len()];
+                let size = op.n * op.n;
+                for (a, l) in inp.chunks(size.max(1)).zip(l.chunks_mut(size.max(1))) {
+                    cholesky(op.n, a, l);
+                }
+                for (o, l) in out.buf_iter_mut().zip(l.into_iter()) {
+                    *o = l as $Ty;
+                }
+                Ok(())
+            }
+
+            fn backward<S: Shape>(
+                &self,
+                op: CholeskyOp,
+                out: &Self::Storage<S, $Ty>,
+                grad_inp: &mut Self::Storage<S, $Ty>,
+                grad_out: &Self::Storage<S, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let (l, grad_out) = (to_vec(out), to_vec(grad_out));
+                let size = op.n * op.n;
+                let mut g = Vec::with_capacity(l.len());
+                for (l, grad_l) in l.chunks(size.max(1)).zip(grad_out.chunks(size.max(1))) {
+                    g.extend(cholesky_backward(op.n, l, grad_l));
+                }
+                let mut g = g.into_iter();
+                let mut grad_inp = grad_inp.iter_mut();
+                while let Some(gi) = grad_inp.next() {
+                    *gi += g.next().unwrap() as $Ty;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_cholesky!(f32);
+impl_cholesky!(f64);
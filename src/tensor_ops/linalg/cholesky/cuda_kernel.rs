@@ -0,0 +1,31 @@
+use super::{CholeskyKernel, CholeskyOp};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the decomposition runs on the host.
+impl CholeskyKernel<f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        op: CholeskyOp,
+        inp: &Self::Storage<S, f32>,
+        out: &mut Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_out = self.storage_to_cpu(out)?;
+        CholeskyKernel::<f32>::forward(&self.cpu, op, &cpu_inp, &mut cpu_out)?;
+        self.storage_from_cpu(out, &cpu_out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        op: CholeskyOp,
+        out: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_out = self.storage_to_cpu(out)?;
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+        CholeskyKernel::<f32>::backward(&self.cpu, op, &cpu_out, &mut cpu_grad_inp, &cpu_grad_out)?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
@@ -0,0 +1,210 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, Invariant, PutTape, SplitTape, Tensor},
+    tensor_ops::Device,
+};
+
+/// The number & size of the matrices [CholeskyKernel] decomposes.
+#[derive(Debug, Copy, Clone)]
+pub struct CholeskyOp {
+    pub batch: usize,
+    pub n: usize,
+}
+
+pub trait CholeskyKernel<E: Dtype>: DeviceStorage {
+    /// Writes the lower triangular factor of each matrix of `inp` to `out`.
+    fn forward<S: Shape>(
+        &self,
+        op: CholeskyOp,
+        inp: &Self::Storage<S, E>,
+        out: &mut Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds the gradient of the matrices to `grad_inp`, given the factors `out`.
+    fn backward<S: Shape>(
+        &self,
+        op: CholeskyOp,
+        out: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// [Cholesky decomposition](https://en.wikipedia.org/wiki/Cholesky_decomposition) of the
+/// symmetric positive definite matrices in the last two axes. Returns the lower triangular
+/// `L` where `t = L * L^T`, with zeros above the diagonal.
+///
+/// Only the lower triangle of `t` is read. The gradient is symmetric, i.e. it assumes a change
+/// to `t[i][j]` is also a change to `t[j][i]`, which is what you want when `t` is built as a
+/// covariance, like `m * m^T`. This always runs on the host, even for `Cuda` tensors.
+///
+/// If a matrix isn't positive definite, its factor has `NaN`s.
+///
+/// **Pytorch equivalent**: `torch.linalg.cholesky(t)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[4.0, 2.0], [2.0, 5.0]]);
+/// let l = t.cholesky();
+/// assert_eq!(l.array(), [[2.0, 0.0], [1.0, 2.0]]);
+/// ```
+///
+/// The log density of a multivariate normal, with a covariance built from parameters:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: Tensor<Rank2<3, 3>, f32, _> = dev.sample_normal();
+/// let eye = dev.tensor([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]);
+/// let cov = m.trace().matmul(m.clone().permute()) + eye.clone() * 0.1;
+/// let l = cov.cholesky();
+/// let log_det = (l * eye).sum::<Rank1<3>, Axis<1>>().ln().sum() * 2.0;
+/// let _ = log_det.backward();
+/// ```
+///
+/// Returns [ShapeMismatch] if the last two axes are different sizes.
+///
+/// In checked mode, returns an error if the result is not finite, e.g. because a matrix isn't
+/// positive definite.
+pub fn cholesky<S: Shape + HasAxes<Axis<1>>, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.cholesky()
+}
+
+impl<S: Shape + HasAxes<Axis<1>>, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [cholesky]
+    #[track_caller]
+    pub fn cholesky(self) -> Self {
+        self.try_cholesky().unwrap()
+    }
+
+    /// See [cholesky]
+    #[track_caller]
+    pub fn try_cholesky(self) -> Result<Self, D::Err> {
+        let shape = *self.shape();
+        let dims = shape.concrete();
+        let num_dims = S::NUM_DIMS;
+        ShapeMismatch::check_axes("cholesky", (&shape, num_dims - 2), (&shape, num_dims - 1))?;
+        let n = dims[num_dims - 1];
+        let op = CholeskyOp {
+            batch: shape.num_elements() / (n * n).max(1),
+            n,
+        };
+
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&shape)?;
+        CholeskyKernel::<E>::forward(&inp.device, op, &inp.storage, &mut out.storage)?;
+        out.try_check("cholesky", "output", Invariant::Finite)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            let out = &phantom_out.storage;
+            CholeskyKernel::<E>::backward(&inp.device, op, out, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gradients::OwnedTape, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_cholesky_reconstructs() {
+        let dev: TestDevice = Default::default();
+        let m: Tensor<Rank3<2, 4, 4>, f32, _> = dev.sample_normal();
+        let a = m.clone().matmul(m.permute::<_, Axes3<0, 2, 1>>()) + dev.ones() * 0.5;
+        let l = a.clone().cholesky();
+        for (l, a) in l.array().iter().zip(a.array().iter()) {
+            for i in 0..4 {
+                for j in 0..4 {
+                    if j > i {
+                        assert_eq!(l[i][j], 0.0);
+                    }
+                    let dot: f32 = (0..4).map(|k| l[i][k] * l[j][k]).sum();
+                    assert!((dot - a[i][j]).abs() < 1e-4, "{dot} != {}", a[i][j]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cholesky_known_values() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([
+            [4.0, 12.0, -16.0],
+            [12.0, 37.0, -43.0],
+            [-16.0, -43.0, 98.0],
+        ]);
+        let l = a.trace().cholesky();
+        assert_close(
+            &l.array(),
+            &[[2.0, 0.0, 0.0], [6.0, 1.0, 0.0], [-8.0, 5.0, 3.0]],
+        );
+        // d/dA of sum(L), compared with finite differences of symmetric perturbations
+        let g = l.sum().backward();
+        assert_close(
+            &g.get(&a).array(),
+            &[
+                [30.666666, -7.583333, 1.9166666],
+                [-7.583333, 2.1666667, -0.33333334],
+                [1.9166666, -0.33333334, 0.16666667],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_cholesky_grad_through_covariance() {
+        let dev: TestDevice = Default::default();
+        let m: Tensor<Rank2<3, 3>, f32, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 3>, f32, _> = dev.sample_normal();
+        let eye = dev.tensor([[0.5, 0.0, 0.0], [0.0, 0.5, 0.0], [0.0, 0.0, 0.5]]);
+        let f = |m: Tensor<Rank2<3, 3>, f32, _, OwnedTape<_>>| {
+            let mt = m.retaped::<OwnedTape<_>>().permute();
+            let a = m.matmul(mt) + eye.clone();
+            (a.cholesky() * w.clone()).sum()
+        };
+        let g = f(m.trace()).backward();
+        let g = g.get(&m).array();
+
+        let eps = 1e-2;
+        let m_arr = m.array();
+        for i in 0..3 {
+            for j in 0..3 {
+                let mut hi = m_arr;
+                hi[i][j] += eps;
+                let mut lo = m_arr;
+                lo[i][j] -= eps;
+                let hi = f(dev.tensor(hi).trace()).array();
+                let lo = f(dev.tensor(lo).trace()).array();
+                let numeric = (hi - lo) / (2.0 * eps);
+                assert!(
+                    (numeric - g[i][j]).abs() < 1e-2 * (1.0 + numeric.abs()),
+                    "{numeric} != {}",
+                    g[i][j]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cholesky_not_positive_definite() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [2.0, 1.0]]);
+        let l = a.clone().cholesky();
+        assert!(l.array()[1][1].is_nan());
+
+        let a: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 3));
+        assert!(a.try_cholesky().is_err());
+    }
+}
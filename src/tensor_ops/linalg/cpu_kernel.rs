@@ -0,0 +1,35 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{LendingIterator, StridedArray},
+};
+
+use std::vec::Vec;
+
+/// The elements of `arr` in logical order.
+pub(super) fn to_vec<S: Shape, E: Copy + Into<f64>>(arr: &StridedArray<S, E>) -> Vec<f64> {
+    let mut buf = Vec::with_capacity(arr.shape.num_elements());
+    let mut iter = arr.iter();
+    while let Some(v) = iter.next() {
+        buf.push((*v).into());
+    }
+    buf
+}
+
+/// Applies the rotation `[[c, s], [-s, c]]` to columns `p` & `q` of the row major `a`
+/// with `cols` columns.
+pub(super) fn rotate_cols(a: &mut [f64], cols: usize, p: usize, q: usize, c: f64, s: f64) {
+    for row in a.chunks_mut(cols) {
+        let (x, y) = (row[p], row[q]);
+        row[p] = c * x - s * y;
+        row[q] = s * x + c * y;
+    }
+}
+
+/// The `t` of the Jacobi rotation that zeros the off diagonal of the symmetric 2x2 matrix
+/// `[[app, apq], [apq, aqq]]`, as `(c, s) = (1, t) / sqrt(1 + t^2)`.
+pub(super) fn jacobi_rotation(app: f64, aqq: f64, apq: f64) -> (f64, f64) {
+    let theta = (aqq - app) / (2.0 * apq);
+    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+    let c = 1.0 / (t * t + 1.0).sqrt();
+    (c, t * c)
+}
@@ -0,0 +1,125 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, LendingIterator},
+};
+
+use super::{super::cpu_kernel::to_vec, DetOp};
+
+use std::{vec, vec::Vec};
+
+/// LU decomposition with partial pivoting of the row major `a` in place, with the unit lower
+/// triangle of `L` below the diagonal. Returns the rows of `a` in the order they ended up in,
+/// and the sign of that permutation.
+fn lu(n: usize, a: &mut [f64]) -> (Vec<usize>, f64) {
+    let mut perm: Vec<usize> = (0..n).collect();
+    let mut sign = 1.0;
+    for k in 0..n {
+        let p = (k..n)
+            .max_by(|&i, &j| a[i * n + k].abs().total_cmp(&a[j * n + k].abs()))
+            .unwrap();
+        if p != k {
+            for j in 0..n {
+                a.swap(k * n + j, p * n + j);
+            }
+            perm.swap(k, p);
+            sign = -sign;
+        }
+        let pivot = a[k * n + k];
+        if pivot == 0.0 {
+            continue;
+        }
+        for i in k + 1..n {
+            let l = a[i * n + k] / pivot;
+            a[i * n + k] = l;
+            for j in k + 1..n {
+                a[i * n + j] -= l * a[k * n + j];
+            }
+        }
+    }
+    (perm, sign)
+}
+
+fn det(op: &DetOp, a: &[f64]) -> f64 {
+    let n = op.n;
+    let mut a = a.to_vec();
+    let (_, sign) = lu(n, &mut a);
+    let diag = (0..n).map(|i| a[i * n + i]);
+    match op.log {
+        true => diag.map(|d| d.abs().ln()).sum(),
+        false => sign * diag.product::<f64>(),
+    }
+}
+
+/// The transposed inverse of the row major `a`, by solving for each column of the inverse.
+fn inverse_transpose(n: usize, a: &[f64]) -> Vec<f64> {
+    let mut lu_a = a.to_vec();
+    let (perm, _) = lu(n, &mut lu_a);
+    let mut inv_t = vec![0.0; n * n];
+    let mut x = vec![0.0; n];
+    for c in 0..n {
+        // L U x = P e_c
+        for i in 0..n {
+            let b = if perm[i] == c { 1.0 } else { 0.0 };
+            x[i] = b - (0..i).map(|j| lu_a[i * n + j] * x[j]).sum::<f64>();
+        }
+        for i in (0..n).rev() {
+            let s: f64 = (i + 1..n).map(|j| lu_a[i * n + j] * x[j]).sum();
+            x[i] = (x[i] - s) / lu_a[i * n + i];
+        }
+        // column c of the inverse is row c of its transpose
+        inv_t[c * n..(c + 1) * n].copy_from_slice(&x);
+    }
+    inv_t
+}
+
+macro_rules! impl_det {
+    ($Ty:ty) => {
+        impl super::DetKernel<$Ty> for Cpu {
+            fn forward<S: Shape, B: Shape>(
+                &self,
+                op: DetOp,
+                inp: &Self::Storage<S, $Ty>,
+                out: &mut Self::Storage<B, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let inp = to_vec(inp);
+                let dets = inp
+                    .chunks((op.n * op.n).max(1))
+                    .take(op.batch)
+                    .map(|a| det(&op, a));
+                for (o, d) in out.buf_iter_mut().zip(dets) {
+                    *o = d as $Ty;
+                }
+                Ok(())
+            }
+
+            fn backward<S: Shape, B: Shape>(
+                &self,
+                op: DetOp,
+                inp: &Self::Storage<S, $Ty>,
+                out: &Self::Storage<B, $Ty>,
+                grad_inp: &mut Self::Storage<S, $Ty>,
+                grad_out: &Self::Storage<B, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let (inp, out, grad_out) = (to_vec(inp), to_vec(out), to_vec(grad_out));
+                let n = op.n;
+                let mut g: Vec<f64> = Vec::with_capacity(inp.len());
+                for (a, (d, go)) in inp
+                    .chunks((n * n).max(1))
+                    .zip(out.iter().zip(grad_out.iter()))
+                {
+                    let scale = if op.log { *go } else { go * d };
+                    g.extend(inverse_transpose(n, a).into_iter().map(|v| v * scale));
+                }
+                let mut g = g.into_iter();
+                let mut grad_inp = grad_inp.iter_mut();
+                while let Some(gi) = grad_inp.next() {
+                    *gi += g.next().unwrap() as $Ty;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_det!(f32);
+impl_det!(f64);
@@ -0,0 +1,40 @@
+use super::{DetKernel, DetOp};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the determinants are computed on the host.
+impl DetKernel<f32> for Cuda {
+    fn forward<S: Shape, B: Shape>(
+        &self,
+        op: DetOp,
+        inp: &Self::Storage<S, f32>,
+        out: &mut Self::Storage<B, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_out = self.storage_to_cpu(out)?;
+        DetKernel::<f32>::forward(&self.cpu, op, &cpu_inp, &mut cpu_out)?;
+        self.storage_from_cpu(out, &cpu_out)
+    }
+
+    fn backward<S: Shape, B: Shape>(
+        &self,
+        op: DetOp,
+        inp: &Self::Storage<S, f32>,
+        out: &Self::Storage<B, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<B, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let cpu_out = self.storage_to_cpu(out)?;
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+        DetKernel::<f32>::backward(
+            &self.cpu,
+            op,
+            &cpu_inp,
+            &cpu_out,
+            &mut cpu_grad_inp,
+            &cpu_grad_out,
+        )?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
@@ -0,0 +1,204 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{batch_rows_cols, MatrixShape};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+    tensor_ops::Device,
+};
+
+/// The number & size of the matrices [DetKernel] computes the determinants of, and whether
+/// to compute `ln(|det|)` instead.
+#[derive(Debug, Copy, Clone)]
+pub struct DetOp {
+    pub batch: usize,
+    pub n: usize,
+    pub log: bool,
+}
+
+pub trait DetKernel<E: Dtype>: DeviceStorage {
+    /// Writes the determinant (or the log of its absolute value) of each matrix of `inp`
+    /// to `out`.
+    fn forward<S: Shape, B: Shape>(
+        &self,
+        op: DetOp,
+        inp: &Self::Storage<S, E>,
+        out: &mut Self::Storage<B, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds `grad_out * out * inp^-T` to `grad_inp`, or `grad_out * inp^-T` for `ln(|det|)`.
+    fn backward<S: Shape, B: Shape>(
+        &self,
+        op: DetOp,
+        inp: &Self::Storage<S, E>,
+        out: &Self::Storage<B, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<B, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// [Determinant](https://en.wikipedia.org/wiki/Determinant) of the square matrices in the
+/// last two axes, computed with an LU decomposition.
+///
+/// The gradient is `det * t^-T`, so it is not finite for singular matrices, even though the
+/// determinant is `0`. Use [logdet()] for large matrices, whose determinant easily over or
+/// underflows.
+///
+/// **Pytorch equivalent**: `torch.linalg.det(t)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[[2.0, 1.0], [1.0, 3.0]], [[0.0, 1.0], [1.0, 0.0]]]);
+/// assert_eq!(t.det().array(), [5.0, -1.0]);
+/// ```
+///
+/// Returns [ShapeMismatch] if the last two axes are different sizes.
+pub fn det<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S::Batch, E, D, T> {
+    t.det()
+}
+
+/// The log of the absolute value of the [det()] of the square matrices in the last two axes,
+/// computed as the sum of the logs of the pivots of an LU decomposition, so it doesn't over
+/// or underflow. This is `-inf` for singular matrices.
+///
+/// The gradient is `t^-T`. For example, the log density of a
+/// [normalizing flow](https://arxiv.org/abs/1505.05770) `y = f(x)` has a
+/// `logdet` of the jacobian of `f`.
+///
+/// **Pytorch equivalent**: `torch.linalg.slogdet(t).logabsdet`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[0.0, 2.0], [-3.0, 0.0]]);
+/// let r = t.trace().logdet();
+/// assert_eq!(r.array(), 6f32.ln());
+/// let g = r.backward();
+/// assert_eq!(g.get(&t).array(), [[0.0, 0.5], [-1.0 / 3.0, 0.0]]);
+/// ```
+///
+/// Returns [ShapeMismatch] if the last two axes are different sizes.
+pub fn logdet<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S::Batch, E, D, T> {
+    t.logdet()
+}
+
+impl<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [det]
+    #[track_caller]
+    pub fn det(self) -> Tensor<S::Batch, E, D, T> {
+        self.try_det().unwrap()
+    }
+
+    /// See [det]
+    #[track_caller]
+    pub fn try_det(self) -> Result<Tensor<S::Batch, E, D, T>, D::Err> {
+        self.try_det_op("det", false)
+    }
+
+    /// See [logdet]
+    #[track_caller]
+    pub fn logdet(self) -> Tensor<S::Batch, E, D, T> {
+        self.try_logdet().unwrap()
+    }
+
+    /// See [logdet]
+    #[track_caller]
+    pub fn try_logdet(self) -> Result<Tensor<S::Batch, E, D, T>, D::Err> {
+        self.try_det_op("logdet", true)
+    }
+
+    #[track_caller]
+    fn try_det_op(
+        self,
+        name: &'static str,
+        log: bool,
+    ) -> Result<Tensor<S::Batch, E, D, T>, D::Err> {
+        let shape = *self.shape();
+        ShapeMismatch::check_axes(name, (&shape, S::NUM_DIMS - 2), (&shape, S::NUM_DIMS - 1))?;
+        let (batch, n, _) = batch_rows_cols(&shape);
+        let op = DetOp { batch, n, log };
+
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&shape.batch())?;
+        DetKernel::<E>::forward(&inp.device, op, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            let out = &phantom_out.storage;
+            DetKernel::<E>::backward(&inp.device, op, &inp.storage, out, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_det_known_values() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[2.0, -1.0, 0.0], [-1.0, 2.0, -1.0], [0.0, -1.0, 2.0]]);
+        assert_close(&t.clone().det().array(), &4.0);
+        assert_close(&t.logdet().array(), &4f32.ln());
+
+        // needs pivoting, & a row swap flips the sign
+        let t = dev.tensor([[0.0, 0.0, 1.0], [0.0, 2.0, 0.0], [3.0, 0.0, 0.0]]);
+        assert_close(&t.clone().det().array(), &-6.0);
+        assert_close(&t.logdet().array(), &6f32.ln());
+
+        let t = dev.tensor([[1.0, 2.0], [2.0, 4.0]]);
+        assert_eq!(t.clone().det().array(), 0.0);
+        assert_eq!(t.logdet().array(), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_det_gradients() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 2, 2>, f32, _> =
+            dev.tensor([[[1.0, 2.0], [3.0, 4.0]], [[2.0, 0.5], [1.0, 3.0]]]);
+        let w = dev.tensor([1.0, -2.0]);
+        let r = t.trace().det();
+        assert_close(&r.array(), &[-2.0, 5.5]);
+        let g = (r * w.clone()).sum().backward();
+        // the gradient of `ad - bc` is `[[d, -c], [-b, a]]`
+        assert_close(
+            &g.get(&t).array(),
+            &[[[4.0, -3.0], [-2.0, 1.0]], [[-6.0, 2.0], [1.0, -4.0]]],
+        );
+
+        let g = (t.trace().logdet() * w).sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [[-2.0, 1.5], [1.0, -0.5]],
+                [[-6.0 / 5.5, 2.0 / 5.5], [1.0 / 5.5, -4.0 / 5.5]],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_logdet_matches_sum_of_log_eigenvalues() {
+        let dev: TestDevice = Default::default();
+        let m: Tensor<Rank2<6, 6>, f32, _> = dev.sample_normal();
+        let a = m.clone().matmul(m.permute()) + dev.ones() * 0.1;
+        let (vals, _) = a.clone().symeig();
+        assert_close_with_tolerance(&a.logdet().array(), &vals.ln().sum().array(), 1e-4);
+
+        let t: Tensor<(usize, usize), f32, _> = dev.ones_like(&(3, 4));
+        assert!(t.try_det().is_err());
+    }
+}
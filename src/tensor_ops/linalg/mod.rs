@@ -0,0 +1,80 @@
+//! Linear algebra on batches of matrices, which are always the last two axes.
+//!
+//! These all run on the host in `f64`, even for `Cuda` tensors.
+
+mod cholesky;
+mod cpu_kernel;
+mod det;
+mod svd;
+mod symeig;
+
+pub use cholesky::cholesky;
+pub use det::{det, logdet};
+pub use svd::svd;
+pub use symeig::symeig;
+
+pub(crate) use cholesky::CholeskyKernel;
+pub(crate) use det::DetKernel;
+pub(crate) use svd::SvdKernel;
+pub(crate) use symeig::SymEigKernel;
+
+use crate::shapes::{Dim, Shape};
+
+/// Shapes `(..., M, N)` of batches of matrices, see [svd()], [symeig()] & [det()].
+pub trait MatrixShape: Shape {
+    /// Both matrix axes removed, `(...)`. This is the shape of the determinants.
+    type Batch: Shape;
+    /// The last axis removed, `(..., M)`. This is the shape of the eigenvalues of square
+    /// matrices.
+    type Eigenvalues: Shape;
+    /// `(..., K)`, the shape of the singular values.
+    type Singular: Shape;
+    /// `(..., M, K)`, the shape of the left singular vectors.
+    type Left: Shape;
+    /// `(..., K, N)`, the shape of the right singular vectors.
+    type Right: Shape;
+
+    fn batch(&self) -> Self::Batch;
+    fn eigenvalues(&self) -> Self::Eigenvalues;
+    fn singular(&self, k: usize) -> (Self::Left, Self::Singular, Self::Right);
+}
+
+macro_rules! matrix_shapes {
+    ([$($Vars:tt),*], [$($Idx:tt),*], $Rows:tt, $Cols:tt) => {
+        impl<$($Vars: Dim, )* M: Dim, N: Dim> MatrixShape for ($($Vars, )* M, N) {
+            type Batch = ($($Vars, )*);
+            type Eigenvalues = ($($Vars, )* M,);
+            type Singular = ($($Vars, )* usize,);
+            type Left = ($($Vars, )* M, usize);
+            type Right = ($($Vars, )* usize, N);
+
+            #[allow(clippy::unused_unit)]
+            fn batch(&self) -> Self::Batch {
+                ($(self.$Idx, )*)
+            }
+
+            fn eigenvalues(&self) -> Self::Eigenvalues {
+                ($(self.$Idx, )* self.$Rows,)
+            }
+
+            fn singular(&self, k: usize) -> (Self::Left, Self::Singular, Self::Right) {
+                (
+                    ($(self.$Idx, )* self.$Rows, k),
+                    ($(self.$Idx, )* k,),
+                    ($(self.$Idx, )* k, self.$Cols),
+                )
+            }
+        }
+    };
+}
+
+matrix_shapes!([], [], 0, 1);
+matrix_shapes!([A], [0], 1, 2);
+matrix_shapes!([A, B], [0, 1], 2, 3);
+
+/// The number of matrices in a batch of shape `shape`, and their rows & columns.
+fn batch_rows_cols<S: Shape>(shape: &S) -> (usize, usize, usize) {
+    let dims = shape.concrete();
+    let (m, n) = (dims[S::NUM_DIMS - 2], dims[S::NUM_DIMS - 1]);
+    (shape.num_elements() / (m * n).max(1), m, n)
+}
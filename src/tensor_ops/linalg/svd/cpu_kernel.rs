@@ -0,0 +1,177 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, LendingIterator},
+};
+
+use super::{
+    super::cpu_kernel::{jacobi_rotation, rotate_cols, to_vec},
+    SvdOp,
+};
+
+use std::{vec, vec::Vec};
+
+const MAX_SWEEPS: usize = 100;
+
+/// One sided Jacobi SVD of the row major `(rows, cols)` matrix `w`, with `cols <= rows`.
+/// Returns `(u, s, v)`, where `u` is `(rows, cols)`, `v` is `(cols, cols)`, and `s`
+/// is in descending order.
+fn svd_tall(rows: usize, cols: usize, mut w: Vec<f64>) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let mut v = vec![0.0; cols * cols];
+    for i in 0..cols {
+        v[i * cols + i] = 1.0;
+    }
+    let col_dot = |w: &[f64], p: usize, q: usize| -> f64 {
+        (0..rows).map(|r| w[r * cols + p] * w[r * cols + q]).sum()
+    };
+
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..cols {
+            for q in p + 1..cols {
+                let (alpha, beta) = (col_dot(&w, p, p), col_dot(&w, q, q));
+                let gamma = col_dot(&w, p, q);
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let (c, s) = jacobi_rotation(alpha, beta, gamma);
+                rotate_cols(&mut w, cols, p, q, c, s);
+                rotate_cols(&mut v, cols, p, q, c, s);
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let norms: Vec<f64> = (0..cols).map(|c| col_dot(&w, c, c).sqrt()).collect();
+    let mut order: Vec<usize> = (0..cols).collect();
+    order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+    let largest = norms.iter().copied().fold(0.0, f64::max);
+
+    let mut u = vec![0.0; rows * cols];
+    let mut s = vec![0.0; cols];
+    let mut v_sorted = vec![0.0; cols * cols];
+    for (c, &i) in order.iter().enumerate() {
+        for r in 0..cols {
+            v_sorted[r * cols + c] = v[r * cols + i];
+        }
+        if norms[i] > f64::EPSILON * rows as f64 * largest {
+            s[c] = norms[i];
+            for r in 0..rows {
+                u[r * cols + c] = w[r * cols + i] / norms[i];
+            }
+        }
+    }
+    complete_columns(rows, cols, &mut u, &s);
+    (u, s, v_sorted)
+}
+
+/// Fills the columns of `u` whose singular value is zero with unit vectors orthogonal
+/// to all the other columns, using Gram-Schmidt on the standard basis.
+fn complete_columns(rows: usize, cols: usize, u: &mut [f64], s: &[f64]) {
+    let mut basis = 0;
+    for c in (0..cols).filter(|&c| s[c] == 0.0) {
+        while basis < rows {
+            let mut x = vec![0.0; rows];
+            x[basis] = 1.0;
+            basis += 1;
+            for other in (0..cols).filter(|&o| o != c) {
+                let dot: f64 = (0..rows).map(|r| x[r] * u[r * cols + other]).sum();
+                for (r, x) in x.iter_mut().enumerate() {
+                    *x -= dot * u[r * cols + other];
+                }
+            }
+            let norm = x.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm > 1e-6 {
+                for (r, x) in x.iter().enumerate() {
+                    u[r * cols + c] = x / norm;
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the `(m, k)` u, `k` singular values, and `(k, n)` vt of the row major `a`.
+fn svd(op: &SvdOp, a: &[f64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let SvdOp { m, n, k, .. } = *op;
+    if m >= n {
+        let (u, s, v) = svd_tall(m, n, a.to_vec());
+        let vt = (0..k * n).map(|i| v[(i % n) * k + i / n]).collect();
+        (u, s, vt)
+    } else {
+        // a^T = w s v^T, so a = v s w^T
+        let at = (0..n * m).map(|i| a[(i % m) * n + i / m]).collect();
+        let (w, s, v) = svd_tall(n, m, at);
+        let vt = (0..k * n).map(|i| w[(i % n) * k + i / n]).collect();
+        (v, s, vt)
+    }
+}
+
+macro_rules! impl_svd {
+    ($Ty:ty) => {
+        impl super::SvdKernel<$Ty> for Cpu {
+            fn forward<I: Shape, L: Shape, V: Shape, R: Shape>(
+                &self,
+                op: SvdOp,
+                inp: &Self::Storage<I, $Ty>,
+                u: &mut Self::Storage<L, $Ty>,
+                s: &mut Self::Storage<V, $Ty>,
+                vt: &mut Self::Storage<R, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let inp = to_vec(inp);
+                let (mut all_u, mut all_s, mut all_vt) = (Vec::new(), Vec::new(), Vec::new());
+                for a in inp.chunks((op.m * op.n).max(1)).take(op.batch) {
+                    let (u, s, vt) = svd(&op, a);
+                    all_u.extend(u);
+                    all_s.extend(s);
+                    all_vt.extend(vt);
+                }
+                for (o, v) in u.buf_iter_mut().zip(all_u.into_iter()) {
+                    *o = v as $Ty;
+                }
+                for (o, v) in s.buf_iter_mut().zip(all_s.into_iter()) {
+                    *o = v as $Ty;
+                }
+                for (o, v) in vt.buf_iter_mut().zip(all_vt.into_iter()) {
+                    *o = v as $Ty;
+                }
+                Ok(())
+            }
+
+            fn backward<I: Shape, L: Shape, V: Shape, R: Shape>(
+                &self,
+                op: SvdOp,
+                u: &Self::Storage<L, $Ty>,
+                vt: &Self::Storage<R, $Ty>,
+                grad_inp: &mut Self::Storage<I, $Ty>,
+                grad_s: &Self::Storage<V, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let (u, vt, grad_s) = (to_vec(u), to_vec(vt), to_vec(grad_s));
+                let SvdOp { m, n, k, .. } = op;
+                let mut g: Vec<f64> = Vec::with_capacity(op.batch * m * n);
+                for ((u, vt), gs) in u
+                    .chunks((m * k).max(1))
+                    .zip(vt.chunks((k * n).max(1)))
+                    .zip(grad_s.chunks(k.max(1)))
+                {
+                    for i in 0..m {
+                        for j in 0..n {
+                            g.push((0..k).map(|l| u[i * k + l] * gs[l] * vt[l * n + j]).sum());
+                        }
+                    }
+                }
+                let mut g = g.into_iter();
+                let mut grad_inp = grad_inp.iter_mut();
+                while let Some(gi) = grad_inp.next() {
+                    *gi += g.next().unwrap() as $Ty;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_svd!(f32);
+impl_svd!(f64);
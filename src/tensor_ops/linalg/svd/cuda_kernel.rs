@@ -0,0 +1,46 @@
+use super::{SvdKernel, SvdOp};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the decomposition runs on the host.
+impl SvdKernel<f32> for Cuda {
+    fn forward<I: Shape, L: Shape, V: Shape, R: Shape>(
+        &self,
+        op: SvdOp,
+        inp: &Self::Storage<I, f32>,
+        u: &mut Self::Storage<L, f32>,
+        s: &mut Self::Storage<V, f32>,
+        vt: &mut Self::Storage<R, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_u = self.storage_to_cpu(u)?;
+        let mut cpu_s = self.storage_to_cpu(s)?;
+        let mut cpu_vt = self.storage_to_cpu(vt)?;
+        SvdKernel::<f32>::forward(&self.cpu, op, &cpu_inp, &mut cpu_u, &mut cpu_s, &mut cpu_vt)?;
+        self.storage_from_cpu(u, &cpu_u)?;
+        self.storage_from_cpu(s, &cpu_s)?;
+        self.storage_from_cpu(vt, &cpu_vt)
+    }
+
+    fn backward<I: Shape, L: Shape, V: Shape, R: Shape>(
+        &self,
+        op: SvdOp,
+        u: &Self::Storage<L, f32>,
+        vt: &Self::Storage<R, f32>,
+        grad_inp: &mut Self::Storage<I, f32>,
+        grad_s: &Self::Storage<V, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_u = self.storage_to_cpu(u)?;
+        let cpu_vt = self.storage_to_cpu(vt)?;
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_s = self.storage_to_cpu(grad_s)?;
+        SvdKernel::<f32>::backward(
+            &self.cpu,
+            op,
+            &cpu_u,
+            &cpu_vt,
+            &mut cpu_grad_inp,
+            &cpu_grad_s,
+        )?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
@@ -0,0 +1,233 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{batch_rows_cols, MatrixShape};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+    tensor_ops::Device,
+};
+
+/// The number & size of the matrices [SvdKernel] decomposes, where `k = min(m, n)`.
+#[derive(Debug, Copy, Clone)]
+pub struct SvdOp {
+    pub batch: usize,
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+}
+
+pub trait SvdKernel<E: Dtype>: DeviceStorage {
+    /// Writes the `(m, k)` left singular vectors of each matrix of `inp` to `u`, the
+    /// singular values in descending order to `s`, and the `(k, n)` transposed right
+    /// singular vectors to `vt`.
+    fn forward<I: Shape, L: Shape, V: Shape, R: Shape>(
+        &self,
+        op: SvdOp,
+        inp: &Self::Storage<I, E>,
+        u: &mut Self::Storage<L, E>,
+        s: &mut Self::Storage<V, E>,
+        vt: &mut Self::Storage<R, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds `u * diag(grad_s) * vt` to `grad_inp`.
+    fn backward<I: Shape, L: Shape, V: Shape, R: Shape>(
+        &self,
+        op: SvdOp,
+        u: &Self::Storage<L, E>,
+        vt: &Self::Storage<R, E>,
+        grad_inp: &mut Self::Storage<I, E>,
+        grad_s: &Self::Storage<V, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Reduced [singular value decomposition](https://en.wikipedia.org/wiki/Singular_value_decomposition)
+/// of the matrices in the last two axes. For `(..., M, N)` matrices, returns
+/// `(u, s, vt)` with shapes `(..., M, K)`, `(..., K)` & `(..., K, N)`, where
+/// `K = min(M, N)`, such that `t = u * diag(s) * vt`.
+///
+/// The singular values are in descending order. Only they are differentiable: `u` & `vt`
+/// are returned without a tape, since their gradient isn't defined when singular values
+/// repeat. The singular vectors of zero singular values are an arbitrary orthonormal
+/// completion.
+///
+/// Uses one sided Jacobi rotations, which is accurate but slow, so is meant for small
+/// matrices, like the weights of a layer for spectral norms.
+///
+/// **Pytorch equivalent**: `torch.linalg.svd(t, full_matrices=False)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[0.0, 2.0, 0.0], [3.0, 0.0, 0.0]]);
+/// let (u, s, vt) = t.svd();
+/// assert_eq!(u.shape(), &(Const::<2>, 2));
+/// assert_eq!(s.as_vec(), [3.0, 2.0]);
+/// assert_eq!(vt.shape(), &(2, Const::<3>));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn svd<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> (
+    Tensor<S::Left, E, D>,
+    Tensor<S::Singular, E, D, T>,
+    Tensor<S::Right, E, D>,
+) {
+    t.svd()
+}
+
+impl<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [svd]
+    #[allow(clippy::type_complexity)]
+    pub fn svd(
+        self,
+    ) -> (
+        Tensor<S::Left, E, D>,
+        Tensor<S::Singular, E, D, T>,
+        Tensor<S::Right, E, D>,
+    ) {
+        self.try_svd().unwrap()
+    }
+
+    /// See [svd]
+    #[allow(clippy::type_complexity)]
+    pub fn try_svd(
+        self,
+    ) -> Result<
+        (
+            Tensor<S::Left, E, D>,
+            Tensor<S::Singular, E, D, T>,
+            Tensor<S::Right, E, D>,
+        ),
+        D::Err,
+    > {
+        let shape = *self.shape();
+        let (batch, m, n) = batch_rows_cols(&shape);
+        let op = SvdOp {
+            batch,
+            m,
+            n,
+            k: m.min(n),
+        };
+        let (u_shape, s_shape, vt_shape) = shape.singular(op.k);
+
+        let (inp, mut tape) = self.split_tape();
+        let mut u = inp.device.try_zeros_like(&u_shape)?;
+        let mut s = inp.device.try_zeros_like(&s_shape)?;
+        let mut vt = inp.device.try_zeros_like(&vt_shape)?;
+        SvdKernel::<E>::forward(
+            &inp.device,
+            op,
+            &inp.storage,
+            &mut u.storage,
+            &mut s.storage,
+            &mut vt.storage,
+        )?;
+        let phantom_s = s.clone();
+        let (phantom_u, phantom_vt) = (u.clone(), vt.clone());
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&s)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_s) = grads.mut_and_ref(&inp, &phantom_s);
+            let (u, vt) = (&phantom_u.storage, &phantom_vt.storage);
+            SvdKernel::<E>::backward(&inp.device, op, u, vt, grad_inp, grad_s)
+        });
+        Ok((u, s.put_tape(tape), vt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn assert_decomposes(a: &[f32], m: usize, n: usize, u: &[f32], s: &[f32], vt: &[f32]) {
+        let k = m.min(n);
+        assert!(s.windows(2).all(|w| w[0] >= w[1]));
+        assert!(s.iter().all(|&s| s >= 0.0));
+        for i in 0..m {
+            for j in 0..n {
+                let recon: f32 = (0..k).map(|l| u[i * k + l] * s[l] * vt[l * n + j]).sum();
+                assert!(
+                    (recon - a[i * n + j]).abs() < 1e-4,
+                    "{recon} != {}",
+                    a[i * n + j]
+                );
+            }
+        }
+        for p in 0..k {
+            for q in 0..k {
+                let eye = if p == q { 1.0 } else { 0.0 };
+                let uu: f32 = (0..m).map(|i| u[i * k + p] * u[i * k + q]).sum();
+                assert!((uu - eye).abs() < 1e-5);
+                let vv: f32 = (0..n).map(|j| vt[p * n + j] * vt[q * n + j]).sum();
+                assert!((vv - eye).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_svd_rectangular() {
+        let dev: TestDevice = Default::default();
+
+        let a: Tensor<Rank2<5, 3>, f32, _> = dev.sample_normal();
+        let (u, s, vt) = a.clone().svd();
+        assert_eq!(u.shape(), &(Const, 3));
+        assert_eq!(vt.shape(), &(3, Const));
+        assert_decomposes(&a.as_vec(), 5, 3, &u.as_vec(), &s.as_vec(), &vt.as_vec());
+
+        let a: Tensor<Rank2<3, 5>, f32, _> = dev.sample_normal();
+        let (u, s, vt) = a.clone().svd();
+        assert_decomposes(&a.as_vec(), 3, 5, &u.as_vec(), &s.as_vec(), &vt.as_vec());
+    }
+
+    #[test]
+    fn test_svd_batched_and_rank_deficient() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 4, 4>, f32, _> = dev.sample_normal();
+        let (u, s, vt) = a.clone().svd();
+        assert_eq!(s.shape(), &(Const, 4));
+        let (a, u, s, vt) = (a.as_vec(), u.as_vec(), s.as_vec(), vt.as_vec());
+        for b in 0..2 {
+            let (a, u) = (&a[b * 16..][..16], &u[b * 16..][..16]);
+            let (s, vt) = (&s[b * 4..][..4], &vt[b * 16..][..16]);
+            assert_decomposes(a, 4, 4, u, s, vt);
+        }
+
+        // rank 1, so the other singular vectors are a completion
+        let a = dev.tensor([[1.0, 2.0, 2.0], [2.0, 4.0, 4.0], [0.0, 0.0, 0.0]]);
+        let (u, s, vt) = a.clone().svd();
+        let s = s.as_vec();
+        assert_close(&s, &std::vec![45f32.sqrt(), 0.0, 0.0]);
+        assert_decomposes(&a.as_vec(), 3, 3, &u.as_vec(), &s, &vt.as_vec());
+    }
+
+    #[test]
+    fn test_svd_gradient() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[3.0, 1.0], [1.0, 2.0], [0.0, 1.0]]);
+        let w = dev.tensor_from_vec(std::vec![1.0, -0.5], (2,));
+        let (_, s, _) = a.trace().svd();
+        let g = (s * w).sum().backward();
+        let g = g.get(&a).array();
+
+        // compare to finite differences
+        let eps = 1e-3;
+        let a = a.array();
+        for i in 0..3 {
+            for j in 0..2 {
+                let f = |d: f32| {
+                    let mut a = a;
+                    a[i][j] += d;
+                    let s = dev.tensor(a).svd().1.as_vec();
+                    s[0] * 1.0 - s[1] * 0.5
+                };
+                let fd = (f(eps) - f(-eps)) / (2.0 * eps);
+                assert!((fd - g[i][j]).abs() < 1e-2, "{fd} != {}", g[i][j]);
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, LendingIterator},
+};
+
+use super::{
+    super::cpu_kernel::{jacobi_rotation, rotate_cols, to_vec},
+    SymEigOp,
+};
+
+use std::{vec, vec::Vec};
+
+const MAX_SWEEPS: usize = 100;
+
+/// Cyclic Jacobi eigenvalue algorithm on the lower triangle of the row major `a`.
+/// Returns the eigenvalues in ascending order, and the eigenvectors as the columns
+/// of a row major matrix.
+fn symeig(n: usize, a: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let mut a: Vec<f64> = (0..n * n)
+        .map(|i| {
+            let (r, c) = (i / n, i % n);
+            a[r.max(c) * n + r.min(c)]
+        })
+        .collect();
+    let mut v = vec![0.0; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+
+    let norm: f64 = a.iter().map(|x| x * x).sum();
+    for _ in 0..MAX_SWEEPS {
+        let off: f64 = (0..n * n)
+            .filter(|i| i / n != i % n)
+            .map(|i| a[i] * a[i])
+            .sum();
+        if off <= f64::EPSILON * f64::EPSILON * norm {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                let (c, s) = jacobi_rotation(a[p * n + p], a[q * n + q], apq);
+                // A = J^T A J, and V = V J
+                rotate_cols(&mut a, n, p, q, c, s);
+                for k in 0..n {
+                    let (x, y) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * x - s * y;
+                    a[q * n + k] = s * x + c * y;
+                }
+                rotate_cols(&mut v, n, p, q, c, s);
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[i * n + i].total_cmp(&a[j * n + j]));
+    let vals = order.iter().map(|&i| a[i * n + i]).collect();
+    let mut vecs = vec![0.0; n * n];
+    for r in 0..n {
+        for (c, &i) in order.iter().enumerate() {
+            vecs[r * n + c] = v[r * n + i];
+        }
+    }
+    (vals, vecs)
+}
+
+macro_rules! impl_symeig {
+    ($Ty:ty) => {
+        impl super::SymEigKernel<$Ty> for Cpu {
+            fn forward<S: Shape, V: Shape>(
+                &self,
+                op: SymEigOp,
+                inp: &Self::Storage<S, $Ty>,
+                vals: &mut Self::Storage<V, $Ty>,
+                vecs: &mut Self::Storage<S, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let inp = to_vec(inp);
+                let (mut all_vals, mut all_vecs) = (Vec::new(), Vec::new());
+                for a in inp.chunks((op.n * op.n).max(1)).take(op.batch) {
+                    let (v, vs) = symeig(op.n, a);
+                    all_vals.extend(v);
+                    all_vecs.extend(vs);
+                }
+                for (o, v) in vals.buf_iter_mut().zip(all_vals.into_iter()) {
+                    *o = v as $Ty;
+                }
+                for (o, v) in vecs.buf_iter_mut().zip(all_vecs.into_iter()) {
+                    *o = v as $Ty;
+                }
+                Ok(())
+            }
+
+            fn backward<S: Shape, V: Shape>(
+                &self,
+                op: SymEigOp,
+                vecs: &Self::Storage<S, $Ty>,
+                grad_inp: &mut Self::Storage<S, $Ty>,
+                grad_vals: &Self::Storage<V, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let (vecs, grad_vals) = (to_vec(vecs), to_vec(grad_vals));
+                let n = op.n;
+                let mut g: Vec<f64> = Vec::with_capacity(vecs.len());
+                for (v, gv) in vecs.chunks((n * n).max(1)).zip(grad_vals.chunks(n.max(1))) {
+                    for i in 0..n {
+                        for j in 0..n {
+                            g.push((0..n).map(|k| v[i * n + k] * gv[k] * v[j * n + k]).sum());
+                        }
+                    }
+                }
+                let mut g = g.into_iter();
+                let mut grad_inp = grad_inp.iter_mut();
+                while let Some(gi) = grad_inp.next() {
+                    *gi += g.next().unwrap() as $Ty;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_symeig!(f32);
+impl_symeig!(f64);
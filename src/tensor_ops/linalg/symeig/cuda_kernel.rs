@@ -0,0 +1,34 @@
+use super::{SymEigKernel, SymEigOp};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+
+/// There are no cuda kernels yet, so the decomposition runs on the host.
+impl SymEigKernel<f32> for Cuda {
+    fn forward<S: Shape, V: Shape>(
+        &self,
+        op: SymEigOp,
+        inp: &Self::Storage<S, f32>,
+        vals: &mut Self::Storage<V, f32>,
+        vecs: &mut Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_vals = self.storage_to_cpu(vals)?;
+        let mut cpu_vecs = self.storage_to_cpu(vecs)?;
+        SymEigKernel::<f32>::forward(&self.cpu, op, &cpu_inp, &mut cpu_vals, &mut cpu_vecs)?;
+        self.storage_from_cpu(vals, &cpu_vals)?;
+        self.storage_from_cpu(vecs, &cpu_vecs)
+    }
+
+    fn backward<S: Shape, V: Shape>(
+        &self,
+        op: SymEigOp,
+        vecs: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_vals: &Self::Storage<V, f32>,
+    ) -> Result<(), Self::Err> {
+        let cpu_vecs = self.storage_to_cpu(vecs)?;
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_vals = self.storage_to_cpu(grad_vals)?;
+        SymEigKernel::<f32>::backward(&self.cpu, op, &cpu_vecs, &mut cpu_grad_inp, &cpu_grad_vals)?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
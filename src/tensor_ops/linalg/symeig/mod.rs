@@ -0,0 +1,183 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::{batch_rows_cols, MatrixShape};
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+    tensor_ops::Device,
+};
+
+/// The number & size of the matrices [SymEigKernel] decomposes.
+#[derive(Debug, Copy, Clone)]
+pub struct SymEigOp {
+    pub batch: usize,
+    pub n: usize,
+}
+
+pub trait SymEigKernel<E: Dtype>: DeviceStorage {
+    /// Writes the eigenvalues of each matrix of `inp` in ascending order to `vals`, and the
+    /// eigenvectors as the columns of `vecs`.
+    fn forward<S: Shape, V: Shape>(
+        &self,
+        op: SymEigOp,
+        inp: &Self::Storage<S, E>,
+        vals: &mut Self::Storage<V, E>,
+        vecs: &mut Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds `vecs * diag(grad_vals) * vecs^T` to `grad_inp`.
+    fn backward<S: Shape, V: Shape>(
+        &self,
+        op: SymEigOp,
+        vecs: &Self::Storage<S, E>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_vals: &Self::Storage<V, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Eigendecomposition of the symmetric matrices in the last two axes. Returns the eigenvalues
+/// in ascending order, and the eigenvectors as the columns of a matrix, so
+/// `t = vecs * diag(vals) * vecs^T`.
+///
+/// Only the lower triangle of `t` is read. Only the eigenvalues are differentiable: the
+/// eigenvectors are returned without a tape, since their gradient isn't defined when
+/// eigenvalues repeat. Like [super::cholesky()], the gradient is symmetric.
+///
+/// Uses the Jacobi eigenvalue algorithm, which is accurate but `O(n^3)` per sweep, so is
+/// meant for small matrices (up to a few hundred rows), like covariances for whitening.
+///
+/// **Pytorch equivalent**: `torch.linalg.eigh(t)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[2.0, 1.0], [1.0, 2.0]]);
+/// let (vals, vecs) = t.symeig();
+/// assert_eq!(vals.array(), [1.0, 3.0]);
+/// let v = vecs.array();
+/// assert!((v[0][0] + v[1][0]).abs() < 1e-6);
+/// assert!((v[0][1] - v[1][1]).abs() < 1e-6);
+/// ```
+///
+/// Returns [ShapeMismatch] if the last two axes are different sizes.
+#[allow(clippy::type_complexity)]
+pub fn symeig<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> (Tensor<S::Eigenvalues, E, D, T>, Tensor<S, E, D>) {
+    t.symeig()
+}
+
+impl<S: MatrixShape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [symeig]
+    #[track_caller]
+    #[allow(clippy::type_complexity)]
+    pub fn symeig(self) -> (Tensor<S::Eigenvalues, E, D, T>, Tensor<S, E, D>) {
+        self.try_symeig().unwrap()
+    }
+
+    /// See [symeig]
+    #[track_caller]
+    #[allow(clippy::type_complexity)]
+    pub fn try_symeig(self) -> Result<(Tensor<S::Eigenvalues, E, D, T>, Tensor<S, E, D>), D::Err> {
+        let shape = *self.shape();
+        ShapeMismatch::check_axes(
+            "symeig",
+            (&shape, S::NUM_DIMS - 2),
+            (&shape, S::NUM_DIMS - 1),
+        )?;
+        let (batch, n, _) = batch_rows_cols(&shape);
+        let op = SymEigOp { batch, n };
+
+        let (inp, mut tape) = self.split_tape();
+        let mut vals = inp.device.try_zeros_like(&shape.eigenvalues())?;
+        let mut vecs = inp.device.try_zeros_like(&shape)?;
+        SymEigKernel::<E>::forward(
+            &inp.device,
+            op,
+            &inp.storage,
+            &mut vals.storage,
+            &mut vecs.storage,
+        )?;
+        let phantom_vals = vals.clone();
+        let phantom_vecs = vecs.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&vals)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_vals) = grads.mut_and_ref(&inp, &phantom_vals);
+            let vecs = &phantom_vecs.storage;
+            SymEigKernel::<E>::backward(&inp.device, op, vecs, grad_inp, grad_vals)
+        });
+        Ok((vals.put_tape(tape), vecs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_symeig_reconstructs() {
+        let dev: TestDevice = Default::default();
+        let m: Tensor<Rank3<3, 5, 5>, f32, _> = dev.sample_normal();
+        let a = m.clone() + m.permute::<_, Axes3<0, 2, 1>>();
+        let (vals, vecs) = a.clone().symeig();
+        let (vals, vecs, a) = (vals.array(), vecs.array(), a.array());
+        for b in 0..3 {
+            assert!(vals[b].windows(2).all(|w| w[0] <= w[1]));
+            for i in 0..5 {
+                for j in 0..5 {
+                    let recon: f32 = (0..5)
+                        .map(|k| vecs[b][i][k] * vals[b][k] * vecs[b][j][k])
+                        .sum();
+                    assert!((recon - a[b][i][j]).abs() < 1e-4);
+                    let dot: f32 = (0..5).map(|k| vecs[b][k][i] * vecs[b][k][j]).sum();
+                    assert!((dot - if i == j { 1.0 } else { 0.0 }).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_symeig_gradient() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[2.0, 1.0, 0.0], [1.0, 3.0, 1.0], [0.0, 1.0, 4.0]]);
+        let w = dev.tensor([1.0, -2.0, 0.5]);
+        let (vals, vecs) = a.trace().symeig();
+        let g = (vals * w.clone()).sum().backward();
+
+        // the gradient of `w . vals` is `vecs * diag(w) * vecs^T`
+        let (v, w) = (vecs.array(), w.array());
+        let mut expected = [[0.0; 3]; 3];
+        for (i, row) in expected.iter_mut().enumerate() {
+            for (j, e) in row.iter_mut().enumerate() {
+                *e = (0..3).map(|k| v[i][k] * w[k] * v[j][k]).sum();
+            }
+        }
+        assert_close(&g.get(&a).array(), &expected);
+
+        // the sum of the eigenvalues is the trace
+        let (vals, _) = a.trace().symeig();
+        let g = vals.sum().backward();
+        assert_close(
+            &g.get(&a).array(),
+            &[[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        );
+    }
+
+    #[test]
+    fn test_symeig_runtime_shapes() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, usize), f32, _> = dev.ones_like(&(4, 4));
+        let (vals, _) = a.symeig();
+        assert_eq!(vals.shape(), &(4,));
+        assert_close(&vals.as_vec(), &std::vec![0.0, 0.0, 0.0, 4.0]);
+
+        let a: Tensor<(usize, usize), f32, _> = dev.ones_like(&(4, 3));
+        assert!(a.try_symeig().is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{
+    shapes::{Axes, ReduceShape, ReduceStridesTo, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+macro_rules! impl_log_softmax {
+    ($Ty:ty) => {
+        impl super::LogSoftmaxKernel<$Ty> for Cpu {
+            fn forward<S: Shape + ReduceShape<Ax>, Ax: Axes>(
+                &self,
+                inp: &Self::Storage<S, $Ty>,
+                out: &mut Self::Storage<S, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let shape = inp.shape;
+                let reduced: S::Reduced = shape.reduced();
+
+                let mut max: StridedArray<S::Reduced, $Ty> =
+                    StridedArray::try_new_with(reduced, <$Ty>::NEG_INFINITY)?;
+                let mut max_iter = max.iter_mut_as(&shape);
+                let mut inp_iter = inp.iter();
+                while let Some((m, x)) = max_iter.next().zip(inp_iter.next()) {
+                    *m = m.max(*x);
+                }
+
+                // `x - logsumexp = (x - max) - ln(sum(exp(x - max)))`, which doesn't lose
+                // the precision of `x - max` to rounding `logsumexp` when `max` is large
+                let mut log_sum: StridedArray<S::Reduced, $Ty> = StridedArray::new(reduced)?;
+                let mut sum_iter = log_sum.iter_mut_as(&shape);
+                let mut max_iter = max.iter_as(&shape);
+                let mut inp_iter = inp.iter();
+                while let Some(((s, m), x)) =
+                    sum_iter.next().zip(max_iter.next()).zip(inp_iter.next())
+                {
+                    *s += (x - m).exp();
+                }
+                for s in log_sum.buf_iter_mut() {
+                    *s = s.ln();
+                }
+
+                let mut out_iter = out.iter_mut();
+                let mut sum_iter = log_sum.iter_as(&shape);
+                let mut max_iter = max.iter_as(&shape);
+                let mut inp_iter = inp.iter();
+                while let Some((((o, s), m), x)) = out_iter
+                    .next()
+                    .zip(sum_iter.next())
+                    .zip(max_iter.next())
+                    .zip(inp_iter.next())
+                {
+                    *o = (x - m) - s;
+                }
+                Ok(())
+            }
+
+            fn backward<S: Shape + ReduceShape<Ax>, Ax: Axes>(
+                &self,
+                out: &Self::Storage<S, $Ty>,
+                grad_inp: &mut Self::Storage<S, $Ty>,
+                grad_out: &Self::Storage<S, $Ty>,
+            ) -> Result<(), Self::Err> {
+                let shape = out.shape;
+                let mut sum: StridedArray<S::Reduced, $Ty> =
+                    StridedArray::new(ReduceStridesTo::<_, Ax>::reduced(&shape))?;
+                let mut sum_iter = sum.iter_mut_as(&shape);
+                let mut go_iter = grad_out.iter();
+                while let Some((s, go)) = sum_iter.next().zip(go_iter.next()) {
+                    *s += *go;
+                }
+
+                let mut gi_iter = grad_inp.iter_mut();
+                let mut sum_iter = sum.iter_as(&shape);
+                let mut out_iter = out.iter();
+                let mut go_iter = grad_out.iter();
+                while let Some((((gi, s), o), go)) = gi_iter
+                    .next()
+                    .zip(sum_iter.next())
+                    .zip(out_iter.next())
+                    .zip(go_iter.next())
+                {
+                    *gi += go - o.exp() * s;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_log_softmax!(f32);
+impl_log_softmax!(f64);
@@ -0,0 +1,41 @@
+use super::LogSoftmaxKernel;
+use crate::{
+    shapes::{Axes, HasShape, ReduceShape, Shape},
+    tensor::{cuda::Cuda, DeviceStorage},
+    tensor_ops::{BroadcastTo, LogSumExpTo, SumTo, TryAdd, TryMul, TrySub},
+};
+
+/// There is no fused cuda kernel yet, so this is composed from other ops, which keeps
+/// everything on the device.
+impl LogSoftmaxKernel<f32> for Cuda {
+    fn forward<S: Shape + ReduceShape<Ax>, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<S, f32>,
+        out: &mut Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let inp = self.upgrade(inp.clone());
+        let logsumexp = inp
+            .clone()
+            .try_logsumexp::<S::Reduced, Ax>()?
+            .try_broadcast_like(inp.shape())?;
+        *out = inp.try_sub(logsumexp)?.storage;
+        Ok(())
+    }
+
+    fn backward<S: Shape + ReduceShape<Ax>, Ax: Axes>(
+        &self,
+        out: &Self::Storage<S, f32>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let out = self.upgrade(out.clone());
+        let grad_out = self.upgrade(grad_out.clone());
+        let sum = grad_out
+            .clone()
+            .try_sum::<S::Reduced, Ax>()?
+            .try_broadcast_like(out.shape())?;
+        let grad = grad_out.try_sub(out.try_exp()?.try_mul(sum)?)?;
+        *grad_inp = self.upgrade(grad_inp.clone()).try_add(grad)?.storage;
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use super::MaskedKernel;
+use crate::{
+    shapes::Shape,
+    tensor::{
+        cpu::LendingIterator,
+        cuda::{Cuda, CudaArray},
+        HasErr,
+    },
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/masked.ptx"));
+const MODULE_NAME: &str = "masked";
+const FILL_FWD_FN_NAME: &str = "masked_fill_forward";
+const FILL_BWD_FN_NAME: &str = "masked_fill_backward";
+const SELECT_FWD_FN_NAME: &str = "masked_select_forward";
+const SELECT_BWD_FN_NAME: &str = "masked_select_backward";
+const ALL_FN_NAMES: [&str; 4] = [
+    FILL_FWD_FN_NAME,
+    FILL_BWD_FN_NAME,
+    SELECT_FWD_FN_NAME,
+    SELECT_BWD_FN_NAME,
+];
+
+impl Cuda {
+    fn load_masked_module(&self) -> Result<(), <Self as HasErr>::Err> {
+        if !self.dev.has_func(MODULE_NAME, FILL_FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+        Ok(())
+    }
+
+    /// The row major indices of the elements where `mask` is true.
+    fn masked_positions<S: Shape>(
+        &self,
+        mask: &CudaArray<S, bool>,
+    ) -> Result<CudaSlice<usize>, <Self as HasErr>::Err> {
+        let cpu_mask = self.storage_to_cpu(mask)?;
+        let mut positions = Vec::new();
+        let mut mask_iter = cpu_mask.iter();
+        let mut i = 0;
+        while let Some(m) = mask_iter.next() {
+            if *m {
+                positions.push(i);
+            }
+            i += 1;
+        }
+        Ok(self.dev.take_async(positions)?)
+    }
+}
+
+impl MaskedKernel<f32> for Cuda {
+    fn fill_forward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        inp: &Self::Storage<S, f32>,
+        value: f32,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        self.load_masked_module()?;
+
+        let shape = inp.shape;
+        let strides = shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let mask_strides: CudaSlice<usize> = self.dev.take_async(mask.strides.into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FILL_FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,              // const size_t numel,
+            S::NUM_DIMS,        // const size_t num_dims,
+            &dims,              // const size_t *dims,
+            mask.data.as_ref(), // const bool *mask,
+            &mask_strides,      // const size_t *mask_strides,
+            inp.data.as_ref(),  // const float *inp,
+            &inp_strides,       // const size_t *inp_strides,
+            value,              // const float value,
+            &mut storage,       // float *out,
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn fill_backward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let cpu_mask = self.storage_to_cpu(mask)?;
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            MaskedKernel::<f32>::fill_backward(
+                &self.cpu,
+                &cpu_mask,
+                &mut cpu_grad_inp,
+                &cpu_grad_out,
+            )?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, FILL_BWD_FN_NAME).unwrap();
+        let numel = mask.shape.num_elements();
+
+        let dims: CudaSlice<usize> = self.dev.take_async(mask.shape.concrete().into())?;
+        let mask_strides: CudaSlice<usize> = self.dev.take_async(mask.strides.into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            S::NUM_DIMS,                       // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            mask.data.as_ref(),                // const bool *mask,
+            &mask_strides,                     // const size_t *mask_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn select_forward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        inp: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<(usize,), f32>, Self::Err> {
+        self.load_masked_module()?;
+
+        let positions = self.masked_positions(mask)?;
+        let shape = (positions.len(),);
+        let numel = shape.0;
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+        if numel == 0 {
+            return Ok(CudaArray {
+                data: Arc::new(storage),
+                shape,
+                strides: shape.strides(),
+            });
+        }
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, SELECT_FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,             // const size_t numel,
+            S::NUM_DIMS,       // const size_t num_dims,
+            &dims,             // const size_t *dims,
+            &positions,        // const size_t *positions,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut storage,      // float *out,
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides: shape.strides(),
+        })
+    }
+
+    fn select_backward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        grad_inp: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<(usize,), f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let cpu_mask = self.storage_to_cpu(mask)?;
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            MaskedKernel::<f32>::select_backward(
+                &self.cpu,
+                &cpu_mask,
+                &mut cpu_grad_inp,
+                &cpu_grad_out,
+            )?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let positions = self.masked_positions(mask)?;
+        let numel = positions.len();
+        if numel == 0 {
+            return Ok(());
+        }
+
+        let dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, SELECT_BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            S::NUM_DIMS,                       // const size_t num_dims,
+            &dims,                             // const size_t *dims,
+            &positions,                        // const size_t *positions,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
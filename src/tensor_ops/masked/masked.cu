@@ -0,0 +1,83 @@
+#include "cuda_utils.cuh"
+
+extern "C" __global__ void masked_fill_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const bool *mask,
+    const size_t *mask_strides,
+    const float *inp,
+    const size_t *inp_strides,
+    const float value,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(out_i, num_dims, dims, inp_strides);
+    unsigned int mask_i = get_strided_index(out_i, num_dims, dims, mask_strides);
+
+    out[out_i] = mask[mask_i] ? value : inp[inp_i];
+}
+
+extern "C" __global__ void masked_fill_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const bool *mask,
+    const size_t *mask_strides,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(out_i, num_dims, dims, inp_strides);
+    unsigned int mask_i = get_strided_index(out_i, num_dims, dims, mask_strides);
+
+    if (!mask[mask_i]) {
+        atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+    }
+}
+
+// `positions` are the row major indices of the selected elements
+extern "C" __global__ void masked_select_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *positions,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(positions[out_i], num_dims, dims, inp_strides);
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void masked_select_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const size_t *positions,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i = get_strided_index(positions[out_i], num_dims, dims, inp_strides);
+    atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+}
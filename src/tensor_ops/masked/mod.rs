@@ -0,0 +1,229 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait MaskedKernel<E: Dtype>: DeviceStorage {
+    /// Copies `inp`, with `value` everywhere `mask` is true.
+    fn fill_forward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        inp: &Self::Storage<S, E>,
+        value: E,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    /// Adds `grad_out` to `grad_inp` everywhere `mask` is false.
+    fn fill_backward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// The values of `inp` where `mask` is true, in row major order.
+    fn select_forward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<(usize,), E>, Self::Err>;
+
+    /// Adds `grad_out` back to the positions of `grad_inp` where `mask` is true.
+    fn select_backward<S: Shape>(
+        &self,
+        mask: &Self::Storage<S, bool>,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<(usize,), E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Replaces the values of `t` with `value` wherever `mask` is true.
+///
+/// This is the same as `mask.choose(value, t)`, without having to allocate a tensor full of `value`,
+/// which makes it useful for masking attention scores. The gradient only flows
+/// to the positions that weren't filled.
+///
+/// **Pytorch equivalent**: `t.masked_fill(mask, value)`
+///
+/// Returns [ShapeMismatch] if `mask` and `t` have different shapes.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+/// let mask = dev.tensor([[false, true], [false, false]]);
+/// let r = t.masked_fill(mask, f32::NEG_INFINITY);
+/// assert_eq!(r.array(), [[1.0, f32::NEG_INFINITY], [3.0, 4.0]]);
+/// ```
+#[track_caller]
+pub fn masked_fill<S: Shape, E: Dtype, D: MaskedKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    mask: Tensor<S, bool, D>,
+    value: E,
+) -> Tensor<S, E, D, T> {
+    t.masked_fill(mask, value)
+}
+
+/// Selects the values of `t` where `mask` is true, and flattens them into a 1d tensor in row major order.
+/// The gradient is added back to the selected positions.
+///
+/// **Pytorch equivalent**: `t.masked_select(mask)`
+///
+/// Returns [ShapeMismatch] if `mask` and `t` have different shapes.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+/// let mask = dev.tensor([[false, true], [true, true]]);
+/// let r: Tensor<(usize,), f32, _> = t.masked_select(mask);
+/// assert_eq!(r.as_vec(), [2.0, 3.0, 4.0]);
+/// ```
+#[track_caller]
+pub fn masked_select<S: Shape, E: Dtype, D: MaskedKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    mask: Tensor<S, bool, D>,
+) -> Tensor<(usize,), E, D, T> {
+    t.masked_select(mask)
+}
+
+impl<S: Shape, E: Dtype, D: MaskedKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [masked_fill]
+    #[track_caller]
+    pub fn masked_fill(self, mask: Tensor<S, bool, D>, value: E) -> Self {
+        self.try_masked_fill(mask, value).unwrap()
+    }
+
+    /// See [masked_fill]
+    #[track_caller]
+    pub fn try_masked_fill(self, mask: Tensor<S, bool, D>, value: E) -> Result<Self, D::Err> {
+        DeviceMismatch::check_same("masked_fill", &self.device, &mask.device)?;
+        ShapeMismatch::check_same("masked_fill", &self, &mask)?;
+
+        let (inp, mut tape) = self.split_tape();
+        let storage = inp
+            .device
+            .fill_forward(&mask.storage, &inp.storage, value)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.fill_backward(&mask.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+
+    /// See [masked_select]
+    #[track_caller]
+    pub fn masked_select(self, mask: Tensor<S, bool, D>) -> Tensor<(usize,), E, D, T> {
+        self.try_masked_select(mask).unwrap()
+    }
+
+    /// See [masked_select]
+    #[track_caller]
+    pub fn try_masked_select(
+        self,
+        mask: Tensor<S, bool, D>,
+    ) -> Result<Tensor<(usize,), E, D, T>, D::Err> {
+        DeviceMismatch::check_same("masked_select", &self.device, &mask.device)?;
+        ShapeMismatch::check_same("masked_select", &self, &mask)?;
+
+        let (inp, mut tape) = self.split_tape();
+        let storage = inp.device.select_forward(&mask.storage, &inp.storage)?;
+        let out = inp.device.upgrade(storage);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .select_backward(&mask.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_masked_fill_matches_choose() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let mask = dev.tensor([
+            [true, false, false, true],
+            [false, false, false, false],
+            [true, true, false, true],
+        ]);
+        let r = t.trace().masked_fill(mask.clone(), -5.0);
+        let e = bool_not(&mask).choose(t.trace(), dev.ones() * -5.0);
+        assert_eq!(r.array(), e.array());
+
+        let r_g = r.exp().sum().backward();
+        let e_g = e.exp().sum().backward();
+        assert_eq!(r_g.get(&t).array(), e_g.get(&t).array());
+    }
+
+    #[test]
+    fn test_masked_fill_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let mask = dev.tensor([[true, false, false], [true, true, false]]);
+        let r = t
+            .trace()
+            .broadcast::<Rank2<2, 3>, _>()
+            .masked_fill(mask, 0.0);
+        assert_eq!(r.array(), [[0.0, 2.0, 3.0], [0.0, 0.0, 3.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [0.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_masked_select() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let mask = dev.tensor([[false, true, true], [true, false, true]]);
+        let r = t.trace().masked_select(mask);
+        assert_eq!(r.shape(), &(4,));
+        assert_eq!(r.as_vec(), [2.0, 3.0, 4.0, 6.0]);
+        let g = (r * dev.tensor_from_vec(std::vec![1.0, 2.0, 3.0, 4.0], (4,)))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[0.0, 1.0, 2.0], [3.0, 0.0, 4.0]]);
+    }
+
+    #[test]
+    fn test_masked_select_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let mask = dev.tensor([[true, true], [false, true]]);
+        let r = t.permute::<_, Axes2<1, 0>>().masked_select(mask);
+        assert_eq!(r.as_vec(), [1.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_masked_select_none() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, f32, _> = dev.ones();
+        let r = t.masked_select(dev.tensor([false; 3]));
+        assert_eq!(r.shape(), &(0,));
+    }
+
+    #[test]
+    fn test_masked_fill_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize,), f32, _> = dev.zeros_like(&(3,));
+        let mask: Tensor<(usize,), bool, _> = dev.zeros_like(&(4,));
+        assert!(t.try_masked_fill(mask, 1.0).is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::shapes::*;
 use crate::tensor::cpu::{Cpu, StridedArray, View, ViewMut};
+use crate::tensor::{CpuGemm, GemmArgs};
 
 #[cfg(feature = "cblas")]
 use cblas_sys::{
@@ -46,6 +47,32 @@ pub(crate) fn matmul<M: Dim, K: Dim, N: Dim>(
     }
 }
 
+impl Cpu {
+    /// [matmul()], or the [CpuGemm] override of this device if there is one.
+    #[inline]
+    pub(crate) fn gemm<M: Dim, K: Dim, N: Dim>(
+        &self,
+        a: View<(M, K), f32>,
+        b: View<(K, N), f32>,
+        c: &mut ViewMut<(M, N), f32>,
+    ) {
+        match self.overrides.get::<CpuGemm<f32>>() {
+            Some(kernel) => kernel(GemmArgs {
+                m: a.shape.0.size(),
+                k: a.shape.1.size(),
+                n: b.shape.1.size(),
+                a: a.data,
+                a_strides: a.strides,
+                b: b.data,
+                b_strides: b.strides,
+                c: c.data,
+                c_strides: c.strides,
+            }),
+            None => matmul(a, b, c),
+        }
+    }
+}
+
 impl super::VecVecKernel<f32> for Cpu {
     fn forward<M: Dim, N: Dim>(
         &self,
@@ -53,7 +80,7 @@ impl super::VecVecKernel<f32> for Cpu {
         rhs: &Self::Storage<(N,), f32>,
     ) -> Result<Self::Storage<(M, N), f32>, Self::Err> {
         let mut out = StridedArray::new((lhs.shape().0, rhs.shape().0))?;
-        matmul(lhs.view().br1(), rhs.view().br0(), &mut out.view_mut());
+        self.gemm(lhs.view().br1(), rhs.view().br0(), &mut out.view_mut());
         Ok(out)
     }
     fn backward<M: Dim, N: Dim>(
@@ -67,8 +94,8 @@ impl super::VecVecKernel<f32> for Cpu {
         let grad_out = grad_out.view();
         let lhs = lhs.view().br1().tr();
         let rhs = rhs.view().br0().tr();
-        matmul(grad_out, rhs, &mut grad_lhs.view_mut().br1());
-        matmul(lhs, grad_out, &mut grad_rhs.view_mut().br0());
+        self.gemm(grad_out, rhs, &mut grad_lhs.view_mut().br1());
+        self.gemm(lhs, grad_out, &mut grad_rhs.view_mut().br0());
         Ok(())
     }
 }
@@ -80,7 +107,7 @@ impl super::VecMatKernel<f32> for Cpu {
         rhs: &Self::Storage<(Const<K>, N), f32>,
     ) -> Result<Self::Storage<(N,), f32>, Self::Err> {
         let mut out = StridedArray::new((rhs.shape.1,))?;
-        matmul(lhs.view().br0(), rhs.view(), &mut out.view_mut().br0());
+        self.gemm(lhs.view().br0(), rhs.view(), &mut out.view_mut().br0());
         Ok(out)
     }
     fn backward<const K: usize, N: Dim>(
@@ -92,33 +119,33 @@ impl super::VecMatKernel<f32> for Cpu {
         grad_out: &Self::Storage<(N,), f32>,
     ) -> Result<(), Self::Err> {
         let grad_out = grad_out.view().br0();
-        matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut().br0());
-        matmul(lhs.view().br0().tr(), grad_out, &mut grad_rhs.view_mut());
+        self.gemm(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut().br0());
+        self.gemm(lhs.view().br0().tr(), grad_out, &mut grad_rhs.view_mut());
         Ok(())
     }
 }
 
 impl super::MatMatKernel<f32> for Cpu {
-    fn forward<M: Dim, const K: usize, N: Dim>(
+    fn forward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), f32>,
-        rhs: &Self::Storage<(Const<K>, N), f32>,
+        lhs: &Self::Storage<(M, K), f32>,
+        rhs: &Self::Storage<(K, N), f32>,
     ) -> Result<Self::Storage<(M, N), f32>, Self::Err> {
         let mut out = StridedArray::new((lhs.shape.0, rhs.shape.1))?;
-        matmul(lhs.view(), rhs.view(), &mut out.view_mut());
+        self.gemm(lhs.view(), rhs.view(), &mut out.view_mut());
         Ok(out)
     }
-    fn backward<M: Dim, const K: usize, N: Dim>(
+    fn backward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), f32>,
-        grad_lhs: &mut Self::Storage<(M, Const<K>), f32>,
-        rhs: &Self::Storage<(Const<K>, N), f32>,
-        grad_rhs: &mut Self::Storage<(Const<K>, N), f32>,
+        lhs: &Self::Storage<(M, K), f32>,
+        grad_lhs: &mut Self::Storage<(M, K), f32>,
+        rhs: &Self::Storage<(K, N), f32>,
+        grad_rhs: &mut Self::Storage<(K, N), f32>,
         grad_out: &Self::Storage<(M, N), f32>,
     ) -> Result<(), Self::Err> {
         let grad_out = grad_out.view();
-        matmul(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut());
-        matmul(lhs.view().tr(), grad_out, &mut grad_rhs.view_mut());
+        self.gemm(grad_out, rhs.view().tr(), &mut grad_lhs.view_mut());
+        self.gemm(lhs.view().tr(), grad_out, &mut grad_rhs.view_mut());
         Ok(())
     }
 }
@@ -136,7 +163,7 @@ impl super::MatMatBrKernel<f32> for Cpu {
         let b = rhs.view();
         let mut c = out.view_mut();
         for batch in 0..batch.size() {
-            matmul(a.idx(batch), b, &mut c.idx_mut(batch));
+            self.gemm(a.idx(batch), b, &mut c.idx_mut(batch));
         }
         Ok(out)
     }
@@ -156,8 +183,8 @@ impl super::MatMatBrKernel<f32> for Cpu {
         let grad_out = grad_out.view();
         for b in 0..batch_size {
             let go = grad_out.idx(b);
-            matmul(go, rhs, &mut grad_lhs.idx_mut(b));
-            matmul(lhs.idx(b).tr(), go, &mut grad_rhs);
+            self.gemm(go, rhs, &mut grad_lhs.idx_mut(b));
+            self.gemm(lhs.idx(b).tr(), go, &mut grad_rhs);
         }
         Ok(())
     }
@@ -176,7 +203,7 @@ impl super::MatMatBatch3Kernel<f32> for Cpu {
         let b = rhs.view();
         let mut c = out.view_mut();
         for batch in 0..B {
-            matmul(a.idx(batch), b.idx(batch), &mut c.idx_mut(batch));
+            self.gemm(a.idx(batch), b.idx(batch), &mut c.idx_mut(batch));
         }
         Ok(out)
     }
@@ -195,8 +222,8 @@ impl super::MatMatBatch3Kernel<f32> for Cpu {
         let grad_out = grad_out.view();
         for b in 0..B {
             let go = grad_out.idx(b);
-            matmul(go, rhs.idx(b).tr(), &mut grad_lhs.idx_mut(b));
-            matmul(lhs.idx(b).tr(), go, &mut grad_rhs.idx_mut(b));
+            self.gemm(go, rhs.idx(b).tr(), &mut grad_lhs.idx_mut(b));
+            self.gemm(lhs.idx(b).tr(), go, &mut grad_rhs.idx_mut(b));
         }
         Ok(())
     }
@@ -219,7 +246,7 @@ impl super::MatMatBatch4Kernel<f32> for Cpu {
             let r_b = rhs.idx(b);
             let mut o_b = out_view.idx_mut(b);
             for s in 0..S {
-                matmul(l_b.idx(s), r_b.idx(s), &mut o_b.idx_mut(s));
+                self.gemm(l_b.idx(s), r_b.idx(s), &mut o_b.idx_mut(s));
             }
         }
         Ok(out)
@@ -244,8 +271,8 @@ impl super::MatMatBatch4Kernel<f32> for Cpu {
             let mut gr_b = grad_rhs.idx_mut(b);
             let go_b = grad_out.idx(b);
             for s in 0..S {
-                matmul(go_b.idx(s), r_b.idx(s).tr(), &mut gl_b.idx_mut(s));
-                matmul(l_b.idx(s).tr(), go_b.idx(s), &mut gr_b.idx_mut(s));
+                self.gemm(go_b.idx(s), r_b.idx(s).tr(), &mut gl_b.idx_mut(s));
+                self.gemm(l_b.idx(s).tr(), go_b.idx(s), &mut gr_b.idx_mut(s));
             }
         }
         Ok(())
@@ -303,10 +303,10 @@ impl super::VecMatKernel<f32> for Cuda {
 }
 
 impl super::MatMatKernel<f32> for Cuda {
-    fn forward<M: Dim, const K: usize, N: Dim>(
+    fn forward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), f32>,
-        rhs: &Self::Storage<(Const<K>, N), f32>,
+        lhs: &Self::Storage<(M, K), f32>,
+        rhs: &Self::Storage<(K, N), f32>,
     ) -> Result<Self::Storage<(M, N), f32>, Self::Err> {
         let (m, _) = lhs.shape;
         let (k, n) = rhs.shape;
@@ -335,12 +335,12 @@ impl super::MatMatKernel<f32> for Cuda {
         })
     }
 
-    fn backward<M: Dim, const K: usize, N: Dim>(
+    fn backward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), f32>,
-        grad_lhs: &mut Self::Storage<(M, Const<K>), f32>,
-        rhs: &Self::Storage<(Const<K>, N), f32>,
-        grad_rhs: &mut Self::Storage<(Const<K>, N), f32>,
+        lhs: &Self::Storage<(M, K), f32>,
+        grad_lhs: &mut Self::Storage<(M, K), f32>,
+        rhs: &Self::Storage<(K, N), f32>,
+        grad_rhs: &mut Self::Storage<(K, N), f32>,
         grad_out: &Self::Storage<(M, N), f32>,
     ) -> Result<(), Self::Err> {
         let (m, _) = lhs.shape;
@@ -7,8 +7,8 @@ pub(super) mod cuda_kernel;
 
 use crate::{
     gradients::{Merge, Tape},
-    shapes::{Const, Dim, Dtype, Shape},
-    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+    shapes::{Const, Dim, Dtype, Shape, ShapeMismatch},
+    tensor::{DeviceMismatch, DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
 };
 
 /// Matrix * Matrix, Vector * Matrix, Vector * Vector, and broadcasted/batched versions.
@@ -58,6 +58,16 @@ use crate::{
 /// let y: Tensor<Rank2<2, 4>, f32, _> = dev.zeros();
 /// let _: Tensor<Rank3<10, 3, 4>, f32, _> = x.matmul(y);
 /// ```
+///
+/// 6. Runtime sized matmul
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(3, 2));
+/// let y: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 4));
+/// let _: Tensor<(usize, usize), f32, _> = x.matmul(y);
+/// ```
+#[track_caller]
 pub fn matmul<Lhs, Rhs>(lhs: Lhs, rhs: Rhs) -> Lhs::Output
 where
     Lhs: TryMatMul<Rhs>,
@@ -68,6 +78,7 @@ where
 /// Fallible matrix multiplication. See [matmul] for examples.
 pub trait TryMatMul<Rhs>: HasErr {
     type Output;
+    #[track_caller]
     fn matmul(self, rhs: Rhs) -> Self::Output {
         self.try_matmul(rhs).unwrap()
     }
@@ -75,6 +86,7 @@ pub trait TryMatMul<Rhs>: HasErr {
 }
 
 #[rustfmt::skip]
+#[track_caller]
 fn try_binary_op<
     Lhs: Shape,
     Rhs: Shape,
@@ -84,13 +96,14 @@ fn try_binary_op<
     RhsTape: Tape<D>,
     LhsTape: Tape<D> + Merge<RhsTape>,
     Fwd: 'static + FnMut(&D, &D::Storage<Lhs, E>, &D::Storage<Rhs, E>) -> Result<D::Storage<Out, E>, D::Err>,
-    Bwd: 'static + FnMut(&D, &D::Storage<Lhs, E>, &mut D::Storage<Lhs, E>, &D::Storage<Rhs, E>, &mut D::Storage<Rhs, E>, &D::Storage<Out, E>) -> Result<(), D::Err>,
+    Bwd: 'static + Send + FnMut(&D, &D::Storage<Lhs, E>, &mut D::Storage<Lhs, E>, &D::Storage<Rhs, E>, &mut D::Storage<Rhs, E>, &D::Storage<Out, E>) -> Result<(), D::Err>,
 >(
     lhs: Tensor<Lhs, E, D, LhsTape>,
     rhs: Tensor<Rhs, E, D, RhsTape>,
     mut fwd: Fwd,
     mut bwd: Bwd,
 ) -> Result<Tensor<Out, E, D, LhsTape>, D::Err> {
+    DeviceMismatch::check_same("matmul", &lhs.device, &rhs.device)?;
     let (lhs, ltape) = lhs.split_tape();
     let (rhs, rtape) = rhs.split_tape();
     let mut tape = ltape.merge(rtape);
@@ -127,6 +140,7 @@ impl<M: Dim, N: Dim, E: Dtype, D: VecVecKernel<E>, T: Tape<D> + Merge<R>, R: Tap
     TryMatMul<Tensor<(N,), E, D, R>> for Tensor<(M,), E, D, T>
 {
     type Output = Tensor<(M, N), E, D, T>;
+    #[track_caller]
     fn try_matmul(self, rhs: Tensor<(N,), E, D, R>) -> Result<Self::Output, Self::Err> {
         try_binary_op(self, rhs, D::forward, D::backward)
     }
@@ -153,36 +167,39 @@ impl<const K: usize, N: Dim, E: Dtype, D: VecMatKernel<E>, T: Tape<D> + Merge<R>
     TryMatMul<Tensor<(Const<K>, N), E, D, R>> for Tensor<(Const<K>,), E, D, T>
 {
     type Output = Tensor<(N,), E, D, T>;
+    #[track_caller]
     fn try_matmul(self, rhs: Tensor<(Const<K>, N), E, D, R>) -> Result<Self::Output, Self::Err> {
         try_binary_op(self, rhs, D::forward, D::backward)
     }
 }
 
 pub trait MatMatKernel<E: Dtype>: DeviceStorage {
-    fn forward<M: Dim, const K: usize, N: Dim>(
+    fn forward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), E>,
-        rhs: &Self::Storage<(Const<K>, N), E>,
+        lhs: &Self::Storage<(M, K), E>,
+        rhs: &Self::Storage<(K, N), E>,
     ) -> Result<Self::Storage<(M, N), E>, Self::Err>;
 
-    fn backward<M: Dim, const K: usize, N: Dim>(
+    fn backward<M: Dim, K: Dim, N: Dim>(
         &self,
-        lhs: &Self::Storage<(M, Const<K>), E>,
-        grad_lhs: &mut Self::Storage<(M, Const<K>), E>,
-        rhs: &Self::Storage<(Const<K>, N), E>,
-        grad_rhs: &mut Self::Storage<(Const<K>, N), E>,
+        lhs: &Self::Storage<(M, K), E>,
+        grad_lhs: &mut Self::Storage<(M, K), E>,
+        rhs: &Self::Storage<(K, N), E>,
+        grad_rhs: &mut Self::Storage<(K, N), E>,
         grad_out: &Self::Storage<(M, N), E>,
     ) -> Result<(), Self::Err>;
 }
 
-impl<M: Dim, const K: usize, N: Dim, E: Dtype, D: MatMatKernel<E>, T, R>
-    TryMatMul<Tensor<(Const<K>, N), E, D, R>> for Tensor<(M, Const<K>), E, D, T>
+impl<M: Dim, K: Dim, N: Dim, E: Dtype, D: MatMatKernel<E>, T, R> TryMatMul<Tensor<(K, N), E, D, R>>
+    for Tensor<(M, K), E, D, T>
 where
     T: Tape<D> + Merge<R>,
     R: Tape<D>,
 {
     type Output = Tensor<(M, N), E, D, T>;
-    fn try_matmul(self, rhs: Tensor<(Const<K>, N), E, D, R>) -> Result<Self::Output, Self::Err> {
+    #[track_caller]
+    fn try_matmul(self, rhs: Tensor<(K, N), E, D, R>) -> Result<Self::Output, Self::Err> {
+        ShapeMismatch::check_axes("matmul", (&self, 1), (&rhs, 0))?;
         try_binary_op(self, rhs, D::forward, D::backward)
     }
 }
@@ -211,6 +228,7 @@ where
     R: Tape<D>,
 {
     type Output = Tensor<(B, M, N), E, D, T>;
+    #[track_caller]
     fn try_matmul(self, rhs: Tensor<(Const<K>, N), E, D, R>) -> Result<Self::Output, Self::Err> {
         try_binary_op(self, rhs, D::forward, D::backward)
     }
@@ -241,6 +259,7 @@ where
     R: Tape<D>,
 {
     type Output = Tensor<(Const<B>, M, N), E, D, T>;
+    #[track_caller]
     fn try_matmul(
         self,
         rhs: Tensor<(Const<B>, Const<K>, N), E, D, R>,
@@ -275,6 +294,7 @@ where
     R: Tape<D>,
 {
     type Output = Tensor<(Const<B>, Const<S>, M, N), E, D, T>;
+    #[track_caller]
     fn try_matmul(
         self,
         rhs: Tensor<(Const<B>, Const<S>, Const<K>, N), E, D, R>,
@@ -394,6 +414,44 @@ mod tests {
         assert_close(&g1.get(&b).array(), &g2.get(&b).array());
     }
 
+    #[test]
+    fn test_matmul_runtime_inner_dim() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+        let b: Tensor<Rank2<3, 2>, f32, _> = dev.sample_normal();
+        let c = a.trace().matmul(b.trace());
+        let g1 = c.exp().mean().backward();
+
+        let mut a2: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(4, 3));
+        a2.copy_from(&a.as_vec());
+        let mut b2: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(3, 2));
+        b2.copy_from(&b.as_vec());
+        let c2 = a2.trace().matmul(b2.trace());
+        assert_eq!(c2.shape(), &(4, 2));
+        let g2 = c2.exp().mean().backward();
+
+        assert_eq!(g1.get(&a).as_vec(), g2.get(&a2).as_vec());
+        assert_eq!(g1.get(&b).as_vec(), g2.get(&b2).as_vec());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_matmul_runtime_inner_dim_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(4, 3));
+        let b: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 2));
+        let _ = a.matmul(b);
+    }
+
+    #[test]
+    fn test_try_matmul_runtime_inner_dim_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(4, 3));
+        let b: Tensor<(usize, usize), f32, _> = dev.zeros_like(&(2, 2));
+        let err = a.try_matmul(b).unwrap_err();
+        assert!(std::format!("{err}").contains("matmul"));
+    }
+
     #[test]
     fn test_matmul_broadcast() {
         const N: usize = 5;
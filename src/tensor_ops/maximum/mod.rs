@@ -12,6 +12,12 @@ pub struct MaximumKernelOp;
 
 /// Element wise maximum.
 ///
+/// The gradient goes to whichever of `lhs` and `rhs` is larger. Where they are equal, it is
+/// split evenly between them (a subgradient), so neither input stops learning.
+///
+/// To take the maximum with a tensor of a different shape, [crate::tensor_ops::BroadcastTo::broadcast()] it first.
+/// The gradient of a broadcasted tensor is summed along the broadcasted axes.
+///
 /// **Pytorch equivalent**: `torch.maximum(a, b)`
 ///
 /// Example:
@@ -22,6 +28,17 @@ pub struct MaximumKernelOp;
 /// let b = dev.tensor([[1.0, 0.5, 1.0], [-2.0, 2.0, -3.5]]);
 /// let r = a.maximum(b);
 /// assert_eq!(r.array(), [[1.0, 2.0, 3.0], [-1.0, 2.0, -3.0]]);
+/// ```
+///
+/// Broadcasting a row to every row:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([[1.0, 2.0, 0.0], [-1.0, 0.0, 3.0]]);
+/// let r = a.maximum(dev.tensor([0.5; 3]).broadcast());
+/// assert_eq!(r.array(), [[1.0, 2.0, 0.5], [0.5, 0.5, 3.0]]);
+/// ```
+#[track_caller]
 pub fn maximum<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D> + Merge<RTape>, RTape: Tape<D>>(
     lhs: Tensor<S, E, D, LTape>,
     rhs: Tensor<S, E, D, RTape>,
@@ -31,6 +48,7 @@ pub fn maximum<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D> + Merge<RTape>,
 
 impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
     /// See [maximum]
+    #[track_caller]
     pub fn maximum<RTape: Tape<D>>(self, rhs: Tensor<S, E, D, RTape>) -> Self
     where
         LTape: Merge<RTape>,
@@ -39,11 +57,12 @@ impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
     }
 
     /// See [maximum]
+    #[track_caller]
     pub fn try_maximum<R: Tape<D>>(self, rhs: Tensor<S, E, D, R>) -> Result<Self, D::Err>
     where
         LTape: Merge<R>,
     {
-        try_binary_op(MaximumKernelOp, self, rhs)
+        try_binary_op("maximum", MaximumKernelOp, self, rhs)
     }
 }
 
@@ -64,4 +83,17 @@ mod tests {
         assert_eq!(g.get(&a).array(), [[0.0, 0.5, 1.0], [0.5, 1.0, 0.0]]);
         assert_eq!(g.get(&b).array(), [[1.0, 0.5, 0.0], [0.5, 0.0, 1.0]]);
     }
+
+    #[test]
+    fn test_maximum_broadcast() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, -1.0, 2.0], [0.0, 3.0, -2.0]]);
+        let b = dev.tensor([0.0, 0.0, 2.0]);
+        let r = a.trace().maximum(b.trace().broadcast());
+        assert_eq!(r.array(), [[1.0, 0.0, 2.0], [0.0, 3.0, 2.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[1.0, 0.0, 0.5], [0.5, 1.0, 0.0]]);
+        // summed over the rows it was broadcast to
+        assert_eq!(g.get(&b).array(), [0.5, 1.0, 1.5]);
+    }
 }
@@ -12,6 +12,12 @@ pub struct MinimumKernelOp;
 
 /// Element wise minimum.
 ///
+/// The gradient goes to whichever of `lhs` and `rhs` is smaller. Where they are equal, it is
+/// split evenly between them (a subgradient), so neither input stops learning.
+///
+/// To take the minimum with a tensor of a different shape, [crate::tensor_ops::BroadcastTo::broadcast()] it first.
+/// The gradient of a broadcasted tensor is summed along the broadcasted axes.
+///
 /// **Pytorch equivalent**: `torch.minimum(a, b)`
 ///
 /// Example:
@@ -22,6 +28,17 @@ pub struct MinimumKernelOp;
 /// let b = dev.tensor([[1.0, 0.5, 1.0], [-2.0, 2.0, -3.5]]);
 /// let r = a.minimum(b);
 /// assert_eq!(r.array(), [[1.0, 0.5, 1.0], [-2.0, -2.0, -3.5]]);
+/// ```
+///
+/// Broadcasting a row to every row:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a = dev.tensor([[1.0, 2.0, 0.0], [-1.0, 0.0, 3.0]]);
+/// let r = a.minimum(dev.tensor([0.5; 3]).broadcast());
+/// assert_eq!(r.array(), [[0.5, 0.5, 0.0], [-1.0, 0.0, 0.5]]);
+/// ```
+#[track_caller]
 pub fn minimum<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D> + Merge<RTape>, RTape: Tape<D>>(
     lhs: Tensor<S, E, D, LTape>,
     rhs: Tensor<S, E, D, RTape>,
@@ -31,6 +48,7 @@ pub fn minimum<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D> + Merge<RTape>,
 
 impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
     /// See [minimum]
+    #[track_caller]
     pub fn minimum<RTape: Tape<D>>(self, rhs: Tensor<S, E, D, RTape>) -> Self
     where
         LTape: Merge<RTape>,
@@ -39,13 +57,15 @@ impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>> Tensor<S, E, D, LTape> {
     }
 
     /// See [minimum]
+    #[track_caller]
     pub fn try_minimum<RTape: Tape<D>>(self, rhs: Tensor<S, E, D, RTape>) -> Result<Self, D::Err>
     where
         LTape: Merge<RTape>,
     {
-        try_binary_op(MinimumKernelOp, self, rhs)
+        try_binary_op("minimum", MinimumKernelOp, self, rhs)
     }
 }
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::TestDevice};
@@ -63,4 +83,17 @@ mod tests {
         assert_eq!(g.get(&a).array(), [[1.0, 0.5, 0.0], [0.5, 0.0, 1.0]]);
         assert_eq!(g.get(&b).array(), [[0.0, 0.5, 1.0], [0.5, 1.0, 0.0]]);
     }
+
+    #[test]
+    fn test_minimum_broadcast() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, -1.0, 2.0], [0.0, 3.0, -2.0]]);
+        let b = dev.tensor([0.0, 0.0, 2.0]);
+        let r = a.trace().minimum(b.trace().broadcast());
+        assert_eq!(r.array(), [[0.0, -1.0, 2.0], [0.0, 0.0, -2.0]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&a).array(), [[0.0, 1.0, 0.5], [0.5, 0.0, 1.0]]);
+        // summed over the rows it was broadcast to
+        assert_eq!(g.get(&b).array(), [1.5, 1.0, 0.5]);
+    }
 }
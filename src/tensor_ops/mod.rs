@@ -1,4 +1,4 @@
-//! Operations on tensors like [relu()], [matmul()], [softmax()], and more.
+//! Operations on tensors like [relu()], [matmul()], [softmax()], [einsum()], and more.
 //!
 //! # Generic function and struct methods
 //!
@@ -52,14 +52,36 @@
 //! - [MaxTo]
 //! - [MeanTo]
 //! - [MinTo]
+//! - [ProdTo]
 //! - [SumTo]
 //! - [VarTo]
 //! - [StddevTo]
 //! - [LogSumExpTo]
+//! - [NormTo]
+//!
+//! To get the indices of the largest or smallest values along an axis instead, see
+//! [crate::tensor::Tensor::argmax()] and [crate::tensor::Tensor::argmin()]. For medians and
+//! quantiles along an axis, see [median()] and [quantile()].
+//!
+//! # Precision of reductions
+//!
+//! Reductions accumulate in the dtype of the tensor. The reductions that transformers lean on
+//! are written so that they don't overflow or cancel out for large inputs:
+//!
+//! - [softmax()], [log_softmax()] and [LogSumExpTo] subtract the max along the reduced axes
+//!   before exponentiating, so large logits don't overflow to `inf` and produce `NaN`s.
+//! - [VarTo], [StddevTo] and [normalize()] (which [crate::nn::LayerNorm1D] uses) subtract
+//!   the mean before squaring, instead of computing `mean(x^2) - mean(x)^2`, so values with
+//!   a large offset keep their precision and the variance can't become negative.
+//!
+//! [VarTo], [StddevTo] and [normalize()] are only implemented for `f32`, and there are no
+//! half precision dtypes yet. Half precision versions of these ops should accumulate in `f32`,
+//! since sums of exponentials & squares over a row of activations easily exceed the range of `f16`.
 //!
 //! # Broadcasts
 //!
-//! Broadcasting tensors is provided through the [BroadcastTo] trait. Similar to reductions
+//! Broadcasting tensors is provided through the [BroadcastTo] trait, and expanding axes
+//! of size 1 through the [ExpandTo] trait. Similar to reductions
 //! there are two generic parameters to broadcast:
 //! 1. (Required) The target shape
 //! 2. (usually optional) The axes *of the result type* to broadcast
@@ -143,21 +165,41 @@ mod utilities;
 pub use utilities::*;
 
 mod abs;
+mod adaptive_pool2d;
 mod add;
+mod affine;
+mod argmax;
+mod as_strided;
 mod bce;
 mod boolean;
 mod broadcast_to;
+mod checks;
 mod choose;
 mod clamp;
+mod concat_along;
 mod cos;
+mod cumprod;
+mod cumsum;
+mod diag;
 mod div;
 mod dropout;
+mod einsum;
+mod erf;
 mod exp;
+mod expand_to;
+mod fft;
+mod flip;
+mod fma;
+mod gather_along;
 mod gelu;
 mod huber_error;
+mod jacobian;
+mod lerp;
+mod linalg;
 mod ln;
 mod log_softmax;
 mod logsumexp_to;
+mod masked;
 mod matmul;
 mod max_to;
 mod maximum;
@@ -167,39 +209,73 @@ mod minimum;
 mod mul;
 mod nans_to;
 mod negate;
+mod norm_to;
 mod normalize;
+mod pad2d;
 mod permute_to;
 mod pow;
+mod prod_to;
+mod quantile;
 mod relu;
 mod reshape_to;
+mod roll;
+mod round;
+mod sample_logits;
+mod scatter_add;
 mod select_and_gather;
 mod sigmoid;
 mod sin;
+mod slice;
 mod softmax;
+mod sort;
+mod split;
 mod sqrt;
+mod stack;
 mod square;
 mod stddev_to;
 mod sub;
 mod sum_to;
 mod tanh;
+mod topk;
+mod triangular;
+mod upsample2d;
 mod var_to;
 
 pub use abs::abs;
+pub(crate) use adaptive_pool2d::{ConstAdaptiveAvgPool2D, ConstAdaptiveMaxPool2D};
+pub use adaptive_pool2d::{TryAdaptiveAvgPool2D, TryAdaptiveMaxPool2D};
 pub use add::{add, TryAdd};
+pub use affine::affine;
+pub use as_strided::AsStrided;
 pub use bce::bce_with_logits;
 pub use boolean::{bool_and, bool_not, bool_or, bool_xor};
 pub use broadcast_to::BroadcastTo;
+pub use checks::assert_all;
 pub use choose::ChooseFrom;
 pub use clamp::clamp;
+pub use concat_along::ConcatAlong;
 pub use cos::cos;
+pub use cumprod::cumprod;
+pub use cumsum::cumsum;
 pub use div::{div, TryDiv};
 pub use dropout::dropout;
+pub use einsum::{einsum, TryEinsum};
+pub use erf::{erf, normal_cdf};
 pub use exp::exp;
+pub use expand_to::ExpandTo;
+pub use fft::{irfft, rfft, IrfftShape, RfftShape};
+pub use flip::flip;
+pub use fma::fma;
+pub use gather_along::GatherAlong;
 pub use gelu::gelu;
 pub use huber_error::huber_error;
+pub use jacobian::{jacobian, try_jacobian};
+pub use lerp::{lerp, TryLerp};
+pub use linalg::{cholesky, det, logdet, svd, symeig, MatrixShape};
 pub use ln::ln;
 pub use log_softmax::log_softmax;
 pub use logsumexp_to::LogSumExpTo;
+pub use masked::{masked_fill, masked_select};
 pub use matmul::{matmul, TryMatMul};
 pub use max_to::MaxTo;
 pub use maximum::maximum;
@@ -209,33 +285,75 @@ pub use minimum::minimum;
 pub use mul::{mul, TryMul};
 pub use nans_to::nans_to;
 pub use negate::negate;
+pub use norm_to::NormTo;
 pub use normalize::normalize;
+#[cfg(feature = "nightly")]
+pub use pad2d::ConstPad2D;
+pub use pad2d::{PadMode, TryPad2D};
 pub use permute_to::PermuteTo;
 pub use pow::{powf, powi};
+pub use prod_to::ProdTo;
+pub use quantile::{median, quantile};
 pub use relu::relu;
 pub use reshape_to::ReshapeTo;
+pub use roll::roll;
+pub use round::{ceil, floor, round, sign};
+pub use sample_logits::sample_logits;
+pub use scatter_add::ScatterAdd;
 pub use select_and_gather::{GatherTo, SelectTo};
 pub use sigmoid::sigmoid;
 pub use sin::sin;
-pub use softmax::softmax;
+pub use softmax::{masked_softmax, softmax};
+pub use sort::Sort;
 pub use sqrt::sqrt;
+pub use stack::Stack;
 pub use square::square;
 pub use stddev_to::StddevTo;
 pub use sub::{sub, TrySub};
 pub use sum_to::SumTo;
 pub use tanh::tanh;
+pub use topk::TopK;
+pub use triangular::{tril, triu};
+pub(crate) use upsample2d::ConstUpsample2D;
+pub use upsample2d::{TryUpsample2D, UpsampleMode};
 pub use var_to::VarTo;
 
+#[cfg(feature = "nightly")]
+mod conv1d;
+#[cfg(feature = "nightly")]
+pub use conv1d::TryConv1D;
+#[cfg(feature = "nightly")]
+pub(crate) use conv1d::TryConv1DTo;
+
 #[cfg(feature = "nightly")]
 mod conv2d;
 #[cfg(feature = "nightly")]
-pub use conv2d::TryConv2D;
+pub use conv2d::{TryConv2D, TryConv2DNhwc};
+#[cfg(feature = "nightly")]
+pub(crate) use conv2d::{TryConv2DNhwcTo, TryConv2DTo};
+
+#[cfg(feature = "nightly")]
+mod conv3d;
+#[cfg(feature = "nightly")]
+pub use conv3d::TryConv3D;
+#[cfg(feature = "nightly")]
+pub(crate) use conv3d::TryConv3DTo;
+
+#[cfg(feature = "nightly")]
+mod conv_transpose2d;
+#[cfg(feature = "nightly")]
+pub use conv_transpose2d::TryConvTrans2D;
 #[cfg(feature = "nightly")]
-pub(crate) use conv2d::TryConv2DTo;
+pub(crate) use conv_transpose2d::TryConvTrans2DTo;
 
 #[cfg(feature = "nightly")]
 mod pool2d;
 #[cfg(feature = "nightly")]
-pub(crate) use pool2d::{ConstAvgPool2D, ConstMaxPool2D, ConstMinPool2D};
+pub(crate) use pool2d::{
+    ConstAvgPool2D, ConstMaxPool2D, ConstMaxUnpool2D, ConstMinPool2D, NhwcAvgPool2D, NhwcMaxPool2D,
+    NhwcMinPool2D,
+};
 #[cfg(feature = "nightly")]
-pub use pool2d::{TryAvgPool2D, TryMaxPool2D, TryMinPool2D};
+pub use pool2d::{
+    TryAvgPool2D, TryMaxPool2D, TryMaxPool2DWithIndices, TryMaxUnpool2D, TryMinPool2D,
+};
@@ -35,6 +35,7 @@ pub struct ScalarMulKernelOp<E> {
 /// let r = a * 2.0;
 /// assert_eq!(r.array(), [[2.0, 4.0, 6.0], [-2.0, -4.0, -6.0]]);
 /// ```
+#[track_caller]
 pub fn mul<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
@@ -50,8 +51,9 @@ impl<S: Shape, E: Dtype, D: Device<E>, LhsTape: Tape<D>, RhsTape: Tape<D>>
 where
     LhsTape: Merge<RhsTape>,
 {
+    #[track_caller]
     fn try_mul(self, rhs: Tensor<S, E, D, RhsTape>) -> Result<Self, Self::Err> {
-        try_binary_op(BinaryMulKernelOp, self, rhs)
+        try_binary_op("mul", BinaryMulKernelOp, self, rhs)
     }
 }
 
@@ -67,6 +69,7 @@ where
     Self: TryMul<Rhs>,
 {
     type Output = Self;
+    #[track_caller]
     fn mul(self, rhs: Rhs) -> Self::Output {
         self.try_mul(rhs).unwrap()
     }
@@ -0,0 +1,105 @@
+use super::*;
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+/// Reduction along multiple axes using the [p-norm](https://en.wikipedia.org/wiki/Norm_(mathematics)#p-norm).
+pub trait NormTo: HasErr + HasShape {
+    /// p-norm reduction, `sum(|t|^p)^(1/p)`. `p = 1.0` is the sum of absolute values, `p = 2.0`
+    /// the euclidean norm, and `p = f32::INFINITY` the max of absolute values.
+    ///
+    /// Like [sqrt()], the gradient is `NaN` where the norm is `0` for `1 < p < inf`.
+    ///
+    /// **Pytorch equivalent**: `torch.linalg.vector_norm(t, ord=p, dim=Axes)`
+    ///
+    /// Examples:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[3.0, -4.0, 0.0], [1.0, 1.0, -2.0]]);
+    /// let r = t.clone().norm::<Rank1<2>, _>(2.0); // or `norm::<_, Axis<1>>(2.0)`
+    /// assert_eq!(r.array(), [5.0, 6f32.sqrt()]);
+    /// let r = t.clone().norm::<_, Axis<1>>(1.0);
+    /// assert_eq!(r.array(), [7.0, 4.0]);
+    /// let r = t.norm::<Rank1<2>, _>(f32::INFINITY);
+    /// assert_eq!(r.array(), [4.0, 2.0]);
+    /// ```
+    ///
+    /// Panics if `p` isn't positive.
+    fn norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_norm(p).unwrap()
+    }
+    /// Fallible version of [NormTo::norm]
+    fn try_norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, D: Device<f32>, T: Tape<D>> NormTo for Tensor<S, f32, D, T> {
+    fn try_norm<Dst: Shape, Ax: Axes>(self, p: f32) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        assert!(p > 0.0, "norm needs a positive `p`, but got {p}");
+        if p == 1.0 {
+            self.try_abs()?.try_sum()
+        } else if p == 2.0 {
+            self.try_square()?.try_sum()?.try_sqrt()
+        } else if p == f32::INFINITY {
+            self.try_abs()?.try_max()
+        } else {
+            self.try_abs()?.try_powf(p)?.try_sum()?.try_powf(1.0 / p)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_norm_l1_l2() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[3.0, -4.0], [-1.0, 2.0]]);
+        let r = t.trace().norm::<_, Axis<1>>(2.0);
+        assert_close(&r.array(), &[5.0, 5f32.sqrt()]);
+        let g = r.sum().backward();
+        assert_close(
+            &g.get(&t).array(),
+            &[[0.6, -0.8], [-1.0 / 5f32.sqrt(), 2.0 / 5f32.sqrt()]],
+        );
+
+        let r = t.trace().norm::<_, Axis<1>>(1.0);
+        assert_eq!(r.array(), [7.0, 3.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, -1.0], [-1.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_norm_general_p() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 2.0]);
+        let r = t.trace().norm::<Rank0, _>(3.0);
+        assert_close(&r.array(), &17f32.powf(1.0 / 3.0));
+        // the gradient is `sign(t) * |t|^(p-1) / norm^(p-1)`
+        let g = r.backward();
+        let n2 = 17f32.powf(2.0 / 3.0);
+        assert_close(&g.get(&t).array(), &[1.0 / n2, -4.0 / n2, 4.0 / n2]);
+
+        // matches the l2 norm
+        let r = t.clone().norm::<Rank0, _>(2.000001);
+        assert_close(&r.array(), &3.0);
+    }
+
+    #[test]
+    fn test_norm_inf() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, -5.0, 2.0], [0.5, 0.0, 0.25]]);
+        let r = t.trace().norm::<Rank0, _>(f32::INFINITY);
+        assert_eq!(r.array(), 5.0);
+        let g = r.backward();
+        assert_eq!(g.get(&t).array(), [[0.0, -1.0, 0.0], [0.0, 0.0, 0.0]]);
+    }
+}
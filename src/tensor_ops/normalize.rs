@@ -65,6 +65,14 @@ mod tests {
         assert_close(&g.get(&a).array(), &[0.033410847, -0.04677555, 0.013364702]);
     }
 
+    #[test]
+    fn test_normalize_large_offset() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([9999.0, 10001.0, 10006.0]);
+        let r = a.normalize(1e-5);
+        assert_close(&r.array(), &[-1.0190487, -0.3396829, 1.3587316]);
+    }
+
     #[test]
     fn test_2d_normalize_axis_last() {
         let dev: TestDevice = Default::default();
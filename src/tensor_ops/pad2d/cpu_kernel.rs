@@ -0,0 +1,55 @@
+use super::{Pad2DOp, PadMode};
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator},
+};
+
+impl<E> Pad2DOp<E> {
+    /// The index of `inp` that `out_idx` is copied from, or `None` if it's the constant.
+    #[inline(always)]
+    fn src<I: Shape, O: Shape>(&self, inp: &I, out_idx: O::Concrete) -> Option<I::Concrete> {
+        let n = I::NUM_DIMS;
+        let dims = inp.concrete();
+        let mut idx: I::Concrete = Default::default();
+        for d in 0..n - 2 {
+            idx[d] = out_idx[d];
+        }
+        idx[n - 2] = self.src_index(out_idx[n - 2], self.top, dims[n - 2])?;
+        idx[n - 1] = self.src_index(out_idx[n - 1], self.left, dims[n - 1])?;
+        Some(idx)
+    }
+}
+
+impl<E: Dtype> super::Pad2DKernel<E> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<E>,
+        inp: &Self::Storage<I, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err> {
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            *o = match (op.src::<I, O>(&inp.shape, i), op.mode) {
+                (Some(i_inp), _) => inp[i_inp],
+                (None, PadMode::Constant(value)) => value,
+                (None, _) => unreachable!(),
+            };
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<E>,
+        grad_inp: &mut Self::Storage<I, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err> {
+        let mut out_iter = grad_out.iter_with_index();
+        while let Some((g, i)) = out_iter.next() {
+            if let Some(i_inp) = op.src::<I, O>(&grad_inp.shape, i) {
+                grad_inp[i_inp] += *g;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Pad2DKernel, Pad2DOp, PadMode};
+use crate::{shapes::Shape, tensor::cuda::Cuda};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/pad2d.ptx"));
+const MODULE_NAME: &str = "pad2d";
+const FWD_FN_NAME: &str = "pad2d_forward";
+const BWD_FN_NAME: &str = "pad2d_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl Pad2DOp<f32> {
+    /// The mode code & constant value that the cuda kernels use
+    fn mode_and_value(&self) -> (u8, f32) {
+        match self.mode {
+            PadMode::Constant(value) => (0, value),
+            PadMode::Reflect => (1, 0.0),
+            PadMode::Replicate => (2, 0.0),
+        }
+    }
+}
+
+impl Pad2DKernel<f32> for Cuda {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<f32>,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = out.shape.num_elements();
+        let (mode, value) = op.mode_and_value();
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_dims: CudaSlice<usize> = self.dev.take_async(out.shape.concrete().into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                        // const size_t numel,
+            I::NUM_DIMS,                  // const size_t num_dims,
+            &inp_dims,                    // const size_t *inp_dims,
+            &inp_strides,                 // const size_t *inp_strides,
+            &out_dims,                    // const size_t *out_dims,
+            op.top,                       // const size_t top,
+            op.left,                      // const size_t left,
+            mode,                         // const uint8_t mode,
+            value,                        // const float value,
+            inp.data.as_ref(),            // const float *inp,
+            Arc::make_mut(&mut out.data), // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<f32>,
+        grad_inp: &mut Self::Storage<I, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            Pad2DKernel::<f32>::backward(&self.cpu, op, &mut cpu_grad_inp, &cpu_grad_out)?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let numel = grad_out.shape.num_elements();
+        let (mode, _) = op.mode_and_value();
+        let inp_dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let out_dims: CudaSlice<usize> = self.dev.take_async(grad_out.shape.concrete().into())?;
+
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            numel,                             // const size_t numel,
+            I::NUM_DIMS,                       // const size_t num_dims,
+            &inp_dims,                         // const size_t *inp_dims,
+            &inp_strides,                      // const size_t *inp_strides,
+            &out_dims,                         // const size_t *out_dims,
+            op.top,                            // const size_t top,
+            op.left,                           // const size_t left,
+            mode,                              // const uint8_t mode,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+/// How [TryPad2D] fills in the padding.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PadMode<E> {
+    /// Fill the padding with a value.
+    Constant(E),
+    /// Mirror the values at the edges, without repeating the edge itself.
+    /// `[1, 2, 3]` padded by 2 is `[3, 2, 1, 2, 3, 2, 1]`.
+    Reflect,
+    /// Repeat the values at the edges. `[1, 2, 3]` padded by 2 is `[1, 1, 1, 2, 3, 3, 3]`.
+    Replicate,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Pad2DOp<E> {
+    pub top: usize,
+    pub left: usize,
+    pub mode: PadMode<E>,
+}
+
+impl<E> Pad2DOp<E> {
+    /// The index into an input dimension of size `dim` that `i` (which is offset by the padding)
+    /// reads from, or `None` if it reads the constant.
+    #[inline(always)]
+    pub(super) fn src_index(&self, i: usize, pad: usize, dim: usize) -> Option<usize> {
+        if i >= pad && i - pad < dim {
+            return Some(i - pad);
+        }
+        match self.mode {
+            PadMode::Constant(_) => None,
+            PadMode::Reflect if i < pad => Some(pad - i),
+            PadMode::Reflect => Some(2 * (dim - 1) + pad - i),
+            PadMode::Replicate if i < pad => Some(0),
+            PadMode::Replicate => Some(dim - 1),
+        }
+    }
+}
+
+pub trait Pad2DKernel<E: Dtype>: DeviceStorage {
+    /// Pads the last two dimensions of `inp` into `out`.
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<E>,
+        inp: &Self::Storage<I, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds `grad_out` to the elements of `grad_inp` they were read from.
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pad2DOp<E>,
+        grad_inp: &mut Self::Storage<I, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Pads the last two (height & width) dimensions of images, i.e. `(H, W)`, `(C, H, W)`
+/// or `(B, C, H, W)`, with a [PadMode]. Gradients of the padding are added back to the
+/// values it was copied from.
+///
+/// The padding amounts are given at runtime, as `[top, bottom, left, right]`, and
+/// the padded dimensions are `usize`. For static shapes, see [ConstPad2D].
+///
+/// **Pytorch equivalent**: `torch.nn.functional.pad(t, (left, right, top, bottom), mode)`
+///
+/// **Panics** if reflecting by more than the size of a dimension minus 1,
+/// or replicating an empty dimension.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0]]);
+/// let r = t.clone().pad2d([0, 0, 2, 1], PadMode::Reflect);
+/// assert_eq!(r.as_vec(), [3.0, 2.0, 1.0, 2.0, 3.0, 2.0]);
+/// let r = t.pad2d([1, 0, 0, 1], PadMode::Constant(0.0));
+/// assert_eq!(r.shape(), &(2, 4));
+/// assert_eq!(r.as_vec(), [0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 3.0, 0.0]);
+/// ```
+pub trait TryPad2D<E>: HasErr {
+    type Output;
+
+    /// See [TryPad2D]
+    fn pad2d(self, padding: [usize; 4], mode: PadMode<E>) -> Self::Output {
+        self.try_pad2d(padding, mode).unwrap()
+    }
+
+    /// Fallible version of [TryPad2D::pad2d]
+    fn try_pad2d(self, padding: [usize; 4], mode: PadMode<E>) -> Result<Self::Output, Self::Err>;
+}
+
+fn try_pad2d_op<I: Shape, O: Shape, E: Dtype, D, T: Tape<D>>(
+    t: Tensor<I, E, D, T>,
+    out_shape: O,
+    [top, bottom, left, right]: [usize; 4],
+    mode: PadMode<E>,
+) -> Result<Tensor<O, E, D, T>, D::Err>
+where
+    D: Pad2DKernel<E> + ZerosTensor<E>,
+{
+    let dims = t.shape().concrete();
+    let (h, w) = (dims[I::NUM_DIMS - 2], dims[I::NUM_DIMS - 1]);
+    match mode {
+        PadMode::Constant(_) => {}
+        PadMode::Reflect => assert!(
+            top.max(bottom) < h && left.max(right) < w,
+            "reflect padding must be smaller than the padded dimension"
+        ),
+        PadMode::Replicate => assert!(
+            (h > 0 || top + bottom == 0) && (w > 0 || left + right == 0),
+            "can't replicate an empty dimension"
+        ),
+    }
+
+    let op = Pad2DOp { top, left, mode };
+    let (inp, mut tape) = t.split_tape();
+    let mut out = inp.device.try_zeros_like(&out_shape)?;
+    inp.device.forward(op, &inp.storage, &mut out.storage)?;
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&inp)?;
+    tape.try_alloc_grad(&out)?;
+    tape.add_backward_op(move |grads| {
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+        inp.device.backward(op, grad_inp, grad_out)
+    });
+    Ok(out.put_tape(tape))
+}
+
+impl<H: Dim, W: Dim, E: Dtype, D: Pad2DKernel<E> + ZerosTensor<E>, T: Tape<D>> TryPad2D<E>
+    for Tensor<(H, W), E, D, T>
+{
+    type Output = Tensor<(usize, usize), E, D, T>;
+    fn try_pad2d(self, padding: [usize; 4], mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        let &(h, w) = self.shape();
+        let [top, bottom, left, right] = padding;
+        let shape = (h.size() + top + bottom, w.size() + left + right);
+        try_pad2d_op(self, shape, padding, mode)
+    }
+}
+
+impl<C: Dim, H: Dim, W: Dim, E: Dtype, D: Pad2DKernel<E> + ZerosTensor<E>, T: Tape<D>> TryPad2D<E>
+    for Tensor<(C, H, W), E, D, T>
+{
+    type Output = Tensor<(C, usize, usize), E, D, T>;
+    fn try_pad2d(self, padding: [usize; 4], mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        let &(c, h, w) = self.shape();
+        let [top, bottom, left, right] = padding;
+        let shape = (c, h.size() + top + bottom, w.size() + left + right);
+        try_pad2d_op(self, shape, padding, mode)
+    }
+}
+
+impl<B: Dim, C: Dim, H: Dim, W: Dim, E: Dtype, D, T: Tape<D>> TryPad2D<E>
+    for Tensor<(B, C, H, W), E, D, T>
+where
+    D: Pad2DKernel<E> + ZerosTensor<E>,
+{
+    type Output = Tensor<(B, C, usize, usize), E, D, T>;
+    fn try_pad2d(self, padding: [usize; 4], mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        let &(b, c, h, w) = self.shape();
+        let [top, bottom, left, right] = padding;
+        let shape = (b, c, h.size() + top + bottom, w.size() + left + right);
+        try_pad2d_op(self, shape, padding, mode)
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub trait PadAlgebra<const P: usize>: ConstDim {
+    type Padded: ConstDim;
+}
+
+#[cfg(feature = "nightly")]
+impl<const D: usize, const P: usize> PadAlgebra<P> for Const<D>
+where
+    Const<{ D + 2 * P }>: Sized,
+{
+    type Padded = Const<{ D + 2 * P }>;
+}
+
+/// **Requires Nightly** Pads height & width by `P` on every side, keeping the shape static.
+/// See [TryPad2D] for the padding modes.
+///
+/// ```ignore
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank3<3, 8, 8>, f32, _> = dev.zeros();
+/// let r: Tensor<Rank3<3, 10, 10>, f32, _> = t.pad2d_const::<1>(PadMode::Replicate);
+/// ```
+#[cfg(feature = "nightly")]
+pub trait ConstPad2D<const P: usize, E>: HasErr {
+    type Output;
+
+    /// See [ConstPad2D]
+    fn pad2d_const(self, mode: PadMode<E>) -> Self::Output {
+        self.try_pad2d_const(mode).unwrap()
+    }
+
+    /// Fallible version of [ConstPad2D::pad2d_const]
+    fn try_pad2d_const(self, mode: PadMode<E>) -> Result<Self::Output, Self::Err>;
+}
+
+#[cfg(feature = "nightly")]
+impl<const H: usize, const W: usize, E: Dtype, D, T: Tape<D>, const P: usize> ConstPad2D<P, E>
+    for Tensor<(Const<H>, Const<W>), E, D, T>
+where
+    D: Pad2DKernel<E> + ZerosTensor<E>,
+    Const<H>: PadAlgebra<P>,
+    Const<W>: PadAlgebra<P>,
+{
+    type Output = Tensor<
+        (
+            <Const<H> as PadAlgebra<P>>::Padded,
+            <Const<W> as PadAlgebra<P>>::Padded,
+        ),
+        E,
+        D,
+        T,
+    >;
+    fn try_pad2d_const(self, mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        try_pad2d_op(self, Default::default(), [P; 4], mode)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<C: Dim, const H: usize, const W: usize, E: Dtype, D, T: Tape<D>, const P: usize>
+    ConstPad2D<P, E> for Tensor<(C, Const<H>, Const<W>), E, D, T>
+where
+    D: Pad2DKernel<E> + ZerosTensor<E>,
+    Const<H>: PadAlgebra<P>,
+    Const<W>: PadAlgebra<P>,
+{
+    type Output = Tensor<
+        (
+            C,
+            <Const<H> as PadAlgebra<P>>::Padded,
+            <Const<W> as PadAlgebra<P>>::Padded,
+        ),
+        E,
+        D,
+        T,
+    >;
+    fn try_pad2d_const(self, mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        let &(c, _, _) = self.shape();
+        let shape = (c, Default::default(), Default::default());
+        try_pad2d_op(self, shape, [P; 4], mode)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<B: Dim, C: Dim, const H: usize, const W: usize, E: Dtype, D, T: Tape<D>, const P: usize>
+    ConstPad2D<P, E> for Tensor<(B, C, Const<H>, Const<W>), E, D, T>
+where
+    D: Pad2DKernel<E> + ZerosTensor<E>,
+    Const<H>: PadAlgebra<P>,
+    Const<W>: PadAlgebra<P>,
+{
+    type Output = Tensor<
+        (
+            B,
+            C,
+            <Const<H> as PadAlgebra<P>>::Padded,
+            <Const<W> as PadAlgebra<P>>::Padded,
+        ),
+        E,
+        D,
+        T,
+    >;
+    fn try_pad2d_const(self, mode: PadMode<E>) -> Result<Self::Output, Self::Err> {
+        let &(b, c, _, _) = self.shape();
+        let shape = (b, c, Default::default(), Default::default());
+        try_pad2d_op(self, shape, [P; 4], mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_pad2d_modes() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+
+        let r = t.clone().pad2d([1, 2, 2, 1], PadMode::Reflect);
+        assert_eq!(r.shape(), &(6, 6));
+        #[rustfmt::skip]
+        assert_eq!(r.as_vec(), [
+            6.0, 5.0, 4.0, 5.0, 6.0, 5.0,
+            3.0, 2.0, 1.0, 2.0, 3.0, 2.0,
+            6.0, 5.0, 4.0, 5.0, 6.0, 5.0,
+            9.0, 8.0, 7.0, 8.0, 9.0, 8.0,
+            6.0, 5.0, 4.0, 5.0, 6.0, 5.0,
+            3.0, 2.0, 1.0, 2.0, 3.0, 2.0,
+        ]);
+
+        let r = t.clone().pad2d([2, 0, 0, 1], PadMode::Replicate);
+        #[rustfmt::skip]
+        assert_eq!(r.as_vec(), [
+            1.0, 2.0, 3.0, 3.0,
+            1.0, 2.0, 3.0, 3.0,
+            1.0, 2.0, 3.0, 3.0,
+            4.0, 5.0, 6.0, 6.0,
+            7.0, 8.0, 9.0, 9.0,
+        ]);
+
+        let r = t.pad2d([0, 1, 1, 0], PadMode::Constant(-1.0));
+        #[rustfmt::skip]
+        assert_eq!(r.as_vec(), [
+            -1.0, 1.0, 2.0, 3.0,
+            -1.0, 4.0, 5.0, 6.0,
+            -1.0, 7.0, 8.0, 9.0,
+            -1.0, -1.0, -1.0, -1.0,
+        ]);
+    }
+
+    #[test]
+    fn test_pad2d_backward() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0]]);
+
+        let g = t
+            .trace()
+            .pad2d([0, 0, 2, 2], PadMode::Reflect)
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[2.0, 3.0, 2.0]]);
+
+        let g = t
+            .trace()
+            .pad2d([1, 1, 1, 2], PadMode::Replicate)
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[6.0, 3.0, 9.0]]);
+
+        let g = t
+            .trace()
+            .pad2d([1, 1, 1, 1], PadMode::Constant(5.0))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[1.0; 3]]);
+    }
+
+    #[test]
+    fn test_pad2d_4d_permuted() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank4<2, 3, 4, 5>, f32, _> = dev.sample_normal();
+        let p = t.clone().permute::<_, Axes4<0, 1, 3, 2>>();
+        let r = p.trace().pad2d([1, 1, 2, 2], PadMode::Reflect);
+        assert_eq!(r.shape(), &(Const::<2>, Const::<3>, 7, 8));
+        let t_arr = t.array();
+        let r_vec = r.as_vec();
+        for (b, t_b) in t_arr.iter().enumerate() {
+            for (c, t_c) in t_b.iter().enumerate() {
+                for (j, row) in t_c.iter().enumerate() {
+                    for (i, x) in row.iter().enumerate() {
+                        let o = ((b * 3 + c) * 7 + i + 1) * 8 + j + 2;
+                        assert_eq!(r_vec[o], *x);
+                    }
+                }
+            }
+        }
+        let g = r.mean().backward();
+        let total: f32 = g.get(&p).as_vec().iter().sum();
+        assert!((total - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    #[should_panic = "reflect padding must be smaller than the padded dimension"]
+    fn test_pad2d_reflect_too_big() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 2>, f32, _> = dev.zeros();
+        let _ = t.pad2d([2, 0, 0, 0], PadMode::Reflect);
+    }
+}
@@ -0,0 +1,94 @@
+#include "cuda_utils.cuh"
+
+// mode: 0 is constant, 1 is reflect, 2 is replicate
+__device__ bool pad_src_index(size_t i, size_t pad, size_t dim, uint8_t mode, size_t *src) {
+    if (i >= pad && i - pad < dim) {
+        *src = i - pad;
+        return true;
+    }
+    if (mode == 0) {
+        return false;
+    }
+    if (mode == 1) {
+        *src = i < pad ? pad - i : 2 * (dim - 1) + pad - i;
+    } else {
+        *src = i < pad ? 0 : dim - 1;
+    }
+    return true;
+}
+
+// the strided index of `inp` that the contiguous `out_i` reads from
+__device__ bool pad2d_src(
+    unsigned int out_i,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const size_t top,
+    const size_t left,
+    const uint8_t mode,
+    unsigned int *inp_i
+) {
+    *inp_i = 0;
+    for (int d = num_dims - 1; d >= 0; d--) {
+        size_t i = out_i % out_dims[d];
+        out_i /= out_dims[d];
+        if (d == num_dims - 1 || d == num_dims - 2) {
+            size_t pad = d == num_dims - 1 ? left : top;
+            if (!pad_src_index(i, pad, inp_dims[d], mode, &i)) {
+                return false;
+            }
+        }
+        *inp_i += i * inp_strides[d];
+    }
+    return true;
+}
+
+extern "C" __global__ void pad2d_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const size_t top,
+    const size_t left,
+    const uint8_t mode,
+    const float value,
+    const float *inp,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i;
+    if (pad2d_src(out_i, num_dims, inp_dims, inp_strides, out_dims, top, left, mode, &inp_i)) {
+        out[out_i] = inp[inp_i];
+    } else {
+        out[out_i] = value;
+    }
+}
+
+extern "C" __global__ void pad2d_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *inp_dims,
+    const size_t *inp_strides,
+    const size_t *out_dims,
+    const size_t top,
+    const size_t left,
+    const uint8_t mode,
+    float *grad_inp,
+    const float *grad_out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int inp_i;
+    if (pad2d_src(out_i, num_dims, inp_dims, inp_strides, out_dims, top, left, mode, &inp_i)) {
+        atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+    }
+}
@@ -3,10 +3,13 @@ use crate::tensor::cpu::Cpu;
 
 use std::sync::Arc;
 
-fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
-    match S::NUM_DIMS {
-        3 => [0, strides[0], strides[1], strides[2]],
-        4 => [strides[0], strides[1], strides[2], strides[3]],
+/// Returns strides in (batch, channel, height, width) order.
+fn make_4d<S: Shape>(strides: S::Concrete, channels_last: bool) -> [usize; 4] {
+    match (S::NUM_DIMS, channels_last) {
+        (3, false) => [0, strides[0], strides[1], strides[2]],
+        (4, false) => [strides[0], strides[1], strides[2], strides[3]],
+        (3, true) => [0, strides[2], strides[0], strides[1]],
+        (4, true) => [strides[0], strides[3], strides[1], strides[2]],
         _ => panic!("Only implemented for 3d & 4d arrays"),
     }
 }
@@ -18,8 +21,8 @@ impl super::AvgPool2DKernel<f32> for Cpu {
         inp: &Self::Storage<I, f32>,
         out: &mut Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let buf = inp.data.as_ref();
         let out_buf = Arc::make_mut(&mut out.data);
@@ -58,8 +61,8 @@ impl super::AvgPool2DKernel<f32> for Cpu {
         out: &Self::Storage<O, f32>,
         grad_out: &Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let ginp_buf = Arc::make_mut(&mut grad_inp.data);
         let buf = grad_out.data.as_ref();
@@ -100,8 +103,8 @@ impl super::MaxPool2DKernel<f32> for Cpu {
         inp: &Self::Storage<I, f32>,
         out: &mut Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let buf = inp.data.as_ref();
         let out_buf = Arc::make_mut(&mut out.data);
@@ -141,8 +144,8 @@ impl super::MaxPool2DKernel<f32> for Cpu {
         out: &Self::Storage<O, f32>,
         grad_out: &Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let inp_buf = inp.data.as_ref();
         let ginp_buf = Arc::make_mut(&mut grad_inp.data);
@@ -186,8 +189,8 @@ impl super::MinPool2DKernel<f32> for Cpu {
         inp: &Self::Storage<I, f32>,
         out: &mut Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let buf = inp.data.as_ref();
         let out_buf = Arc::make_mut(&mut out.data);
@@ -227,8 +230,8 @@ impl super::MinPool2DKernel<f32> for Cpu {
         out: &Self::Storage<O, f32>,
         grad_out: &Self::Storage<O, f32>,
     ) -> Result<(), Self::Err> {
-        let istr = make_4d::<I>(inp.strides);
-        let ostr = make_4d::<O>(out.strides);
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
 
         let inp_buf = inp.data.as_ref();
         let ginp_buf = Arc::make_mut(&mut grad_inp.data);
@@ -264,3 +267,145 @@ impl super::MinPool2DKernel<f32> for Cpu {
         Ok(())
     }
 }
+
+impl super::MaxPool2DIndicesKernel<f32> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+        indices: &mut Self::Storage<O, usize>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
+        let idx_str = make_4d::<O>(indices.strides, op.channels_last);
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        let idx_buf = Arc::make_mut(&mut indices.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    for ow in 0..op.w_out {
+                        let mut tmp = f32::NEG_INFINITY;
+                        let mut argmax = None;
+                        for k1 in 0..op.kernel {
+                            let y = (oh * op.stride + k1).checked_sub(op.padding);
+                            for k2 in 0..op.kernel {
+                                let x = (ow * op.stride + k2).checked_sub(op.padding);
+                                if let Some((y, x)) = y.zip(x) {
+                                    if y < op.h_in && x < op.w_in {
+                                        let v = buf
+                                            [b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3]];
+                                        if argmax.is_none() || v > tmp {
+                                            tmp = v;
+                                            argmax = Some(y * op.w_in + x);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        out_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]] = tmp;
+                        idx_buf
+                            [b * idx_str[0] + c * idx_str[1] + oh * idx_str[2] + ow * idx_str[3]] =
+                            argmax.unwrap_or(0);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        indices: &Self::Storage<O, usize>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(grad_inp.strides, op.channels_last);
+        let ostr = make_4d::<O>(grad_out.strides, op.channels_last);
+        let idx_str = make_4d::<O>(indices.strides, op.channels_last);
+
+        let ginp_buf = Arc::make_mut(&mut grad_inp.data);
+        let idx_buf = indices.data.as_ref();
+        let gout_buf = grad_out.data.as_ref();
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    for ow in 0..op.w_out {
+                        let i = idx_buf
+                            [b * idx_str[0] + c * idx_str[1] + oh * idx_str[2] + ow * idx_str[3]];
+                        let (y, x) = (i / op.w_in, i % op.w_in);
+                        ginp_buf[b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3]] +=
+                            gout_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]];
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl super::MaxUnpool2DKernel<f32> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        inp: &Self::Storage<I, f32>,
+        indices: &Self::Storage<I, usize>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides, op.channels_last);
+        let idx_str = make_4d::<I>(indices.strides, op.channels_last);
+        let ostr = make_4d::<O>(out.strides, op.channels_last);
+
+        let buf = inp.data.as_ref();
+        let idx_buf = indices.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    for ow in 0..op.w_out {
+                        let i = idx_buf
+                            [b * idx_str[0] + c * idx_str[1] + oh * idx_str[2] + ow * idx_str[3]];
+                        assert!(i < op.h_in * op.w_in, "unpool index {i} out of bounds");
+                        let (y, x) = (i / op.w_in, i % op.w_in);
+                        out_buf[b * ostr[0] + c * ostr[1] + y * ostr[2] + x * ostr[3]] =
+                            buf[b * istr[0] + c * istr[1] + oh * istr[2] + ow * istr[3]];
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        indices: &Self::Storage<I, usize>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(grad_inp.strides, op.channels_last);
+        let idx_str = make_4d::<I>(indices.strides, op.channels_last);
+        let ostr = make_4d::<O>(grad_out.strides, op.channels_last);
+
+        let ginp_buf = Arc::make_mut(&mut grad_inp.data);
+        let idx_buf = indices.data.as_ref();
+        let gout_buf = grad_out.data.as_ref();
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    for ow in 0..op.w_out {
+                        let i = idx_buf
+                            [b * idx_str[0] + c * idx_str[1] + oh * idx_str[2] + ow * idx_str[3]];
+                        let (y, x) = (i / op.w_in, i % op.w_in);
+                        ginp_buf[b * istr[0] + c * istr[1] + oh * istr[2] + ow * istr[3]] +=
+                            gout_buf[b * ostr[0] + c * ostr[1] + y * ostr[2] + x * ostr[3]];
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
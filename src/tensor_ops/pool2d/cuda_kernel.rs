@@ -11,15 +11,33 @@ const MAX_FWD: &str = "max_pool2d_forward";
 const MAX_BWD: &str = "max_pool2d_backward";
 const MIN_FWD: &str = "min_pool2d_forward";
 const MIN_BWD: &str = "min_pool2d_backward";
-const ALL_FN_NAMES: [&str; 6] = [AVG_FWD, AVG_BWD, MAX_FWD, MAX_BWD, MIN_FWD, MIN_BWD];
+const MAX_IDX_FWD: &str = "max_pool2d_indices_forward";
+const MAX_IDX_BWD: &str = "max_pool2d_indices_backward";
+const UNPOOL_FWD: &str = "max_unpool2d_forward";
+const UNPOOL_BWD: &str = "max_unpool2d_backward";
+const ALL_FN_NAMES: [&str; 10] = [
+    AVG_FWD,
+    AVG_BWD,
+    MAX_FWD,
+    MAX_BWD,
+    MIN_FWD,
+    MIN_BWD,
+    MAX_IDX_FWD,
+    MAX_IDX_BWD,
+    UNPOOL_FWD,
+    UNPOOL_BWD,
+];
 const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/pool2d.ptx"));
 
 unsafe impl AsKernelParam for super::Pool2DOp {}
 
-fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
-    match S::NUM_DIMS {
-        3 => [0, strides[0], strides[1], strides[2]],
-        4 => [strides[0], strides[1], strides[2], strides[3]],
+/// Returns strides in (batch, channel, height, width) order.
+fn make_4d<S: Shape>(strides: S::Concrete, channels_last: bool) -> [usize; 4] {
+    match (S::NUM_DIMS, channels_last) {
+        (3, false) => [0, strides[0], strides[1], strides[2]],
+        (4, false) => [strides[0], strides[1], strides[2], strides[3]],
+        (3, true) => [0, strides[2], strides[0], strides[1]],
+        (4, true) => [strides[0], strides[3], strides[1], strides[2]],
         _ => panic!("Only implemented for 3d & 4d arrays"),
     }
 }
@@ -38,8 +56,12 @@ macro_rules! pool_impl {
                         .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
                 }
 
-                let inp_strides = self.dev.take_async(make_4d::<I>(inp.strides).into())?;
-                let out_strides = self.dev.take_async(make_4d::<O>(out.strides).into())?;
+                let inp_strides = self
+                    .dev
+                    .take_async(make_4d::<I>(inp.strides, op.channels_last).into())?;
+                let out_strides = self
+                    .dev
+                    .take_async(make_4d::<O>(out.strides, op.channels_last).into())?;
                 let fwd_fn = self.dev.get_func(MODULE_NAME, $FwdFn).unwrap();
                 let cfg = LaunchConfig::for_num_elems(out.shape().num_elements() as u32);
                 let params = (
@@ -60,8 +82,12 @@ macro_rules! pool_impl {
                 out: &Self::Storage<O, f32>,
                 grad_out: &Self::Storage<O, f32>,
             ) -> Result<(), Self::Err> {
-                let inp_strides = self.dev.take_async(make_4d::<I>(inp.strides).into())?;
-                let out_strides = self.dev.take_async(make_4d::<O>(out.strides).into())?;
+                let inp_strides = self
+                    .dev
+                    .take_async(make_4d::<I>(inp.strides, op.channels_last).into())?;
+                let out_strides = self
+                    .dev
+                    .take_async(make_4d::<O>(out.strides, op.channels_last).into())?;
                 let bwd_fn = self.dev.get_func(MODULE_NAME, $BwdFn).unwrap();
                 let cfg = LaunchConfig::for_num_elems(grad_inp.shape().num_elements() as u32);
                 let params = (
@@ -83,3 +109,141 @@ macro_rules! pool_impl {
 pool_impl!(super::AvgPool2DKernel<f32>, Fwd = AVG_FWD, Bwd = AVG_BWD);
 pool_impl!(super::MaxPool2DKernel<f32>, Fwd = MAX_FWD, Bwd = MAX_BWD);
 pool_impl!(super::MinPool2DKernel<f32>, Fwd = MIN_FWD, Bwd = MIN_BWD);
+
+impl super::MaxPool2DIndicesKernel<f32> for Cuda {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+        indices: &mut Self::Storage<O, usize>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, MAX_IDX_FWD) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let inp_strides = self
+            .dev
+            .take_async(make_4d::<I>(inp.strides, false).into())?;
+        let out_strides = self
+            .dev
+            .take_async(make_4d::<O>(out.strides, false).into())?;
+        let idx_strides = self
+            .dev
+            .take_async(make_4d::<O>(indices.strides, false).into())?;
+        let fwd_fn = self.dev.get_func(MODULE_NAME, MAX_IDX_FWD).unwrap();
+        let cfg = LaunchConfig::for_num_elems(out.shape().num_elements() as u32);
+        let params = (
+            op,                               // const Pool2dOp op,
+            &inp_strides,                     // const size_t *inp_strides,
+            &out_strides,                     // const size_t *out_strides,
+            &idx_strides,                     // const size_t *idx_strides,
+            inp.data.as_ref(),                // const float *inp,
+            Arc::make_mut(&mut out.data),     // float *out,
+            Arc::make_mut(&mut indices.data), // size_t *indices
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        indices: &Self::Storage<O, usize>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let inp_strides = self
+            .dev
+            .take_async(make_4d::<I>(grad_inp.strides, false).into())?;
+        let out_strides = self
+            .dev
+            .take_async(make_4d::<O>(grad_out.strides, false).into())?;
+        let idx_strides = self
+            .dev
+            .take_async(make_4d::<O>(indices.strides, false).into())?;
+        let bwd_fn = self.dev.get_func(MODULE_NAME, MAX_IDX_BWD).unwrap();
+        let cfg = LaunchConfig::for_num_elems(grad_out.shape().num_elements() as u32);
+        let params = (
+            op,                                // const Pool2dOp op,
+            &inp_strides,                      // const size_t *inp_strides,
+            &out_strides,                      // const size_t *out_strides,
+            &idx_strides,                      // const size_t *idx_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            indices.data.as_ref(),             // const size_t *indices,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
+
+impl super::MaxUnpool2DKernel<f32> for Cuda {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        inp: &Self::Storage<I, f32>,
+        indices: &Self::Storage<I, usize>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, UNPOOL_FWD) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let inp_strides = self
+            .dev
+            .take_async(make_4d::<I>(inp.strides, false).into())?;
+        let out_strides = self
+            .dev
+            .take_async(make_4d::<O>(out.strides, false).into())?;
+        let idx_strides = self
+            .dev
+            .take_async(make_4d::<I>(indices.strides, false).into())?;
+        let fwd_fn = self.dev.get_func(MODULE_NAME, UNPOOL_FWD).unwrap();
+        let cfg = LaunchConfig::for_num_elems(inp.shape().num_elements() as u32);
+        let params = (
+            op,                           // const Pool2dOp op,
+            &inp_strides,                 // const size_t *inp_strides,
+            &out_strides,                 // const size_t *out_strides,
+            &idx_strides,                 // const size_t *idx_strides,
+            inp.data.as_ref(),            // const float *inp,
+            indices.data.as_ref(),        // const size_t *indices,
+            Arc::make_mut(&mut out.data), // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        indices: &Self::Storage<I, usize>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let inp_strides = self
+            .dev
+            .take_async(make_4d::<I>(grad_inp.strides, false).into())?;
+        let out_strides = self
+            .dev
+            .take_async(make_4d::<O>(grad_out.strides, false).into())?;
+        let idx_strides = self
+            .dev
+            .take_async(make_4d::<I>(indices.strides, false).into())?;
+        let bwd_fn = self.dev.get_func(MODULE_NAME, UNPOOL_BWD).unwrap();
+        let cfg = LaunchConfig::for_num_elems(grad_inp.shape().num_elements() as u32);
+        let params = (
+            op,                                // const Pool2dOp op,
+            &inp_strides,                      // const size_t *inp_strides,
+            &out_strides,                      // const size_t *out_strides,
+            &idx_strides,                      // const size_t *idx_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            indices.data.as_ref(),             // const size_t *indices,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -23,6 +23,7 @@ pub struct Pool2DOp {
     pub h_out: usize,
     pub w_in: usize,
     pub w_out: usize,
+    pub channels_last: bool,
 }
 
 impl Pool2DOp {
@@ -37,12 +38,36 @@ impl Pool2DOp {
             h_out: (h_in + 2 * p - k) / s + 1,
             w_in,
             w_out: (w_in + 2 * p - k) / s + 1,
+            channels_last: false,
         }
     }
+
+    /// The op for unpooling `(h_out, w_out)` images back into `(h_in, w_in)` images.
+    /// The kernel isn't needed, since the windows are encoded in the indices.
+    fn unpool([b, c, h_in, w_in]: [usize; 4], [h_out, w_out]: [usize; 2]) -> Self {
+        Self {
+            kernel: 0,
+            stride: 0,
+            padding: 0,
+            batch: b,
+            chan: c,
+            h_in,
+            h_out,
+            w_in,
+            w_out,
+            channels_last: false,
+        }
+    }
+
+    /// Marks the images as stored in (batch, height, width, channel) order.
+    fn with_channels_last(mut self) -> Self {
+        self.channels_last = true;
+        self
+    }
 }
 
 macro_rules! pool2d {
-    (Kernel=$Kernel:ident, ConstTrait=$ConstTrait:ident, TryTrait=$TryTrait:ident, Meth=$Meth:ident, TryMeth=$TryMeth:ident) => {
+    (Kernel=$Kernel:ident, ConstTrait=$ConstTrait:ident, NhwcTrait=$NhwcTrait:ident, TryTrait=$TryTrait:ident, Meth=$Meth:ident, TryMeth=$TryMeth:ident, NhwcMeth=$NhwcMeth:ident, TryNhwcMeth=$TryNhwcMeth:ident) => {
         pub trait $Kernel<E: Dtype>: DeviceStorage {
             fn forward<I: Shape, O: Shape>(
                 &self,
@@ -66,6 +91,12 @@ macro_rules! pool2d {
             fn try_pool2d(self) -> Result<Self::Output, Self::Err>;
         }
 
+        /// Pooling over channels last images, i.e. `(H, W, C)` or `(B, H, W, C)`.
+        pub trait $NhwcTrait<const K: usize, const S: usize, const P: usize>: HasErr {
+            type Output;
+            fn try_pool2d_nhwc(self) -> Result<Self::Output, Self::Err>;
+        }
+
         pub trait $TryTrait {
             fn $Meth<const K: usize, const S: usize, const P: usize>(self) -> Self::Output
             where
@@ -81,6 +112,20 @@ macro_rules! pool2d {
             {
                 self.try_pool2d()
             }
+            fn $NhwcMeth<const K: usize, const S: usize, const P: usize>(self) -> Self::Output
+            where
+                Self: $NhwcTrait<K, S, P>,
+            {
+                self.try_pool2d_nhwc().unwrap()
+            }
+            fn $TryNhwcMeth<const K: usize, const S: usize, const P: usize>(
+                self,
+            ) -> Result<Self::Output, Self::Err>
+            where
+                Self: $NhwcTrait<K, S, P>,
+            {
+                self.try_pool2d_nhwc()
+            }
         }
         impl<T> $TryTrait for T {}
 
@@ -178,33 +223,452 @@ macro_rules! pool2d {
                 Ok(out.put_tape(tape))
             }
         }
+
+        impl<
+                C: Dim,
+                const H: usize,
+                const W: usize,
+                D: $Kernel<f32> + ZerosTensor<f32>,
+                T: 'static + Tape<D>,
+                const K: usize,
+                const S: usize,
+                const P: usize,
+            > $NhwcTrait<K, S, P> for Tensor<(Const<H>, Const<W>, C), f32, D, T>
+        where
+            Const<H>: ConvAlgebra<K, S, P>,
+            Const<W>: ConvAlgebra<K, S, P>,
+        {
+            type Output = Tensor<
+                (
+                    <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+                    <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+                    C,
+                ),
+                f32,
+                D,
+                T,
+            >;
+
+            fn try_pool2d_nhwc(self) -> Result<Self::Output, Self::Err> {
+                let &(_, _, chan) = self.shape();
+                let op = Pool2DOp::new(K, S, P, [1, chan.size(), H, W]).with_channels_last();
+                let (inp, mut tape) = self.split_tape();
+                let mut out =
+                    inp.device
+                        .try_zeros_like(&(Default::default(), Default::default(), chan))?;
+                inp.device.forward(op, &inp.storage, &mut out.storage)?;
+                let phantom_out = out.clone();
+                tape.try_alloc_grad(&inp)?;
+                tape.try_alloc_grad(&out)?;
+                tape.add_backward_op(move |grads| {
+                    let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+                    inp.device
+                        .backward(op, &inp.storage, grad_inp, &phantom_out.storage, grad_out)
+                });
+                Ok(out.put_tape(tape))
+            }
+        }
+
+        impl<
+                B: Dim,
+                C: Dim,
+                const H: usize,
+                const W: usize,
+                D: $Kernel<f32> + ZerosTensor<f32>,
+                T: 'static + Tape<D>,
+                const K: usize,
+                const S: usize,
+                const P: usize,
+            > $NhwcTrait<K, S, P> for Tensor<(B, Const<H>, Const<W>, C), f32, D, T>
+        where
+            Const<H>: ConvAlgebra<K, S, P>,
+            Const<W>: ConvAlgebra<K, S, P>,
+        {
+            type Output = Tensor<
+                (
+                    B,
+                    <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+                    <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+                    C,
+                ),
+                f32,
+                D,
+                T,
+            >;
+
+            fn try_pool2d_nhwc(self) -> Result<Self::Output, Self::Err> {
+                let &(batch, _, _, chan) = self.shape();
+                let op =
+                    Pool2DOp::new(K, S, P, [batch.size(), chan.size(), H, W]).with_channels_last();
+                let (inp, mut tape) = self.split_tape();
+                let mut out = inp.device.try_zeros_like(&(
+                    batch,
+                    Default::default(),
+                    Default::default(),
+                    chan,
+                ))?;
+                inp.device.forward(op, &inp.storage, &mut out.storage)?;
+                let phantom_out = out.clone();
+                tape.try_alloc_grad(&inp)?;
+                tape.try_alloc_grad(&out)?;
+                tape.add_backward_op(move |grads| {
+                    let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+                    inp.device
+                        .backward(op, &inp.storage, grad_inp, &phantom_out.storage, grad_out)
+                });
+                Ok(out.put_tape(tape))
+            }
+        }
     };
 }
 
 pool2d!(
     Kernel = AvgPool2DKernel,
     ConstTrait = ConstAvgPool2D,
+    NhwcTrait = NhwcAvgPool2D,
     TryTrait = TryAvgPool2D,
     Meth = avg_pool2d,
-    TryMeth = try_avg_pool2d
+    TryMeth = try_avg_pool2d,
+    NhwcMeth = avg_pool2d_nhwc,
+    TryNhwcMeth = try_avg_pool2d_nhwc
 );
 
 pool2d!(
     Kernel = MaxPool2DKernel,
     ConstTrait = ConstMaxPool2D,
+    NhwcTrait = NhwcMaxPool2D,
     TryTrait = TryMaxPool2D,
     Meth = max_pool2d,
-    TryMeth = try_max_pool2d
+    TryMeth = try_max_pool2d,
+    NhwcMeth = max_pool2d_nhwc,
+    TryNhwcMeth = try_max_pool2d_nhwc
 );
 
 pool2d!(
     Kernel = MinPool2DKernel,
     ConstTrait = ConstMinPool2D,
+    NhwcTrait = NhwcMinPool2D,
     TryTrait = TryMinPool2D,
     Meth = min_pool2d,
-    TryMeth = try_min_pool2d
+    TryMeth = try_min_pool2d,
+    NhwcMeth = min_pool2d_nhwc,
+    TryNhwcMeth = try_min_pool2d_nhwc
 );
 
+/// Max pooling that also records where the maximum of each window is, as an index
+/// into the flattened `(height, width)` plane of the input.
+pub trait MaxPool2DIndicesKernel<E: Dtype>: DeviceStorage {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pool2DOp,
+        inp: &Self::Storage<I, E>,
+        out: &mut Self::Storage<O, E>,
+        indices: &mut Self::Storage<O, usize>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pool2DOp,
+        grad_inp: &mut Self::Storage<I, E>,
+        indices: &Self::Storage<O, usize>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Places every element of the pooled images at its index in the `(h_in, w_in)` plane
+/// described by `op`, and zeros everywhere else.
+pub trait MaxUnpool2DKernel<E: Dtype>: DeviceStorage {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pool2DOp,
+        inp: &Self::Storage<I, E>,
+        indices: &Self::Storage<I, usize>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pool2DOp,
+        grad_inp: &mut Self::Storage<I, E>,
+        indices: &Self::Storage<I, usize>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+pub trait ConstMaxPool2DWithIndices<const K: usize, const S: usize, const P: usize>:
+    HasErr
+{
+    type Output;
+    type Indices;
+    fn try_pool2d_with_indices(self) -> Result<(Self::Output, Self::Indices), Self::Err>;
+}
+
+pub trait ConstMaxUnpool2D<const H: usize, const W: usize>: HasErr {
+    type Output;
+    type Indices;
+    fn try_unpool2d(self, indices: Self::Indices) -> Result<Self::Output, Self::Err>;
+}
+
+/// Max pooling over `(C, H, W)` or `(B, C, H, W)` images, that also returns the position of each
+/// maximum. Use with [TryMaxUnpool2D] to build encoder/decoder networks like
+/// [SegNet](https://arxiv.org/abs/1511.00561).
+///
+/// The indices have the same shape as the pooled images, and index into the flattened
+/// `(H, W)` plane of their channel, i.e. `y * W + x`. If a window has multiple maximums,
+/// the first one is used, and only it receives gradient.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x = dev.tensor([[[1.0, 2.0], [4.0, 3.0]]]);
+/// let (y, idx) = x.max_pool2d_with_indices::<2, 2, 0>();
+/// assert_eq!(y.array(), [[[4.0]]]);
+/// assert_eq!(idx.array(), [[[2]]]);
+/// ```
+pub trait TryMaxPool2DWithIndices {
+    #[allow(clippy::type_complexity)]
+    fn max_pool2d_with_indices<const K: usize, const S: usize, const P: usize>(
+        self,
+    ) -> (Self::Output, Self::Indices)
+    where
+        Self: ConstMaxPool2DWithIndices<K, S, P>,
+    {
+        self.try_pool2d_with_indices().unwrap()
+    }
+    #[allow(clippy::type_complexity)]
+    fn try_max_pool2d_with_indices<const K: usize, const S: usize, const P: usize>(
+        self,
+    ) -> Result<(Self::Output, Self::Indices), Self::Err>
+    where
+        Self: ConstMaxPool2DWithIndices<K, S, P>,
+    {
+        self.try_pool2d_with_indices()
+    }
+}
+impl<T> TryMaxPool2DWithIndices for T {}
+
+/// Inverse of [TryMaxPool2DWithIndices]: places every element of the pooled images at the
+/// position in `indices`, in images of size `(H, W)` that are zero everywhere else.
+///
+/// Each index must be less than `H * W`. The indices of overlapping windows
+/// (i.e. stride < kernel) may repeat, in which case only one of the values is kept.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x = dev.tensor([[[1.0, 2.0], [4.0, 3.0]]]);
+/// let (y, idx) = x.max_pool2d_with_indices::<2, 2, 0>();
+/// let r = y.max_unpool2d::<2, 2>(idx);
+/// assert_eq!(r.array(), [[[0.0, 0.0], [4.0, 0.0]]]);
+/// ```
+pub trait TryMaxUnpool2D {
+    fn max_unpool2d<const H: usize, const W: usize>(self, indices: Self::Indices) -> Self::Output
+    where
+        Self: ConstMaxUnpool2D<H, W>,
+    {
+        self.try_unpool2d(indices).unwrap()
+    }
+    fn try_max_unpool2d<const H: usize, const W: usize>(
+        self,
+        indices: Self::Indices,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: ConstMaxUnpool2D<H, W>,
+    {
+        self.try_unpool2d(indices)
+    }
+}
+impl<T> TryMaxUnpool2D for T {}
+
+impl<
+        C: Dim,
+        const H: usize,
+        const W: usize,
+        D: MaxPool2DIndicesKernel<f32> + ZerosTensor<f32> + ZerosTensor<usize>,
+        T: 'static + Tape<D>,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+    > ConstMaxPool2DWithIndices<K, S, P> for Tensor<(C, Const<H>, Const<W>), f32, D, T>
+where
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            C,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+    type Indices = Tensor<
+        (
+            C,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        usize,
+        D,
+    >;
+
+    fn try_pool2d_with_indices(self) -> Result<(Self::Output, Self::Indices), Self::Err> {
+        let &(chan, _, _) = self.shape();
+        let op = Pool2DOp::new(K, S, P, [1, chan.size(), H, W]);
+        let out_shape = (chan, Default::default(), Default::default());
+        let (inp, mut tape) = self.split_tape();
+        let mut out: Tensor<_, f32, D> = inp.device.try_zeros_like(&out_shape)?;
+        let mut indices: Self::Indices = inp.device.try_zeros_like(&out_shape)?;
+        inp.device
+            .forward(op, &inp.storage, &mut out.storage, &mut indices.storage)?;
+        let phantom_out = out.clone();
+        let phantom_indices = indices.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(op, grad_inp, &phantom_indices.storage, grad_out)
+        });
+        Ok((out.put_tape(tape), indices))
+    }
+}
+
+impl<
+        B: Dim,
+        C: Dim,
+        const H: usize,
+        const W: usize,
+        D: MaxPool2DIndicesKernel<f32> + ZerosTensor<f32> + ZerosTensor<usize>,
+        T: 'static + Tape<D>,
+        const K: usize,
+        const S: usize,
+        const P: usize,
+    > ConstMaxPool2DWithIndices<K, S, P> for Tensor<(B, C, Const<H>, Const<W>), f32, D, T>
+where
+    Const<H>: ConvAlgebra<K, S, P>,
+    Const<W>: ConvAlgebra<K, S, P>,
+{
+    type Output = Tensor<
+        (
+            B,
+            C,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        f32,
+        D,
+        T,
+    >;
+    type Indices = Tensor<
+        (
+            B,
+            C,
+            <Const<H> as ConvAlgebra<K, S, P>>::Convolved,
+            <Const<W> as ConvAlgebra<K, S, P>>::Convolved,
+        ),
+        usize,
+        D,
+    >;
+
+    fn try_pool2d_with_indices(self) -> Result<(Self::Output, Self::Indices), Self::Err> {
+        let &(batch, chan, _, _) = self.shape();
+        let op = Pool2DOp::new(K, S, P, [batch.size(), chan.size(), H, W]);
+        let out_shape = (batch, chan, Default::default(), Default::default());
+        let (inp, mut tape) = self.split_tape();
+        let mut out: Tensor<_, f32, D> = inp.device.try_zeros_like(&out_shape)?;
+        let mut indices: Self::Indices = inp.device.try_zeros_like(&out_shape)?;
+        inp.device
+            .forward(op, &inp.storage, &mut out.storage, &mut indices.storage)?;
+        let phantom_out = out.clone();
+        let phantom_indices = indices.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(op, grad_inp, &phantom_indices.storage, grad_out)
+        });
+        Ok((out.put_tape(tape), indices))
+    }
+}
+
+impl<
+        C: Dim,
+        Hp: Dim,
+        Wp: Dim,
+        const H: usize,
+        const W: usize,
+        D: MaxUnpool2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > ConstMaxUnpool2D<H, W> for Tensor<(C, Hp, Wp), f32, D, T>
+{
+    type Output = Tensor<(C, Const<H>, Const<W>), f32, D, T>;
+    type Indices = Tensor<(C, Hp, Wp), usize, D>;
+
+    fn try_unpool2d(self, indices: Self::Indices) -> Result<Self::Output, Self::Err> {
+        ShapeMismatch::check_same("max_unpool2d", &self, &indices)?;
+        let &(chan, h_out, w_out) = self.shape();
+        let op = Pool2DOp::unpool([1, chan.size(), H, W], [h_out.size(), w_out.size()]);
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp
+            .device
+            .try_zeros_like(&(chan, Default::default(), Default::default()))?;
+        inp.device
+            .forward(op, &inp.storage, &indices.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(op, grad_inp, &indices.storage, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        C: Dim,
+        Hp: Dim,
+        Wp: Dim,
+        const H: usize,
+        const W: usize,
+        D: MaxUnpool2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+    > ConstMaxUnpool2D<H, W> for Tensor<(B, C, Hp, Wp), f32, D, T>
+{
+    type Output = Tensor<(B, C, Const<H>, Const<W>), f32, D, T>;
+    type Indices = Tensor<(B, C, Hp, Wp), usize, D>;
+
+    fn try_unpool2d(self, indices: Self::Indices) -> Result<Self::Output, Self::Err> {
+        ShapeMismatch::check_same("max_unpool2d", &self, &indices)?;
+        let &(batch, chan, h_out, w_out) = self.shape();
+        let op = Pool2DOp::unpool(
+            [batch.size(), chan.size(), H, W],
+            [h_out.size(), w_out.size()],
+        );
+        let (inp, mut tape) = self.split_tape();
+        let mut out =
+            inp.device
+                .try_zeros_like(&(batch, chan, Default::default(), Default::default()))?;
+        inp.device
+            .forward(op, &inp.storage, &indices.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device
+                .backward(op, grad_inp, &indices.storage, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,4 +776,126 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_pool2d_nhwc_matches_nchw() {
+        let dev = TestDevice::seed_from_u64(233);
+        let x: Tensor<Rank4<2, 5, 4, 3>, f32, _> = dev.sample_normal();
+        let x_nchw = dev.tensor(x.clone().permute::<_, Axes4<0, 3, 1, 2>>().array());
+
+        let r = x.trace().max_pool2d_nhwc::<2, 2, 1>();
+        let r_nchw = x_nchw.trace().max_pool2d::<2, 2, 1>();
+        assert_close(
+            &r.array(),
+            &dev.tensor(r_nchw.array())
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+
+        let g = r.exp().mean().backward();
+        let g_nchw = r_nchw.exp().mean().backward();
+        assert_close(
+            &g.get(&x).array(),
+            &dev.tensor(g_nchw.get(&x_nchw).array())
+                .permute::<_, Axes4<0, 2, 3, 1>>()
+                .array(),
+        );
+    }
+
+    #[test]
+    fn test_pool2d_3d_avg2d_nhwc() {
+        let dev = TestDevice::seed_from_u64(234);
+        let x: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let r = x
+            .clone()
+            .permute::<_, Axes3<1, 2, 0>>()
+            .avg_pool2d_nhwc::<2, 2, 0>();
+        assert_close(
+            &r.permute::<_, Axes3<2, 0, 1>>().array(),
+            &x.avg_pool2d::<2, 2, 0>().array(),
+        );
+    }
+
+    #[test]
+    fn test_max_pool2d_with_indices_matches_max_pool2d() {
+        let dev = TestDevice::seed_from_u64(234);
+        let x: Tensor<Rank4<2, 3, 5, 4>, f32, _> = dev.sample_normal();
+        let (r, idx) = x.trace().max_pool2d_with_indices::<2, 2, 1>();
+        let r2 = x.trace().max_pool2d::<2, 2, 1>();
+        assert_eq!(r.array(), r2.array());
+
+        // the indices point at the maximums
+        let flat = x.clone().reshape::<Rank3<2, 3, 20>>();
+        let gathered = flat.gather(idx.reshape::<Rank3<2, 3, 9>>());
+        assert_eq!(
+            gathered.array(),
+            x.clone()
+                .max_pool2d::<2, 2, 1>()
+                .reshape::<Rank3<2, 3, 9>>()
+                .array()
+        );
+
+        // there are no ties, so gradients match too
+        let g = r.exp().mean().backward();
+        let g2 = r2.exp().mean().backward();
+        assert_close(&g.get(&x).array(), &g2.get(&x).array());
+    }
+
+    #[test]
+    fn test_max_pool2d_with_indices_ties() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0f32, 1., 0.5, 0.2], [0.2, 0.2, 0.5, 1.2]]]);
+        let (r, idx) = x.trace().max_pool2d_with_indices::<2, 1, 0>();
+        assert_eq!(r.array(), [[[1., 1., 1.2]]]);
+        assert_eq!(idx.array(), [[[0, 1, 7]]]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [[[1., 1., 0., 0.], [0., 0., 0., 1.]]]);
+    }
+
+    #[test]
+    fn test_max_unpool2d() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[
+            [1.0, 2.0, 5.0, 0.0],
+            [4.0, 3.0, -1.0, 6.0],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 7.0, -2.0, -3.0],
+        ]]]);
+        let (y, idx) = x.max_pool2d_with_indices::<2, 2, 0>();
+        assert_eq!(y.array(), [[[[4.0, 6.0], [7.0, 0.0]]]]);
+        assert_eq!(idx.array(), [[[[4, 7], [13, 10]]]]);
+
+        let r = y.trace().max_unpool2d::<4, 4>(idx);
+        assert_eq!(
+            r.array(),
+            [[[
+                [0.0, 0.0, 0.0, 0.0],
+                [4.0, 0.0, 0.0, 6.0],
+                [0.0, 0.0, 0.0, 0.0],
+                [0.0, 7.0, 0.0, 0.0],
+            ]]]
+        );
+
+        let w = dev.tensor([[[
+            [1.0, 2.0, 3.0, 4.0],
+            [5.0, 6.0, 7.0, 8.0],
+            [9.0, 10.0, 11.0, 12.0],
+            [13.0, 14.0, 15.0, 16.0],
+        ]]]);
+        let g = (r * w).sum().backward();
+        assert_eq!(g.get(&y).array(), [[[[5.0, 8.0], [14.0, 11.0]]]]);
+    }
+
+    #[test]
+    fn test_max_unpool2d_3d() {
+        let dev = TestDevice::seed_from_u64(3);
+        let x: Tensor<Rank3<2, 4, 6>, f32, _> = dev.sample_normal();
+        let (y, idx) = x.clone().max_pool2d_with_indices::<2, 2, 0>();
+        let r = y.max_unpool2d::<4, 6>(idx);
+        // every kept element is either the original value or zero
+        for (a, b) in r.as_vec().into_iter().zip(x.as_vec()) {
+            assert!(a == 0.0 || a == b);
+        }
+        assert_eq!(r.as_vec().iter().filter(|&&a| a != 0.0).count(), 2 * 2 * 3);
+    }
 }
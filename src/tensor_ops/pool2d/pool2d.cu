@@ -8,6 +8,7 @@ struct Pool2dOp {
     size_t h_out;
     size_t w_in;
     size_t w_out;
+    bool channels_last;
 };
 
 extern "C" __global__ void avg_pool2d_forward(
@@ -51,7 +52,8 @@ extern "C" __global__ void avg_pool2d_forward(
     }
 
     tmp /= static_cast<float>(op.kernel * op.kernel);
-    out[i] = tmp;
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    out[out_i] = tmp;
 }
 
 extern "C" __global__ void avg_pool2d_backward(
@@ -79,6 +81,8 @@ extern "C" __global__ void avg_pool2d_backward(
     const size_t b = idx % op.batch;
     idx /= op.batch;
 
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+
     float tmp = 0.0;
     for(size_t k1 = 0; k1 < op.kernel; k1++) {
         for (size_t k2 = 0; k2 < op.kernel; k2++) {
@@ -101,7 +105,7 @@ extern "C" __global__ void avg_pool2d_backward(
         }
     }
 
-    grad_inp[i] += tmp / static_cast<float>(op.kernel * op.kernel);
+    grad_inp[inp_i] += tmp / static_cast<float>(op.kernel * op.kernel);
 }
 
 extern "C" __global__ void max_pool2d_forward(
@@ -144,7 +148,8 @@ extern "C" __global__ void max_pool2d_forward(
         }
     }
 
-    out[i] = tmp;
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    out[out_i] = tmp;
 }
 
 extern "C" __global__ void max_pool2d_backward(
@@ -172,7 +177,8 @@ extern "C" __global__ void max_pool2d_backward(
     const size_t b = idx % op.batch;
     idx /= op.batch;
 
-    const float inp_v = inp[i];
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+    const float inp_v = inp[inp_i];
 
     float tmp = 0.0;
     for(size_t k1 = 0; k1 < op.kernel; k1++) {
@@ -199,7 +205,7 @@ extern "C" __global__ void max_pool2d_backward(
         }
     }
 
-    grad_inp[i] += tmp;
+    grad_inp[inp_i] += tmp;
 }
 
 
@@ -243,7 +249,8 @@ extern "C" __global__ void min_pool2d_forward(
         }
     }
 
-    out[i] = tmp;
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    out[out_i] = tmp;
 }
 
 extern "C" __global__ void min_pool2d_backward(
@@ -271,7 +278,8 @@ extern "C" __global__ void min_pool2d_backward(
     const size_t b = idx % op.batch;
     idx /= op.batch;
 
-    const float inp_v = inp[i];
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+    const float inp_v = inp[inp_i];
 
     float tmp = 0.0;
     for(size_t k1 = 0; k1 < op.kernel; k1++) {
@@ -298,5 +306,166 @@ extern "C" __global__ void min_pool2d_backward(
         }
     }
 
-    grad_inp[i] += tmp;
+    grad_inp[inp_i] += tmp;
+}
+
+extern "C" __global__ void max_pool2d_indices_forward(
+    const Pool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const size_t *idx_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *out, // 4d (Batch, Channels, HeightOut, WidthOut)
+    size_t *indices // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    float tmp = -INFINITY;
+    size_t argmax = 0;
+    bool found = false;
+    for(size_t k1 = 0; k1 < op.kernel; k1++) {
+        for (size_t k2 = 0; k2 < op.kernel; k2++) {
+            const size_t y_plus_p = oh * op.stride + k1;
+            if (y_plus_p < op.padding) { continue; }
+            const size_t y = y_plus_p - op.padding;
+            if (y >= op.h_in) { continue; }
+            const size_t x_plus_p = ow * op.stride + k2;
+            if (x_plus_p < op.padding) { continue; }
+            const size_t x = x_plus_p - op.padding;
+            if (x >= op.w_in) { continue; }
+
+            auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+            const float v = inp[inp_i];
+            if (!found || v > tmp) {
+                tmp = v;
+                argmax = y * op.w_in + x;
+                found = true;
+            }
+        }
+    }
+
+    out[b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3]] = tmp;
+    indices[b * idx_strides[0] + c * idx_strides[1] + oh * idx_strides[2] + ow * idx_strides[3]] = argmax;
+}
+
+extern "C" __global__ void max_pool2d_indices_backward(
+    const Pool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const size_t *idx_strides,
+    float *grad_inp, // 4d (Batch, Channels, Height, Width)
+    const size_t *indices, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const float *grad_out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t argmax = indices[b * idx_strides[0] + c * idx_strides[1] + oh * idx_strides[2] + ow * idx_strides[3]];
+    const size_t y = argmax / op.w_in;
+    const size_t x = argmax % op.w_in;
+
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+    auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+    atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+}
+
+extern "C" __global__ void max_unpool2d_forward(
+    const Pool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const size_t *idx_strides,
+    const float *inp, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const size_t *indices, // 4d (Batch, Channels, HeightOut, WidthOut)
+    float *out // 4d (Batch, Channels, Height, Width)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t target = indices[b * idx_strides[0] + c * idx_strides[1] + oh * idx_strides[2] + ow * idx_strides[3]];
+    if (target >= op.h_in * op.w_in) {
+        return;
+    }
+    const size_t y = target / op.w_in;
+    const size_t x = target % op.w_in;
+
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + oh * inp_strides[2] + ow * inp_strides[3];
+    auto out_i = b * out_strides[0] + c * out_strides[1] + y * out_strides[2] + x * out_strides[3];
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void max_unpool2d_backward(
+    const Pool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const size_t *idx_strides,
+    float *grad_inp, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const size_t *indices, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const float *grad_out // 4d (Batch, Channels, Height, Width)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % op.w_out;
+    idx /= op.w_out;
+    const size_t oh = idx % op.h_out;
+    idx /= op.h_out;
+    const size_t c = idx % op.chan;
+    idx /= op.chan;
+    const size_t b = idx % op.batch;
+    idx /= op.batch;
+
+    const size_t target = indices[b * idx_strides[0] + c * idx_strides[1] + oh * idx_strides[2] + ow * idx_strides[3]];
+    if (target >= op.h_in * op.w_in) {
+        return;
+    }
+    const size_t y = target / op.w_in;
+    const size_t x = target % op.w_in;
+
+    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + oh * inp_strides[2] + ow * inp_strides[3];
+    auto out_i = b * out_strides[0] + c * out_strides[1] + y * out_strides[2] + x * out_strides[3];
+    grad_inp[inp_i] += grad_out[out_i];
 }
@@ -0,0 +1,71 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+impl super::ProdReduceKernel<f32> for Cpu {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out: StridedArray<Dst, f32> = StridedArray::try_new_with(dst, 1.0)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some((out_i, inp_i)) = out_iter.next().zip(inp_iter.next()) {
+            *out_i *= *inp_i;
+        }
+        Ok(out)
+    }
+
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        // the product of the non zero values, and the number of zeros, of each output
+        let mut nonzero: StridedArray<Dst, f32> = StridedArray::try_new_with(grad_out.shape, 1.0)?;
+        let mut zeros: StridedArray<Dst, usize> = StridedArray::new(grad_out.shape)?;
+        {
+            let mut inp_iter = inp.iter();
+            let mut nonzero_iter = nonzero.iter_mut_as(&inp.shape);
+            let mut zeros_iter = zeros.iter_mut_as(&inp.shape);
+            for _ in 0..inp.shape.num_elements() {
+                let x = *inp_iter.next().unwrap();
+                let p = nonzero_iter.next().unwrap();
+                let z = zeros_iter.next().unwrap();
+                if x == 0.0 {
+                    *z += 1;
+                } else {
+                    *p *= x;
+                }
+            }
+        }
+
+        let mut inp_iter = inp.iter();
+        let mut grad_inp_iter = grad_inp.iter_mut();
+        let mut nonzero_iter = nonzero.iter_as(&inp.shape);
+        let mut zeros_iter = zeros.iter_as(&inp.shape);
+        let mut grad_out_iter = grad_out.iter_as(&inp.shape);
+        for _ in 0..inp.shape.num_elements() {
+            let x = *inp_iter.next().unwrap();
+            let p = *nonzero_iter.next().unwrap();
+            let z = *zeros_iter.next().unwrap();
+            let go = *grad_out_iter.next().unwrap();
+            let d = match (z, x == 0.0) {
+                (0, _) => p / x,
+                (1, true) => p,
+                _ => 0.0,
+            };
+            *grad_inp_iter.next().unwrap() += go * d;
+        }
+        Ok(())
+    }
+}
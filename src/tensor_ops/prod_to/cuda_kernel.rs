@@ -0,0 +1,49 @@
+use crate::{
+    shapes::{Axes, ReduceShapeTo, Shape},
+    tensor::cuda::{Cuda, CudaArray},
+};
+
+use std::sync::Arc;
+
+/// There are no cuda kernels yet, so the products are computed on the host.
+impl super::ProdReduceKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let cpu_out = super::ProdReduceKernel::<f32>::forward(&self.cpu, dst, &cpu_inp)?;
+        let mut out = CudaArray {
+            data: Arc::new(self.dev.alloc_zeros_async::<f32>(dst.num_elements())?),
+            shape: dst,
+            strides: dst.strides(),
+        };
+        self.storage_from_cpu(&mut out, &cpu_out)?;
+        Ok(out)
+    }
+
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, f32>,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+        super::ProdReduceKernel::<f32>::backward(
+            &self.cpu,
+            &cpu_inp,
+            &mut cpu_grad_inp,
+            &cpu_grad_out,
+        )?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
@@ -0,0 +1,150 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{gradients::Tape, shapes::*, tensor::*};
+
+pub trait ProdReduceKernel<E: Dtype>: DeviceStorage {
+    fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+    fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        inp: &Self::Storage<Src, E>,
+        grad_inp: &mut Self::Storage<Src, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+}
+
+/// Reduction along multiple axes using `*`.
+pub trait ProdTo: HasErr + HasShape {
+    /// Product reduction. **Pytorch equivalent**: `t.prod(Ax)`
+    ///
+    /// The gradient of each value is the product of all the other values it was multiplied
+    /// with, so it is correct even when the input contains zeros.
+    ///
+    /// Example reducing a single axis:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 3>, f32, _> = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+    /// let r = t.prod::<Rank1<2>, _>(); // or `prod::<_, Axis<1>>()`
+    /// assert_eq!(r.array(), [6.0, -2.0]);
+    /// ```
+    ///
+    /// Reducing multiple axes:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// # let t = dev.tensor([[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]]);
+    /// let r = t.prod::<Rank0, _>();
+    /// assert_eq!(r.array(), -12.0);
+    /// ```
+    fn prod<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_prod().unwrap()
+    }
+    /// Fallible version of [ProdTo::prod]
+    fn try_prod<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+}
+
+impl<S: Shape, E: Dtype, D: ProdReduceKernel<E>, T: Tape<D>> ProdTo for Tensor<S, E, D, T> {
+    fn try_prod<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let (inp, mut tape) = self.split_tape();
+        let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(&inp.storage, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor_ops::*;
+    use crate::tests::{assert_close, TestDevice};
+
+    #[test]
+    fn test_prod_valid_axes() {
+        let dev: TestDevice = Default::default();
+        let _ = dev.zeros::<Rank1<5>>().prod::<Rank0, _>();
+        let _ = dev.zeros::<Rank2<5, 3>>().prod::<Rank1<3>, _>();
+        let _ = dev.zeros::<Rank2<5, 3>>().prod::<Rank1<5>, _>();
+        let _ = dev.zeros::<Rank3<7, 5, 3>>().prod::<Rank2<5, 3>, _>();
+        let _ = dev.zeros::<Rank3<7, 5, 3>>().prod::<Rank2<7, 3>, _>();
+        let _ = dev.zeros::<Rank3<7, 5, 3>>().prod::<Rank2<7, 5>, _>();
+    }
+
+    #[test]
+    fn test_prod_axis_0_2d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, -3.0], [4.0, 0.5, 2.0]]);
+        let r = t.trace().prod::<Rank1<3>, _>();
+        assert_eq!(r.array(), [4.0, 1.0, -6.0]);
+        let g = r.exp().sum().backward();
+        let e = f32::exp;
+        assert_close(
+            &g.get(&t).array(),
+            &[
+                [4.0 * e(4.0), 0.5 * e(1.0), 2.0 * e(-6.0)],
+                [1.0 * e(4.0), 2.0 * e(1.0), -3.0 * e(-6.0)],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_prod_with_zeros() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[2.0, 0.0, 3.0], [0.0, 0.0, 5.0], [1.0, 2.0, 4.0]]);
+        let r = t.trace().prod::<_, Axis<1>>();
+        assert_eq!(r.array(), [0.0, 0.0, 8.0]);
+        let g = r.sum().backward();
+        // one zero: only the zero gets a gradient. two zeros: nothing does.
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 6.0, 0.0], [0.0, 0.0, 0.0], [8.0, 4.0, 2.0]]
+        );
+    }
+
+    #[test]
+    fn test_prod_axes_3d_to_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.sample_normal::<Rank3<2, 3, 4>>();
+        let r = t.trace().prod::<Rank1<4>, _>();
+        let r2 = t.trace().prod::<Rank2<3, 4>, _>().prod::<Rank1<4>, _>();
+        assert_close(&r.array(), &r2.array());
+        let g = r.sum().backward();
+        let g2 = r2.sum().backward();
+        assert_close(&g.get(&t).array(), &g2.get(&t).array());
+    }
+
+    #[test]
+    fn test_prod_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([2.0, -1.0, 0.5]);
+        let r = t.trace().broadcast::<Rank2<2, 3>, _>().prod::<Rank0, _>();
+        assert_eq!(r.array(), 1.0);
+        let g = r.backward();
+        assert_eq!(g.get(&t).array(), [1.0, -2.0, 4.0]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+/// The ranks of the two values `q` interpolates between, and the weight of the upper one.
+fn ranks(q: f32, n: usize) -> (usize, usize, f32) {
+    let pos = q * (n - 1) as f32;
+    let lo = (pos.floor() as usize).min(n - 1);
+    let hi = (lo + 1).min(n - 1);
+    (lo, hi, pos - lo as f32)
+}
+
+/// Strides of a tensor reduced along `ax`, with a 0 stride inserted for `ax`.
+fn expand_strides(strides: impl Into<Vec<usize>>, ax: usize) -> Vec<usize> {
+    let mut strides = strides.into();
+    strides.insert(ax, 0);
+    strides
+}
+
+impl super::QuantileKernel<f32> for Cpu {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<
+        (
+            Self::Storage<Dst, f32>,
+            Self::Storage<Dst, usize>,
+            Self::Storage<Dst, usize>,
+        ),
+        Self::Err,
+    > {
+        let mut values: StridedArray<Dst, f32> = StridedArray::new(dst)?;
+        let mut lo_idx: StridedArray<Dst, usize> = StridedArray::new(dst)?;
+        let mut hi_idx: StridedArray<Dst, usize> = StridedArray::new(dst)?;
+        let n = inp.shape.concrete()[ax];
+        let (lo, hi, frac) = ranks(q, n);
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let dst_strides = expand_strides(values.strides, ax);
+        let values_buf = Arc::make_mut(&mut values.data);
+        let lo_buf = Arc::make_mut(&mut lo_idx.data);
+        let hi_buf = Arc::make_mut(&mut hi_idx.data);
+        let mut row: Vec<usize> = Vec::with_capacity(n);
+        for_each_row(&dims, ax, [&inp_strides, &dst_strides], |[i_inp, i_dst]| {
+            let value = |j: usize| inp.data[i_inp + j * inp_strides[ax]];
+            row.clear();
+            row.extend(0..n);
+            row.sort_by(|&a, &b| {
+                value(a)
+                    .partial_cmp(&value(b))
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            });
+            let (v_lo, v_hi) = (value(row[lo]), value(row[hi]));
+            values_buf[i_dst] = if frac == 0.0 {
+                v_lo
+            } else {
+                v_lo + frac * (v_hi - v_lo)
+            };
+            lo_buf[i_dst] = row[lo];
+            hi_buf[i_dst] = row[hi];
+        });
+        Ok((values, lo_idx, hi_idx))
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        lo: &Self::Storage<Dst, usize>,
+        hi: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        let (_, _, frac) = ranks(q, grad_inp.shape.concrete()[ax]);
+        let dims: Vec<usize> = grad_inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = grad_inp.strides.into();
+        let lo_strides = expand_strides(lo.strides, ax);
+        let hi_strides = expand_strides(hi.strides, ax);
+        let out_strides = expand_strides(grad_out.strides, ax);
+        let grad_inp_buf = Arc::make_mut(&mut grad_inp.data);
+        for_each_row(
+            &dims,
+            ax,
+            [&inp_strides, &lo_strides, &hi_strides, &out_strides],
+            |[i_inp, i_lo, i_hi, i_out]| {
+                let g = grad_out.data[i_out];
+                grad_inp_buf[i_inp + lo.data[i_lo] * inp_strides[ax]] += g * (1.0 - frac);
+                grad_inp_buf[i_inp + hi.data[i_hi] * inp_strides[ax]] += g * frac;
+            },
+        );
+        Ok(())
+    }
+}
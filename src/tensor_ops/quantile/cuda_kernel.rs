@@ -0,0 +1,73 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+
+use std::sync::Arc;
+
+/// There are no cuda kernels yet, so the quantiles are computed on the host.
+impl super::QuantileKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<
+        (
+            Self::Storage<Dst, f32>,
+            Self::Storage<Dst, usize>,
+            Self::Storage<Dst, usize>,
+        ),
+        Self::Err,
+    > {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let (cpu_values, cpu_lo, cpu_hi) =
+            super::QuantileKernel::<f32>::forward(&self.cpu, ax, q, dst, &cpu_inp)?;
+        let numel = dst.num_elements();
+        let mut values = CudaArray {
+            data: Arc::new(self.dev.alloc_zeros_async::<f32>(numel)?),
+            shape: dst,
+            strides: dst.strides(),
+        };
+        let mut lo = CudaArray {
+            data: Arc::new(self.dev.alloc_zeros_async::<usize>(numel)?),
+            shape: dst,
+            strides: dst.strides(),
+        };
+        let mut hi = CudaArray {
+            data: Arc::new(self.dev.alloc_zeros_async::<usize>(numel)?),
+            shape: dst,
+            strides: dst.strides(),
+        };
+        self.storage_from_cpu(&mut values, &cpu_values)?;
+        self.storage_from_cpu(&mut lo, &cpu_lo)?;
+        self.storage_from_cpu(&mut hi, &cpu_hi)?;
+        Ok((values, lo, hi))
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        lo: &Self::Storage<Dst, usize>,
+        hi: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+        let cpu_lo = self.storage_to_cpu(lo)?;
+        let cpu_hi = self.storage_to_cpu(hi)?;
+        let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+        super::QuantileKernel::<f32>::backward(
+            &self.cpu,
+            ax,
+            q,
+            &mut cpu_grad_inp,
+            &cpu_lo,
+            &cpu_hi,
+            &cpu_grad_out,
+        )?;
+        self.storage_from_cpu(grad_inp, &cpu_grad_inp)
+    }
+}
@@ -0,0 +1,225 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::{Axes, Dtype, HasShape, ReduceShape, ReduceStridesTo, Shape},
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait QuantileKernel<E: Dtype>: DeviceStorage {
+    /// The `q`-th quantile along axis `ax`, linearly interpolated between the two closest
+    /// ranks, along with the indices of those two values.
+    #[allow(clippy::type_complexity)]
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<
+        (
+            Self::Storage<Dst, E>,
+            Self::Storage<Dst, usize>,
+            Self::Storage<Dst, usize>,
+        ),
+        Self::Err,
+    >;
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        q: f32,
+        grad_inp: &mut Self::Storage<Src, E>,
+        lo: &Self::Storage<Dst, usize>,
+        hi: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The `q`-th quantile along `Ax`, where `q` is in `[0, 1]`. **Pytorch equivalent**: `t.quantile(q, dim)`
+///
+/// Values between two ranks are linearly interpolated, which is the default of both pytorch
+/// and numpy: for `n` values the `q`-th quantile sits at rank `q * (n - 1)` of the sorted values.
+///
+/// The gradient is split between the two values that were interpolated, in proportion to their
+/// interpolation weights. The order of NaNs is unspecified.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[3.0, 1.0, 2.0], [10.0, 40.0, 20.0]]);
+/// assert_eq!(t.quantile::<Axis<1>>(0.75).array(), [2.5, 30.0]);
+/// ```
+///
+/// **Panics** if `q` is outside of `[0, 1]`, or if the axis is empty.
+pub fn quantile<
+    Ax: Axes<Array = [isize; 1]>,
+    S: Shape,
+    E: Dtype,
+    D: QuantileKernel<E>,
+    T: Tape<D>,
+>(
+    t: Tensor<S, E, D, T>,
+    q: f32,
+) -> Tensor<S::Reduced, E, D, T>
+where
+    S: ReduceShape<Ax>,
+{
+    t.quantile::<Ax>(q)
+}
+
+/// The median along `Ax`, which is [quantile()] with `q = 0.5`. The median of an even number
+/// of values is the mean of the two middle ones.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[3.0, 1.0, 2.0], [10.0, 40.0, 20.0]]);
+/// assert_eq!(t.clone().median::<Axis<1>>().array(), [2.0, 20.0]);
+/// assert_eq!(t.median::<Axis<0>>().array(), [6.5, 20.5, 11.0]);
+/// ```
+pub fn median<Ax: Axes<Array = [isize; 1]>, S: Shape, E: Dtype, D: QuantileKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S::Reduced, E, D, T>
+where
+    S: ReduceShape<Ax>,
+{
+    t.median::<Ax>()
+}
+
+impl<S: Shape, E: Dtype, D: QuantileKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [quantile]
+    pub fn quantile<Ax: Axes<Array = [isize; 1]>>(self, q: f32) -> Tensor<S::Reduced, E, D, T>
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_quantile::<Ax>(q).unwrap()
+    }
+
+    /// See [quantile]
+    pub fn try_quantile<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        q: f32,
+    ) -> Result<Tensor<S::Reduced, E, D, T>, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        assert!(
+            (0.0..=1.0).contains(&q),
+            "quantile: q must be in [0, 1], but got {q}"
+        );
+        let ax = Ax::as_array()[0] as usize;
+        assert!(
+            self.shape().concrete()[ax] > 0,
+            "quantile: can't take a quantile of empty axis {ax}"
+        );
+        let dst: S::Reduced = self.shape().reduced();
+        let (inp, mut tape) = self.split_tape();
+        let (values, lo, hi) = inp.device.forward(ax, q, dst, &inp.storage)?;
+        let out = inp.device.upgrade(values);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(ax, q, grad_inp, &lo, &hi, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+
+    /// See [median]
+    pub fn median<Ax: Axes<Array = [isize; 1]>>(self) -> Tensor<S::Reduced, E, D, T>
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_median::<Ax>().unwrap()
+    }
+
+    /// See [median]
+    pub fn try_median<Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<Tensor<S::Reduced, E, D, T>, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_quantile::<Ax>(0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_median_odd_and_even() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([5.0, -1.0, 3.0, 0.5, 2.0]);
+        assert_eq!(t.median::<Axis<0>>().array(), 2.0);
+        let t = dev.tensor([4.0, 1.0, 3.0, 2.0]);
+        let r = t.trace().median::<Axis<0>>();
+        assert_eq!(r.array(), 2.5);
+        let g = r.backward();
+        assert_eq!(g.get(&t).array(), [0.0, 0.0, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_quantile_interpolation() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([10.0, 0.0, 30.0, 20.0, 40.0]);
+        assert_eq!(t.clone().quantile::<Axis<0>>(0.0).array(), 0.0);
+        assert_eq!(t.clone().quantile::<Axis<0>>(1.0).array(), 40.0);
+        assert_close(&t.clone().quantile::<Axis<0>>(0.1).array(), &4.0);
+        let r = t.trace().quantile::<Axis<0>>(0.3);
+        assert_close(&r.array(), &12.0);
+        let g = r.backward();
+        assert_close(&g.get(&t).array(), &[0.8, 0.0, 0.0, 0.2, 0.0]);
+    }
+
+    #[test]
+    fn test_quantile_2d_axes() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 6.0, 2.0], [4.0, 2.0, 9.0], [3.0, 5.0, 7.0]]);
+        let r = t.trace().median::<Axis<0>>();
+        assert_eq!(r.array(), [3.0, 5.0, 7.0]);
+        let g = r.exp().sum().backward();
+        let e = f32::exp;
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [e(3.0), e(5.0), e(7.0)]]
+        );
+
+        let r = t.trace().quantile::<Axis<1>>(0.25);
+        assert_eq!(r.array(), [1.5, 3.0, 4.0]);
+        let g = r.sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.5, 0.0, 0.5], [0.5, 0.5, 0.0], [0.5, 0.5, 0.0]]
+        );
+    }
+
+    #[test]
+    fn test_median_3d_matches_sorted() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let m = t.clone().median::<Axis<1>>().array();
+        let t = t.array();
+        for b in 0..2 {
+            for c in 0..3 {
+                let mut sorted: std::vec::Vec<f32> = (0..5).map(|j| t[b][j][c]).collect();
+                sorted.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                assert_eq!(m[b][c], sorted[2]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quantile_out_of_range() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<3>, f32, _> = dev.zeros();
+        let _ = t.quantile::<Axis<0>>(1.5);
+    }
+}
@@ -63,6 +63,13 @@ impl super::ReshapeKernel<f32> for Cuda {
     where
         Src: HasSameNumelAs<Dst>,
     {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            super::ReshapeKernel::<f32>::backward(&self.cpu, &mut cpu_grad_inp, &cpu_grad_out)?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
         let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
         let numel = grad_inp.data.len();
 
@@ -0,0 +1,42 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+/// The index of the element that ends up at `i` after rolling `ax` by `shift`.
+#[inline(always)]
+fn rolled<S: Shape>(shape: &S, ax: usize, shift: usize, mut i: S::Concrete) -> S::Concrete {
+    let size = shape.concrete()[ax];
+    i[ax] = (i[ax] + size - shift) % size;
+    i
+}
+
+impl<E: Dtype> super::RollKernel<E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        shift: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: Self::Storage<S, E> = StridedArray::new(inp.shape)?;
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            *o = inp[rolled(&inp.shape, ax, shift, i)];
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        shift: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err> {
+        let mut out_iter = grad_out.iter_with_index();
+        while let Some((o, i)) = out_iter.next() {
+            grad_inp[rolled(&grad_out.shape, ax, shift, i)] += *o;
+        }
+        Ok(())
+    }
+}
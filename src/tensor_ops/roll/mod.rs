@@ -0,0 +1,137 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait RollKernel<E: Dtype>: DeviceStorage {
+    /// Copies `inp`, with the elements along `ax` moved `shift` places forward,
+    /// wrapping around to the start. `shift` is always less than the size of `ax`.
+    fn forward<S: Shape>(
+        &self,
+        ax: usize,
+        shift: usize,
+        inp: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    /// Adds `grad_out`, with the elements along `ax` moved `shift` places backward, to `grad_inp`.
+    fn backward<S: Shape>(
+        &self,
+        ax: usize,
+        shift: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Moves the elements along `Ax` forward by `shift` places. Elements that are moved past the
+/// end wrap around to the start. `shift` can be negative, which moves elements backward.
+///
+/// **Pytorch equivalent**: `t.roll(shift, Ax)`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// assert_eq!(t.clone().roll::<Axis<1>>(1).array(), [[3.0, 1.0, 2.0], [6.0, 4.0, 5.0]]);
+/// assert_eq!(t.roll::<Axis<1>>(-1).array(), [[2.0, 3.0, 1.0], [5.0, 6.0, 4.0]]);
+/// ```
+pub fn roll<Ax: Axes<Array = [isize; 1]>, S, E: Dtype, D: RollKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    shift: isize,
+) -> Tensor<S, E, D, T>
+where
+    S: Shape + HasAxes<Ax>,
+{
+    t.roll::<Ax>(shift)
+}
+
+impl<S: Shape, E: Dtype, D: RollKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [roll]
+    pub fn roll<Ax: Axes<Array = [isize; 1]>>(self, shift: isize) -> Self
+    where
+        S: HasAxes<Ax>,
+    {
+        self.try_roll::<Ax>(shift).unwrap()
+    }
+
+    /// See [roll]
+    pub fn try_roll<Ax: Axes<Array = [isize; 1]>>(self, shift: isize) -> Result<Self, D::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let size = self.shape().concrete()[ax].max(1) as isize;
+        let shift = shift.rem_euclid(size) as usize;
+        let (inp, mut tape) = self.split_tape();
+        let out = inp
+            .device
+            .upgrade(inp.device.forward(ax, shift, &inp.storage)?);
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(ax, shift, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_roll_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, -2.0, 3.0, 0.5]);
+        let r = t.trace().roll::<Axis<0>>(1);
+        assert_eq!(r.array(), [0.5, 1.0, -2.0, 3.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0, 4.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [2.0, 3.0, 4.0, 1.0]);
+    }
+
+    #[test]
+    fn test_roll_wraps_shift() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        assert_eq!(t.clone().roll::<Axis<0>>(-1).array(), [2.0, 3.0, 1.0]);
+        assert_eq!(t.clone().roll::<Axis<0>>(5).array(), [2.0, 3.0, 1.0]);
+        assert_eq!(t.clone().roll::<Axis<0>>(-3).array(), t.array());
+    }
+
+    #[test]
+    fn test_roll_3d_axis_1() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let r = t.trace().roll::<Axis<1>>(-2);
+        let (a, b) = (t.array(), r.array());
+        for i in 0..2 {
+            for j in 0..5 {
+                assert_eq!(b[i][j], a[i][(j + 2) % 5]);
+            }
+        }
+        let w: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let g = (r * w.clone()).sum().backward();
+        assert_eq!(g.get(&t).array(), w.roll::<Axis<1>>(2).array());
+    }
+
+    #[test]
+    fn test_roll_permuted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let r = t.trace().permute::<Rank2<3, 2>, _>().roll::<Axis<0>>(1);
+        assert_eq!(r.array(), [[3.0, 6.0], [1.0, 4.0], [2.0, 5.0]]);
+        let g = (r * dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[3.0, 5.0, 1.0], [4.0, 6.0, 2.0]]);
+    }
+}
@@ -0,0 +1,66 @@
+#include "cuda_utils.cuh"
+
+// the contiguous index of the element that ends up at `i` after rolling `ax` by `shift`
+__device__ unsigned int rolled_index(
+    unsigned int i,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t shift,
+    const size_t *dims
+) {
+    unsigned int idx = 0;
+    unsigned int stride = 1;
+    for (unsigned int d = 0; d < num_dims; d++) {
+        unsigned int dim_idx = num_dims - 1 - d;
+        unsigned int j = i % dims[dim_idx];
+        i /= dims[dim_idx];
+        if (dim_idx == ax) {
+            j = (j + dims[dim_idx] - shift) % dims[dim_idx];
+        }
+        idx += j * stride;
+        stride *= dims[dim_idx];
+    }
+    return idx;
+}
+
+extern "C" __global__ void roll_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t shift,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int src = rolled_index(out_i, num_dims, ax, shift, dims);
+    unsigned int inp_i = get_strided_index(src, num_dims, dims, inp_strides);
+    out[out_i] = inp[inp_i];
+}
+
+extern "C" __global__ void roll_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t shift,
+    const size_t *dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    unsigned int src = rolled_index(out_i, num_dims, ax, shift, dims);
+    unsigned int inp_i = get_strided_index(src, num_dims, dims, inp_strides);
+    unsigned int grad_out_i = get_strided_index(out_i, num_dims, dims, out_strides);
+    atomicAdd(grad_inp + inp_i, grad_out[grad_out_i]);
+}
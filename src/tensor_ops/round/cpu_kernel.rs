@@ -0,0 +1,67 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+
+impl UnaryDerivative<f32> for super::FloorKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x.floor()
+    }
+    #[inline(always)]
+    fn df(&self, _: &f32) -> f32 {
+        if self.straight_through {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl UnaryDerivative<f32> for super::CeilKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x.ceil()
+    }
+    #[inline(always)]
+    fn df(&self, _: &f32) -> f32 {
+        if self.straight_through {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl UnaryDerivative<f32> for super::RoundKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        x.round()
+    }
+    #[inline(always)]
+    fn df(&self, _: &f32) -> f32 {
+        if self.straight_through {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl UnaryDerivative<f32> for super::SignKernelOp {
+    #[inline(always)]
+    fn f(&self, x: &f32) -> f32 {
+        if *x == 0.0 {
+            0.0
+        } else if x.is_nan() {
+            *x
+        } else {
+            x.signum()
+        }
+    }
+    #[inline(always)]
+    fn df(&self, _: &f32) -> f32 {
+        if self.straight_through {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::tensor_ops::cuda_kernels::UnaryOpCudaKernel;
+
+unsafe impl cudarc::driver::AsKernelParam for super::FloorKernelOp {}
+unsafe impl cudarc::driver::AsKernelParam for super::CeilKernelOp {}
+unsafe impl cudarc::driver::AsKernelParam for super::RoundKernelOp {}
+unsafe impl cudarc::driver::AsKernelParam for super::SignKernelOp {}
+
+impl UnaryOpCudaKernel for super::FloorKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/round.ptx"));
+    const MODULE_NAME: &'static str = "floor";
+    const FWD_FN_NAME: &'static str = "floor_forward";
+    const BWD_FN_NAME: &'static str = "floor_backward";
+}
+
+impl UnaryOpCudaKernel for super::CeilKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/round.ptx"));
+    const MODULE_NAME: &'static str = "ceil";
+    const FWD_FN_NAME: &'static str = "ceil_forward";
+    const BWD_FN_NAME: &'static str = "ceil_backward";
+}
+
+impl UnaryOpCudaKernel for super::RoundKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/round.ptx"));
+    const MODULE_NAME: &'static str = "round";
+    const FWD_FN_NAME: &'static str = "round_forward";
+    const BWD_FN_NAME: &'static str = "round_backward";
+}
+
+impl UnaryOpCudaKernel for super::SignKernelOp {
+    const PTX_SRC: &'static str = include_str!(concat!(env!("OUT_DIR"), "/round.ptx"));
+    const MODULE_NAME: &'static str = "sign";
+    const FWD_FN_NAME: &'static str = "sign_forward";
+    const BWD_FN_NAME: &'static str = "sign_backward";
+}
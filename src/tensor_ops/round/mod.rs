@@ -0,0 +1,298 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use super::ops::{try_unary_op, UnaryKernel};
+use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct FloorKernelOp {
+    pub straight_through: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct CeilKernelOp {
+    pub straight_through: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RoundKernelOp {
+    pub straight_through: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SignKernelOp {
+    pub straight_through: bool,
+}
+
+/// Rounds down to the nearest integer.
+///
+/// The derivative is `0.0` almost everywhere, so nothing before this receives a gradient.
+/// Use [Tensor::floor_ste()] for the straight-through estimator instead, which passes the
+/// gradient through unchanged (i.e. uses a derivative of `1.0`), like in quantization aware
+/// training.
+///
+/// **Pytorch equivalent**: `t.floor()`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, -0.5, 0.0, 0.5, 1.5]);
+/// let r = t.floor();
+/// assert_eq!(r.array(), [-2.0, -1.0, 0.0, 0.0, 1.0]);
+/// ```
+pub fn floor<S: Shape, E: Dtype, D: UnaryKernel<FloorKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.floor()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<FloorKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [floor]
+    pub fn floor(self) -> Self {
+        self.try_floor().unwrap()
+    }
+    /// See [floor]
+    pub fn try_floor(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            FloorKernelOp {
+                straight_through: false,
+            },
+            self,
+        )
+    }
+    /// [floor] with the straight-through estimator as its gradient.
+    pub fn floor_ste(self) -> Self {
+        self.try_floor_ste().unwrap()
+    }
+    /// [floor] with the straight-through estimator as its gradient.
+    pub fn try_floor_ste(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            FloorKernelOp {
+                straight_through: true,
+            },
+            self,
+        )
+    }
+}
+
+/// Rounds up to the nearest integer.
+///
+/// The derivative is `0.0` almost everywhere, so nothing before this receives a gradient.
+/// Use [Tensor::ceil_ste()] for the straight-through estimator instead, which passes the
+/// gradient through unchanged (i.e. uses a derivative of `1.0`), like in quantization aware
+/// training.
+///
+/// **Pytorch equivalent**: `t.ceil()`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, -0.5, 0.0, 0.5, 1.5]);
+/// let r = t.ceil();
+/// assert_eq!(r.array(), [-1.0, -0.0, 0.0, 1.0, 2.0]);
+/// ```
+pub fn ceil<S: Shape, E: Dtype, D: UnaryKernel<CeilKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.ceil()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<CeilKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [ceil]
+    pub fn ceil(self) -> Self {
+        self.try_ceil().unwrap()
+    }
+    /// See [ceil]
+    pub fn try_ceil(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            CeilKernelOp {
+                straight_through: false,
+            },
+            self,
+        )
+    }
+    /// [ceil] with the straight-through estimator as its gradient.
+    pub fn ceil_ste(self) -> Self {
+        self.try_ceil_ste().unwrap()
+    }
+    /// [ceil] with the straight-through estimator as its gradient.
+    pub fn try_ceil_ste(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            CeilKernelOp {
+                straight_through: true,
+            },
+            self,
+        )
+    }
+}
+
+/// Rounds to the nearest integer, with halfway values rounded away from `0.0`.
+///
+/// Note that pytorch rounds halfway values to the nearest even integer instead.
+///
+/// The derivative is `0.0` almost everywhere, so nothing before this receives a gradient.
+/// Use [Tensor::round_ste()] for the straight-through estimator instead, which passes the
+/// gradient through unchanged (i.e. uses a derivative of `1.0`), like in quantization aware
+/// training.
+///
+/// **Pytorch equivalent**: `t.round()`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, -0.4, 0.0, 0.5, 1.6]);
+/// let r = t.round();
+/// assert_eq!(r.array(), [-2.0, -0.0, 0.0, 1.0, 2.0]);
+/// ```
+pub fn round<S: Shape, E: Dtype, D: UnaryKernel<RoundKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.round()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<RoundKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [round]
+    pub fn round(self) -> Self {
+        self.try_round().unwrap()
+    }
+    /// See [round]
+    pub fn try_round(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            RoundKernelOp {
+                straight_through: false,
+            },
+            self,
+        )
+    }
+    /// [round] with the straight-through estimator as its gradient.
+    pub fn round_ste(self) -> Self {
+        self.try_round_ste().unwrap()
+    }
+    /// [round] with the straight-through estimator as its gradient.
+    pub fn try_round_ste(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            RoundKernelOp {
+                straight_through: true,
+            },
+            self,
+        )
+    }
+}
+
+/// `-1.0` for negative values, `1.0` for positive values, and `0.0` for `0.0`.
+///
+/// The derivative is `0.0` almost everywhere, so nothing before this receives a gradient.
+/// Use [Tensor::sign_ste()] for the straight-through estimator instead, which passes the
+/// gradient through unchanged (i.e. uses a derivative of `1.0`), like in quantization aware
+/// training.
+///
+/// **Pytorch equivalent**: `t.sign()`
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.5, -0.0, 0.0, 0.5, 2.0]);
+/// let r = t.sign();
+/// assert_eq!(r.array(), [-1.0, 0.0, 0.0, 1.0, 1.0]);
+/// ```
+pub fn sign<S: Shape, E: Dtype, D: UnaryKernel<SignKernelOp, E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.sign()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SignKernelOp, E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [sign]
+    pub fn sign(self) -> Self {
+        self.try_sign().unwrap()
+    }
+    /// See [sign]
+    pub fn try_sign(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            SignKernelOp {
+                straight_through: false,
+            },
+            self,
+        )
+    }
+    /// [sign] with the straight-through estimator as its gradient.
+    pub fn sign_ste(self) -> Self {
+        self.try_sign_ste().unwrap()
+    }
+    /// [sign] with the straight-through estimator as its gradient.
+    pub fn try_sign_ste(self) -> Result<Self, D::Err> {
+        try_unary_op(
+            SignKernelOp {
+                straight_through: true,
+            },
+            self,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_floor_ceil() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.5, -1.0, -0.2, 0.0, 0.7, 3.0]);
+        let r = x.trace().floor();
+        assert_eq!(r.array(), [-3.0, -1.0, -1.0, 0.0, 0.0, 3.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 6]);
+
+        let r = x.trace().ceil();
+        assert_eq!(r.array(), [-2.0, -1.0, -0.0, 0.0, 1.0, 3.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 6]);
+    }
+
+    #[test]
+    fn test_round_sign() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-2.5, -1.2, -0.0, 0.0, 0.5, 2.49]);
+        let r = x.trace().round();
+        assert_eq!(r.array(), [-3.0, -1.0, 0.0, 0.0, 1.0, 2.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 6]);
+
+        let r = x.trace().sign();
+        assert_eq!(r.array(), [-1.0, -1.0, 0.0, 0.0, 1.0, 1.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [0.0; 6]);
+        assert!(dev.tensor([f32::NAN]).sign().array()[0].is_nan());
+    }
+
+    #[test]
+    fn test_straight_through() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([-1.7, -0.2, 0.4, 1.5]);
+        let r = x.trace().round_ste();
+        assert_eq!(r.array(), [-2.0, 0.0, 0.0, 2.0]);
+        let g = (r * 2.0).sum().backward();
+        assert_eq!(g.get(&x).array(), [2.0; 4]);
+
+        // quantizing to steps of 0.25
+        let q = (x.trace() * 4.0).floor_ste() / 4.0;
+        assert_eq!(q.array(), [-1.75, -0.25, 0.25, 1.5]);
+        let g = q.square().sum().backward();
+        assert_close(&g.get(&x).array(), &[-3.5, -0.5, 0.5, 3.0]);
+
+        for r in [x.trace().ceil_ste(), x.trace().sign_ste()] {
+            let g = r.sum().backward();
+            assert_eq!(g.get(&x).array(), [1.0; 4]);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+#include "unary_op_macros.cuh"
+
+struct FloorKernelOp {
+    bool straight_through;
+};
+
+struct CeilKernelOp {
+    bool straight_through;
+};
+
+struct RoundKernelOp {
+    bool straight_through;
+};
+
+struct SignKernelOp {
+    bool straight_through;
+};
+
+UNARY_OP(floor_forward, floor_backward, FloorKernelOp,
+        floorf(x),
+        op.straight_through ? 1.0 : 0.0)
+
+UNARY_OP(ceil_forward, ceil_backward, CeilKernelOp,
+        ceilf(x),
+        op.straight_through ? 1.0 : 0.0)
+
+UNARY_OP(round_forward, round_backward, RoundKernelOp,
+        roundf(x),
+        op.straight_through ? 1.0 : 0.0)
+
+UNARY_OP(sign_forward, sign_backward, SignKernelOp,
+        x > 0.0 ? 1.0 : (x < 0.0 ? -1.0 : x),
+        op.straight_through ? 1.0 : 0.0)
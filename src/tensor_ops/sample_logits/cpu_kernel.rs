@@ -0,0 +1,83 @@
+use super::SampleLogitsOp;
+use crate::{
+    shapes::Shape,
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl super::SampleLogitsKernel<f32> for Cpu {
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        op: SampleLogitsOp,
+        dst: Dst,
+        logits: &Self::Storage<S, f32>,
+        uniform: Vec<f32>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err> {
+        let mut out: StridedArray<Dst, usize> = StridedArray::new(dst)?;
+        let dims: Vec<usize> = logits.shape.concrete().into();
+        let strides: Vec<usize> = logits.strides.into();
+        let ax = S::NUM_DIMS - 1;
+        let buf = Arc::make_mut(&mut out.data);
+        let mut row = 0;
+        let mut values = Vec::with_capacity(dims[ax]);
+        let mut sorted = Vec::with_capacity(dims[ax]);
+        for_each_row(&dims, ax, [&strides], |[i_inp]| {
+            values.clear();
+            values.extend((0..dims[ax]).map(|j| logits.data[i_inp + j * strides[ax]]));
+            buf[row] = sample_row(op, &values, uniform[row], &mut sorted);
+            row += 1;
+        });
+        Ok(out)
+    }
+}
+
+fn sample_row(op: SampleLogitsOp, logits: &[f32], u: f32, sorted: &mut Vec<f32>) -> usize {
+    let mut argmax = 0;
+    for (j, &l) in logits.iter().enumerate() {
+        if l > logits[argmax] {
+            argmax = j;
+        }
+    }
+    if op.temperature <= 0.0 {
+        return argmax;
+    }
+
+    let max = logits[argmax];
+    let weight = |l: f32, min: f32| {
+        if l >= min {
+            ((l - max) / op.temperature).exp()
+        } else {
+            0.0
+        }
+    };
+    let total = |min: f32| -> f32 { logits.iter().map(|&l| weight(l, min)).sum() };
+
+    sorted.clear();
+    sorted.extend_from_slice(logits);
+    sorted.sort_by(|a, b| b.partial_cmp(a).unwrap_or(core::cmp::Ordering::Equal));
+    let mut min = sorted[op.top_k - 1];
+
+    if op.top_p < 1.0 {
+        let target = op.top_p * total(min);
+        let mut cum = 0.0;
+        for &l in sorted.iter() {
+            cum += weight(l, min);
+            if cum >= target {
+                min = l;
+                break;
+            }
+        }
+    }
+
+    let target = u * total(min);
+    let mut cum = 0.0;
+    for (j, &l) in logits.iter().enumerate() {
+        let w = weight(l, min);
+        cum += w;
+        if w > 0.0 && cum > target {
+            return j;
+        }
+    }
+    argmax
+}
@@ -0,0 +1,55 @@
+use super::SampleLogitsOp;
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::{sync::Arc, vec::Vec};
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/sample_logits.ptx"));
+const MODULE_NAME: &str = "sample_logits";
+const FN_NAME: &str = "sample_logits";
+const ALL_FN_NAMES: [&str; 1] = [FN_NAME];
+
+impl super::SampleLogitsKernel<f32> for Cuda {
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        op: SampleLogitsOp,
+        dst: Dst,
+        logits: &Self::Storage<S, f32>,
+        uniform: Vec<f32>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let num_rows = dst.num_elements();
+        let mut storage = self.dev.alloc_zeros_async::<usize>(num_rows)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(logits.shape.concrete().into())?;
+        let strides: CudaSlice<usize> = self.dev.take_async(logits.strides.into())?;
+        let uniform: CudaSlice<f32> = self.dev.take_async(uniform)?;
+
+        let f = self.dev.get_func(MODULE_NAME, FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,             // const size_t num_rows,
+            S::NUM_DIMS,          // const size_t num_dims,
+            &dims,                // const size_t *dims,
+            logits.data.as_ref(), // const float *logits,
+            &strides,             // const size_t *strides,
+            op.temperature,       // const float temperature,
+            op.top_k,             // const size_t top_k,
+            op.top_p,             // const float top_p,
+            &uniform,             // const float *uniform,
+            &mut storage,         // size_t *out
+        );
+        unsafe { f.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape: dst,
+            strides: dst.strides(),
+        })
+    }
+}
@@ -0,0 +1,167 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, SplitTape, Tensor},
+};
+use rand::Rng;
+use std::vec::Vec;
+
+/// The filtering done by [sample_logits()], for each row of logits.
+#[derive(Debug, Clone, Copy)]
+pub struct SampleLogitsOp {
+    /// Logits are divided by this before the softmax. `<= 0.0` means argmax.
+    pub temperature: f32,
+    /// Only logits at least as large as the `top_k`th largest are kept. In `1..=n`.
+    pub top_k: usize,
+    /// Only the most likely logits whose probabilities add up to at least `top_p` are kept.
+    pub top_p: f32,
+}
+
+pub trait SampleLogitsKernel<E: Dtype>: DeviceStorage {
+    /// Samples an index along the last axis of `logits` for each row, using
+    /// `uniform[row]` (in `[0, 1)`) as the source of randomness.
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        op: SampleLogitsOp,
+        dst: Dst,
+        logits: &Self::Storage<S, E>,
+        uniform: Vec<f32>,
+    ) -> Result<Self::Storage<Dst, usize>, Self::Err>;
+}
+
+/// Samples an index along the last axis from the distribution `softmax(logits / temperature)`,
+/// after filtering it with top k and top p (nucleus) sampling. This is done in a single
+/// kernel, without any round trips to the host.
+///
+/// Arguments:
+/// - `temperature`: `0.0` always picks the largest logit (lowest index for ties).
+/// - `top_k`: only keeps the logits that are at least as large as the `k`th largest logit.
+/// - `top_p`: then only keeps the most likely logits that make up at least `p` of the
+///   probability mass (ties with the least likely of these are also kept).
+/// - `rng`: draws one uniform sample per row of `logits`.
+///
+/// Not differentiable, so the tape of `logits` is dropped.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// # let dev: Cpu = Default::default();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let logits = dev.tensor([[1.0, 5.0, 2.0, 4.9], [3.0, 0.0, -1.0, 2.0]]);
+/// let greedy = logits.clone().sample_logits(0.0, None, None, &mut rng);
+/// assert_eq!(greedy.array(), [1, 0]);
+/// let tokens = logits.sample_logits(0.7, Some(2), Some(0.9), &mut rng);
+/// assert!(tokens.array()[0] == 1 || tokens.array()[0] == 3);
+/// ```
+pub fn sample_logits<
+    Ax: Axes<Array = [isize; 1]>,
+    S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+    E: Dtype,
+    D: SampleLogitsKernel<E>,
+    T: Tape<D>,
+>(
+    logits: Tensor<S, E, D, T>,
+    temperature: f32,
+    top_k: Option<usize>,
+    top_p: Option<f32>,
+    rng: &mut impl Rng,
+) -> Tensor<S::Reduced, usize, D> {
+    logits.sample_logits(temperature, top_k, top_p, rng)
+}
+
+impl<
+        Ax: Axes<Array = [isize; 1]>,
+        S: Shape<LastAxis = Ax> + ReduceShape<Ax>,
+        E: Dtype,
+        D: SampleLogitsKernel<E>,
+        T: Tape<D>,
+    > Tensor<S, E, D, T>
+{
+    /// See [sample_logits]
+    pub fn sample_logits(
+        self,
+        temperature: f32,
+        top_k: Option<usize>,
+        top_p: Option<f32>,
+        rng: &mut impl Rng,
+    ) -> Tensor<S::Reduced, usize, D> {
+        self.try_sample_logits(temperature, top_k, top_p, rng)
+            .unwrap()
+    }
+
+    /// See [sample_logits]
+    pub fn try_sample_logits(
+        self,
+        temperature: f32,
+        top_k: Option<usize>,
+        top_p: Option<f32>,
+        rng: &mut impl Rng,
+    ) -> Result<Tensor<S::Reduced, usize, D>, D::Err> {
+        let n = <S as HasAxes<Ax>>::size(self.shape());
+        assert!(n > 0, "sample_logits: can't sample from an empty axis");
+        let op = SampleLogitsOp {
+            temperature,
+            top_k: top_k.unwrap_or(n).clamp(1, n),
+            top_p: top_p.unwrap_or(1.0),
+        };
+        let (logits, _) = self.split_tape();
+        let dst: S::Reduced = logits.shape().reduced();
+        let uniform: Vec<f32> = (0..dst.num_elements()).map(|_| rng.gen()).collect();
+        let out = logits.device.forward(op, dst, &logits.storage, uniform)?;
+        Ok(logits.device.upgrade(out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_sample_logits_greedy() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let t = dev.tensor([[[0.0, 2.0, 2.0], [-1.0, -3.0, -2.0]]; 2]);
+        let r = t.sample_logits(0.0, None, None, &mut rng);
+        assert_eq!(r.array(), [[1, 0]; 2]);
+    }
+
+    #[test]
+    fn test_sample_logits_top_k_and_top_p() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let t = dev.tensor([0.0, 3.0, 1.0, 2.9, 2.0]);
+        for _ in 0..100 {
+            let i = t.clone().sample_logits(1.0, Some(3), None, &mut rng);
+            assert!([1, 3, 4].contains(&i.array()));
+            // 3.0 & 2.9 make up ~0.84 of the probability mass of the top 3
+            let i = t.clone().sample_logits(1.0, Some(3), Some(0.6), &mut rng);
+            assert!([1, 3].contains(&i.array()));
+            let i = t.clone().sample_logits(1.0, None, Some(0.01), &mut rng);
+            assert_eq!(i.array(), 1);
+        }
+    }
+
+    #[test]
+    fn test_sample_logits_distribution() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let t: Tensor<Rank2<4000, 3>, f32, _> =
+            dev.tensor([0.0, 1.0f32.ln(), 2.0f32.ln()]).broadcast();
+        let r = t.sample_logits(1.0, None, None, &mut rng).array();
+        let mut counts = [0usize; 3];
+        for i in r {
+            counts[i] += 1;
+        }
+        // probabilities are 0.25, 0.25, 0.5
+        assert!((900..1100).contains(&counts[0]), "{counts:?}");
+        assert!((900..1100).contains(&counts[1]), "{counts:?}");
+        assert!((1900..2100).contains(&counts[2]), "{counts:?}");
+    }
+}
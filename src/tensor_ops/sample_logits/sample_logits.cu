@@ -0,0 +1,106 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles a single row along the last axis. Instead of sorting the row,
+// the thresholds for top k & top p are found with binary searches over the bits of the logits.
+
+// Maps floats to unsigned ints with the same ordering.
+__device__ unsigned int float_key(float f) {
+    unsigned int bits = __float_as_uint(f);
+    return (bits & 0x80000000) ? ~bits : (bits | 0x80000000);
+}
+
+__device__ float weight(float l, unsigned int min_key, float max, float temperature) {
+    return float_key(l) >= min_key ? expf((l - max) / temperature) : 0.0;
+}
+
+extern "C" __global__ void sample_logits(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *logits,
+    const size_t *strides,
+    const float temperature,
+    const size_t top_k,
+    const float top_p,
+    const float *uniform,
+    size_t *out
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    const size_t ax = num_dims - 1;
+    const size_t n = dims[ax];
+    const size_t stride = strides[ax];
+    const float *inp = logits + get_row_index(row, num_dims, ax, dims, strides);
+
+    size_t argmax = 0;
+    for (size_t j = 1; j < n; j++) {
+        if (inp[j * stride] > inp[argmax * stride]) {
+            argmax = j;
+        }
+    }
+    if (temperature <= 0.0) {
+        out[row] = argmax;
+        return;
+    }
+    const float max = inp[argmax * stride];
+
+    // the largest key with at least top_k logits >= it, i.e. the key of the top_k'th largest logit
+    unsigned long long lo = 0;
+    unsigned long long hi = 0xFFFFFFFF;
+    while (lo < hi) {
+        unsigned long long mid = lo + (hi - lo + 1) / 2;
+        size_t count = 0;
+        for (size_t j = 0; j < n; j++) {
+            count += float_key(inp[j * stride]) >= mid;
+        }
+        if (count >= top_k) {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    unsigned int min_key = lo;
+
+    float total = 0.0;
+    for (size_t j = 0; j < n; j++) {
+        total += weight(inp[j * stride], min_key, max, temperature);
+    }
+
+    if (top_p < 1.0) {
+        // the largest key whose logits >= it make up at least top_p of the mass
+        const float target = top_p * total;
+        hi = 0xFFFFFFFF;
+        while (lo < hi) {
+            unsigned long long mid = lo + (hi - lo + 1) / 2;
+            float mass = 0.0;
+            for (size_t j = 0; j < n; j++) {
+                mass += weight(inp[j * stride], mid, max, temperature);
+            }
+            if (mass >= target) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        min_key = lo;
+        total = 0.0;
+        for (size_t j = 0; j < n; j++) {
+            total += weight(inp[j * stride], min_key, max, temperature);
+        }
+    }
+
+    const float target = uniform[row] * total;
+    float cum = 0.0;
+    for (size_t j = 0; j < n; j++) {
+        float w = weight(inp[j * stride], min_key, max, temperature);
+        cum += w;
+        if (w > 0.0 && cum > target) {
+            out[row] = j;
+            return;
+        }
+    }
+    out[row] = argmax;
+}
@@ -0,0 +1,140 @@
+use super::{gather_along::check_idx_shape, Device, TryAdd};
+use crate::{
+    gradients::{Merge, Tape},
+    shapes::{Axes, Dtype, HasAxes, HasShape, Shape, ShapeMismatch},
+    tensor::{DeviceMismatch, HasErr, PutTape, SplitTape, Tensor},
+};
+
+/// Adds the values of `src` along `Ax` at the positions in `idx`, which has the same
+/// shape as `src`. This is the opposite of [crate::tensor_ops::GatherAlong]:
+///
+/// ```text
+/// out[idx[i][j][k]][j][k] += src[i][j][k] # if Ax is 0
+/// out[i][idx[i][j][k]][k] += src[i][j][k] # if Ax is 1
+/// out[i][j][idx[i][j][k]] += src[i][j][k] # if Ax is 2
+/// ```
+///
+/// Values with the same index are summed, which makes this useful for things
+/// like embedding bags, histograms, and aggregating messages in graphs.
+/// Both `self` and `src` get gradients.
+///
+/// **Pytorch equivalent**: `t.scatter_add(Ax, idx, src)`
+///
+/// Returns [ShapeMismatch] if `idx` and `src` have different shapes, or if they
+/// are bigger than `self` along an axis other than `Ax`.
+/// **Panics** if an index is out of bounds.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let counts: Tensor<Rank1<3>, f32, _> = dev.zeros();
+/// let idx = dev.tensor([0, 2, 2, 1, 2]);
+/// let r = counts.scatter_add::<Axis<0>>(idx, dev.ones());
+/// assert_eq!(r.array(), [1.0, 1.0, 3.0]);
+/// ```
+pub trait ScatterAdd<Idx, Src>: HasErr + HasShape {
+    /// See [ScatterAdd]
+    #[track_caller]
+    fn scatter_add<Ax: Axes<Array = [isize; 1]>>(self, idx: Idx, src: Src) -> Self
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_scatter_add::<Ax>(idx, src).unwrap()
+    }
+
+    /// Fallible version of [ScatterAdd::scatter_add]
+    fn try_scatter_add<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Idx,
+        src: Src,
+    ) -> Result<Self, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax>;
+}
+
+impl<S, Dst, E, D, T, R> ScatterAdd<Tensor<Dst, usize, D>, Tensor<Dst, E, D, R>>
+    for Tensor<S, E, D, T>
+where
+    S: Shape,
+    Dst: Shape<Concrete = S::Concrete>,
+    E: Dtype,
+    D: Device<E>,
+    T: Tape<D> + Merge<R>,
+    R: Tape<D>,
+{
+    #[track_caller]
+    fn try_scatter_add<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        idx: Tensor<Dst, usize, D>,
+        src: Tensor<Dst, E, D, R>,
+    ) -> Result<Self, Self::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        DeviceMismatch::check_same("scatter_add", &self.device, &idx.device)?;
+        DeviceMismatch::check_same("scatter_add", &self.device, &src.device)?;
+        ShapeMismatch::check_same("scatter_add", &idx, &src)?;
+        check_idx_shape("scatter_add", ax, self.shape(), idx.shape())?;
+
+        // scatter into zeros, so the gradient of `self` comes from the addition
+        let (src, mut tape) = src.split_tape();
+        let mut scattered = src.device.try_zeros_like(self.shape())?;
+        src.device
+            .scatter_add(ax, &mut scattered.storage, &idx.storage, &src.storage)?;
+        let phantom_scattered = scattered.clone();
+        tape.try_alloc_grad(&src)?;
+        tape.try_alloc_grad(&scattered)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_src, grad_scattered) = grads.mut_and_ref(&src, &phantom_scattered);
+            src.device
+                .gather_add(ax, grad_scattered, &idx.storage, grad_src)
+        });
+        self.try_add(scattered.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_scatter_add_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let src = dev.tensor([1.0, 2.0, 3.0, 4.0]);
+        let r = t
+            .trace()
+            .scatter_add::<Axis<0>>(dev.tensor([2, 0, 2, 2]), src.trace());
+        assert_eq!(r.array(), [3.0, 2.0, 11.0]);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [1.0, 2.0, 3.0]);
+        assert_eq!(g.get(&src).array(), [3.0, 1.0, 3.0, 3.0]);
+    }
+
+    #[test]
+    fn test_scatter_add_2d_axis_1() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let idx = dev.tensor([[0, 0], [2, 1]]);
+        let src = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let r = t.trace().scatter_add::<Axis<1>>(idx.clone(), src.trace());
+        assert_eq!(r.array(), [[3.0, 0.0, 0.0], [0.0, 4.0, 3.0]]);
+
+        // scattering then gathering with the same indices sums the duplicates
+        let g = r.gather_along::<Axis<1>>(idx);
+        assert_eq!(g.array(), [[3.0, 3.0], [3.0, 4.0]]);
+        let grads = g.sum().backward();
+        assert_eq!(grads.get(&src).array(), [[2.0, 2.0], [1.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_scatter_add_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize, Const<3>), f32, _> = dev.zeros_like(&(2, Const));
+        let idx: Tensor<(usize, Const<3>), usize, _> = dev.zeros_like(&(2, Const));
+        let src: Tensor<(usize, Const<3>), f32, _> = dev.zeros_like(&(3, Const));
+        assert!(t.try_scatter_add::<Axis<1>>(idx, src).is_err());
+    }
+}
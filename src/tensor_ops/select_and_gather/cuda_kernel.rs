@@ -75,6 +75,19 @@ impl super::ReplaceDimKernel<f32> for Cuda {
     where
         Src: ReplaceDimTo<Dst, Idx>,
     {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_idx = self.storage_to_cpu(idx)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            super::ReplaceDimKernel::<f32>::backward(
+                &self.cpu,
+                &mut cpu_grad_inp,
+                &cpu_idx,
+                &cpu_grad_out,
+            )?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
         let bwd_fn = self
             .dev
             .get_func(GATHER_MODULE_NAME, GATHER_BWD_FN_NAME)
@@ -176,6 +189,19 @@ impl super::RemoveDimKernel<f32> for Cuda {
     where
         Src: RemoveDimTo<Dst, Idx>,
     {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_idx = self.storage_to_cpu(idx)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            super::RemoveDimKernel::<f32>::backward(
+                &self.cpu,
+                &mut cpu_grad_inp,
+                &cpu_idx,
+                &cpu_grad_out,
+            )?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
         let bwd_fn = self
             .dev
             .get_func(SELECT_MODULE_NAME, SELECT_BWD_FN_NAME)
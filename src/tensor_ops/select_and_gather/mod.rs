@@ -88,6 +88,7 @@ pub trait SelectTo<D: DeviceStorage>: HasErr + HasShape {
 }
 
 impl<Src: Shape, E: Dtype, D: RemoveDimKernel<E>, T: Tape<D>> SelectTo<D> for Tensor<Src, E, D, T> {
+    #[track_caller]
     fn try_select<Dst: Shape, Idx: Shape>(
         self,
         idx: Tensor<Idx, usize, D>,
@@ -95,6 +96,7 @@ impl<Src: Shape, E: Dtype, D: RemoveDimKernel<E>, T: Tape<D>> SelectTo<D> for Te
     where
         Self::Shape: RemoveDimTo<Dst, Idx>,
     {
+        DeviceMismatch::check_same("select", &self.device, &idx.device)?;
         let (inp, mut tape) = self.split_tape();
         let storage = inp.device.forward(&inp.storage, &idx.storage)?;
         let out = inp.device.upgrade(storage);
@@ -157,6 +159,7 @@ pub trait GatherTo<D: DeviceStorage>: HasErr + HasShape {
 impl<Src: Shape, E: Dtype, D: ReplaceDimKernel<E>, T: Tape<D>> GatherTo<D>
     for Tensor<Src, E, D, T>
 {
+    #[track_caller]
     fn try_gather<Dst: Shape, Idx: Shape>(
         self,
         idx: Tensor<Idx, usize, D>,
@@ -164,6 +167,7 @@ impl<Src: Shape, E: Dtype, D: ReplaceDimKernel<E>, T: Tape<D>> GatherTo<D>
     where
         Self::Shape: ReplaceDimTo<Dst, Idx>,
     {
+        DeviceMismatch::check_same("gather", &self.device, &idx.device)?;
         let (inp, mut tape) = self.split_tape();
         let storage = inp.device.forward(&inp.storage, &idx.storage)?;
         let out = inp.device.upgrade(storage);
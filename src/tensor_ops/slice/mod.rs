@@ -0,0 +1,206 @@
+use super::concat_along::ConcatAlongKernel;
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+use core::ops::{Bound, RangeBounds};
+
+impl<S: Shape, E: Dtype, D: ConcatAlongKernel<E> + ZerosTensor<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// The elements in `range` along `Ax`. **Pytorch equivalent**: `t[:, 2:7]` or `t.narrow(1, 2, 5)`
+    ///
+    /// Any kind of range works, e.g. `2..7`, `2..=6`, `2..` or `..7`. The gradient of the result
+    /// is added to the same sub-region of the gradient of `self`, and the rest of it is unchanged.
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]]);
+    /// let r = t.slice::<Axis<1>>(1..3);
+    /// assert_eq!(r.shape(), &(Const::<2>, 2));
+    /// assert_eq!(r.as_vec(), [2.0, 3.0, 6.0, 7.0]);
+    /// ```
+    ///
+    /// **Panics** if `range` is out of bounds for `Ax`.
+    #[track_caller]
+    pub fn slice<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        range: impl RangeBounds<usize>,
+    ) -> Tensor<S::Output, E, D, T>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        self.try_slice::<Ax>(range).unwrap()
+    }
+
+    /// Fallible version of [Tensor::slice]
+    #[track_caller]
+    pub fn try_slice<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<Tensor<S::Output, E, D, T>, <Self as HasErr>::Err>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let n = self.shape().concrete()[ax];
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => n,
+        };
+        assert!(
+            start <= end && end <= n,
+            "slice: range {start}..{end} is out of bounds for axis {ax} of size {n}"
+        );
+        self.try_narrow::<Ax>(start, end - start)
+    }
+
+    /// The `len` elements along `Ax` that start at `start`, which is the same as
+    /// [Tensor::slice] with `start..start + len`. **Pytorch equivalent**: `t.narrow(Ax, start, len)`
+    ///
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// assert_eq!(t.narrow::<Axis<0>>(3, 2).as_vec(), [4.0, 5.0]);
+    /// ```
+    ///
+    /// **Panics** if `start + len` is more than the size of `Ax`.
+    #[track_caller]
+    pub fn narrow<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        start: usize,
+        len: usize,
+    ) -> Tensor<S::Output, E, D, T>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        self.try_narrow::<Ax>(start, len).unwrap()
+    }
+
+    /// Fallible version of [Tensor::narrow]
+    #[track_caller]
+    pub fn try_narrow<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        start: usize,
+        len: usize,
+    ) -> Result<Tensor<S::Output, E, D, T>, <Self as HasErr>::Err>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        self.try_narrow_to::<Ax, usize>(start, len)
+    }
+
+    /// Same as [Tensor::try_narrow], but `len` can be any [Dim], e.g. a [Const] for crops
+    /// of a known size.
+    #[track_caller]
+    pub(crate) fn try_narrow_to<Ax: Axes<Array = [isize; 1]>, New: Dim>(
+        self,
+        start: usize,
+        len: New,
+    ) -> Result<Tensor<S::Output, E, D, T>, <Self as HasErr>::Err>
+    where
+        S: ReplaceAxis<Ax, New>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let n = self.shape().concrete()[ax];
+        let size = len.size();
+        assert!(
+            start + size <= n,
+            "narrow: {size} elements starting at {start} are out of bounds for axis {ax} of size {n}"
+        );
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&inp.shape().replace_axis(len))?;
+        inp.device
+            .backward(ax, start, &mut out.storage, &inp.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.forward(ax, start, grad_out, grad_inp)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_slice_ranges() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(t.clone().slice::<Axis<0>>(1..3).as_vec(), [1.0, 2.0]);
+        assert_eq!(t.clone().slice::<Axis<0>>(1..=3).as_vec(), [1.0, 2.0, 3.0]);
+        assert_eq!(t.clone().slice::<Axis<0>>(3..).as_vec(), [3.0, 4.0]);
+        assert_eq!(t.clone().slice::<Axis<0>>(..2).as_vec(), [0.0, 1.0]);
+        assert_eq!(t.clone().slice::<Axis<0>>(..).as_vec(), t.as_vec());
+        assert_eq!(t.slice::<Axis<0>>(5..).shape(), &(0,));
+    }
+
+    #[test]
+    fn test_slice_gradients() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 6, 3>, f32, _> = dev.sample_normal();
+        let r = t.trace().slice::<Axis<1>>(2..5);
+        assert_eq!(r.shape(), &(Const::<2>, 3, Const::<3>));
+        let t_a = t.array();
+        let r_v = r.as_vec();
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..3 {
+                    assert_eq!(r_v[i * 9 + j * 3 + k], t_a[i][j + 2][k]);
+                }
+            }
+        }
+        let g = r.exp().sum().backward();
+        let mut expected = [[[0.0; 3]; 6]; 2];
+        for i in 0..2 {
+            for j in 2..5 {
+                for k in 0..3 {
+                    expected[i][j][k] = t_a[i][j][k].exp();
+                }
+            }
+        }
+        assert_close(&g.get(&t).array(), &expected);
+    }
+
+    #[test]
+    fn test_slice_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t
+            .trace()
+            .broadcast::<Rank2<4, 3>, _>()
+            .slice::<Axis<0>>(1..3);
+        assert_eq!(r.as_vec(), [1.0, 2.0, 3.0, 1.0, 2.0, 3.0]);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [2.0; 3]);
+    }
+
+    #[test]
+    fn test_narrow_matches_slice() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 8>, f32, _> = dev.sample_normal();
+        let a = t.clone().narrow::<Axis<1>>(2, 4);
+        let b = t.slice::<Axis<1>>(2..6);
+        assert_eq!(a.as_vec(), b.as_vec());
+    }
+
+    #[test]
+    #[should_panic = "slice: range 2..6 is out of bounds for axis 0 of size 5"]
+    fn test_slice_out_of_bounds() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank1<5>, f32, _> = dev.zeros();
+        let _ = t.slice::<Axis<0>>(2..6);
+    }
+}
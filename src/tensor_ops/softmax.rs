@@ -1,5 +1,9 @@
-use super::Device;
-use crate::{gradients::Tape, shapes::*, tensor::Tensor};
+use super::{BroadcastTo, ChooseFrom, Device, MaxTo, MinTo, SumTo, TryAdd, TryDiv, TrySub};
+use crate::{
+    gradients::{NoneTape, Tape},
+    shapes::*,
+    tensor::{DeviceMismatch, Invariant, Tensor},
+};
 
 /// Computes the [softmax function](https://en.wikipedia.org/wiki/Softmax_function) across
 /// `Ax`.
@@ -15,6 +19,8 @@ use crate::{gradients::Tape, shapes::*, tensor::Tensor};
 /// let t: Tensor<Rank3<2, 3, 5>, f32, _> = dev.zeros();
 /// let _ = t.softmax::<Axis<2>>();
 /// ```
+///
+/// In checked mode, returns an error if `t` is not finite, or the result is not in `[0, 1]`.
 pub fn softmax<Ax: Axes, S: Shape, E: Dtype, D: Device<E>, T: Tape<D>>(
     t: Tensor<S, E, D, T>,
 ) -> Tensor<S, E, D, T>
@@ -24,8 +30,40 @@ where
     t.softmax::<Ax>()
 }
 
+/// [softmax()] across `Ax`, where the positions where `mask` is true are treated as `-inf`,
+/// so they are `0` in the output and get no gradient.
+///
+/// Unlike filling the masked positions with `-inf` & calling [softmax()], rows where every position
+/// is masked (like the padding of a batch) are all `0` instead of `NaN`, and have `0` gradient.
+/// The masked positions of `t` can hold any value, including `NaN`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[0.0, 1.0, f32::NAN], [2.0, 3.0, 4.0]]);
+/// let mask = dev.tensor([[false, false, true], [true, true, true]]);
+/// let r = t.masked_softmax::<Axis<1>>(mask);
+/// assert_eq!(r.array()[1], [0.0; 3]);
+/// assert_eq!(r.array()[0][2], 0.0);
+/// ```
+///
+/// Returns [ShapeMismatch] if `mask` and `t` have different shapes.
+///
+/// In checked mode, returns an error if the result is not in `[0, 1]`.
+pub fn masked_softmax<Ax: Axes, S: Shape, E: Dtype, D: Device<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    mask: Tensor<S, bool, D>,
+) -> Tensor<S, E, D, T>
+where
+    S: ReduceShape<Ax>,
+{
+    t.masked_softmax::<Ax>(mask)
+}
+
 impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
     /// See [softmax]
+    #[track_caller]
     pub fn softmax<Ax: Axes>(self) -> Self
     where
         S: ReduceShape<Ax>,
@@ -33,11 +71,68 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<D>> Tensor<S, E, D, T> {
         self.try_softmax::<Ax>().unwrap()
     }
     /// See [softmax]
+    #[track_caller]
     pub fn try_softmax<Ax: Axes>(self) -> Result<Self, D::Err>
     where
         S: ReduceShape<Ax>,
     {
-        self.try_log_softmax::<Ax>()?.try_exp()
+        let probs = self.try_log_softmax::<Ax>()?.try_exp()?;
+        probs.try_check("softmax", "output", Invariant::Probability)?;
+        Ok(probs)
+    }
+
+    /// See [masked_softmax]
+    #[track_caller]
+    pub fn masked_softmax<Ax: Axes>(self, mask: Tensor<S, bool, D>) -> Self
+    where
+        S: ReduceShape<Ax>,
+    {
+        self.try_masked_softmax::<Ax>(mask).unwrap()
+    }
+    /// See [masked_softmax]
+    #[track_caller]
+    pub fn try_masked_softmax<Ax: Axes>(self, mask: Tensor<S, bool, D>) -> Result<Self, D::Err>
+    where
+        S: ReduceShape<Ax>,
+    {
+        DeviceMismatch::check_same("masked_softmax", &self.device, &mask.device)?;
+        ShapeMismatch::check_same("masked_softmax", &self, &mask)?;
+        let shape = *self.shape();
+        let zero = E::default();
+
+        // the masked values may not be finite, so they're replaced before anything else
+        let x = self.try_masked_fill(mask.clone(), zero)?;
+
+        // the max of the unmasked values, or the min of the row if they are all masked,
+        // so that it's always finite
+        let min: Tensor<S::Reduced, E, D> = x.retaped::<NoneTape>().try_min::<_, Ax>()?;
+        let min = min.try_broadcast_like(&shape)?;
+        let max: Tensor<S::Reduced, E, D> = mask
+            .clone()
+            .try_choose(min, x.retaped::<NoneTape>())?
+            .try_max::<_, Ax>()?;
+        let max = max.try_broadcast_like(&shape)?;
+
+        // `x - max` may overflow at the masked positions, so they are zeroed before `exp`
+        let exp = x
+            .try_sub(max)?
+            .try_masked_fill(mask.clone(), zero)?
+            .try_exp()?
+            .try_masked_fill(mask.clone(), zero)?;
+
+        // rows with an unmasked value sum to at least 1, and the others to 0,
+        // so adding 1 to only the fully masked rows makes them 0 instead of NaN
+        let all_masked: Tensor<S::Reduced, E, D> = mask
+            .try_choose(
+                exp.device.try_ones_like(&shape)?,
+                exp.device.try_zeros_like(&shape)?,
+            )?
+            .try_min::<_, Ax>()?;
+        let sum = exp.retaped::<T>().try_sum::<S::Reduced, Ax>()?;
+        let sum = sum.try_add(all_masked)?.try_broadcast_like(&shape)?;
+        let probs = exp.try_div(sum)?;
+        probs.try_check("masked_softmax", "output", Invariant::Probability)?;
+        Ok(probs)
     }
 }
 
@@ -69,6 +164,13 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_softmax_large_logits() {
+        let dev: TestDevice = Default::default();
+        let r = dev.tensor([10000.0, 10001.0, -10000.0]).softmax::<Axis<0>>();
+        assert_close(&r.array(), &[0.26894143, 0.7310586, 0.0]);
+    }
+
     #[test]
     fn test_softmax_2d() {
         let dev: TestDevice = Default::default();
@@ -137,4 +239,69 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_masked_softmax_matches_softmax_of_unmasked() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[-2.0, 5.0, 0.0, 1.0], [1.0, 4.0, 7.0, -1.0]]);
+        let mask = dev.tensor([[false, true, false, false], [false, false, false, false]]);
+        let r = t.trace().masked_softmax::<Axis<1>>(mask);
+        let ra = r.array();
+        let w = dev.tensor([[1.0, 2.0, 3.0, 4.0], [-1.0, 0.5, 2.0, 1.0]]);
+        let g = (r * w.clone()).sum().backward();
+
+        let row = dev.tensor([-2.0, 0.0, 1.0]);
+        let e = row.trace().softmax();
+        let ea = e.array();
+        let eg = (e * dev.tensor([1.0, 3.0, 4.0])).sum().backward();
+        let eg = eg.get(&row).array();
+        assert_close(&ra[0], &[ea[0], 0.0, ea[1], ea[2]]);
+        assert_close(&g.get(&t).array()[0], &[eg[0], 0.0, eg[1], eg[2]]);
+
+        // nothing masked is the same as softmax
+        let full = t.trace().softmax::<Axis<1>>();
+        assert_close(&ra[1], &full.array()[1]);
+        let fg = (full * w).sum().backward();
+        assert_close(&g.get(&t).array()[1], &fg.get(&t).array()[1]);
+    }
+
+    #[test]
+    fn test_masked_softmax_all_masked_rows_are_zero() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([
+            [f32::NEG_INFINITY, f32::NAN, 1.0],
+            [-300.0, -200.0, -100.0],
+            [1e30, -1e30, 0.0],
+        ]);
+        let mask = dev.tensor([[true, true, true], [true, false, true], [true, true, true]]);
+        let r = t.trace().masked_softmax::<Axis<1>>(mask);
+        assert_eq!(r.array(), [[0.0; 3], [0.0, 1.0, 0.0], [0.0; 3]]);
+        let g = (r * dev.sample_normal::<Rank2<3, 3>>()).sum().backward();
+        assert_eq!(g.get(&t).array(), [[0.0; 3]; 3]);
+    }
+
+    #[test]
+    fn test_masked_softmax_0th_axis() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let mask = dev.tensor([
+            [true, false, true, false],
+            [true, false, false, false],
+            [true, true, false, false],
+        ]);
+        let r = t.trace().masked_softmax::<Axis<0>>(mask);
+        let mask_t = dev.tensor([
+            [true, true, true],
+            [false, false, true],
+            [true, false, false],
+            [false, false, false],
+        ]);
+        let p = t
+            .clone()
+            .permute::<_, Axes2<1, 0>>()
+            .masked_softmax::<Axis<1>>(mask_t);
+        assert_close(&r.array(), &p.permute().array());
+        assert_eq!(r.array().map(|row| row[0]), [0.0; 3]);
+        assert_close(&r.sum::<Rank1<4>, _>().array(), &[0.0, 1.0, 1.0, 1.0]);
+    }
 }
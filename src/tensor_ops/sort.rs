@@ -0,0 +1,116 @@
+use super::topk::{try_sorted_along, TopKKernel};
+use crate::{
+    gradients::Tape,
+    shapes::{Axes, Dtype, HasAxes, HasShape, Shape},
+    tensor::{DeviceStorage, HasErr, Tensor},
+};
+
+/// Sorts values along an axis in ascending order, returning the sorted values and
+/// the indices they came from along that axis.
+///
+/// The sort is stable, so equal values are ordered by their index. The order of NaNs is unspecified.
+///
+/// The gradient of each sorted value is added to the position it came from.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[3.0, 1.0, 2.0], [0.0, 0.0, -1.0]]);
+/// let (values, indices) = t.clone().sort::<Axis<1>>();
+/// assert_eq!(values.array(), [[1.0, 2.0, 3.0], [-1.0, 0.0, 0.0]]);
+/// assert_eq!(indices.array(), [[1, 2, 0], [2, 0, 1]]);
+/// assert_eq!(t.argsort::<Axis<1>>().array(), [[1, 2, 0], [2, 0, 1]]);
+/// ```
+pub trait Sort<D: DeviceStorage>: HasErr + HasShape {
+    /// See [Sort]
+    fn sort<Ax: Axes<Array = [isize; 1]>>(self) -> (Self, Tensor<Self::Shape, usize, D>)
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_sort::<Ax>().unwrap()
+    }
+
+    /// Fallible version of [Sort::sort]
+    #[allow(clippy::type_complexity)]
+    fn try_sort<Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<(Self, Tensor<Self::Shape, usize, D>), Self::Err>
+    where
+        Self::Shape: HasAxes<Ax>;
+
+    /// The indices that would sort the values along an axis, see [Sort].
+    fn argsort<Ax: Axes<Array = [isize; 1]>>(self) -> Tensor<Self::Shape, usize, D>
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        self.try_argsort::<Ax>().unwrap()
+    }
+
+    /// Fallible version of [Sort::argsort]
+    fn try_argsort<Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<Tensor<Self::Shape, usize, D>, Self::Err>
+    where
+        Self::Shape: HasAxes<Ax>,
+    {
+        Ok(self.try_sort::<Ax>()?.1)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: TopKKernel<E>, T: Tape<D>> Sort<D> for Tensor<S, E, D, T> {
+    fn try_sort<Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<(Self, Tensor<S, usize, D>), Self::Err>
+    where
+        S: HasAxes<Ax>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let shape = *self.shape();
+        try_sorted_along(self, ax, false, shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_sort_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([2.0, -1.0, 3.0, 2.0, 0.5]);
+        let (v, i) = t.trace().sort::<Axis<0>>();
+        assert_eq!(v.array(), [-1.0, 0.5, 2.0, 2.0, 3.0]);
+        assert_eq!(i.array(), [1, 4, 0, 3, 2]);
+        let g = (v * dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [3.0, 1.0, 5.0, 4.0, 2.0]);
+    }
+
+    #[test]
+    fn test_sort_2d_axis_0() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 6.0], [4.0, 2.0], [3.0, 5.0]]);
+        let (v, i) = t.trace().sort::<Axis<0>>();
+        assert_eq!(v.array(), [[1.0, 2.0], [3.0, 5.0], [4.0, 6.0]]);
+        assert_eq!(i.array(), [[0, 1], [2, 2], [1, 0]]);
+        let g = (v * dev.tensor([[1.0, 1.0], [2.0, 2.0], [3.0, 3.0]]))
+            .sum()
+            .backward();
+        assert_eq!(g.get(&t).array(), [[1.0, 3.0], [3.0, 1.0], [2.0, 2.0]]);
+    }
+
+    #[test]
+    fn test_argsort_3d() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 6>, f32, _> = dev.sample_normal();
+        let i = t.clone().argsort::<Axis<2>>().array();
+        let t = t.array();
+        for b in 0..2 {
+            for r in 0..3 {
+                for j in 1..6 {
+                    assert!(t[b][r][i[b][r][j - 1]] <= t[b][r][i[b][r][j]]);
+                }
+            }
+        }
+    }
+}
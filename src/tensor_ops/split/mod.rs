@@ -0,0 +1,220 @@
+use super::concat_along::ConcatAlongKernel;
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+use std::vec::Vec;
+
+impl<S: Shape, E: Dtype, D: ConcatAlongKernel<E> + ZerosTensor<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// Splits the tensor into pieces along `Ax`, where piece `i` has `sizes[i]` elements
+    /// along `Ax`. **Pytorch equivalent**: `t.split(sizes, Ax)`
+    ///
+    /// The tape of `self`, which has the backward ops of every piece, goes on the first piece,
+    /// and the other pieces get empty tapes. Like the tapes of [Tensor::retaped()] tensors,
+    /// their operations are merged into the first piece's tape when they are combined again,
+    /// so the first piece (or a tensor computed from it) should be on the left of those ops.
+    ///
+    /// Splitting the features of a batch into two heads:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<2, 5>, f32, _> = dev.tensor([[1.0; 5], [2.0; 5]]);
+    /// let pieces = t.split::<Axis<1>>(&[2, 3]);
+    /// assert_eq!(pieces[0].shape(), &(Const::<2>, 2));
+    /// assert_eq!(pieces[1].as_vec(), [1.0, 1.0, 1.0, 2.0, 2.0, 2.0]);
+    /// ```
+    ///
+    /// **Panics** if `sizes` doesn't add up to the size of `Ax`.
+    #[track_caller]
+    pub fn split<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        sizes: &[usize],
+    ) -> Vec<Tensor<S::Output, E, D, T>>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        self.try_split::<Ax>(sizes).unwrap()
+    }
+
+    /// Fallible version of [Tensor::split]
+    #[track_caller]
+    #[allow(clippy::type_complexity)]
+    pub fn try_split<Ax: Axes<Array = [isize; 1]>>(
+        self,
+        sizes: &[usize],
+    ) -> Result<Vec<Tensor<S::Output, E, D, T>>, <Self as HasErr>::Err>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let n = self.shape().concrete()[ax];
+        let total: usize = sizes.iter().sum();
+        assert_eq!(
+            total, n,
+            "split: sizes {sizes:?} add up to {total}, but axis {ax} has size {n}"
+        );
+
+        let (inp, mut tape) = self.split_tape();
+        let mut pieces = Vec::with_capacity(sizes.len());
+        let mut offset = 0;
+        for &size in sizes {
+            let piece = inp.clone().put_tape(tape).try_narrow::<Ax>(offset, size)?;
+            let (piece, piece_tape) = piece.split_tape();
+            tape = piece_tape;
+            pieces.push(piece);
+            offset += size;
+        }
+
+        let mut tape = Some(tape);
+        Ok(pieces
+            .into_iter()
+            .map(|piece| piece.put_tape(tape.take().unwrap_or_default()))
+            .collect())
+    }
+
+    /// Splits the tensor into `N` pieces along `Ax`, with sizes as equal as possible.
+    /// When the size of `Ax` isn't divisible by `N`, the first pieces have one more element.
+    /// **Pytorch equivalent**: `t.tensor_split(N, Ax)`
+    ///
+    /// See [Tensor::split] for where the tape goes.
+    ///
+    /// Splitting the output of a linear layer into queries, keys & values:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let qkv: Tensor<Rank2<4, 24>, f32, _> = dev.zeros();
+    /// let [q, k, v] = qkv.chunk::<Axis<1>, 3>();
+    /// assert_eq!(q.shape(), &(Const::<4>, 8));
+    /// ```
+    ///
+    /// Uneven chunks:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t = dev.tensor([1.0, 2.0, 3.0, 4.0, 5.0]);
+    /// let [a, b, c] = t.chunk::<Axis<0>, 3>();
+    /// assert_eq!((a.as_vec(), b.as_vec(), c.as_vec()), (vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0]));
+    /// ```
+    #[track_caller]
+    pub fn chunk<Ax: Axes<Array = [isize; 1]>, const N: usize>(
+        self,
+    ) -> [Tensor<S::Output, E, D, T>; N]
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        self.try_chunk::<Ax, N>().unwrap()
+    }
+
+    /// Fallible version of [Tensor::chunk]
+    #[track_caller]
+    #[allow(clippy::type_complexity)]
+    pub fn try_chunk<Ax: Axes<Array = [isize; 1]>, const N: usize>(
+        self,
+    ) -> Result<[Tensor<S::Output, E, D, T>; N], <Self as HasErr>::Err>
+    where
+        S: ReplaceAxis<Ax, usize>,
+    {
+        let n = self.shape().concrete()[Ax::as_array()[0] as usize];
+        let sizes: Vec<usize> = (0..N).map(|i| n / N + usize::from(i < n % N)).collect();
+        let pieces = self.try_split::<Ax>(&sizes)?;
+        Ok(pieces.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_split_values() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let pieces = t.clone().split::<Axis<1>>(&[1, 2]);
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[0].shape(), &(Const::<2>, 1));
+        assert_eq!(pieces[0].as_vec(), [1.0, 4.0]);
+        assert_eq!(pieces[1].as_vec(), [2.0, 3.0, 5.0, 6.0]);
+
+        let rows = t.split::<Axis<0>>(&[0, 2]);
+        assert_eq!(rows[0].shape(), &(0, Const::<3>));
+        assert_eq!(rows[1].as_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_split_gradients() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let mut pieces = t.trace().split::<Axis<1>>(&[2, 3]).into_iter();
+        let a = pieces.next().unwrap();
+        let b = pieces.next().unwrap();
+        let a = a.square().sum::<Rank1<2>, _>();
+        let b = b.exp().sum::<Rank1<2>, _>();
+        let g = (a + b).sum().backward();
+
+        let t_a = t.array();
+        let mut expected = [[[0.0; 3]; 5]; 2];
+        for i in 0..2 {
+            for j in 0..5 {
+                for k in 0..3 {
+                    let x = t_a[i][j][k];
+                    expected[i][j][k] = if j < 2 { 2.0 * x } else { x.exp() };
+                }
+            }
+        }
+        assert_close(&g.get(&t).array(), &expected);
+    }
+
+    #[test]
+    fn test_split_unused_piece_has_zero_grad() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let mut pieces = t.trace().split::<Axis<0>>(&[1, 2]).into_iter();
+        let a = pieces.next().unwrap();
+        let g = a.sum().backward();
+        assert_eq!(g.get(&t).array(), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_chunk_uneven() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<7, 2>, f32, _> = dev.sample_normal();
+        let chunks = t.clone().chunk::<Axis<0>, 3>();
+        let sizes: std::vec::Vec<usize> = chunks.iter().map(|c| c.shape().0).collect();
+        assert_eq!(sizes, [3, 2, 2]);
+        let all: std::vec::Vec<f32> = chunks.iter().flat_map(|c| c.as_vec()).collect();
+        assert_eq!(all, t.as_vec());
+    }
+
+    #[test]
+    fn test_chunk_concat_round_trip() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+        let [a, b] = t.trace().chunk::<Axis<1>, 2>();
+        let r = a.concat_along::<Axis<1>, usize>(b * 2.0);
+        let mut expected = t.array();
+        for row in expected.iter_mut() {
+            row[2] *= 2.0;
+            row[3] *= 2.0;
+        }
+        assert_eq!(
+            r.as_vec(),
+            expected
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<std::vec::Vec<_>>()
+        );
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [[1.0, 1.0, 2.0, 2.0]; 3]);
+    }
+
+    #[test]
+    #[should_panic = "split: sizes [1, 1] add up to 2, but axis 0 has size 3"]
+    fn test_split_wrong_sizes() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let _ = t.split::<Axis<0>>(&[1, 1]);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator},
+};
+use std::sync::Arc;
+
+impl<E: Dtype> super::StackKernel<E> for Cpu {
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        i: usize,
+        inp: &Self::Storage<S, E>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let out_strides = out.strides;
+        let buf = Arc::make_mut(&mut out.data);
+        let mut inp_iter = inp.iter_with_index();
+        while let Some((x, idx)) = inp_iter.next() {
+            let mut j = i * out_strides[0];
+            for d in 0..S::NUM_DIMS {
+                j += idx[d] * out_strides[d + 1];
+            }
+            buf[j] += *x;
+        }
+        Ok(())
+    }
+
+    fn backward<S: Shape, Dst: Shape>(
+        &self,
+        i: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let out_strides = grad_out.strides;
+        let mut inp_iter = grad_inp.iter_mut_with_index();
+        while let Some((g, idx)) = inp_iter.next() {
+            let mut j = i * out_strides[0];
+            for d in 0..S::NUM_DIMS {
+                j += idx[d] * out_strides[d + 1];
+            }
+            *g += grad_out.data[j];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceMismatch, DeviceStorage, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+use std::vec::Vec;
+
+pub trait StackKernel<E: Dtype>: DeviceStorage {
+    /// Adds `inp` to item `i` along the first axis of `out`.
+    fn forward<S: Shape, Dst: Shape>(
+        &self,
+        i: usize,
+        inp: &Self::Storage<S, E>,
+        out: &mut Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+
+    /// Adds item `i` along the first axis of `grad_out` to `grad_inp`.
+    fn backward<S: Shape, Dst: Shape>(
+        &self,
+        i: usize,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Stacks a [Vec] of tensors with the same shape into a single tensor, with a new first
+/// axis of size `items.len()`. **Pytorch equivalent**: `torch.stack(items)`
+///
+/// This builds batches out of a number of items that is only known at runtime, like the
+/// samples from a data loader. The tapes of the items are merged, and each item gets
+/// its slice of the gradient.
+///
+/// Returns [ShapeMismatch] if the items don't all have the same shape, or [DeviceMismatch]
+/// if they are on another device. **Panics** if `items` is empty.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let samples: Vec<Tensor<Rank1<2>, f32, _>> = (0..3)
+///     .map(|i| dev.tensor([i as f32, -(i as f32)]))
+///     .collect();
+/// let batch: Tensor<(usize, Const<2>), f32, _> = dev.stack(samples);
+/// assert_eq!(batch.shape(), &(3, Const::<2>));
+/// assert_eq!(batch.as_vec(), [0.0, -0.0, 1.0, -1.0, 2.0, -2.0]);
+/// ```
+pub trait Stack<E: Dtype>: DeviceStorage {
+    /// See [Stack]
+    #[track_caller]
+    fn stack<S: AddDim<usize>, T: Tape<Self>>(
+        &self,
+        items: Vec<Tensor<S, E, Self, T>>,
+    ) -> Tensor<S::Larger, E, Self, T> {
+        self.try_stack(items).unwrap()
+    }
+
+    /// Fallible version of [Stack::stack]
+    fn try_stack<S: AddDim<usize>, T: Tape<Self>>(
+        &self,
+        items: Vec<Tensor<S, E, Self, T>>,
+    ) -> Result<Tensor<S::Larger, E, Self, T>, Self::Err>;
+}
+
+impl<E: Dtype, D: StackKernel<E> + ZerosTensor<E>> Stack<E> for D {
+    #[track_caller]
+    fn try_stack<S: AddDim<usize>, T: Tape<Self>>(
+        &self,
+        items: Vec<Tensor<S, E, Self, T>>,
+    ) -> Result<Tensor<S::Larger, E, Self, T>, Self::Err> {
+        assert!(!items.is_empty(), "stack: can't stack an empty Vec");
+        let shape = *items[0].shape();
+        for item in items.iter() {
+            DeviceMismatch::check_same("stack", self, &item.device)?;
+            ShapeMismatch::check_same("stack", &items[0], item)?;
+        }
+
+        let mut out = self.try_zeros_like(&shape.add_dim(items.len()))?;
+        let mut tape: T = Default::default();
+        let mut inps = Vec::with_capacity(items.len());
+        for (i, item) in items.into_iter().enumerate() {
+            let (inp, item_tape) = item.split_tape();
+            self.forward(i, &inp.storage, &mut out.storage)?;
+            tape = tape.merge(item_tape);
+            inps.push(inp);
+        }
+        let phantom_out = out.clone();
+        // one op per item, so the same tensor can be stacked more than once
+        for (i, inp) in inps.into_iter().enumerate() {
+            tape.try_alloc_grad(&inp)?;
+            tape.try_alloc_grad(&out)?;
+            let phantom_out = phantom_out.clone();
+            tape.add_backward_op(move |grads| {
+                let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+                inp.device.backward(i, grad_inp, grad_out)
+            });
+        }
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_stack_const_items() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let b = dev.tensor([[5.0, 6.0], [7.0, 8.0]]);
+        let r = dev.stack(std::vec![a.trace(), b.trace()]);
+        assert_eq!(r.shape(), &(2, Const::<2>, Const::<2>));
+        assert_eq!(r.as_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        let g = r.exp().sum().backward();
+        assert_close(&g.get(&a).array(), &a.exp().array());
+        assert_close(&g.get(&b).array(), &b.exp().array());
+    }
+
+    #[test]
+    fn test_stack_runtime_items() {
+        let dev: TestDevice = Default::default();
+        let items: std::vec::Vec<Tensor<(usize,), f32, _>> = (0..4)
+            .map(|i| dev.tensor_from_vec(std::vec![i as f32; 3], (3,)))
+            .collect();
+        let r = dev.stack(items.clone());
+        assert_eq!(r.shape(), &(4, 3));
+        let expected: std::vec::Vec<f32> = (0..4).flat_map(|i| [i as f32; 3]).collect();
+        assert_eq!(r.as_vec(), expected);
+    }
+
+    #[test]
+    fn test_stack_same_tensor_twice() {
+        let dev: TestDevice = Default::default();
+        let a = dev.tensor([1.0, 2.0]);
+        let r = dev.stack(std::vec![a.trace(), a.trace()]);
+        let w = dev.tensor_from_vec(std::vec![1.0, 2.0, 3.0, 4.0], (2, Const::<2>));
+        let g = (r * w).sum().backward();
+        assert_eq!(g.get(&a).array(), [4.0, 6.0]);
+    }
+
+    #[test]
+    fn test_stack_shape_mismatch() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<(usize,), f32, _> = dev.zeros_like(&(2,));
+        let b: Tensor<(usize,), f32, _> = dev.zeros_like(&(3,));
+        assert!(dev.try_stack(std::vec![a, b]).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stack_empty() {
+        let dev: TestDevice = Default::default();
+        let items: std::vec::Vec<Tensor<Rank1<2>, f32, _>> = std::vec::Vec::new();
+        let _ = dev.stack(items);
+    }
+}
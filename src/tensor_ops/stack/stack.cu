@@ -0,0 +1,46 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles one element of `inp`, which is item `i` of `out` along
+// its first axis. `offset` is `i * out_strides[0]`, and `out_strides` are the
+// strides of the other axes of `out`.
+
+extern "C" __global__ void stack_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    const size_t offset,
+    float *out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_inp = get_strided_index(i, num_dims, dims, inp_strides);
+    size_t i_out = offset + get_strided_index(i, num_dims, dims, out_strides);
+    out[i_out] += inp[i_inp];
+}
+
+extern "C" __global__ void stack_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const size_t offset,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (i >= numel) {
+        return;
+    }
+
+    size_t i_inp = get_strided_index(i, num_dims, dims, inp_strides);
+    size_t i_out = offset + get_strided_index(i, num_dims, dims, out_strides);
+    // broadcasted inputs share their gradient between threads
+    atomicAdd(grad_inp + i_inp, grad_out[i_out]);
+}
@@ -36,6 +36,7 @@ pub struct ScalarSubKernelOp<E> {
 /// let r = a - 1.0;
 /// assert_eq!(r.array(), [[0.0, 1.0, 2.0], [-2.0, -3.0, -4.0]]);
 /// ```
+#[track_caller]
 pub fn sub<S: Shape, E: Dtype, D: Device<E>, T: Tape<D> + Merge<RhsTape>, RhsTape: Tape<D>>(
     lhs: Tensor<S, E, D, T>,
     rhs: Tensor<S, E, D, RhsTape>,
@@ -53,8 +54,9 @@ impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<D>, RTape: Tape<D>>
 where
     LTape: Merge<RTape>,
 {
+    #[track_caller]
     fn try_sub(self, rhs: Tensor<S, E, D, RTape>) -> Result<Self, Self::Err> {
-        try_binary_op(BinarySubKernelOp, self, rhs)
+        try_binary_op("sub", BinarySubKernelOp, self, rhs)
     }
 }
 
@@ -70,6 +72,7 @@ where
     Self: TrySub<Rhs>,
 {
     type Output = Self;
+    #[track_caller]
     fn sub(self, rhs: Rhs) -> Self::Output {
         self.try_sub(rhs).unwrap()
     }
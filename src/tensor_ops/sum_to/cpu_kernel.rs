@@ -1,8 +1,49 @@
 use crate::{
     shapes::{Axes, Dtype, ReduceShapeTo, Shape},
-    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+    tensor::{
+        cpu::{Cpu, LendingIterator, StridedArray},
+        DeviceStorage,
+    },
 };
 
+use std::vec::Vec;
+
+/// The number of elements summed sequentially by [pairwise_sum()], small enough to stay in L1 cache.
+const BLOCK_LEN: usize = 128;
+
+/// Sums `x` by splitting it in half until the halves are at most [BLOCK_LEN] long, which keeps
+/// the rounding error at `O(log n)` instead of `O(n)`. Blocks are summed with 8 independent
+/// accumulators, which the compiler can vectorize.
+fn pairwise_sum<E: Dtype>(x: &[E]) -> E {
+    if x.len() > BLOCK_LEN {
+        let (lhs, rhs) = x.split_at(x.len() / 2);
+        return pairwise_sum(lhs) + pairwise_sum(rhs);
+    }
+    let mut acc = [E::default(); 8];
+    let chunks = x.chunks_exact(8);
+    let rem = chunks.remainder();
+    for chunk in chunks {
+        for (a, &v) in acc.iter_mut().zip(chunk.iter()) {
+            *a += v;
+        }
+    }
+    let mut sum = ((acc[0] + acc[1]) + (acc[2] + acc[3])) + ((acc[4] + acc[5]) + (acc[6] + acc[7]));
+    for &v in rem {
+        sum += v;
+    }
+    sum
+}
+
+/// The offset of the `i`th element (in row major order) of the axes `dims_strides`.
+fn offset_of(mut i: usize, dims_strides: &[(usize, usize)]) -> usize {
+    let mut offset = 0;
+    for &(dim, stride) in dims_strides.iter().rev() {
+        offset += (i % dim) * stride;
+        i /= dim;
+    }
+    offset
+}
+
 impl<E: Dtype> super::SumKernel<E> for Cpu {
     fn forward<Src: Shape, Dst: Shape, Ax: Axes>(
         &self,
@@ -12,6 +53,9 @@ impl<E: Dtype> super::SumKernel<E> for Cpu {
     where
         Src: ReduceShapeTo<Dst, Ax>,
     {
+        if self.compensated() {
+            return self.forward_compensated(dst, inp);
+        }
         let mut out: StridedArray<Dst, E> = StridedArray::new(dst)?;
         let mut out_iter = out.iter_mut_as(&inp.shape);
         let mut inp_iter = inp.iter();
@@ -20,6 +64,68 @@ impl<E: Dtype> super::SumKernel<E> for Cpu {
         }
         Ok(out)
     }
+    fn forward_compensated<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let mut out: StridedArray<Dst, E> = StridedArray::new(dst)?;
+        let mut err: StridedArray<Dst, E> = StridedArray::new(dst)?;
+        let mut out_iter = out.iter_mut_as(&inp.shape);
+        let mut err_iter = err.iter_mut_as(&inp.shape);
+        let mut inp_iter = inp.iter();
+        while let Some(((o, e), i)) = out_iter.next().zip(err_iter.next()).zip(inp_iter.next()) {
+            // `e` is the part of the previous additions that was rounded off of `o`
+            let y = *i - *e;
+            let t = *o + y;
+            *e = (t - *o) - y;
+            *o = t;
+        }
+        Ok(out)
+    }
+    fn forward_pairwise<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let (dims, strides) = (inp.shape.concrete(), inp.strides);
+        let (mut kept, mut reduced) = (Vec::new(), Vec::new());
+        for i in 0..Src::NUM_DIMS {
+            match Ax::as_array().into_iter().any(|ax| ax as usize == i) {
+                true => reduced.push((dims[i], strides[i])),
+                false => kept.push((dims[i], strides[i])),
+            }
+        }
+        let chunk_len: usize = reduced.iter().map(|&(dim, _)| dim).product();
+
+        // when the reduced axes are contiguous, each chunk can be summed in place
+        let mut expected_stride = 1;
+        let mut contiguous = true;
+        for &(dim, stride) in reduced.iter().rev() {
+            contiguous &= dim == 1 || stride == expected_stride;
+            expected_stride *= dim;
+        }
+
+        let mut out: StridedArray<Dst, E> = StridedArray::new(dst)?;
+        let mut chunk = Vec::with_capacity(if contiguous { 0 } else { chunk_len });
+        for (i, o) in out.buf_iter_mut().enumerate() {
+            let start = offset_of(i, &kept);
+            *o = if contiguous {
+                pairwise_sum(&inp.data[start..start + chunk_len])
+            } else {
+                chunk.clear();
+                chunk.extend((0..chunk_len).map(|j| inp.data[start + offset_of(j, &reduced)]));
+                pairwise_sum(&chunk)
+            };
+        }
+        Ok(out)
+    }
     fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
         &self,
         grad_inp: &mut Self::Storage<Src, E>,
@@ -1,7 +1,10 @@
 use crate::tensor_ops::internal_reshapes::permute_for_reductions;
 use crate::{
     shapes::{Axes, BroadcastStridesTo, ReduceShapeTo, Shape},
-    tensor::cuda::{Cuda, CudaArray},
+    tensor::{
+        cuda::{Cuda, CudaArray},
+        DeviceStorage,
+    },
 };
 
 use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
@@ -28,6 +31,20 @@ impl super::SumKernel<f32> for Cuda {
                 .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
         }
 
+        if self.compensated() {
+            return super::SumKernel::<f32>::forward_compensated::<Src, Dst, Ax>(self, dst, inp);
+        }
+
+        if self.is_deterministic() {
+            let cpu_inp = self.storage_to_cpu(inp)?;
+            let out = super::SumKernel::<f32>::forward::<Src, Dst, Ax>(&self.cpu, dst, &cpu_inp)?;
+            return Ok(CudaArray {
+                data: Arc::new(self.dev.take_async(out.data.as_ref().clone())?),
+                shape: out.shape,
+                strides: out.strides,
+            });
+        }
+
         let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
 
         let (dims, strides) = permute_for_reductions::<_, Ax>(inp.shape.concrete(), inp.strides);
@@ -62,6 +79,47 @@ impl super::SumKernel<f32> for Cuda {
         })
     }
 
+    fn forward_pairwise<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        if self.is_deterministic() {
+            let cpu_inp = self.storage_to_cpu(inp)?;
+            let out = super::SumKernel::<f32>::forward_pairwise::<Src, Dst, Ax>(
+                &self.cpu, dst, &cpu_inp,
+            )?;
+            return Ok(CudaArray {
+                data: Arc::new(self.dev.take_async(out.data.as_ref().clone())?),
+                shape: out.shape,
+                strides: out.strides,
+            });
+        }
+        // each block of threads already sums as a tree
+        super::SumKernel::<f32>::forward::<Src, Dst, Ax>(self, dst, inp)
+    }
+
+    fn forward_compensated<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<Self::Storage<Dst, f32>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>,
+    {
+        let cpu_inp = self.storage_to_cpu(inp)?;
+        let out =
+            super::SumKernel::<f32>::forward_compensated::<Src, Dst, Ax>(&self.cpu, dst, &cpu_inp)?;
+        Ok(CudaArray {
+            data: Arc::new(self.dev.take_async(out.data.as_ref().clone())?),
+            shape: out.shape,
+            strides: out.strides,
+        })
+    }
+
     fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
         &self,
         grad_inp: &mut Self::Storage<Src, f32>,
@@ -11,6 +11,22 @@ pub trait SumKernel<E: Dtype>: DeviceStorage {
         dst: Dst,
         inp: &Self::Storage<Src, E>,
     ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+    /// Like [SumKernel::forward()], but with pairwise summation, see [SumTo::sum_pairwise()].
+    fn forward_pairwise<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
+    where
+        Src: ReduceShapeTo<Dst, Ax>;
+    /// Like [SumKernel::forward()], but with Kahan summation, see [SumTo::sum_compensated()].
+    fn forward_compensated<Src: Shape, Dst: Shape, Ax: Axes>(
+        &self,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<Self::Storage<Dst, E>, Self::Err>
     where
         Src: ReduceShapeTo<Dst, Ax>;
     fn backward<Src: Shape, Dst: Shape, Ax: Axes>(
@@ -53,6 +69,70 @@ pub trait SumTo: HasErr + HasShape {
     fn try_sum<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
     where
         Self::Shape: ReduceShapeTo<Dst, Ax>;
+
+    /// [SumTo::sum] using [pairwise summation](https://en.wikipedia.org/wiki/Pairwise_summation),
+    /// which has much less rounding error when summing many elements. For example summing
+    /// `20M` ones in `f32` with [SumTo::sum] gets stuck at `2^24 = 16777216`, since
+    /// `2^24 + 1` can't be represented, while this results in exactly `20M`.
+    ///
+    /// The elements of each sum are split into blocks that fit in cache, which are summed
+    /// sequentially & then added together in a tree. This is usually faster than [SumTo::sum]
+    /// when summing along contiguous axes too.
+    ///
+    /// On `Cuda`, sums are already computed as a tree in each block of threads, so this is the
+    /// same as [SumTo::sum] except in deterministic mode.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let t: Tensor<Rank2<4, 1000>, f32, _> = dev.ones() * 0.1;
+    /// let r = t.sum_pairwise::<Rank1<4>, _>();
+    /// assert!(r.array().iter().all(|&x| (x - 100.0).abs() < 1e-4));
+    /// ```
+    fn sum_pairwise<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_sum_pairwise().unwrap()
+    }
+    /// Fallible version of [SumTo::sum_pairwise]
+    fn try_sum_pairwise<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
+
+    /// [SumTo::sum] using [Kahan summation](https://en.wikipedia.org/wiki/Kahan_summation_algorithm),
+    /// which keeps the rounding error of the sum so far, and adds it back with the next element.
+    /// The error doesn't grow with the number of elements, even when they are visited in a
+    /// strided order, at the cost of 4 operations per element.
+    ///
+    /// Use `Cpu::set_compensated()` to make every [SumTo::sum] (and so `mean` and
+    /// everything else built on it) on a device use this instead.
+    ///
+    /// On `Cuda`, this runs on the host.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// // 1 followed by 1000 elements that are too small to change it on their own
+    /// let mut x = std::vec![1e-8f32; 1001];
+    /// x[0] = 1.0;
+    /// let t = dev.tensor_from_vec(x, (1001,));
+    /// assert_eq!(t.clone().sum::<Rank0, _>().array(), 1.0);
+    /// let r = t.sum_compensated::<Rank0, _>();
+    /// assert!((r.array() - 1.00001).abs() < 2e-7);
+    /// ```
+    fn sum_compensated<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        self.try_sum_compensated().unwrap()
+    }
+    /// Fallible version of [SumTo::sum_compensated]
+    fn try_sum_compensated<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>;
 }
 
 impl<S: Shape, E: Dtype, D: SumKernel<E>, T: Tape<D>> SumTo for Tensor<S, E, D, T> {
@@ -61,19 +141,53 @@ impl<S: Shape, E: Dtype, D: SumKernel<E>, T: Tape<D>> SumTo for Tensor<S, E, D,
         Self::Shape: ReduceShapeTo<Dst, Ax>,
     {
         let dst: Dst = self.shape().reduced();
-        let (inp, mut tape) = self.split_tape();
-        let out = inp.device.upgrade(inp.device.forward(dst, &inp.storage)?);
-        let phantom_out = out.clone();
-        tape.try_alloc_grad(&inp)?;
-        tape.try_alloc_grad(&out)?;
-        tape.add_backward_op(move |grads| {
-            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
-            inp.device.backward(grad_inp, grad_out)
-        });
-        Ok(out.put_tape(tape))
+        let (inp, tape) = self.split_tape();
+        let out = inp.device.forward(dst, &inp.storage)?;
+        sum_backward(inp, tape, out)
+    }
+
+    fn try_sum_pairwise<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let (inp, tape) = self.split_tape();
+        let out = inp.device.forward_pairwise(dst, &inp.storage)?;
+        sum_backward(inp, tape, out)
+    }
+
+    fn try_sum_compensated<Dst: Shape, Ax: Axes>(self) -> Result<Self::WithShape<Dst>, Self::Err>
+    where
+        Self::Shape: ReduceShapeTo<Dst, Ax>,
+    {
+        let dst: Dst = self.shape().reduced();
+        let (inp, tape) = self.split_tape();
+        let out = inp.device.forward_compensated(dst, &inp.storage)?;
+        sum_backward(inp, tape, out)
     }
 }
 
+/// Records the backward op of a sum of `inp` into `out`, which is the same no matter
+/// how the sum was computed.
+fn sum_backward<Src: Shape, Dst: Shape, Ax: Axes, E: Dtype, D: SumKernel<E>, T: Tape<D>>(
+    inp: Tensor<Src, E, D>,
+    mut tape: T,
+    out: D::Storage<Dst, E>,
+) -> Result<Tensor<Dst, E, D, T>, D::Err>
+where
+    Src: ReduceShapeTo<Dst, Ax>,
+{
+    let out = inp.device.upgrade(out);
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&inp)?;
+    tape.try_alloc_grad(&out)?;
+    tape.add_backward_op(move |grads| {
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+        inp.device.backward(grad_inp, grad_out)
+    });
+    Ok(out.put_tape(tape))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,4 +261,81 @@ mod tests {
         let g = r.sum().backward();
         assert_close(&g.get(&t).array(), &t.array());
     }
+
+    #[test]
+    fn test_sum_pairwise_matches_sum() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<3, 5, 300>, f32, _> = dev.sample_normal();
+        let r = t.trace().sum_pairwise::<Rank1<3>, _>();
+        assert_close_with_tolerance(&r.array(), &t.clone().sum::<Rank1<3>, _>().array(), 1e-4);
+        let g = (r * dev.tensor([1.0, 2.0, 3.0])).sum().backward();
+        assert_eq!(
+            g.get(&t).array(),
+            [[[1.0; 300]; 5], [[2.0; 300]; 5], [[3.0; 300]; 5]]
+        );
+
+        // non contiguous & broadcasted reductions
+        assert_close_with_tolerance(
+            &t.clone().sum_pairwise::<Rank2<3, 300>, _>().array(),
+            &t.clone().sum::<Rank2<3, 300>, _>().array(),
+            1e-4,
+        );
+        assert_close_with_tolerance(
+            &t.clone().sum_pairwise::<Rank1<300>, _>().array(),
+            &t.clone().sum::<Rank1<300>, _>().array(),
+            1e-4,
+        );
+        let b = t.clone().broadcast::<Rank4<3, 5, 2, 300>, _>();
+        assert_close_with_tolerance(
+            &b.clone().sum_pairwise::<Rank2<3, 300>, _>().array(),
+            &b.sum::<Rank2<3, 300>, _>().array(),
+            1e-4,
+        );
+        let p = t.clone().permute::<Rank3<300, 5, 3>, _>();
+        assert_close_with_tolerance(
+            &p.clone().sum_pairwise::<Rank1<3>, _>().array(),
+            &t.sum::<Rank1<3>, _>().array(),
+            1e-4,
+        );
+    }
+
+    #[test]
+    fn test_sum_pairwise_accuracy() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<(usize,), f32, _> = dev.ones_like(&(20_000_000,));
+        assert_eq!(t.clone().sum::<Rank0, _>().array(), 16777216.0);
+        assert_eq!(t.sum_pairwise::<Rank0, _>().array(), 20_000_000.0);
+
+        let t: Tensor<(usize,), f32, _> = dev.zeros_like(&(0,));
+        assert_eq!(t.sum_pairwise::<Rank0, _>().array(), 0.0);
+    }
+
+    #[test]
+    fn test_sum_compensated() {
+        let dev: TestDevice = Default::default();
+        let mut x = std::vec![0.1; 3 * 100_000];
+        for i in 0..3 {
+            x[i * 100_000] = 1e4;
+        }
+        let t = dev.tensor_from_vec(x, (3, 100_000));
+        let naive: std::vec::Vec<f32> = t.clone().sum::<(usize,), Axis<1>>().as_vec();
+        let r = t.trace().sum_compensated::<(usize,), Axis<1>>();
+        assert!((naive[0] - 19999.9).abs() > 0.1);
+        assert_close_with_tolerance(&r.as_vec(), &std::vec![19999.9; 3], 1e-3);
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).as_vec(), std::vec![1.0; 3 * 100_000]);
+
+        // strided reductions keep a separate error for each output
+        let p = t.clone().permute::<(usize, usize), _>();
+        let r = p.sum_compensated::<(usize,), Axis<0>>();
+        assert_close_with_tolerance(&r.as_vec(), &std::vec![19999.9; 3], 1e-3);
+
+        // the device setting applies to every sum, including mean
+        dev.set_compensated(true);
+        let r = t.clone().sum::<(usize,), Axis<1>>();
+        assert_close_with_tolerance(&r.as_vec(), &std::vec![19999.9; 3], 1e-3);
+        let m = t.mean::<Rank0, _>();
+        assert!((m.array() - 19999.9 / 100_000.0).abs() < 1e-6);
+        dev.set_compensated(false);
+    }
 }
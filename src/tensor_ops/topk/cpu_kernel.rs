@@ -0,0 +1,72 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, StridedArray},
+    tensor_ops::utilities::cpu_kernels::for_each_row,
+};
+use std::{sync::Arc, vec::Vec};
+
+impl<E: Dtype> super::TopKKernel<E> for Cpu {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        descending: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<(Self::Storage<Dst, E>, Self::Storage<Dst, usize>), Self::Err> {
+        let mut values: StridedArray<Dst, E> = StridedArray::new(dst)?;
+        let mut idx: StridedArray<Dst, usize> = StridedArray::new(dst)?;
+        let n = inp.shape.concrete()[ax];
+        let k = dst.concrete()[ax];
+        let dims: Vec<usize> = inp.shape.concrete().into();
+        let inp_strides: Vec<usize> = inp.strides.into();
+        let dst_strides: Vec<usize> = values.strides.into();
+        let values_buf = Arc::make_mut(&mut values.data);
+        let idx_buf = Arc::make_mut(&mut idx.data);
+        let mut row: Vec<usize> = Vec::with_capacity(n);
+        for_each_row(&dims, ax, [&inp_strides, &dst_strides], |[i_inp, i_dst]| {
+            let value = |j: usize| inp.data[i_inp + j * inp_strides[ax]];
+            row.clear();
+            row.extend(0..n);
+            // stable, so equal values stay ordered by index
+            row.sort_by(|&a, &b| {
+                let (a, b) = if descending { (b, a) } else { (a, b) };
+                value(a)
+                    .partial_cmp(&value(b))
+                    .unwrap_or(core::cmp::Ordering::Equal)
+            });
+            for (i_k, &j) in row.iter().take(k).enumerate() {
+                values_buf[i_dst + i_k * dst_strides[ax]] = value(j);
+                idx_buf[i_dst + i_k * dst_strides[ax]] = j;
+            }
+        });
+        Ok((values, idx))
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        grad_inp: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err> {
+        let k = grad_out.shape.concrete()[ax];
+        let dims: Vec<usize> = grad_out.shape.concrete().into();
+        let inp_strides: Vec<usize> = grad_inp.strides.into();
+        let idx_strides: Vec<usize> = idx.strides.into();
+        let out_strides: Vec<usize> = grad_out.strides.into();
+        let grad_inp_buf = Arc::make_mut(&mut grad_inp.data);
+        for_each_row(
+            &dims,
+            ax,
+            [&inp_strides, &idx_strides, &out_strides],
+            |[i_inp, i_idx, i_out]| {
+                for i_k in 0..k {
+                    let j = idx.data[i_idx + i_k * idx_strides[ax]];
+                    grad_inp_buf[i_inp + j * inp_strides[ax]] +=
+                        grad_out.data[i_out + i_k * out_strides[ax]];
+                }
+            },
+        );
+        Ok(())
+    }
+}
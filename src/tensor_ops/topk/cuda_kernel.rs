@@ -0,0 +1,102 @@
+use crate::{
+    shapes::Shape,
+    tensor::cuda::{Cuda, CudaArray},
+};
+use cudarc::driver::{CudaSlice, LaunchAsync, LaunchConfig};
+use std::sync::Arc;
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/topk.ptx"));
+const MODULE_NAME: &str = "topk";
+const FWD_FN_NAME: &str = "topk_forward";
+const BWD_FN_NAME: &str = "topk_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+
+impl super::TopKKernel<f32> for Cuda {
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        descending: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, f32>,
+    ) -> Result<(Self::Storage<Dst, f32>, Self::Storage<Dst, usize>), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let numel = dst.num_elements();
+        let k = dst.concrete()[ax];
+        let num_rows = inp.shape.num_elements() / inp.shape.concrete()[ax].max(1);
+        let dst_strides = dst.strides();
+        let mut values = self.dev.alloc_zeros_async::<f32>(numel)?;
+        let mut idx = self.dev.alloc_zeros_async::<usize>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(inp.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(dst_strides.into())?;
+
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,          // const size_t num_rows,
+            Src::NUM_DIMS,     // const size_t num_dims,
+            ax,                // const size_t ax,
+            k,                 // const size_t k,
+            descending as u8,  // const uint8_t descending,
+            &dims,             // const size_t *dims,
+            inp.data.as_ref(), // const float *inp,
+            &inp_strides,      // const size_t *inp_strides,
+            &mut values,       // float *values,
+            &mut idx,          // size_t *idx,
+            &out_strides,      // const size_t *dst_strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+
+        let values = CudaArray {
+            data: Arc::new(values),
+            shape: dst,
+            strides: dst_strides,
+        };
+        let idx = CudaArray {
+            data: Arc::new(idx),
+            shape: dst,
+            strides: dst_strides,
+        };
+        Ok((values, idx))
+    }
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        grad_inp: &mut Self::Storage<Src, f32>,
+        idx: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, f32>,
+    ) -> Result<(), Self::Err> {
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+
+        let k = grad_out.shape.concrete()[ax];
+        let num_rows = grad_out.shape.num_elements() / k.max(1);
+
+        let dims: CudaSlice<usize> = self.dev.take_async(grad_inp.shape.concrete().into())?;
+        let inp_strides: CudaSlice<usize> = self.dev.take_async(grad_inp.strides.into())?;
+        let idx_strides: CudaSlice<usize> = self.dev.take_async(idx.strides.into())?;
+        let out_strides: CudaSlice<usize> = self.dev.take_async(grad_out.strides.into())?;
+
+        let cfg = LaunchConfig::for_num_elems(num_rows as u32);
+        let params = (
+            num_rows,                          // const size_t num_rows,
+            Src::NUM_DIMS,                     // const size_t num_dims,
+            ax,                                // const size_t ax,
+            k,                                 // const size_t k,
+            &dims,                             // const size_t *dims,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            &inp_strides,                      // const size_t *inp_strides,
+            idx.data.as_ref(),                 // const size_t *idx,
+            &idx_strides,                      // const size_t *idx_strides,
+            grad_out.data.as_ref(),            // const float *grad_out,
+            &out_strides,                      // const size_t *out_strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::{Axes, Const, Dtype, HasShape, ReplaceAxis, Shape},
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor},
+};
+
+pub trait TopKKernel<E: Dtype>: DeviceStorage {
+    /// The `k = dst.concrete()[ax]` largest (or smallest if `!descending`) values along
+    /// axis `ax`, and their indices. Equal values are ordered by index.
+    #[allow(clippy::type_complexity)]
+    fn forward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        descending: bool,
+        dst: Dst,
+        inp: &Self::Storage<Src, E>,
+    ) -> Result<(Self::Storage<Dst, E>, Self::Storage<Dst, usize>), Self::Err>;
+
+    fn backward<Src: Shape, Dst: Shape>(
+        &self,
+        ax: usize,
+        grad_inp: &mut Self::Storage<Src, E>,
+        idx: &Self::Storage<Dst, usize>,
+        grad_out: &Self::Storage<Dst, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+/// The `K` largest values along an axis, and their indices along that axis.
+///
+/// Values are sorted in descending order. Equal values are ordered by their index,
+/// and the order of NaNs is unspecified.
+///
+/// The gradient of each value is added to the position it was selected from.
+///
+/// **Panics** if `K` is larger than the size of the axis.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 5.0, 3.0, 2.0], [0.0, -1.0, 4.0, 4.0]]);
+/// let (values, indices) = t.topk::<2, Axis<1>>();
+/// assert_eq!(values.array(), [[5.0, 3.0], [4.0, 4.0]]);
+/// assert_eq!(indices.array(), [[1, 2], [2, 3]]);
+/// ```
+pub trait TopK<D: DeviceStorage>: HasErr + HasShape {
+    /// See [TopK]
+    #[allow(clippy::type_complexity)]
+    fn topk<const K: usize, Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> (
+        Self::WithShape<<Self::Shape as ReplaceAxis<Ax, Const<K>>>::Output>,
+        Tensor<<Self::Shape as ReplaceAxis<Ax, Const<K>>>::Output, usize, D>,
+    )
+    where
+        Self::Shape: ReplaceAxis<Ax, Const<K>>,
+    {
+        self.try_topk::<K, Ax>().unwrap()
+    }
+
+    /// Fallible version of [TopK::topk]
+    #[allow(clippy::type_complexity)]
+    fn try_topk<const K: usize, Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<
+        (
+            Self::WithShape<<Self::Shape as ReplaceAxis<Ax, Const<K>>>::Output>,
+            Tensor<<Self::Shape as ReplaceAxis<Ax, Const<K>>>::Output, usize, D>,
+        ),
+        Self::Err,
+    >
+    where
+        Self::Shape: ReplaceAxis<Ax, Const<K>>;
+}
+
+impl<S: Shape, E: Dtype, D: TopKKernel<E>, T: Tape<D>> TopK<D> for Tensor<S, E, D, T> {
+    fn try_topk<const K: usize, Ax: Axes<Array = [isize; 1]>>(
+        self,
+    ) -> Result<
+        (
+            Self::WithShape<<S as ReplaceAxis<Ax, Const<K>>>::Output>,
+            Tensor<<S as ReplaceAxis<Ax, Const<K>>>::Output, usize, D>,
+        ),
+        Self::Err,
+    >
+    where
+        S: ReplaceAxis<Ax, Const<K>>,
+    {
+        let ax = Ax::as_array()[0] as usize;
+        let size = self.shape().concrete()[ax];
+        assert!(
+            K <= size,
+            "topk: can't take the top {K} values of axis {ax}, which only has size {size}"
+        );
+        let dst = self.shape().replace_axis(Const);
+        try_sorted_along(self, ax, true, dst)
+    }
+}
+
+/// The first `dst.concrete()[ax]` values of `t` sorted along `ax`, and their indices.
+/// The gradient of each value is added to the position it came from.
+#[allow(clippy::type_complexity)]
+pub(crate) fn try_sorted_along<S: Shape, Dst: Shape, E: Dtype, D: TopKKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    ax: usize,
+    descending: bool,
+    dst: Dst,
+) -> Result<(Tensor<Dst, E, D, T>, Tensor<Dst, usize, D>), D::Err> {
+    let (inp, mut tape) = t.split_tape();
+    let (values, idx) = inp.device.forward(ax, descending, dst, &inp.storage)?;
+    let out = inp.device.upgrade(values);
+    let idx = inp.device.upgrade(idx);
+    let phantom_out = out.clone();
+    let phantom_idx = idx.clone();
+    tape.try_alloc_grad(&inp)?;
+    tape.try_alloc_grad(&out)?;
+    tape.add_backward_op(move |grads| {
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+        inp.device
+            .backward(ax, grad_inp, &phantom_idx.storage, grad_out)
+    });
+    Ok((out.put_tape(tape), idx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_topk_1d() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([0.5, -1.0, 3.0, 2.0, 3.0]);
+        let (v, i) = t.trace().topk::<3, Axis<0>>();
+        assert_eq!(v.array(), [3.0, 3.0, 2.0]);
+        assert_eq!(i.array(), [2, 4, 3]);
+        let g = (v * dev.tensor([1.0, 2.0, 3.0])).sum().backward();
+        assert_eq!(g.get(&t).array(), [0.0, 0.0, 1.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn test_topk_2d_axis_0() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([[1.0, 6.0], [4.0, 2.0], [3.0, 5.0]]);
+        let (v, i) = t.trace().topk::<2, Axis<0>>();
+        assert_eq!(v.array(), [[4.0, 6.0], [3.0, 5.0]]);
+        assert_eq!(i.array(), [[1, 0], [2, 2]]);
+        let g = v.exp().sum().backward();
+        let e = f32::exp;
+        assert_eq!(
+            g.get(&t).array(),
+            [[0.0, e(6.0)], [e(4.0), 0.0], [e(3.0), e(5.0)]]
+        );
+    }
+
+    #[test]
+    fn test_topk_3d_matches_sorted() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 5, 3>, f32, _> = dev.sample_normal();
+        let (v, i) = t.clone().topk::<5, Axis<1>>();
+        let (t, v, i) = (t.array(), v.array(), i.array());
+        for b in 0..2 {
+            for c in 0..3 {
+                let mut expected: std::vec::Vec<f32> = (0..5).map(|j| t[b][j][c]).collect();
+                expected.sort_by(|x, y| y.partial_cmp(x).unwrap());
+                for k in 0..5 {
+                    assert_eq!(v[b][k][c], expected[k]);
+                    assert_eq!(t[b][i[b][k][c]][c], expected[k]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_topk_too_large() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+        let _ = t.topk::<4, Axis<1>>();
+    }
+}
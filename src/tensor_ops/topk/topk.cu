@@ -0,0 +1,84 @@
+#include "cuda_utils.cuh"
+
+// Each thread handles a single row along `ax`.
+// Values are ordered by value (descending or ascending), then by index (ascending).
+
+__device__ bool comes_before(float a, size_t i, float b, size_t j, bool descending) {
+    return (descending ? a > b : a < b) || (a == b && i < j);
+}
+
+extern "C" __global__ void topk_forward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t k,
+    const uint8_t descending,
+    const size_t *dims,
+    const float *inp,
+    const size_t *inp_strides,
+    float *values,
+    size_t *idx,
+    const size_t *dst_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_dst = get_row_index(row, num_dims, ax, dims, dst_strides);
+
+    // selects the next value k times, which doesn't need any extra memory
+    const size_t n = dims[ax];
+    float prev_v = 0.0;
+    size_t prev_j = 0;
+    for (size_t i_k = 0; i_k < k; i_k++) {
+        bool found = false;
+        float best_v = 0.0;
+        size_t best_j = 0;
+        for (size_t j = 0; j < n; j++) {
+            float v = inp[i_inp + j * inp_strides[ax]];
+            if (i_k > 0 && !comes_before(prev_v, prev_j, v, j, descending)) {
+                continue;
+            }
+            if (!found || comes_before(v, j, best_v, best_j, descending)) {
+                found = true;
+                best_v = v;
+                best_j = j;
+            }
+        }
+        values[i_dst + i_k * dst_strides[ax]] = best_v;
+        idx[i_dst + i_k * dst_strides[ax]] = best_j;
+        prev_v = best_v;
+        prev_j = best_j;
+    }
+}
+
+// The indices in a row are unique, so this doesn't need atomics.
+extern "C" __global__ void topk_backward(
+    const size_t num_rows,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t k,
+    const size_t *dims,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const size_t *idx,
+    const size_t *idx_strides,
+    const float *grad_out,
+    const size_t *out_strides
+) {
+    unsigned int row = blockIdx.x * blockDim.x + threadIdx.x;
+    if (row >= num_rows) {
+        return;
+    }
+
+    size_t i_inp = get_row_index(row, num_dims, ax, dims, inp_strides);
+    size_t i_idx = get_row_index(row, num_dims, ax, dims, idx_strides);
+    size_t i_out = get_row_index(row, num_dims, ax, dims, out_strides);
+
+    for (size_t i_k = 0; i_k < k; i_k++) {
+        size_t j = idx[i_idx + i_k * idx_strides[ax]];
+        grad_inp[i_inp + j * inp_strides[ax]] += grad_out[i_out + i_k * out_strides[ax]];
+    }
+}
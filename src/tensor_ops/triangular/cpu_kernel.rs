@@ -0,0 +1,49 @@
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::cpu::{Cpu, LendingIterator, StridedArray},
+};
+
+/// Whether the last two dims of `i` are in the lower (or upper) triangle.
+#[inline(always)]
+fn in_triangle<S: Shape>(i: S::Concrete, upper: bool) -> bool {
+    let (row, col) = (i[S::NUM_DIMS - 2], i[S::NUM_DIMS - 1]);
+    if upper {
+        col >= row
+    } else {
+        col <= row
+    }
+}
+
+impl<E: Dtype> super::TriangleKernel<E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        inp: &Self::Storage<S, E>,
+        upper: bool,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: Self::Storage<S, E> = StridedArray::new(inp.shape)?;
+        let mut inp_iter = inp.iter();
+        let mut out_iter = out.iter_mut_with_index();
+        while let Some(((o, i), x)) = out_iter.next().zip(inp_iter.next()) {
+            if in_triangle::<S>(i, upper) {
+                *o = *x;
+            }
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+        upper: bool,
+    ) -> Result<(), Self::Err> {
+        let mut inp_iter = grad_inp.iter_mut_with_index();
+        let mut out_iter = grad_out.iter();
+        while let Some(((g, i), o)) = inp_iter.next().zip(out_iter.next()) {
+            if in_triangle::<S>(i, upper) {
+                *g += *o;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,173 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+};
+
+pub trait TriangleKernel<E: Dtype>: DeviceStorage {
+    /// Copies `inp`, with everything outside of the lower (or upper) triangle set to zero.
+    fn forward<S: Shape>(
+        &self,
+        inp: &Self::Storage<S, E>,
+        upper: bool,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    /// Adds the lower (or upper) triangle of `grad_out` to `grad_inp`.
+    fn backward<S: Shape>(
+        &self,
+        grad_inp: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+        upper: bool,
+    ) -> Result<(), Self::Err>;
+}
+
+/// Keeps the values on and below the diagonal of the last two dimensions, and sets everything
+/// above it to zero. The gradient of the zeroed values is zero.
+///
+/// **Pytorch equivalent**: `t.tril()`
+///
+/// **Panics** if `t` has less than 2 dimensions.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// assert_eq!(t.tril().array(), [[1.0, 0.0, 0.0], [4.0, 5.0, 0.0]]);
+/// ```
+pub fn tril<S: Shape, E: Dtype, D: TriangleKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.tril()
+}
+
+/// Keeps the values on and above the diagonal of the last two dimensions, and sets everything
+/// below it to zero. The gradient of the zeroed values is zero.
+///
+/// **Pytorch equivalent**: `t.triu()`
+///
+/// **Panics** if `t` has less than 2 dimensions.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+/// assert_eq!(t.triu().array(), [[1.0, 2.0, 3.0], [0.0, 5.0, 6.0]]);
+/// ```
+pub fn triu<S: Shape, E: Dtype, D: TriangleKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.triu()
+}
+
+impl<S: Shape, E: Dtype, D: TriangleKernel<E>, T: Tape<D>> Tensor<S, E, D, T> {
+    /// See [tril]
+    pub fn tril(self) -> Self {
+        self.try_tril().unwrap()
+    }
+
+    /// See [tril]
+    pub fn try_tril(self) -> Result<Self, D::Err> {
+        try_triangle_op(self, false)
+    }
+
+    /// See [triu]
+    pub fn triu(self) -> Self {
+        self.try_triu().unwrap()
+    }
+
+    /// See [triu]
+    pub fn try_triu(self) -> Result<Self, D::Err> {
+        try_triangle_op(self, true)
+    }
+}
+
+fn try_triangle_op<S: Shape, E: Dtype, D: TriangleKernel<E>, T: Tape<D>>(
+    t: Tensor<S, E, D, T>,
+    upper: bool,
+) -> Result<Tensor<S, E, D, T>, D::Err> {
+    assert!(S::NUM_DIMS >= 2, "tril/triu need at least 2 dimensions");
+    let (inp, mut tape) = t.split_tape();
+    let storage = inp.device.forward(&inp.storage, upper)?;
+    let out = inp.device.upgrade(storage);
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&inp)?;
+    tape.try_alloc_grad(&out)?;
+    tape.add_backward_op(move |grads| {
+        let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+        inp.device.backward(grad_inp, grad_out, upper)
+    });
+    Ok(out.put_tape(tape))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_tril_triu_3d() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank3<2, 3, 4>, f32, _> = dev.sample_normal();
+        let lower: Tensor<Rank3<2, 3, 4>, f32, _> = dev.lower_triangular();
+        let upper: Tensor<Rank3<2, 3, 4>, f32, _> = dev.upper_triangular();
+        assert_eq!(t.clone().tril().array(), (t.clone() * lower).array());
+        assert_eq!(t.clone().triu().array(), (t.clone() * upper).array());
+
+        let diag = (t.clone().tril() + t.clone().triu() - t.clone()).array();
+        for (i, m) in diag.iter().enumerate() {
+            for (r, row) in m.iter().enumerate() {
+                for (c, x) in row.iter().enumerate() {
+                    let e = if r == c { t.array()[i][r][c] } else { 0.0 };
+                    assert_eq!(*x, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tril_triu_backward() {
+        let dev: TestDevice = Default::default();
+        let e = |x: f32| x.exp();
+
+        // values outside of the triangle don't leak into the result, even if they aren't finite
+        let t = dev.tensor([[1.0, f32::NEG_INFINITY], [3.0, 4.0]]);
+        let r = t.trace().tril();
+        assert_eq!(r.array(), [[1.0, 0.0], [3.0, 4.0]]);
+        let g = r.exp().sum().backward();
+        assert_eq!(g.get(&t).array(), [[e(1.0), 0.0], [e(3.0), e(4.0)]]);
+
+        let t = dev.tensor([[1.0, 2.0], [f32::NAN, 4.0]]);
+        let r = t.trace().triu();
+        assert_eq!(r.array(), [[1.0, 2.0], [0.0, 4.0]]);
+        let g = r.exp().sum().backward();
+        assert_eq!(g.get(&t).array(), [[e(1.0), e(2.0)], [0.0, e(4.0)]]);
+    }
+
+    #[test]
+    fn test_tril_broadcasted() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([1.0, 2.0, 3.0]);
+        let r = t.trace().broadcast::<Rank2<3, 3>, Axis<0>>().tril();
+        assert_eq!(
+            r.array(),
+            [[1.0, 0.0, 0.0], [1.0, 2.0, 0.0], [1.0, 2.0, 3.0]]
+        );
+        let g = r.sum().backward();
+        assert_eq!(g.get(&t).array(), [3.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_triangular_masks() {
+        let dev: TestDevice = Default::default();
+        let lower: Tensor<(usize, usize), bool, _> = dev.lower_triangular_like(&(2, 3));
+        assert_eq!(lower.as_vec(), [true, false, false, true, true, false]);
+        let upper: Tensor<Rank2<3, 2>, f32, _> = dev.upper_triangular();
+        assert_eq!(upper.array(), [[1.0, 1.0], [0.0, 1.0], [0.0, 0.0]]);
+    }
+}
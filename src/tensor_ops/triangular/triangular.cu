@@ -0,0 +1,56 @@
+#include "cuda_utils.cuh"
+
+// whether the last two dims of the contiguous index `i` are in the triangle
+__device__ bool in_triangle(
+    unsigned int i,
+    const size_t num_dims,
+    const size_t *dims,
+    const uint8_t upper
+) {
+    size_t num_cols = dims[num_dims - 1];
+    size_t col = i % num_cols;
+    size_t row = (i / num_cols) % dims[num_dims - 2];
+    return upper ? col >= row : col <= row;
+}
+
+extern "C" __global__ void triangle_forward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const uint8_t upper,
+    const float *inp,
+    const size_t *inp_strides,
+    float *out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    if (in_triangle(out_i, num_dims, dims, upper)) {
+        unsigned int inp_i = get_strided_index(out_i, num_dims, dims, inp_strides);
+        out[out_i] = inp[inp_i];
+    } else {
+        out[out_i] = 0.0;
+    }
+}
+
+extern "C" __global__ void triangle_backward(
+    const size_t numel,
+    const size_t num_dims,
+    const size_t *dims,
+    const uint8_t upper,
+    float *grad_inp,
+    const size_t *inp_strides,
+    const float *grad_out
+) {
+    unsigned int out_i = blockIdx.x * blockDim.x + threadIdx.x;
+    if (out_i >= numel) {
+        return;
+    }
+
+    if (in_triangle(out_i, num_dims, dims, upper)) {
+        unsigned int inp_i = get_strided_index(out_i, num_dims, dims, inp_strides);
+        atomicAdd(grad_inp + inp_i, grad_out[out_i]);
+    }
+}
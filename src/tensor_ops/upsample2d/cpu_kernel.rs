@@ -0,0 +1,77 @@
+use crate::shapes::*;
+use crate::tensor::cpu::Cpu;
+
+use super::Upsample2DOp;
+
+use std::sync::Arc;
+
+/// Returns strides in (batch, channel, height, width) order.
+fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
+    match S::NUM_DIMS {
+        3 => [0, strides[0], strides[1], strides[2]],
+        4 => [strides[0], strides[1], strides[2], strides[3]],
+        _ => panic!("Only implemented for 3d & 4d arrays"),
+    }
+}
+
+impl super::Upsample2DKernel<f32> for Cpu {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                let inp_bc = &buf[b * istr[0] + c * istr[1]..];
+                for oh in 0..op.h_out {
+                    let (y0, y1, ly) = op.src(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1, lx) = op.src(ow, op.w_in, op.w_out);
+                        let at = |y: usize, x: usize| inp_bc[y * istr[2] + x * istr[3]];
+                        let top = (1.0 - lx) * at(y0, x0) + lx * at(y0, x1);
+                        let bottom = (1.0 - lx) * at(y1, x0) + lx * at(y1, x1);
+                        out_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]] =
+                            (1.0 - ly) * top + ly * bottom;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        let istr = make_4d::<I>(grad_inp.strides);
+        let ostr = make_4d::<O>(grad_out.strides);
+
+        let ginp_buf = Arc::make_mut(&mut grad_inp.data);
+        let buf = grad_out.data.as_ref();
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                let ginp_bc = &mut ginp_buf[b * istr[0] + c * istr[1]..];
+                for oh in 0..op.h_out {
+                    let (y0, y1, ly) = op.src(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let (x0, x1, lx) = op.src(ow, op.w_in, op.w_out);
+                        let go = buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]];
+                        ginp_bc[y0 * istr[2] + x0 * istr[3]] += (1.0 - ly) * (1.0 - lx) * go;
+                        ginp_bc[y0 * istr[2] + x1 * istr[3]] += (1.0 - ly) * lx * go;
+                        ginp_bc[y1 * istr[2] + x0 * istr[3]] += ly * (1.0 - lx) * go;
+                        ginp_bc[y1 * istr[2] + x1 * istr[3]] += ly * lx * go;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
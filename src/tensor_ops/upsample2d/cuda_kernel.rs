@@ -0,0 +1,104 @@
+use super::{Upsample2DKernel, Upsample2DOp, UpsampleMode};
+use crate::{shapes::*, tensor::cuda::Cuda};
+
+use std::sync::Arc;
+
+use cudarc::driver::{LaunchAsync, LaunchConfig};
+
+const MODULE_NAME: &str = "upsample2d";
+const FWD_FN_NAME: &str = "upsample2d_forward";
+const BWD_FN_NAME: &str = "upsample2d_backward";
+const ALL_FN_NAMES: [&str; 2] = [FWD_FN_NAME, BWD_FN_NAME];
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/upsample2d.ptx"));
+
+/// Returns strides in (batch, channel, height, width) order.
+fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
+    match S::NUM_DIMS {
+        3 => [0, strides[0], strides[1], strides[2]],
+        4 => [strides[0], strides[1], strides[2], strides[3]],
+        _ => panic!("Only implemented for 3d & 4d arrays"),
+    }
+}
+
+impl Upsample2DOp {
+    /// The mode code that the cuda kernels use
+    fn mode_code(&self) -> u8 {
+        match self.mode {
+            UpsampleMode::Nearest => 0,
+            UpsampleMode::Bilinear {
+                align_corners: false,
+            } => 1,
+            UpsampleMode::Bilinear {
+                align_corners: true,
+            } => 2,
+        }
+    }
+}
+
+impl Upsample2DKernel<f32> for Cuda {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        inp: &Self::Storage<I, f32>,
+        out: &mut Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if !self.dev.has_func(MODULE_NAME, FWD_FN_NAME) {
+            self.dev
+                .load_ptx(PTX_SRC.into(), MODULE_NAME, &ALL_FN_NAMES)?;
+        }
+
+        let inp_strides = self.dev.take_async(make_4d::<I>(inp.strides).into())?;
+        let out_strides = self.dev.take_async(make_4d::<O>(out.strides).into())?;
+        let fwd_fn = self.dev.get_func(MODULE_NAME, FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(out.shape().num_elements() as u32);
+        let params = (
+            op.batch,                     // const size_t batch,
+            op.chan,                      // const size_t chan,
+            op.h_in,                      // const size_t h_in,
+            op.h_out,                     // const size_t h_out,
+            op.w_in,                      // const size_t w_in,
+            op.w_out,                     // const size_t w_out,
+            op.mode_code(),               // const uint8_t mode,
+            &inp_strides,                 // const size_t *inp_strides,
+            &out_strides,                 // const size_t *out_strides,
+            inp.data.as_ref(),            // const float *inp,
+            Arc::make_mut(&mut out.data), // float *out
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        grad_inp: &mut Self::Storage<I, f32>,
+        grad_out: &Self::Storage<O, f32>,
+    ) -> Result<(), Self::Err> {
+        if self.is_deterministic() {
+            let mut cpu_grad_inp = self.storage_to_cpu(grad_inp)?;
+            let cpu_grad_out = self.storage_to_cpu(grad_out)?;
+            Upsample2DKernel::<f32>::backward(&self.cpu, op, &mut cpu_grad_inp, &cpu_grad_out)?;
+            return self.storage_from_cpu(grad_inp, &cpu_grad_inp);
+        }
+
+        let inp_strides = self.dev.take_async(make_4d::<I>(grad_inp.strides).into())?;
+        let out_strides = self.dev.take_async(make_4d::<O>(grad_out.strides).into())?;
+        let bwd_fn = self.dev.get_func(MODULE_NAME, BWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(grad_out.shape().num_elements() as u32);
+        let params = (
+            op.batch,                          // const size_t batch,
+            op.chan,                           // const size_t chan,
+            op.h_in,                           // const size_t h_in,
+            op.h_out,                          // const size_t h_out,
+            op.w_in,                           // const size_t w_in,
+            op.w_out,                          // const size_t w_out,
+            op.mode_code(),                    // const uint8_t mode,
+            &inp_strides,                      // const size_t *inp_strides,
+            &out_strides,                      // const size_t *out_strides,
+            Arc::make_mut(&mut grad_inp.data), // float *grad_inp,
+            grad_out.data.as_ref(),            // const float *grad_out
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{
+    gradients::Tape,
+    shapes::*,
+    tensor::{DeviceStorage, HasErr, PutTape, SplitTape, Tensor, ZerosTensor},
+};
+
+/// How [TryUpsample2D] computes the values between input pixels.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum UpsampleMode {
+    /// Copy the value of the nearest input pixel to the left/top.
+    #[default]
+    Nearest,
+    /// Linearly interpolate between the 4 nearest input pixels.
+    ///
+    /// With `align_corners`, the corner pixels of the input & output are aligned, so the
+    /// corner values are preserved. Otherwise the pixels are treated as squares, and the
+    /// corners of the squares are aligned instead.
+    Bilinear { align_corners: bool },
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Upsample2DOp {
+    pub batch: usize,
+    pub chan: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+    pub mode: UpsampleMode,
+}
+
+impl Upsample2DOp {
+    fn new([b, c, h_in, w_in]: [usize; 4], [h_out, w_out]: [usize; 2], mode: UpsampleMode) -> Self {
+        Self {
+            batch: b,
+            chan: c,
+            h_in,
+            h_out,
+            w_in,
+            w_out,
+            mode,
+        }
+    }
+
+    /// The two input positions that output position `i` of `n_out` reads from, and
+    /// the weight of the second one. [UpsampleMode::Nearest] reads from a single position.
+    #[inline(always)]
+    pub(super) fn src(&self, i: usize, n_in: usize, n_out: usize) -> (usize, usize, f32) {
+        let src = match self.mode {
+            UpsampleMode::Nearest => {
+                let j = i * n_in / n_out;
+                return (j, j, 0.0);
+            }
+            UpsampleMode::Bilinear { align_corners } if align_corners => match n_out {
+                1 => 0.0,
+                _ => (i * (n_in - 1)) as f32 / (n_out - 1) as f32,
+            },
+            UpsampleMode::Bilinear { .. } => {
+                ((i as f32 + 0.5) * n_in as f32 / n_out as f32 - 0.5).max(0.0)
+            }
+        };
+        let i0 = (src as usize).min(n_in - 1);
+        let i1 = (i0 + 1).min(n_in - 1);
+        (i0, i1, src - i0 as f32)
+    }
+}
+
+pub trait Upsample2DKernel<E: Dtype>: DeviceStorage {
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        inp: &Self::Storage<I, E>,
+        out: &mut Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Upsample2DOp,
+        grad_inp: &mut Self::Storage<I, E>,
+        grad_out: &Self::Storage<O, E>,
+    ) -> Result<(), Self::Err>;
+}
+
+pub trait ConstUpsample2D<const OH: usize, const OW: usize>: HasErr {
+    type Output;
+    fn try_upsample2d(self, mode: UpsampleMode) -> Result<Self::Output, Self::Err>;
+}
+
+/// Resizes images (3d) and batches of images (4d) of any height & width to `(OH, OW)`,
+/// with an [UpsampleMode]. Usually used to upsample, as in UNet & FPN decoders, but
+/// `OH` & `OW` can be smaller than the input as well.
+///
+/// **Pytorch equivalent**: `torch.nn.functional.interpolate(x, size=(OH, OW), mode, align_corners)`
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank3<1, 2, 2>, f32, _> = dev.tensor([[[1.0, 2.0], [3.0, 4.0]]]);
+/// let r = t.clone().upsample_nearest2d::<4, 4>();
+/// assert_eq!(r.array()[0][1], [1.0, 1.0, 2.0, 2.0]);
+/// let r = t.upsample_bilinear2d::<3, 3>(true);
+/// assert_eq!(r.array(), [[[1.0, 1.5, 2.0], [2.0, 2.5, 3.0], [3.0, 3.5, 4.0]]]);
+/// ```
+pub trait TryUpsample2D {
+    /// Resizes with [UpsampleMode::Nearest]. See [TryUpsample2D].
+    fn upsample_nearest2d<const OH: usize, const OW: usize>(self) -> Self::Output
+    where
+        Self: ConstUpsample2D<OH, OW>,
+    {
+        self.try_upsample2d(UpsampleMode::Nearest).unwrap()
+    }
+    /// Fallible version of [TryUpsample2D::upsample_nearest2d].
+    fn try_upsample_nearest2d<const OH: usize, const OW: usize>(
+        self,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: ConstUpsample2D<OH, OW>,
+    {
+        self.try_upsample2d(UpsampleMode::Nearest)
+    }
+    /// Resizes with [UpsampleMode::Bilinear]. See [TryUpsample2D].
+    fn upsample_bilinear2d<const OH: usize, const OW: usize>(
+        self,
+        align_corners: bool,
+    ) -> Self::Output
+    where
+        Self: ConstUpsample2D<OH, OW>,
+    {
+        self.try_upsample2d(UpsampleMode::Bilinear { align_corners })
+            .unwrap()
+    }
+    /// Fallible version of [TryUpsample2D::upsample_bilinear2d].
+    fn try_upsample_bilinear2d<const OH: usize, const OW: usize>(
+        self,
+        align_corners: bool,
+    ) -> Result<Self::Output, Self::Err>
+    where
+        Self: ConstUpsample2D<OH, OW>,
+    {
+        self.try_upsample2d(UpsampleMode::Bilinear { align_corners })
+    }
+}
+impl<T> TryUpsample2D for T {}
+
+impl<
+        C: Dim,
+        H: Dim,
+        W: Dim,
+        D: Upsample2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+        const OH: usize,
+        const OW: usize,
+    > ConstUpsample2D<OH, OW> for Tensor<(C, H, W), f32, D, T>
+{
+    type Output = Tensor<(C, Const<OH>, Const<OW>), f32, D, T>;
+
+    fn try_upsample2d(self, mode: UpsampleMode) -> Result<Self::Output, Self::Err> {
+        let &(chan, h, w) = self.shape();
+        let op = Upsample2DOp::new([1, chan.size(), h.size(), w.size()], [OH, OW], mode);
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&(chan, Const, Const))?;
+        inp.device.forward(op, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(op, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+impl<
+        B: Dim,
+        C: Dim,
+        H: Dim,
+        W: Dim,
+        D: Upsample2DKernel<f32> + ZerosTensor<f32>,
+        T: 'static + Tape<D>,
+        const OH: usize,
+        const OW: usize,
+    > ConstUpsample2D<OH, OW> for Tensor<(B, C, H, W), f32, D, T>
+{
+    type Output = Tensor<(B, C, Const<OH>, Const<OW>), f32, D, T>;
+
+    fn try_upsample2d(self, mode: UpsampleMode) -> Result<Self::Output, Self::Err> {
+        let &(batch, chan, h, w) = self.shape();
+        let op = Upsample2DOp::new(
+            [batch.size(), chan.size(), h.size(), w.size()],
+            [OH, OW],
+            mode,
+        );
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.try_zeros_like(&(batch, chan, Const, Const))?;
+        inp.device.forward(op, &inp.storage, &mut out.storage)?;
+        let phantom_out = out.clone();
+        tape.try_alloc_grad(&inp)?;
+        tape.try_alloc_grad(&out)?;
+        tape.add_backward_op(move |grads| {
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp, &phantom_out);
+            inp.device.backward(op, grad_inp, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_upsample_nearest2d() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]]);
+        let r = x.trace().upsample_nearest2d::<4, 6>();
+        assert_eq!(
+            r.array(),
+            [[
+                [1.0, 1.0, 2.0, 2.0, 3.0, 3.0],
+                [1.0, 1.0, 2.0, 2.0, 3.0, 3.0],
+                [4.0, 4.0, 5.0, 5.0, 6.0, 6.0],
+                [4.0, 4.0, 5.0, 5.0, 6.0, 6.0],
+            ]]
+        );
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [[[4.0; 3]; 2]]);
+    }
+
+    #[test]
+    fn test_upsample_nearest2d_uneven() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0, 2.0], [3.0, 4.0]]]);
+        let r = x.trace().upsample_nearest2d::<3, 5>();
+        assert_eq!(
+            r.array(),
+            [[
+                [1.0, 1.0, 1.0, 2.0, 2.0],
+                [1.0, 1.0, 1.0, 2.0, 2.0],
+                [3.0, 3.0, 3.0, 4.0, 4.0],
+            ]]
+        );
+        let g = r.sum().backward();
+        assert_eq!(g.get(&x).array(), [[[6.0, 4.0], [3.0, 2.0]]]);
+    }
+
+    #[test]
+    fn test_upsample_bilinear2d() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0, 2.0], [3.0, 4.0]]]);
+        let r = x.trace().upsample_bilinear2d::<4, 4>(false);
+        assert_close(
+            &r.array(),
+            &[[
+                [1.0, 1.25, 1.75, 2.0],
+                [1.5, 1.75, 2.25, 2.5],
+                [2.5, 2.75, 3.25, 3.5],
+                [3.0, 3.25, 3.75, 4.0],
+            ]],
+        );
+        let g = r.sum().backward();
+        assert_close(&g.get(&x).array(), &[[[4.0; 2]; 2]]);
+    }
+
+    #[test]
+    fn test_upsample_bilinear2d_align_corners() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[1.0, 2.0], [3.0, 4.0]]]);
+        let r = x.trace().upsample_bilinear2d::<3, 4>(true);
+        assert_close(
+            &r.array(),
+            &[[
+                [1.0, 1.3333334, 1.6666667, 2.0],
+                [2.0, 2.3333334, 2.6666667, 3.0],
+                [3.0, 3.3333334, 3.6666667, 4.0],
+            ]],
+        );
+        let g = r.sum().backward();
+        assert_close(&g.get(&x).array(), &[[[3.0; 2]; 2]]);
+    }
+
+    #[test]
+    fn test_upsample2d_batched_matches_unbatched() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank4<2, 3, 4, 5>, f32, _> = dev.sample_normal();
+        let r = x.trace().upsample_bilinear2d::<7, 9>(false);
+        let r_array = r.array();
+        for (i, r_i) in r_array.iter().enumerate() {
+            let x_i = x.clone().select(dev.tensor(i));
+            assert_close(&x_i.upsample_bilinear2d::<7, 9>(false).array(), r_i);
+        }
+        let g = r.exp().mean().backward();
+        assert_ne!(g.get(&x).array(), [[[[0.0; 5]; 4]; 3]; 2]);
+    }
+
+    #[test]
+    fn test_upsample2d_dynamic_input_sizes() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(Const<2>, usize, usize), f32, _> =
+            dev.sample_like(&(Const, 3, 5), rand_distr::StandardNormal);
+        let r: Tensor<Rank3<2, 6, 10>, f32, _> = x.clone().upsample_nearest2d::<6, 10>();
+        let r2: Tensor<Rank3<2, 3, 5>, f32, _> = r.adaptive_avg_pool2d::<3, 5>();
+        assert_close(&r2.as_vec(), &x.as_vec());
+    }
+}
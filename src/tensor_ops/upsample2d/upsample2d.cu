@@ -0,0 +1,112 @@
+#include "cuda_utils.cuh"
+
+// mode: 0 is nearest, 1 is bilinear, 2 is bilinear with aligned corners
+__device__ void upsample_src(
+    size_t i,
+    size_t n_in,
+    size_t n_out,
+    uint8_t mode,
+    size_t *i0,
+    size_t *i1,
+    float *weight
+) {
+    if (mode == 0) {
+        *i0 = i * n_in / n_out;
+        *i1 = *i0;
+        *weight = 0.0;
+        return;
+    }
+    float src;
+    if (mode == 2) {
+        src = n_out > 1 ? static_cast<float>(i * (n_in - 1)) / static_cast<float>(n_out - 1) : 0.0;
+    } else {
+        src = fmaxf((static_cast<float>(i) + 0.5) * n_in / n_out - 0.5, 0.0);
+    }
+    *i0 = min(static_cast<size_t>(src), n_in - 1);
+    *i1 = min(*i0 + 1, n_in - 1);
+    *weight = src - *i0;
+}
+
+extern "C" __global__ void upsample2d_forward(
+    const size_t batch,
+    const size_t chan,
+    const size_t h_in,
+    const size_t h_out,
+    const size_t w_in,
+    const size_t w_out,
+    const uint8_t mode,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const float *inp, // 4d (Batch, Channels, Height, Width)
+    float *out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = batch * chan * h_out * w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % w_out;
+    idx /= w_out;
+    const size_t oh = idx % h_out;
+    idx /= h_out;
+    const size_t c = idx % chan;
+    idx /= chan;
+    const size_t b = idx % batch;
+
+    size_t y0, y1, x0, x1;
+    float ly, lx;
+    upsample_src(oh, h_in, h_out, mode, &y0, &y1, &ly);
+    upsample_src(ow, w_in, w_out, mode, &x0, &x1, &lx);
+
+    const float *inp_bc = inp + b * inp_strides[0] + c * inp_strides[1];
+    float top = (1.0 - lx) * inp_bc[y0 * inp_strides[2] + x0 * inp_strides[3]]
+        + lx * inp_bc[y0 * inp_strides[2] + x1 * inp_strides[3]];
+    float bottom = (1.0 - lx) * inp_bc[y1 * inp_strides[2] + x0 * inp_strides[3]]
+        + lx * inp_bc[y1 * inp_strides[2] + x1 * inp_strides[3]];
+    out[b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3]] =
+        (1.0 - ly) * top + ly * bottom;
+}
+
+extern "C" __global__ void upsample2d_backward(
+    const size_t batch,
+    const size_t chan,
+    const size_t h_in,
+    const size_t h_out,
+    const size_t w_in,
+    const size_t w_out,
+    const uint8_t mode,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    float *grad_inp, // 4d (Batch, Channels, Height, Width)
+    const float *grad_out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x;
+    const size_t numel = batch * chan * h_out * w_out;
+    if (i >= numel) {
+        return;
+    }
+
+    unsigned int idx = i;
+    const size_t ow = idx % w_out;
+    idx /= w_out;
+    const size_t oh = idx % h_out;
+    idx /= h_out;
+    const size_t c = idx % chan;
+    idx /= chan;
+    const size_t b = idx % batch;
+
+    size_t y0, y1, x0, x1;
+    float ly, lx;
+    upsample_src(oh, h_in, h_out, mode, &y0, &y1, &ly);
+    upsample_src(ow, w_in, w_out, mode, &x0, &x1, &lx);
+
+    const float go = grad_out[b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3]];
+    float *grad_inp_bc = grad_inp + b * inp_strides[0] + c * inp_strides[1];
+    // many outputs read from the same input when upsampling
+    atomicAdd(grad_inp_bc + y0 * inp_strides[2] + x0 * inp_strides[3], (1.0 - ly) * (1.0 - lx) * go);
+    atomicAdd(grad_inp_bc + y0 * inp_strides[2] + x1 * inp_strides[3], (1.0 - ly) * lx * go);
+    atomicAdd(grad_inp_bc + y1 * inp_strides[2] + x0 * inp_strides[3], ly * (1.0 - lx) * go);
+    atomicAdd(grad_inp_bc + y1 * inp_strides[2] + x1 * inp_strides[3], ly * lx * go);
+}
@@ -17,7 +17,9 @@ pub trait Backward: HasErr {
 impl<E: Dtype, D: OneFillStorage<E>> Backward for Tensor<Rank0, E, D, OwnedTape<D>> {
     fn try_backward(self) -> Result<Gradients, Self::Err> {
         let (t, mut tape) = self.split_tape();
+        let num_threads = t.device.backward_threads();
+        tape.0.prune(t.id);
         tape.add_backward_op(move |grads| t.device.try_fill_with_ones(grads.get_mut(&t)));
-        tape.0.execute()
+        tape.0.execute(num_threads)
     }
 }
@@ -1,9 +1,35 @@
-use super::ops::{BinaryKernel, UnaryKernel};
+use super::ops::{BinaryKernel, TernaryKernel, UnaryKernel};
 use crate::{
     shapes::{Dtype, Shape},
     tensor::cpu::{Cpu, LendingIterator, StridedArray},
 };
 
+/// Calls `f` with the offsets of the start of each row along `ax`, computed with
+/// each of `strides`. Every shape must be at least as big as `dims`, except along `ax`.
+pub(crate) fn for_each_row<const N: usize>(
+    dims: &[usize],
+    ax: usize,
+    strides: [&[usize]; N],
+    mut f: impl FnMut([usize; N]),
+) {
+    let num_rows: usize = (0..dims.len())
+        .filter(|&d| d != ax)
+        .map(|d| dims[d])
+        .product();
+    for row in 0..num_rows {
+        let mut offsets = [0; N];
+        let mut rem = row;
+        for d in (0..dims.len()).rev().filter(|&d| d != ax) {
+            let i = rem % dims[d];
+            rem /= dims[d];
+            for (o, s) in offsets.iter_mut().zip(strides.iter()) {
+                *o += i * s[d];
+            }
+        }
+        f(offsets);
+    }
+}
+
 pub trait UnaryDerivative<E> {
     fn f(&self, x: &E) -> E;
     fn df(&self, x: &E) -> E;
@@ -15,6 +41,13 @@ pub trait BinaryDerivative<E> {
     fn dfdy(&self, x: &E, y: &E) -> E;
 }
 
+pub trait TernaryDerivative<E> {
+    fn f(&self, x: &E, y: &E, z: &E) -> E;
+    fn dfdx(&self, x: &E, y: &E, z: &E) -> E;
+    fn dfdy(&self, x: &E, y: &E, z: &E) -> E;
+    fn dfdz(&self, x: &E, y: &E, z: &E) -> E;
+}
+
 impl<E: Dtype, Op: UnaryDerivative<E>> UnaryKernel<Op, E> for Cpu {
     fn forward<S: Shape>(
         &self,
@@ -86,3 +119,59 @@ impl<E: Dtype, Op: BinaryDerivative<E>> BinaryKernel<Op, E> for Cpu {
         Ok(())
     }
 }
+
+impl<E: Dtype, Op: TernaryDerivative<E>> TernaryKernel<Op, E> for Cpu {
+    fn forward<S: Shape>(
+        &self,
+        op: Op,
+        a: &Self::Storage<S, E>,
+        b: &Self::Storage<S, E>,
+        c: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err> {
+        let mut out: Self::Storage<S, E> = StridedArray::new(a.shape)?;
+        let mut a_iter = a.iter();
+        let mut b_iter = b.iter();
+        let mut c_iter = c.iter();
+        let mut out_iter = out.iter_mut();
+        while let Some((o, (x, (y, z)))) = out_iter
+            .next()
+            .zip(a_iter.next().zip(b_iter.next().zip(c_iter.next())))
+        {
+            *o = op.f(x, y, z);
+        }
+        Ok(out)
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        op: Op,
+        a: &Self::Storage<S, E>,
+        grad_a: &mut Self::Storage<S, E>,
+        b: &Self::Storage<S, E>,
+        grad_b: &mut Self::Storage<S, E>,
+        c: &Self::Storage<S, E>,
+        grad_c: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err> {
+        let mut a_iter = a.iter();
+        let mut b_iter = b.iter();
+        let mut c_iter = c.iter();
+        let mut grad_a_iter = grad_a.iter_mut();
+        let mut grad_b_iter = grad_b.iter_mut();
+        let mut grad_c_iter = grad_c.iter_mut();
+        let mut grad_out_iter = grad_out.iter();
+        for _ in 0..a.shape.num_elements() {
+            let x = a_iter.next().unwrap();
+            let y = b_iter.next().unwrap();
+            let z = c_iter.next().unwrap();
+            let go = *grad_out_iter.next().unwrap();
+            let ga = grad_a_iter.next().unwrap();
+            *ga += op.dfdx(x, y, z) * go;
+            let gb = grad_b_iter.next().unwrap();
+            *gb += op.dfdy(x, y, z) * go;
+            let gc = grad_c_iter.next().unwrap();
+            *gc += op.dfdz(x, y, z) * go;
+        }
+        Ok(())
+    }
+}
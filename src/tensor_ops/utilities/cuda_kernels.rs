@@ -1,10 +1,10 @@
 use crate::{
     shapes::Shape,
     tensor::cuda::{Cuda, CudaArray},
-    tensor_ops::ops::{BinaryKernel, UnaryKernel},
+    tensor_ops::ops::{BinaryKernel, TernaryKernel, UnaryKernel},
 };
 use cudarc::driver::{AsKernelParam, CudaSlice, LaunchAsync, LaunchConfig};
-use std::sync::Arc;
+use std::{sync::Arc, vec::Vec};
 
 pub trait UnaryOpCudaKernel {
     /// Compiled by build.rs
@@ -172,3 +172,111 @@ impl<K: BinaryOpCudaKernel + AsKernelParam> BinaryKernel<K, f32> for Cuda {
         Ok(())
     }
 }
+
+pub trait TernaryOpCudaKernel {
+    /// Compiled by build.rs
+    const PTX_SRC: &'static str;
+
+    /// Unique name for the kernel
+    const MODULE_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const FWD_FN_NAME: &'static str;
+
+    /// Name of function in the .cu file
+    const BWD_FN_NAME: &'static str;
+
+    const ALL_FN_NAMES: [&'static str; 2] = [Self::FWD_FN_NAME, Self::BWD_FN_NAME];
+}
+
+/// Concatenates the strides of all the tensors of a ternary op, since kernels
+/// can't take enough parameters to pass them separately.
+fn ternary_strides<T: Into<Vec<usize>>>(a: T, b: T, c: T, out: T) -> Vec<usize> {
+    let mut strides = a.into();
+    strides.append(&mut b.into());
+    strides.append(&mut c.into());
+    strides.append(&mut out.into());
+    strides
+}
+
+impl<K: TernaryOpCudaKernel + AsKernelParam> TernaryKernel<K, f32> for Cuda {
+    fn forward<S: Shape>(
+        &self,
+        op: K,
+        a: &Self::Storage<S, f32>,
+        b: &Self::Storage<S, f32>,
+        c: &Self::Storage<S, f32>,
+    ) -> Result<Self::Storage<S, f32>, Self::Err> {
+        if !self.dev.has_func(K::MODULE_NAME, K::FWD_FN_NAME) {
+            self.dev
+                .load_ptx(K::PTX_SRC.into(), K::MODULE_NAME, &K::ALL_FN_NAMES)?;
+        }
+
+        let shape = a.shape;
+        let strides = a.shape.strides();
+        let numel = shape.num_elements();
+
+        let mut storage = self.dev.alloc_zeros_async::<f32>(numel)?;
+
+        let dims: CudaSlice<usize> = self.dev.take_async(shape.concrete().into())?;
+        let all_strides = ternary_strides(a.strides, b.strides, c.strides, strides);
+        let all_strides: CudaSlice<usize> = self.dev.take_async(all_strides)?;
+
+        let fwd_fn = self.dev.get_func(K::MODULE_NAME, K::FWD_FN_NAME).unwrap();
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            op,
+            numel,           // const size_t numel,
+            S::NUM_DIMS,     // const size_t num_dims,
+            &dims,           // const size_t *dims,
+            a.data.as_ref(), // const float *a,
+            b.data.as_ref(), // const float *b,
+            c.data.as_ref(), // const float *c,
+            &mut storage,    // float *out,
+            &all_strides,    // const size_t *strides
+        );
+        unsafe { fwd_fn.launch_async(cfg, params) }?;
+        Ok(CudaArray {
+            data: Arc::new(storage),
+            shape,
+            strides,
+        })
+    }
+
+    fn backward<S: Shape>(
+        &self,
+        op: K,
+        a: &Self::Storage<S, f32>,
+        grad_a: &mut Self::Storage<S, f32>,
+        b: &Self::Storage<S, f32>,
+        grad_b: &mut Self::Storage<S, f32>,
+        c: &Self::Storage<S, f32>,
+        grad_c: &mut Self::Storage<S, f32>,
+        grad_out: &Self::Storage<S, f32>,
+    ) -> Result<(), Self::Err> {
+        let bwd_fn = self.dev.get_func(K::MODULE_NAME, K::BWD_FN_NAME).unwrap();
+        let numel = a.shape.num_elements();
+
+        let dims: CudaSlice<usize> = self.dev.take_async(a.shape.concrete().into())?;
+        let all_strides = ternary_strides(a.strides, b.strides, c.strides, grad_out.strides);
+        let all_strides: CudaSlice<usize> = self.dev.take_async(all_strides)?;
+
+        let cfg = LaunchConfig::for_num_elems(numel as u32);
+        let params = (
+            op,
+            numel,                           // const size_t numel,
+            S::NUM_DIMS,                     // const size_t num_dims,
+            &dims,                           // const size_t *dims,
+            a.data.as_ref(),                 // const float *a,
+            Arc::make_mut(&mut grad_a.data), // float *grad_a,
+            b.data.as_ref(),                 // const float *b,
+            Arc::make_mut(&mut grad_b.data), // float *grad_b,
+            c.data.as_ref(),                 // const float *c,
+            Arc::make_mut(&mut grad_c.data), // float *grad_c,
+            grad_out.data.as_ref(),          // const float *grad_out,
+            &all_strides,                    // const size_t *strides
+        );
+        unsafe { bwd_fn.launch_async(cfg, params) }?;
+        Ok(())
+    }
+}
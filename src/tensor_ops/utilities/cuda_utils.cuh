@@ -27,6 +27,27 @@ __device__ unsigned int get_unstrided_index(
     return idx;
 }
 
+// The index of the first element of the `row`th row along `ax`,
+// i.e. of the `row`th element of the shape with `ax` removed.
+__device__ size_t get_row_index(
+    size_t row,
+    const size_t num_dims,
+    const size_t ax,
+    const size_t *dims,
+    const size_t *strides
+) {
+    size_t strided_i = 0;
+    for (size_t d = 0; d < num_dims; d++) {
+        size_t dim_idx = num_dims - 1 - d;
+        if (dim_idx == ax) {
+            continue;
+        }
+        strided_i += (row % dims[dim_idx]) * strides[dim_idx];
+        row /= dims[dim_idx];
+    }
+    return strided_i;
+}
+
 // Sourced from https://graphics.stanford.edu/~seander/bithacks.html#RoundUpPowerOf2
 // used in reductions
 __device__ __forceinline__ unsigned int next_power_of_two(unsigned int v) {
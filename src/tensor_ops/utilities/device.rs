@@ -1,4 +1,4 @@
-use super::super::ops::{BinaryKernel, UnaryKernel};
+use super::super::ops::{BinaryKernel, TernaryKernel, UnaryKernel};
 use crate::{
     shapes::Dtype,
     tensor::{CopySlice, DeviceStorage},
@@ -13,21 +13,41 @@ pub trait Device<E: Dtype>:
     + crate::tensor::ZerosTensor<E>
     + crate::tensor::OnesTensor<E>
     + crate::tensor::SampleTensor<E>
+    + crate::tensor::TriangleTensor<E>
     + crate::tensor::OneFillStorage<E>
     + crate::tensor::ZeroFillStorage<E>
 
     // broadcast & reduces
     + super::super::broadcast_to::BroadcastKernel<E>
+    + super::super::expand_to::ExpandKernel<E>
     + super::super::sum_to::SumKernel<E>
     + super::super::max_to::MaxReduceKernel<E>
     + super::super::min_to::MinReduceKernel<E>
+    + super::super::prod_to::ProdReduceKernel<E>
+    + super::super::quantile::QuantileKernel<E>
+    + super::super::log_softmax::LogSoftmaxKernel<E>
     + super::super::permute_to::PermuteKernel<E>
     + super::super::reshape_to::ReshapeKernel<E>
+    + super::super::as_strided::AsStridedKernel<E>
 
     // indexing
     + super::super::select_and_gather::ReplaceDimKernel<E>
     + super::super::select_and_gather::RemoveDimKernel<E>
     + super::super::choose::ChooseKernel<E>
+    + super::super::masked::MaskedKernel<E>
+    + super::super::triangular::TriangleKernel<E>
+    + super::super::pad2d::Pad2DKernel<E>
+    + super::super::gather_along::GatherAlongKernel<E>
+    + super::super::concat_along::ConcatAlongKernel<E>
+    + super::super::stack::StackKernel<E>
+    + super::super::topk::TopKKernel<E>
+    + super::super::argmax::ArgMaxKernel<E>
+    + super::super::cumsum::CumSumKernel<E>
+    + super::super::cumprod::CumProdKernel<E>
+    + super::super::roll::RollKernel<E>
+    + super::super::flip::FlipKernel<E>
+    + super::super::diag::DiagKernel<E>
+    + super::super::sample_logits::SampleLogitsKernel<E>
 
     // matmuls
     + super::super::matmul::VecMatKernel<E>
@@ -36,6 +56,16 @@ pub trait Device<E: Dtype>:
     + super::super::matmul::MatMatBrKernel<E>
     + super::super::matmul::MatMatBatch3Kernel<E>
     + super::super::matmul::MatMatBatch4Kernel<E>
+    + super::super::einsum::EinsumKernel<E>
+
+    // spectral
+    + super::super::fft::FftKernel<E>
+
+    // linear algebra
+    + super::super::linalg::CholeskyKernel<E>
+    + super::super::linalg::DetKernel<E>
+    + super::super::linalg::SvdKernel<E>
+    + super::super::linalg::SymEigKernel<E>
 
     // scalar arithmetic
     + UnaryKernel<super::super::add::ScalarAddKernelOp<E>, E>
@@ -52,16 +82,25 @@ pub trait Device<E: Dtype>:
     // boolean operations
     + super::super::boolean::BooleanKernel
 
+    // checked mode
+    + super::super::checks::CheckKernel<E>
+
     // unary
     + UnaryKernel<super::super::abs::AbsKernelOp, E>
     + UnaryKernel<super::super::clamp::ClampKernelOp<E>, E>
     + UnaryKernel<super::super::cos::CosKernelOp, E>
     + UnaryKernel<super::super::dropout::DropoutKernelOp, E>
+    + UnaryKernel<super::super::erf::ErfKernelOp, E>
+    + UnaryKernel<super::super::erf::NormalCdfKernelOp, E>
     + UnaryKernel<super::super::exp::ExpKernelOp, E>
     + UnaryKernel<super::super::ln::LnKernelOp, E>
     + UnaryKernel<super::super::nans_to::NansToKernelOp<E>, E>
     + UnaryKernel<super::super::negate::NegateKernelOp, E>
     + UnaryKernel<super::super::relu::ReLUKernelOp, E>
+    + UnaryKernel<super::super::round::FloorKernelOp, E>
+    + UnaryKernel<super::super::round::CeilKernelOp, E>
+    + UnaryKernel<super::super::round::RoundKernelOp, E>
+    + UnaryKernel<super::super::round::SignKernelOp, E>
     + UnaryKernel<super::super::gelu::GeLUKernelOp, E>
     + UnaryKernel<super::super::sigmoid::SigmoidKernelOp, E>
     + UnaryKernel<super::super::sin::SinKernelOp, E>
@@ -76,6 +115,12 @@ pub trait Device<E: Dtype>:
     + BinaryKernel<super::super::huber_error::HuberErrorKernelOp<E>, E>
     + BinaryKernel<super::super::maximum::MaximumKernelOp, E>
     + BinaryKernel<super::super::minimum::MinimumKernelOp, E>
+    + BinaryKernel<super::super::lerp::ScalarLerpKernelOp<E>, E>
+
+    // ternary
+    + super::super::affine::AffineKernel<E>
+    + TernaryKernel<super::super::fma::FmaKernelOp, E>
+    + TernaryKernel<super::super::lerp::LerpKernelOp, E>
 {
 }
 
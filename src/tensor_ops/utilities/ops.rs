@@ -1,7 +1,7 @@
 use crate::{
     gradients::{Merge, Tape},
-    shapes::{Dtype, HasShape, Shape},
-    tensor::{DeviceStorage, PutTape, SplitTape, Tensor},
+    shapes::{Dtype, Shape, ShapeMismatch},
+    tensor::{DeviceMismatch, DeviceStorage, PutTape, SplitTape, Tensor},
 };
 
 pub trait UnaryKernel<Op, E: Dtype>: DeviceStorage {
@@ -38,8 +38,31 @@ pub trait BinaryKernel<Op, E: Dtype>: DeviceStorage {
     ) -> Result<(), Self::Err>;
 }
 
+pub trait TernaryKernel<Op, E: Dtype>: DeviceStorage {
+    fn forward<S: Shape>(
+        &self,
+        op: Op,
+        a: &Self::Storage<S, E>,
+        b: &Self::Storage<S, E>,
+        c: &Self::Storage<S, E>,
+    ) -> Result<Self::Storage<S, E>, Self::Err>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<S: Shape>(
+        &self,
+        op: Op,
+        a: &Self::Storage<S, E>,
+        grad_a: &mut Self::Storage<S, E>,
+        b: &Self::Storage<S, E>,
+        grad_b: &mut Self::Storage<S, E>,
+        c: &Self::Storage<S, E>,
+        grad_c: &mut Self::Storage<S, E>,
+        grad_out: &Self::Storage<S, E>,
+    ) -> Result<(), Self::Err>;
+}
+
 pub(crate) fn try_unary_op<
-    Op: 'static + Clone,
+    Op: 'static + Clone + Send,
     S: Shape,
     E: Dtype,
     D: UnaryKernel<Op, E>,
@@ -62,19 +85,22 @@ pub(crate) fn try_unary_op<
     Ok(out.put_tape(tape))
 }
 
+#[track_caller]
 pub(crate) fn try_binary_op<
-    Op: 'static + Copy,
+    Op: 'static + Copy + Send,
     S: Shape,
     E: Dtype,
     D: BinaryKernel<Op, E>,
     RhsTape: Tape<D>,
     LhsTape: Tape<D> + Merge<RhsTape>,
 >(
+    name: &'static str,
     op: Op,
     lhs: Tensor<S, E, D, LhsTape>,
     rhs: Tensor<S, E, D, RhsTape>,
 ) -> Result<Tensor<S, E, D, LhsTape>, D::Err> {
-    assert_eq!(lhs.shape(), rhs.shape());
+    DeviceMismatch::check_same(name, &lhs.device, &rhs.device)?;
+    ShapeMismatch::check_same(name, &lhs, &rhs)?;
     let (lhs, ltape) = lhs.split_tape();
     let (rhs, rtape) = rhs.split_tape();
     let mut tape = ltape.merge(rtape);
@@ -92,3 +118,44 @@ pub(crate) fn try_binary_op<
     });
     Ok(out.put_tape(tape))
 }
+
+#[track_caller]
+pub(crate) fn try_ternary_op<
+    Op: 'static + Copy + Send,
+    S: Shape,
+    E: Dtype,
+    D: TernaryKernel<Op, E>,
+    ATape: Tape<D> + Merge<BTape> + Merge<CTape>,
+    BTape: Tape<D>,
+    CTape: Tape<D>,
+>(
+    name: &'static str,
+    op: Op,
+    a: Tensor<S, E, D, ATape>,
+    b: Tensor<S, E, D, BTape>,
+    c: Tensor<S, E, D, CTape>,
+) -> Result<Tensor<S, E, D, ATape>, D::Err> {
+    DeviceMismatch::check_same(name, &a.device, &b.device)?;
+    DeviceMismatch::check_same(name, &a.device, &c.device)?;
+    ShapeMismatch::check_same(name, &a, &b)?;
+    ShapeMismatch::check_same(name, &a, &c)?;
+    let (a, a_tape) = a.split_tape();
+    let (b, b_tape) = b.split_tape();
+    let (c, c_tape) = c.split_tape();
+    let mut tape = a_tape.merge(b_tape).merge(c_tape);
+    let storage = a.device.forward(op, &a.storage, &b.storage, &c.storage)?;
+    let out = a.device.upgrade(storage);
+    let phantom_out = out.clone();
+    tape.try_alloc_grad(&a)?;
+    tape.try_alloc_grad(&b)?;
+    tape.try_alloc_grad(&c)?;
+    tape.try_alloc_grad(&out)?;
+    tape.add_backward_op(move |grads| {
+        let (grad_a, grad_b, grad_c, grad_out) = grads.three_muts_and_ref(&a, &b, &c, &phantom_out);
+        a.device.backward(
+            op, &a.storage, grad_a, &b.storage, grad_b, &c.storage, grad_c, grad_out,
+        )?;
+        Ok(())
+    });
+    Ok(out.put_tape(tape))
+}
@@ -0,0 +1,76 @@
+#include "cuda_utils.cuh"
+
+// `strides` holds the strides of `a`, `b`, `c`, and `out` one after another,
+// since kernels can't take enough parameters to pass them separately.
+
+#define LONG_TERNARY_OP(FORWARD, BACKWARD, OP_STRUCT, FUNC, DERIVATIVES) \
+extern "C" __global__ void FORWARD( \
+    const OP_STRUCT op, \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t *dims, \
+    const float *a, \
+    const float *b, \
+    const float *c, \
+    float *out, \
+    const size_t *strides \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+\
+    unsigned int a_i = get_strided_index(i, num_dims, dims, strides); \
+    unsigned int b_i = get_strided_index(i, num_dims, dims, strides + num_dims); \
+    unsigned int c_i = get_strided_index(i, num_dims, dims, strides + 2 * num_dims); \
+    unsigned int out_i = get_strided_index(i, num_dims, dims, strides + 3 * num_dims); \
+\
+    float x = a[a_i]; \
+    float y = b[b_i]; \
+    float z = c[c_i]; \
+    float fx; \
+\
+    FUNC\
+\
+    out[out_i] = fx; \
+} \
+\
+extern "C" __global__ void BACKWARD( \
+    const OP_STRUCT op, \
+    const size_t numel, \
+    const size_t num_dims, \
+    const size_t *dims, \
+    const float *a, \
+    float *grad_a, \
+    const float *b, \
+    float *grad_b, \
+    const float *c, \
+    float *grad_c, \
+    const float *grad_out, \
+    const size_t *strides \
+) { \
+    unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; \
+    if (i >= numel) { \
+        return; \
+    } \
+\
+    unsigned int a_i = get_strided_index(i, num_dims, dims, strides); \
+    unsigned int b_i = get_strided_index(i, num_dims, dims, strides + num_dims); \
+    unsigned int c_i = get_strided_index(i, num_dims, dims, strides + 2 * num_dims); \
+    unsigned int out_i = get_strided_index(i, num_dims, dims, strides + 3 * num_dims); \
+\
+    auto x = a[a_i]; \
+    auto y = b[b_i]; \
+    auto z = c[c_i]; \
+    auto go = grad_out[out_i]; \
+\
+    float dfdx, dfdy, dfdz; \
+    DERIVATIVES \
+\
+    atomicAdd(grad_a + a_i, dfdx * go); \
+    atomicAdd(grad_b + b_i, dfdy * go); \
+    atomicAdd(grad_c + c_i, dfdz * go); \
+}
+
+#define TERNARY_OP(FORWARD, BACKWARD, OP_STRUCT, FUNC, DFDX, DFDY, DFDZ) \
+    LONG_TERNARY_OP(FORWARD, BACKWARD, OP_STRUCT, fx = (FUNC);, dfdx = (DFDX); dfdy = (DFDY); dfdz = (DFDZ);)
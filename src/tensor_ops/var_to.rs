@@ -15,6 +15,8 @@ pub trait VarTo: HasErr + HasShape {
     /// let r = t.var::<Rank1<2>, _>(); // or `var::<_, Axis<1>>()`
     /// assert_eq!(r.array(), [0.6666667, 6.0]);
     /// ```
+    ///
+    /// In checked mode, returns an error if the result is negative or `NaN`.
     fn var<Dst: Shape, Ax: Axes>(self) -> Self::WithShape<Dst>
     where
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
@@ -36,7 +38,9 @@ impl<S: Shape, D: Device<f32>, T: Tape<D>> VarTo for Tensor<S, f32, D, T> {
             .retaped::<T>()
             .try_mean::<Dst, Ax>()?
             .try_broadcast_like(self.shape())?;
-        mean.try_sub(self)?.try_square()?.try_mean()
+        let var = mean.try_sub(self)?.try_square()?.try_mean()?;
+        var.try_check("var", "output", Invariant::NonNegative)?;
+        Ok(var)
     }
 }
 
@@ -73,4 +77,11 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_var_large_offset() {
+        let dev: TestDevice = Default::default();
+        let t = dev.tensor([10001.0, 10002.0, 10003.0, 10004.0]);
+        assert_eq!(t.var::<Rank0, _>().array(), 1.25);
+    }
 }
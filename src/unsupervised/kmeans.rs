@@ -0,0 +1,341 @@
+use crate::{shapes::*, tensor::*, tensor_ops::*};
+use std::{vec, vec::Vec};
+
+/// [Mini-batch k-means](https://dl.acm.org/doi/10.1145/1772690.1772862) clustering of the rows
+/// of `(batch, features)` tensors.
+///
+/// [KMeans::init()] picks the initial centroids from the data with k-means++, and each
+/// [KMeans::partial_fit()] moves every centroid toward the mean of the rows assigned to it, with
+/// a learning rate of `1 / (rows assigned to it so far)`. [KMeans::fit()] does both, with
+/// minibatches sampled from a dataset.
+///
+/// The distances & updates are computed on the device; only the assignments of each batch are
+/// copied to the host.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, unsupervised::KMeans};
+/// # use rand::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let data = dev.tensor([[0.0, 0.1], [0.1, 0.0], [5.0, 5.1], [5.1, 5.0]]);
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let kmeans = KMeans::fit(&data, 2, 4, 10, &mut rng);
+/// let clusters = kmeans.predict(&data).array();
+/// assert_eq!(clusters[0], clusters[1]);
+/// assert_eq!(clusters[2], clusters[3]);
+/// assert_ne!(clusters[0], clusters[2]);
+///
+/// // replace each row with its centroid, like a vector quantizer
+/// let quantized = kmeans.quantize(&data);
+/// assert_eq!(quantized.shape(), &(Const::<4>, Const::<2>));
+/// ```
+#[derive(Debug, Clone)]
+pub struct KMeans<F: Dim, D: DeviceStorage> {
+    /// The `(k, features)` cluster centers.
+    pub centroids: Tensor<(usize, F), f32, D>,
+    /// The number of rows assigned to each centroid by [KMeans::partial_fit()].
+    pub counts: Vec<usize>,
+}
+
+impl<F: Dim, D> KMeans<F, D>
+where
+    D: Device<f32> + TensorFromVec<f32> + TensorFromVec<usize> + CopySlice<usize>,
+{
+    /// Picks `k` rows of `data` as the initial centroids with
+    /// [k-means++](https://en.wikipedia.org/wiki/K-means%2B%2B), which samples each row with
+    /// probability proportional to its squared distance from the closest centroid so far.
+    ///
+    /// **Panics** if `k` is `0` or more than the number of rows.
+    pub fn init<B: Dim, R: rand::Rng>(
+        data: &Tensor<(B, F), f32, D>,
+        k: usize,
+        rng: &mut R,
+    ) -> Self {
+        Self::try_init(data, k, rng).unwrap()
+    }
+
+    /// Fallible version of [KMeans::init()]
+    pub fn try_init<B: Dim, R: rand::Rng>(
+        data: &Tensor<(B, F), f32, D>,
+        k: usize,
+        rng: &mut R,
+    ) -> Result<Self, D::Err> {
+        let n = data.shape().0.size();
+        assert!(k > 0 && k <= n, "can't pick {k} centroids from {n} rows");
+        let dev = &data.device;
+        let mut chosen = vec![rng.gen_range(0..n)];
+        let mut closest = vec![f32::INFINITY; n];
+        let mut dists = vec![0.0; n];
+        while chosen.len() < k {
+            let last = dev.try_tensor_from_vec(vec![chosen[chosen.len() - 1]], ())?;
+            let centroid = data.clone().try_select(last)?;
+            data.clone()
+                .try_sub(centroid.try_broadcast_like::<_, Axis<0>>(data.shape())?)?
+                .try_square()?
+                .try_sum::<(B,), Axis<1>>()?
+                .copy_into(&mut dists);
+            for (c, &d) in closest.iter_mut().zip(dists.iter()) {
+                *c = c.min(d);
+            }
+            let total: f32 = closest.iter().sum();
+            let next = if total > 0.0 {
+                let mut x = rng.gen::<f32>() * total;
+                closest
+                    .iter()
+                    .position(|&d| {
+                        x -= d;
+                        x < 0.0
+                    })
+                    // rounding can leave `x` slightly positive after the last row
+                    .unwrap_or_else(|| closest.iter().rposition(|&d| d > 0.0).unwrap())
+            } else {
+                // every row is on a centroid already
+                rng.gen_range(0..n)
+            };
+            chosen.push(next);
+        }
+        let centroids = data
+            .clone()
+            .try_gather(dev.try_tensor_from_vec(chosen, (k,))?)?;
+        Ok(Self {
+            centroids,
+            counts: vec![0; k],
+        })
+    }
+
+    /// Initializes `k` centroids with [KMeans::init()], and then calls
+    /// [KMeans::partial_fit()] `steps` times with `batch_size` rows of `data` sampled with
+    /// replacement.
+    ///
+    /// **Panics** if `k` is `0` or more than the number of rows.
+    pub fn fit<B: Dim, R: rand::Rng>(
+        data: &Tensor<(B, F), f32, D>,
+        k: usize,
+        batch_size: usize,
+        steps: usize,
+        rng: &mut R,
+    ) -> Self {
+        Self::try_fit(data, k, batch_size, steps, rng).unwrap()
+    }
+
+    /// Fallible version of [KMeans::fit()]
+    pub fn try_fit<B: Dim, R: rand::Rng>(
+        data: &Tensor<(B, F), f32, D>,
+        k: usize,
+        batch_size: usize,
+        steps: usize,
+        rng: &mut R,
+    ) -> Result<Self, D::Err> {
+        let mut kmeans = Self::try_init(data, k, rng)?;
+        let n = data.shape().0.size();
+        for _ in 0..steps {
+            let idx: Vec<usize> = (0..batch_size).map(|_| rng.gen_range(0..n)).collect();
+            let idx = data.device.try_tensor_from_vec(idx, (batch_size,))?;
+            kmeans.try_partial_fit(&data.clone().try_gather(idx)?)?;
+        }
+        Ok(kmeans)
+    }
+
+    /// Assigns each row of `batch` to its closest centroid, and moves each centroid `c` to
+    /// `c + (sum of its rows - num rows * c) / counts[c]`, after adding the rows to `counts`.
+    /// Centroids without any rows in the batch don't move.
+    pub fn partial_fit<B: Dim>(&mut self, batch: &Tensor<(B, F), f32, D>) {
+        self.try_partial_fit(batch).unwrap()
+    }
+
+    /// Fallible version of [KMeans::partial_fit()]
+    pub fn try_partial_fit<B: Dim>(
+        &mut self,
+        batch: &Tensor<(B, F), f32, D>,
+    ) -> Result<(), D::Err> {
+        let (b, _) = *batch.shape();
+        let k = self.counts.len();
+        let mut labels = vec![0; b.size()];
+        self.try_predict(batch)?.copy_into(&mut labels);
+
+        let mut one_hot = vec![0.0; b.size() * k];
+        let mut batch_counts = vec![0.0; k];
+        for (i, &label) in labels.iter().enumerate() {
+            one_hot[i * k + label] = 1.0;
+            batch_counts[label] += 1.0;
+            self.counts[label] += 1;
+        }
+        let rates: Vec<f32> = self
+            .counts
+            .iter()
+            .map(|&c| if c > 0 { 1.0 / c as f32 } else { 0.0 })
+            .collect();
+
+        let dev = &batch.device;
+        let shape = *self.centroids.shape();
+        let sums = dev
+            .try_tensor_from_vec(one_hot, (b, k))?
+            .try_permute::<_, Axes2<1, 0>>()?
+            .try_matmul(batch.clone())?;
+        let batch_counts = dev
+            .try_tensor_from_vec(batch_counts, (k,))?
+            .try_broadcast_like::<_, Axis<1>>(&shape)?;
+        let rates = dev
+            .try_tensor_from_vec(rates, (k,))?
+            .try_broadcast_like::<_, Axis<1>>(&shape)?;
+        let step = sums
+            .try_sub(self.centroids.clone().try_mul(batch_counts)?)?
+            .try_mul(rates)?;
+        self.centroids = self.centroids.clone().try_add(step)?;
+        Ok(())
+    }
+
+    /// The squared euclidean distance from each row of `x` to each centroid, with shape
+    /// `(batch, k)`.
+    pub fn distances<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<(B, usize), f32, D> {
+        self.try_distances(x).unwrap()
+    }
+
+    /// Fallible version of [KMeans::distances()]
+    pub fn try_distances<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<(B, usize), f32, D>, D::Err> {
+        let shape = (x.shape().0, self.counts.len());
+        let xx = x
+            .clone()
+            .try_square()?
+            .try_sum::<(B,), Axis<1>>()?
+            .try_broadcast_like::<_, Axis<1>>(&shape)?;
+        let cc = self
+            .centroids
+            .clone()
+            .try_square()?
+            .try_sum::<(usize,), Axis<1>>()?
+            .try_broadcast_like::<_, Axis<0>>(&shape)?;
+        let xc = x
+            .clone()
+            .try_matmul(self.centroids.clone().try_permute::<_, Axes2<1, 0>>()?)?;
+        // rounding can make the distance from a centroid to itself slightly negative
+        xx.try_add(cc)?.try_sub(xc.try_mul(2.0)?)?.try_relu()
+    }
+
+    /// The index of the closest centroid to each row of `x`.
+    pub fn predict<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<(B,), usize, D> {
+        self.try_predict(x).unwrap()
+    }
+
+    /// Fallible version of [KMeans::predict()]
+    pub fn try_predict<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<(B,), usize, D>, D::Err> {
+        self.try_distances(x)?.try_argmin::<Axis<1>>()
+    }
+
+    /// Replaces each row of `x` with its closest centroid.
+    pub fn quantize<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<(B, F), f32, D> {
+        self.try_quantize(x).unwrap()
+    }
+
+    /// Fallible version of [KMeans::quantize()]
+    pub fn try_quantize<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<(B, F), f32, D>, D::Err> {
+        self.centroids.clone().try_gather(self.try_predict(x)?)
+    }
+
+    /// The sum of the squared distances from each row of `x` to its closest centroid, which
+    /// k-means minimizes.
+    pub fn inertia<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<Rank0, f32, D> {
+        self.try_inertia(x).unwrap()
+    }
+
+    /// Fallible version of [KMeans::inertia()]
+    pub fn try_inertia<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<Rank0, f32, D>, D::Err> {
+        self.try_distances(x)?.try_min::<(B,), Axis<1>>()?.try_sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use rand::prelude::*;
+
+    #[test]
+    fn test_kmeans_distances() {
+        let dev: TestDevice = Default::default();
+        let kmeans = KMeans {
+            centroids: dev.tensor_from_vec(std::vec![0.0, 0.0, 1.0, 2.0], (2, Const::<2>)),
+            counts: std::vec![0; 2],
+        };
+        let x = dev.tensor([[1.0, 0.0], [1.0, 2.0], [3.0, 3.0]]);
+        let d = kmeans.distances(&x);
+        assert_close(&d.as_vec(), &std::vec![1.0, 4.0, 5.0, 0.0, 18.0, 5.0]);
+        assert_eq!(kmeans.predict(&x).array(), [0, 1, 1]);
+        assert_close(&kmeans.inertia(&x).array(), &6.0);
+        assert_close(
+            &kmeans.quantize(&x).array(),
+            &[[0.0, 0.0], [1.0, 2.0], [1.0, 2.0]],
+        );
+    }
+
+    #[test]
+    fn test_kmeans_partial_fit() {
+        let dev: TestDevice = Default::default();
+        let mut kmeans = KMeans {
+            centroids: dev.tensor_from_vec(std::vec![0.0, 0.0, 10.0, 10.0], (2, Const::<2>)),
+            counts: std::vec![0; 2],
+        };
+        // the first batch moves the centroids to the means of their rows
+        kmeans.partial_fit(&dev.tensor([[1.0, 0.0], [3.0, 2.0], [9.0, 9.0]]));
+        assert_eq!(kmeans.counts, [2, 1]);
+        assert_close(&kmeans.centroids.as_vec(), &std::vec![2.0, 1.0, 9.0, 9.0]);
+
+        // the next batches move them less, to the mean of all their rows so far
+        kmeans.partial_fit(&dev.tensor([[5.0, 4.0]]));
+        assert_eq!(kmeans.counts, [3, 1]);
+        assert_close(&kmeans.centroids.as_vec(), &std::vec![3.0, 2.0, 9.0, 9.0]);
+    }
+
+    #[test]
+    fn test_kmeans_init_picks_distinct_rows() {
+        let dev: TestDevice = Default::default();
+        let data = dev.tensor([[0.0, 0.0], [0.0, 0.0], [0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]);
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..10 {
+            let kmeans = KMeans::init(&data, 3, &mut rng);
+            let mut rows: Vec<[u32; 2]> = kmeans
+                .centroids
+                .as_vec()
+                .chunks(2)
+                .map(|c| [c[0] as u32, c[1] as u32])
+                .collect();
+            rows.sort_unstable();
+            assert_eq!(rows, [[0, 0], [1, 1], [2, 2]]);
+        }
+    }
+
+    #[test]
+    fn test_kmeans_fit_blobs() {
+        let dev: TestDevice = Default::default();
+        let mut rng = StdRng::seed_from_u64(0);
+        let centers = [[0.0, 0.0, 0.0], [4.0, 0.0, 4.0], [0.0, 4.0, -4.0]];
+        let mut data = Vec::new();
+        for i in 0..60 {
+            for c in centers[i % 3] {
+                data.push(c + rng.gen_range(-0.5..0.5));
+            }
+        }
+        let data = dev.tensor_from_vec(data, (60, Const::<3>));
+        let kmeans = KMeans::fit(&data, 3, 16, 20, &mut rng);
+        let labels = kmeans.predict(&data).as_vec();
+        for i in 0..60 {
+            assert_eq!(labels[i], labels[i % 3]);
+        }
+        assert_ne!(labels[0], labels[1]);
+        assert_ne!(labels[1], labels[2]);
+        assert_ne!(labels[0], labels[2]);
+        // each row has a variance of 3 / 12 around its center
+        assert!(kmeans.inertia(&data).array() < 60.0 * 0.3);
+    }
+}
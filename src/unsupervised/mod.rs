@@ -0,0 +1,12 @@
+//! Unsupervised learning on the rows of `(batch, features)` tensors, which runs on the same
+//! device as the model, e.g. to analyse embeddings.
+//!
+//! [KMeans] clusters rows, and its centroids can initialize the codebook of a vector
+//! quantizer. [Pca] projects rows onto their principal components, for dimensionality
+//! reduction and whitening.
+
+mod kmeans;
+mod pca;
+
+pub use kmeans::KMeans;
+pub use pca::Pca;
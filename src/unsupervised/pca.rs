@@ -0,0 +1,213 @@
+use crate::{shapes::*, tensor::*, tensor_ops::*};
+use std::vec::Vec;
+
+/// [Principal component analysis](https://en.wikipedia.org/wiki/Principal_component_analysis)
+/// of the rows of a `(batch, features)` tensor, computed with the [svd()] of the centered data.
+///
+/// [Pca::transform()] projects rows onto the components with the most variance, and
+/// [Pca::whiten()] also scales the projections to unit variance, so they are uncorrelated
+/// features with the same scale.
+///
+/// Like any singular vectors, each component is only defined up to its sign.
+///
+/// ```rust
+/// # use dfdx::{prelude::*, unsupervised::Pca};
+/// # let dev: Cpu = Default::default();
+/// // points on the line `y = 2x`
+/// let data = dev.tensor([[0.0, 0.0], [1.0, 2.0], [2.0, 4.0], [3.0, 6.0]]);
+/// let pca = Pca::fit(&data, 1);
+/// assert!((pca.explained_variance_ratio().as_vec()[0] - 1.0).abs() < 1e-5);
+///
+/// let projected = pca.transform(&data);
+/// assert_eq!(projected.shape(), &(Const::<4>, 1));
+/// let reconstructed = pca.inverse_transform(&projected);
+/// assert!((reconstructed.array()[3][1] - 6.0).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pca<F: Dim, D: DeviceStorage> {
+    /// The mean of each feature, which is subtracted before projecting.
+    pub mean: Tensor<(F,), f32, D>,
+    /// The `(num_components, features)` orthonormal principal axes, in order of decreasing
+    /// variance.
+    pub components: Tensor<(usize, F), f32, D>,
+    /// The variance of the data along each component.
+    pub explained_variance: Tensor<(usize,), f32, D>,
+    /// The sum of the variances of all the features.
+    pub total_variance: f32,
+}
+
+impl<F: Dim, D: Device<f32> + TensorFromVec<usize>> Pca<F, D> {
+    /// Fits the first `num_components` principal components of the rows of `data`. Variances
+    /// use the unbiased estimator, which divides by `batch - 1`.
+    ///
+    /// Uses [svd()], which is slow for large matrices, so fit on a sample of a large
+    /// dataset.
+    ///
+    /// **Panics** if there are less than 2 rows, or `num_components` is more than
+    /// `min(batch, features)`.
+    pub fn fit<B: Dim>(data: &Tensor<(B, F), f32, D>, num_components: usize) -> Self {
+        Self::try_fit(data, num_components).unwrap()
+    }
+
+    /// Fallible version of [Pca::fit()]
+    pub fn try_fit<B: Dim>(
+        data: &Tensor<(B, F), f32, D>,
+        num_components: usize,
+    ) -> Result<Self, D::Err> {
+        let (b, f) = *data.shape();
+        let (b, f) = (b.size(), f.size());
+        assert!(b > 1, "PCA needs at least 2 rows, found {b}");
+        assert!(
+            num_components <= b.min(f),
+            "can't fit {num_components} components to {b} rows of {f} features"
+        );
+        let mean = data.clone().try_mean::<(F,), Axis<0>>()?;
+        let centered = data.clone().try_sub(
+            mean.clone()
+                .try_broadcast_like::<_, Axis<0>>(data.shape())?,
+        )?;
+        let (_, s, vt) = centered.try_svd()?;
+        let variance = s.try_square()?.try_div((b - 1) as f32)?;
+        let mut total_variance = [0.0];
+        variance.clone().try_sum()?.copy_into(&mut total_variance);
+
+        let idx: Vec<usize> = (0..num_components).collect();
+        let idx = data.device.try_tensor_from_vec(idx, (num_components,))?;
+        Ok(Self {
+            mean,
+            components: vt.try_gather(idx.clone())?,
+            explained_variance: variance.try_gather(idx)?,
+            total_variance: total_variance[0],
+        })
+    }
+
+    /// The fraction of the total variance along each component.
+    pub fn explained_variance_ratio(&self) -> Tensor<(usize,), f32, D> {
+        self.try_explained_variance_ratio().unwrap()
+    }
+
+    /// Fallible version of [Pca::explained_variance_ratio()]
+    pub fn try_explained_variance_ratio(&self) -> Result<Tensor<(usize,), f32, D>, D::Err> {
+        self.explained_variance.clone().try_div(self.total_variance)
+    }
+
+    /// Projects the rows of `x` onto the components, with shape `(batch, num_components)`.
+    pub fn transform<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<(B, usize), f32, D> {
+        self.try_transform(x).unwrap()
+    }
+
+    /// Fallible version of [Pca::transform()]
+    pub fn try_transform<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<(B, usize), f32, D>, D::Err> {
+        let mean = self
+            .mean
+            .clone()
+            .try_broadcast_like::<_, Axis<0>>(x.shape())?;
+        x.clone()
+            .try_sub(mean)?
+            .try_matmul(self.components.clone().try_permute::<_, Axes2<1, 0>>()?)
+    }
+
+    /// [Pca::transform()] divided by the standard deviation along each component, so each
+    /// column of the result has unit variance over the fitted data.
+    ///
+    /// Components with `0.0` variance become NaN or infinite, so only whiten with
+    /// components that have variance.
+    pub fn whiten<B: Dim>(&self, x: &Tensor<(B, F), f32, D>) -> Tensor<(B, usize), f32, D> {
+        self.try_whiten(x).unwrap()
+    }
+
+    /// Fallible version of [Pca::whiten()]
+    pub fn try_whiten<B: Dim>(
+        &self,
+        x: &Tensor<(B, F), f32, D>,
+    ) -> Result<Tensor<(B, usize), f32, D>, D::Err> {
+        let projected = self.try_transform(x)?;
+        let std = self
+            .explained_variance
+            .clone()
+            .try_sqrt()?
+            .try_broadcast_like::<_, Axis<0>>(projected.shape())?;
+        projected.try_div(std)
+    }
+
+    /// Maps `(batch, num_components)` projections back to the space of the features. This
+    /// reconstructs the rows of [Pca::transform()] exactly when all the components are kept.
+    pub fn inverse_transform<B: Dim>(
+        &self,
+        z: &Tensor<(B, usize), f32, D>,
+    ) -> Tensor<(B, F), f32, D> {
+        self.try_inverse_transform(z).unwrap()
+    }
+
+    /// Fallible version of [Pca::inverse_transform()]
+    pub fn try_inverse_transform<B: Dim>(
+        &self,
+        z: &Tensor<(B, usize), f32, D>,
+    ) -> Result<Tensor<(B, F), f32, D>, D::Err> {
+        let x = z.clone().try_matmul(self.components.clone())?;
+        let mean = self
+            .mean
+            .clone()
+            .try_broadcast_like::<_, Axis<0>>(x.shape())?;
+        x.try_add(mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_pca_line() {
+        let dev: TestDevice = Default::default();
+        let data = dev.tensor([[1.0, 1.0], [2.0, 3.0], [3.0, 5.0], [4.0, 7.0]]);
+        let pca = Pca::fit(&data, 2);
+        assert_close(&pca.mean.array(), &[2.5, 4.0]);
+        // var(x) = 5/3 and var(y) = 20/3
+        assert!((pca.total_variance - 25.0 / 3.0).abs() < 1e-4);
+        assert_close(
+            &pca.explained_variance.as_vec(),
+            &std::vec![25.0 / 3.0, 0.0],
+        );
+        let c = pca.components.as_vec();
+        let sign = c[0].signum();
+        let r = 1.0 / 5f32.sqrt();
+        assert_close(&[c[0] * sign, c[1] * sign], &[r, 2.0 * r]);
+
+        let z = pca.transform(&data);
+        assert_close(&pca.inverse_transform(&z).array(), &data.array());
+    }
+
+    #[test]
+    fn test_pca_whiten_decorrelates() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<50, 3>, f32, _> = dev.sample_normal();
+        let mix = dev.tensor([[2.0, 0.5, 0.0], [0.0, 1.0, -1.0], [0.3, 0.0, 0.1]]);
+        let data = x.matmul(mix) + 3.0;
+        let pca = Pca::fit(&data, 3);
+        let ratio = pca.explained_variance_ratio().as_vec();
+        assert!((ratio.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+        assert!(ratio[0] >= ratio[1] && ratio[1] >= ratio[2]);
+
+        let w = pca.whiten(&data);
+        // the covariance of whitened data is the identity
+        let cov = w.clone().permute::<_, Axes2<1, 0>>().matmul(w) / 49.0;
+        assert_close_with_tolerance(
+            &cov.as_vec(),
+            &std::vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            1e-5,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_pca_too_many_components() {
+        let dev: TestDevice = Default::default();
+        let data: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+        Pca::fit(&data, 3);
+    }
+}